@@ -0,0 +1,585 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::JoinHandle;
+
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use buttercup_bts::tick::TickStatus;
+
+use crate::instances::{TreeInstance, TreeInstanceService, TreeInstanceServiceError};
+
+type TickResult = Result<TickStatus, TreeInstanceServiceError>;
+
+const MIN_OWNED_INSTANCES_SWEEP: usize = 1024;
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum InstanceExecutorError {
+
+    ExecutorStopped,
+    IOError(String),
+    Saturated,
+    TickCancelled
+
+}
+
+impl From<std::io::Error> for InstanceExecutorError {
+    fn from(err: std::io::Error) -> Self {
+        InstanceExecutorError::IOError(err.to_string())
+    }
+}
+
+#[derive(Default)]
+struct ShardQueue {
+
+    state: Mutex<ShardQueueState>,
+    available: Notify
+
+}
+
+#[derive(Default)]
+struct ShardQueueState {
+
+    pending: VecDeque<(Uuid, oneshot::Sender<TickResult>)>,
+    running: HashSet<Uuid>,
+    stopped: bool
+
+}
+
+impl ShardQueueState {
+
+    fn take_ready(&mut self,
+                  max_running: usize) -> Vec<(Uuid, Vec<oneshot::Sender<TickResult>>)> {
+        let mut batch: Vec<(Uuid, Vec<oneshot::Sender<TickResult>>)> = Vec::new();
+        let mut deferred = VecDeque::new();
+
+        while let Option::Some((instance_id, sender)) = self.pending.pop_front() {
+            match batch.iter().position(|(id, _)| *id == instance_id) {
+                Option::Some(index) => batch[index].1.push(sender),
+                Option::None if self.running.len() + batch.len() < max_running
+                    && !self.running.contains(&instance_id) =>
+                    batch.push((instance_id, vec![sender])),
+                Option::None => deferred.push_back((instance_id, sender))
+            }
+        }
+
+        self.pending = deferred;
+        self.running.extend(batch.iter().map(|(instance_id, _)| *instance_id));
+
+        batch
+    }
+
+}
+
+impl ShardQueue {
+
+    fn push(&self,
+            instance_id: Uuid,
+            sender: oneshot::Sender<TickResult>,
+            max_pending: Option<usize>) -> Result<(), InstanceExecutorError> {
+        let mut state = self.state.lock().unwrap();
+        if state.stopped {
+            return Result::Err(InstanceExecutorError::ExecutorStopped);
+        }
+        if max_pending.is_some_and(|max_pending| state.pending.len() + state.running.len() >= max_pending) {
+            return Result::Err(InstanceExecutorError::Saturated);
+        }
+
+        state.pending.push_back((instance_id, sender));
+        self.available.notify_one();
+
+        Result::Ok(())
+    }
+
+    // Takes distinct instances in arrival order, as many as keep the ticks running in the
+    // shard within max_running. Repeated requests for an instance already in the batch are
+    // coalesced into its single tick, so a busy instance cannot take more than one slot.
+    // Instances which are still being ticked are left waiting until their tick finishes,
+    // as are all of them while the shard runs max_running ticks.
+    async fn take_batch(&self,
+                        max_running: usize) -> Option<Vec<(Uuid, Vec<oneshot::Sender<TickResult>>)>> {
+        loop {
+            let available = self.available.notified();
+
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.stopped {
+                    return Option::None;
+                }
+
+                let batch = state.take_ready(max_running);
+                if !batch.is_empty() {
+                    return Option::Some(batch);
+                }
+            }
+
+            available.await;
+        }
+    }
+
+    fn finish(&self,
+              instance_id: &Uuid) {
+        self.state.lock().unwrap().running.remove(instance_id);
+        self.available.notify_one();
+    }
+
+    fn get_depth(&self) -> usize {
+        self.state.lock().unwrap().pending.len()
+    }
+
+    fn get_running(&self) -> usize {
+        self.state.lock().unwrap().running.len()
+    }
+
+    fn stop(&self) {
+        self.state.lock().unwrap().stopped = true;
+        self.available.notify_one();
+    }
+
+}
+
+// Instances are assigned to a fixed number of shards by a consistent hash of their id,
+// each shard is served by a single worker thread which runs at most batch_size ticks at a
+// time and ticks each instance as a task of its own, so that instances waiting for a
+// signal, a task or a breakpoint do not hold up the others. Workers keep weak references
+// to the instances they tick, so a busy instance is looked up in the shared instance
+// service only once, while removed instances are still dropped, together with their
+// blackboards, right away.
+pub struct ShardedInstanceExecutor {
+
+    shards: Vec<Arc<ShardQueue>>,
+    workers: Vec<JoinHandle<()>>,
+    max_pending: Option<usize>,
+    rejected: AtomicU64
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct InstanceExecutorMetrics {
+
+    queue_depths: Vec<usize>,
+    running: Vec<usize>,
+    max_pending: Option<usize>,
+    rejected: u64
+
+}
+
+impl InstanceExecutorMetrics {
+
+    pub fn get_queue_depths(&self) -> &Vec<usize> {
+        &self.queue_depths
+    }
+
+    pub fn get_running(&self) -> &Vec<usize> {
+        &self.running
+    }
+
+    pub fn get_rejected(&self) -> &u64 {
+        &self.rejected
+    }
+
+}
+
+impl ShardedInstanceExecutor {
+
+    pub fn new(instance_service: Arc<TreeInstanceService>,
+               num_shards: usize,
+               batch_size: usize) -> Result<ShardedInstanceExecutor, InstanceExecutorError> {
+        let mut shards = Vec::with_capacity(num_shards);
+        let mut workers = Vec::with_capacity(num_shards);
+
+        for shard_index in 0..num_shards.max(1) {
+            let shard = Arc::new(ShardQueue::default());
+            let runtime = Builder::new_current_thread().enable_all().build()?;
+
+            let worker = std::thread::Builder::new()
+                .name(format!("instance-shard-{}", shard_index))
+                .spawn({
+                    let shard = shard.clone();
+                    let instance_service = instance_service.clone();
+                    move || ShardedInstanceExecutor::run(
+                        runtime, shard, instance_service, batch_size.max(1))
+                })?;
+
+            shards.push(shard);
+            workers.push(worker);
+        }
+
+        Result::Ok(
+            ShardedInstanceExecutor {
+                shards,
+                workers,
+                max_pending: Option::None,
+                rejected: AtomicU64::new(0)
+            })
+    }
+
+    // Bounds the number of ticks waiting or running in every shard, ticks above the bound
+    // are rejected right away instead of queueing up behind the ones already taken.
+    pub fn with_max_pending(mut self,
+                            max_pending: usize) -> ShardedInstanceExecutor {
+        self.max_pending = Option::Some(max_pending);
+        self
+    }
+
+    pub fn get_metrics(&self) -> InstanceExecutorMetrics {
+        InstanceExecutorMetrics {
+            queue_depths: self.shards.iter().map(|shard| shard.get_depth()).collect(),
+            running: self.shards.iter().map(|shard| shard.get_running()).collect(),
+            max_pending: self.max_pending,
+            rejected: self.rejected.load(Ordering::Relaxed)
+        }
+    }
+
+    pub async fn tick(&self,
+                      instance_id: &Uuid) -> Result<TickResult, InstanceExecutorError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.get_shard(instance_id)
+            .push(*instance_id, sender, self.max_pending)
+            .map_err(|err| {
+                if err == InstanceExecutorError::Saturated {
+                    self.rejected.fetch_add(1, Ordering::Relaxed);
+                }
+                err
+            })?;
+
+        receiver.await.map_err(|_| InstanceExecutorError::TickCancelled)
+    }
+
+    fn get_shard(&self,
+                 instance_id: &Uuid) -> &ShardQueue {
+        let mut hasher = DefaultHasher::new();
+        instance_id.hash(&mut hasher);
+
+        &self.shards[get_jump_hash_bucket(hasher.finish(), self.shards.len())]
+    }
+
+    // Ticks still running when the executor stops are dropped with the runtime, their
+    // callers get TickCancelled.
+    fn run(runtime: Runtime,
+           shard: Arc<ShardQueue>,
+           instance_service: Arc<TreeInstanceService>,
+           batch_size: usize) {
+        let mut owned: HashMap<Uuid, Weak<TreeInstance>> = HashMap::new();
+        let mut sweep_at = MIN_OWNED_INSTANCES_SWEEP;
+
+        runtime.block_on(async {
+            while let Option::Some(batch) = shard.take_batch(batch_size).await {
+                for (instance_id, senders) in batch {
+                    let instance = ShardedInstanceExecutor::get_owned(&mut owned, &instance_service, &instance_id);
+                    let shard = shard.clone();
+
+                    tokio::spawn(async move {
+                        let result = match instance {
+                            None => Result::Err(TreeInstanceServiceError::InstanceOfGivenIdNotFound(instance_id)),
+                            Some(instance) => instance.tick().await.map_err(TreeInstanceServiceError::from)
+                        };
+                        shard.finish(&instance_id);
+                        for sender in senders {
+                            let _ = sender.send(result.clone());
+                        }
+                    });
+                }
+
+                // References to removed instances are dropped once in a while, the sweeps get
+                // rarer as the number of owned instances grows.
+                if owned.len() >= sweep_at {
+                    owned.retain(|_, instance| instance.strong_count() > 0);
+                    sweep_at = (owned.len() * 2).max(MIN_OWNED_INSTANCES_SWEEP);
+                }
+            }
+        });
+    }
+
+    fn get_owned(owned: &mut HashMap<Uuid, Weak<TreeInstance>>,
+                 instance_service: &TreeInstanceService,
+                 instance_id: &Uuid) -> Option<Arc<TreeInstance>> {
+        if let Option::Some(instance) = owned.get(instance_id).and_then(Weak::upgrade) {
+            return Option::Some(instance);
+        }
+
+        let instance = instance_service.get_by_id(instance_id)?;
+        owned.insert(*instance_id, Arc::downgrade(&instance));
+
+        Option::Some(instance)
+    }
+
+}
+
+impl Drop for ShardedInstanceExecutor {
+    fn drop(&mut self) {
+        for shard in &self.shards {
+            shard.stop();
+        }
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+// Jump consistent hash, when the number of buckets grows only the keys which move to the
+// new buckets change their bucket.
+fn get_jump_hash_bucket(mut key: u64,
+                        num_buckets: usize) -> usize {
+    let mut bucket: i64 = -1;
+    let mut next: i64 = 0;
+
+    while next < num_buckets as i64 {
+        bucket = next;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+
+    bucket.max(0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use actix_rt::System;
+    use futures::executor::block_on;
+    use futures::FutureExt;
+
+    use buttercup_bts::node::action::logging::PrintLogActionNode;
+    use buttercup_bts::node::action::wait::WaitForSignalActionNode;
+    use buttercup_bts::node::root::one_off::OneOffRootBTNode;
+    use buttercup_bts::tree::{BehaviorTree, BehaviorTreeService};
+    use buttercup_values::ValuesPayload;
+
+    use super::*;
+
+    #[test]
+    fn test_batches_coalesce_repeated_instances() {
+        let queue = ShardQueue::default();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let third = Uuid::new_v4();
+
+        for instance_id in &[first, first, second, third, first] {
+            queue.push(*instance_id, oneshot::channel().0, Option::None).unwrap();
+        }
+
+        let batch = block_on(queue.take_batch(2)).unwrap();
+
+        assert_eq!(vec![(first, 3), (second, 1)],
+                   batch.iter()
+                       .map(|(instance_id, senders)| (*instance_id, senders.len()))
+                       .collect::<Vec<_>>());
+
+        queue.finish(&first);
+        assert_eq!(vec![third],
+                   block_on(queue.take_batch(2))
+                       .unwrap()
+                       .iter()
+                       .map(|(instance_id, _)| *instance_id)
+                       .collect::<Vec<_>>());
+
+        queue.stop();
+        assert!(block_on(queue.take_batch(2)).is_none());
+        assert_eq!(Result::Err(InstanceExecutorError::ExecutorStopped),
+                   queue.push(first, oneshot::channel().0, Option::None));
+    }
+
+    #[test]
+    fn test_shards_run_at_most_batch_size_ticks_at_once() {
+        let _system = System::new();
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  WaitForSignalActionNode::new(3, "go".to_owned()).into())
+                                  .into()));
+        let instance_service = Arc::new(
+            TreeInstanceService::new(Default::default(), tree_service, Duration::from_secs(60)));
+        let executor = ShardedInstanceExecutor::new(instance_service.clone(), 1, 2).unwrap();
+        let instance_ids: Vec<Uuid> = (0..3)
+            .map(|_| instance_service.create_instance(&1).unwrap())
+            .collect();
+
+        let mut ticks: Vec<_> = instance_ids.iter()
+            .map(|instance_id| executor.tick(instance_id).boxed())
+            .collect();
+        for tick in ticks.iter_mut() {
+            assert!(tick.now_or_never().is_none());
+        }
+        while executor.get_metrics().get_running() != &vec![2] {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(&vec![2], executor.get_metrics().get_running());
+        assert_eq!(&vec![1], executor.get_metrics().get_queue_depths());
+        // The third tick has not started, nothing waits for its signal yet.
+        assert_eq!(Result::Ok(false),
+                   instance_service.signal_instance(&instance_ids[2], "go", ValuesPayload::empty()));
+
+        for instance_id in &instance_ids[..2] {
+            instance_service.signal_instance(instance_id, "go", ValuesPayload::empty()).unwrap();
+        }
+        for tick in ticks {
+            assert_eq!(Result::Ok(Result::Ok(TickStatus::Success)), block_on(tick));
+        }
+        assert_eq!(&vec![0], executor.get_metrics().get_running());
+
+        for instance_id in &instance_ids {
+            instance_service.remove_instance(instance_id).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_consistent_hash_moves_keys_only_to_new_buckets() {
+        for key in 0..1000u64 {
+            let key = key.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+            let (before, after) = (get_jump_hash_bucket(key, 4), get_jump_hash_bucket(key, 5));
+
+            assert!(before < 4);
+            assert!(after == before || after == 4);
+        }
+    }
+
+    #[test]
+    fn test_rejects_ticks_above_max_pending() {
+        let queue = ShardQueue::default();
+
+        assert!(queue.push(Uuid::new_v4(), oneshot::channel().0, Option::Some(1)).is_ok());
+        assert_eq!(Result::Err(InstanceExecutorError::Saturated),
+                   queue.push(Uuid::new_v4(), oneshot::channel().0, Option::Some(1)));
+        assert_eq!(1, queue.get_depth());
+    }
+
+    #[test]
+    fn test_rejects_ticks_above_max_pending_while_ticks_run() {
+        let _system = System::new();
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  WaitForSignalActionNode::new(3, "go".to_owned()).into())
+                                  .into()));
+        let instance_service = Arc::new(
+            TreeInstanceService::new(Default::default(), tree_service, Duration::from_secs(60)));
+        let executor = ShardedInstanceExecutor::new(instance_service.clone(), 1, 1)
+            .unwrap()
+            .with_max_pending(2);
+        let instance_ids: Vec<Uuid> = (0..3)
+            .map(|_| instance_service.create_instance(&1).unwrap())
+            .collect();
+
+        let mut running = executor.tick(&instance_ids[0]).boxed();
+        assert!((&mut running).now_or_never().is_none());
+        while executor.get_metrics().get_running() != &vec![1] {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let mut waiting = executor.tick(&instance_ids[1]).boxed();
+        assert!((&mut waiting).now_or_never().is_none());
+
+        assert_eq!(Result::Err(InstanceExecutorError::Saturated),
+                   block_on(executor.tick(&instance_ids[2])));
+        assert_eq!(&1, executor.get_metrics().get_rejected());
+
+        for instance_id in &instance_ids[..2] {
+            instance_service.signal_instance(instance_id, "go", ValuesPayload::empty()).unwrap();
+        }
+        assert_eq!(Result::Ok(Result::Ok(TickStatus::Success)), block_on(running));
+        assert_eq!(Result::Ok(Result::Ok(TickStatus::Success)), block_on(waiting));
+
+        for instance_id in &instance_ids {
+            instance_service.remove_instance(instance_id).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_reports_missing_instances() {
+        let _system = System::new();
+        let executor = ShardedInstanceExecutor::new(
+            Arc::new(
+                TreeInstanceService::new(
+                    Default::default(), Default::default(), std::time::Duration::from_secs(1))),
+            2,
+            4)
+            .unwrap();
+        let instance_id = Uuid::new_v4();
+
+        assert_eq!(Result::Ok(Result::Err(
+            TreeInstanceServiceError::InstanceOfGivenIdNotFound(instance_id))),
+                   block_on(executor.tick(&instance_id)));
+    }
+
+    #[test]
+    fn test_stops_ticking_removed_instances() {
+        let _system = System::new();
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  PrintLogActionNode::new(3, "hello".to_owned()).into())
+                                  .into()));
+        let instance_service = Arc::new(
+            TreeInstanceService::new(Default::default(), tree_service, std::time::Duration::from_secs(60)));
+        let executor = ShardedInstanceExecutor::new(instance_service.clone(), 2, 4).unwrap();
+        let instance_id = instance_service.create_instance(&1).unwrap();
+
+        assert_eq!(Result::Ok(Result::Ok(TickStatus::Success)),
+                   block_on(executor.tick(&instance_id)));
+
+        instance_service.remove_instance(&instance_id).unwrap();
+
+        assert_eq!(Result::Ok(Result::Err(
+            TreeInstanceServiceError::InstanceOfGivenIdNotFound(instance_id))),
+                   block_on(executor.tick(&instance_id)));
+    }
+
+    #[test]
+    fn test_instances_waiting_for_signals_do_not_hold_up_their_shard() {
+        let _system = System::new();
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  WaitForSignalActionNode::new(3, "go".to_owned()).into())
+                                  .into()));
+        tree_service.insert(
+            BehaviorTree::new(4,
+                              OneOffRootBTNode::new(
+                                  5,
+                                  PrintLogActionNode::new(6, "hello".to_owned()).into())
+                                  .into()));
+        let instance_service = Arc::new(
+            TreeInstanceService::new(Default::default(), tree_service, Duration::from_secs(60)));
+        let executor = Arc::new(ShardedInstanceExecutor::new(instance_service.clone(), 1, 4).unwrap());
+        let (waiting, other) = (instance_service.create_instance(&1).unwrap(),
+                                instance_service.create_instance(&4).unwrap());
+
+        let mut waiting_tick = executor.tick(&waiting).boxed();
+        assert!((&mut waiting_tick).now_or_never().is_none());
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn({
+            let executor = executor.clone();
+            move || sender.send(block_on(executor.tick(&other))).unwrap()
+        });
+
+        assert_eq!(Result::Ok(Result::Ok(TickStatus::Success)),
+                   receiver.recv_timeout(Duration::from_secs(5)).unwrap());
+
+        assert!(instance_service.signal_instance(&waiting, "go", ValuesPayload::empty()).unwrap());
+        assert_eq!(Result::Ok(Result::Ok(TickStatus::Success)), block_on(waiting_tick));
+
+        instance_service.remove_instance(&waiting).unwrap();
+        instance_service.remove_instance(&other).unwrap();
+    }
+
+}
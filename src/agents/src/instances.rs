@@ -0,0 +1,617 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use buttercup_blackboards::journal::{BlackboardJournal, BlackboardMutation};
+use buttercup_bts::breakpoint::Breakpoints;
+use buttercup_bts::context::{BTNodeContextService, BTNodeContextServiceError, BTNodeExecutionContextHolder};
+use buttercup_bts::task::{HumanTask, HumanTaskError};
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_bts::tree::{BehaviorTree, BehaviorTreeService};
+use buttercup_values::ValuesPayload;
+
+pub struct TreeInstance {
+
+    id: Uuid,
+    context: Arc<BTNodeExecutionContextHolder>,
+    tree: Arc<BehaviorTree>,
+    breakpoints: Arc<Breakpoints>,
+    created_at_utc: NaiveDateTime,
+    last_active_at_utc: Mutex<NaiveDateTime>
+
+}
+
+impl TreeInstance {
+
+    pub fn new(id: Uuid,
+               context: Arc<BTNodeExecutionContextHolder>,
+               tree: Arc<BehaviorTree>) -> TreeInstance {
+        let now = Utc::now().naive_utc();
+        TreeInstance {
+            id,
+            context,
+            tree,
+            breakpoints: Arc::new(Breakpoints::default()),
+            created_at_utc: now,
+            last_active_at_utc: Mutex::new(now)
+        }
+    }
+
+    pub async fn tick(&self) -> Result<TickStatus, TickError> {
+        *self.last_active_at_utc.lock().unwrap() = Utc::now().naive_utc();
+
+        if self.breakpoints.is_empty() {
+            self.tree.tick(Uuid::new_v4(), self.context.get_context()).await
+        } else {
+            self.tree.tick_with_breakpoints(Uuid::new_v4(), self.context.get_context(), self.breakpoints.clone()).await
+        }
+    }
+
+    pub fn get_breakpoint_state(&self) -> Result<BreakpointState, BTNodeContextServiceError> {
+        Result::Ok(
+            BreakpointState {
+                breakpoints: self.breakpoints.get_node_ids(),
+                paused_node_ids: self.breakpoints.get_paused_node_ids(),
+                values: self.context
+                    .get_context()
+                    .get_all_values()
+                    .map_err(BTNodeContextServiceError::from)?
+            })
+    }
+
+    pub fn get_info(&self) -> TreeInstanceInfo {
+        TreeInstanceInfo {
+            id: self.id,
+            tree_id: *self.tree.get_id(),
+            created_at_utc: self.created_at_utc,
+            last_active_at_utc: *self.last_active_at_utc.lock().unwrap()
+        }
+    }
+
+    fn is_expired(&self,
+                  now: &NaiveDateTime,
+                  ttl: &Duration) -> bool {
+        match (*now - *self.last_active_at_utc.lock().unwrap()).to_std() {
+            Ok(idle) => idle > *ttl,
+            Err(_) => false
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct TreeInstanceInfo {
+
+    id: Uuid,
+    tree_id: i32,
+    created_at_utc: NaiveDateTime,
+    last_active_at_utc: NaiveDateTime
+
+}
+
+impl TreeInstanceInfo {
+
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_created_at_utc(&self) -> &NaiveDateTime {
+        &self.created_at_utc
+    }
+
+    pub fn get_last_active_at_utc(&self) -> &NaiveDateTime {
+        &self.last_active_at_utc
+    }
+
+}
+
+// The breakpoints of an instance, the nodes its tick is paused at, if any, and the
+// values on its blackboard.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct BreakpointState {
+
+    breakpoints: Vec<i32>,
+    paused_node_ids: Vec<i32>,
+    values: ValuesPayload
+
+}
+
+impl BreakpointState {
+
+    pub fn get_breakpoints(&self) -> &Vec<i32> {
+        &self.breakpoints
+    }
+
+    pub fn get_paused_node_ids(&self) -> &Vec<i32> {
+        &self.paused_node_ids
+    }
+
+    pub fn get_values(&self) -> &ValuesPayload {
+        &self.values
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum TreeInstanceServiceError {
+
+    BTNodeContextServiceError(BTNodeContextServiceError),
+    HistoryCompacted(Uuid, u64),
+    HistoryNotRecorded(Uuid),
+    HumanTaskError(HumanTaskError),
+    InstanceOfGivenIdNotFound(Uuid),
+    TickError(TickError),
+    TreeOfGivenIdNotFound(i32)
+
+}
+
+impl From<BTNodeContextServiceError> for TreeInstanceServiceError {
+    fn from(err: BTNodeContextServiceError) -> Self {
+        TreeInstanceServiceError::BTNodeContextServiceError(err)
+    }
+}
+
+impl From<HumanTaskError> for TreeInstanceServiceError {
+    fn from(err: HumanTaskError) -> Self {
+        TreeInstanceServiceError::HumanTaskError(err)
+    }
+}
+
+impl From<TickError> for TreeInstanceServiceError {
+    fn from(err: TickError) -> Self {
+        TreeInstanceServiceError::TickError(err)
+    }
+}
+
+// Each instance owns its execution context and blackboard, instances which were not
+// ticked for longer than the ttl are removed together with their blackboards.
+pub struct TreeInstanceService {
+
+    context_service: Arc<BTNodeContextService>,
+    instances: DashMap<Uuid, Arc<TreeInstance>>,
+    tree_service: Arc<BehaviorTreeService>,
+    ttl: Duration
+
+}
+
+impl TreeInstanceService {
+
+    pub fn new(context_service: Arc<BTNodeContextService>,
+               tree_service: Arc<BehaviorTreeService>,
+               ttl: Duration) -> TreeInstanceService {
+        TreeInstanceService {
+            context_service,
+            instances: DashMap::new(),
+            tree_service,
+            ttl
+        }
+    }
+
+    pub fn create_instance(&self,
+                           tree_id: &i32) -> Result<Uuid, TreeInstanceServiceError> {
+        let tree = self.tree_service.get_by_id(tree_id)
+            .ok_or(TreeInstanceServiceError::TreeOfGivenIdNotFound(*tree_id))?;
+
+        self.create_instance_of(tree)
+    }
+
+    // Instances of trees which are not active, pinned to one of their versions.
+    pub fn create_instance_of(&self,
+                              tree: Arc<BehaviorTree>) -> Result<Uuid, TreeInstanceServiceError> {
+        let context = self.context_service.build_new()?;
+        let instance_id = *context.get_id();
+
+        self.instances.insert(instance_id,
+                              Arc::new(TreeInstance::new(instance_id, Arc::new(context), tree)));
+
+        Result::Ok(instance_id)
+    }
+
+    pub async fn tick_instance(&self,
+                               instance_id: &Uuid) -> Result<TickStatus, TreeInstanceServiceError> {
+        let instance = self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        Result::Ok(instance.tick().await?)
+    }
+
+    pub fn set_breakpoints(&self,
+                           instance_id: &Uuid,
+                           node_ids: HashSet<i32>) -> Result<(), TreeInstanceServiceError> {
+        let instance = self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        instance.breakpoints.set(node_ids);
+
+        Result::Ok(())
+    }
+
+    pub fn get_breakpoint_state(&self,
+                                instance_id: &Uuid) -> Result<BreakpointState, TreeInstanceServiceError> {
+        let instance = self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        Result::Ok(instance.get_breakpoint_state()?)
+    }
+
+    // Returns the ids of the nodes the resumed tick was paused at.
+    pub fn resume_instance(&self,
+                           instance_id: &Uuid) -> Result<Vec<i32>, TreeInstanceServiceError> {
+        let instance = self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        Result::Ok(instance.breakpoints.resume())
+    }
+
+    // Returns whether a node of the instance was waiting for the signal, otherwise the
+    // signal is kept until one waits.
+    pub fn signal_instance(&self,
+                           instance_id: &Uuid,
+                           name: &str,
+                           payload: ValuesPayload) -> Result<bool, TreeInstanceServiceError> {
+        let instance = self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        Result::Ok(instance.context.get_context().get_signals().send(name, payload))
+    }
+
+    pub fn list_tasks(&self,
+                      instance_id: &Uuid,
+                      assignee: Option<&str>) -> Result<Vec<HumanTask>, TreeInstanceServiceError> {
+        let instance = self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        Result::Ok(
+            instance.context
+                .get_context()
+                .get_human_tasks()
+                .get_tasks()
+                .into_iter()
+                .filter(|task| assignee.is_none() || task.get_assignee().as_deref() == assignee)
+                .collect())
+    }
+
+    pub fn assign_task(&self,
+                       instance_id: &Uuid,
+                       task_id: &Uuid,
+                       assignee: Option<String>) -> Result<HumanTask, TreeInstanceServiceError> {
+        let instance = self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        Result::Ok(instance.context.get_context().get_human_tasks().assign(task_id, assignee)?)
+    }
+
+    // Returns the values extracted from the form, which were put onto the blackboard.
+    pub fn complete_task(&self,
+                         instance_id: &Uuid,
+                         task_id: &Uuid,
+                         form_values: &Value) -> Result<ValuesPayload, TreeInstanceServiceError> {
+        let instance = self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        Result::Ok(instance.context.get_context().get_human_tasks().complete(task_id, form_values)?)
+    }
+
+    pub fn get_history(&self,
+                       instance_id: &Uuid,
+                       since: u64) -> Result<Vec<BlackboardMutation>, TreeInstanceServiceError> {
+        Result::Ok(self.get_journal(instance_id)?.get_mutations_since(since))
+    }
+
+    // The blackboard of the instance right after the mutation of the given sequence.
+    pub fn get_values_at(&self,
+                         instance_id: &Uuid,
+                         sequence: u64) -> Result<ValuesPayload, TreeInstanceServiceError> {
+        self.get_journal(instance_id)?
+            .get_values_at(sequence)
+            .ok_or(TreeInstanceServiceError::HistoryCompacted(*instance_id, sequence))
+    }
+
+    fn get_journal(&self,
+                   instance_id: &Uuid) -> Result<Arc<BlackboardJournal>, TreeInstanceServiceError> {
+        self.get_by_id(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+
+        self.context_service
+            .get_journal(instance_id)
+            .ok_or(TreeInstanceServiceError::HistoryNotRecorded(*instance_id))
+    }
+
+    pub fn list_instances(&self,
+                          tree_id: &i32) -> Vec<TreeInstanceInfo> {
+        let mut infos: Vec<TreeInstanceInfo> = self.instances
+            .iter()
+            .map(|entry| entry.value().get_info())
+            .filter(|info| info.tree_id == *tree_id)
+            .collect();
+
+        infos.sort_by_key(|info| info.created_at_utc);
+        infos
+    }
+
+    pub fn get_by_id(&self,
+                     instance_id: &Uuid) -> Option<Arc<TreeInstance>> {
+        self.instances
+            .get(instance_id)
+            .map(|entry| entry.value().clone())
+    }
+
+    pub fn remove_instance(&self,
+                           instance_id: &Uuid) -> Result<(), TreeInstanceServiceError> {
+        self.instances.remove(instance_id)
+            .ok_or(TreeInstanceServiceError::InstanceOfGivenIdNotFound(*instance_id))?;
+        self.context_service.destroy(instance_id)?;
+
+        Result::Ok(())
+    }
+
+    pub fn collect_expired(&self) -> Vec<Uuid> {
+        let now = Utc::now().naive_utc();
+        let expired: Vec<Uuid> = self.instances
+            .iter()
+            .filter(|entry| entry.value().is_expired(&now, &self.ttl))
+            .map(|entry| *entry.key())
+            .collect();
+
+        expired.into_iter()
+            .filter(|instance_id| self.remove_instance(instance_id).is_ok())
+            .collect()
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use buttercup_blackboards::LocalBlackboardService;
+    use buttercup_bts::context::test_utils;
+    use buttercup_bts::node::action::logging::PrintLogActionNode;
+    use buttercup_bts::node::action::messages::{PublishMessageActionNode, ReceiveMessageActionNode};
+    use buttercup_bts::node::action::task::HumanTaskActionNode;
+    use buttercup_bts::node::action::wait::WaitForSignalActionNode;
+    use buttercup_bts::node::root::one_off::OneOffRootBTNode;
+    use buttercup_endpoints::ArgumentDefinition;
+    use buttercup_values::{ValueHolder, ValueType};
+    use buttercup_values::extractors::ValueExtractionPolicy;
+    use serde_json::json;
+
+    use super::*;
+
+    fn service(ttl: Duration) -> TreeInstanceService {
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  PrintLogActionNode::new(3, "hello".to_owned()).into())
+                                  .into()));
+
+        TreeInstanceService::new(Arc::new(BTNodeContextService::default()), tree_service, ttl)
+    }
+
+    #[actix_rt::test]
+    async fn test_instances_are_ticked_independently_and_listed() {
+        let service = service(Duration::from_secs(60));
+        let first = service.create_instance(&1).unwrap();
+        let second = service.create_instance(&1).unwrap();
+
+        assert_eq!(Result::Err(TreeInstanceServiceError::TreeOfGivenIdNotFound(2)),
+                   service.create_instance(&2));
+        assert_eq!(Result::Ok(TickStatus::Success), service.tick_instance(&first).await);
+        assert_eq!(vec![first, second],
+                   service.list_instances(&1)
+                       .iter()
+                       .map(|info| *info.get_id())
+                       .collect::<Vec<_>>());
+        assert!(service.collect_expired().is_empty());
+
+        let paths: Vec<_> = [first, second]
+            .iter()
+            .map(|id| test_utils::get_path(service.get_by_id(id).unwrap().context.get_context()))
+            .collect();
+        drop(service);
+
+        for path in paths {
+            test_utils::destroy(path);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_ticks_pause_at_breakpoints() {
+        let service = service(Duration::from_secs(60));
+        let instance_id = service.create_instance(&1).unwrap();
+
+        service.set_breakpoints(&instance_id, [3].iter().copied().collect()).unwrap();
+
+        let mut tick = Box::pin(service.tick_instance(&instance_id));
+
+        assert!(futures::poll!(&mut tick).is_pending());
+
+        let state = service.get_breakpoint_state(&instance_id).unwrap();
+
+        assert_eq!(&vec![3], state.get_breakpoints());
+        assert_eq!(&vec![3], state.get_paused_node_ids());
+        assert_eq!(Result::Ok(vec![3]), service.resume_instance(&instance_id));
+        assert_eq!(Result::Ok(TickStatus::Success), tick.await);
+
+        let path = test_utils::get_path(service.get_by_id(&instance_id).unwrap().context.get_context());
+        drop(service);
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_reconstructs_blackboard_history() {
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  PrintLogActionNode::new(3, "hello".to_owned()).into())
+                                  .into()));
+        let journaled_service = TreeInstanceService::new(
+            Arc::new(BTNodeContextService::new(Default::default(),
+                                               Arc::new(LocalBlackboardService::default().with_journals(2)))),
+            tree_service,
+            Duration::from_secs(60));
+        let instance_id = journaled_service.create_instance(&1).unwrap();
+        let context = journaled_service.get_by_id(&instance_id).unwrap().context.clone();
+
+        for value in &[1, 2, 3] {
+            context.get_context().put_values(&ValuesPayload::singleton("count".to_owned(), value.to_string().into())).unwrap();
+        }
+
+        assert_eq!(3, journaled_service.get_history(&instance_id, 0).unwrap().len());
+        assert_eq!(ValuesPayload::singleton("count".to_owned(), "2".to_owned().into()),
+                   journaled_service.get_values_at(&instance_id, 2).unwrap());
+
+        let unrecorded_service = service(Duration::from_secs(60));
+        let unrecorded_id = unrecorded_service.create_instance(&1).unwrap();
+
+        assert_eq!(Result::Err(TreeInstanceServiceError::HistoryNotRecorded(unrecorded_id)),
+                   unrecorded_service.get_history(&unrecorded_id, 0));
+
+        unrecorded_service.remove_instance(&unrecorded_id).unwrap();
+        drop(context);
+        journaled_service.remove_instance(&instance_id).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn test_instances_exchange_messages() {
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  PublishMessageActionNode::new(3,
+                                                                "counts".to_owned(),
+                                                                ["count".to_owned()].iter().cloned().collect())
+                                      .into())
+                                  .into()));
+        tree_service.insert(
+            BehaviorTree::new(4,
+                              OneOffRootBTNode::new(
+                                  5,
+                                  ReceiveMessageActionNode::new(6, "counts".to_owned()).into())
+                                  .into()));
+        let service = TreeInstanceService::new(Arc::new(BTNodeContextService::default()),
+                                               tree_service,
+                                               Duration::from_secs(60));
+        let publisher_id = service.create_instance(&1).unwrap();
+        let receiver_id = service.create_instance(&4).unwrap();
+        let count = ValuesPayload::singleton("count".to_owned(), "7".to_owned().into());
+
+        service.get_by_id(&publisher_id).unwrap().context.get_context().put_values(&count).unwrap();
+
+        assert_eq!(Result::Ok(TickStatus::Failure), service.tick_instance(&receiver_id).await);
+        assert_eq!(Result::Ok(TickStatus::Success), service.tick_instance(&publisher_id).await);
+        assert_eq!(Result::Ok(TickStatus::Success), service.tick_instance(&receiver_id).await);
+        assert_eq!(count,
+                   service.get_by_id(&receiver_id).unwrap()
+                       .context
+                       .get_context()
+                       .get_values(&["count".to_owned()].iter().cloned().collect())
+                       .unwrap());
+        assert_eq!(Result::Ok(TickStatus::Failure), service.tick_instance(&receiver_id).await);
+
+        service.remove_instance(&publisher_id).unwrap();
+        service.remove_instance(&receiver_id).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn test_ticks_wait_for_signals() {
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  WaitForSignalActionNode::new(3, "approve".to_owned()).into())
+                                  .into()));
+        let service = TreeInstanceService::new(Arc::new(BTNodeContextService::default()),
+                                               tree_service,
+                                               Duration::from_secs(60));
+        let instance_id = service.create_instance(&1).unwrap();
+        let approval = ValuesPayload::singleton("approver".to_owned(), "alice".to_owned().into());
+
+        let mut tick = Box::pin(service.tick_instance(&instance_id));
+
+        assert!(futures::poll!(&mut tick).is_pending());
+        assert_eq!(Result::Ok(true), service.signal_instance(&instance_id, "approve", approval.clone()));
+        assert_eq!(Result::Ok(TickStatus::Success), tick.await);
+        assert_eq!(approval,
+                   service.get_by_id(&instance_id).unwrap()
+                       .context
+                       .get_context()
+                       .get_values(&["approver".to_owned()].iter().cloned().collect())
+                       .unwrap());
+        assert_eq!(Result::Err(TreeInstanceServiceError::InstanceOfGivenIdNotFound(Uuid::nil())),
+                   service.signal_instance(&Uuid::nil(), "approve", ValuesPayload::empty()));
+
+        service.remove_instance(&instance_id).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn test_ticks_wait_for_human_tasks() {
+        let form: HashMap<String, ArgumentDefinition> = vec![
+            ("approved".to_owned(),
+             ArgumentDefinition::new(1, "approved".to_owned(), ValueType::Boolean, ValueExtractionPolicy::Strict, 1))
+        ].into_iter().collect();
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  HumanTaskActionNode::new(3,
+                                                           "Approve the order".to_owned(),
+                                                           Option::Some("alice".to_owned()),
+                                                           form)
+                                      .into())
+                                  .into()));
+        let service = TreeInstanceService::new(Arc::new(BTNodeContextService::default()),
+                                               tree_service,
+                                               Duration::from_secs(60));
+        let instance_id = service.create_instance(&1).unwrap();
+
+        let mut tick = Box::pin(service.tick_instance(&instance_id));
+
+        assert!(futures::poll!(&mut tick).is_pending());
+        assert!(service.list_tasks(&instance_id, Option::Some("bob")).unwrap().is_empty());
+
+        let tasks = service.list_tasks(&instance_id, Option::Some("alice")).unwrap();
+        let task_id = *tasks[0].get_id();
+
+        assert_eq!(1, tasks.len());
+        assert!(matches!(service.complete_task(&instance_id, &task_id, &json!({})),
+                         Err(TreeInstanceServiceError::HumanTaskError(HumanTaskError::InvalidForm(_, _)))));
+        assert_eq!(Result::Ok(ValuesPayload::singleton("approved".to_owned(), ValueHolder::Boolean(true))),
+                   service.complete_task(&instance_id, &task_id, &json!({ "approved": true })));
+        assert_eq!(Result::Ok(TickStatus::Success), tick.await);
+        assert!(service.list_tasks(&instance_id, Option::None).unwrap().is_empty());
+
+        service.remove_instance(&instance_id).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn test_collects_expired_instances() {
+        let service = service(Duration::from_millis(0));
+        let instance_id = service.create_instance(&1).unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        assert_eq!(vec![instance_id], service.collect_expired());
+        assert!(service.list_instances(&1).is_empty());
+        assert_eq!(Result::Err(TreeInstanceServiceError::InstanceOfGivenIdNotFound(instance_id)),
+                   service.tick_instance(&instance_id).await);
+    }
+
+}
@@ -11,6 +11,8 @@ use buttercup_bts::context::{BTNodeExecutionContext, BTNodeExecutionContextHolde
 use buttercup_bts::tick::{TickError, TickStatus};
 use buttercup_bts::tree::BehaviorTree;
 
+pub mod executor;
+pub mod instances;
 pub mod service;
 
 pub struct Agent {
@@ -34,8 +36,8 @@ impl Agent {
     }
 
     pub async fn start(&self,
+                       exec_id: Uuid,
                        abort_registration: AbortRegistration) -> AgentExecutionResult {
-        let exec_id = Uuid::new_v4();
         let started_at = Utc::now();
 
         let result = self.do_start(abort_registration).await;
@@ -81,6 +83,29 @@ impl From<Aborted> for AgentError {
     }
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum AgentExecution {
+
+    Completed(AgentExecutionResult),
+    Running {
+        agent_id: Uuid,
+        started_at_utc: NaiveDateTime
+    }
+
+}
+
+impl AgentExecution {
+
+    pub fn is_completed(&self) -> bool {
+        match self {
+            AgentExecution::Completed(_) => true,
+            AgentExecution::Running { .. } => false
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct AgentExecutionResult {
 
     id: Uuid,
@@ -107,6 +132,26 @@ impl AgentExecutionResult {
         }
     }
 
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_agent_id(&self) -> &Uuid {
+        &self.agent_id
+    }
+
+    pub fn get_ended_at_utc(&self) -> &NaiveDateTime {
+        &self.ended_at_utc
+    }
+
+    pub fn get_result(&self) -> &Result<TickStatus, AgentError> {
+        &self.result
+    }
+
+    pub fn get_started_at_utc(&self) -> &NaiveDateTime {
+        &self.started_at_utc
+    }
+
 }
 
 
@@ -148,7 +193,7 @@ mod tests {
             );
             let (abort_handle, abort_registration) =
                 AbortHandle::new_pair();
-            let result = agent.start(abort_registration).await;
+            let result = agent.start(Uuid::new_v4(), abort_registration).await;
 
             assert_eq!(result.result.unwrap(), TickStatus::Success);
 
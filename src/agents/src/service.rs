@@ -1,7 +1,9 @@
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use actix::Arbiter;
+use chrono::Utc;
 use dashmap::DashMap;
 use futures::future::AbortHandle;
 use futures::io::Error;
@@ -13,12 +15,17 @@ use buttercup_bts::context::{BTNodeContextService, BTNodeContextServiceError};
 use buttercup_bts::tree::BehaviorTreeService;
 use buttercup_endpoints::endpoints::EndpointService;
 
-use crate::Agent;
+use crate::{Agent, AgentExecution};
 use crate::service::AgentServiceError::AgentAlreadyStarted;
 
+const DEFAULT_EXECUTION_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+// Completed executions can be polled for the execution retention, then they are dropped.
 pub struct AgentService {
 
     context_service: Arc<BTNodeContextService>,
+    executions: Arc<DashMap<Uuid, (AgentExecution, Instant)>>,
+    execution_retention: Duration,
     started_agents: DashMap<Uuid, (Arc<Agent>, AbortHandle)>,
     stopped_agents: DashMap<Uuid, Arc<Agent>>,
     tree_service: Arc<BehaviorTreeService>,
@@ -32,6 +39,8 @@ impl AgentService {
                tree_service: Arc<BehaviorTreeService>) -> Result<AgentService, AgentServiceError> {
         Result::Ok(
             AgentService {
+                executions: Arc::new(DashMap::new()),
+                execution_retention: DEFAULT_EXECUTION_RETENTION,
                 stopped_agents: DashMap::new(),
                 started_agents: DashMap::new(),
                 context_service,
@@ -41,6 +50,18 @@ impl AgentService {
         )
     }
 
+    pub fn with_execution_retention(mut self,
+                                    execution_retention: Duration) -> AgentService {
+        self.execution_retention = execution_retention;
+        self
+    }
+
+    fn is_retained(execution: &(AgentExecution, Instant),
+                   execution_retention: &Duration) -> bool {
+        let (execution, updated_at) = execution;
+        !execution.is_completed() || updated_at.elapsed() < *execution_retention
+    }
+
     pub fn build_new_agent(&self,
                            tree_id: &i32) -> Result<Uuid, AgentServiceError> {
         if let Some(tree) = self.tree_service.get_by_id(tree_id) {
@@ -59,7 +80,7 @@ impl AgentService {
     }
 
     pub fn start_agent_by_id(&self,
-                             agent_id: &Uuid) -> Result<(), AgentServiceError> {
+                             agent_id: &Uuid) -> Result<Uuid, AgentServiceError> {
         match self.stopped_agents.remove(agent_id) {
             None => {
                 if self.started_agents.contains_key(agent_id) {
@@ -74,15 +95,37 @@ impl AgentService {
 
                 let agent_ref = agent.clone();
 
+                let execution_id = Uuid::new_v4();
+                let executions = self.executions.clone();
+                let execution_retention = self.execution_retention;
+
+                executions.retain(|_, execution| AgentService::is_retained(execution, &execution_retention));
+                executions.insert(execution_id,
+                                  (AgentExecution::Running {
+                                      agent_id: *agent_id,
+                                      started_at_utc: Utc::now().naive_utc()
+                                  }, Instant::now()));
+
                 self.runtime.spawn(async move {
-                    agent.start(abort_registration).await
+                    let result = agent.start(execution_id, abort_registration).await;
+
+                    executions.retain(|_, execution| AgentService::is_retained(execution, &execution_retention));
+                    executions.insert(execution_id, (AgentExecution::Completed(result), Instant::now()));
                 });
 
                 self.started_agents.insert(agent_id.clone(), (agent_ref, abort_handle));
+
+                Result::Ok(execution_id)
             }
         }
+    }
 
-        Result::Ok(())
+    pub fn get_execution_by_id(&self,
+                               execution_id: &Uuid) -> Option<AgentExecution> {
+        self.executions
+            .get(execution_id)
+            .filter(|execution| AgentService::is_retained(execution.value(), &self.execution_retention))
+            .map(|execution| execution.value().0.clone())
     }
 
     pub fn stop_agent_by_id(&self,
@@ -124,4 +167,103 @@ impl From<std::io::Error> for AgentServiceError {
     fn from(err: Error) -> Self {
         AgentServiceError::IOError(err.to_string())
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use actix_rt::System;
+
+    use buttercup_bts::context::test_utils;
+    use buttercup_bts::node::action::logging::PrintLogActionNode;
+    use buttercup_bts::node::root::one_off::OneOffRootBTNode;
+    use buttercup_bts::tick::TickStatus;
+    use buttercup_bts::tree::BehaviorTree;
+
+    use super::*;
+
+    #[test]
+    fn test_execution_can_be_polled_until_completed() {
+        let _system = System::new();
+        let context_service = Arc::new(BTNodeContextService::default());
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  PrintLogActionNode::new(
+                                      3,
+                                      "hello".to_owned())
+                                      .into())
+                                  .into()));
+
+        let agent_service = AgentService::new(context_service.clone(), tree_service).unwrap();
+        let agent_id = agent_service.build_new_agent(&1).unwrap();
+        let execution_id = agent_service.start_agent_by_id(&agent_id).unwrap();
+
+        let mut execution = agent_service.get_execution_by_id(&execution_id).unwrap();
+        while !execution.is_completed() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            execution = agent_service.get_execution_by_id(&execution_id).unwrap();
+        }
+
+        match execution {
+            AgentExecution::Completed(result) => {
+                assert_eq!(result.get_id(), &execution_id);
+                assert_eq!(result.get_agent_id(), &agent_id);
+                assert_eq!(result.get_result(), &Result::Ok(TickStatus::Success));
+            }
+            AgentExecution::Running { .. } => unreachable!()
+        }
+
+        assert!(agent_service.get_execution_by_id(&Uuid::new_v4()).is_none());
+
+        let path = test_utils::get_path(
+            agent_service.started_agents.get(&agent_id).unwrap().0.context.get_context());
+
+        drop(agent_service);
+        drop(context_service);
+
+        test_utils::destroy(path);
+    }
+
+    #[test]
+    fn test_drops_completed_executions_after_retention() {
+        let _system = System::new();
+        let context_service = Arc::new(BTNodeContextService::default());
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        tree_service.insert(
+            BehaviorTree::new(1,
+                              OneOffRootBTNode::new(
+                                  2,
+                                  PrintLogActionNode::new(
+                                      3,
+                                      "hello".to_owned())
+                                      .into())
+                                  .into()));
+
+        let agent_service = AgentService::new(context_service.clone(), tree_service).unwrap()
+            .with_execution_retention(Duration::from_secs(0));
+        let agent_id = agent_service.build_new_agent(&1).unwrap();
+        let execution_id = agent_service.start_agent_by_id(&agent_id).unwrap();
+
+        while agent_service.get_execution_by_id(&execution_id).is_some() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        agent_service.stop_agent_by_id(&agent_id).unwrap();
+        agent_service.start_agent_by_id(&agent_id).unwrap();
+
+        assert_eq!(1, agent_service.executions.len());
+        assert!(!agent_service.executions.contains_key(&execution_id));
+
+        let path = test_utils::get_path(
+            agent_service.started_agents.get(&agent_id).unwrap().0.context.get_context());
+
+        drop(agent_service);
+        drop(context_service);
+
+        test_utils::destroy(path);
+    }
+
+}
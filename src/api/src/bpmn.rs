@@ -0,0 +1,301 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use buttercup_conditions::{ConditionExpression, LogicalExpression};
+use buttercup_values::duration::parse_iso_8601;
+use buttercup_variables::VariableSpecification;
+
+use crate::document::{BehaviorTreeDocument, NodeDefinitionDocument, RootDefinitionDocument};
+use crate::table::cell::parse_cell;
+use crate::xml::{XmlElement, XmlError};
+
+const ROOT_ID: i32 = 1;
+
+// Elements of a process which are not part of its flow.
+const IGNORED_ELEMENTS: [&str; 6] =
+    ["association", "documentation", "extensionElements", "laneSet", "sequenceFlow", "textAnnotation"];
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum BpmnImportError {
+
+    InvalidCondition(String, String),
+    InvalidTimer(String),
+    InvalidXml(XmlError),
+    MissingElement(String),
+    MissingProcess,
+    StartEventsCount(usize),
+    UnexpectedFlows(String),
+    UnstructuredFlow(String),
+    UnsupportedElement(String, String)
+
+}
+
+impl From<XmlError> for BpmnImportError {
+    fn from(err: XmlError) -> Self {
+        BpmnImportError::InvalidXml(err)
+    }
+}
+
+// Compiles a process made of tasks, exclusive gateways and timers into a tree, running
+// its flow once. The flow has to be structured: every split gateway is either closed by a
+// single join gateway, which all of its branches reach, or all of its branches end. Splits
+// become fallbacks over the conditions of their flows, with the default flow last.
+//
+// Conditions of flows are conjunctions of comparisons of a value with a literal, such as
+// ${amount > 100 && tier == "gold"}, and timers have to be durations.
+pub struct BpmnImporter;
+
+impl BpmnImporter {
+
+    pub fn import(id: i32,
+                  bpmn: &str) -> Result<BehaviorTreeDocument, BpmnImportError> {
+        let definitions = XmlElement::parse(bpmn)?;
+        let process = definitions.get_children()
+            .iter()
+            .filter(|child| child.get_local_name() == "process")
+            .max_by_key(|process| process.get_attribute("isExecutable") == Option::Some("true"))
+            .ok_or(BpmnImportError::MissingProcess)?;
+
+        let mut compiler = BpmnCompiler::new(process)?;
+        let start_events = process.get_children()
+            .iter()
+            .filter(|child| child.get_local_name() == "startEvent")
+            .collect::<Vec<_>>();
+        let start = match start_events.as_slice() {
+            [start] => get_id(start)?,
+            _ => return Result::Err(BpmnImportError::StartEventsCount(start_events.len()))
+        };
+
+        let (ids, join) = compiler.compile_path(start)?;
+        if let Some(join) = join {
+            return Result::Err(BpmnImportError::UnstructuredFlow(join.to_owned()));
+        }
+        let child_id = compiler.push_sequence(ids);
+
+        Result::Ok(BehaviorTreeDocument::new(id, RootDefinitionDocument::OneOff { id: ROOT_ID, child_id }, compiler.nodes))
+    }
+
+}
+
+struct BpmnCompiler<'a> {
+
+    elements: HashMap<&'a str, &'a XmlElement>,
+    outgoing: HashMap<&'a str, Vec<&'a XmlElement>>,
+    incoming: HashMap<&'a str, usize>,
+    visited: HashSet<&'a str>,
+    nodes: Vec<NodeDefinitionDocument>,
+    last_id: i32
+
+}
+
+impl<'a> BpmnCompiler<'a> {
+
+    fn new(process: &'a XmlElement) -> Result<BpmnCompiler<'a>, BpmnImportError> {
+        let mut compiler = BpmnCompiler {
+            elements: HashMap::new(),
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+            visited: HashSet::new(),
+            nodes: Vec::new(),
+            last_id: ROOT_ID
+        };
+
+        for child in process.get_children() {
+            if child.get_local_name() == "sequenceFlow" {
+                let source = get_attribute(child, "sourceRef")?;
+                let target = get_attribute(child, "targetRef")?;
+
+                compiler.outgoing.entry(source).or_default().push(child);
+                *compiler.incoming.entry(target).or_default() += 1;
+            } else if !IGNORED_ELEMENTS.contains(&child.get_local_name()) {
+                compiler.elements.insert(get_id(child)?, child);
+            }
+        }
+
+        Result::Ok(compiler)
+    }
+
+    fn next_id(&mut self) -> i32 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    // Follows the flow from the element until it ends, or until it reaches a join gateway
+    // which is returned along with the nodes to run in order.
+    fn compile_path(&mut self,
+                    start: &'a str) -> Result<(Vec<i32>, Option<&'a str>), BpmnImportError> {
+        let mut ids = Vec::new();
+        let mut current = start;
+
+        loop {
+            let element = *self.elements.get(current).ok_or_else(|| BpmnImportError::MissingElement(current.to_owned()))?;
+            let outgoing = self.outgoing.get(current).cloned().unwrap_or_default();
+            let incoming = self.incoming.get(current).cloned().unwrap_or_default();
+            let is_gateway = element.get_local_name() == "exclusiveGateway";
+
+            if is_gateway && incoming > 1 && outgoing.len() > 1 {
+                return Result::Err(BpmnImportError::UnexpectedFlows(current.to_owned()));
+            }
+            if is_gateway && incoming > 1 {
+                return Result::Ok((ids, Option::Some(current)));
+            }
+            if !self.visited.insert(current) {
+                return Result::Err(BpmnImportError::UnstructuredFlow(current.to_owned()));
+            }
+
+            match element.get_local_name() {
+                "endEvent" if outgoing.is_empty() => return Result::Ok((ids, Option::None)),
+                "endEvent" => return Result::Err(BpmnImportError::UnexpectedFlows(current.to_owned())),
+                "exclusiveGateway" if outgoing.len() > 1 => {
+                    let (id, join) = self.compile_split(element, &outgoing)?;
+                    ids.push(id);
+
+                    match join {
+                        Some(join) => {
+                            self.visited.insert(join);
+                            current = join;
+                        },
+                        None => return Result::Ok((ids, Option::None))
+                    }
+                },
+                "startEvent" | "exclusiveGateway" => {},
+                _ => {
+                    let id = self.compile_node(element)?;
+                    ids.push(id);
+                }
+            }
+
+            current = match self.outgoing.get(current).map(Vec::as_slice) {
+                Some([flow]) => get_attribute(flow, "targetRef")?,
+                _ => return Result::Err(BpmnImportError::UnexpectedFlows(current.to_owned()))
+            };
+        }
+    }
+
+    fn compile_split(&mut self,
+                     gateway: &'a XmlElement,
+                     outgoing: &[&'a XmlElement]) -> Result<(i32, Option<&'a str>), BpmnImportError> {
+        let gateway_id = get_id(gateway)?;
+        let default = gateway.get_attribute("default");
+        let mut children_ids = Vec::new();
+        let mut default_id = Option::None;
+        let mut joins = HashSet::new();
+
+        for flow in outgoing {
+            let (ids, join) = self.compile_path(get_attribute(flow, "targetRef")?)?;
+            joins.insert(join);
+            let sequence_id = self.push_sequence(ids);
+            let flow_id = get_id(flow)?;
+
+            if default == Option::Some(flow_id) {
+                default_id = Option::Some(sequence_id);
+                continue;
+            }
+
+            let expression = flow.get_child("conditionExpression")
+                .ok_or_else(|| BpmnImportError::InvalidCondition(flow_id.to_owned(), "missing condition".to_owned()))
+                .and_then(|condition| parse_condition(condition.get_text())
+                    .map_err(|err| BpmnImportError::InvalidCondition(flow_id.to_owned(), err)))?;
+            let id = self.next_id();
+
+            self.nodes.push(NodeDefinitionDocument::Condition { id, child_id: sequence_id, expression });
+            children_ids.push(id);
+        }
+
+        let join = match joins.into_iter().collect::<Vec<_>>().as_slice() {
+            [None] => Option::None,
+            [Some(join)] if self.incoming.get(join) == Option::Some(&outgoing.len()) => Option::Some(*join),
+            _ => return Result::Err(BpmnImportError::UnstructuredFlow(gateway_id.to_owned()))
+        };
+
+        let id = self.next_id();
+        children_ids.extend(default_id);
+        self.nodes.push(NodeDefinitionDocument::Fallback { id, children_ids });
+
+        Result::Ok((id, join))
+    }
+
+    fn compile_node(&mut self,
+                    element: &'a XmlElement) -> Result<i32, BpmnImportError> {
+        let element_id = get_id(element)?;
+        let name = element.get_attribute("name").unwrap_or(element_id).to_owned();
+        let id = self.next_id();
+
+        let node = match element.get_local_name() {
+            "task" | "manualTask" | "scriptTask" => NodeDefinitionDocument::PrintLog { id, message: name },
+            "userTask" => NodeDefinitionDocument::HumanTask {
+                id,
+                title: name,
+                assignee: element.get_attribute("assignee").map(str::to_owned),
+                form: HashMap::new()
+            },
+            "serviceTask" | "sendTask" => NodeDefinitionDocument::PublishMessage {
+                id,
+                topic: element.get_attribute("topic").unwrap_or(element_id).to_owned(),
+                value_names: HashSet::new()
+            },
+            "receiveTask" => NodeDefinitionDocument::ReceiveMessage {
+                id,
+                topic: element.get_attribute("messageRef").unwrap_or(element_id).to_owned()
+            },
+            "intermediateCatchEvent" => NodeDefinitionDocument::WaitDuration {
+                id,
+                duration: element.get_child("timerEventDefinition")
+                    .and_then(|timer| timer.get_child("timeDuration"))
+                    .and_then(|duration| parse_iso_8601(duration.get_text()))
+                    .map(VariableSpecification::from)
+                    .ok_or_else(|| BpmnImportError::InvalidTimer(element_id.to_owned()))?
+            },
+            other => return Result::Err(BpmnImportError::UnsupportedElement(element_id.to_owned(), other.to_owned()))
+        };
+
+        self.nodes.push(node);
+        Result::Ok(id)
+    }
+
+    fn push_sequence(&mut self,
+                     mut ids: Vec<i32>) -> i32 {
+        if ids.len() == 1 {
+            return ids.remove(0);
+        }
+
+        let id = self.next_id();
+        self.nodes.push(NodeDefinitionDocument::Sequence { id, children_ids: ids });
+        id
+    }
+
+}
+
+fn get_id(element: &XmlElement) -> Result<&str, BpmnImportError> {
+    get_attribute(element, "id")
+}
+
+fn get_attribute<'a>(element: &'a XmlElement,
+                     name: &str) -> Result<&'a str, BpmnImportError> {
+    element.get_attribute(name)
+        .ok_or_else(|| BpmnImportError::MissingElement(format!("{}@{}", element.get_local_name(), name)))
+}
+
+fn parse_condition(text: &str) -> Result<ConditionExpression, String> {
+    let text = text.trim();
+    let text = text.strip_prefix("${").and_then(|text| text.strip_suffix('}')).unwrap_or(text);
+    let mut relations = Vec::new();
+
+    for comparison in text.split("&&") {
+        let operator = comparison.find(|char| "<>=!".contains(char))
+            .ok_or_else(|| format!("missing operator in {}", comparison.trim()))?;
+        let name = comparison[..operator].trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Result::Err(format!("invalid value name in {}", comparison.trim()));
+        }
+
+        let test = comparison[operator..].trim().replacen("==", "=", 1);
+        relations.extend(parse_cell(name, &test)?.into_iter().map(ConditionExpression::RelationExpression));
+    }
+
+    Result::Ok(match relations.len() {
+        1 => relations.remove(0),
+        _ => ConditionExpression::LogicalExpression(Box::new(LogicalExpression::And(relations)))
+    })
+}
@@ -0,0 +1,373 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use buttercup_bts::node::decorator::repeat::RepeatPolicy;
+use buttercup_variables::VariableSpecification;
+
+use crate::document::{BehaviorTreeDocument, NodeDefinitionDocument, RootDefinitionDocument};
+use crate::xml::{XmlElement, XmlError};
+
+const FORMAT: &str = "4";
+const ROOT_ID: i32 = 1;
+
+// Elements of version 3 which name the custom node in their ID attribute.
+const CATEGORIES: [&str; 4] = ["Action", "Condition", "Control", "Decorator"];
+
+// Fields which link nodes to their children, rather than configure them.
+const CHILDREN_FIELDS: [&str; 3] = ["child_id", "children_ids", "escalation_id"];
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum BtCppError {
+
+    Cycle(i32),
+    InvalidXml(XmlError),
+    MissingNode(i32),
+    MissingTree(String),
+    SerializeError(String),
+    UnsupportedConstructs(Vec<String>)
+
+}
+
+impl From<XmlError> for BtCppError {
+    fn from(err: XmlError) -> Self {
+        BtCppError::InvalidXml(err)
+    }
+}
+
+pub fn get_tree_name(tree_id: &i32) -> String {
+    format!("Tree{}", tree_id)
+}
+
+pub fn parse_tree_name(name: &str) -> Option<i32> {
+    name.strip_prefix("Tree").and_then(|id| id.parse().ok())
+}
+
+// Writes trees in the xml format of BehaviorTree.CPP, version 4, which Groot can show.
+// Sequences, fallbacks, parallels, inverters, subtrees, repeats of a number of times,
+// and timeouts and waits of literal durations become their standard counterparts. Every other node keeps its type as the id of a
+// custom node, with its settings as ports: strings as they are, anything else as json.
+// Roots other than one-off ones wrap the tree in a custom decorator named after them.
+pub struct BtCppExporter;
+
+impl BtCppExporter {
+
+    pub fn export(document: &BehaviorTreeDocument) -> Result<String, BtCppError> {
+        let mut nodes = HashMap::new();
+        for node in document.get_nodes() {
+            let fields = match serde_json::to_value(node) {
+                Ok(Value::Object(fields)) => fields,
+                Ok(_) => return Result::Err(BtCppError::SerializeError("node is not an object".to_owned())),
+                Err(err) => return Result::Err(BtCppError::SerializeError(err.to_string()))
+            };
+            let id = fields.get("id").and_then(Value::as_i64).unwrap_or_default() as i32;
+
+            nodes.insert(id, (node.get_children_ids(), fields));
+        }
+
+        let mut exporter = TreeExporter { nodes, models: BTreeMap::new(), path: HashSet::new() };
+
+        let tree = match document.get_root() {
+            RootDefinitionDocument::OneOff { child_id, .. } => exporter.export_node(child_id)?,
+            RootDefinitionDocument::Reactive { child_id, stop_on_error, .. } => exporter.export_root(
+                "Reactive", vec![("stop_on_error".to_owned(), stop_on_error.to_string())], child_id)?,
+            RootDefinitionDocument::ToFirstError { child_id, .. } => exporter.export_root("ToFirstError", Vec::new(), child_id)?,
+            RootDefinitionDocument::UntilStopped { child_id, .. } => exporter.export_root("UntilStopped", Vec::new(), child_id)?
+        };
+
+        let tree_name = get_tree_name(document.get_id());
+        let behavior_tree = XmlElement::new("BehaviorTree").with_attribute("ID", &tree_name);
+
+        let models = exporter.models
+            .into_iter()
+            .fold(XmlElement::new("TreeNodesModel"), |models, (id, (category, ports))| models.with_child(
+                ports.iter().fold(XmlElement::new(category).with_attribute("ID", &id), |model, port|
+                    model.with_child(XmlElement::new("input_port").with_attribute("name", port)))));
+
+        Result::Ok(XmlElement::new("root")
+            .with_attribute("BTCPP_format", FORMAT)
+            .with_attribute("main_tree_to_execute", &tree_name)
+            .with_child(behavior_tree.with_child(tree))
+            .with_child(models)
+            .to_xml())
+    }
+
+}
+
+fn get_literal_millis(duration: &Value) -> u64 {
+    let duration = &duration["Literal"];
+
+    duration["secs"].as_u64().unwrap_or_default() * 1000 + duration["nanos"].as_u64().unwrap_or_default() / 1_000_000
+}
+
+struct TreeExporter {
+
+    nodes: HashMap<i32, (Vec<i32>, Map<String, Value>)>,
+    models: BTreeMap<String, (&'static str, Vec<String>)>,
+    path: HashSet<i32>
+
+}
+
+impl TreeExporter {
+
+    fn export_root(&mut self,
+                   name: &str,
+                   ports: Vec<(String, String)>,
+                   child_id: &i32) -> Result<XmlElement, BtCppError> {
+        let child = self.export_node(child_id)?;
+
+        Result::Ok(self.export_custom(name, "Decorator", ports).with_child(child))
+    }
+
+    fn export_node(&mut self,
+                   id: &i32) -> Result<XmlElement, BtCppError> {
+        let (children_ids, fields) = self.nodes.get(id).cloned().ok_or(BtCppError::MissingNode(*id))?;
+        if !self.path.insert(*id) {
+            return Result::Err(BtCppError::Cycle(*id));
+        }
+
+        let children = children_ids
+            .iter()
+            .map(|child_id| self.export_node(child_id))
+            .collect::<Result<Vec<XmlElement>, BtCppError>>()?;
+        let node_type = fields.get("type").and_then(Value::as_str).unwrap_or_default();
+        let children_count = children.len();
+
+        let element = match node_type {
+            "Sequence" | "Fallback" => XmlElement::new(node_type),
+            "Invert" => XmlElement::new("Inverter"),
+            "ExecuteSubTree" => XmlElement::new("SubTree")
+                .with_attribute("ID", &get_tree_name(&(fields["tree_id"].as_i64().unwrap_or_default() as i32))),
+            "Parallel" => {
+                let successes = fields["num_successes_to_succeed"].as_u64().unwrap_or_default() as usize;
+
+                XmlElement::new("Parallel")
+                    .with_attribute("success_count", &successes.to_string())
+                    .with_attribute("failure_count", &(children_count + 1).saturating_sub(successes).to_string())
+            },
+            "Repeat" if fields["policy"].get("Times").is_some() => XmlElement::new("Repeat")
+                .with_attribute("num_cycles", &fields["policy"]["Times"].to_string()),
+            "Timeout" if fields["duration"].get("Literal").is_some() => XmlElement::new("Timeout")
+                .with_attribute("msec", &get_literal_millis(&fields["duration"]).to_string()),
+            "WaitDuration" if fields["duration"].get("Literal").is_some() => XmlElement::new("Sleep")
+                .with_attribute("msec", &get_literal_millis(&fields["duration"]).to_string()),
+            _ => {
+                let category = match children_count {
+                    0 => "Action",
+                    1 if fields.contains_key("child_id") => "Decorator",
+                    _ => "Control"
+                };
+                let ports = fields.iter()
+                    .filter(|(name, _)| !["type", "id"].contains(&name.as_str()) && !CHILDREN_FIELDS.contains(&name.as_str()))
+                    .map(|(name, value)| (name.clone(), match value {
+                        Value::String(value) => value.clone(),
+                        value => value.to_string()
+                    }))
+                    .collect();
+
+                self.export_custom(node_type, category, ports)
+            }
+        };
+
+        self.path.remove(id);
+        Result::Ok(children.into_iter().fold(element, XmlElement::with_child))
+    }
+
+    fn export_custom(&mut self,
+                     name: &str,
+                     category: &'static str,
+                     ports: Vec<(String, String)>) -> XmlElement {
+        let model = self.models.entry(name.to_owned()).or_insert_with(|| (category, Vec::new()));
+        for (port, _) in &ports {
+            if !model.1.contains(port) {
+                model.1.push(port.clone());
+            }
+        }
+
+        ports.iter().fold(XmlElement::new(name), |element, (port, value)| element.with_attribute(port, value))
+    }
+
+}
+
+// Reads the main tree of BehaviorTree.CPP xml, of version 3 or 4. Sequences, fallbacks,
+// inverters, parallels, repeats, timeouts, sleeps and subtrees of trees named Tree<id>
+// map to our nodes, as do custom nodes named after our node types, with their settings
+// as ports, which is how trees are exported. Anything else is reported at once, along
+// with its reason, rather than dropped from the tree. Nodes are numbered in the order
+// they appear.
+pub struct BtCppImporter;
+
+impl BtCppImporter {
+
+    pub fn import(id: i32,
+                  xml: &str) -> Result<BehaviorTreeDocument, BtCppError> {
+        let root = XmlElement::parse(xml)?;
+        let trees = root.get_children()
+            .iter()
+            .filter(|child| child.get_name() == "BehaviorTree")
+            .collect::<Vec<_>>();
+        let tree = match root.get_attribute("main_tree_to_execute") {
+            Some(name) => trees.iter().find(|tree| tree.get_attribute("ID") == Option::Some(name)),
+            None => trees.first()
+        }.ok_or_else(|| BtCppError::MissingTree(root.get_attribute("main_tree_to_execute").unwrap_or_default().to_owned()))?;
+
+        let mut importer = TreeImporter { nodes: Vec::new(), unsupported: Vec::new(), last_id: ROOT_ID };
+        let child = match tree.get_children().as_slice() {
+            [child] => child,
+            children => return Result::Err(BtCppError::UnsupportedConstructs(vec![
+                format!("BehaviorTree: expected a single child, found {}", children.len())]))
+        };
+
+        let root_document = match (child.get_name().as_str(), child.get_children().as_slice()) {
+            ("Reactive", [grandchild]) => RootDefinitionDocument::Reactive {
+                id: ROOT_ID,
+                child_id: importer.import_node(grandchild),
+                stop_on_error: child.get_attribute("stop_on_error") == Option::Some("true")
+            },
+            ("ToFirstError", [grandchild]) => RootDefinitionDocument::ToFirstError { id: ROOT_ID, child_id: importer.import_node(grandchild) },
+            ("UntilStopped", [grandchild]) => RootDefinitionDocument::UntilStopped { id: ROOT_ID, child_id: importer.import_node(grandchild) },
+            _ => RootDefinitionDocument::OneOff { id: ROOT_ID, child_id: importer.import_node(child) }
+        };
+
+        if !importer.unsupported.is_empty() {
+            return Result::Err(BtCppError::UnsupportedConstructs(importer.unsupported));
+        }
+
+        Result::Ok(BehaviorTreeDocument::new(id, root_document, importer.nodes))
+    }
+
+}
+
+struct TreeImporter {
+
+    nodes: Vec<NodeDefinitionDocument>,
+    unsupported: Vec<String>,
+    last_id: i32
+
+}
+
+impl TreeImporter {
+
+    // Unsupported nodes still get an id, so that the rest of the tree is checked too.
+    fn import_node(&mut self,
+                   element: &XmlElement) -> i32 {
+        self.last_id += 1;
+        let id = self.last_id;
+        let children_ids: Vec<i32> = element.get_children()
+            .iter()
+            .map(|child| self.import_node(child))
+            .collect();
+
+        let name = match (element.get_name().as_str(), element.get_attribute("ID")) {
+            (category, Some(node_type)) if CATEGORIES.contains(&category) => node_type,
+            (name, _) => name
+        };
+
+        match self.import_standard(id, name, element, &children_ids)
+            .unwrap_or_else(|| self.import_custom(id, name, element, &children_ids)) {
+            Ok(node) => self.nodes.push(node),
+            Err(reason) => self.unsupported.push(format!("{}: {}", name, reason))
+        }
+
+        id
+    }
+
+    fn import_standard(&self,
+                       id: i32,
+                       name: &str,
+                       element: &XmlElement,
+                       children_ids: &[i32]) -> Option<Result<NodeDefinitionDocument, String>> {
+        Option::Some(match (name, children_ids) {
+            ("Sequence", _) => Result::Ok(NodeDefinitionDocument::Sequence { id, children_ids: children_ids.to_vec() }),
+            ("Fallback", _) => Result::Ok(NodeDefinitionDocument::Fallback { id, children_ids: children_ids.to_vec() }),
+            ("Inverter", [child_id]) => Result::Ok(NodeDefinitionDocument::Invert { id, child_id: *child_id }),
+            ("Inverter", _) => Result::Err(format!("expected a single child, found {}", children_ids.len())),
+            ("SubTree", []) => element.get_attribute("ID")
+                .and_then(parse_tree_name)
+                .map(|tree_id| NodeDefinitionDocument::ExecuteSubTree { id, tree_id, namespace: Option::None })
+                .ok_or_else(|| format!("only trees named Tree<id> can be executed, not {}", element.get_attribute("ID").unwrap_or_default())),
+            ("Parallel", _) => import_parallel(id, element, children_ids),
+            // Repeating forever until a failure, which fails then, has no counterpart.
+            ("Repeat", [child_id]) if element.get_attribute("num_cycles").is_some() => element.get_attribute("num_cycles")
+                .and_then(|cycles| cycles.parse().ok())
+                .map(|cycles| NodeDefinitionDocument::Repeat { id, child_id: *child_id, policy: RepeatPolicy::Times(cycles) })
+                .ok_or_else(|| "expected a positive num_cycles".to_owned()),
+            ("Timeout", [child_id]) if element.get_attribute("msec").is_some() => parse_millis(element)
+                .map(|duration| NodeDefinitionDocument::Timeout { id, child_id: *child_id, duration }),
+            ("Sleep", []) => parse_millis(element)
+                .map(|duration| NodeDefinitionDocument::WaitDuration { id, duration }),
+            _ => return Option::None
+        })
+    }
+
+    // Ports are read as json, or as strings when that does not fit the node.
+    fn import_custom(&self,
+                     id: i32,
+                     name: &str,
+                     element: &XmlElement,
+                     children_ids: &[i32]) -> Result<NodeDefinitionDocument, String> {
+        let mut fields = Map::new();
+        fields.insert("type".to_owned(), Value::String(name.to_owned()));
+        fields.insert("id".to_owned(), id.into());
+        if let [child_id, ..] = children_ids {
+            fields.insert("child_id".to_owned(), (*child_id).into());
+            fields.insert("children_ids".to_owned(), children_ids.into());
+        }
+        if let [_, escalation_id] = children_ids {
+            fields.insert("escalation_id".to_owned(), (*escalation_id).into());
+        }
+
+        let parsed = element.get_attributes()
+            .iter()
+            .map(|(port, value)| (port.clone(), serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.clone()))))
+            .chain(fields.clone())
+            .collect::<Map<String, Value>>();
+        let node = serde_json::from_value::<NodeDefinitionDocument>(Value::Object(parsed))
+            .or_else(|err| {
+                let raw = element.get_attributes()
+                    .iter()
+                    .map(|(port, value)| (port.clone(), Value::String(value.clone())))
+                    .chain(fields)
+                    .collect::<Map<String, Value>>();
+
+                serde_json::from_value::<NodeDefinitionDocument>(Value::Object(raw)).map_err(|_| err.to_string())
+            })?;
+
+        if node.get_children_ids() != children_ids {
+            return Result::Err(format!("children do not match the node, found {}", children_ids.len()));
+        }
+
+        Result::Ok(node)
+    }
+
+}
+
+fn parse_millis(element: &XmlElement) -> Result<VariableSpecification<Duration>, String> {
+    element.get_attribute("msec")
+        .and_then(|msec| msec.parse().ok())
+        .map(|msec| VariableSpecification::Literal(Arc::new(Duration::from_millis(msec))))
+        .ok_or_else(|| "expected msec in milliseconds".to_owned())
+}
+
+// Parallels succeed once enough of their children do, and fail as soon as they no longer
+// can, so the failure count of BehaviorTree.CPP has to follow from the success count.
+fn import_parallel(id: i32,
+                   element: &XmlElement,
+                   children_ids: &[i32]) -> Result<NodeDefinitionDocument, String> {
+    // Negative counts stand for all the children.
+    let count = |attribute| element.get_attribute(attribute)
+        .map(|count| count.parse::<i64>().map_err(|_| format!("invalid {} {}", attribute, count)))
+        .transpose()
+        .map(|count| count.map(|count| if count < 0 { children_ids.len() } else { count as usize }));
+
+    let successes = count("success_count")?.unwrap_or(children_ids.len());
+    let failures = count("failure_count")?.unwrap_or(1);
+    if successes + failures != children_ids.len() + 1 {
+        return Result::Err(format!("failure_count has to be {}", (children_ids.len() + 1).saturating_sub(successes)));
+    }
+
+    Result::Ok(NodeDefinitionDocument::Parallel { id, children_ids: children_ids.to_vec(), num_successes_to_succeed: successes })
+}
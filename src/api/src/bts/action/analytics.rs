@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+
+use buttercup_bts::node::action::analytics::{EmitEventActionNode, EmitMetricActionNode, MetricOperation};
+use buttercup_bts::node::BTNode;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct EmitMetricActionNodeDefinition {
+
+    id: i32,
+    name: String,
+    operation: MetricOperation,
+    labels: Vec<(String, String)>
+
+}
+
+impl EmitMetricActionNodeDefinition {
+
+    pub fn new(id: i32,
+               name: String,
+               operation: MetricOperation,
+               labels: Vec<(String, String)>) -> EmitMetricActionNodeDefinition {
+        EmitMetricActionNodeDefinition {
+            id,
+            name,
+            operation,
+            labels
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for EmitMetricActionNodeDefinition {
+
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            EmitMetricActionNode::new(
+                self.id,
+                self.name.clone(),
+                self.operation.clone(),
+                self.labels.clone()).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+pub struct EmitEventActionNodeDefinition {
+
+    id: i32,
+    name: String,
+    value_names: HashSet<String>
+
+}
+
+impl EmitEventActionNodeDefinition {
+
+    pub fn new(id: i32,
+               name: String,
+               value_names: HashSet<String>) -> EmitEventActionNodeDefinition {
+        EmitEventActionNodeDefinition {
+            id,
+            name,
+            value_names
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for EmitEventActionNodeDefinition {
+
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            EmitEventActionNode::new(
+                self.id,
+                self.name.clone(),
+                self.value_names.clone()).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::command::ContentCommandAddress;
+use buttercup_bts::node::action::command::SelectCommandActionNode;
+use buttercup_bts::node::BTNode;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+// A command is given either by its address or by its name in the command registry, which is
+// resolved when the tree is built.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[serde(untagged)]
+pub enum CommandReference {
+
+    Address(ContentCommandAddress),
+    Name(String)
+
+}
+
+pub struct SelectCommandActionNodeDefinition {
+
+    id: i32,
+    command: CommandReference
+
+}
+
+impl SelectCommandActionNodeDefinition {
+
+    pub fn new(id: i32,
+               command: CommandReference) -> SelectCommandActionNodeDefinition {
+        SelectCommandActionNodeDefinition {
+            id,
+            command
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for SelectCommandActionNodeDefinition {
+
+    fn build(&self,
+             context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        let address = match &self.command {
+            CommandReference::Address(address) => *address,
+            CommandReference::Name(name) => context.get_command_address(&self.id, name)?
+        };
+
+        Result::Ok(SelectCommandActionNode::new(self.id, address).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
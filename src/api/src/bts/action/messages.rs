@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+
+use buttercup_bts::node::action::messages::{PublishMessageActionNode, ReceiveMessageActionNode};
+use buttercup_bts::node::BTNode;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct PublishMessageActionNodeDefinition {
+
+    id: i32,
+    topic: String,
+    value_names: HashSet<String>
+
+}
+
+impl PublishMessageActionNodeDefinition {
+
+    pub fn new(id: i32,
+               topic: String,
+               value_names: HashSet<String>) -> PublishMessageActionNodeDefinition {
+        PublishMessageActionNodeDefinition {
+            id,
+            topic,
+            value_names
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for PublishMessageActionNodeDefinition {
+
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            PublishMessageActionNode::new(
+                self.id,
+                self.topic.clone(),
+                self.value_names.clone()).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+pub struct ReceiveMessageActionNodeDefinition {
+
+    id: i32,
+    topic: String
+
+}
+
+impl ReceiveMessageActionNodeDefinition {
+
+    pub fn new(id: i32,
+               topic: String) -> ReceiveMessageActionNodeDefinition {
+        ReceiveMessageActionNodeDefinition {
+            id,
+            topic
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for ReceiveMessageActionNodeDefinition {
+
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            ReceiveMessageActionNode::new(
+                self.id,
+                self.topic.clone()).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
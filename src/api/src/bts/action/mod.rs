@@ -1,4 +1,9 @@
-
+pub mod analytics;
+pub mod command;
 pub mod logging;
+pub mod messages;
+pub mod quota;
 pub mod subtree;
-pub mod wait;
+pub mod task;
+pub mod values;
+pub mod wait;
\ No newline at end of file
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use buttercup_bts::node::action::quota::QuotaActionNode;
+use buttercup_bts::node::BTNode;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct QuotaActionNodeDefinition {
+
+    id: i32,
+    counter: String,
+    subject_value_name: Option<String>,
+    limit: u64,
+    window: Duration
+
+}
+
+impl QuotaActionNodeDefinition {
+
+    pub fn new(id: i32,
+               counter: String,
+               subject_value_name: Option<String>,
+               limit: u64,
+               window: Duration) -> QuotaActionNodeDefinition {
+        QuotaActionNodeDefinition {
+            id,
+            counter,
+            subject_value_name,
+            limit,
+            window
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for QuotaActionNodeDefinition {
+
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            QuotaActionNode::new(
+                self.id,
+                self.counter.clone(),
+                self.subject_value_name.clone(),
+                self.limit,
+                self.window).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -8,7 +8,8 @@ use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, Behavio
 pub struct ExecuteSubTreeActionNodeDefinition {
 
     id: i32,
-    tree_id: i32
+    tree_id: i32,
+    namespace: Option<String>
 
 }
 
@@ -18,10 +19,17 @@ impl ExecuteSubTreeActionNodeDefinition {
                tree_id: i32) -> ExecuteSubTreeActionNodeDefinition {
         ExecuteSubTreeActionNodeDefinition {
             id,
-            tree_id
+            tree_id,
+            namespace: Option::None
         }
     }
 
+    pub fn with_namespace(mut self,
+                          namespace: String) -> ExecuteSubTreeActionNodeDefinition {
+        self.namespace = Option::Some(namespace);
+        self
+    }
+
 }
 
 impl BehaviorTreeNodeDefinition for ExecuteSubTreeActionNodeDefinition {
@@ -29,7 +37,12 @@ impl BehaviorTreeNodeDefinition for ExecuteSubTreeActionNodeDefinition {
              context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
         let subtree = context.get_subtree(&self.tree_id)?;
 
-        Result::Ok(ExecuteSubTreeActionNode::new(self.id, subtree)?.into())
+        let node = ExecuteSubTreeActionNode::new(self.id, subtree)?;
+
+        Result::Ok(match &self.namespace {
+            Some(namespace) => node.with_namespace(namespace.clone()),
+            None => node
+        }.into())
     }
 
     fn get_id(&self) -> &i32 {
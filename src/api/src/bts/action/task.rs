@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use buttercup_bts::node::action::task::HumanTaskActionNode;
+use buttercup_bts::node::BTNode;
+use buttercup_endpoints::ArgumentDefinition;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct HumanTaskActionNodeDefinition {
+
+    id: i32,
+    title: String,
+    assignee: Option<String>,
+    form: HashMap<String, ArgumentDefinition>
+
+}
+
+impl HumanTaskActionNodeDefinition {
+
+    pub fn new(id: i32,
+               title: String,
+               assignee: Option<String>,
+               form: HashMap<String, ArgumentDefinition>) -> HumanTaskActionNodeDefinition {
+        HumanTaskActionNodeDefinition {
+            id,
+            title,
+            assignee,
+            form
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for HumanTaskActionNodeDefinition {
+
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            HumanTaskActionNode::new(
+                self.id,
+                self.title.clone(),
+                self.assignee.clone(),
+                self.form.clone()).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn get_declared_symbols(&self) -> HashMap<String, Vec<String>> {
+        self.form
+            .iter()
+            .filter(|(_, definition)| !definition.get_symbols().is_empty())
+            .map(|(name, definition)| (name.clone(), definition.get_symbols().clone()))
+            .collect()
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use buttercup_bts::node::action::values::{SetValueActionNode, TransformValueActionNode};
+use buttercup_bts::node::BTNode;
+use buttercup_transformations::Transformer;
+use buttercup_values::ValueHolder;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct SetValueActionNodeDefinition {
+
+    id: i32,
+    value_name: String,
+    value: ValueHolder
+
+}
+
+impl SetValueActionNodeDefinition {
+
+    pub fn new(id: i32,
+               value_name: String,
+               value: ValueHolder) -> SetValueActionNodeDefinition {
+        SetValueActionNodeDefinition {
+            id,
+            value_name,
+            value
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for SetValueActionNodeDefinition {
+
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            SetValueActionNode::new(
+                self.id,
+                self.value_name.clone(),
+                self.value.clone()).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+pub struct TransformValueActionNodeDefinition {
+
+    id: i32,
+    input_names: HashSet<String>,
+    transformer: Arc<Transformer>
+
+}
+
+impl TransformValueActionNodeDefinition {
+
+    pub fn new(id: i32,
+               input_names: HashSet<String>,
+               transformer: Transformer) -> TransformValueActionNodeDefinition {
+        TransformValueActionNodeDefinition {
+            id,
+            input_names,
+            transformer: Arc::new(transformer)
+        }
+    }
+}
+
+impl BehaviorTreeNodeDefinition for TransformValueActionNodeDefinition {
+
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            TransformValueActionNode::new(
+                self.id,
+                self.input_names.clone(),
+                self.transformer.clone()).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use buttercup_bts::node::action::wait::WaitDurationActionNode;
+use buttercup_bts::node::action::wait::{WaitDurationActionNode, WaitForSignalActionNode};
 use buttercup_bts::node::BTNode;
 use buttercup_variables::VariableSpecification;
 
@@ -13,6 +13,18 @@ pub struct WaitDurationActionNodeDefinition {
 
 }
 
+impl WaitDurationActionNodeDefinition {
+
+    pub fn new(id: i32,
+               duration: VariableSpecification<Duration>) -> WaitDurationActionNodeDefinition {
+        WaitDurationActionNodeDefinition {
+            id,
+            duration
+        }
+    }
+
+}
+
 impl BehaviorTreeNodeDefinition for WaitDurationActionNodeDefinition {
     fn build(&self,
              _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
@@ -26,3 +38,37 @@ impl BehaviorTreeNodeDefinition for WaitDurationActionNodeDefinition {
         &self.id
     }
 }
+
+
+pub struct WaitForSignalActionNodeDefinition {
+
+    id: i32,
+    name: String
+
+}
+
+impl WaitForSignalActionNodeDefinition {
+
+    pub fn new(id: i32,
+               name: String) -> WaitForSignalActionNodeDefinition {
+        WaitForSignalActionNodeDefinition {
+            id,
+            name
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for WaitForSignalActionNodeDefinition {
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            WaitForSignalActionNode::new(self.id, self.name.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
\ No newline at end of file
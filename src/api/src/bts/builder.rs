@@ -0,0 +1,298 @@
+use std::sync::Arc;
+
+use buttercup_bts::node::composite::parallel::ParallelPolicy;
+use buttercup_conditions::ConditionExpression;
+
+use crate::bts::{BehaviorTreeBuildingError, BehaviorTreeDefinition, BehaviorTreeNodeDefinition};
+use crate::bts::action::logging::PrintLogActionNodeDefinition;
+use crate::bts::action::subtree::ExecuteSubTreeActionNodeDefinition;
+use crate::bts::composite::fallback::FallbackCompositeNodeDefinition;
+use crate::bts::composite::parallel::ParallelCompositeNodeDefinition;
+use crate::bts::composite::sequence::SequenceCompositeNodeDefinition;
+use crate::bts::decorator::condition::ConditionDecoratorNodeDefinition;
+use crate::bts::decorator::invert::InvertDecoratorNodeDefinition;
+use crate::bts::root::OneOffRootBTNodeDefinition;
+
+const ROOT_ID: i32 = 1;
+
+type NodeDefinitionResult = Result<Arc<dyn BehaviorTreeNodeDefinition>, BehaviorTreeBuildingError>;
+
+// Builds definitions of trees with one-off roots without numbering their nodes by hand:
+//
+//     Tree::new(1, Sequence::new()
+//         .child(PrintLog::new("hello"))
+//         .child(Subtree(10)))
+//         .build()
+//
+// Nodes are numbered from 2, parents before their children. Nodes without a builder of
+// their own are built with Node, which is given the ids to use.
+pub struct Tree {
+
+    id: i32,
+    root: NodeBuilder
+
+}
+
+impl Tree {
+
+    pub fn new(id: i32,
+               root: impl Into<NodeBuilder>) -> Tree {
+        Tree {
+            id,
+            root: root.into()
+        }
+    }
+
+    pub fn build(self) -> Result<BehaviorTreeDefinition, BehaviorTreeBuildingError> {
+        let mut last_id = ROOT_ID;
+        let mut definitions = Vec::new();
+        let child_id = self.root.build(&mut last_id, &mut definitions)?;
+
+        Result::Ok(BehaviorTreeDefinition::new(
+            self.id, definitions, Box::new(OneOffRootBTNodeDefinition::new(ROOT_ID, child_id))))
+    }
+
+}
+
+pub struct NodeBuilder {
+
+    children: Vec<NodeBuilder>,
+    build: Box<dyn FnOnce(i32, Vec<i32>) -> NodeDefinitionResult>
+
+}
+
+impl NodeBuilder {
+
+    fn build(self,
+             last_id: &mut i32,
+             definitions: &mut Vec<Arc<dyn BehaviorTreeNodeDefinition>>) -> Result<i32, BehaviorTreeBuildingError> {
+        *last_id += 1;
+        let id = *last_id;
+        let children_ids = self.children
+            .into_iter()
+            .map(|child| child.build(last_id, definitions))
+            .collect::<Result<Vec<i32>, BehaviorTreeBuildingError>>()?;
+
+        let definition = (self.build)(id, children_ids)?;
+        if *definition.get_id() != id {
+            return Result::Err(BehaviorTreeBuildingError::UnexpectedNodeId(id, *definition.get_id()));
+        }
+
+        definitions.push(definition);
+        Result::Ok(id)
+    }
+
+}
+
+// Any node, built from its id and the ids of its children.
+pub struct Node {
+
+    children: Vec<NodeBuilder>,
+    build: Box<dyn FnOnce(i32, Vec<i32>) -> Arc<dyn BehaviorTreeNodeDefinition>>
+
+}
+
+impl Node {
+
+    pub fn new<F>(build: F) -> Node
+        where F: FnOnce(i32, Vec<i32>) -> Arc<dyn BehaviorTreeNodeDefinition> + 'static {
+        Node {
+            children: Vec::new(),
+            build: Box::new(build)
+        }
+    }
+
+    pub fn child(mut self,
+                 child: impl Into<NodeBuilder>) -> Node {
+        self.children.push(child.into());
+        self
+    }
+
+}
+
+impl From<Node> for NodeBuilder {
+    fn from(node: Node) -> Self {
+        let build = node.build;
+
+        NodeBuilder {
+            children: node.children,
+            build: Box::new(move |id, children_ids| Result::Ok(build(id, children_ids)))
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Sequence {
+
+    children: Vec<NodeBuilder>
+
+}
+
+impl Sequence {
+
+    pub fn new() -> Sequence {
+        Sequence::default()
+    }
+
+    pub fn child(mut self,
+                 child: impl Into<NodeBuilder>) -> Sequence {
+        self.children.push(child.into());
+        self
+    }
+
+}
+
+impl From<Sequence> for NodeBuilder {
+    fn from(sequence: Sequence) -> Self {
+        composite(sequence.children, |id, children_ids| Arc::new(SequenceCompositeNodeDefinition::new(id, children_ids)))
+    }
+}
+
+#[derive(Default)]
+pub struct Fallback {
+
+    children: Vec<NodeBuilder>
+
+}
+
+impl Fallback {
+
+    pub fn new() -> Fallback {
+        Fallback::default()
+    }
+
+    pub fn child(mut self,
+                 child: impl Into<NodeBuilder>) -> Fallback {
+        self.children.push(child.into());
+        self
+    }
+
+}
+
+impl From<Fallback> for NodeBuilder {
+    fn from(fallback: Fallback) -> Self {
+        composite(fallback.children, |id, children_ids| Arc::new(FallbackCompositeNodeDefinition::new(id, children_ids)))
+    }
+}
+
+pub struct Parallel {
+
+    policy: ParallelPolicy,
+    children: Vec<NodeBuilder>
+
+}
+
+impl Parallel {
+
+    pub fn new(num_successes_to_succeed: usize) -> Parallel {
+        Parallel::with_policy(ParallelPolicy::RequireN(num_successes_to_succeed))
+    }
+
+    pub fn with_policy(policy: ParallelPolicy) -> Parallel {
+        Parallel {
+            policy,
+            children: Vec::new()
+        }
+    }
+
+    pub fn child(mut self,
+                 child: impl Into<NodeBuilder>) -> Parallel {
+        self.children.push(child.into());
+        self
+    }
+
+}
+
+impl From<Parallel> for NodeBuilder {
+    fn from(parallel: Parallel) -> Self {
+        let policy = parallel.policy;
+
+        composite(parallel.children, move |id, children_ids|
+            Arc::new(ParallelCompositeNodeDefinition::with_policy(id, children_ids, policy)))
+    }
+}
+
+pub struct Invert(NodeBuilder);
+
+impl Invert {
+
+    pub fn new(child: impl Into<NodeBuilder>) -> Invert {
+        Invert(child.into())
+    }
+
+}
+
+impl From<Invert> for NodeBuilder {
+    fn from(invert: Invert) -> Self {
+        NodeBuilder::from(Node::new(|id, children_ids| Arc::new(InvertDecoratorNodeDefinition::new(id, children_ids[0])))
+            .child(invert.0))
+    }
+}
+
+pub struct Condition {
+
+    expression: ConditionExpression,
+    child: NodeBuilder
+
+}
+
+impl Condition {
+
+    pub fn new(expression: ConditionExpression,
+               child: impl Into<NodeBuilder>) -> Condition {
+        Condition {
+            expression,
+            child: child.into()
+        }
+    }
+
+}
+
+impl From<Condition> for NodeBuilder {
+    fn from(condition: Condition) -> Self {
+        let expression = condition.expression;
+
+        NodeBuilder::from(Node::new(move |id, children_ids|
+            Arc::new(ConditionDecoratorNodeDefinition::new(id, children_ids[0], expression)))
+            .child(condition.child))
+    }
+}
+
+pub struct PrintLog(String);
+
+impl PrintLog {
+
+    pub fn new(message: &str) -> PrintLog {
+        PrintLog(message.to_owned())
+    }
+
+}
+
+impl From<PrintLog> for NodeBuilder {
+    fn from(print_log: PrintLog) -> Self {
+        NodeBuilder::from(Node::new(move |id, _| Arc::new(PrintLogActionNodeDefinition::new(id, print_log.0))))
+    }
+}
+
+// Executes the tree of the given id.
+pub struct Subtree(pub i32);
+
+impl From<Subtree> for NodeBuilder {
+    fn from(subtree: Subtree) -> Self {
+        NodeBuilder::from(Node::new(move |id, _| Arc::new(ExecuteSubTreeActionNodeDefinition::new(id, subtree.0))))
+    }
+}
+
+// Composites without children are most likely children forgotten.
+fn composite<F>(children: Vec<NodeBuilder>,
+                build: F) -> NodeBuilder
+    where F: FnOnce(i32, Vec<i32>) -> Arc<dyn BehaviorTreeNodeDefinition> + 'static {
+    NodeBuilder {
+        children,
+        build: Box::new(move |id, children_ids| if children_ids.is_empty() {
+            Result::Err(BehaviorTreeBuildingError::CompositeNodeWithoutChildren(id))
+        } else {
+            Result::Ok(build(id, children_ids))
+        })
+    }
+}
@@ -0,0 +1,47 @@
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::composite::compensating::{CompensatedStep, CompensatingSequenceCompositeNode};
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct CompensatingSequenceCompositeNodeDefinition {
+
+    id: i32,
+    steps: Vec<(i32, Option<i32>)>
+
+}
+
+impl CompensatingSequenceCompositeNodeDefinition {
+
+    pub fn new(id: i32,
+               steps: Vec<(i32, Option<i32>)>) -> CompensatingSequenceCompositeNodeDefinition {
+        CompensatingSequenceCompositeNodeDefinition {
+            id,
+            steps
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for CompensatingSequenceCompositeNodeDefinition {
+    fn build(&self, context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        let mut steps = Vec::new();
+
+        for (action_id, compensation_id) in &self.steps {
+            let compensation = match compensation_id {
+                None => Option::None,
+                Some(compensation_id) => Option::Some(context.build_child(compensation_id)?)
+            };
+
+            steps.push(CompensatedStep::new(context.build_child(action_id)?, compensation));
+        }
+
+        Ok(
+            CompensatingSequenceCompositeNode::new(self.id, steps)
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -1,3 +1,5 @@
+pub mod compensating;
 pub mod fallback;
 pub mod parallel;
-pub mod sequence;
\ No newline at end of file
+pub mod sequence;
+pub mod utility;
\ No newline at end of file
@@ -1,5 +1,5 @@
 use buttercup_bts::node::BTNode;
-use buttercup_bts::node::composite::parallel::{ParallelCompositeNode, ParallelCompositeNodeBuildingError};
+use buttercup_bts::node::composite::parallel::{ParallelCompositeNode, ParallelCompositeNodeBuildingError, ParallelPolicy};
 
 use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
 
@@ -7,7 +7,7 @@ pub struct ParallelCompositeNodeDefinition {
 
     id: i32,
     children_ids: Vec<i32>,
-    num_successes_to_succeed: usize
+    policy: ParallelPolicy
 
 }
 
@@ -16,10 +16,16 @@ impl ParallelCompositeNodeDefinition {
     pub fn new(id: i32,
                children_ids: Vec<i32>,
                num_successes_to_succeed: usize) -> ParallelCompositeNodeDefinition {
+        ParallelCompositeNodeDefinition::with_policy(id, children_ids, ParallelPolicy::RequireN(num_successes_to_succeed))
+    }
+
+    pub fn with_policy(id: i32,
+                       children_ids: Vec<i32>,
+                       policy: ParallelPolicy) -> ParallelCompositeNodeDefinition {
         ParallelCompositeNodeDefinition {
             id,
             children_ids,
-            num_successes_to_succeed
+            policy
         }
     }
 
@@ -29,10 +35,10 @@ impl BehaviorTreeNodeDefinition for ParallelCompositeNodeDefinition {
     fn build(&self, context: &BehaviorTreeBuildingContext)
         -> Result<BTNode, BehaviorTreeBuildingError> {
         Ok(
-            ParallelCompositeNode::new(
+            ParallelCompositeNode::with_policy(
                 self.id,
                 context.build_children(&self.children_ids)?,
-                self.num_successes_to_succeed)?
+                &self.policy)?
                 .into()
         )
     }
@@ -0,0 +1,38 @@
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::composite::utility::{ScoreExpression, UtilityCompositeNode};
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct UtilityCompositeNodeDefinition {
+
+    id: i32,
+    scored_children: Vec<(ScoreExpression, i32)>
+
+}
+
+impl UtilityCompositeNodeDefinition {
+
+    pub fn new(id: i32,
+               scored_children: Vec<(ScoreExpression, i32)>) -> UtilityCompositeNodeDefinition {
+        UtilityCompositeNodeDefinition {
+            id,
+            scored_children
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for UtilityCompositeNodeDefinition {
+    fn build(&self, context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        let mut children = Vec::with_capacity(self.scored_children.len());
+        for (score, child_id) in &self.scored_children {
+            children.push((score.clone(), context.build_child(child_id)?));
+        }
+
+        Ok(UtilityCompositeNode::new(self.id, children).into())
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
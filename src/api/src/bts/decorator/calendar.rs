@@ -0,0 +1,47 @@
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::calendar::BusinessHoursDecoratorNode;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct BusinessHoursDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    calendar: String,
+    time_value_name: Option<String>
+
+}
+
+impl BusinessHoursDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               calendar: String,
+               time_value_name: Option<String>) -> BusinessHoursDecoratorNodeDefinition {
+        BusinessHoursDecoratorNodeDefinition {
+            id,
+            child_id,
+            calendar,
+            time_value_name
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for BusinessHoursDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            BusinessHoursDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?,
+                self.calendar.clone(),
+                self.time_value_name.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -29,11 +29,14 @@ impl ConditionDecoratorNodeDefinition {
 impl BehaviorTreeNodeDefinition for ConditionDecoratorNodeDefinition {
     fn build(&self,
              ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        ctx.verify_condition(&self.id, &self.expression)?;
+
         Ok(
             ConditionDecoratorNode::new(
                 self.id,
                 ctx.build_child(&self.child_id)?,
-                ConditionExpressionWrapper::new(self.expression.clone())
+                ConditionExpressionWrapper::try_new(self.expression.clone())
+                    .map_err(|err| BehaviorTreeBuildingError::InvalidConditionExpression(self.id, err))?
             ).into()
         )
     }
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::dedup::DeduplicateDecoratorNode;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct DeduplicateDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    value_names: HashSet<String>,
+    subject_value_name: Option<String>,
+    ttl: Duration
+
+}
+
+impl DeduplicateDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               value_names: HashSet<String>,
+               subject_value_name: Option<String>,
+               ttl: Duration) -> DeduplicateDecoratorNodeDefinition {
+        DeduplicateDecoratorNodeDefinition {
+            id,
+            child_id,
+            value_names,
+            subject_value_name,
+            ttl
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for DeduplicateDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            DeduplicateDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?,
+                self.value_names.clone(),
+                self.subject_value_name.clone(),
+                self.ttl)
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::escalation::{EscalationDecoratorNode, EscalationPolicy};
+use buttercup_variables::VariableSpecification;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct EscalationDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    escalation_id: i32,
+    sla: VariableSpecification<Duration>,
+    policy: EscalationPolicy
+
+}
+
+impl EscalationDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               escalation_id: i32,
+               sla: VariableSpecification<Duration>,
+               policy: EscalationPolicy) -> EscalationDecoratorNodeDefinition {
+        EscalationDecoratorNodeDefinition {
+            id,
+            child_id,
+            escalation_id,
+            sla,
+            policy
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for EscalationDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            EscalationDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?,
+                ctx.build_child(&self.escalation_id)?,
+                self.sla.clone(),
+                self.policy.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
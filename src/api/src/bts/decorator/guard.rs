@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::guard::GuardDecoratorNode;
+use buttercup_endpoints::ArgumentDefinition;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct GuardDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    requirements: HashMap<String, ArgumentDefinition>
+
+}
+
+impl GuardDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               requirements: HashMap<String, ArgumentDefinition>) -> GuardDecoratorNodeDefinition {
+        GuardDecoratorNodeDefinition {
+            id,
+            child_id,
+            requirements
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for GuardDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Ok(
+            GuardDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?,
+                self.requirements.clone()
+            ).into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn get_declared_symbols(&self) -> HashMap<String, Vec<String>> {
+        self.requirements
+            .iter()
+            .filter(|(_, definition)| !definition.get_symbols().is_empty())
+            .map(|(name, definition)| (name.clone(), definition.get_symbols().clone()))
+            .collect()
+    }
+}
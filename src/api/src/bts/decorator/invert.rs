@@ -9,6 +9,18 @@ pub struct InvertDecoratorNodeDefinition {
 
 }
 
+impl InvertDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32) -> InvertDecoratorNodeDefinition {
+        InvertDecoratorNodeDefinition {
+            id,
+            child_id
+        }
+    }
+
+}
+
 impl BehaviorTreeNodeDefinition for InvertDecoratorNodeDefinition {
     fn build(&self,
              ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
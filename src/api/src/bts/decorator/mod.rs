@@ -1,3 +1,10 @@
+pub mod calendar;
 pub mod condition;
+pub mod dedup;
+pub mod escalation;
+pub mod guard;
 pub mod invert;
-pub mod reactive;
\ No newline at end of file
+pub mod reactive;
+pub mod repeat;
+pub mod retry;
+pub mod timeout;
\ No newline at end of file
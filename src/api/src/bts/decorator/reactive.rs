@@ -12,14 +12,31 @@ pub struct ReactiveConditionDecoratorNodeDefinition {
 
 }
 
+impl ReactiveConditionDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               expression: ConditionExpression) -> ReactiveConditionDecoratorNodeDefinition {
+        ReactiveConditionDecoratorNodeDefinition {
+            id,
+            child_id,
+            expression
+        }
+    }
+
+}
+
 impl BehaviorTreeNodeDefinition for ReactiveConditionDecoratorNodeDefinition {
     fn build(&self,
              ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        ctx.verify_condition(&self.id, &self.expression)?;
+
         Ok(
             ReactiveConditionDecoratorNode::new(
                 self.id,
                 ctx.build_child(&self.child_id)?,
-                ConditionExpressionWrapper::new(self.expression.clone())
+                ConditionExpressionWrapper::try_new(self.expression.clone())
+                    .map_err(|err| BehaviorTreeBuildingError::InvalidConditionExpression(self.id, err))?
             ).into()
         )
     }
@@ -0,0 +1,43 @@
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::repeat::{RepeatDecoratorNode, RepeatPolicy};
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct RepeatDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    policy: RepeatPolicy
+
+}
+
+impl RepeatDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               policy: RepeatPolicy) -> RepeatDecoratorNodeDefinition {
+        RepeatDecoratorNodeDefinition {
+            id,
+            child_id,
+            policy
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for RepeatDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            RepeatDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?,
+                self.policy.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
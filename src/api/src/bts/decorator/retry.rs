@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::retry::RetryDecoratorNode;
+use buttercup_variables::VariableSpecification;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct RetryDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    max_retries: u32,
+    delay: VariableSpecification<Duration>
+
+}
+
+impl RetryDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               max_retries: u32,
+               delay: VariableSpecification<Duration>) -> RetryDecoratorNodeDefinition {
+        RetryDecoratorNodeDefinition {
+            id,
+            child_id,
+            max_retries,
+            delay
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for RetryDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            RetryDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?,
+                self.max_retries,
+                self.delay.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
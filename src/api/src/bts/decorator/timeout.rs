@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::timeout::TimeoutDecoratorNode;
+use buttercup_variables::VariableSpecification;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+pub struct TimeoutDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    duration: VariableSpecification<Duration>
+
+}
+
+impl TimeoutDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               duration: VariableSpecification<Duration>) -> TimeoutDecoratorNodeDefinition {
+        TimeoutDecoratorNodeDefinition {
+            id,
+            child_id,
+            duration
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for TimeoutDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            TimeoutDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?,
+                self.duration.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -1,36 +1,190 @@
 use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use dashmap::DashMap;
 use dashmap::mapref::one::Ref;
 
+use buttercup_bts::budget::EvaluationBudget;
 use buttercup_bts::node::BTNode;
-use buttercup_bts::tree::{BehaviorTree, BehaviorTreeService};
+use buttercup_bts::semantics::SemanticsVersion;
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_bts::command::{CommandRegistry, ContentCommandAddress};
+use buttercup_bts::tree::{BehaviorTree, BehaviorTreeFixture, BehaviorTreeService};
+use buttercup_conditions::{ConditionExpression, ConditionExpressionError, UnitsVerifier};
+use buttercup_values::ValuesPayload;
 
 use crate::bts::root::RootBTNodeDefinition;
+use crate::events::{DefinitionEvent, DefinitionEventService};
 
 pub mod action;
+pub mod builder;
 pub mod composite;
 pub mod decorator;
 pub mod root;
 
+// While read-only, no version can be put or activated, so that nothing changes during
+// incidents.
 #[derive(Default)]
 pub struct BehaviorTreeDefinitionService {
 
-    definitions: DashMap<i32, BehaviorTreeDefinition>
+    active_versions: DashMap<i32, u32>,
+    definitions: DashMap<i32, BehaviorTreeDefinition>,
+    standby_definitions: DashMap<(i32, u32), BehaviorTreeDefinition>,
+    read_only: AtomicBool
 
 }
 
 impl BehaviorTreeDefinitionService {
 
+    pub fn set_read_only(&self,
+                         read_only: bool) {
+        self.read_only.store(read_only, Ordering::SeqCst);
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::SeqCst)
+    }
+
+    pub fn activate(&self,
+                    id: &i32,
+                    version: &u32) -> Result<Option<u32>, BehaviorTreeBuildingError> {
+        if self.is_read_only() {
+            return Result::Err(BehaviorTreeBuildingError::ReadOnly);
+        }
+
+        if self.get_active_version(id) == Option::Some(*version) {
+            return Result::Ok(Option::Some(*version));
+        }
+
+        // Looked up before the active version is touched, so that activating an unknown
+        // version leaves no trace of the tree behind.
+        let (_, definition) = self.standby_definitions
+            .remove(&(*id, *version))
+            .ok_or(BehaviorTreeBuildingError::CouldNotFindTreeVersion(*id, *version))?;
+        let mut active_version = self.active_versions.entry(*id).or_insert(0);
+
+        let previous_version = match self.definitions.insert(*id, definition) {
+            None => Option::None,
+            Some(previous) => {
+                self.standby_definitions.insert((*id, *active_version), previous);
+                Option::Some(*active_version)
+            }
+        };
+
+        *active_version = *version;
+
+        Result::Ok(previous_version)
+    }
+
+    // Puts the active version of a tree back on standby, e.g. when the first version
+    // activated fails to build and there is no previous one to return to.
+    pub fn deactivate(&self,
+                      id: &i32) {
+        if let Some((_, definition)) = self.definitions.remove(id) {
+            if let Some((_, version)) = self.active_versions.remove(id) {
+                self.standby_definitions.insert((*id, version), definition);
+            }
+        }
+    }
+
     pub fn get(&self,
                id: &i32) -> Option<Ref<i32, BehaviorTreeDefinition>> {
         self.definitions.get(id)
     }
 
+    // Whether any version of the tree, active or standby, has been inserted.
+    pub fn contains(&self,
+                    id: &i32) -> bool {
+        self.active_versions.contains_key(id)
+            || self.standby_definitions.iter().any(|entry| entry.key().0 == *id)
+    }
+
+    pub fn get_active_version(&self,
+                              id: &i32) -> Option<u32> {
+        if !self.definitions.contains_key(id) {
+            return Option::None;
+        }
+
+        self.active_versions
+            .get(id)
+            .map(|version| *version)
+    }
+
+    // The active version and every standby version of the tree, in order.
+    pub fn get_versions(&self,
+                        id: &i32) -> Vec<u32> {
+        let mut versions: Vec<u32> = self.standby_definitions
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|(tree_id, _)| tree_id == id)
+            .map(|(_, version)| version)
+            .chain(self.get_active_version(id))
+            .collect();
+
+        versions.sort_unstable();
+        versions
+    }
+
+    // The latest version older than the active one.
+    pub fn get_previous_version(&self,
+                                id: &i32) -> Option<u32> {
+        let active_version = self.get_active_version(id)?;
+
+        self.standby_definitions
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|(tree_id, version)| tree_id == id && *version < active_version)
+            .map(|(_, version)| version)
+            .max()
+    }
+
+    pub fn get_standby(&self,
+                       id: &i32,
+                       version: &u32) -> Option<Ref<'_, (i32, u32), BehaviorTreeDefinition>> {
+        self.standby_definitions.get(&(*id, *version))
+    }
+
     pub fn insert(&self, definition: BehaviorTreeDefinition) {
-        self.definitions.insert(definition.id, definition);
+        let id = definition.id;
+        let mut active_version = self.active_versions.entry(id).or_insert(0);
+
+        if let Some(previous) = self.definitions.insert(id, definition) {
+            self.standby_definitions.insert((id, *active_version), previous);
+        }
+
+        *active_version = self.get_next_version(&id, *active_version);
+    }
+
+    pub fn insert_standby(&self,
+                          definition: BehaviorTreeDefinition,
+                          version: u32) -> Result<(), BehaviorTreeBuildingError> {
+        let id = definition.id;
+
+        if self.is_read_only() {
+            return Result::Err(BehaviorTreeBuildingError::ReadOnly);
+        }
+
+        if self.active_versions.get(&id).map(|active| *active) == Option::Some(version)
+            && self.definitions.contains_key(&id) {
+            return Result::Err(BehaviorTreeBuildingError::TreeVersionIsActive(id, version));
+        }
+
+        self.standby_definitions.insert((id, version), definition);
+
+        Result::Ok(())
+    }
+
+    fn get_next_version(&self,
+                        id: &i32,
+                        active_version: u32) -> u32 {
+        self.standby_definitions
+            .iter()
+            .filter(|entry| entry.key().0 == *id)
+            .map(|entry| entry.key().1)
+            .fold(active_version, u32::max) + 1
     }
 
 }
@@ -39,6 +193,9 @@ pub struct BehaviorTreeDefinition {
 
     id: i32,
     definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
+    fixtures: Vec<BehaviorTreeFixture>,
+    fallback_tree_id: Option<i32>,
+    semantics_version: SemanticsVersion,
     root_node: Box<dyn RootBTNodeDefinition>
 
 }
@@ -47,7 +204,9 @@ impl BehaviorTreeDefinition {
 
     pub fn build(&self,
                  context: &BehaviorTreeBuildingContext) -> Result<BehaviorTree, BehaviorTreeBuildingError> {
-        Result::Ok(BehaviorTree::new(self.id, self.root_node.build(&context)?))
+        Result::Ok(BehaviorTree::new(self.id, self.root_node.build(context)?)
+            .with_fallback_tree_id(self.fallback_tree_id)
+            .with_semantics_version(self.semantics_version))
     }
 
     pub fn get_id(&self) -> &i32 {
@@ -58,6 +217,18 @@ impl BehaviorTreeDefinition {
         &self.definitions
     }
 
+    pub fn get_fixtures(&self) -> &Vec<BehaviorTreeFixture> {
+        &self.fixtures
+    }
+
+    pub fn get_fallback_tree_id(&self) -> &Option<i32> {
+        &self.fallback_tree_id
+    }
+
+    pub fn get_semantics_version(&self) -> &SemanticsVersion {
+        &self.semantics_version
+    }
+
     pub fn get_subtree_ids(&self,
                            service: &BehaviorTreeDefinitionService)
         -> Result<HashSet<i32>, BehaviorTreeBuildingError> {
@@ -80,13 +251,34 @@ impl BehaviorTreeDefinition {
         BehaviorTreeDefinition {
             id,
             definitions,
+            fixtures: Vec::new(),
+            fallback_tree_id: Option::None,
+            semantics_version: SemanticsVersion::default(),
             root_node
         }
     }
+
+    pub fn with_fixtures(mut self,
+                         fixtures: Vec<BehaviorTreeFixture>) -> BehaviorTreeDefinition {
+        self.fixtures = fixtures;
+        self
+    }
+
+    pub fn with_fallback_tree_id(mut self,
+                                 fallback_tree_id: Option<i32>) -> BehaviorTreeDefinition {
+        self.fallback_tree_id = fallback_tree_id;
+        self
+    }
+
+    pub fn with_semantics_version(mut self,
+                                  semantics_version: SemanticsVersion) -> BehaviorTreeDefinition {
+        self.semantics_version = semantics_version;
+        self
+    }
 }
 
 
-pub trait BehaviorTreeNodeDefinition {
+pub trait BehaviorTreeNodeDefinition: Send + Sync {
 
     fn build(&self,
              ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError>;
@@ -99,13 +291,52 @@ pub trait BehaviorTreeNodeDefinition {
         Result::Ok(HashSet::new())
     }
 
+    // Symbols allowed for the arguments the node declares, by argument name.
+    fn get_declared_symbols(&self) -> HashMap<String, Vec<String>> {
+        HashMap::new()
+    }
+
+}
+
+// Versions whose ticks end with an error more than max_error_percent of the time, over
+// at least min_ticks ticks since the last check, are rolled back.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct ErrorRateRollback {
+
+    max_error_percent: u64,
+    min_ticks: u64
+
+}
+
+impl ErrorRateRollback {
+
+    pub fn new(max_error_percent: u64,
+               min_ticks: u64) -> ErrorRateRollback {
+        ErrorRateRollback {
+            max_error_percent,
+            min_ticks
+        }
+    }
+
+    fn is_exceeded(&self,
+                   ticks: u64,
+                   errors: u64) -> bool {
+        ticks > 0 && ticks >= self.min_ticks && errors * 100 > ticks * self.max_error_percent
+    }
+
 }
 
 #[derive(Default)]
 pub struct BehaviorTreeBuildingService {
 
     behavior_tree_service: Arc<BehaviorTreeService>,
-    definition_service: Arc<BehaviorTreeDefinitionService>
+    definition_service: Arc<BehaviorTreeDefinitionService>,
+    slow_tick_threshold: Option<Duration>,
+    evaluation_budget: EvaluationBudget,
+    static_values: Option<Arc<ValuesPayload>>,
+    events: Arc<DefinitionEventService>,
+    command_registry: Arc<CommandRegistry>,
+    error_rate_rollback: Option<ErrorRateRollback>
 
 }
 
@@ -115,21 +346,270 @@ impl BehaviorTreeBuildingService {
                definition_service: Arc<BehaviorTreeDefinitionService>) -> BehaviorTreeBuildingService {
         BehaviorTreeBuildingService {
             behavior_tree_service,
-            definition_service
+            definition_service,
+            slow_tick_threshold: Option::None,
+            evaluation_budget: EvaluationBudget::default(),
+            static_values: Option::None,
+            events: Arc::new(DefinitionEventService::default()),
+            command_registry: Arc::new(CommandRegistry::default()),
+            error_rate_rollback: Option::None
+        }
+    }
+
+    pub fn with_slow_tick_threshold(mut self,
+                                    slow_tick_threshold: Duration) -> BehaviorTreeBuildingService {
+        self.slow_tick_threshold = Option::Some(slow_tick_threshold);
+        self
+    }
+
+    pub fn with_evaluation_budget(mut self,
+                                  evaluation_budget: EvaluationBudget) -> BehaviorTreeBuildingService {
+        self.evaluation_budget = evaluation_budget;
+        self
+    }
+
+    pub fn with_static_values(mut self,
+                              static_values: Arc<ValuesPayload>) -> BehaviorTreeBuildingService {
+        self.static_values = Option::Some(static_values);
+        self
+    }
+
+    pub fn with_events(mut self,
+                       events: Arc<DefinitionEventService>) -> BehaviorTreeBuildingService {
+        self.events = events;
+        self
+    }
+
+    // Commands selected by name are resolved to their addresses when trees are built.
+    pub fn with_command_registry(mut self,
+                                 command_registry: Arc<CommandRegistry>) -> BehaviorTreeBuildingService {
+        self.command_registry = command_registry;
+        self
+    }
+
+    pub fn with_error_rate_rollback(mut self,
+                                    error_rate_rollback: ErrorRateRollback) -> BehaviorTreeBuildingService {
+        self.error_rate_rollback = Option::Some(error_rate_rollback);
+        self
+    }
+
+    pub async fn activate(&self,
+                          id: &i32,
+                          version: &u32) -> Result<Arc<BehaviorTree>, BehaviorTreeBuildingError> {
+        let previous_version = self.definition_service.activate(id, version)?;
+
+        match self.build_self_tested(id).await {
+            Ok(tree) => {
+                let tree = Arc::new(tree);
+                self.behavior_tree_service.insert_arc(tree.clone());
+
+                // Activating a version older than the active one is a rollback.
+                self.events.publish(match previous_version {
+                    Some(previous_version) if previous_version > *version =>
+                        DefinitionEvent::RolledBack { tree_id: *id, version: *version },
+                    _ => DefinitionEvent::Activated { tree_id: *id, version: *version }
+                });
+
+                Result::Ok(tree)
+            }
+            Err(err) => {
+                if let BehaviorTreeBuildingError::SelfTestFailed(_, mismatches) = &err {
+                    self.events.publish(DefinitionEvent::SelfTestFailed {
+                        tree_id: *id,
+                        version: *version,
+                        failed_fixtures: mismatches.len()
+                    });
+                }
+
+                match previous_version {
+                    None => self.definition_service.deactivate(id),
+                    Some(previous_version) => {
+                        self.definition_service.activate(id, &previous_version)?;
+                    }
+                }
+
+                Result::Err(err)
+            }
+        }
+    }
+
+    pub async fn rollback(&self,
+                          id: &i32) -> Result<u32, BehaviorTreeBuildingError> {
+        let version = self.definition_service.get_previous_version(id)
+            .ok_or(BehaviorTreeBuildingError::NoPreviousVersion(*id))?;
+
+        self.activate(id, &version).await?;
+
+        Result::Ok(version)
+    }
+
+    // Takes the tick outcomes of every tree and rolls back those whose error rate went over
+    // the threshold since the last check, returning the versions they were rolled back to.
+    // A tree without a previous version keeps serving and is returned with
+    // NoPreviousVersion.
+    pub async fn rollback_error_spikes(&self) -> Vec<(i32, Result<u32, BehaviorTreeBuildingError>)> {
+        let error_rate_rollback = match self.error_rate_rollback {
+            None => return Vec::new(),
+            Some(error_rate_rollback) => error_rate_rollback
+        };
+
+        let mut rollbacks = Vec::new();
+
+        for tree in self.behavior_tree_service.get_all() {
+            let (ticks, errors) = tree.get_tick_outcomes().take();
+
+            if error_rate_rollback.is_exceeded(ticks, errors) && self.definition_service.get(tree.get_id()).is_some() {
+                rollbacks.push((*tree.get_id(), self.rollback(tree.get_id()).await));
+            }
         }
+
+        rollbacks.sort_by_key(|(id, _)| *id);
+        rollbacks
+    }
+
+    pub async fn self_test(&self,
+                           id: &i32) -> Result<Vec<FixtureMismatch>, BehaviorTreeBuildingError> {
+        let tree = self.build(id)?;
+        let mismatches = self.run_fixtures(id, &tree).await;
+
+        if !mismatches.is_empty() {
+            if let Some(version) = self.definition_service.get_active_version(id) {
+                self.events.publish(
+                    DefinitionEvent::SelfTestFailed { tree_id: *id, version, failed_fixtures: mismatches.len() });
+            }
+        }
+
+        Result::Ok(mismatches)
+    }
+
+    // Runs the fixtures of every tree being served, such as at startup, when trees built as
+    // subtrees of others have not been self-tested yet. Trees with mismatches are no
+    // longer served, neither are the trees which embed one of them as a subtree, as they
+    // would keep running the failing copy. Both are returned with the reason.
+    pub async fn self_test_served(&self) -> Vec<(i32, BehaviorTreeBuildingError)> {
+        let mut failed = Vec::new();
+
+        for tree in self.behavior_tree_service.get_all() {
+            let mismatches = self.run_fixtures(tree.get_id(), &tree).await;
+
+            if !mismatches.is_empty() {
+                self.behavior_tree_service.remove(tree.get_id());
+
+                if let Some(version) = self.definition_service.get_active_version(tree.get_id()) {
+                    self.events.publish(DefinitionEvent::SelfTestFailed {
+                        tree_id: *tree.get_id(),
+                        version,
+                        failed_fixtures: mismatches.len()
+                    });
+                }
+
+                failed.push((*tree.get_id(), BehaviorTreeBuildingError::SelfTestFailed(*tree.get_id(), mismatches)));
+            }
+        }
+
+        let failed_ids: HashSet<i32> = failed.iter().map(|(id, _)| *id).collect();
+
+        for tree in self.behavior_tree_service.get_all() {
+            let subtree_ids = match self.definition_service.get(tree.get_id()) {
+                None => continue,
+                Some(definition) => definition.get_subtree_ids(&self.definition_service)
+            };
+
+            if let Some(subtree_id) = subtree_ids.iter().flatten().filter(|id| failed_ids.contains(id)).min() {
+                self.behavior_tree_service.remove(tree.get_id());
+                failed.push((*tree.get_id(), BehaviorTreeBuildingError::SubtreeSelfTestFailed(*tree.get_id(), *subtree_id)));
+            }
+        }
+
+        failed.sort_by_key(|(id, _)| *id);
+        failed
+    }
+
+    async fn build_self_tested(&self,
+                               id: &i32) -> Result<BehaviorTree, BehaviorTreeBuildingError> {
+        let tree = self.build(id)?;
+        let mismatches = self.run_fixtures(id, &tree).await;
+
+        if !mismatches.is_empty() {
+            return Result::Err(BehaviorTreeBuildingError::SelfTestFailed(*id, mismatches));
+        }
+
+        tree.get_hit_counters().reset();
+        tree.get_tick_outcomes().take();
+
+        Result::Ok(tree)
+    }
+
+    async fn run_fixtures(&self,
+                          id: &i32,
+                          tree: &BehaviorTree) -> Vec<FixtureMismatch> {
+        let fixtures = match self.definition_service.get(id) {
+            None => Vec::new(),
+            Some(definition) => definition.get_fixtures().clone()
+        };
+
+        BehaviorTreeBuildingService::check_fixtures(tree, &fixtures).await
+    }
+
+    pub async fn check_fixtures(tree: &BehaviorTree,
+                                fixtures: &[BehaviorTreeFixture]) -> Vec<FixtureMismatch> {
+        let mut mismatches = Vec::new();
+
+        for (index, fixture) in fixtures.iter().enumerate() {
+            let result = tree.evaluate_fixture(fixture).await;
+
+            if result.as_ref() != Result::Ok(fixture.get_expected_status()) {
+                mismatches.push(
+                    FixtureMismatch::new(index, fixture.get_expected_status().clone(), result));
+            }
+        }
+
+        mismatches
     }
 
     pub fn build(&self,
                  id: &i32) -> Result<BehaviorTree, BehaviorTreeBuildingError> {
         match self.definition_service.get(&id) {
             None => Result::Err(BehaviorTreeBuildingError::CouldNotFindSubtreeWithId(*id)),
-            Some(definition) => {
-                let context =
-                    self.get_context(definition.value())?;
+            Some(definition) => self.build_definition(definition.value())
+        }
+    }
 
-                definition.build(&context)
+    // Builds any version of the tree without activating it.
+    pub fn build_version(&self,
+                         id: &i32,
+                         version: &u32) -> Result<BehaviorTree, BehaviorTreeBuildingError> {
+        if self.definition_service.get_active_version(id) == Option::Some(*version) {
+            return self.build(id);
+        }
+
+        match self.definition_service.get_standby(id, version) {
+            None => Result::Err(BehaviorTreeBuildingError::CouldNotFindTreeVersion(*id, *version)),
+            Some(definition) => self.build_definition(definition.value())
+        }
+    }
+
+    pub fn build_definition(&self,
+                            definition: &BehaviorTreeDefinition) -> Result<BehaviorTree, BehaviorTreeBuildingError> {
+        // A fallback tree which is missing would only be noticed once the tree fails.
+        if let Some(fallback_tree_id) = definition.get_fallback_tree_id() {
+            if self.definition_service.get(fallback_tree_id).is_none() {
+                return Result::Err(BehaviorTreeBuildingError::CouldNotFindFallbackTreeWithId(*fallback_tree_id));
             }
         }
+
+        let context = self.get_context(definition)?;
+        let tree = definition.build(&context)?
+            .with_evaluation_budget(self.evaluation_budget.clone());
+        let tree = match &self.static_values {
+            None => tree,
+            Some(static_values) => tree.with_static_values(static_values.clone())
+        };
+
+        Result::Ok(match self.slow_tick_threshold {
+            None => tree,
+            Some(slow_tick_threshold) => tree.with_slow_tick_threshold(slow_tick_threshold)
+        })
     }
 
     fn get_context(&self,
@@ -153,13 +633,26 @@ impl BehaviorTreeBuildingService {
             subtrees.insert(*subtree.get_id(), subtree);
         }
 
-        Result::Ok(
-            BehaviorTreeBuildingContext::new(
-                tree_definition.get_definitions()
-                    .into_iter()
-                    .map(|def| (*def.get_id(), def.clone()))
-                    .collect(),
-                subtrees))
+        // Definitions of the same id would silently replace each other.
+        let mut node_definitions = HashMap::new();
+        let mut declared_symbols = HashMap::new();
+
+        for definition in tree_definition.get_definitions() {
+            if node_definitions.insert(*definition.get_id(), definition.clone()).is_some() {
+                return Result::Err(BehaviorTreeBuildingError::DuplicateNodeId(*definition.get_id()));
+            }
+
+            declared_symbols.extend(definition.get_declared_symbols());
+        }
+
+        let context = BehaviorTreeBuildingContext::new(node_definitions, subtrees)
+            .with_declared_symbols(declared_symbols)
+            .with_command_registry(self.command_registry.clone());
+
+        Result::Ok(match &self.static_values {
+            None => context,
+            Some(static_values) => context.with_known_values(static_values.clone())
+        })
     }
 
 }
@@ -167,19 +660,69 @@ impl BehaviorTreeBuildingService {
 #[derive(Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
 pub enum BehaviorTreeBuildingError {
 
+    CompositeNodeWithoutChildren(i32),
     CouldNotFindChildDefinitionWithId(i32),
+    CouldNotFindFallbackTreeWithId(i32),
     CouldNotFindTreeWithId(i32),
     CouldNotFindSubtreeWithId(i32),
+    CouldNotFindTreeVersion(i32, u32),
+    DuplicateNodeId(i32),
     GotUnexpectedNodeType(i32),
+    InvalidConditionExpression(i32, ConditionExpressionError),
+    NoPreviousVersion(i32),
     ParallelCompositeNodeBuildingError,
     ProvidedTreeCannotBeASubtreeError,
+    ReadOnly,
+    SelfTestFailed(i32, Vec<FixtureMismatch>),
+    SubtreeSelfTestFailed(i32, i32),
+    TreeVersionIsActive(i32, u32),
+    UnexpectedNodeId(i32, i32),
+    UnknownCommandName(i32, String)
+
+}
+
+#[derive(Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub struct FixtureMismatch {
+
+    fixture_index: usize,
+    expected_status: TickStatus,
+    actual_result: Result<TickStatus, TickError>
+
+}
+
+impl FixtureMismatch {
+
+    pub fn new(fixture_index: usize,
+               expected_status: TickStatus,
+               actual_result: Result<TickStatus, TickError>) -> FixtureMismatch {
+        FixtureMismatch {
+            fixture_index,
+            expected_status,
+            actual_result
+        }
+    }
+
+    pub fn get_fixture_index(&self) -> &usize {
+        &self.fixture_index
+    }
+
+    pub fn get_expected_status(&self) -> &TickStatus {
+        &self.expected_status
+    }
+
+    pub fn get_actual_result(&self) -> &Result<TickStatus, TickError> {
+        &self.actual_result
+    }
 
 }
 
 pub struct BehaviorTreeBuildingContext {
 
     node_definitions: HashMap<i32, Arc<dyn BehaviorTreeNodeDefinition>>,
-    subtrees: HashMap<i32, Arc<BehaviorTree>>
+    subtrees: HashMap<i32, Arc<BehaviorTree>>,
+    known_values: Arc<ValuesPayload>,
+    declared_symbols: HashMap<String, Vec<String>>,
+    command_registry: Arc<CommandRegistry>
 
 }
 
@@ -189,10 +732,31 @@ impl BehaviorTreeBuildingContext {
                subtrees: HashMap<i32, Arc<BehaviorTree>>) -> BehaviorTreeBuildingContext {
         BehaviorTreeBuildingContext {
             node_definitions,
-            subtrees
+            subtrees,
+            known_values: Arc::new(ValuesPayload::empty()),
+            declared_symbols: HashMap::new(),
+            command_registry: Arc::new(CommandRegistry::default())
         }
     }
 
+    pub fn with_declared_symbols(mut self,
+                                 declared_symbols: HashMap<String, Vec<String>>) -> BehaviorTreeBuildingContext {
+        self.declared_symbols = declared_symbols;
+        self
+    }
+
+    pub fn with_known_values(mut self,
+                             known_values: Arc<ValuesPayload>) -> BehaviorTreeBuildingContext {
+        self.known_values = known_values;
+        self
+    }
+
+    pub fn with_command_registry(mut self,
+                                 command_registry: Arc<CommandRegistry>) -> BehaviorTreeBuildingContext {
+        self.command_registry = command_registry;
+        self
+    }
+
     pub fn build_child(&self, id: &i32) -> Result<BTNode, BehaviorTreeBuildingError> {
         match self.node_definitions.get(id) {
             None =>
@@ -212,6 +776,26 @@ impl BehaviorTreeBuildingContext {
         Result::Ok(ret)
     }
 
+    // Values known before any evaluation, such as the static ones, are compared with the
+    // literals of the condition, so that mismatched units are rejected when the tree is built.
+    // So are the symbols declared by the arguments of the tree.
+    pub fn verify_condition(&self,
+                            node_id: &i32,
+                            condition: &ConditionExpression) -> Result<(), BehaviorTreeBuildingError> {
+        condition.verify_symbols(&self.declared_symbols)
+            .and_then(|_| condition.verify_units(&self.known_values))
+            .map_err(|err| BehaviorTreeBuildingError::InvalidConditionExpression(*node_id, err))
+    }
+
+    pub fn get_command_address(&self,
+                               node_id: &i32,
+                               name: &str) -> Result<ContentCommandAddress, BehaviorTreeBuildingError> {
+        self.command_registry
+            .get_address(name)
+            .copied()
+            .ok_or_else(|| BehaviorTreeBuildingError::UnknownCommandName(*node_id, name.to_owned()))
+    }
+
     pub fn get_subtree(&self,
                        id: &i32) -> Result<Arc<BehaviorTree>, BehaviorTreeBuildingError> {
         match self.subtrees.get(id) {
@@ -8,7 +8,7 @@ use buttercup_bts::node::root::reactive::ReactiveRootBTNode;
 use buttercup_bts::node::root::to_first::ToFirstErrorRootBTNode;
 use buttercup_bts::node::root::until_stopped::UntilStoppedRootBTNode;
 
-pub trait RootBTNodeDefinition {
+pub trait RootBTNodeDefinition: Send + Sync {
 
     fn build(&self,
              context: &BehaviorTreeBuildingContext) -> Result<RootBTNode, BehaviorTreeBuildingError>;
@@ -53,6 +53,16 @@ pub struct ReactiveRootBTNodeDefinition {
 
 impl ReactiveRootBTNodeDefinition {
 
+    pub fn new(id: i32,
+               child_id: i32,
+               stop_on_error: bool) -> ReactiveRootBTNodeDefinition {
+        ReactiveRootBTNodeDefinition {
+            id,
+            child_id,
+            stop_on_error
+        }
+    }
+
     fn get_reactive_node(bt_node: BTNode)
                          -> Result<ReactiveConditionDecoratorNode, BehaviorTreeBuildingError> {
         let node_id = bt_node.get_id();
@@ -88,6 +98,18 @@ pub struct ToFirstErrorRootBTNodeDefinition {
 
 }
 
+impl ToFirstErrorRootBTNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32) -> ToFirstErrorRootBTNodeDefinition {
+        ToFirstErrorRootBTNodeDefinition {
+            id,
+            child_id
+        }
+    }
+
+}
+
 impl RootBTNodeDefinition for ToFirstErrorRootBTNodeDefinition {
     fn build(&self,
              context: &BehaviorTreeBuildingContext) -> Result<RootBTNode, BehaviorTreeBuildingError> {
@@ -103,6 +125,18 @@ pub struct UntilStoppedRootBTNodeDefinition {
 
 }
 
+impl UntilStoppedRootBTNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32) -> UntilStoppedRootBTNodeDefinition {
+        UntilStoppedRootBTNodeDefinition {
+            id,
+            child_id
+        }
+    }
+
+}
+
 impl RootBTNodeDefinition for UntilStoppedRootBTNodeDefinition {
     fn build(&self,
              context: &BehaviorTreeBuildingContext) -> Result<RootBTNode, BehaviorTreeBuildingError> {
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub enum ComplexityLimit {
+
+    ConditionsPerEdge,
+    Edges,
+    ExpressionDepth,
+    Nodes
+
+}
+
+// Caps on the size of submitted definitions, so that a single giant tree cannot slow
+// down the whole server. No cap means no limit.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct DefinitionLimits {
+
+    max_nodes: Option<usize>,
+    max_edges: Option<usize>,
+    max_expression_depth: Option<usize>,
+    max_conditions_per_edge: Option<usize>
+
+}
+
+impl DefinitionLimits {
+
+    pub fn with_max_nodes(mut self,
+                          max_nodes: usize) -> DefinitionLimits {
+        self.max_nodes = Option::Some(max_nodes);
+        self
+    }
+
+    pub fn with_max_edges(mut self,
+                          max_edges: usize) -> DefinitionLimits {
+        self.max_edges = Option::Some(max_edges);
+        self
+    }
+
+    pub fn with_max_expression_depth(mut self,
+                                     max_expression_depth: usize) -> DefinitionLimits {
+        self.max_expression_depth = Option::Some(max_expression_depth);
+        self
+    }
+
+    pub fn with_max_conditions_per_edge(mut self,
+                                        max_conditions_per_edge: usize) -> DefinitionLimits {
+        self.max_conditions_per_edge = Option::Some(max_conditions_per_edge);
+        self
+    }
+
+    pub fn get_limit(&self,
+                     limit: ComplexityLimit) -> &Option<usize> {
+        match limit {
+            ComplexityLimit::ConditionsPerEdge => &self.max_conditions_per_edge,
+            ComplexityLimit::Edges => &self.max_edges,
+            ComplexityLimit::ExpressionDepth => &self.max_expression_depth,
+            ComplexityLimit::Nodes => &self.max_nodes
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ComplexityViolation {
+
+    limit: ComplexityLimit,
+    actual: usize,
+    allowed: usize
+
+}
+
+impl ComplexityViolation {
+
+    pub fn new(limit: ComplexityLimit,
+               actual: usize,
+               allowed: usize) -> ComplexityViolation {
+        ComplexityViolation {
+            limit,
+            actual,
+            allowed
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ComplexityReport {
+
+    nodes: usize,
+    edges: usize,
+    expression_depth: usize,
+    conditions_per_edge: usize,
+    violations: Vec<ComplexityViolation>
+
+}
+
+impl ComplexityReport {
+
+    pub fn new(nodes: usize,
+               edges: usize,
+               expression_depth: usize,
+               conditions_per_edge: usize) -> ComplexityReport {
+        ComplexityReport {
+            nodes,
+            edges,
+            expression_depth,
+            conditions_per_edge,
+            violations: Vec::new()
+        }
+    }
+
+    pub fn check(mut self,
+                 limits: &DefinitionLimits) -> ComplexityReport {
+        self.violations = [
+            (ComplexityLimit::Nodes, self.nodes),
+            (ComplexityLimit::Edges, self.edges),
+            (ComplexityLimit::ExpressionDepth, self.expression_depth),
+            (ComplexityLimit::ConditionsPerEdge, self.conditions_per_edge)
+        ]
+            .iter()
+            .filter_map(|(limit, actual)| match limits.get_limit(*limit) {
+                Some(allowed) if actual > allowed => Option::Some(ComplexityViolation::new(*limit, *actual, *allowed)),
+                _ => Option::None
+            })
+            .collect();
+        self
+    }
+
+    pub fn get_nodes(&self) -> &usize {
+        &self.nodes
+    }
+
+    pub fn get_edges(&self) -> &usize {
+        &self.edges
+    }
+
+    pub fn get_expression_depth(&self) -> &usize {
+        &self.expression_depth
+    }
+
+    pub fn get_conditions_per_edge(&self) -> &usize {
+        &self.conditions_per_edge
+    }
+
+    pub fn get_violations(&self) -> &Vec<ComplexityViolation> {
+        &self.violations
+    }
+
+}
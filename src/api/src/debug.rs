@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use buttercup_bts::debug::{DebugSession, DebugState, EvaluationPath};
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_bts::tree::BehaviorTree;
+use buttercup_values::{ValuesPayload, ValueType};
+
+const MAX_SESSIONS: usize = 256;
+const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum DebugSessionError {
+
+    TooManySessions(usize)
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct StartedDebugSession {
+
+    session_id: Uuid,
+    result: Result<TickStatus, TickError>,
+    steps: usize
+
+}
+
+impl StartedDebugSession {
+
+    pub fn get_session_id(&self) -> &Uuid {
+        &self.session_id
+    }
+
+    pub fn get_result(&self) -> &Result<TickStatus, TickError> {
+        &self.result
+    }
+
+    pub fn get_steps(&self) -> &usize {
+        &self.steps
+    }
+
+}
+
+// The payload is evaluated right away with everything it does recorded, stepping
+// through a session replays the recording, so the tree is never paused half way.
+// Sessions are kept until they are closed, or until they have not been stepped through
+// for the session ttl.
+pub struct DebugSessionService {
+
+    sessions: DashMap<Uuid, (DebugSession, Instant)>,
+    session_ttl: Duration
+
+}
+
+impl Default for DebugSessionService {
+    fn default() -> Self {
+        DebugSessionService {
+            sessions: DashMap::new(),
+            session_ttl: DEFAULT_SESSION_TTL
+        }
+    }
+}
+
+impl DebugSessionService {
+
+    pub fn with_session_ttl(mut self,
+                            session_ttl: Duration) -> DebugSessionService {
+        self.session_ttl = session_ttl;
+        self
+    }
+
+    pub async fn start(&self,
+                       tree: &BehaviorTree,
+                       payload: &ValuesPayload) -> Result<StartedDebugSession, DebugSessionError> {
+        let now = Instant::now();
+        self.sessions.retain(|_, (_, touched_at)| now.duration_since(*touched_at) < self.session_ttl);
+
+        if self.sessions.len() >= MAX_SESSIONS {
+            return Result::Err(DebugSessionError::TooManySessions(MAX_SESSIONS));
+        }
+
+        let (result, events) = tree.evaluate_debugged(payload).await;
+        let session_id = Uuid::new_v4();
+        let steps = events.len();
+
+        self.sessions.insert(session_id, (DebugSession::new(events), Instant::now()));
+
+        Result::Ok(StartedDebugSession {
+            session_id,
+            result,
+            steps
+        })
+    }
+
+    pub fn step(&self,
+                session_id: &Uuid) -> Option<DebugState> {
+        self.sessions
+            .get_mut(session_id)
+            .map(|mut session| {
+                let (session, touched_at) = session.value_mut();
+                *touched_at = Instant::now();
+                session.step()
+            })
+    }
+
+    pub fn close(&self,
+                 session_id: &Uuid) -> bool {
+        self.sessions.remove(session_id).is_some()
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct Explanation {
+
+    result: Result<TickStatus, TickError>,
+    path: EvaluationPath,
+    value_types: BTreeMap<String, ValueType>
+
+}
+
+impl Explanation {
+
+    pub fn get_result(&self) -> &Result<TickStatus, TickError> {
+        &self.result
+    }
+
+    pub fn get_path(&self) -> &EvaluationPath {
+        &self.path
+    }
+
+    pub fn get_value_types(&self) -> &BTreeMap<String, ValueType> {
+        &self.value_types
+    }
+
+}
+
+// Evaluates the payload in a throwaway context, as fixtures are, and tells which way the
+// evaluation went and which types the values of the payload were read as.
+pub async fn explain(tree: &BehaviorTree,
+                     payload: &ValuesPayload) -> Explanation {
+    let (result, events) = tree.evaluate_debugged(payload).await;
+
+    Explanation {
+        result,
+        path: EvaluationPath::new(&events),
+        value_types: payload.iter()
+            .map(|(name, value)| (name.clone(), value.get_value_type()))
+            .collect()
+    }
+}
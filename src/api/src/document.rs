@@ -0,0 +1,1023 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::{Duration, SystemTime};
+
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::node::action::analytics::MetricOperation;
+use buttercup_bts::node::decorator::escalation::EscalationPolicy;
+use buttercup_bts::node::decorator::repeat::RepeatPolicy;
+use buttercup_bts::node::composite::utility::ScoreExpression;
+use buttercup_bts::semantics::SemanticsVersion;
+use buttercup_bts::tree::BehaviorTreeFixture;
+use buttercup_conditions::ConditionExpression;
+use buttercup_conditions::mutation::ConditionMutant;
+use buttercup_endpoints::ArgumentDefinition;
+use buttercup_transformations::Transformer;
+use buttercup_values::{ValueHolder, ValuesPayload};
+use buttercup_variables::VariableSpecification;
+
+use crate::bts::{BehaviorTreeBuildingError, BehaviorTreeDefinition, BehaviorTreeDefinitionService, BehaviorTreeNodeDefinition};
+use crate::bts::action::analytics::{EmitEventActionNodeDefinition, EmitMetricActionNodeDefinition};
+use crate::bts::action::command::{CommandReference, SelectCommandActionNodeDefinition};
+use crate::bts::action::logging::PrintLogActionNodeDefinition;
+use crate::bts::action::messages::{PublishMessageActionNodeDefinition, ReceiveMessageActionNodeDefinition};
+use crate::bts::action::quota::QuotaActionNodeDefinition;
+use crate::bts::action::subtree::ExecuteSubTreeActionNodeDefinition;
+use crate::bts::action::task::HumanTaskActionNodeDefinition;
+use crate::bts::action::values::{SetValueActionNodeDefinition, TransformValueActionNodeDefinition};
+use crate::bts::action::wait::{WaitDurationActionNodeDefinition, WaitForSignalActionNodeDefinition};
+use crate::bts::composite::compensating::CompensatingSequenceCompositeNodeDefinition;
+use crate::bts::composite::fallback::FallbackCompositeNodeDefinition;
+use crate::bts::composite::parallel::ParallelCompositeNodeDefinition;
+use crate::bts::composite::sequence::SequenceCompositeNodeDefinition;
+use crate::bts::composite::utility::UtilityCompositeNodeDefinition;
+use crate::bts::decorator::calendar::BusinessHoursDecoratorNodeDefinition;
+use crate::bts::decorator::condition::ConditionDecoratorNodeDefinition;
+use crate::bts::decorator::dedup::DeduplicateDecoratorNodeDefinition;
+use crate::bts::decorator::escalation::EscalationDecoratorNodeDefinition;
+use crate::bts::decorator::guard::GuardDecoratorNodeDefinition;
+use crate::bts::decorator::invert::InvertDecoratorNodeDefinition;
+use crate::bts::decorator::reactive::ReactiveConditionDecoratorNodeDefinition;
+use crate::bts::decorator::repeat::RepeatDecoratorNodeDefinition;
+use crate::bts::decorator::retry::RetryDecoratorNodeDefinition;
+use crate::bts::decorator::timeout::TimeoutDecoratorNodeDefinition;
+use crate::bts::root::{OneOffRootBTNodeDefinition, ReactiveRootBTNodeDefinition, RootBTNodeDefinition, ToFirstErrorRootBTNodeDefinition, UntilStoppedRootBTNodeDefinition};
+use crate::complexity::{ComplexityReport, DefinitionLimits};
+use crate::events::{DefinitionEvent, DefinitionEventService};
+use crate::naming::resolve_node_names;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum DefinitionDocumentError {
+
+    DeserializeError(String),
+    DuplicateNodeId(i32),
+    DuplicateNodeName(String),
+    NodeIdOutOfRange(i64),
+    UnknownNodeName(String)
+
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum NodeDefinitionDocument {
+
+    BusinessHours { id: i32, child_id: i32, calendar: String, time_value_name: Option<String> },
+    CompensatingSequence { id: i32, steps: Vec<(i32, Option<i32>)> },
+    Condition { id: i32, child_id: i32, expression: ConditionExpression },
+    Deduplicate { id: i32, child_id: i32, value_names: HashSet<String>, subject_value_name: Option<String>, ttl_secs: u64 },
+    EmitEvent { id: i32, name: String, value_names: HashSet<String> },
+    EmitMetric { id: i32, name: String, operation: MetricOperation, labels: Vec<(String, String)> },
+    Escalation { id: i32, child_id: i32, escalation_id: i32, sla: VariableSpecification<Duration>, policy: EscalationPolicy },
+    ExecuteSubTree { id: i32, tree_id: i32, namespace: Option<String> },
+    Fallback { id: i32, children_ids: Vec<i32> },
+    Guard { id: i32, child_id: i32, requirements: HashMap<String, ArgumentDefinition> },
+    HumanTask { id: i32, title: String, assignee: Option<String>, form: HashMap<String, ArgumentDefinition> },
+    Invert { id: i32, child_id: i32 },
+    Parallel { id: i32, children_ids: Vec<i32>, num_successes_to_succeed: usize },
+    PrintLog { id: i32, message: String },
+    PublishMessage { id: i32, topic: String, value_names: HashSet<String> },
+    Quota { id: i32, counter: String, subject_value_name: Option<String>, limit: u64, window_secs: u64 },
+    ReactiveCondition { id: i32, child_id: i32, expression: ConditionExpression },
+    ReceiveMessage { id: i32, topic: String },
+    Repeat { id: i32, child_id: i32, policy: RepeatPolicy },
+    Retry { id: i32, child_id: i32, max_retries: u32, delay: VariableSpecification<Duration> },
+    SelectCommand { id: i32, command: CommandReference },
+    Sequence { id: i32, children_ids: Vec<i32> },
+    SetValue { id: i32, value_name: String, value: ValueHolder },
+    Timeout { id: i32, child_id: i32, duration: VariableSpecification<Duration> },
+    TransformValue { id: i32, input_names: HashSet<String>, transformer: Transformer },
+    Utility { id: i32, scored_children: Vec<(ScoreExpression, i32)> },
+    WaitDuration { id: i32, duration: VariableSpecification<Duration> },
+    WaitForSignal { id: i32, name: String }
+
+}
+
+impl NodeDefinitionDocument {
+
+    // Children in the order they are run, with compensations right after their steps and
+    // escalations after the nodes they escalate.
+    pub fn get_children_ids(&self) -> Vec<i32> {
+        match self {
+            NodeDefinitionDocument::CompensatingSequence { steps, .. } => steps.iter()
+                .flat_map(|(step_id, compensation_id)| std::iter::once(*step_id).chain(*compensation_id))
+                .collect(),
+            NodeDefinitionDocument::Fallback { children_ids, .. }
+            | NodeDefinitionDocument::Parallel { children_ids, .. }
+            | NodeDefinitionDocument::Sequence { children_ids, .. } => children_ids.clone(),
+            NodeDefinitionDocument::Utility { scored_children, .. } => scored_children.iter()
+                .map(|(_, child_id)| *child_id)
+                .collect(),
+            NodeDefinitionDocument::Escalation { child_id, escalation_id, .. } => vec![*child_id, *escalation_id],
+            NodeDefinitionDocument::BusinessHours { child_id, .. }
+            | NodeDefinitionDocument::Condition { child_id, .. }
+            | NodeDefinitionDocument::Deduplicate { child_id, .. }
+            | NodeDefinitionDocument::Guard { child_id, .. }
+            | NodeDefinitionDocument::Invert { child_id, .. }
+            | NodeDefinitionDocument::ReactiveCondition { child_id, .. }
+            | NodeDefinitionDocument::Repeat { child_id, .. }
+            | NodeDefinitionDocument::Retry { child_id, .. }
+            | NodeDefinitionDocument::Timeout { child_id, .. } => vec![*child_id],
+            _ => Vec::new()
+        }
+    }
+
+}
+
+impl From<NodeDefinitionDocument> for Arc<dyn BehaviorTreeNodeDefinition> {
+    fn from(document: NodeDefinitionDocument) -> Self {
+        match document {
+            NodeDefinitionDocument::BusinessHours { id, child_id, calendar, time_value_name } =>
+                Arc::new(BusinessHoursDecoratorNodeDefinition::new(id, child_id, calendar, time_value_name)),
+            NodeDefinitionDocument::CompensatingSequence { id, steps } =>
+                Arc::new(CompensatingSequenceCompositeNodeDefinition::new(id, steps)),
+            NodeDefinitionDocument::Condition { id, child_id, expression } =>
+                Arc::new(ConditionDecoratorNodeDefinition::new(id, child_id, expression)),
+            NodeDefinitionDocument::Deduplicate { id, child_id, value_names, subject_value_name, ttl_secs } =>
+                Arc::new(DeduplicateDecoratorNodeDefinition::new(id, child_id, value_names, subject_value_name, Duration::from_secs(ttl_secs))),
+            NodeDefinitionDocument::EmitEvent { id, name, value_names } =>
+                Arc::new(EmitEventActionNodeDefinition::new(id, name, value_names)),
+            NodeDefinitionDocument::EmitMetric { id, name, operation, labels } =>
+                Arc::new(EmitMetricActionNodeDefinition::new(id, name, operation, labels)),
+            NodeDefinitionDocument::Escalation { id, child_id, escalation_id, sla, policy } =>
+                Arc::new(EscalationDecoratorNodeDefinition::new(id, child_id, escalation_id, sla, policy)),
+            NodeDefinitionDocument::ExecuteSubTree { id, tree_id, namespace } => {
+                let definition = ExecuteSubTreeActionNodeDefinition::new(id, tree_id);
+
+                Arc::new(match namespace {
+                    Some(namespace) => definition.with_namespace(namespace),
+                    None => definition
+                })
+            },
+            NodeDefinitionDocument::Fallback { id, children_ids } =>
+                Arc::new(FallbackCompositeNodeDefinition::new(id, children_ids)),
+            NodeDefinitionDocument::Guard { id, child_id, requirements } =>
+                Arc::new(GuardDecoratorNodeDefinition::new(id, child_id, requirements)),
+            NodeDefinitionDocument::HumanTask { id, title, assignee, form } =>
+                Arc::new(HumanTaskActionNodeDefinition::new(id, title, assignee, form)),
+            NodeDefinitionDocument::Invert { id, child_id } =>
+                Arc::new(InvertDecoratorNodeDefinition::new(id, child_id)),
+            NodeDefinitionDocument::Parallel { id, children_ids, num_successes_to_succeed } =>
+                Arc::new(ParallelCompositeNodeDefinition::new(id, children_ids, num_successes_to_succeed)),
+            NodeDefinitionDocument::PrintLog { id, message } =>
+                Arc::new(PrintLogActionNodeDefinition::new(id, message)),
+            NodeDefinitionDocument::PublishMessage { id, topic, value_names } =>
+                Arc::new(PublishMessageActionNodeDefinition::new(id, topic, value_names)),
+            NodeDefinitionDocument::Quota { id, counter, subject_value_name, limit, window_secs } =>
+                Arc::new(QuotaActionNodeDefinition::new(id, counter, subject_value_name, limit, Duration::from_secs(window_secs))),
+            NodeDefinitionDocument::ReactiveCondition { id, child_id, expression } =>
+                Arc::new(ReactiveConditionDecoratorNodeDefinition::new(id, child_id, expression)),
+            NodeDefinitionDocument::ReceiveMessage { id, topic } =>
+                Arc::new(ReceiveMessageActionNodeDefinition::new(id, topic)),
+            NodeDefinitionDocument::Repeat { id, child_id, policy } =>
+                Arc::new(RepeatDecoratorNodeDefinition::new(id, child_id, policy)),
+            NodeDefinitionDocument::Retry { id, child_id, max_retries, delay } =>
+                Arc::new(RetryDecoratorNodeDefinition::new(id, child_id, max_retries, delay)),
+            NodeDefinitionDocument::SelectCommand { id, command } =>
+                Arc::new(SelectCommandActionNodeDefinition::new(id, command)),
+            NodeDefinitionDocument::Sequence { id, children_ids } =>
+                Arc::new(SequenceCompositeNodeDefinition::new(id, children_ids)),
+            NodeDefinitionDocument::SetValue { id, value_name, value } =>
+                Arc::new(SetValueActionNodeDefinition::new(id, value_name, value)),
+            NodeDefinitionDocument::Timeout { id, child_id, duration } =>
+                Arc::new(TimeoutDecoratorNodeDefinition::new(id, child_id, duration)),
+            NodeDefinitionDocument::TransformValue { id, input_names, transformer } =>
+                Arc::new(TransformValueActionNodeDefinition::new(id, input_names, transformer)),
+            NodeDefinitionDocument::Utility { id, scored_children } =>
+                Arc::new(UtilityCompositeNodeDefinition::new(id, scored_children)),
+            NodeDefinitionDocument::WaitDuration { id, duration } =>
+                Arc::new(WaitDurationActionNodeDefinition::new(id, duration)),
+            NodeDefinitionDocument::WaitForSignal { id, name } =>
+                Arc::new(WaitForSignalActionNodeDefinition::new(id, name))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum RootDefinitionDocument {
+
+    OneOff { id: i32, child_id: i32 },
+    Reactive { id: i32, child_id: i32, stop_on_error: bool },
+    ToFirstError { id: i32, child_id: i32 },
+    UntilStopped { id: i32, child_id: i32 }
+
+}
+
+impl From<RootDefinitionDocument> for Box<dyn RootBTNodeDefinition> {
+    fn from(document: RootDefinitionDocument) -> Self {
+        match document {
+            RootDefinitionDocument::OneOff { id, child_id } =>
+                Box::new(OneOffRootBTNodeDefinition::new(id, child_id)),
+            RootDefinitionDocument::Reactive { id, child_id, stop_on_error } =>
+                Box::new(ReactiveRootBTNodeDefinition::new(id, child_id, stop_on_error)),
+            RootDefinitionDocument::ToFirstError { id, child_id } =>
+                Box::new(ToFirstErrorRootBTNodeDefinition::new(id, child_id)),
+            RootDefinitionDocument::UntilStopped { id, child_id } =>
+                Box::new(UntilStoppedRootBTNodeDefinition::new(id, child_id))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct DefinitionMetadata {
+
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    team: Option<String>,
+    #[serde(default)]
+    links: Vec<String>
+
+}
+
+impl DefinitionMetadata {
+
+    pub fn get_owner(&self) -> &Option<String> {
+        &self.owner
+    }
+
+    pub fn get_team(&self) -> &Option<String> {
+        &self.team
+    }
+
+    pub fn get_links(&self) -> &Vec<String> {
+        &self.links
+    }
+
+}
+
+// Fields which a deployment can require every document to fill in.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub enum MetadataField {
+
+    Description,
+    Links,
+    Name,
+    Owner,
+    Tags,
+    Team
+
+}
+
+impl FromStr for MetadataField {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "description" => Result::Ok(MetadataField::Description),
+            "links" => Result::Ok(MetadataField::Links),
+            "name" => Result::Ok(MetadataField::Name),
+            "owner" => Result::Ok(MetadataField::Owner),
+            "tags" => Result::Ok(MetadataField::Tags),
+            "team" => Result::Ok(MetadataField::Team),
+            _ => Result::Err(value.to_owned())
+        }
+    }
+}
+
+// Serialized form of a tree definition, nodes refer to their children by id the same
+// way the definitions built in code do.
+#[derive(Serialize, Deserialize)]
+pub struct BehaviorTreeDocument {
+
+    id: i32,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    metadata: DefinitionMetadata,
+    root: RootDefinitionDocument,
+    nodes: Vec<NodeDefinitionDocument>,
+    #[serde(default)]
+    fixtures: Vec<BehaviorTreeFixture>,
+    #[serde(default)]
+    fallback_tree_id: Option<i32>,
+    #[serde(default)]
+    semantics_version: SemanticsVersion
+
+}
+
+impl BehaviorTreeDocument {
+
+    pub fn new(id: i32,
+               root: RootDefinitionDocument,
+               nodes: Vec<NodeDefinitionDocument>) -> BehaviorTreeDocument {
+        BehaviorTreeDocument {
+            id,
+            name: Option::None,
+            description: Option::None,
+            tags: Vec::new(),
+            metadata: DefinitionMetadata::default(),
+            root,
+            nodes,
+            fixtures: Vec::new(),
+            fallback_tree_id: Option::None,
+            semantics_version: SemanticsVersion::default()
+        }
+    }
+
+    // Takes over the name, description, tags and metadata, but none of the nodes.
+    pub fn with_details_of(mut self,
+                           document: BehaviorTreeDocument) -> BehaviorTreeDocument {
+        self.name = document.name;
+        self.description = document.description;
+        self.tags = document.tags;
+        self.metadata = document.metadata;
+        self
+    }
+
+    pub fn from_json(json: &str) -> Result<BehaviorTreeDocument, DefinitionDocumentError> {
+        let mut document: serde_json::Value = serde_json::from_str(json)
+            .map_err(|err| DefinitionDocumentError::DeserializeError(err.to_string()))?;
+        resolve_node_names(&mut document)?;
+
+        serde_json::from_value(document)
+            .map_err(|err| DefinitionDocumentError::DeserializeError(err.to_string()))
+    }
+
+    pub fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    pub fn get_name(&self) -> &Option<String> {
+        &self.name
+    }
+
+    pub fn get_description(&self) -> &Option<String> {
+        &self.description
+    }
+
+    pub fn get_tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn get_metadata(&self) -> &DefinitionMetadata {
+        &self.metadata
+    }
+
+    pub fn get_root(&self) -> &RootDefinitionDocument {
+        &self.root
+    }
+
+    pub fn get_nodes(&self) -> &Vec<NodeDefinitionDocument> {
+        &self.nodes
+    }
+
+    pub fn get_missing_metadata(&self,
+                                required: &[MetadataField]) -> Vec<MetadataField> {
+        required.iter()
+            .filter(|field| match field {
+                MetadataField::Description => self.description.is_none(),
+                MetadataField::Links => self.metadata.links.is_empty(),
+                MetadataField::Name => self.name.is_none(),
+                MetadataField::Owner => self.metadata.owner.is_none(),
+                MetadataField::Tags => self.tags.is_empty(),
+                MetadataField::Team => self.metadata.team.is_none()
+            })
+            .copied()
+            .collect()
+    }
+
+    // Edges are the links between parents and their children, including the one of the
+    // root. Conditions per edge count the comparisons of a condition or requirements of
+    // a guard.
+    pub fn get_complexity_report(&self) -> ComplexityReport {
+        let mut edges = 1;
+        let mut expression_depth = 0;
+        let mut conditions_per_edge = 0;
+
+        for node in &self.nodes {
+            edges += match node {
+                NodeDefinitionDocument::CompensatingSequence { steps, .. } => steps.iter()
+                    .map(|(_, compensation_id)| 1 + compensation_id.iter().count())
+                    .sum(),
+                NodeDefinitionDocument::Fallback { children_ids, .. }
+                | NodeDefinitionDocument::Parallel { children_ids, .. }
+                | NodeDefinitionDocument::Sequence { children_ids, .. } => children_ids.len(),
+                NodeDefinitionDocument::Utility { scored_children, .. } => scored_children.len(),
+                NodeDefinitionDocument::Escalation { .. } => 2,
+                NodeDefinitionDocument::BusinessHours { .. }
+                | NodeDefinitionDocument::Condition { .. }
+                | NodeDefinitionDocument::Deduplicate { .. }
+                | NodeDefinitionDocument::Guard { .. }
+                | NodeDefinitionDocument::Invert { .. }
+                | NodeDefinitionDocument::ReactiveCondition { .. }
+                | NodeDefinitionDocument::Repeat { .. }
+                | NodeDefinitionDocument::Retry { .. }
+                | NodeDefinitionDocument::Timeout { .. } => 1,
+                _ => 0
+            };
+
+            match node {
+                NodeDefinitionDocument::Condition { expression, .. }
+                | NodeDefinitionDocument::ReactiveCondition { expression, .. } => {
+                    expression_depth = expression_depth.max(expression.get_depth());
+                    conditions_per_edge = conditions_per_edge.max(expression.get_relations_count());
+                },
+                NodeDefinitionDocument::Guard { requirements, .. } =>
+                    conditions_per_edge = conditions_per_edge.max(requirements.len()),
+                _ => {}
+            }
+        }
+
+        ComplexityReport::new(self.nodes.len() + 1, edges, expression_depth, conditions_per_edge)
+    }
+
+    // Mutants of every condition in the tree, along with the id of the node they belong to.
+    pub fn get_condition_mutants(&self) -> Vec<(i32, ConditionMutant)> {
+        self.nodes.iter()
+            .flat_map(|node| match node {
+                NodeDefinitionDocument::Condition { id, expression, .. }
+                | NodeDefinitionDocument::ReactiveCondition { id, expression, .. } => expression
+                    .get_mutants()
+                    .into_iter()
+                    .map(|mutant| (*id, mutant))
+                    .collect(),
+                _ => Vec::new()
+            })
+            .collect()
+    }
+
+    pub fn with_condition_expression(mut self,
+                                     node_id: &i32,
+                                     condition: ConditionExpression) -> BehaviorTreeDocument {
+        for node in self.nodes.iter_mut() {
+            match node {
+                NodeDefinitionDocument::Condition { id, expression, .. }
+                | NodeDefinitionDocument::ReactiveCondition { id, expression, .. } if id == node_id => {
+                    *expression = condition;
+                    break;
+                },
+                _ => {}
+            }
+        }
+        self
+    }
+
+}
+
+impl From<BehaviorTreeDocument> for BehaviorTreeDefinition {
+    fn from(document: BehaviorTreeDocument) -> Self {
+        BehaviorTreeDefinition::new(document.id,
+                                    document.nodes.into_iter().map(|node| node.into()).collect(),
+                                    document.root.into())
+            .with_fixtures(document.fixtures)
+            .with_fallback_tree_id(document.fallback_tree_id)
+            .with_semantics_version(document.semantics_version)
+    }
+}
+
+// Payloads are passed around as json objects mapping value names to values, e.g.
+// {"age": {"Integer": [1, [18]]}}.
+pub fn parse_payload(json: &str) -> Result<ValuesPayload, DefinitionDocumentError> {
+    serde_json::from_str::<HashMap<String, ValueHolder>>(json)
+        .map(ValuesPayload::new)
+        .map_err(|err| DefinitionDocumentError::DeserializeError(err.to_string()))
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum DefinitionDocumentServiceError {
+
+    BehaviorTreeBuildingError(BehaviorTreeBuildingError),
+    DefinitionDocumentError(DefinitionDocumentError),
+    InvalidStatusTransition(i32, DefinitionStatus, DefinitionStatus),
+    MissingMetadata(i32, Vec<MetadataField>),
+    TooComplex(i32, ComplexityReport),
+    TreeIsArchived(i32),
+    TreeIsDisabled(i32),
+    TreeIdMismatch(i32, i32),
+    TreeOfGivenIdNotFound(i32),
+    VersionMismatch(i32, Option<u32>)
+
+}
+
+#[derive(Clone)]
+pub struct StoredDefinitionDocument {
+
+    json: String,
+    modified_at: SystemTime,
+    summary: DefinitionDocumentSummary
+
+}
+
+impl StoredDefinitionDocument {
+
+    pub fn get_json(&self) -> &String {
+        &self.json
+    }
+
+    pub fn get_modified_at(&self) -> &SystemTime {
+        &self.modified_at
+    }
+
+    pub fn get_version(&self) -> &u32 {
+        &self.summary.version
+    }
+
+    pub fn get_summary(&self) -> &DefinitionDocumentSummary {
+        &self.summary
+    }
+
+}
+
+// Drafts and active definitions follow the activation of their versions, deprecation
+// and archiving are set explicitly and stay in place until they are lifted.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+pub enum DefinitionStatus {
+
+    Draft,
+    Active,
+    Deprecated,
+    Archived
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct DefinitionUsageMetrics {
+
+    deprecated_evaluations: HashMap<i32, u64>
+
+}
+
+impl DefinitionUsageMetrics {
+
+    pub fn get_deprecated_evaluations(&self) -> &HashMap<i32, u64> {
+        &self.deprecated_evaluations
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DefinitionDocumentSummary {
+
+    id: i32,
+    name: Option<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+    metadata: DefinitionMetadata,
+    version: u32,
+    status: DefinitionStatus
+
+}
+
+impl DefinitionDocumentSummary {
+
+    pub fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    pub fn get_name(&self) -> &Option<String> {
+        &self.name
+    }
+
+    pub fn get_metadata(&self) -> &DefinitionMetadata {
+        &self.metadata
+    }
+
+    pub fn get_version(&self) -> &u32 {
+        &self.version
+    }
+
+    pub fn get_status(&self) -> &DefinitionStatus {
+        &self.status
+    }
+
+    fn matches(&self,
+               query: &DefinitionDocumentQuery) -> bool {
+        let matches_tag = query.tag
+            .as_ref()
+            .is_none_or(|tag| self.tags.contains(tag));
+        let matches_owner = query.owner
+            .as_ref()
+            .is_none_or(|owner| self.metadata.owner.as_ref() == Option::Some(owner));
+        let matches_team = query.team
+            .as_ref()
+            .is_none_or(|team| self.metadata.team.as_ref() == Option::Some(team));
+        let matches_status = query.status
+            .is_none_or(|status| self.status == status);
+        let matches_search = query.search
+            .as_ref()
+            .map(|search| search.to_lowercase())
+            .is_none_or(|search| self.name.iter()
+                .chain(self.description.iter())
+                .any(|text| text.to_lowercase().contains(&search)));
+
+        matches_tag && matches_owner && matches_team && matches_status && matches_search
+    }
+
+}
+
+// Documents are listed in the order of their ids, the cursor is the id of the last
+// document of the previous page.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct DefinitionDocumentQuery {
+
+    #[serde(default)]
+    cursor: Option<i32>,
+    #[serde(default)]
+    limit: Option<usize>,
+    #[serde(default)]
+    tag: Option<String>,
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    team: Option<String>,
+    #[serde(default)]
+    status: Option<DefinitionStatus>,
+    #[serde(default)]
+    search: Option<String>
+
+}
+
+impl DefinitionDocumentQuery {
+
+    pub fn with_cursor(mut self,
+                       cursor: i32) -> DefinitionDocumentQuery {
+        self.cursor = Option::Some(cursor);
+        self
+    }
+
+    pub fn with_limit(mut self,
+                      limit: usize) -> DefinitionDocumentQuery {
+        self.limit = Option::Some(limit);
+        self
+    }
+
+    pub fn with_tag(mut self,
+                    tag: String) -> DefinitionDocumentQuery {
+        self.tag = Option::Some(tag);
+        self
+    }
+
+    pub fn with_owner(mut self,
+                      owner: String) -> DefinitionDocumentQuery {
+        self.owner = Option::Some(owner);
+        self
+    }
+
+    pub fn with_team(mut self,
+                     team: String) -> DefinitionDocumentQuery {
+        self.team = Option::Some(team);
+        self
+    }
+
+    pub fn with_status(mut self,
+                       status: DefinitionStatus) -> DefinitionDocumentQuery {
+        self.status = Option::Some(status);
+        self
+    }
+
+    pub fn with_search(mut self,
+                       search: String) -> DefinitionDocumentQuery {
+        self.search = Option::Some(search);
+        self
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DefinitionDocumentPage {
+
+    items: Vec<DefinitionDocumentSummary>,
+    next_cursor: Option<i32>
+
+}
+
+impl DefinitionDocumentPage {
+
+    pub fn get_items(&self) -> &Vec<DefinitionDocumentSummary> {
+        &self.items
+    }
+
+    pub fn get_next_cursor(&self) -> &Option<i32> {
+        &self.next_cursor
+    }
+
+}
+
+// Keeps the latest document put for every tree, so that it can be read back the way it
+// was written. Every put inserts a new standby version which still has to be activated.
+pub struct DefinitionDocumentService {
+
+    definition_service: Arc<BehaviorTreeDefinitionService>,
+    deprecated_evaluations: DashMap<i32, u64>,
+    documents: DashMap<i32, StoredDefinitionDocument>,
+    events: Arc<DefinitionEventService>,
+    limits: DefinitionLimits,
+    next_tree_id: AtomicI32,
+    required_metadata: Vec<MetadataField>,
+    retired_statuses: DashMap<i32, DefinitionStatus>,
+    disabled_trees: DashMap<i32, ()>
+
+}
+
+const DEFAULT_PAGE_LIMIT: usize = 50;
+const MAX_PAGE_LIMIT: usize = 500;
+
+// Expected by puts which may only create the document, versions start at one.
+pub const NO_VERSION: u32 = 0;
+
+impl DefinitionDocumentService {
+
+    pub fn new(definition_service: Arc<BehaviorTreeDefinitionService>) -> DefinitionDocumentService {
+        DefinitionDocumentService {
+            definition_service,
+            deprecated_evaluations: DashMap::new(),
+            documents: DashMap::new(),
+            events: Arc::new(DefinitionEventService::default()),
+            limits: DefinitionLimits::default(),
+            next_tree_id: AtomicI32::new(1),
+            required_metadata: Vec::new(),
+            retired_statuses: DashMap::new(),
+            disabled_trees: DashMap::new()
+        }
+    }
+
+    pub fn with_required_metadata(mut self,
+                                  required_metadata: Vec<MetadataField>) -> DefinitionDocumentService {
+        self.required_metadata = required_metadata;
+        self
+    }
+
+    pub fn with_limits(mut self,
+                       limits: DefinitionLimits) -> DefinitionDocumentService {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_events(mut self,
+                       events: Arc<DefinitionEventService>) -> DefinitionDocumentService {
+        self.events = events;
+        self
+    }
+
+    pub fn get(&self,
+               tree_id: &i32) -> Option<StoredDefinitionDocument> {
+        self.documents
+            .get(tree_id)
+            .map(|document| document.clone())
+    }
+
+    pub fn list(&self,
+                query: &DefinitionDocumentQuery) -> DefinitionDocumentPage {
+        let limit = query.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT);
+
+        let mut items: Vec<DefinitionDocumentSummary> = self.documents
+            .iter()
+            .filter(|entry| query.cursor.is_none_or(|cursor| *entry.key() > cursor))
+            .map(|entry| self.get_summary(entry.value()))
+            .filter(|summary| summary.matches(query))
+            .collect();
+
+        items.sort_by_key(|summary| summary.id);
+
+        let next_cursor = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(|summary| summary.id)
+        } else {
+            Option::None
+        };
+
+        DefinitionDocumentPage {
+            items,
+            next_cursor
+        }
+    }
+
+    pub fn get_status(&self,
+                      tree_id: &i32) -> Option<DefinitionStatus> {
+        if !self.documents.contains_key(tree_id) {
+            return Option::None;
+        }
+
+        Option::Some(self.get_document_status(tree_id))
+    }
+
+    // Active definitions can be deprecated, deprecated ones archived, both steps can be
+    // taken back. Drafts become active only by activating one of their versions.
+    pub fn transition(&self,
+                      tree_id: &i32,
+                      status: DefinitionStatus) -> Result<DefinitionStatus, DefinitionDocumentServiceError> {
+        if self.definition_service.is_read_only() {
+            return Result::Err(
+                DefinitionDocumentServiceError::BehaviorTreeBuildingError(BehaviorTreeBuildingError::ReadOnly));
+        }
+
+        let current_status = self.get_status(tree_id)
+            .ok_or(DefinitionDocumentServiceError::TreeOfGivenIdNotFound(*tree_id))?;
+
+        match (current_status, status) {
+            (DefinitionStatus::Active, DefinitionStatus::Deprecated)
+            | (DefinitionStatus::Deprecated, DefinitionStatus::Archived)
+            | (DefinitionStatus::Archived, DefinitionStatus::Deprecated) => {
+                self.retired_statuses.insert(*tree_id, status);
+            },
+            (DefinitionStatus::Deprecated, DefinitionStatus::Active) => {
+                self.retired_statuses.remove(tree_id);
+            },
+            (current_status, status) if current_status == status => return Result::Ok(status),
+            (current_status, status) => return Result::Err(
+                DefinitionDocumentServiceError::InvalidStatusTransition(*tree_id, current_status, status))
+        }
+
+        self.events.publish(DefinitionEvent::StatusChanged { tree_id: *tree_id, status });
+
+        Result::Ok(status)
+    }
+
+    // Disabled trees are refused whatever their status is, until they are enabled again.
+    // Only trees with a document or a version can be disabled, so that a tree created
+    // later under the id of an unknown one does not start disabled.
+    pub fn set_disabled(&self,
+                        tree_id: &i32,
+                        disabled: bool) -> Result<(), DefinitionDocumentServiceError> {
+        if !self.documents.contains_key(tree_id) && !self.definition_service.contains(tree_id) {
+            return Result::Err(DefinitionDocumentServiceError::TreeOfGivenIdNotFound(*tree_id));
+        }
+
+        match disabled {
+            true => { self.disabled_trees.insert(*tree_id, ()); },
+            false => { self.disabled_trees.remove(tree_id); }
+        }
+
+        Result::Ok(())
+    }
+
+    pub fn is_disabled(&self,
+                       tree_id: &i32) -> bool {
+        self.disabled_trees.contains_key(tree_id)
+    }
+
+    // Called before the tree is evaluated, disabled and archived trees are refused and
+    // evaluations of deprecated ones are counted. Trees without a document are only
+    // checked for being disabled.
+    pub fn check_evaluation(&self,
+                            tree_id: &i32) -> Result<Option<DefinitionStatus>, DefinitionDocumentServiceError> {
+        if self.is_disabled(tree_id) {
+            return Result::Err(DefinitionDocumentServiceError::TreeIsDisabled(*tree_id));
+        }
+
+        let status = self.get_status(tree_id);
+
+        match status {
+            Some(DefinitionStatus::Archived) =>
+                return Result::Err(DefinitionDocumentServiceError::TreeIsArchived(*tree_id)),
+            Some(DefinitionStatus::Deprecated) =>
+                *self.deprecated_evaluations.entry(*tree_id).or_insert(0) += 1,
+            _ => {}
+        }
+
+        Result::Ok(status)
+    }
+
+    pub fn get_usage_metrics(&self) -> DefinitionUsageMetrics {
+        DefinitionUsageMetrics {
+            deprecated_evaluations: self.deprecated_evaluations
+                .iter()
+                .map(|entry| (*entry.key(), *entry.value()))
+                .collect()
+        }
+    }
+
+    fn get_summary(&self,
+                   document: &StoredDefinitionDocument) -> DefinitionDocumentSummary {
+        let mut summary = document.summary.clone();
+
+        summary.status = self.get_document_status(&summary.id);
+        summary
+    }
+
+    fn get_document_status(&self,
+                           tree_id: &i32) -> DefinitionStatus {
+        if let Some(status) = self.retired_statuses.get(tree_id) {
+            return *status;
+        }
+
+        match self.definition_service.get_active_version(tree_id) {
+            Some(_) => DefinitionStatus::Active,
+            None => DefinitionStatus::Draft
+        }
+    }
+
+    // Puts the first version of a tree under an id which is not used yet, the id of the
+    // document itself is replaced and can be left out.
+    pub fn create(&self,
+                  json: &str) -> Result<(i32, u32), DefinitionDocumentServiceError> {
+        let mut document: serde_json::Value = serde_json::from_str(json)
+            .map_err(|err| DefinitionDocumentServiceError::DefinitionDocumentError(
+                DefinitionDocumentError::DeserializeError(err.to_string())))?;
+
+        // Another document can be put under the reserved id in the meantime, the put then
+        // fails and the next id is tried.
+        loop {
+            let tree_id = self.reserve_tree_id();
+            if let Some(fields) = document.as_object_mut() {
+                fields.insert("id".to_owned(), serde_json::Value::from(tree_id));
+            }
+
+            match self.put(&tree_id, &document.to_string(), Option::Some(&[NO_VERSION])) {
+                Err(DefinitionDocumentServiceError::VersionMismatch(_, _)) => continue,
+                result => return result.map(|version| (tree_id, version))
+            }
+        }
+    }
+
+    fn reserve_tree_id(&self) -> i32 {
+        loop {
+            let tree_id = self.next_tree_id.fetch_add(1, Ordering::SeqCst);
+
+            if !self.documents.contains_key(&tree_id) && !self.definition_service.contains(&tree_id) {
+                return tree_id;
+            }
+        }
+    }
+
+    // The document is only stored when the latest version is one of the expected ones,
+    // no expected versions means that any version, or none at all, can be replaced and
+    // NO_VERSION means that there must be no document yet.
+    pub fn put(&self,
+               tree_id: &i32,
+               json: &str,
+               expected_versions: Option<&[u32]>) -> Result<u32, DefinitionDocumentServiceError> {
+        let document = BehaviorTreeDocument::from_json(json)
+            .map_err(DefinitionDocumentServiceError::DefinitionDocumentError)?;
+
+        if document.get_id() != tree_id {
+            return Result::Err(
+                DefinitionDocumentServiceError::TreeIdMismatch(*tree_id, *document.get_id()));
+        }
+
+        let missing_metadata = document.get_missing_metadata(&self.required_metadata);
+        if !missing_metadata.is_empty() {
+            return Result::Err(
+                DefinitionDocumentServiceError::MissingMetadata(*tree_id, missing_metadata));
+        }
+
+        let complexity_report = document.get_complexity_report().check(&self.limits);
+        if !complexity_report.get_violations().is_empty() {
+            return Result::Err(
+                DefinitionDocumentServiceError::TooComplex(*tree_id, complexity_report));
+        }
+
+        let entry = self.documents.entry(*tree_id);
+        let current_version = match &entry {
+            Entry::Occupied(entry) => Option::Some(entry.get().summary.version),
+            Entry::Vacant(_) => Option::None
+        };
+
+        if let Some(expected_versions) = expected_versions {
+            if !expected_versions.contains(&current_version.unwrap_or(NO_VERSION)) {
+                return Result::Err(
+                    DefinitionDocumentServiceError::VersionMismatch(*tree_id, current_version));
+            }
+        }
+
+        let version = current_version
+            .into_iter()
+            .chain(self.definition_service.get_active_version(tree_id))
+            .max()
+            .unwrap_or(0) + 1;
+        let summary = DefinitionDocumentSummary {
+            id: *tree_id,
+            name: document.name.clone(),
+            description: document.description.clone(),
+            tags: document.tags.clone(),
+            metadata: document.metadata.clone(),
+            version,
+            status: DefinitionStatus::Draft
+        };
+
+        self.definition_service
+            .insert_standby(document.into(), version)
+            .map_err(DefinitionDocumentServiceError::BehaviorTreeBuildingError)?;
+
+        let document = StoredDefinitionDocument {
+            json: json.to_owned(),
+            modified_at: SystemTime::now(),
+            summary
+        };
+
+        match entry {
+            Entry::Occupied(mut entry) => { entry.insert(document); },
+            Entry::Vacant(entry) => { entry.insert(document); }
+        }
+
+        self.events.publish(match current_version {
+            None => DefinitionEvent::Created { tree_id: *tree_id, version },
+            Some(_) => DefinitionEvent::Updated { tree_id: *tree_id, version }
+        });
+
+        Result::Ok(version)
+    }
+
+}
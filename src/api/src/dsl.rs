@@ -0,0 +1,237 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use buttercup_conditions::{ConditionExpression, LogicalExpression, RelationalExpression, RelationalExpressionSpecification};
+use buttercup_conditions::relational::{EqualsRelationalExpression, GreaterThanOrEqualsRelationalExpression, GreaterThanRelationalExpression, LessThanOrEqualsRelationalExpression, LessThanRelationalExpression, NotEqualsRelationalExpression};
+use buttercup_values::ValueHolder;
+use buttercup_variables::VariableSpecification;
+
+use crate::document::{BehaviorTreeDocument, NodeDefinitionDocument, RootDefinitionDocument};
+use crate::table::cell::parse_literal;
+
+const ROOT_ID: i32 = 1;
+
+// Builds trees with one-off roots, for embedders and tests, without numbering the nodes
+// by hand. Nodes are numbered from 2, parents before their children:
+//
+//     tree! {
+//         7 => sequence [
+//             when (amount > 100 && tier == "gold") -> log("gold"),
+//             invert(signal("cancel")),
+//             fallback [subtree(10), wait(250)],
+//             parallel(1, [log("left"), log("right")]),
+//             set(done = true),
+//             node(custom_node())
+//         ]
+//     }
+//
+// Conditions are conjunctions of comparisons of a value with a literal or with another
+// value. Waits are in milliseconds, and node(...) takes any DslNode.
+#[macro_export]
+macro_rules! tree {
+    ($id:literal => $($node:tt)+) => {
+        $crate::dsl::document($id, $crate::tree!(@node $($node)+))
+    };
+
+    (@node when ($($condition:tt)+) -> $kind:ident $args:tt) => {
+        $crate::dsl::condition($crate::tree!(@condition [] $($condition)+), $crate::tree!(@node $kind $args))
+    };
+    (@node sequence [$($children:tt)*]) => {
+        $crate::dsl::sequence($crate::tree!(@children [] $($children)*))
+    };
+    (@node fallback [$($children:tt)*]) => {
+        $crate::dsl::fallback($crate::tree!(@children [] $($children)*))
+    };
+    (@node parallel ($successes:expr, [$($children:tt)*])) => {
+        $crate::dsl::parallel($successes, $crate::tree!(@children [] $($children)*))
+    };
+    (@node invert ($($child:tt)+)) => {
+        $crate::dsl::invert($crate::tree!(@node $($child)+))
+    };
+    (@node log ($message:expr)) => {
+        $crate::dsl::print_log($message)
+    };
+    (@node subtree ($tree_id:expr)) => {
+        $crate::dsl::subtree($tree_id)
+    };
+    (@node wait ($millis:expr)) => {
+        $crate::dsl::wait(::std::time::Duration::from_millis($millis))
+    };
+    (@node signal ($name:expr)) => {
+        $crate::dsl::wait_for_signal($name)
+    };
+    (@node set ($name:ident = $value:literal)) => {
+        $crate::dsl::set_value(stringify!($name), $crate::dsl::literal(stringify!($value)))
+    };
+    (@node node ($node:expr)) => {
+        $node
+    };
+
+    (@children [$($out:expr),*]) => {
+        vec![$($out),*]
+    };
+    (@children [$($out:expr),*] when $condition:tt -> $kind:ident $args:tt $(, $($rest:tt)*)?) => {
+        $crate::tree!(@children [$($out,)* $crate::tree!(@node when $condition -> $kind $args)] $($($rest)*)?)
+    };
+    (@children [$($out:expr),*] $kind:ident $args:tt $(, $($rest:tt)*)?) => {
+        $crate::tree!(@children [$($out,)* $crate::tree!(@node $kind $args)] $($($rest)*)?)
+    };
+
+    (@condition [$($out:expr),*]) => {
+        $crate::dsl::all(vec![$($out),*])
+    };
+    (@condition [$($out:expr),*] $name:ident $operator:tt $value:literal $(&& $($rest:tt)+)?) => {
+        $crate::tree!(@condition [$($out,)* $crate::dsl::relation(
+            stringify!($name), $crate::tree!(@operator $operator), $crate::dsl::DslOperand::Literal($crate::dsl::literal(stringify!($value))))] $($($rest)+)?)
+    };
+    (@condition [$($out:expr),*] $name:ident $operator:tt $other:ident $(&& $($rest:tt)+)?) => {
+        $crate::tree!(@condition [$($out,)* $crate::dsl::relation(
+            stringify!($name), $crate::tree!(@operator $operator), $crate::dsl::DslOperand::Name(stringify!($other).to_owned()))] $($($rest)+)?)
+    };
+
+    (@operator ==) => { "==" };
+    (@operator !=) => { "!=" };
+    (@operator <) => { "<" };
+    (@operator <=) => { "<=" };
+    (@operator >) => { ">" };
+    (@operator >=) => { ">=" };
+}
+
+// A node whose id, and the ids of its children, are only known once the tree is built.
+pub struct DslNode {
+
+    children: Vec<DslNode>,
+    build: Box<dyn FnOnce(i32, Vec<i32>) -> NodeDefinitionDocument>
+
+}
+
+impl DslNode {
+
+    pub fn new<F>(children: Vec<DslNode>,
+                  build: F) -> DslNode
+        where F: FnOnce(i32, Vec<i32>) -> NodeDefinitionDocument + 'static {
+        DslNode {
+            children,
+            build: Box::new(build)
+        }
+    }
+
+    pub fn leaf<F>(build: F) -> DslNode
+        where F: FnOnce(i32) -> NodeDefinitionDocument + 'static {
+        DslNode::new(Vec::new(), move |id, _| build(id))
+    }
+
+    fn flatten(self,
+               last_id: &mut i32,
+               nodes: &mut Vec<NodeDefinitionDocument>) -> i32 {
+        *last_id += 1;
+        let id = *last_id;
+        let children_ids = self.children
+            .into_iter()
+            .map(|child| child.flatten(last_id, nodes))
+            .collect();
+
+        nodes.push((self.build)(id, children_ids));
+        id
+    }
+
+}
+
+pub enum DslOperand {
+
+    Literal(ValueHolder),
+    Name(String)
+
+}
+
+pub fn document(id: i32,
+                node: DslNode) -> BehaviorTreeDocument {
+    let mut last_id = ROOT_ID;
+    let mut nodes = Vec::new();
+    let child_id = node.flatten(&mut last_id, &mut nodes);
+
+    BehaviorTreeDocument::new(id, RootDefinitionDocument::OneOff { id: ROOT_ID, child_id }, nodes)
+}
+
+pub fn sequence(children: Vec<DslNode>) -> DslNode {
+    DslNode::new(children, |id, children_ids| NodeDefinitionDocument::Sequence { id, children_ids })
+}
+
+pub fn fallback(children: Vec<DslNode>) -> DslNode {
+    DslNode::new(children, |id, children_ids| NodeDefinitionDocument::Fallback { id, children_ids })
+}
+
+pub fn parallel(num_successes_to_succeed: usize,
+                children: Vec<DslNode>) -> DslNode {
+    DslNode::new(children, move |id, children_ids|
+        NodeDefinitionDocument::Parallel { id, children_ids, num_successes_to_succeed })
+}
+
+pub fn invert(child: DslNode) -> DslNode {
+    DslNode::new(vec![child], |id, children_ids| NodeDefinitionDocument::Invert { id, child_id: children_ids[0] })
+}
+
+pub fn condition(expression: ConditionExpression,
+                 child: DslNode) -> DslNode {
+    DslNode::new(vec![child], move |id, children_ids|
+        NodeDefinitionDocument::Condition { id, child_id: children_ids[0], expression })
+}
+
+pub fn print_log(message: &str) -> DslNode {
+    let message = message.to_owned();
+
+    DslNode::leaf(move |id| NodeDefinitionDocument::PrintLog { id, message })
+}
+
+pub fn subtree(tree_id: i32) -> DslNode {
+    DslNode::leaf(move |id| NodeDefinitionDocument::ExecuteSubTree { id, tree_id, namespace: Option::None })
+}
+
+pub fn wait(duration: Duration) -> DslNode {
+    DslNode::leaf(move |id| NodeDefinitionDocument::WaitDuration { id, duration: VariableSpecification::Literal(Arc::new(duration)) })
+}
+
+pub fn wait_for_signal(name: &str) -> DslNode {
+    let name = name.to_owned();
+
+    DslNode::leaf(move |id| NodeDefinitionDocument::WaitForSignal { id, name })
+}
+
+pub fn set_value(value_name: &str,
+                 value: ValueHolder) -> DslNode {
+    let value_name = value_name.to_owned();
+
+    DslNode::leaf(move |id| NodeDefinitionDocument::SetValue { id, value_name, value })
+}
+
+// Literals are tokens of the macro, so they are always either numbers, strings or booleans.
+pub fn literal(token: &str) -> ValueHolder {
+    parse_literal(token).unwrap_or_else(|err| panic!("Invalid literal {}: {}", token, err))
+}
+
+pub fn relation(name: &str,
+                operator: &str,
+                operand: DslOperand) -> ConditionExpression {
+    let specification = match operand {
+        DslOperand::Literal(value) => RelationalExpressionSpecification::NameAndLiteral(name.to_owned(), value),
+        DslOperand::Name(other) => RelationalExpressionSpecification::NameAndName(name.to_owned(), other)
+    };
+
+    ConditionExpression::RelationExpression(match operator {
+        "==" => RelationalExpression::Equals(EqualsRelationalExpression::new(specification)),
+        "!=" => RelationalExpression::NotEquals(NotEqualsRelationalExpression::new(specification)),
+        "<" => RelationalExpression::LessThan(LessThanRelationalExpression::new(specification)),
+        "<=" => RelationalExpression::LessThanOrEquals(LessThanOrEqualsRelationalExpression::new(specification)),
+        ">" => RelationalExpression::GreaterThan(GreaterThanRelationalExpression::new(specification)),
+        ">=" => RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(specification)),
+        // The macro only passes the operators above.
+        _ => panic!("Unsupported operator {}", operator)
+    })
+}
+
+pub fn all(mut relations: Vec<ConditionExpression>) -> ConditionExpression {
+    match relations.len() {
+        1 => relations.remove(0),
+        _ => ConditionExpression::LogicalExpression(Box::new(LogicalExpression::And(relations)))
+    }
+}
@@ -0,0 +1,604 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use buttercup_blackboards::{LocalBlackboard, LocalBlackboardError};
+use buttercup_bts::arbitration::{ArbitrationDecision, CommandArbiter};
+use buttercup_bts::budget::{EvaluationBudget, EvaluationUsage};
+use buttercup_bts::calendar::BusinessCalendars;
+use buttercup_bts::command::CommandRegistry;
+use buttercup_bts::context::BTNodeExecutionContext;
+use buttercup_bts::debug::{DebugRecorder, EvaluationPath};
+use buttercup_bts::dedup::{FingerprintStore, InMemoryFingerprintStore};
+#[cfg(feature = "fault-injection")]
+use buttercup_bts::faults::FaultInjector;
+use buttercup_bts::footprint::TreeFootprint;
+use buttercup_bts::hits::HitCountsSnapshot;
+use buttercup_bts::node::decorator::guard::GuardReport;
+use buttercup_bts::quota::{CounterStore, InMemoryCounterStore};
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_bts::trace::{ChromeTrace, NodeTiming, TickTrace};
+use buttercup_bts::tree::{BehaviorTree, BehaviorTreeService};
+use buttercup_values::{DEFAULT_MAX_NUMBER_DIGITS, ValuesPayload};
+
+use crate::bts::{BehaviorTreeBuildingError, BehaviorTreeBuildingService, BehaviorTreeDefinition, BehaviorTreeDefinitionService, FixtureMismatch};
+use crate::complexity::{ComplexityReport, DefinitionLimits};
+use crate::document::{BehaviorTreeDocument, DefinitionDocumentError};
+use crate::mutation::SurvivingMutant;
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum EngineError {
+
+    BehaviorTreeBuildingError(BehaviorTreeBuildingError),
+    BlackboardError(LocalBlackboardError),
+    DefinitionDocumentError(DefinitionDocumentError),
+    NumberTooLong(String, usize),
+    TickError(TickError),
+    TooComplex(ComplexityReport),
+    TreeOfGivenIdNotFound(i32)
+
+}
+
+impl From<BehaviorTreeBuildingError> for EngineError {
+    fn from(err: BehaviorTreeBuildingError) -> Self {
+        EngineError::BehaviorTreeBuildingError(err)
+    }
+}
+
+impl From<LocalBlackboardError> for EngineError {
+    fn from(err: LocalBlackboardError) -> Self {
+        EngineError::BlackboardError(err)
+    }
+}
+
+impl From<DefinitionDocumentError> for EngineError {
+    fn from(err: DefinitionDocumentError) -> Self {
+        EngineError::DefinitionDocumentError(err)
+    }
+}
+
+impl From<TickError> for EngineError {
+    fn from(err: TickError) -> Self {
+        EngineError::TickError(err)
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct AdhocEvaluation {
+
+    result: Result<TickStatus, TickError>,
+    trace: Vec<NodeTiming>,
+    hits: HitCountsSnapshot,
+    usage: EvaluationUsage,
+    path: Option<EvaluationPath>,
+    guard_reports: Vec<GuardReport>
+
+}
+
+impl AdhocEvaluation {
+
+    pub fn get_result(&self) -> &Result<TickStatus, TickError> {
+        &self.result
+    }
+
+    pub fn get_trace(&self) -> &Vec<NodeTiming> {
+        &self.trace
+    }
+
+    pub fn get_hits(&self) -> &HitCountsSnapshot {
+        &self.hits
+    }
+
+    pub fn get_usage(&self) -> &EvaluationUsage {
+        &self.usage
+    }
+
+    pub fn get_path(&self) -> &Option<EvaluationPath> {
+        &self.path
+    }
+
+    pub fn get_guard_reports(&self) -> &Vec<GuardReport> {
+        &self.guard_reports
+    }
+
+    pub fn get_chrome_trace(&self) -> ChromeTrace {
+        ChromeTrace::new(&self.trace)
+    }
+
+}
+
+// Evaluation of one of the trees which selected commands for the same subject, with the
+// decisions the arbitration made about the commands the tree selected.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ArbitratedEvaluation {
+
+    tree_id: i32,
+    result: Result<TickStatus, TickError>,
+    trace: Vec<NodeTiming>,
+    decisions: Vec<ArbitrationDecision>
+
+}
+
+impl ArbitratedEvaluation {
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_result(&self) -> &Result<TickStatus, TickError> {
+        &self.result
+    }
+
+    pub fn get_trace(&self) -> &Vec<NodeTiming> {
+        &self.trace
+    }
+
+    pub fn get_decisions(&self) -> &Vec<ArbitrationDecision> {
+        &self.decisions
+    }
+
+    pub fn get_accepted(&self) -> Vec<&ArbitrationDecision> {
+        self.decisions.iter().filter(|decision| decision.is_accepted()).collect()
+    }
+
+}
+
+// Result of an evaluation which may have been handed over to the fallback tree, in which
+// case the tree id is the one of the fallback and the reason is the error of the original.
+#[derive(Serialize, Eq, PartialEq, Debug, Clone)]
+pub struct FallbackEvaluation {
+
+    tree_id: i32,
+    result: TickStatus,
+    fallback_reason: Option<TickError>
+
+}
+
+impl FallbackEvaluation {
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_result(&self) -> &TickStatus {
+        &self.result
+    }
+
+    pub fn get_fallback_reason(&self) -> &Option<TickError> {
+        &self.fallback_reason
+    }
+
+}
+
+// Runs trees in process, without the http server and the endpoint service. Every tree
+// gets its own context on the first tick, values are fed into it with put_values.
+pub struct ButtercupEngine {
+
+    building_service: BehaviorTreeBuildingService,
+    calendars: Arc<BusinessCalendars>,
+    command_arbiter: Arc<CommandArbiter>,
+    contexts: DashMap<i32, (Uuid, Arc<BTNodeExecutionContext>)>,
+    counter_store: Arc<dyn CounterStore>,
+    definition_service: Arc<BehaviorTreeDefinitionService>,
+    fallback_counts: DashMap<i32, u64>,
+    #[cfg(feature = "fault-injection")]
+    fault_injector: Option<Arc<FaultInjector>>,
+    fingerprint_store: Arc<dyn FingerprintStore>,
+    max_number_digits: usize,
+    tree_service: Arc<BehaviorTreeService>
+
+}
+
+impl Default for ButtercupEngine {
+    fn default() -> Self {
+        ButtercupEngine::new(Arc::new(BehaviorTreeService::default()),
+                             Arc::new(BehaviorTreeDefinitionService::default()))
+    }
+}
+
+impl ButtercupEngine {
+
+    pub fn new(tree_service: Arc<BehaviorTreeService>,
+               definition_service: Arc<BehaviorTreeDefinitionService>) -> ButtercupEngine {
+        ButtercupEngine {
+            building_service: BehaviorTreeBuildingService::new(tree_service.clone(),
+                                                               definition_service.clone()),
+            calendars: Arc::new(BusinessCalendars::default()),
+            command_arbiter: Arc::new(CommandArbiter::new(Arc::new(InMemoryCounterStore::default()))),
+            contexts: DashMap::new(),
+            counter_store: Arc::new(InMemoryCounterStore::default()),
+            definition_service,
+            fallback_counts: DashMap::new(),
+            #[cfg(feature = "fault-injection")]
+            fault_injector: Option::None,
+            fingerprint_store: Arc::new(InMemoryFingerprintStore::default()),
+            max_number_digits: DEFAULT_MAX_NUMBER_DIGITS,
+            tree_service
+        }
+    }
+
+    // Applies to the trees built from now on.
+    pub fn with_evaluation_budget(mut self,
+                                  evaluation_budget: EvaluationBudget) -> ButtercupEngine {
+        self.building_service = std::mem::take(&mut self.building_service).with_evaluation_budget(evaluation_budget);
+        self
+    }
+
+    pub fn with_command_registry(mut self,
+                                 command_registry: Arc<CommandRegistry>) -> ButtercupEngine {
+        self.building_service = std::mem::take(&mut self.building_service).with_command_registry(command_registry);
+        self
+    }
+
+    // Applies to the contexts the trees get on their first tick, see put_values.
+    #[cfg(feature = "fault-injection")]
+    pub fn with_fault_injector(mut self,
+                               fault_injector: Arc<FaultInjector>) -> ButtercupEngine {
+        self.fault_injector = Option::Some(fault_injector);
+        self
+    }
+
+    pub fn with_calendars(mut self,
+                          calendars: Arc<BusinessCalendars>) -> ButtercupEngine {
+        self.calendars = calendars;
+        self
+    }
+
+    pub fn with_command_arbiter(mut self,
+                                command_arbiter: Arc<CommandArbiter>) -> ButtercupEngine {
+        self.command_arbiter = command_arbiter;
+        self
+    }
+
+    pub fn with_counter_store(mut self,
+                              counter_store: Arc<dyn CounterStore>) -> ButtercupEngine {
+        self.counter_store = counter_store;
+        self
+    }
+
+    pub fn with_fingerprint_store(mut self,
+                                  fingerprint_store: Arc<dyn FingerprintStore>) -> ButtercupEngine {
+        self.fingerprint_store = fingerprint_store;
+        self
+    }
+
+    // Payloads with longer numbers are rejected before any condition compares them.
+    pub fn with_max_number_digits(mut self,
+                                  max_number_digits: usize) -> ButtercupEngine {
+        self.max_number_digits = max_number_digits;
+        self
+    }
+
+    pub fn insert_definition(&self,
+                             definition: BehaviorTreeDefinition,
+                             version: u32) -> Result<(), EngineError> {
+        Result::Ok(self.definition_service.insert_standby(definition, version)?)
+    }
+
+    // Inserts the definition as the given version and activates it right away, returns
+    // the id of the tree.
+    pub async fn load_definition(&self,
+                                 json: &str,
+                                 version: u32) -> Result<i32, EngineError> {
+        let document = BehaviorTreeDocument::from_json(json)?;
+        let tree_id = *document.get_id();
+
+        self.insert_definition(document.into(), version)?;
+        self.activate(&tree_id, &version).await?;
+
+        Result::Ok(tree_id)
+    }
+
+    // Builds the definition without inserting it, subtrees it refers to have to be loaded.
+    pub fn validate_definition(&self,
+                               json: &str) -> Result<i32, EngineError> {
+        let definition: BehaviorTreeDefinition = BehaviorTreeDocument::from_json(json)?.into();
+        self.building_service.build_definition(&definition)?;
+
+        Result::Ok(*definition.get_id())
+    }
+
+    // Builds the definition in a sandbox of its own, which is dropped right after the
+    // payload is evaluated, so nothing is persisted and no other tree is visible to it.
+    pub async fn evaluate_adhoc(json: &str,
+                                payload: &ValuesPayload,
+                                limits: &DefinitionLimits,
+                                budget: &EvaluationBudget) -> Result<AdhocEvaluation, EngineError> {
+        ButtercupEngine::evaluate_sandboxed(json, payload, limits, budget, Option::None).await
+    }
+
+    // Same as evaluate_adhoc, but returns the path the evaluation took as well, to find
+    // out why the tree ended up where it did.
+    pub async fn explain_adhoc(json: &str,
+                               payload: &ValuesPayload,
+                               limits: &DefinitionLimits,
+                               budget: &EvaluationBudget) -> Result<AdhocEvaluation, EngineError> {
+        let debug_recorder = Arc::new(DebugRecorder::default());
+
+        ButtercupEngine::evaluate_sandboxed(json, payload, limits, budget, Option::Some(debug_recorder)).await
+    }
+
+    async fn evaluate_sandboxed(json: &str,
+                                payload: &ValuesPayload,
+                                limits: &DefinitionLimits,
+                                budget: &EvaluationBudget,
+                                debug_recorder: Option<Arc<DebugRecorder>>) -> Result<AdhocEvaluation, EngineError> {
+        let document = BehaviorTreeDocument::from_json(json)?;
+        let complexity_report = document.get_complexity_report().check(limits);
+
+        if !complexity_report.get_violations().is_empty() {
+            return Result::Err(EngineError::TooComplex(complexity_report));
+        }
+
+        let sandbox = ButtercupEngine::default().with_evaluation_budget(budget.clone());
+        let tree_id = *document.get_id();
+
+        sandbox.insert_definition(document.into(), 1)?;
+        sandbox.activate(&tree_id, &1).await?;
+        sandbox.check_number_digits(payload)?;
+
+        let tree = sandbox.get_tree(&tree_id)?;
+        let (result, trace, usage) = tree.evaluate_traced(payload, debug_recorder.clone()).await;
+
+        Result::Ok(AdhocEvaluation {
+            result,
+            trace: trace.get_timings(),
+            hits: tree.get_hit_counters().get_snapshot(),
+            usage,
+            path: debug_recorder.map(|debug_recorder| EvaluationPath::new(&debug_recorder.get_events())),
+            guard_reports: trace.get_guard_reports()
+        })
+    }
+
+    // Runs the fixtures of the definition against every mutant of its conditions, a
+    // mutant which fails to build counts as killed.
+    pub async fn find_surviving_mutants(&self,
+                                        json: &str) -> Result<Vec<SurvivingMutant>, EngineError> {
+        let mutants = BehaviorTreeDocument::from_json(json)?.get_condition_mutants();
+        let mut surviving_mutants = Vec::new();
+
+        for (node_id, mutant) in mutants {
+            let (description, expression) = mutant.unpack();
+            let definition: BehaviorTreeDefinition = BehaviorTreeDocument::from_json(json)?
+                .with_condition_expression(&node_id, expression)
+                .into();
+
+            if let Ok(tree) = self.building_service.build_definition(&definition) {
+                let mismatches =
+                    BehaviorTreeBuildingService::check_fixtures(&tree, definition.get_fixtures()).await;
+
+                if mismatches.is_empty() {
+                    surviving_mutants.push(SurvivingMutant::new(node_id, description));
+                }
+            }
+        }
+
+        Result::Ok(surviving_mutants)
+    }
+
+    pub async fn activate(&self,
+                          tree_id: &i32,
+                          version: &u32) -> Result<(), EngineError> {
+        self.building_service.activate(tree_id, version).await?;
+
+        Result::Ok(())
+    }
+
+    pub async fn self_test(&self,
+                           tree_id: &i32) -> Result<Vec<FixtureMismatch>, EngineError> {
+        Result::Ok(self.building_service.self_test(tree_id).await?)
+    }
+
+    pub fn get_active_version(&self,
+                              tree_id: &i32) -> Option<u32> {
+        self.definition_service.get_active_version(tree_id)
+    }
+
+    pub async fn evaluate(&self,
+                          tree_id: &i32,
+                          payload: &ValuesPayload) -> Result<TickStatus, EngineError> {
+        self.check_number_digits(payload)?;
+        Result::Ok(self.get_tree(tree_id)?.evaluate(payload).await?)
+    }
+
+    // Same as evaluate, but a failed evaluation is repeated with the fallback tree of the
+    // definition, if it has one. Fallbacks of fallback trees are not followed, and the
+    // error of the tree is returned if its fallback is no longer there.
+    pub async fn evaluate_with_fallback(&self,
+                                        tree_id: &i32,
+                                        payload: &ValuesPayload) -> Result<FallbackEvaluation, EngineError> {
+        self.check_number_digits(payload)?;
+
+        let tree = self.get_tree(tree_id)?;
+        let err = match tree.evaluate(payload).await {
+            Ok(result) => return Result::Ok(FallbackEvaluation {
+                tree_id: *tree_id,
+                result,
+                fallback_reason: Option::None
+            }),
+            Err(err) => err
+        };
+
+        let fallback_tree_id = match tree.get_fallback_tree_id() {
+            None => return Result::Err(EngineError::TickError(err)),
+            Some(fallback_tree_id) => *fallback_tree_id
+        };
+        let fallback_tree = match self.tree_service.get_by_id(&fallback_tree_id) {
+            None => return Result::Err(EngineError::TickError(err)),
+            Some(fallback_tree) => fallback_tree
+        };
+
+        *self.fallback_counts.entry(*tree_id).or_insert(0) += 1;
+
+        Result::Ok(FallbackEvaluation {
+            tree_id: fallback_tree_id,
+            result: fallback_tree.evaluate(payload).await?,
+            fallback_reason: Option::Some(err)
+        })
+    }
+
+    // Number of evaluations handed over to the fallback tree, by the id of the tree which
+    // failed.
+    pub fn get_fallback_counts(&self) -> HashMap<i32, u64> {
+        self.fallback_counts
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect()
+    }
+
+    // Same as evaluate, but returns the work the evaluation did as well.
+    pub async fn evaluate_metered(&self,
+                                  tree_id: &i32,
+                                  payload: &ValuesPayload) -> Result<(TickStatus, EvaluationUsage), EngineError> {
+        self.check_number_digits(payload)?;
+
+        let (result, usage) = self.get_tree(tree_id)?.evaluate_metered(payload).await;
+
+        Result::Ok((result?, usage))
+    }
+
+    // Evaluates the payload of the subject with every one of the trees, then arbitrates
+    // between the commands they selected. The decisions are recorded in the trace of the
+    // tree which selected the command, trees which failed take part with the commands they
+    // selected before failing.
+    pub async fn evaluate_arbitrated(&self,
+                                     subject: &str,
+                                     tree_ids: &[i32],
+                                     payload: &ValuesPayload) -> Result<Vec<ArbitratedEvaluation>, EngineError> {
+        self.check_number_digits(payload)?;
+
+        let mut evaluations = Vec::new();
+
+        for tree_id in tree_ids {
+            let tree = self.get_tree(tree_id)?;
+            let trace = Arc::new(TickTrace::default());
+            let result = tree.evaluate_into_trace(payload, trace.clone()).await;
+
+            evaluations.push((*tree_id, result, trace));
+        }
+
+        let selections = evaluations.iter()
+            .flat_map(|(_, _, trace)| trace.get_command_selections())
+            .collect();
+
+        for decision in self.command_arbiter.arbitrate(subject, selections) {
+            let selecting = evaluations.iter().find(|(tree_id, _, _)| tree_id == decision.get_selection().get_tree_id());
+
+            if let Some((_, _, trace)) = selecting {
+                trace.record_arbitration_decision(decision);
+            }
+        }
+
+        Result::Ok(evaluations.into_iter()
+            .map(|(tree_id, result, trace)| ArbitratedEvaluation {
+                tree_id,
+                result,
+                trace: trace.get_timings(),
+                decisions: trace.get_arbitration_decisions()
+            })
+            .collect())
+    }
+
+    pub fn put_values(&self,
+                      tree_id: &i32,
+                      payload: &ValuesPayload) -> Result<(), EngineError> {
+        self.check_number_digits(payload)?;
+        Result::Ok(self.get_context(tree_id)?.put_values(payload)?)
+    }
+
+    pub async fn tick(&self,
+                      tree_id: &i32) -> Result<TickStatus, EngineError> {
+        let tree = self.get_tree(tree_id)?;
+        let context = self.get_context(tree_id)?;
+
+        Result::Ok(tree.tick(Uuid::new_v4(), &context).await?)
+    }
+
+    pub fn get_hit_counts(&self,
+                          tree_id: &i32) -> Result<HitCountsSnapshot, EngineError> {
+        Result::Ok(self.get_tree(tree_id)?.get_hit_counters().get_snapshot())
+    }
+
+    pub fn get_footprint(&self,
+                         tree_id: &i32) -> Result<TreeFootprint, EngineError> {
+        Result::Ok(self.get_tree(tree_id)?.get_footprint())
+    }
+
+    // Destroys the context of the tree, the next tick starts with an empty blackboard.
+    pub fn reset(&self,
+                 tree_id: &i32) -> Result<(), EngineError> {
+        match self.contexts.remove(tree_id) {
+            None => Result::Ok(()),
+            Some((_, (blackboard_id, context))) => {
+                drop(context);
+                Result::Ok(LocalBlackboard::destroy(ButtercupEngine::get_path(&blackboard_id))?)
+            }
+        }
+    }
+
+    fn check_number_digits(&self,
+                           payload: &ValuesPayload) -> Result<(), EngineError> {
+        match payload.find_number_longer_than(self.max_number_digits) {
+            None => Result::Ok(()),
+            Some(name) => Result::Err(EngineError::NumberTooLong(name.clone(), self.max_number_digits))
+        }
+    }
+
+    fn get_tree(&self,
+                tree_id: &i32) -> Result<Arc<BehaviorTree>, EngineError> {
+        self.tree_service
+            .get_by_id(tree_id)
+            .ok_or(EngineError::TreeOfGivenIdNotFound(*tree_id))
+    }
+
+    fn get_context(&self,
+                   tree_id: &i32) -> Result<Arc<BTNodeExecutionContext>, EngineError> {
+        self.get_tree(tree_id)?;
+
+        match self.contexts.entry(*tree_id) {
+            Entry::Occupied(entry) => Result::Ok(entry.get().1.clone()),
+            Entry::Vacant(entry) => {
+                let blackboard_id = Uuid::new_v4();
+                let context = BTNodeExecutionContext::new(
+                    Arc::new(LocalBlackboard::new(ButtercupEngine::get_path(&blackboard_id))?),
+                    Arc::new(Default::default()))
+                    .with_calendars(self.calendars.clone())
+                    .with_counter_store(self.counter_store.clone())
+                    .with_fingerprint_store(self.fingerprint_store.clone());
+                #[cfg(feature = "fault-injection")]
+                let context = match &self.fault_injector {
+                    None => context,
+                    Some(fault_injector) => context.with_fault_injector(fault_injector.clone())
+                };
+                let context = Arc::new(context);
+
+                entry.insert((blackboard_id, context.clone()));
+
+                Result::Ok(context)
+            }
+        }
+    }
+
+    fn get_path(blackboard_id: &Uuid) -> OsString {
+        LocalBlackboard::get_temporary_path(blackboard_id)
+    }
+
+}
+
+impl Drop for ButtercupEngine {
+    fn drop(&mut self) {
+        let tree_ids: Vec<i32> = self.contexts
+            .iter()
+            .map(|entry| *entry.key())
+            .collect();
+
+        for tree_id in tree_ids {
+            let _ = self.reset(&tree_id);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Mutex;
+
+use futures::channel::mpsc::{self, Receiver, Sender};
+use serde::{Deserialize, Serialize};
+
+use crate::document::DefinitionStatus;
+
+const MAX_PENDING_EVENTS: usize = 256;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum DefinitionEvent {
+
+    Created { tree_id: i32, version: u32 },
+    Updated { tree_id: i32, version: u32 },
+    Activated { tree_id: i32, version: u32 },
+    RolledBack { tree_id: i32, version: u32 },
+    SelfTestFailed { tree_id: i32, version: u32, failed_fixtures: usize },
+    StatusChanged { tree_id: i32, status: DefinitionStatus }
+
+}
+
+impl DefinitionEvent {
+
+    pub fn get_tree_id(&self) -> &i32 {
+        match self {
+            DefinitionEvent::Created { tree_id, .. }
+            | DefinitionEvent::Updated { tree_id, .. }
+            | DefinitionEvent::Activated { tree_id, .. }
+            | DefinitionEvent::RolledBack { tree_id, .. }
+            | DefinitionEvent::SelfTestFailed { tree_id, .. }
+            | DefinitionEvent::StatusChanged { tree_id, .. } => tree_id
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            DefinitionEvent::Created { .. } => "created",
+            DefinitionEvent::Updated { .. } => "updated",
+            DefinitionEvent::Activated { .. } => "activated",
+            DefinitionEvent::RolledBack { .. } => "rolled_back",
+            DefinitionEvent::SelfTestFailed { .. } => "self_test_failed",
+            DefinitionEvent::StatusChanged { .. } => "status_changed"
+        }
+    }
+
+}
+
+// Notifies subscribers about changes of definitions, so that compiled trees can be
+// invalidated without polling. Subscribers which fall behind are disconnected rather
+// than skipped, they have to reconnect and read the definitions they care about again.
+#[derive(Default)]
+pub struct DefinitionEventService {
+
+    subscribers: Mutex<Vec<Sender<DefinitionEvent>>>
+
+}
+
+impl DefinitionEventService {
+
+    pub fn subscribe(&self) -> Receiver<DefinitionEvent> {
+        let (sender, receiver) = mpsc::channel(MAX_PENDING_EVENTS);
+
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    pub fn publish(&self,
+                   event: DefinitionEvent) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain_mut(|subscriber| subscriber.try_send(event.clone()).is_ok());
+    }
+
+    pub fn get_subscribers_count(&self) -> usize {
+        self.subscribers.lock().unwrap().len()
+    }
+
+}
@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::bts::{BehaviorTreeBuildingError, BehaviorTreeBuildingService};
+use crate::document::{BehaviorTreeDocument, DefinitionDocumentService, DefinitionDocumentServiceError};
+
+const DEFINITION_EXTENSION: &str = "json";
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum DefinitionFileError {
+
+    BehaviorTreeBuildingError(BehaviorTreeBuildingError),
+    DefinitionDocumentServiceError(DefinitionDocumentServiceError),
+    FileRemoved(i32),
+    ReadError(String)
+
+}
+
+// Keeps the trees defined by the json documents of a directory, one tree per file, in
+// sync with the files. Every reload puts and activates a new version of each tree whose
+// file changed since the previous reload, the active tree is swapped only once the new
+// version is built, so a broken file leaves the tree it defines as it was and is retried
+// on the next reload. The tree of a removed file is disabled until its file is back.
+pub struct DefinitionFileLoader {
+
+    directory: PathBuf,
+    document_service: Arc<DefinitionDocumentService>,
+    building_service: Arc<BehaviorTreeBuildingService>,
+    loaded_files: Mutex<HashMap<PathBuf, (SystemTime, i32)>>,
+    disabled_tree_ids: Mutex<HashSet<i32>>
+
+}
+
+impl DefinitionFileLoader {
+
+    pub fn new(directory: &Path,
+               document_service: Arc<DefinitionDocumentService>,
+               building_service: Arc<BehaviorTreeBuildingService>) -> DefinitionFileLoader {
+        DefinitionFileLoader {
+            directory: directory.to_owned(),
+            document_service,
+            building_service,
+            loaded_files: Mutex::new(HashMap::new()),
+            disabled_tree_ids: Mutex::new(HashSet::new())
+        }
+    }
+
+    // Returns the activated version, or the error, of every tree whose file changed or
+    // was removed.
+    pub async fn reload(&self) -> io::Result<Vec<(PathBuf, Result<u32, DefinitionFileError>)>> {
+        let files = self.get_files()?;
+        let mut results = Vec::new();
+
+        for (path, modified_at) in self.get_changed_files(&files) {
+            let result = self.load(&path).await;
+
+            if let Ok((tree_id, _)) = result {
+                self.loaded_files.lock().unwrap().insert(path.clone(), (modified_at, tree_id));
+
+                // Trees loaded from files have a document, so they are always found.
+                if self.disabled_tree_ids.lock().unwrap().remove(&tree_id) {
+                    let _ = self.document_service.set_disabled(&tree_id, false);
+                }
+            }
+
+            results.push((path, result.map(|(_, version)| version)));
+        }
+
+        for (path, tree_id) in self.take_removed_files(&files) {
+            let _ = self.document_service.set_disabled(&tree_id, true);
+            self.disabled_tree_ids.lock().unwrap().insert(tree_id);
+            results.push((path, Result::Err(DefinitionFileError::FileRemoved(tree_id))));
+        }
+
+        Result::Ok(results)
+    }
+
+    fn get_files(&self) -> io::Result<HashMap<PathBuf, SystemTime>> {
+        let mut files = HashMap::new();
+
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Option::Some(DEFINITION_EXTENSION) {
+                continue;
+            }
+
+            // Files which are being written can disappear in the meantime.
+            if let Ok(modified_at) = fs::metadata(&path).and_then(|metadata| metadata.modified()) {
+                files.insert(path, modified_at);
+            }
+        }
+
+        Result::Ok(files)
+    }
+
+    fn get_changed_files(&self,
+                         files: &HashMap<PathBuf, SystemTime>) -> Vec<(PathBuf, SystemTime)> {
+        let loaded_files = self.loaded_files.lock().unwrap();
+        let mut changed_files: Vec<(PathBuf, SystemTime)> = files
+            .iter()
+            .filter(|(path, modified_at)|
+                loaded_files.get(*path).map(|(loaded_at, _)| loaded_at) != Option::Some(*modified_at))
+            .map(|(path, modified_at)| (path.clone(), *modified_at))
+            .collect();
+
+        changed_files.sort();
+        changed_files
+    }
+
+    fn take_removed_files(&self,
+                          files: &HashMap<PathBuf, SystemTime>) -> Vec<(PathBuf, i32)> {
+        let mut loaded_files = self.loaded_files.lock().unwrap();
+        let mut removed_files: Vec<(PathBuf, i32)> = loaded_files
+            .iter()
+            .filter(|(path, _)| !files.contains_key(*path))
+            .map(|(path, (_, tree_id))| (path.clone(), *tree_id))
+            .collect();
+
+        for (path, _) in &removed_files {
+            loaded_files.remove(path);
+        }
+
+        removed_files.sort();
+        removed_files
+    }
+
+    async fn load(&self,
+                  path: &Path) -> Result<(i32, u32), DefinitionFileError> {
+        let json = fs::read_to_string(path)
+            .map_err(|err| DefinitionFileError::ReadError(err.to_string()))?;
+        let tree_id = *BehaviorTreeDocument::from_json(&json)
+            .map_err(|err| DefinitionFileError::DefinitionDocumentServiceError(
+                DefinitionDocumentServiceError::DefinitionDocumentError(err)))?
+            .get_id();
+
+        let version = self.document_service
+            .put(&tree_id, &json, Option::None)
+            .map_err(DefinitionFileError::DefinitionDocumentServiceError)?;
+
+        self.building_service
+            .activate(&tree_id, &version)
+            .await
+            .map_err(DefinitionFileError::BehaviorTreeBuildingError)?;
+
+        Result::Ok((tree_id, version))
+    }
+
+}
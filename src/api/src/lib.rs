@@ -1,2 +1,16 @@
 
+pub mod bpmn;
+pub mod btcpp;
 pub mod bts;
+pub mod complexity;
+pub mod debug;
+pub mod dsl;
+pub mod document;
+pub mod engine;
+pub mod events;
+pub mod files;
+pub mod mutation;
+pub mod naming;
+pub mod table;
+pub mod usage;
+pub mod xml;
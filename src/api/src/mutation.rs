@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+// A mutant of a condition which none of the fixtures of the tree tells apart from the
+// original, the fixtures should get a payload for which the two evaluate differently.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct SurvivingMutant {
+
+    node_id: i32,
+    description: String
+
+}
+
+impl SurvivingMutant {
+
+    pub fn new(node_id: i32,
+               description: String) -> SurvivingMutant {
+        SurvivingMutant {
+            node_id,
+            description
+        }
+    }
+
+    pub fn get_node_id(&self) -> &i32 {
+        &self.node_id
+    }
+
+    pub fn get_description(&self) -> &String {
+        &self.description
+    }
+
+}
@@ -0,0 +1,121 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+use serde_json::Value;
+
+use crate::document::DefinitionDocumentError;
+
+// Lets documents name their nodes instead of numbering them by hand:
+//
+//     "root": { "type": "OneOff", "id": 1, "child_id": "greet" },
+//     "nodes": [{ "type": "PrintLog", "id": "greet", "message": "hello" }]
+//
+// Named nodes are given the ids following the highest numeric id in the document, in the
+// order they appear, so numeric documents are left as they are and both can be mixed.
+// Every id, numeric or named, can only be used once.
+pub fn resolve_node_names(document: &mut Value) -> Result<(), DefinitionDocumentError> {
+    let mut ids = HashSet::new();
+    let mut names = Vec::new();
+
+    for node in nodes_of(document) {
+        match node.get("id") {
+            Some(Value::Number(id)) => if let Some(id) = id.as_i64() {
+                let id = i32::try_from(id)
+                    .map_err(|_| DefinitionDocumentError::NodeIdOutOfRange(id))?;
+
+                if !ids.insert(id) {
+                    return Result::Err(DefinitionDocumentError::DuplicateNodeId(id));
+                }
+            },
+            Some(Value::String(name)) => {
+                if names.contains(name) {
+                    return Result::Err(DefinitionDocumentError::DuplicateNodeName(name.clone()));
+                }
+                names.push(name.clone());
+            },
+            _ => {}
+        }
+    }
+
+    if names.is_empty() {
+        return Result::Ok(());
+    }
+
+    let max_id = ids.iter().max().copied().unwrap_or(0);
+    let mut resolved: HashMap<String, i32> = HashMap::new();
+
+    for (index, name) in names.into_iter().enumerate() {
+        let id = i32::try_from(index)
+            .ok()
+            .and_then(|index| max_id.checked_add(1)?.checked_add(index))
+            .ok_or_else(|| DefinitionDocumentError::NodeIdOutOfRange(i64::from(max_id) + 1 + index as i64))?;
+
+        resolved.insert(name, id);
+    }
+
+    for node in nodes_of_mut(document) {
+        resolve(node.get_mut("id"), &resolved)?;
+        resolve(node.get_mut("child_id"), &resolved)?;
+        resolve(node.get_mut("escalation_id"), &resolved)?;
+
+        if let Some(Value::Array(children_ids)) = node.get_mut("children_ids") {
+            for child_id in children_ids {
+                resolve(Option::Some(child_id), &resolved)?;
+            }
+        }
+
+        // Steps are pairs of a step and its optional compensation.
+        if let Some(Value::Array(steps)) = node.get_mut("steps") {
+            for step in steps {
+                if let Value::Array(step) = step {
+                    for step_id in step {
+                        resolve(Option::Some(step_id), &resolved)?;
+                    }
+                }
+            }
+        }
+
+        // Scored children are pairs of a score and a child.
+        if let Some(Value::Array(scored_children)) = node.get_mut("scored_children") {
+            for scored_child in scored_children {
+                resolve(scored_child.get_mut(1), &resolved)?;
+            }
+        }
+    }
+
+    Result::Ok(())
+}
+
+fn resolve(id: Option<&mut Value>,
+           resolved: &HashMap<String, i32>) -> Result<(), DefinitionDocumentError> {
+    if let Some(id) = id {
+        if let Value::String(name) = id {
+            match resolved.get(name) {
+                Some(resolved_id) => *id = Value::from(*resolved_id),
+                None => return Result::Err(DefinitionDocumentError::UnknownNodeName(name.clone()))
+            }
+        }
+    }
+    Result::Ok(())
+}
+
+fn nodes_of(document: &Value) -> Vec<&Value> {
+    let mut nodes: Vec<&Value> = document.get("root").into_iter().collect();
+    if let Some(Value::Array(others)) = document.get("nodes") {
+        nodes.extend(others);
+    }
+    nodes
+}
+
+fn nodes_of_mut(document: &mut Value) -> Vec<&mut Value> {
+    match document {
+        Value::Object(document) => document.iter_mut()
+            .flat_map(|(key, value)| match (key.as_str(), value) {
+                ("root", root) => vec![root],
+                ("nodes", Value::Array(nodes)) => nodes.iter_mut().collect(),
+                _ => Vec::new()
+            })
+            .collect(),
+        _ => Vec::new()
+    }
+}
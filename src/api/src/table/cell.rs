@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use buttercup_conditions::{RelationalExpression, RelationalExpressionSpecification};
+use buttercup_conditions::relational::{EqualsRelationalExpression, GreaterThanOrEqualsRelationalExpression, GreaterThanRelationalExpression, IsInRelationalExpression, LessThanOrEqualsRelationalExpression, LessThanRelationalExpression, NotEqualsRelationalExpression};
+use buttercup_values::{ValueHolder, ValueType};
+use buttercup_values::extractors::{ValueExtractionPolicy, ValueExtractorInput, ValueExtractorService};
+use buttercup_values::lists::ValueHoldersList;
+
+const ANY_VALUE: &str = "-";
+const RANGE_SEPARATOR: &str = "..";
+
+// Cells take a subset of the unary tests of FEEL:
+//  - an operator followed by a literal, a literal alone is compared for equality,
+//  - ranges, such as [1..10), where square brackets include the endpoint,
+//  - lists of literals, which match any of them,
+//  - not(...) of a comparison or of a list of literals.
+// Empty cells and '-' match any value. Every test of a cell has to hold.
+pub fn parse_cell(name: &str,
+                  text: &str) -> Result<Vec<RelationalExpression>, String> {
+    let text = text.trim();
+    if text.is_empty() || text == ANY_VALUE {
+        return Result::Ok(Vec::new());
+    }
+
+    if let Some(negated) = text.strip_prefix("not(").and_then(|text| text.strip_suffix(')')) {
+        return parse_negation(name, negated);
+    }
+
+    let tests = split_list(text);
+    if tests.len() > 1 {
+        let literals = tests.iter()
+            .map(|test| parse_literal(test))
+            .collect::<Result<Vec<ValueHolder>, String>>()?;
+
+        return Result::Ok(vec![RelationalExpression::IsIn(IsInRelationalExpression::new(
+            RelationalExpressionSpecification::NameAndLiteral(name.to_owned(), to_list(literals)?)))]);
+    }
+
+    match parse_range(name, text)? {
+        Some(range) => Result::Ok(range),
+        None => Result::Ok(vec![parse_comparison(name, text)?])
+    }
+}
+
+fn parse_negation(name: &str,
+                  text: &str) -> Result<Vec<RelationalExpression>, String> {
+    let tests = split_list(text);
+    if tests.len() > 1 {
+        return tests.iter()
+            .map(|test| parse_literal(test).map(|literal| RelationalExpression::NotEquals(
+                NotEqualsRelationalExpression::new(RelationalExpressionSpecification::NameAndLiteral(name.to_owned(), literal)))))
+            .collect();
+    }
+
+    if parse_range(name, text)?.is_some() {
+        return Result::Err(format!("unsupported negation of {}", text));
+    }
+
+    let negated = match parse_comparison(name, text)? {
+        RelationalExpression::Equals(expr) =>
+            RelationalExpression::NotEquals(NotEqualsRelationalExpression::new(expr.get_specification().clone())),
+        RelationalExpression::NotEquals(expr) =>
+            RelationalExpression::Equals(EqualsRelationalExpression::new(expr.get_specification().clone())),
+        RelationalExpression::LessThan(expr) =>
+            RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(expr.get_specification().clone())),
+        RelationalExpression::LessThanOrEquals(expr) =>
+            RelationalExpression::GreaterThan(GreaterThanRelationalExpression::new(expr.get_specification().clone())),
+        RelationalExpression::GreaterThan(expr) =>
+            RelationalExpression::LessThanOrEquals(LessThanOrEqualsRelationalExpression::new(expr.get_specification().clone())),
+        RelationalExpression::GreaterThanOrEquals(expr) =>
+            RelationalExpression::LessThan(LessThanRelationalExpression::new(expr.get_specification().clone())),
+        _ => return Result::Err(format!("unsupported negation of {}", text))
+    };
+
+    Result::Ok(vec![negated])
+}
+
+// Ranges start with [ when they include their start, with ( or ] otherwise. They end with
+// ] when they include their end, with ) or [ otherwise.
+fn parse_range(name: &str,
+               text: &str) -> Result<Option<Vec<RelationalExpression>>, String> {
+    let (lower_inclusive, text) = match text.chars().next() {
+        Some('[') => (true, &text[1..]),
+        Some('(') | Some(']') => (false, &text[1..]),
+        _ => return Result::Ok(Option::None)
+    };
+    let (upper_inclusive, text) = match text.chars().last() {
+        Some(']') => (true, &text[..text.len() - 1]),
+        Some(')') | Some('[') => (false, &text[..text.len() - 1]),
+        _ => return Result::Err(format!("unterminated range {}", text))
+    };
+    let (lower, upper) = text.split_once(RANGE_SEPARATOR)
+        .ok_or_else(|| format!("missing {} in range", RANGE_SEPARATOR))?;
+    let lower = RelationalExpressionSpecification::NameAndLiteral(name.to_owned(), parse_literal(lower)?);
+    let upper = RelationalExpressionSpecification::NameAndLiteral(name.to_owned(), parse_literal(upper)?);
+
+    Result::Ok(Option::Some(vec![
+        if lower_inclusive {
+            RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(lower))
+        } else {
+            RelationalExpression::GreaterThan(GreaterThanRelationalExpression::new(lower))
+        },
+        if upper_inclusive {
+            RelationalExpression::LessThanOrEquals(LessThanOrEqualsRelationalExpression::new(upper))
+        } else {
+            RelationalExpression::LessThan(LessThanRelationalExpression::new(upper))
+        }
+    ]))
+}
+
+fn parse_comparison(name: &str,
+                    text: &str) -> Result<RelationalExpression, String> {
+    let (operator, literal) = ["<=", ">=", "!=", "<", ">", "="]
+        .iter()
+        .find(|operator| text.starts_with(*operator))
+        .map(|operator| (*operator, &text[operator.len()..]))
+        .unwrap_or(("=", text));
+    let specification = RelationalExpressionSpecification::NameAndLiteral(name.to_owned(), parse_literal(literal)?);
+
+    Result::Ok(match operator {
+        "<=" => RelationalExpression::LessThanOrEquals(LessThanOrEqualsRelationalExpression::new(specification)),
+        ">=" => RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(specification)),
+        "!=" => RelationalExpression::NotEquals(NotEqualsRelationalExpression::new(specification)),
+        "<" => RelationalExpression::LessThan(LessThanRelationalExpression::new(specification)),
+        ">" => RelationalExpression::GreaterThan(GreaterThanRelationalExpression::new(specification)),
+        _ => RelationalExpression::Equals(EqualsRelationalExpression::new(specification))
+    })
+}
+
+// Splits on the commas which are neither quoted nor inside of a range.
+fn split_list(text: &str) -> Vec<&str> {
+    let mut tests = Vec::new();
+    let mut quoted = false;
+    let mut in_range = false;
+    let mut start = 0;
+
+    for (index, char) in text.char_indices() {
+        match char {
+            '"' => quoted = !quoted,
+            '[' | '(' | ']' if !quoted && !in_range => in_range = true,
+            ']' | ')' | '[' if !quoted => in_range = false,
+            ',' if !quoted && !in_range => {
+                tests.push(text[start..index].trim());
+                start = index + 1;
+            },
+            _ => {}
+        }
+    }
+
+    tests.push(text[start..].trim());
+    tests
+}
+
+fn to_list(literals: Vec<ValueHolder>) -> Result<ValueHolder, String> {
+    let value_type = ValueType::all_value_types()
+        .iter()
+        .find(|value_type| literals.first().is_some_and(|literal| value_type.matches(literal)))
+        .cloned()
+        .ok_or_else(|| "empty list".to_owned())?;
+
+    ValueHoldersList::new(literals, value_type)
+        .map(|list| ValueHolder::List(Arc::new(list)))
+        .map_err(|_| "list of literals of different types".to_owned())
+}
+
+// Literals are read as json, so strings may be quoted to keep their spaces or to be taken
+// as text when they look like numbers. Bare words are taken as strings.
+pub fn parse_literal(text: &str) -> Result<ValueHolder, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Result::Err("missing literal".to_owned());
+    }
+
+    match serde_json::from_str::<Value>(text) {
+        Ok(Value::Bool(value)) => Result::Ok(ValueHolder::Boolean(value)),
+        Ok(Value::String(value)) => Result::Ok(value.into()),
+        Ok(value @ Value::Number(_)) => {
+            let value_type = if value.is_f64() { ValueType::Decimal } else { ValueType::Integer };
+
+            ValueExtractorService::extract(&ValueExtractorInput::new(&value, &value_type, &ValueExtractionPolicy::Strict))
+                .map_err(|err| format!("{:?}", err))
+        },
+        Ok(value) => Result::Err(format!("unsupported literal {}", value)),
+        Err(_) => Result::Ok(text.into())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::table::{DecisionRule, DecisionTable, DecisionTableError, HitPolicy};
+use crate::table::cell::{parse_cell, parse_literal};
+
+impl DecisionTable {
+
+    // The header names the inputs, with the output in its last column. Every other line is
+    // a rule, with a cell for every input followed by the output literal. Errors point at
+    // lines and columns of the csv, counted from zero.
+    pub fn from_csv(csv: &str,
+                    hit_policy: HitPolicy) -> Result<DecisionTable, DecisionTableError> {
+        let mut rows = split_rows(csv)?.into_iter();
+        let (_, mut header) = rows.next().ok_or(DecisionTableError::MissingHeader)?;
+        let output = header.pop().filter(|output| !output.is_empty()).ok_or(DecisionTableError::MissingHeader)?;
+        let mut table = DecisionTable::new(header, output).with_hit_policy(hit_policy);
+
+        for (row, mut fields) in rows {
+            if fields.len() != table.inputs.len() + 1 {
+                return Result::Err(DecisionTableError::CellsCountMismatch(row, fields.len()));
+            }
+
+            let output = fields.pop().unwrap_or_default();
+            let output = parse_literal(&output)
+                .map_err(|err| DecisionTableError::InvalidCell(row, table.inputs.len(), err))?;
+            let cells = table.inputs
+                .iter()
+                .zip(&fields)
+                .enumerate()
+                .map(|(column, (name, text))| parse_cell(name, text)
+                    .map_err(|err| DecisionTableError::InvalidCell(row, column, err)))
+                .collect::<Result<Vec<_>, DecisionTableError>>()?;
+
+            table = table.with_rule(DecisionRule::new(cells, output));
+        }
+
+        Result::Ok(table)
+    }
+
+}
+
+// Splits lines into fields, skipping the empty ones. Fields may be quoted to hold commas
+// and line breaks, with quotes escaped by doubling them.
+fn split_rows(csv: &str) -> Result<Vec<(usize, Vec<String>)>, DecisionTableError> {
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 0;
+    let mut row_line = 0;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {},
+            '\n' if !quoted => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|field| !field.trim().is_empty()) {
+                    rows.push((row_line, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                row_line = line;
+            },
+            '\n' => {
+                field.push(char);
+                line += 1;
+            },
+            _ => field.push(char)
+        }
+    }
+
+    if quoted {
+        return Result::Err(DecisionTableError::UnterminatedQuote(row_line));
+    }
+
+    fields.push(field);
+    if fields.iter().any(|field| !field.trim().is_empty()) {
+        rows.push((row_line, fields));
+    }
+
+    Result::Ok(rows.into_iter()
+        .map(|(line, fields)| (line, fields.into_iter().map(|field| field.trim().to_owned()).collect()))
+        .collect())
+}
@@ -0,0 +1,510 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use buttercup_conditions::{ConditionExpression, LogicalExpression, RelationalExpression, RelationalExpressionSpecification};
+use buttercup_values::{ValueHolder, ValueType};
+use buttercup_values::extractors::{ValueExtractionPolicy, ValueExtractorInput, ValueExtractorService};
+
+use crate::document::{BehaviorTreeDocument, NodeDefinitionDocument, RootDefinitionDocument};
+
+pub mod cell;
+pub mod csv;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum DecisionTableError {
+
+    CellOutOfColumn(usize, String),
+    CellsCountMismatch(usize, usize),
+    InputTypeMismatch(usize, String),
+    InvalidCell(usize, usize, String),
+    MissingHeader,
+    OutputsMismatch(i32, String),
+    OverlappingRules(usize, usize),
+    UnterminatedQuote(usize),
+    UnexpectedNode(i32),
+    UnsupportedCondition(i32)
+
+}
+
+// A single row, with a cell of comparisons for every input of the table. The comparisons
+// of a cell all have to hold, an empty cell matches any value.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DecisionRule {
+
+    cells: Vec<Vec<RelationalExpression>>,
+    output: ValueHolder
+
+}
+
+impl DecisionRule {
+
+    pub fn new(cells: Vec<Vec<RelationalExpression>>,
+               output: ValueHolder) -> DecisionRule {
+        DecisionRule {
+            cells,
+            output
+        }
+    }
+
+    pub fn get_cells(&self) -> &Vec<Vec<RelationalExpression>> {
+        &self.cells
+    }
+
+    pub fn get_output(&self) -> &ValueHolder {
+        &self.output
+    }
+
+    fn get_condition(&self) -> ConditionExpression {
+        let mut relations: Vec<ConditionExpression> = self.cells
+            .iter()
+            .flatten()
+            .cloned()
+            .map(ConditionExpression::RelationExpression)
+            .collect();
+
+        match relations.len() {
+            0 => ConditionExpression::ConstantExpression(true),
+            1 => relations.remove(0),
+            _ => ConditionExpression::LogicalExpression(Box::new(LogicalExpression::And(relations)))
+        }
+    }
+
+}
+
+// First takes the first rule which matches. Unique requires rules which never match the
+// same values, Any allows them as long as their outputs are equal.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub enum HitPolicy {
+
+    #[default]
+    First,
+    Unique,
+    Any
+
+}
+
+// Flat view of a tree which sets a single output value from the first of its rules which
+// matches. As a tree it is a fallback under a one-off root, with a condition over a set
+// value action for every rule.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DecisionTable {
+
+    inputs: Vec<String>,
+    output: String,
+    rules: Vec<DecisionRule>,
+    #[serde(default)]
+    hit_policy: HitPolicy,
+    #[serde(default)]
+    input_types: HashMap<String, ValueType>
+
+}
+
+const ROOT_ID: i32 = 1;
+const FALLBACK_ID: i32 = 2;
+
+impl DecisionTable {
+
+    pub fn new(inputs: Vec<String>,
+               output: String) -> DecisionTable {
+        DecisionTable {
+            inputs,
+            output,
+            rules: Vec::new(),
+            hit_policy: HitPolicy::First,
+            input_types: HashMap::new()
+        }
+    }
+
+    pub fn with_input_type(mut self,
+                           input: String,
+                           value_type: ValueType) -> DecisionTable {
+        self.input_types.insert(input, value_type);
+        self
+    }
+
+    pub fn with_hit_policy(mut self,
+                           hit_policy: HitPolicy) -> DecisionTable {
+        self.hit_policy = hit_policy;
+        self
+    }
+
+    pub fn with_rule(mut self,
+                     rule: DecisionRule) -> DecisionTable {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn get_inputs(&self) -> &Vec<String> {
+        &self.inputs
+    }
+
+    pub fn get_output(&self) -> &String {
+        &self.output
+    }
+
+    pub fn get_rules(&self) -> &Vec<DecisionRule> {
+        &self.rules
+    }
+
+    pub fn get_hit_policy(&self) -> &HitPolicy {
+        &self.hit_policy
+    }
+
+    pub fn get_input_types(&self) -> &HashMap<String, ValueType> {
+        &self.input_types
+    }
+
+    // Inputs are listed in the order in which the rules first compare them. Conditions
+    // have to be comparisons of values with literals, alone or joined with And.
+    pub fn from_document(document: &BehaviorTreeDocument) -> Result<DecisionTable, DecisionTableError> {
+        let nodes: HashMap<i32, &NodeDefinitionDocument> = document.get_nodes()
+            .iter()
+            .filter_map(|node| match node {
+                NodeDefinitionDocument::Condition { id, .. }
+                | NodeDefinitionDocument::Fallback { id, .. }
+                | NodeDefinitionDocument::SetValue { id, .. } => Option::Some((*id, node)),
+                _ => Option::None
+            })
+            .collect();
+        let get_node = |id: &i32| nodes
+            .get(id)
+            .copied()
+            .ok_or(DecisionTableError::UnexpectedNode(*id));
+
+        let children_ids = match document.get_root() {
+            RootDefinitionDocument::OneOff { child_id, .. } => match get_node(child_id)? {
+                NodeDefinitionDocument::Fallback { children_ids, .. } => children_ids,
+                _ => return Result::Err(DecisionTableError::UnexpectedNode(*child_id))
+            },
+            RootDefinitionDocument::Reactive { id, .. }
+            | RootDefinitionDocument::ToFirstError { id, .. }
+            | RootDefinitionDocument::UntilStopped { id, .. } =>
+                return Result::Err(DecisionTableError::UnexpectedNode(*id))
+        };
+
+        let mut output: Option<&String> = Option::None;
+        let mut rows = Vec::new();
+
+        for child_id in children_ids {
+            let (child_id, expression) = match get_node(child_id)? {
+                NodeDefinitionDocument::Condition { child_id, expression, .. } => (child_id, expression),
+                _ => return Result::Err(DecisionTableError::UnexpectedNode(*child_id))
+            };
+            let (value_name, value) = match get_node(child_id)? {
+                NodeDefinitionDocument::SetValue { value_name, value, .. } => (value_name, value),
+                _ => return Result::Err(DecisionTableError::UnexpectedNode(*child_id))
+            };
+
+            match output {
+                Some(output) if output != value_name =>
+                    return Result::Err(DecisionTableError::OutputsMismatch(*child_id, value_name.clone())),
+                _ => output = Option::Some(value_name)
+            }
+
+            rows.push((DecisionTable::get_relations(child_id, expression)?, value.clone()));
+        }
+
+        let mut inputs: Vec<String> = Vec::new();
+        for (relations, _) in &rows {
+            for (name, _) in relations {
+                if !inputs.contains(name) {
+                    inputs.push(name.clone());
+                }
+            }
+        }
+
+        let rules = rows.into_iter()
+            .map(|(relations, value)| {
+                let mut cells = vec![Vec::new(); inputs.len()];
+                for (name, relation) in relations {
+                    if let Some(index) = inputs.iter().position(|input| *input == name) {
+                        cells[index].push(relation);
+                    }
+                }
+                DecisionRule::new(cells, value)
+            })
+            .collect();
+
+        Result::Ok(DecisionTable {
+            inputs,
+            output: output.cloned().unwrap_or_default(),
+            rules,
+            hit_policy: HitPolicy::First,
+            input_types: HashMap::new()
+        })
+    }
+
+    fn get_relations(node_id: &i32,
+                     expression: &ConditionExpression) -> Result<Vec<(String, RelationalExpression)>, DecisionTableError> {
+        let relations = match expression {
+            ConditionExpression::ConstantExpression(true) => Vec::new(),
+            ConditionExpression::RelationExpression(relation) => vec![relation],
+            ConditionExpression::LogicalExpression(expr) => match expr.as_ref() {
+                LogicalExpression::And(expressions) => expressions.iter()
+                    .map(|expr| match expr {
+                        ConditionExpression::RelationExpression(relation) => Result::Ok(relation),
+                        _ => Result::Err(DecisionTableError::UnsupportedCondition(*node_id))
+                    })
+                    .collect::<Result<Vec<&RelationalExpression>, DecisionTableError>>()?,
+                _ => return Result::Err(DecisionTableError::UnsupportedCondition(*node_id))
+            },
+            ConditionExpression::ConstantExpression(false) =>
+                return Result::Err(DecisionTableError::UnsupportedCondition(*node_id))
+        };
+
+        relations.into_iter()
+            .map(|relation| match relation.get_specification() {
+                RelationalExpressionSpecification::NameAndLiteral(name, _) => Result::Ok((name.clone(), relation.clone())),
+                _ => Result::Err(DecisionTableError::UnsupportedCondition(*node_id))
+            })
+            .collect()
+    }
+
+    // Every cell may only compare the value of its own column, with a literal. Whatever
+    // the hit policy, the rules are compiled in order, as the ones which may match the
+    // same values have been checked to set the same output.
+    pub fn to_document(&self,
+                       id: i32) -> Result<BehaviorTreeDocument, DecisionTableError> {
+        let rules = self.get_typed_rules()?;
+        self.check_hit_policy(&rules)?;
+
+        let mut nodes = vec![NodeDefinitionDocument::Fallback {
+            id: FALLBACK_ID,
+            children_ids: (0..rules.len() as i32).map(|index| FALLBACK_ID + 1 + 2 * index).collect()
+        }];
+
+        for (index, rule) in rules.iter().enumerate() {
+            for (input, cell) in self.inputs.iter().zip(&rule.cells) {
+                for relation in cell {
+                    match relation.get_specification() {
+                        RelationalExpressionSpecification::NameAndLiteral(name, _) if name == input => {},
+                        _ => return Result::Err(DecisionTableError::CellOutOfColumn(index, input.clone()))
+                    }
+                }
+            }
+
+            let condition_id = FALLBACK_ID + 1 + 2 * index as i32;
+            nodes.push(NodeDefinitionDocument::Condition {
+                id: condition_id,
+                child_id: condition_id + 1,
+                expression: rule.get_condition()
+            });
+            nodes.push(NodeDefinitionDocument::SetValue {
+                id: condition_id + 1,
+                value_name: self.output.clone(),
+                value: rule.output.clone()
+            });
+        }
+
+        Result::Ok(BehaviorTreeDocument::new(id,
+                                             RootDefinitionDocument::OneOff { id: ROOT_ID, child_id: FALLBACK_ID },
+                                             nodes))
+    }
+
+    // Literals compared with an input all have to be of one type, the declared one when
+    // there is one, as values of other types never match them. String literals are read
+    // as the declared type the way values of lax arguments are, e.g. "Monday" as a day.
+    fn get_typed_rules(&self) -> Result<Vec<DecisionRule>, DecisionTableError> {
+        let mut input_types = self.input_types.clone();
+        let mut rules = Vec::new();
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.cells.len() != self.inputs.len() {
+                return Result::Err(DecisionTableError::CellsCountMismatch(index, rule.cells.len()));
+            }
+
+            let mut cells = Vec::new();
+            for (input, cell) in self.inputs.iter().zip(&rule.cells) {
+                let mut relations = Vec::new();
+                for relation in cell {
+                    relations.push(match (relation, relation.get_specification()) {
+                        (RelationalExpression::Equals(_), RelationalExpressionSpecification::NameAndLiteral(name, literal))
+                        | (RelationalExpression::NotEquals(_), RelationalExpressionSpecification::NameAndLiteral(name, literal))
+                        | (RelationalExpression::GreaterThan(_), RelationalExpressionSpecification::NameAndLiteral(name, literal))
+                        | (RelationalExpression::GreaterThanOrEquals(_), RelationalExpressionSpecification::NameAndLiteral(name, literal))
+                        | (RelationalExpression::LessThan(_), RelationalExpressionSpecification::NameAndLiteral(name, literal))
+                        | (RelationalExpression::LessThanOrEquals(_), RelationalExpressionSpecification::NameAndLiteral(name, literal)) => {
+                            let value_type = input_types
+                                .entry(input.clone())
+                                .or_insert_with(|| literal.get_value_type());
+                            let literal = coerce(literal, value_type)
+                                .ok_or_else(|| DecisionTableError::InputTypeMismatch(index, input.clone()))?;
+
+                            relation.with_specification(RelationalExpressionSpecification::NameAndLiteral(name.clone(), literal))
+                        },
+                        _ => relation.clone()
+                    });
+                }
+                cells.push(relations);
+            }
+
+            rules.push(DecisionRule::new(cells, rule.output.clone()));
+        }
+
+        Result::Ok(rules)
+    }
+
+    fn check_hit_policy(&self,
+                        rules: &[DecisionRule]) -> Result<(), DecisionTableError> {
+        if self.hit_policy == HitPolicy::First {
+            return Result::Ok(());
+        }
+
+        for (first_index, first) in rules.iter().enumerate() {
+            for (second_index, second) in rules.iter().enumerate().skip(first_index + 1) {
+                let allowed = self.hit_policy == HitPolicy::Any && first.output == second.output;
+
+                if !allowed && DecisionTable::may_overlap(first, second) {
+                    return Result::Err(DecisionTableError::OverlappingRules(first_index, second_index));
+                }
+            }
+        }
+
+        Result::Ok(())
+    }
+
+    // Rules overlap unless the cells of one of the columns can never hold together. Only
+    // equalities and orderings are taken into account, the rest of the comparisons may
+    // always hold.
+    fn may_overlap(first: &DecisionRule,
+                   second: &DecisionRule) -> bool {
+        !first.cells
+            .iter()
+            .zip(&second.cells)
+            .any(|(first, second)| !CellBounds::new(first.iter().chain(second)).is_satisfiable())
+    }
+
+}
+
+#[derive(Default)]
+struct CellBounds<'a> {
+
+    lower: Option<(&'a ValueHolder, bool)>,
+    upper: Option<(&'a ValueHolder, bool)>,
+    candidates: Option<Vec<&'a ValueHolder>>,
+    not_equals: Vec<&'a ValueHolder>
+
+}
+
+impl<'a> CellBounds<'a> {
+
+    // Bounds are kept with a flag which tells if they are inclusive. Equalities and lists
+    // narrow down the values which may match to a set of candidates.
+    fn new(relations: impl Iterator<Item = &'a RelationalExpression>) -> CellBounds<'a> {
+        let mut bounds = CellBounds::default();
+
+        for relation in relations {
+            let literal = match relation.get_specification() {
+                RelationalExpressionSpecification::NameAndLiteral(_, literal) => literal,
+                _ => continue
+            };
+
+            match relation {
+                RelationalExpression::Equals(_) => bounds.narrow(&[literal]),
+                RelationalExpression::IsIn(_) => if let ValueHolder::List(list) = literal {
+                    bounds.narrow(&list.get_elements().iter().collect::<Vec<_>>())
+                },
+                RelationalExpression::NotEquals(_) => bounds.not_equals.push(literal),
+                RelationalExpression::NotIn(_) => if let ValueHolder::List(list) = literal {
+                    bounds.not_equals.extend(list.get_elements().iter())
+                },
+                RelationalExpression::GreaterThan(_) => bounds.raise_lower(literal, false),
+                RelationalExpression::GreaterThanOrEquals(_) => bounds.raise_lower(literal, true),
+                RelationalExpression::LessThan(_) => bounds.lower_upper(literal, false),
+                RelationalExpression::LessThanOrEquals(_) => bounds.lower_upper(literal, true),
+                _ => {}
+            }
+        }
+
+        bounds
+    }
+
+    fn narrow(&mut self,
+              values: &[&'a ValueHolder]) {
+        self.candidates = Option::Some(match self.candidates.take() {
+            Some(candidates) => candidates.into_iter().filter(|candidate| values.contains(candidate)).collect(),
+            None => values.to_vec()
+        });
+    }
+
+    fn raise_lower(&mut self,
+                   literal: &'a ValueHolder,
+                   inclusive: bool) {
+        self.lower = match self.lower {
+            Some((lower, lower_inclusive)) => match literal.partial_cmp(lower) {
+                Some(Ordering::Greater) => Option::Some((literal, inclusive)),
+                Some(Ordering::Equal) => Option::Some((lower, lower_inclusive && inclusive)),
+                _ => Option::Some((lower, lower_inclusive))
+            },
+            None => Option::Some((literal, inclusive))
+        };
+    }
+
+    fn lower_upper(&mut self,
+                   literal: &'a ValueHolder,
+                   inclusive: bool) {
+        self.upper = match self.upper {
+            Some((upper, upper_inclusive)) => match literal.partial_cmp(upper) {
+                Some(Ordering::Less) => Option::Some((literal, inclusive)),
+                Some(Ordering::Equal) => Option::Some((upper, upper_inclusive && inclusive)),
+                _ => Option::Some((upper, upper_inclusive))
+            },
+            None => Option::Some((literal, inclusive))
+        };
+    }
+
+    fn is_within(&self,
+                 value: &ValueHolder) -> bool {
+        let above_lower = match self.lower {
+            Some((lower, inclusive)) => match value.partial_cmp(lower) {
+                Some(Ordering::Less) => false,
+                Some(Ordering::Equal) => inclusive,
+                _ => true
+            },
+            None => true
+        };
+        let below_upper = match self.upper {
+            Some((upper, inclusive)) => match value.partial_cmp(upper) {
+                Some(Ordering::Greater) => false,
+                Some(Ordering::Equal) => inclusive,
+                _ => true
+            },
+            None => true
+        };
+
+        above_lower && below_upper
+    }
+
+    fn is_satisfiable(&self) -> bool {
+        if let Some(candidates) = &self.candidates {
+            return candidates.iter().any(|value| !self.not_equals.contains(value) && self.is_within(value));
+        }
+
+        match (self.lower, self.upper) {
+            (Some((lower, lower_inclusive)), Some((upper, upper_inclusive))) => match lower.partial_cmp(upper) {
+                Some(Ordering::Greater) => false,
+                Some(Ordering::Equal) => lower_inclusive && upper_inclusive && !self.not_equals.contains(&lower),
+                _ => true
+            },
+            _ => true
+        }
+    }
+
+}
+
+
+// Every value is matched by the type of the same name.
+fn coerce(literal: &ValueHolder,
+          value_type: &ValueType) -> Option<ValueHolder> {
+    if value_type.matches(literal) {
+        return Option::Some(literal.clone());
+    }
+
+    match literal {
+        ValueHolder::String(text) => ValueExtractorService::extract(&ValueExtractorInput::new(
+            &serde_json::Value::String(text.to_string()), value_type, &ValueExtractionPolicy::Lax)).ok(),
+        _ => Option::None
+    }
+}
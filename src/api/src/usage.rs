@@ -0,0 +1,164 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+const ANONYMOUS_CALLER: &str = "anonymous";
+const OTHER_CALLERS: &str = "other";
+const FINGERPRINT_LENGTH: usize = 16;
+const MAX_CALLERS: usize = 1000;
+const MAX_TOP_CALLERS: usize = 10;
+const RETAINED_DAYS: i64 = 90;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DefinitionUsage {
+
+    evaluations_per_day: BTreeMap<NaiveDate, u64>,
+    // Stats files written before callers were fingerprinted hold raw api keys under another
+    // name, those counts are dropped on load.
+    #[serde(default)]
+    evaluations_per_fingerprint: HashMap<String, u64>,
+    last_evaluated_at_utc: NaiveDateTime
+
+}
+
+impl DefinitionUsage {
+
+    fn new(now: NaiveDateTime) -> DefinitionUsage {
+        DefinitionUsage {
+            evaluations_per_day: BTreeMap::new(),
+            evaluations_per_fingerprint: HashMap::new(),
+            last_evaluated_at_utc: now
+        }
+    }
+
+    fn record(&mut self,
+              caller: &str,
+              now: NaiveDateTime) {
+        *self.evaluations_per_day.entry(now.date()).or_insert(0) += 1;
+        let caller = match self.evaluations_per_fingerprint.contains_key(caller) {
+            false if self.evaluations_per_fingerprint.len() >= MAX_CALLERS => OTHER_CALLERS,
+            _ => caller
+        };
+
+        *self.evaluations_per_fingerprint.entry(caller.to_owned()).or_insert(0) += 1;
+        self.last_evaluated_at_utc = now;
+
+        let oldest_day = now.date() - Duration::days(RETAINED_DAYS);
+        self.evaluations_per_day = self.evaluations_per_day.split_off(&oldest_day);
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DefinitionUsageStats {
+
+    tree_id: i32,
+    total_evaluations: u64,
+    evaluations_per_day: BTreeMap<NaiveDate, u64>,
+    last_evaluated_at_utc: NaiveDateTime,
+    top_callers: Vec<(String, u64)>
+
+}
+
+impl DefinitionUsageStats {
+
+    pub fn get_total_evaluations(&self) -> &u64 {
+        &self.total_evaluations
+    }
+
+    pub fn get_evaluations_per_day(&self) -> &BTreeMap<NaiveDate, u64> {
+        &self.evaluations_per_day
+    }
+
+    pub fn get_last_evaluated_at_utc(&self) -> &NaiveDateTime {
+        &self.last_evaluated_at_utc
+    }
+
+    pub fn get_top_callers(&self) -> &Vec<(String, u64)> {
+        &self.top_callers
+    }
+
+}
+
+// Counts evaluations of every tree, per day and per caller, so that rules nobody calls
+// anymore can be found. Callers are identified by a fingerprint of their api key, if they
+// send one, the key itself is never kept. Callers beyond the first MAX_CALLERS of a tree
+// are counted together.
+#[derive(Default)]
+pub struct DefinitionUsageService {
+
+    usages: DashMap<i32, DefinitionUsage>
+
+}
+
+impl DefinitionUsageService {
+
+    pub fn record(&self,
+                  tree_id: &i32,
+                  caller: Option<&str>) {
+        let now = Utc::now().naive_utc();
+        let caller = caller.map(DefinitionUsageService::fingerprint);
+
+        self.usages
+            .entry(*tree_id)
+            .or_insert_with(|| DefinitionUsage::new(now))
+            .record(caller.as_deref().unwrap_or(ANONYMOUS_CALLER), now);
+    }
+
+    pub fn fingerprint(api_key: &str) -> String {
+        let mut fingerprint = sha1::Sha1::from(api_key).digest().to_string();
+
+        fingerprint.truncate(FINGERPRINT_LENGTH);
+        fingerprint
+    }
+
+    pub fn get_stats(&self,
+                     tree_id: &i32) -> Option<DefinitionUsageStats> {
+        self.usages.get(tree_id).map(|usage| {
+            let mut top_callers: Vec<(String, u64)> = usage.evaluations_per_fingerprint
+                .iter()
+                .map(|(caller, evaluations)| (caller.clone(), *evaluations))
+                .collect();
+
+            top_callers.sort_by(|(first_caller, first), (second_caller, second)|
+                second.cmp(first).then_with(|| first_caller.cmp(second_caller)));
+            top_callers.truncate(MAX_TOP_CALLERS);
+
+            DefinitionUsageStats {
+                tree_id: *tree_id,
+                total_evaluations: usage.evaluations_per_fingerprint.values().sum(),
+                evaluations_per_day: usage.evaluations_per_day.clone(),
+                last_evaluated_at_utc: usage.last_evaluated_at_utc,
+                top_callers
+            }
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<DefinitionUsageService> {
+        let usages: HashMap<i32, DefinitionUsage> = serde_json::from_slice(&fs::read(path)?)?;
+
+        Result::Ok(DefinitionUsageService {
+            usages: usages.into_iter().collect()
+        })
+    }
+
+    // Written to a temporary file first, so that a crash never leaves a truncated file.
+    pub fn save(&self,
+                path: &Path) -> io::Result<()> {
+        let usages: HashMap<i32, DefinitionUsage> = self.usages
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect();
+        let temporary_path = path.with_extension("tmp");
+
+        fs::write(&temporary_path, serde_json::to_vec(&usages)?)?;
+        fs::rename(temporary_path, path)
+    }
+
+}
+
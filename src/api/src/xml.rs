@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+
+// Elements are read recursively, deeper documents are rejected before they can overflow
+// the stack.
+pub const MAX_DEPTH: usize = 256;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum XmlError {
+
+    InvalidEntity(usize, String),
+    MismatchedTag(usize, String),
+    MissingRoot,
+    TooDeep(usize),
+    UnexpectedChar(usize, char),
+    UnexpectedEnd
+
+}
+
+// Just enough of xml to read and write definitions of other tools, without namespaces
+// being resolved, doctypes or entities other than the predefined ones.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct XmlElement {
+
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String
+
+}
+
+impl XmlElement {
+
+    pub fn new(name: &str) -> XmlElement {
+        XmlElement {
+            name: name.to_owned(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new()
+        }
+    }
+
+    pub fn with_attribute(mut self,
+                          name: &str,
+                          value: &str) -> XmlElement {
+        self.attributes.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    pub fn with_child(mut self,
+                      child: XmlElement) -> XmlElement {
+        self.children.push(child);
+        self
+    }
+
+    pub fn with_text(mut self,
+                     text: &str) -> XmlElement {
+        self.text = text.to_owned();
+        self
+    }
+
+    pub fn parse(xml: &str) -> Result<XmlElement, XmlError> {
+        let mut reader = XmlReader { xml, position: 0 };
+
+        reader.skip_misc()?;
+        if reader.is_at_end() {
+            return Result::Err(XmlError::MissingRoot);
+        }
+        let root = reader.read_element(1)?;
+        reader.skip_misc()?;
+
+        match reader.peek() {
+            Some(char) => Result::Err(XmlError::UnexpectedChar(reader.position, char)),
+            None => Result::Ok(root)
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    // Name without the namespace prefix, if there is one.
+    pub fn get_local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    pub fn get_attributes(&self) -> &Vec<(String, String)> {
+        &self.attributes
+    }
+
+    // Attributes are looked up by their name, with or without the namespace prefix.
+    pub fn get_attribute(&self,
+                         name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .or_else(|| self.attributes.iter().find(|(attribute, _)| local_name(attribute) == name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn get_children(&self) -> &Vec<XmlElement> {
+        &self.children
+    }
+
+    pub fn get_child(&self,
+                     local_name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.get_local_name() == local_name)
+    }
+
+    pub fn get_text(&self) -> &str {
+        self.text.trim()
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+        self.write(&mut xml, 0);
+        xml
+    }
+
+    fn write(&self,
+             xml: &mut String,
+             depth: usize) {
+        let indent = "  ".repeat(depth);
+
+        xml.push_str(&indent);
+        xml.push('<');
+        xml.push_str(&self.name);
+        for (name, value) in &self.attributes {
+            xml.push_str(&format!(" {}=\"{}\"", name, escape(value, true)));
+        }
+
+        if self.children.is_empty() && self.text.is_empty() {
+            xml.push_str("/>\n");
+            return;
+        }
+
+        xml.push('>');
+        if self.children.is_empty() {
+            xml.push_str(&escape(&self.text, false));
+        } else {
+            xml.push('\n');
+            for child in &self.children {
+                child.write(xml, depth + 1);
+            }
+            xml.push_str(&indent);
+        }
+        xml.push_str(&format!("</{}>\n", self.name));
+    }
+
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn escape(text: &str,
+          in_attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if in_attribute => escaped.push_str("&quot;"),
+            _ => escaped.push(char)
+        }
+    }
+
+    escaped
+}
+
+struct XmlReader<'a> {
+
+    xml: &'a str,
+    position: usize
+
+}
+
+impl<'a> XmlReader<'a> {
+
+    fn is_at_end(&self) -> bool {
+        self.position >= self.xml.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.xml[self.position..].chars().next()
+    }
+
+    fn starts_with(&self,
+                   prefix: &str) -> bool {
+        self.xml[self.position..].starts_with(prefix)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(char) = self.peek().filter(|char| char.is_whitespace()) {
+            self.position += char.len_utf8();
+        }
+    }
+
+    fn skip_past(&mut self,
+                 end: &str) -> Result<(), XmlError> {
+        match self.xml[self.position..].find(end) {
+            Some(index) => {
+                self.position += index + end.len();
+                Result::Ok(())
+            },
+            None => Result::Err(XmlError::UnexpectedEnd)
+        }
+    }
+
+    fn expect(&mut self,
+              expected: char) -> Result<(), XmlError> {
+        match self.peek() {
+            Some(char) if char == expected => {
+                self.position += char.len_utf8();
+                Result::Ok(())
+            },
+            Some(char) => Result::Err(XmlError::UnexpectedChar(self.position, char)),
+            None => Result::Err(XmlError::UnexpectedEnd)
+        }
+    }
+
+    // Declarations, processing instructions, comments and doctypes around the root.
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Result::Ok(());
+            }
+        }
+    }
+
+    fn read_name(&mut self) -> Result<String, XmlError> {
+        let start = self.position;
+
+        while let Some(char) = self.peek() {
+            if char.is_whitespace() || "/>=".contains(char) {
+                break;
+            }
+            self.position += char.len_utf8();
+        }
+
+        match self.peek() {
+            None => Result::Err(XmlError::UnexpectedEnd),
+            Some(char) if self.position == start => Result::Err(XmlError::UnexpectedChar(self.position, char)),
+            Some(_) => Result::Ok(self.xml[start..self.position].to_owned())
+        }
+    }
+
+    fn read_element(&mut self,
+                    depth: usize) -> Result<XmlElement, XmlError> {
+        if depth > MAX_DEPTH {
+            return Result::Err(XmlError::TooDeep(self.position));
+        }
+        self.expect('<')?;
+        let mut element = XmlElement::new(&self.read_name()?);
+
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("/>") {
+                self.position += 2;
+                return Result::Ok(element);
+            }
+            if self.starts_with(">") {
+                self.position += 1;
+                break;
+            }
+
+            let name = self.read_name()?;
+            self.skip_whitespace();
+            self.expect('=')?;
+            self.skip_whitespace();
+            let quote = self.peek().ok_or(XmlError::UnexpectedEnd)?;
+            if quote != '"' && quote != '\'' {
+                return Result::Err(XmlError::UnexpectedChar(self.position, quote));
+            }
+            self.position += 1;
+            let start = self.position;
+            self.skip_past(&quote.to_string())?;
+            element.attributes.push((name, unescape(&self.xml[start..self.position - 1], start)?));
+        }
+
+        loop {
+            if self.is_at_end() {
+                return Result::Err(XmlError::UnexpectedEnd);
+            } else if self.starts_with("</") {
+                let position = self.position;
+                self.position += 2;
+                let name = self.read_name()?;
+                self.skip_whitespace();
+                self.expect('>')?;
+
+                return if name == element.name {
+                    Result::Ok(element)
+                } else {
+                    Result::Err(XmlError::MismatchedTag(position, name))
+                };
+            } else if self.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.starts_with("<![CDATA[") {
+                self.position += "<![CDATA[".len();
+                let start = self.position;
+                self.skip_past("]]>")?;
+                element.text.push_str(&self.xml[start..self.position - "]]>".len()]);
+            } else if self.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.starts_with("<") {
+                let child = self.read_element(depth + 1)?;
+                element.children.push(child);
+            } else {
+                let start = self.position;
+                let end = self.xml[start..].find('<').map_or(self.xml.len(), |index| start + index);
+                self.position = end;
+                // Indentation between elements is not text.
+                if !self.xml[start..end].trim().is_empty() {
+                    element.text.push_str(&unescape(&self.xml[start..end], start)?);
+                }
+            }
+        }
+    }
+
+}
+
+fn unescape(text: &str,
+            position: usize) -> Result<String, XmlError> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..].find(';')
+            .ok_or_else(|| XmlError::InvalidEntity(position, rest[start..].to_owned()))?;
+        let entity = &rest[start + 1..start + end];
+        let char = match entity {
+            "lt" => Option::Some('<'),
+            "gt" => Option::Some('>'),
+            "amp" => Option::Some('&'),
+            "quot" => Option::Some('"'),
+            "apos" => Option::Some('\''),
+            _ => entity.strip_prefix("#x")
+                .map(|code| u32::from_str_radix(code, 16))
+                .or_else(|| entity.strip_prefix('#').map(|code| code.parse::<u32>()))
+                .and_then(|code| code.ok())
+                .and_then(std::char::from_u32)
+        };
+
+        unescaped.push(char.ok_or_else(|| XmlError::InvalidEntity(position, entity.to_owned()))?);
+        rest = &rest[start + end + 1..];
+    }
+
+    unescaped.push_str(rest);
+    Result::Ok(unescaped)
+}
@@ -0,0 +1,340 @@
+use std::sync::Arc;
+
+use buttercup_api::bts::{BehaviorTreeBuildingError, BehaviorTreeBuildingService, BehaviorTreeDefinition, BehaviorTreeDefinitionService, ErrorRateRollback, FixtureMismatch};
+use buttercup_api::bts::action::logging::PrintLogActionNodeDefinition;
+use buttercup_api::bts::action::subtree::ExecuteSubTreeActionNodeDefinition;
+use buttercup_api::bts::decorator::condition::ConditionDecoratorNodeDefinition;
+use buttercup_api::bts::root::OneOffRootBTNodeDefinition;
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_bts::tree::{BehaviorTreeFixture, BehaviorTreeService};
+use buttercup_conditions::{ConditionExpression, RelationalExpression, RelationalExpressionSpecification};
+use buttercup_conditions::relational::EqualsRelationalExpression;
+use buttercup_values::ValuesPayload;
+
+fn tree_definition() -> BehaviorTreeDefinition {
+    BehaviorTreeDefinition::new(1,
+                                vec![
+                                    Arc::new(PrintLogActionNodeDefinition::new(
+                                        1, "Hello!".to_owned()))],
+                                Box::new(
+                                    OneOffRootBTNodeDefinition::new(2, 1)))
+}
+
+fn broken_tree_definition() -> BehaviorTreeDefinition {
+    BehaviorTreeDefinition::new(1,
+                                vec![],
+                                Box::new(
+                                    OneOffRootBTNodeDefinition::new(2, 1)))
+}
+
+fn guarded_tree_definition(fixtures: Vec<BehaviorTreeFixture>) -> BehaviorTreeDefinition {
+    BehaviorTreeDefinition::new(1,
+                                vec![
+                                    Arc::new(PrintLogActionNodeDefinition::new(
+                                        1, "Hello!".to_owned())),
+                                    Arc::new(ConditionDecoratorNodeDefinition::new(
+                                        3,
+                                        1,
+                                        ConditionExpression::RelationExpression(
+                                            RelationalExpression::Equals(
+                                                EqualsRelationalExpression::new(
+                                                    RelationalExpressionSpecification::NameAndLiteral(
+                                                        "mode".to_owned(),
+                                                        "on".to_owned().into()))))))],
+                                Box::new(
+                                    OneOffRootBTNodeDefinition::new(2, 3)))
+        .with_fixtures(fixtures)
+}
+
+fn mode_fixture(mode: &str,
+                expected_status: TickStatus) -> BehaviorTreeFixture {
+    BehaviorTreeFixture::new(ValuesPayload::singleton("mode".to_owned(), mode.to_owned().into()),
+                             expected_status)
+}
+
+#[test]
+fn test_assigns_versions_on_insert() {
+    let definition_service = BehaviorTreeDefinitionService::default();
+
+    assert_eq!(None, definition_service.get_active_version(&1));
+
+    definition_service.insert(tree_definition());
+    assert_eq!(Some(1), definition_service.get_active_version(&1));
+
+    definition_service.insert(tree_definition());
+    assert_eq!(Some(2), definition_service.get_active_version(&1));
+
+    assert_eq!(Ok(Some(2)), definition_service.activate(&1, &1));
+    assert_eq!(Some(1), definition_service.get_active_version(&1));
+}
+
+#[test]
+fn test_activating_unknown_versions_leaves_no_trace() {
+    let definition_service = BehaviorTreeDefinitionService::default();
+
+    assert_eq!(Err(BehaviorTreeBuildingError::CouldNotFindTreeVersion(1, 1)),
+               definition_service.activate(&1, &1));
+    assert!(!definition_service.contains(&1));
+
+    definition_service.insert_standby(tree_definition(), 2).unwrap();
+    assert_eq!(Err(BehaviorTreeBuildingError::CouldNotFindTreeVersion(1, 1)),
+               definition_service.activate(&1, &1));
+    assert_eq!(None, definition_service.get_active_version(&1));
+
+    assert_eq!(Ok(None), definition_service.activate(&1, &2));
+    assert_eq!(Some(2), definition_service.get_active_version(&1));
+}
+
+#[actix_rt::test]
+async fn test_switches_active_tree_on_activation() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let tree_service = Arc::new(BehaviorTreeService::default());
+
+    definition_service.insert(tree_definition());
+    definition_service.insert_standby(tree_definition(), 2).unwrap();
+
+    let building_service =
+        BehaviorTreeBuildingService::new(tree_service.clone(), definition_service.clone());
+
+    assert!(tree_service.get_by_id(&1).is_none());
+    assert!(building_service.activate(&1, &2).await.is_ok());
+    assert!(tree_service.get_by_id(&1).is_some());
+    assert_eq!(Some(2), definition_service.get_active_version(&1));
+
+    assert_eq!(Err(BehaviorTreeBuildingError::TreeVersionIsActive(1, 2)),
+               definition_service.insert_standby(tree_definition(), 2));
+}
+
+#[actix_rt::test]
+async fn test_keeps_previous_version_when_activated_version_fails_to_build() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+
+    definition_service.insert(tree_definition());
+    definition_service.insert_standby(broken_tree_definition(), 2).unwrap();
+
+    let building_service =
+        BehaviorTreeBuildingService::new(Arc::new(BehaviorTreeService::default()),
+                                         definition_service.clone());
+
+    assert_eq!(Err(BehaviorTreeBuildingError::CouldNotFindChildDefinitionWithId(1)),
+               building_service.activate(&1, &2).await.map(|_| ()));
+    assert_eq!(Some(1), definition_service.get_active_version(&1));
+
+    assert_eq!(Err(BehaviorTreeBuildingError::CouldNotFindTreeVersion(1, 3)),
+               building_service.activate(&1, &3).await.map(|_| ()));
+}
+
+#[actix_rt::test]
+async fn test_reports_fixture_mismatches() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+
+    definition_service.insert(
+        guarded_tree_definition(vec![
+            mode_fixture("on", TickStatus::Success),
+            mode_fixture("off", TickStatus::Success),
+            mode_fixture("off", TickStatus::Failure)]));
+
+    let building_service =
+        BehaviorTreeBuildingService::new(Arc::new(BehaviorTreeService::default()),
+                                         definition_service);
+
+    assert_eq!(Ok(vec![FixtureMismatch::new(1, TickStatus::Success, Ok(TickStatus::Failure))]),
+               building_service.self_test(&1).await);
+}
+
+#[actix_rt::test]
+async fn test_stops_serving_trees_which_do_not_match_fixtures() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let tree_service = Arc::new(BehaviorTreeService::default());
+
+    definition_service.insert(
+        guarded_tree_definition(vec![mode_fixture("off", TickStatus::Success)]));
+
+    let building_service =
+        BehaviorTreeBuildingService::new(tree_service.clone(), definition_service);
+    tree_service.insert(building_service.build(&1).unwrap());
+
+    assert_eq!(vec![(1, BehaviorTreeBuildingError::SelfTestFailed(
+        1, vec![FixtureMismatch::new(0, TickStatus::Success, Ok(TickStatus::Failure))]))],
+               building_service.self_test_served().await);
+    assert!(tree_service.get_by_id(&1).is_none());
+    assert!(building_service.self_test_served().await.is_empty());
+}
+
+#[actix_rt::test]
+async fn test_stops_serving_trees_embedding_trees_which_do_not_match_fixtures() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let tree_service = Arc::new(BehaviorTreeService::default());
+
+    definition_service.insert(
+        guarded_tree_definition(vec![mode_fixture("off", TickStatus::Success)]));
+    definition_service.insert(
+        BehaviorTreeDefinition::new(2,
+                                    vec![Arc::new(ExecuteSubTreeActionNodeDefinition::new(1, 1))],
+                                    Box::new(OneOffRootBTNodeDefinition::new(2, 1))));
+    definition_service.insert(
+        BehaviorTreeDefinition::new(3,
+                                    vec![Arc::new(ExecuteSubTreeActionNodeDefinition::new(1, 2))],
+                                    Box::new(OneOffRootBTNodeDefinition::new(2, 1))));
+    definition_service.insert(
+        BehaviorTreeDefinition::new(4,
+                                    vec![Arc::new(PrintLogActionNodeDefinition::new(1, "Hello!".to_owned()))],
+                                    Box::new(OneOffRootBTNodeDefinition::new(2, 1))));
+
+    let building_service =
+        BehaviorTreeBuildingService::new(tree_service.clone(), definition_service);
+    for id in 1..=4 {
+        tree_service.insert(building_service.build(&id).unwrap());
+    }
+
+    let failed = building_service.self_test_served().await;
+
+    assert_eq!(vec![1, 2, 3], failed.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+    assert_eq!(BehaviorTreeBuildingError::SubtreeSelfTestFailed(2, 1), failed[1].1);
+    assert_eq!(BehaviorTreeBuildingError::SubtreeSelfTestFailed(3, 1), failed[2].1);
+    assert!(tree_service.get_by_id(&2).is_none());
+    assert!(tree_service.get_by_id(&3).is_none());
+    assert!(tree_service.get_by_id(&4).is_some());
+}
+
+#[actix_rt::test]
+async fn test_refuses_activation_when_fixtures_do_not_match() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let tree_service = Arc::new(BehaviorTreeService::default());
+
+    definition_service.insert(
+        guarded_tree_definition(vec![mode_fixture("on", TickStatus::Success)]));
+    definition_service.insert_standby(
+        guarded_tree_definition(vec![mode_fixture("on", TickStatus::Failure)]), 2).unwrap();
+
+    let building_service =
+        BehaviorTreeBuildingService::new(tree_service.clone(), definition_service.clone());
+
+    assert_eq!(Err(BehaviorTreeBuildingError::SelfTestFailed(
+        1, vec![FixtureMismatch::new(0, TickStatus::Failure, Ok(TickStatus::Success))])),
+               building_service.activate(&1, &2).await.map(|_| ()));
+    assert_eq!(Some(1), definition_service.get_active_version(&1));
+    assert!(tree_service.get_by_id(&1).is_none());
+
+    assert!(building_service.activate(&1, &1).await.is_ok());
+    assert!(tree_service.get_by_id(&1).is_some());
+}
+
+#[actix_rt::test]
+async fn test_leaves_no_active_version_when_first_activation_fails() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let tree_service = Arc::new(BehaviorTreeService::default());
+
+    definition_service.insert_standby(
+        guarded_tree_definition(vec![mode_fixture("on", TickStatus::Failure)]), 1).unwrap();
+
+    let building_service =
+        BehaviorTreeBuildingService::new(tree_service.clone(), definition_service.clone());
+
+    assert_eq!(Err(BehaviorTreeBuildingError::SelfTestFailed(
+        1, vec![FixtureMismatch::new(0, TickStatus::Failure, Ok(TickStatus::Success))])),
+               building_service.activate(&1, &1).await.map(|_| ()));
+    assert_eq!(None, definition_service.get_active_version(&1));
+    assert!(definition_service.get(&1).is_none());
+    assert!(definition_service.get_standby(&1, &1).is_some());
+    assert!(tree_service.get_by_id(&1).is_none());
+}
+
+#[actix_rt::test]
+async fn test_rolls_back_to_previous_version() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+
+    definition_service.insert(tree_definition());
+    definition_service.insert_standby(tree_definition(), 2).unwrap();
+    definition_service.insert_standby(tree_definition(), 3).unwrap();
+
+    let building_service =
+        BehaviorTreeBuildingService::new(Arc::new(BehaviorTreeService::default()),
+                                         definition_service.clone());
+
+    assert_eq!(Err(BehaviorTreeBuildingError::NoPreviousVersion(2)), building_service.rollback(&2).await);
+
+    building_service.activate(&1, &3).await.unwrap();
+
+    assert_eq!(Ok(2), building_service.rollback(&1).await);
+    assert_eq!(Ok(1), building_service.rollback(&1).await);
+    assert_eq!(Some(1), definition_service.get_active_version(&1));
+    assert_eq!(Err(BehaviorTreeBuildingError::NoPreviousVersion(1)), building_service.rollback(&1).await);
+}
+
+fn record_ticks(tree_service: &BehaviorTreeService,
+                ticks: usize,
+                errors: usize) {
+    let tree = tree_service.get_by_id(&1).unwrap();
+
+    for tick in 0..ticks {
+        tree.get_tick_outcomes().record(&match tick < errors {
+            true => Result::Err(TickError::MissingValues(1, vec!["mode".to_owned()])),
+            false => Result::Ok(TickStatus::Success)
+        });
+    }
+}
+
+#[actix_rt::test]
+async fn test_rolls_back_versions_on_error_spikes() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let tree_service = Arc::new(BehaviorTreeService::default());
+
+    definition_service.insert(tree_definition());
+    definition_service.insert_standby(tree_definition(), 2).unwrap();
+
+    let building_service =
+        BehaviorTreeBuildingService::new(tree_service.clone(), definition_service.clone())
+            .with_error_rate_rollback(ErrorRateRollback::new(50, 10));
+    building_service.activate(&1, &2).await.unwrap();
+
+    record_ticks(&tree_service, 9, 9);
+    assert!(building_service.rollback_error_spikes().await.is_empty());
+
+    record_ticks(&tree_service, 10, 5);
+    assert!(building_service.rollback_error_spikes().await.is_empty());
+
+    record_ticks(&tree_service, 10, 6);
+    assert_eq!(vec![(1, Ok(1))], building_service.rollback_error_spikes().await);
+    assert_eq!(Some(1), definition_service.get_active_version(&1));
+
+    record_ticks(&tree_service, 10, 10);
+    assert_eq!(vec![(1, Err(BehaviorTreeBuildingError::NoPreviousVersion(1)))],
+               building_service.rollback_error_spikes().await);
+    assert_eq!(Some(1), definition_service.get_active_version(&1));
+}
+
+#[test]
+fn test_builds_versions_without_activating_them() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let tree_service = Arc::new(BehaviorTreeService::default());
+
+    definition_service.insert(tree_definition());
+    definition_service.insert_standby(broken_tree_definition(), 2).unwrap();
+
+    let building_service =
+        BehaviorTreeBuildingService::new(tree_service.clone(), definition_service.clone());
+
+    assert!(building_service.build_version(&1, &1).is_ok());
+    assert_eq!(Err(BehaviorTreeBuildingError::CouldNotFindChildDefinitionWithId(1)),
+               building_service.build_version(&1, &2).map(|_| ()));
+    assert_eq!(Err(BehaviorTreeBuildingError::CouldNotFindTreeVersion(1, 3)),
+               building_service.build_version(&1, &3).map(|_| ()));
+    assert_eq!(Some(1), definition_service.get_active_version(&1));
+    assert!(tree_service.get_by_id(&1).is_none());
+}
+
+#[actix_rt::test]
+async fn test_puts_static_values_before_payloads() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    definition_service.insert(guarded_tree_definition(vec![]));
+
+    let building_service =
+        BehaviorTreeBuildingService::new(Arc::new(BehaviorTreeService::default()), definition_service)
+            .with_static_values(Arc::new(ValuesPayload::singleton("mode".to_owned(), "on".to_owned().into())));
+    let tree = building_service.build(&1).unwrap();
+
+    assert_eq!(Result::Ok(TickStatus::Success), tree.evaluate(&ValuesPayload::empty()).await);
+    assert_eq!(Result::Ok(TickStatus::Failure),
+               tree.evaluate(&ValuesPayload::singleton("mode".to_owned(), "off".to_owned().into())).await);
+}
@@ -0,0 +1,103 @@
+use buttercup_api::complexity::DefinitionLimits;
+use buttercup_api::document::parse_payload;
+use buttercup_api::engine::{ButtercupEngine, EngineError};
+use buttercup_bts::budget::{EvaluationBudget, EvaluationResource};
+use buttercup_bts::hits::ConditionHitCounts;
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_endpoints::validation::ArgumentViolation;
+
+const DEFINITION: &str = r#"{
+    "id": 1,
+    "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+    "nodes": [
+        { "type": "Condition", "id": 2, "child_id": 3, "expression": { "RelationExpression": {
+            "GreaterThan": { "specification": { "NameAndLiteral": ["age", { "Integer": [1, [18]] }] } }
+        } } },
+        { "type": "PrintLog", "id": 3, "message": "adult" }
+    ]
+}"#;
+
+#[actix_rt::test]
+async fn test_evaluates_adhoc_definitions() {
+    let payload = parse_payload(r#"{ "age": { "Integer": [1, [30]] } }"#).unwrap();
+    let evaluation = ButtercupEngine::evaluate_adhoc(DEFINITION, &payload, &DefinitionLimits::default(), &EvaluationBudget::default())
+        .await
+        .unwrap();
+
+    assert_eq!(&Result::Ok(TickStatus::Success), evaluation.get_result());
+    assert_eq!(vec![3, 2, 1], evaluation.get_trace().iter().map(|timing| *timing.get_node_id()).collect::<Vec<i32>>());
+    assert_eq!(Option::Some(&ConditionHitCounts::new(1, 0)), evaluation.get_hits().get_conditions().get(&2));
+    assert_eq!(3, evaluation.get_chrome_trace().get_trace_events().len());
+    assert_eq!(&3, evaluation.get_usage().get_nodes_visited());
+    assert_eq!(&1, evaluation.get_usage().get_conditions_evaluated());
+    assert_eq!(&1, evaluation.get_usage().get_lookups());
+}
+
+#[actix_rt::test]
+async fn test_explains_adhoc_evaluations() {
+    let payload = parse_payload(r#"{ "age": { "Integer": [1, [12]] } }"#).unwrap();
+    let evaluation = ButtercupEngine::explain_adhoc(DEFINITION, &payload, &DefinitionLimits::default(), &EvaluationBudget::default())
+        .await
+        .unwrap();
+    let path = evaluation.get_path().as_ref().unwrap();
+
+    assert_eq!(&Result::Ok(TickStatus::Failure), evaluation.get_result());
+    assert_eq!(vec![(1, 2)], path.get_edges());
+    assert_eq!(&Option::Some(false), path.get_visited_nodes()[1].get_condition_passed());
+    assert_eq!(&Option::Some(Result::Ok(TickStatus::Failure)), path.get_visited_nodes()[1].get_result());
+    assert_eq!(&Option::None, ButtercupEngine::evaluate_adhoc(DEFINITION, &payload, &DefinitionLimits::default(), &EvaluationBudget::default())
+        .await
+        .unwrap()
+        .get_path());
+}
+
+#[actix_rt::test]
+async fn test_returns_reports_of_failed_guards() {
+    let definition = r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "Guard", "id": 2, "child_id": 3, "requirements": { "active": {
+                "id": 1, "name": "active", "argument_type": "Boolean", "extraction_policy": "Strict",
+                "argument_set_definition_id": 1
+            } } },
+            { "type": "PrintLog", "id": 3, "message": "active" }
+        ]
+    }"#;
+    let evaluation = ButtercupEngine::evaluate_adhoc(definition,
+                                                     &parse_payload("{}").unwrap(),
+                                                     &DefinitionLimits::default(),
+                                                     &EvaluationBudget::default())
+        .await
+        .unwrap();
+
+    assert_eq!(&Result::Ok(TickStatus::Failure), evaluation.get_result());
+    assert_eq!(1, evaluation.get_guard_reports().len());
+    assert_eq!(&2, evaluation.get_guard_reports()[0].get_node_id());
+    assert_eq!(&vec![ArgumentViolation::MissingArgument("active".to_owned())],
+               evaluation.get_guard_reports()[0].get_violations());
+}
+
+#[actix_rt::test]
+async fn test_aborts_adhoc_evaluations_over_budget() {
+    let payload = parse_payload(r#"{ "age": { "Integer": [1, [30]] } }"#).unwrap();
+    let evaluation = ButtercupEngine::evaluate_adhoc(DEFINITION,
+                                                     &payload,
+                                                     &DefinitionLimits::default(),
+                                                     &EvaluationBudget::default().with_max_nodes_visited(2))
+        .await
+        .unwrap();
+
+    assert_eq!(&Result::Err(TickError::BudgetExceeded(3, EvaluationResource::NodesVisited)), evaluation.get_result());
+    assert_eq!(&Option::Some(EvaluationResource::NodesVisited), evaluation.get_usage().get_exceeded());
+}
+
+#[actix_rt::test]
+async fn test_rejects_adhoc_definitions_over_limits() {
+    let result = ButtercupEngine::evaluate_adhoc(DEFINITION,
+                                                 &parse_payload("{}").unwrap(),
+                                                 &DefinitionLimits::default().with_max_nodes(2),
+                                                 &EvaluationBudget::default()).await;
+
+    assert!(matches!(result, Result::Err(EngineError::TooComplex(_))));
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use buttercup_api::bts::BehaviorTreeBuildingError;
+use buttercup_api::engine::{ButtercupEngine, EngineError};
+use buttercup_bts::arbitration::{ArbitrationOutcome, CommandArbiter, CommandPolicy};
+use buttercup_bts::command::{CommandRegistry, ContentCommandAddress, FrequencyCap};
+use buttercup_bts::quota::InMemoryCounterStore;
+use buttercup_bts::tick::TickStatus;
+use buttercup_values::ValuesPayload;
+
+fn selecting_definition(tree_id: i32,
+                        command_id: u32) -> String {
+    format!(r#"{{
+        "id": {},
+        "root": {{ "type": "OneOff", "id": 1, "child_id": 2 }},
+        "nodes": [
+            {{ "type": "SelectCommand", "id": 2, "command": {{ "command_id": {}, "content_index": 0 }} }}
+        ]
+    }}"#, tree_id, command_id)
+}
+
+#[actix_rt::test]
+async fn test_records_arbitration_decisions_in_traces_of_selecting_trees() {
+    let arbiter = CommandArbiter::new(Arc::new(InMemoryCounterStore::default()))
+        .with_policy(7, CommandPolicy::new(10).with_category("promotions"))
+        .with_policy(8, CommandPolicy::new(20).with_category("promotions"))
+        .with_category_cap("promotions", FrequencyCap::new(1, Duration::from_secs(86_400)));
+    let engine = ButtercupEngine::default().with_command_arbiter(Arc::new(arbiter));
+
+    engine.load_definition(&selecting_definition(1, 7), 1).await.unwrap();
+    engine.load_definition(&selecting_definition(2, 8), 1).await.unwrap();
+
+    let evaluations = engine.evaluate_arbitrated("alice", &[1, 2], &ValuesPayload::empty()).await.unwrap();
+    let outcomes = |evaluation_index: usize| evaluations[evaluation_index].get_decisions()
+        .iter()
+        .map(|decision| decision.get_outcome().clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(&1, evaluations[0].get_tree_id());
+    assert_eq!(&Result::Ok(TickStatus::Success), evaluations[0].get_result());
+    assert_eq!(vec![ArbitrationOutcome::SuppressedByPriority], outcomes(0));
+    assert_eq!(vec![ArbitrationOutcome::Accepted], outcomes(1));
+    assert_eq!(&ContentCommandAddress::new(8, 0), evaluations[1].get_accepted()[0].get_selection().get_address());
+    assert!(!evaluations[1].get_trace().is_empty());
+
+    let evaluations = engine.evaluate_arbitrated("alice", &[1, 2], &ValuesPayload::empty()).await.unwrap();
+
+    assert_eq!(vec![ArbitrationOutcome::SuppressedByCategoryCap("promotions".to_owned())],
+               evaluations[1].get_decisions().iter().map(|decision| decision.get_outcome().clone()).collect::<Vec<_>>());
+    assert!(evaluations[0].get_accepted().is_empty());
+}
+
+#[actix_rt::test]
+async fn test_selects_commands_by_their_names_in_the_registry() {
+    let registry = CommandRegistry::default()
+        .with_command("welcome_email", ContentCommandAddress::new(7, 2));
+    let engine = ButtercupEngine::default().with_command_registry(Arc::new(registry));
+    let definition = |name: &str| format!(r#"{{
+        "id": 1,
+        "root": {{ "type": "OneOff", "id": 1, "child_id": 2 }},
+        "nodes": [{{ "type": "SelectCommand", "id": 2, "command": "{}" }}]
+    }}"#, name);
+
+    engine.load_definition(&definition("welcome_email"), 1).await.unwrap();
+
+    let evaluations = engine.evaluate_arbitrated("alice", &[1], &ValuesPayload::empty()).await.unwrap();
+
+    assert_eq!(&ContentCommandAddress::new(7, 2), evaluations[0].get_accepted()[0].get_selection().get_address());
+    assert_eq!(Result::Err(EngineError::BehaviorTreeBuildingError(
+                   BehaviorTreeBuildingError::UnknownCommandName(2, "farewell_email".to_owned()))),
+               engine.validate_definition(&definition("farewell_email")));
+}
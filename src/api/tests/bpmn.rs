@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use buttercup_api::bpmn::{BpmnImportError, BpmnImporter};
+use buttercup_api::engine::ButtercupEngine;
+use buttercup_api::xml::XmlError;
+use buttercup_bts::tick::TickStatus;
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+const ORDER_PROCESS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<bpmn:definitions xmlns:bpmn="http://www.omg.org/spec/BPMN/20100524/MODEL" id="orders">
+  <bpmn:process id="order" isExecutable="true">
+    <bpmn:startEvent id="start"/>
+    <bpmn:sequenceFlow id="f1" sourceRef="start" targetRef="check"/>
+    <bpmn:task id="check" name="Check the order"/>
+    <bpmn:sequenceFlow id="f2" sourceRef="check" targetRef="split"/>
+    <bpmn:exclusiveGateway id="split" default="f4"/>
+    <bpmn:sequenceFlow id="f3" sourceRef="split" targetRef="wait">
+      <bpmn:conditionExpression>${amount &gt; 1000 &amp;&amp; tier == "gold"}</bpmn:conditionExpression>
+    </bpmn:sequenceFlow>
+    <bpmn:sequenceFlow id="f4" sourceRef="split" targetRef="join"/>
+    <bpmn:intermediateCatchEvent id="wait">
+      <bpmn:timerEventDefinition><bpmn:timeDuration>PT0S</bpmn:timeDuration></bpmn:timerEventDefinition>
+    </bpmn:intermediateCatchEvent>
+    <bpmn:sequenceFlow id="f5" sourceRef="wait" targetRef="join"/>
+    <bpmn:exclusiveGateway id="join"/>
+    <bpmn:sequenceFlow id="f6" sourceRef="join" targetRef="ship"/>
+    <bpmn:scriptTask id="ship" name="Ship the order"/>
+    <bpmn:sequenceFlow id="f7" sourceRef="ship" targetRef="end"/>
+    <bpmn:endEvent id="end"/>
+  </bpmn:process>
+</bpmn:definitions>"#;
+
+fn integer(value: u32) -> ValueHolder {
+    serde_json::from_str(&format!(r#"{{ "Integer": [1, [{}]] }}"#, value)).unwrap()
+}
+
+fn process(elements: &str) -> String {
+    format!(r#"<definitions><process id="p"><startEvent id="start"/>{}</process></definitions>"#, elements)
+}
+
+#[actix_rt::test]
+async fn test_imports_structured_processes() {
+    let document = BpmnImporter::import(3, ORDER_PROCESS).unwrap();
+    let json = serde_json::to_value(&document).unwrap();
+
+    assert_eq!(serde_json::json!({ "type": "OneOff", "id": 1, "child_id": 8 }), json["root"]);
+    assert_eq!(serde_json::json!({ "type": "Fallback", "id": 6, "children_ids": [4, 5] }), json["nodes"][4]);
+
+    let engine = ButtercupEngine::default();
+    engine.load_definition(&serde_json::to_string(&document).unwrap(), 1).await.unwrap();
+
+    for amount in &[10, 5000] {
+        let payload = ValuesPayload::new(vec![("amount".to_owned(), integer(*amount)), ("tier".to_owned(), "gold".into())]
+            .into_iter()
+            .collect::<HashMap<String, ValueHolder>>());
+
+        assert_eq!(Result::Ok(TickStatus::Success), engine.evaluate(&3, &payload).await);
+    }
+}
+
+#[test]
+fn test_rejects_unsupported_processes() {
+    assert_eq!(Result::Err(BpmnImportError::UnsupportedElement("fork".to_owned(), "parallelGateway".to_owned())),
+               BpmnImporter::import(1, &process(r#"
+                   <sequenceFlow id="f1" sourceRef="start" targetRef="fork"/>
+                   <parallelGateway id="fork"/>"#)).map(|_| ()));
+    assert_eq!(Result::Err(BpmnImportError::UnstructuredFlow("split".to_owned())),
+               BpmnImporter::import(1, &process(r#"
+                   <sequenceFlow id="f1" sourceRef="start" targetRef="split"/>
+                   <exclusiveGateway id="split" default="f3"/>
+                   <sequenceFlow id="f2" sourceRef="split" targetRef="end"><conditionExpression>a = 1</conditionExpression></sequenceFlow>
+                   <sequenceFlow id="f3" sourceRef="split" targetRef="join"/>
+                   <sequenceFlow id="f4" sourceRef="split" targetRef="join"><conditionExpression>a = 2</conditionExpression></sequenceFlow>
+                   <exclusiveGateway id="join"/>
+                   <sequenceFlow id="f5" sourceRef="join" targetRef="done"/>
+                   <endEvent id="end"/>
+                   <endEvent id="done"/>"#)).map(|_| ()));
+    assert_eq!(Result::Err(BpmnImportError::InvalidTimer("wait".to_owned())),
+               BpmnImporter::import(1, &process(r#"
+                   <sequenceFlow id="f1" sourceRef="start" targetRef="wait"/>
+                   <intermediateCatchEvent id="wait"><timerEventDefinition><timeDuration>P1Y</timeDuration></timerEventDefinition></intermediateCatchEvent>"#))
+                   .map(|_| ()));
+    assert_eq!(Result::Err(BpmnImportError::InvalidXml(XmlError::MismatchedTag(32, "definition".to_owned()))),
+               BpmnImporter::import(1, "<definitions><process></process></definition>").map(|_| ()));
+}
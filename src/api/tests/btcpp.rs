@@ -0,0 +1,178 @@
+use buttercup_api::btcpp::{BtCppError, BtCppExporter, BtCppImporter};
+use buttercup_api::document::BehaviorTreeDocument;
+
+#[test]
+fn test_exports_trees_to_btcpp() {
+    let document = BehaviorTreeDocument::from_json(r#"{
+        "id": 7,
+        "root": { "type": "Reactive", "id": 1, "child_id": 2, "stop_on_error": true },
+        "nodes": [
+            { "type": "Sequence", "id": 2, "children_ids": [3, 5, 6, 7] },
+            { "type": "Condition", "id": 3, "child_id": 4, "expression": { "ConstantExpression": true } },
+            { "type": "PrintLog", "id": 4, "message": "a < b" },
+            { "type": "Invert", "id": 5, "child_id": 8 },
+            { "type": "WaitDuration", "id": 6, "duration": { "Literal": { "secs": 1, "nanos": 500000000 } } },
+            { "type": "Parallel", "id": 7, "children_ids": [9, 10], "num_successes_to_succeed": 1 },
+            { "type": "ExecuteSubTree", "id": 8, "tree_id": 3 },
+            { "type": "PrintLog", "id": 9, "message": "left" },
+            { "type": "WaitForSignal", "id": 10, "name": "go" }
+        ]
+    }"#).unwrap();
+
+    assert_eq!(Result::Ok(r#"<?xml version="1.0" encoding="UTF-8"?>
+<root BTCPP_format="4" main_tree_to_execute="Tree7">
+  <BehaviorTree ID="Tree7">
+    <Reactive stop_on_error="true">
+      <Sequence>
+        <Condition expression="{&quot;ConstantExpression&quot;:true}">
+          <PrintLog message="a &lt; b"/>
+        </Condition>
+        <Inverter>
+          <SubTree ID="Tree3"/>
+        </Inverter>
+        <Sleep msec="1500"/>
+        <Parallel success_count="1" failure_count="2">
+          <PrintLog message="left"/>
+          <WaitForSignal name="go"/>
+        </Parallel>
+      </Sequence>
+    </Reactive>
+  </BehaviorTree>
+  <TreeNodesModel>
+    <Decorator ID="Condition">
+      <input_port name="expression"/>
+    </Decorator>
+    <Action ID="PrintLog">
+      <input_port name="message"/>
+    </Action>
+    <Decorator ID="Reactive">
+      <input_port name="stop_on_error"/>
+    </Decorator>
+    <Action ID="WaitForSignal">
+      <input_port name="name"/>
+    </Action>
+  </TreeNodesModel>
+</root>
+"#.to_owned()), BtCppExporter::export(&document));
+}
+
+#[test]
+fn test_rejects_trees_with_missing_nodes() {
+    let document = BehaviorTreeDocument::from_json(r#"{
+        "id": 7,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "Invert", "id": 2, "child_id": 3 }]
+    }"#).unwrap();
+
+    assert_eq!(Result::Err(BtCppError::MissingNode(3)), BtCppExporter::export(&document));
+}
+
+#[test]
+fn test_imports_exported_trees() {
+    let document = BehaviorTreeDocument::from_json(r#"{
+        "id": 7,
+        "root": { "type": "UntilStopped", "id": 1, "child_id": 5 },
+        "nodes": [
+            { "type": "Fallback", "id": 5, "children_ids": [3, 2] },
+            { "type": "Invert", "id": 3, "child_id": 4 },
+            { "type": "ExecuteSubTree", "id": 4, "tree_id": 3 },
+            { "type": "Parallel", "id": 2, "children_ids": [6, 7], "num_successes_to_succeed": 2 },
+            { "type": "PrintLog", "id": 6, "message": "12" },
+            { "type": "WaitDuration", "id": 7, "duration": { "Literal": { "secs": 2, "nanos": 0 } } }
+        ]
+    }"#).unwrap();
+    let xml = BtCppExporter::export(&document).unwrap();
+
+    let imported = BtCppImporter::import(7, &xml).unwrap();
+
+    assert_eq!(Result::Ok(xml), BtCppExporter::export(&imported));
+    assert_eq!(serde_json::json!({
+        "type": "UntilStopped", "id": 1, "child_id": 2
+    }), serde_json::to_value(imported.get_root()).unwrap());
+    assert_eq!(serde_json::json!([
+        { "type": "ExecuteSubTree", "id": 4, "tree_id": 3, "namespace": null },
+        { "type": "Invert", "id": 3, "child_id": 4 }
+    ]), serde_json::to_value(&imported.get_nodes()[..2]).unwrap());
+}
+
+#[test]
+fn test_maps_repeats_and_timeouts_to_standard_nodes() {
+    let document = BehaviorTreeDocument::from_json(r#"{
+        "id": 7,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "PrintLog", "id": 5, "message": "again" },
+            { "type": "Repeat", "id": 4, "child_id": 5, "policy": "UntilFailure" },
+            { "type": "Timeout", "id": 3, "child_id": 4, "duration": { "Literal": { "secs": 1, "nanos": 0 } } },
+            { "type": "Repeat", "id": 2, "child_id": 3, "policy": { "Times": 3 } }
+        ]
+    }"#).unwrap();
+    let xml = BtCppExporter::export(&document).unwrap();
+
+    assert!(xml.contains(r#"<Repeat num_cycles="3">
+      <Timeout msec="1000">
+        <Repeat policy="UntilFailure">"#));
+    assert_eq!(serde_json::to_value(document.get_nodes()).unwrap(),
+               serde_json::to_value(BtCppImporter::import(7, &xml).unwrap().get_nodes()).unwrap());
+}
+
+#[test]
+fn test_imports_custom_nodes_of_version_3() {
+    let imported = BtCppImporter::import(2, r#"
+        <root main_tree_to_execute="Main">
+          <BehaviorTree ID="Other">
+            <AlwaysSuccess/>
+          </BehaviorTree>
+          <BehaviorTree ID="Main">
+            <Sequence name="steps">
+              <Action ID="PrintLog" message="true"/>
+              <Action ID="WaitForSignal" name="go"/>
+              <Sleep msec="250"/>
+            </Sequence>
+          </BehaviorTree>
+        </root>"#).unwrap();
+
+    assert_eq!(serde_json::json!([
+        { "type": "PrintLog", "id": 3, "message": "true" },
+        { "type": "WaitForSignal", "id": 4, "name": "go" },
+        { "type": "WaitDuration", "id": 5, "duration": { "Literal": { "secs": 0, "nanos": 250000000 } } },
+        { "type": "Sequence", "id": 2, "children_ids": [3, 4, 5] }
+    ]), serde_json::to_value(imported.get_nodes()).unwrap());
+}
+
+#[test]
+fn test_reports_unsupported_constructs() {
+    let result = BtCppImporter::import(2, r#"
+        <root BTCPP_format="4">
+          <BehaviorTree ID="Main">
+            <ReactiveSequence>
+              <SubTree ID="Other"/>
+              <Parallel success_count="1" failure_count="1">
+                <PrintLog message="left"/>
+                <PrintLog message="right"/>
+              </Parallel>
+              <Inverter/>
+            </ReactiveSequence>
+          </BehaviorTree>
+        </root>"#);
+
+    match result {
+        Err(BtCppError::UnsupportedConstructs(constructs)) => assert_eq!(vec![
+            "SubTree: only trees named Tree<id> can be executed, not Other",
+            "Parallel: failure_count has to be 2",
+            "Inverter: expected a single child, found 0",
+            "ReactiveSequence"
+        ], constructs.iter().map(|construct| construct.split(": unknown").next().unwrap()).collect::<Vec<_>>()),
+        result => panic!("Expected unsupported constructs, got {:?}", result.map(|_| ()))
+    }
+}
+
+#[test]
+fn test_rejects_xml_without_the_main_tree() {
+    assert_eq!(Result::Err(BtCppError::MissingTree("Main".to_owned())), BtCppImporter::import(2, r#"
+        <root main_tree_to_execute="Main">
+          <BehaviorTree ID="Other">
+            <AlwaysSuccess/>
+          </BehaviorTree>
+        </root>"#).map(|_| ()));
+}
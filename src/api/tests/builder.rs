@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use buttercup_api::bts::BehaviorTreeBuildingError;
+use buttercup_api::bts::action::logging::PrintLogActionNodeDefinition;
+use buttercup_api::bts::builder::{Condition, Fallback, Invert, Node, Parallel, PrintLog, Sequence, Subtree, Tree};
+use buttercup_conditions::ConditionExpression;
+
+mod common;
+
+#[test]
+fn test_numbers_every_node_once() {
+    let definition = Tree::new(1, Sequence::new()
+        .child(Condition::new(ConditionExpression::ConstantExpression(true), PrintLog::new("first")))
+        .child(Fallback::new()
+            .child(Invert::new(PrintLog::new("second")))
+            .child(Subtree(10)))
+        .child(Parallel::new(1)
+            .child(PrintLog::new("third"))
+            .child(Node::new(|id, _| Arc::new(PrintLogActionNodeDefinition::new(id, "fourth".to_owned()))))))
+        .build()
+        .unwrap();
+
+    let mut ids: Vec<i32> = definition.get_definitions()
+        .iter()
+        .map(|definition| *definition.get_id())
+        .collect();
+    ids.sort_unstable();
+
+    assert_eq!((2..=11).collect::<Vec<i32>>(), ids);
+}
+
+#[test]
+fn test_builds_trees_of_built_definitions() {
+    common::check_builds_ok(Tree::new(1, Sequence::new()
+        .child(Invert::new(PrintLog::new("first")))
+        .child(Fallback::new().child(PrintLog::new("second"))))
+        .build()
+        .unwrap());
+}
+
+#[test]
+fn test_rejects_composites_without_children() {
+    assert_eq!(Some(BehaviorTreeBuildingError::CompositeNodeWithoutChildren(3)),
+               Tree::new(1, Sequence::new().child(Fallback::new())).build().err());
+}
+
+#[test]
+fn test_rejects_nodes_with_other_ids() {
+    assert_eq!(Some(BehaviorTreeBuildingError::UnexpectedNodeId(2, 7)),
+               Tree::new(1, Node::new(|_, _| Arc::new(PrintLogActionNodeDefinition::new(7, "other".to_owned()))))
+                   .build()
+                   .err());
+}
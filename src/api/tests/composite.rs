@@ -1,19 +1,21 @@
 use std::ops::Deref;
 use std::sync::Arc;
 
-use buttercup_api::bts::{BehaviorTreeDefinition, BehaviorTreeNodeDefinition};
+use buttercup_api::bts::{BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
 use buttercup_api::bts::action::logging::PrintLogActionNodeDefinition;
+use buttercup_api::bts::composite::compensating::CompensatingSequenceCompositeNodeDefinition;
 use buttercup_api::bts::composite::fallback::FallbackCompositeNodeDefinition;
 use buttercup_api::bts::composite::parallel::ParallelCompositeNodeDefinition;
 use buttercup_api::bts::composite::sequence::SequenceCompositeNodeDefinition;
 use buttercup_api::bts::root::OneOffRootBTNodeDefinition;
+use buttercup_bts::node::composite::parallel::ParallelPolicy;
 
 mod common;
 
 #[test]
 fn test_builds_fallback_node_correctly() {
     let (children, composite_node_id) =
-        fallback_node_with_print_log_actions(vec![1]);
+        fallback_node_with_print_log_actions(2, vec![1]);
 
     build_and_check_bt_with_composite(children, composite_node_id);
 }
@@ -22,14 +24,16 @@ fn test_builds_fallback_node_correctly() {
 fn test_builds_multiple_fallback_nodes_correctly() {
     let (children, composite_node_id) =
         add_fallback_node(
+            100,
             vec![
-                fallback_node_with_print_log_actions(vec![1, 2, 3, 4]),
+                fallback_node_with_print_log_actions(101, vec![1, 2, 3, 4]),
                 add_fallback_node(
+                    102,
                     vec![
-                        fallback_node_with_print_log_actions(vec![5, 6]),
-                        fallback_node_with_print_log_actions(vec![7]),
-                        fallback_node_with_print_log_actions(vec![8, 9, 10])]),
-                fallback_node_with_print_log_actions(vec![11, 12])
+                        fallback_node_with_print_log_actions(103, vec![5, 6]),
+                        fallback_node_with_print_log_actions(104, vec![7]),
+                        fallback_node_with_print_log_actions(105, vec![8, 9, 10])]),
+                fallback_node_with_print_log_actions(106, vec![11, 12])
             ]
         );
 
@@ -39,7 +43,7 @@ fn test_builds_multiple_fallback_nodes_correctly() {
 #[test]
 fn test_builds_sequence_node_correctly() {
     let (children, composite_node_id) =
-        sequence_node_with_print_log_actions(vec![1]);
+        sequence_node_with_print_log_actions(2, vec![1]);
 
     build_and_check_bt_with_composite(children, composite_node_id);
 }
@@ -48,20 +52,23 @@ fn test_builds_sequence_node_correctly() {
 fn test_builds_multiple_sequence_nodes_correctly() {
     let (children, composite_node_id) =
         add_sequence_node(
+            100,
             vec![
-                sequence_node_with_print_log_actions(vec![1, 2, 3, 4]),
+                sequence_node_with_print_log_actions(101, vec![1, 2, 3, 4]),
                 add_sequence_node(
+                    102,
                     vec![
                         add_sequence_node(
+                            103,
                             vec![
-                                sequence_node_with_print_log_actions(vec![5, 6]),
-                                sequence_node_with_print_log_actions(vec![15, 16])
+                                sequence_node_with_print_log_actions(104, vec![5, 6]),
+                                sequence_node_with_print_log_actions(105, vec![15, 16])
                             ]),
-                        sequence_node_with_print_log_actions(vec![7]),
-                        sequence_node_with_print_log_actions(vec![8, 9, 10])
+                        sequence_node_with_print_log_actions(106, vec![7]),
+                        sequence_node_with_print_log_actions(107, vec![8, 9, 10])
                     ]),
-                sequence_node_with_print_log_actions(vec![11, 12]),
-                sequence_node_with_print_log_actions(vec![13, 14])
+                sequence_node_with_print_log_actions(108, vec![11, 12]),
+                sequence_node_with_print_log_actions(109, vec![13, 14])
             ]
         );
 
@@ -71,7 +78,7 @@ fn test_builds_multiple_sequence_nodes_correctly() {
 #[test]
 fn test_builds_parallel_node_correctly() {
     let (children, composite_node_id) =
-        parallel_node_with_print_log_actions(vec![1]);
+        parallel_node_with_print_log_actions(2, vec![1]);
 
     build_and_check_bt_with_composite(children, composite_node_id);
 }
@@ -80,61 +87,128 @@ fn test_builds_parallel_node_correctly() {
 fn test_builds_multiple_parallel_nodes_correctly() {
     let (children, fallback_node_id) =
         add_parallel_node(
+            100,
             vec![
-                parallel_node_with_print_log_actions(vec![1, 2, 3, 4]),
+                parallel_node_with_print_log_actions(101, vec![1, 2, 3, 4]),
                 add_parallel_node(
+                    102,
                     vec![
                         add_parallel_node(
+                            103,
                             vec![
-                                parallel_node_with_print_log_actions(vec![5, 6]),
-                                parallel_node_with_print_log_actions(vec![15, 16])
+                                parallel_node_with_print_log_actions(104, vec![5, 6]),
+                                parallel_node_with_print_log_actions(105, vec![15, 16])
                             ]),
-                        parallel_node_with_print_log_actions(vec![7]),
-                        parallel_node_with_print_log_actions(vec![8, 9, 10])]),
-                parallel_node_with_print_log_actions(vec![11, 12]),
-                parallel_node_with_print_log_actions(vec![13, 14]),
+                        parallel_node_with_print_log_actions(106, vec![7]),
+                        parallel_node_with_print_log_actions(107, vec![8, 9, 10])]),
+                parallel_node_with_print_log_actions(108, vec![11, 12]),
+                parallel_node_with_print_log_actions(109, vec![13, 14]),
                 add_parallel_node(
+                    110,
                     vec![
                         add_parallel_node(
+                            111,
                             vec![
-                                parallel_node_with_print_log_actions(vec![17, 18]),
-                                parallel_node_with_print_log_actions(vec![19, 20])
+                                parallel_node_with_print_log_actions(112, vec![17, 18]),
+                                parallel_node_with_print_log_actions(113, vec![19, 20])
                             ]),
-                        parallel_node_with_print_log_actions(vec![21]),
-                        parallel_node_with_print_log_actions(vec![22, 23, 24])])
+                        parallel_node_with_print_log_actions(114, vec![21]),
+                        parallel_node_with_print_log_actions(115, vec![22, 23, 24])])
             ]
         );
 
     build_and_check_bt_with_composite(children, fallback_node_id);
 }
 
-fn add_composite_node<F>(responses: Vec<(Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)>,
+#[test]
+fn test_builds_parallel_nodes_with_policies_correctly() {
+    for policy in [ParallelPolicy::RequireAll, ParallelPolicy::RequireOne, ParallelPolicy::RequireN(2)] {
+        let mut children = print_log_actions(vec![1, 2, 3]);
+        children.push(Arc::new(
+            ParallelCompositeNodeDefinition::with_policy(4, vec![1, 2, 3], policy)));
+
+        build_and_check_bt_with_composite(children, 4);
+    }
+}
+
+#[test]
+fn test_parallel_node_fails_on_too_many_required_successes() {
+    let mut children = print_log_actions(vec![1, 2]);
+    children.push(Arc::new(
+        ParallelCompositeNodeDefinition::with_policy(3, vec![1, 2], ParallelPolicy::RequireN(3))));
+
+    common::check_build_fails(
+        common::one_off_root_tree(3, children),
+        BehaviorTreeBuildingError::ParallelCompositeNodeBuildingError);
+}
+
+#[test]
+fn test_builds_compensating_sequence_node_correctly() {
+    let mut children = print_log_actions(vec![1, 2, 3]);
+    children.push(Arc::new(
+        CompensatingSequenceCompositeNodeDefinition::new(
+            4, vec![(1, Option::Some(2)), (3, Option::None)])));
+
+    build_and_check_bt_with_composite(children, 4);
+}
+
+#[test]
+fn test_compensating_sequence_node_fails_on_missing_compensation() {
+    let mut children = print_log_actions(vec![1, 3]);
+    children.push(Arc::new(
+        CompensatingSequenceCompositeNodeDefinition::new(
+            4, vec![(1, Option::Some(2)), (3, Option::None)])));
+
+    common::check_build_fails(
+        common::one_off_root_tree(4, children),
+        BehaviorTreeBuildingError::CouldNotFindChildDefinitionWithId(2));
+}
+
+#[test]
+fn test_composite_node_fails_on_children_of_the_same_id() {
+    let mut children = print_log_actions(vec![1, 2, 2]);
+    children.push(Arc::new(SequenceCompositeNodeDefinition::new(3, vec![1, 2])));
+
+    common::check_build_fails(
+        common::one_off_root_tree(3, children),
+        BehaviorTreeBuildingError::DuplicateNodeId(2));
+}
+
+fn add_composite_node<F>(id: i32,
+                         responses: Vec<(Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)>,
                          composite_node_provider: F)
                          -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)
-    where F: Fn(Vec<Arc<dyn BehaviorTreeNodeDefinition>>)
+    where F: Fn(i32, Vec<Arc<dyn BehaviorTreeNodeDefinition>>, Vec<i32>)
         -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    let mut children: Vec<Arc<dyn BehaviorTreeNodeDefinition>> =
+    let children_ids: Vec<i32> = responses
+        .iter()
+        .map(|entry| entry.1)
+        .collect();
+    let definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>> =
         responses
             .into_iter()
             .flat_map(|entry| entry.0)
             .collect();
 
-    composite_node_provider(children)
+    composite_node_provider(id, definitions, children_ids)
 }
 
-fn add_fallback_node(responses: Vec<(Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)>)
+fn add_fallback_node(id: i32,
+                     responses: Vec<(Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)>)
                      -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    add_composite_node(responses, fallback_node)
+    add_composite_node(id, responses, fallback_node)
 }
 
-fn add_parallel_node(responses: Vec<(Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)>)
+fn add_parallel_node(id: i32,
+                     responses: Vec<(Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)>)
                      -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    add_composite_node(responses, parallel_node)
+    add_composite_node(id, responses, parallel_node)
 }
 
-fn add_sequence_node(responses: Vec<(Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)>)
+fn add_sequence_node(id: i32,
+                     responses: Vec<(Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)>)
                      -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    add_composite_node(responses, common::sequence_node)
+    add_composite_node(id, responses, sequence_node)
 }
 
 fn build_and_check_bt_with_composite(children: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
@@ -146,50 +220,93 @@ fn build_and_check_bt_with_composite(children: Vec<Arc<dyn BehaviorTreeNodeDefin
     common::check_builds_ok(tree_definition);
 }
 
+fn composite_node<F>(id: i32,
+                     definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
+                     children_ids: Vec<i32>,
+                     constructor: F)
+                     -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)
+    where F: Fn(i32, Vec<i32>) -> Arc<dyn BehaviorTreeNodeDefinition> {
+    let mut response = Vec::new();
+
+    response.extend(definitions);
+
+    response.push(constructor(id, children_ids));
+
+    (response, id)
+}
+
 fn composite_node_with_print_log_actions<F>(composite_node_provider: F,
+                                            id: i32,
                                             ids: Vec<i32>)
                                             -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)
-    where F: Fn(Vec<Arc<dyn BehaviorTreeNodeDefinition>>)
+    where F: Fn(i32, Vec<Arc<dyn BehaviorTreeNodeDefinition>>, Vec<i32>)
         -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32)  {
-    let mut nodes: Vec<Arc<dyn BehaviorTreeNodeDefinition>> = Vec::new();
-
-    for id in ids {
-        nodes.push(Arc::new(
-            PrintLogActionNodeDefinition::new(
-                id, "Hello!".to_owned())));
-    }
-
-    composite_node_provider(nodes)
+    composite_node_provider(id, print_log_actions(ids.clone()), ids)
 }
 
-fn fallback_node(children: Vec<Arc<dyn BehaviorTreeNodeDefinition>>)
+fn fallback_node(id: i32,
+                 definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
+                 children_ids: Vec<i32>)
                  -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    common::composite_node(children,
+    composite_node(id,
+                   definitions,
+                   children_ids,
                    |id, children_ids|
                        Arc::new(FallbackCompositeNodeDefinition::new(id, children_ids))
     )
 }
 
-fn fallback_node_with_print_log_actions(ids: Vec<i32>)
+fn fallback_node_with_print_log_actions(id: i32,
+                                        ids: Vec<i32>)
                                         -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    composite_node_with_print_log_actions(fallback_node, ids)
+    composite_node_with_print_log_actions(fallback_node, id, ids)
 }
 
-fn parallel_node(children: Vec<Arc<dyn BehaviorTreeNodeDefinition>>)
+fn parallel_node(id: i32,
+                 definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
+                 children_ids: Vec<i32>)
                  -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    common::composite_node(children,
+    composite_node(id,
+                   definitions,
+                   children_ids,
                    |id, children_ids|
                        Arc::new(ParallelCompositeNodeDefinition::new(
                            id, children_ids, 1))
     )
 }
 
-fn parallel_node_with_print_log_actions(ids: Vec<i32>)
+fn parallel_node_with_print_log_actions(id: i32,
+                                        ids: Vec<i32>)
                                         -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    composite_node_with_print_log_actions(parallel_node, ids)
+    composite_node_with_print_log_actions(parallel_node, id, ids)
+}
+
+fn print_log_actions(ids: Vec<i32>) -> Vec<Arc<dyn BehaviorTreeNodeDefinition>> {
+    let mut nodes: Vec<Arc<dyn BehaviorTreeNodeDefinition>> = Vec::new();
+
+    for id in ids {
+        nodes.push(Arc::new(
+            PrintLogActionNodeDefinition::new(
+                id, "Hello!".to_owned())));
+    }
+
+    nodes
+}
+
+fn sequence_node(id: i32,
+                 definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
+                 children_ids: Vec<i32>)
+                 -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
+    composite_node(id,
+                   definitions,
+                   children_ids,
+                   |id, children_ids|
+                       Arc::new(SequenceCompositeNodeDefinition::new(id, children_ids))
+    )
 }
 
-fn sequence_node_with_print_log_actions(ids: Vec<i32>)
+fn sequence_node_with_print_log_actions(id: i32,
+                                        ids: Vec<i32>)
                                         -> (Vec<Arc<dyn BehaviorTreeNodeDefinition>>, i32) {
-    composite_node_with_print_log_actions(common::sequence_node, ids)
+    composite_node_with_print_log_actions(sequence_node, id, ids)
 }
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use buttercup_api::bts::BehaviorTreeDefinitionService;
+use buttercup_api::debug::{self, DebugSessionError, DebugSessionService};
+use buttercup_api::document::parse_payload;
+use buttercup_api::engine::ButtercupEngine;
+use buttercup_bts::debug::DebugEvent;
+use buttercup_bts::tick::TickStatus;
+use buttercup_bts::tree::BehaviorTreeService;
+use buttercup_values::ValueType;
+
+const DEFINITION: &str = r#"{
+    "id": 1,
+    "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+    "nodes": [
+        { "type": "Condition", "id": 2, "child_id": 3, "expression": { "RelationExpression": {
+            "GreaterThan": { "specification": { "NameAndLiteral": ["age", { "Integer": [1, [18]] }] } }
+        } } },
+        { "type": "PrintLog", "id": 3, "message": "adult" }
+    ]
+}"#;
+
+#[actix_rt::test]
+async fn test_steps_through_evaluations() {
+    let tree_service = Arc::new(BehaviorTreeService::default());
+    let engine = ButtercupEngine::new(tree_service.clone(), Arc::new(BehaviorTreeDefinitionService::default()));
+    engine.load_definition(DEFINITION, 1).await.unwrap();
+
+    let debug_service = DebugSessionService::default();
+    let payload = parse_payload(r#"{ "age": { "Integer": [1, [30]] } }"#).unwrap();
+    let session = debug_service.start(&tree_service.get_by_id(&1).unwrap(), &payload).await.unwrap();
+    let session_id = *session.get_session_id();
+
+    assert_eq!(&Result::Ok(TickStatus::Success), session.get_result());
+
+    let states: Vec<_> = (0..*session.get_steps())
+        .map(|_| debug_service.step(&session_id).unwrap())
+        .collect();
+    let condition_state = states.iter()
+        .find(|state| matches!(state.get_event(), Option::Some(DebugEvent::ConditionEvaluated(2, true))))
+        .unwrap();
+
+    assert_eq!(&vec![(1, 2)], condition_state.get_pending_edges());
+    assert_eq!(Option::Some(&true), condition_state.get_condition_results().get(&2));
+    assert!(states.last().unwrap().is_finished());
+
+    assert!(debug_service.close(&session_id));
+    assert_eq!(Option::None, debug_service.step(&session_id));
+}
+
+#[actix_rt::test]
+async fn test_drops_sessions_not_stepped_through_within_ttl() {
+    let tree_service = Arc::new(BehaviorTreeService::default());
+    let engine = ButtercupEngine::new(tree_service.clone(), Arc::new(BehaviorTreeDefinitionService::default()));
+    engine.load_definition(DEFINITION, 1).await.unwrap();
+
+    let tree = tree_service.get_by_id(&1).unwrap();
+    let payload = parse_payload(r#"{ "age": { "Integer": [1, [30]] } }"#).unwrap();
+    let kept_service = DebugSessionService::default();
+    let expiring_service = DebugSessionService::default().with_session_ttl(Duration::from_secs(0));
+
+    for _ in 0..256 {
+        kept_service.start(&tree, &payload).await.unwrap();
+        expiring_service.start(&tree, &payload).await.unwrap();
+    }
+
+    assert_eq!(Result::Err(DebugSessionError::TooManySessions(256)),
+               kept_service.start(&tree, &payload).await.map(|_| ()));
+
+    let session = expiring_service.start(&tree, &payload).await.unwrap();
+    expiring_service.start(&tree, &payload).await.unwrap();
+
+    assert_eq!(Option::None, expiring_service.step(session.get_session_id()));
+}
+
+#[actix_rt::test]
+async fn test_explains_evaluations() {
+    let tree_service = Arc::new(BehaviorTreeService::default());
+    let engine = ButtercupEngine::new(tree_service.clone(), Arc::new(BehaviorTreeDefinitionService::default()));
+    engine.load_definition(DEFINITION, 1).await.unwrap();
+
+    let payload = parse_payload(r#"{ "age": { "Integer": [1, [12]] } }"#).unwrap();
+    let explanation = debug::explain(&tree_service.get_by_id(&1).unwrap(), &payload).await;
+
+    assert_eq!(&Result::Ok(TickStatus::Failure), explanation.get_result());
+    assert_eq!(vec![(1, 2)], explanation.get_path().get_edges());
+    assert_eq!(&Option::Some(false), explanation.get_path().get_visited_nodes()[1].get_condition_passed());
+    assert_eq!(Option::Some(&ValueType::Integer), explanation.get_value_types().get("age"));
+}
@@ -1,8 +1,25 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use buttercup_api::bts::{BehaviorTreeBuildingError, BehaviorTreeBuildingService, BehaviorTreeDefinitionService};
 use buttercup_api::bts::action::logging::PrintLogActionNodeDefinition;
 use buttercup_api::bts::decorator::condition::ConditionDecoratorNodeDefinition;
-use buttercup_conditions::ConditionExpression;
+use buttercup_api::bts::decorator::guard::GuardDecoratorNodeDefinition;
+use buttercup_api::bts::decorator::repeat::RepeatDecoratorNodeDefinition;
+use buttercup_api::bts::decorator::retry::RetryDecoratorNodeDefinition;
+use buttercup_api::bts::decorator::timeout::TimeoutDecoratorNodeDefinition;
+use buttercup_bts::node::decorator::repeat::RepeatPolicy;
+use buttercup_bts::tree::BehaviorTreeService;
+use buttercup_conditions::{ConditionExpression, ConditionExpressionError, RelationalExpression, RelationalExpressionSpecification};
+use buttercup_conditions::pattern::MatchesRelationalExpression;
+use buttercup_conditions::relational::{EqualsRelationalExpression, LessThanRelationalExpression};
+use buttercup_endpoints::ArgumentDefinition;
+use buttercup_values::{ValueHolder, ValuesPayload, ValueType};
+use buttercup_values::extractors::ValueExtractionPolicy;
+use buttercup_values::quantity::{Quantity, Unit};
+use buttercup_values::symbol::Symbol;
+use buttercup_variables::VariableSpecification;
 
 mod common;
 
@@ -23,4 +40,126 @@ fn test_builds_ok_with_single_decorator_node() {
                                   ]);
 
     common::check_builds_ok(tree_definition);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_builds_ok_with_nested_repeat_retry_and_timeout_nodes() {
+    let tree_definition =
+        common::one_off_root_tree(1,
+                                  vec![
+                                      Arc::new(
+                                          RepeatDecoratorNodeDefinition::new(
+                                              1, 2, RepeatPolicy::Times(3))),
+                                      Arc::new(
+                                          RetryDecoratorNodeDefinition::new(
+                                              2, 3, 2,
+                                              VariableSpecification::Literal(Arc::new(Duration::from_millis(100))))),
+                                      Arc::new(
+                                          TimeoutDecoratorNodeDefinition::new(
+                                              3, 4,
+                                              VariableSpecification::VariableName("timeout".to_owned().into()))),
+                                      Arc::new(
+                                          PrintLogActionNodeDefinition::new(
+                                              4,
+                                              "I'm a decorator child node.".to_owned()))
+                                  ]);
+
+    common::check_builds_ok(tree_definition);
+}
+
+#[test]
+fn test_build_fails_with_pattern_over_budget() {
+    let tree_definition =
+        common::one_off_root_tree(1,
+                                  vec![
+                                      Arc::new(
+                                          ConditionDecoratorNodeDefinition::new(
+                                              1, 2,
+                                              ConditionExpression::RelationExpression(
+                                                  RelationalExpression::Matches(
+                                                      MatchesRelationalExpression::new(
+                                                          RelationalExpressionSpecification::NameAndLiteral(
+                                                              "name".to_owned(),
+                                                              ValueHolder::from("a".repeat(2000)))))))),
+                                      Arc::new(
+                                          PrintLogActionNodeDefinition::new(
+                                              2,
+                                              "I'm a decorator child node.".to_owned()))
+                                  ]);
+
+    common::check_build_fails(tree_definition,
+                              BehaviorTreeBuildingError::InvalidConditionExpression(
+                                  1, ConditionExpressionError::PatternTooLong(2000, 1024)));
+}
+
+#[test]
+fn test_build_fails_with_unit_mismatch() {
+    let definition_service = BehaviorTreeDefinitionService::default();
+    definition_service.insert(
+        common::one_off_root_tree(1,
+                                  vec![
+                                      Arc::new(
+                                          ConditionDecoratorNodeDefinition::new(
+                                              1, 2,
+                                              ConditionExpression::RelationExpression(
+                                                  RelationalExpression::LessThan(
+                                                      LessThanRelationalExpression::new(
+                                                          RelationalExpressionSpecification::NameAndLiteral(
+                                                              "limit".to_owned(),
+                                                              ValueHolder::Quantity(
+                                                                  "12.5 EUR".parse::<Quantity>().unwrap()))))))),
+                                      Arc::new(
+                                          PrintLogActionNodeDefinition::new(
+                                              2,
+                                              "I'm a decorator child node.".to_owned()))
+                                  ]));
+
+    let building_service =
+        BehaviorTreeBuildingService::new(Arc::new(BehaviorTreeService::default()), Arc::new(definition_service))
+            .with_static_values(Arc::new(ValuesPayload::singleton(
+                "limit".to_owned(),
+                ValueHolder::Quantity("100 USD".parse::<Quantity>().unwrap()))));
+
+    match building_service.build(&1) {
+        Ok(_) => panic!("Expected Error."),
+        Err(err) => assert_eq!(BehaviorTreeBuildingError::InvalidConditionExpression(
+                                   1, ConditionExpressionError::UnitMismatch(
+                                       Unit::Currency("USD".to_owned()), Unit::Currency("EUR".to_owned()))),
+                               err)
+    }
+}
+
+#[test]
+fn test_build_fails_with_undeclared_symbol() {
+    let mut requirements = HashMap::new();
+    requirements.insert("status".to_owned(),
+                        ArgumentDefinition::new(1,
+                                                "status".to_owned(),
+                                                ValueType::Symbol,
+                                                ValueExtractionPolicy::Strict,
+                                                1)
+                            .with_symbols(vec!["ACTIVE".to_owned(), "SUSPENDED".to_owned()]));
+
+    let tree_definition =
+        common::one_off_root_tree(1,
+                                  vec![
+                                      Arc::new(GuardDecoratorNodeDefinition::new(1, 2, requirements)),
+                                      Arc::new(
+                                          ConditionDecoratorNodeDefinition::new(
+                                              2, 3,
+                                              ConditionExpression::RelationExpression(
+                                                  RelationalExpression::Equals(
+                                                      EqualsRelationalExpression::new(
+                                                          RelationalExpressionSpecification::NameAndLiteral(
+                                                              "status".to_owned(),
+                                                              ValueHolder::Symbol(Symbol::new("DELETED").unwrap()))))))),
+                                      Arc::new(
+                                          PrintLogActionNodeDefinition::new(
+                                              3,
+                                              "I'm a decorator child node.".to_owned()))
+                                  ]);
+
+    common::check_build_fails(tree_definition,
+                              BehaviorTreeBuildingError::InvalidConditionExpression(
+                                  2, ConditionExpressionError::UnknownSymbol("status".to_owned(), "DELETED".to_owned())));
+}
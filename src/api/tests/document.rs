@@ -0,0 +1,367 @@
+use std::sync::Arc;
+
+use buttercup_api::bts::{BehaviorTreeBuildingError, BehaviorTreeDefinitionService};
+use buttercup_api::complexity::{ComplexityLimit, ComplexityViolation, DefinitionLimits};
+use buttercup_api::document::{parse_payload, BehaviorTreeDocument, DefinitionDocumentError, DefinitionDocumentQuery, DefinitionDocumentService, DefinitionDocumentServiceError, DefinitionStatus, MetadataField, RootDefinitionDocument, NO_VERSION};
+use buttercup_api::engine::{ButtercupEngine, EngineError};
+use buttercup_bts::tick::TickStatus;
+use buttercup_values::ValuesPayload;
+
+#[actix_rt::test]
+async fn test_builds_definition_from_json() {
+    let document = BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "Sequence", "id": 2, "children_ids": [3, 4] },
+            { "type": "PrintLog", "id": 3, "message": "hello" },
+            { "type": "Invert", "id": 4, "child_id": 5 },
+            { "type": "SetValue", "id": 5, "value_name": "name", "value": { "Boolean": true } }
+        ],
+        "fixtures": [{ "payload": { "values": {}, "keys": [] }, "expected_status": "Failure" }]
+    }"#).unwrap();
+    let engine = ButtercupEngine::default();
+
+    engine.insert_definition(document.into(), 1).unwrap();
+    engine.activate(&1, &1).await.unwrap();
+
+    assert_eq!(Result::Ok(TickStatus::Failure), engine.evaluate(&1, &ValuesPayload::empty()).await);
+}
+
+#[actix_rt::test]
+async fn test_rejects_payloads_with_too_long_numbers() {
+    let engine = ButtercupEngine::default().with_max_number_digits(10);
+
+    engine.load_definition(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#, 1).await.unwrap();
+
+    let payload = parse_payload(r#"{ "amount": { "Integer": [1, [4294967295, 4294967295, 4294967295]] } }"#).unwrap();
+
+    assert_eq!(Result::Err(EngineError::NumberTooLong("amount".to_owned(), 10)),
+               engine.evaluate(&1, &payload).await);
+}
+
+#[test]
+fn test_reports_malformed_documents() {
+    assert!(matches!(BehaviorTreeDocument::from_json(r#"{ "id": 1 }"#),
+                     Result::Err(DefinitionDocumentError::DeserializeError(_))));
+}
+
+#[actix_rt::test]
+async fn test_assigns_ids_to_named_nodes() {
+    let document = BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": "main" },
+        "nodes": [
+            { "type": "Sequence", "id": "main", "children_ids": ["greet", 2, "negate"] },
+            { "type": "PrintLog", "id": "greet", "message": "hello" },
+            { "type": "PrintLog", "id": 2, "message": "legacy" },
+            { "type": "Invert", "id": "negate", "child_id": "set" },
+            { "type": "SetValue", "id": "set", "value_name": "name", "value": { "Boolean": true } }
+        ]
+    }"#).unwrap();
+
+    assert_eq!(&RootDefinitionDocument::OneOff { id: 1, child_id: 3 }, document.get_root());
+    assert_eq!(vec![4, 2, 5], document.get_nodes()[0].get_children_ids());
+    assert_eq!(vec![6], document.get_nodes()[3].get_children_ids());
+
+    let engine = ButtercupEngine::default();
+    engine.insert_definition(document.into(), 1).unwrap();
+    engine.activate(&1, &1).await.unwrap();
+
+    assert_eq!(Result::Ok(TickStatus::Failure), engine.evaluate(&1, &ValuesPayload::empty()).await);
+}
+
+#[test]
+fn test_reports_colliding_and_unknown_node_ids() {
+    assert_eq!(Some(DefinitionDocumentError::DuplicateNodeId(2)), BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "PrintLog", "id": 2, "message": "first" },
+            { "type": "PrintLog", "id": 2, "message": "second" }
+        ]
+    }"#).err());
+    assert_eq!(Some(DefinitionDocumentError::DuplicateNodeName("greet".to_owned())), BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": "greet" },
+        "nodes": [
+            { "type": "PrintLog", "id": "greet", "message": "first" },
+            { "type": "PrintLog", "id": "greet", "message": "second" }
+        ]
+    }"#).err());
+    assert_eq!(Some(DefinitionDocumentError::UnknownNodeName("missing".to_owned())), BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": "greet" },
+        "nodes": [{ "type": "Invert", "id": "greet", "child_id": "missing" }]
+    }"#).err());
+    assert_eq!(Some(DefinitionDocumentError::NodeIdOutOfRange(4294967298)), BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 4294967298 },
+        "nodes": [{ "type": "PrintLog", "id": 4294967298, "message": "truncated" }]
+    }"#).err());
+    assert_eq!(Some(DefinitionDocumentError::NodeIdOutOfRange(2147483648)), BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 2147483647, "child_id": "greet" },
+        "nodes": [{ "type": "PrintLog", "id": "greet", "message": "overflowing" }]
+    }"#).err());
+}
+
+#[test]
+fn test_validates_definitions_without_loading_them() {
+    let engine = ButtercupEngine::default();
+
+    assert_eq!(Result::Ok(2), engine.validate_definition(r#"{
+        "id": 2,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#));
+    assert_eq!(Result::Err(EngineError::BehaviorTreeBuildingError(
+        BehaviorTreeBuildingError::CouldNotFindChildDefinitionWithId(3))),
+               engine.validate_definition(r#"{
+        "id": 2,
+        "root": { "type": "OneOff", "id": 1, "child_id": 3 },
+        "nodes": []
+    }"#));
+    assert_eq!(Option::None, engine.get_active_version(&2));
+}
+
+#[test]
+fn test_rejects_documents_over_complexity_limits() {
+    let document_service = DefinitionDocumentService::new(Arc::new(BehaviorTreeDefinitionService::default()))
+        .with_limits(DefinitionLimits::default().with_max_nodes(3).with_max_expression_depth(2));
+    let json = r#"{
+        "id": 4,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "Condition", "id": 2, "child_id": 3, "expression": { "LogicalExpression": { "Not": {
+                "LogicalExpression": { "Not": { "ConstantExpression": true } }
+            } } } },
+            { "type": "Sequence", "id": 3, "children_ids": [4, 5] },
+            { "type": "PrintLog", "id": 4, "message": "hello" },
+            { "type": "PrintLog", "id": 5, "message": "world" }
+        ]
+    }"#;
+
+    match document_service.put(&4, json, Option::None) {
+        Err(DefinitionDocumentServiceError::TooComplex(4, report)) => {
+            assert_eq!((&5, &4, &3, &0), (report.get_nodes(), report.get_edges(),
+                                          report.get_expression_depth(), report.get_conditions_per_edge()));
+            assert_eq!(&vec![ComplexityViolation::new(ComplexityLimit::Nodes, 5, 3),
+                             ComplexityViolation::new(ComplexityLimit::ExpressionDepth, 3, 2)],
+                       report.get_violations());
+        },
+        other => panic!("Expected document to be rejected, got: {:?}", other)
+    }
+}
+
+#[test]
+fn test_creates_documents_under_unused_ids() {
+    let document_service = DefinitionDocumentService::new(Arc::new(BehaviorTreeDefinitionService::default()));
+    let json = r#"{
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#;
+
+    assert_eq!(Result::Ok(1), document_service.put(&2, &json.replace("\"root\"", "\"id\": 2, \"root\""), Option::None));
+    assert_eq!(Result::Ok((1, 1)), document_service.create(json));
+    assert_eq!(Result::Ok((3, 1)), document_service.create(&json.replace("\"root\"", "\"id\": 1, \"root\"")));
+    assert!(matches!(document_service.create("{ \"root\": 1 }"),
+                     Result::Err(DefinitionDocumentServiceError::DefinitionDocumentError(_))));
+    assert_eq!(&3, BehaviorTreeDocument::from_json(document_service.get(&3).unwrap().get_json()).unwrap().get_id());
+}
+
+#[test]
+fn test_creates_documents_concurrently_under_distinct_ids() {
+    let document_service = Arc::new(DefinitionDocumentService::new(Arc::new(BehaviorTreeDefinitionService::default())));
+    let json = r#"{
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#;
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let document_service = document_service.clone();
+            std::thread::spawn(move || (0..25)
+                .map(|_| document_service.create(json).unwrap())
+                .collect::<Vec<(i32, u32)>>())
+        })
+        .collect();
+    let mut created: Vec<(i32, u32)> = threads.into_iter()
+        .flat_map(|thread| thread.join().unwrap())
+        .collect();
+    created.sort();
+
+    assert_eq!((1..=100).map(|tree_id| (tree_id, 1)).collect::<Vec<(i32, u32)>>(), created);
+}
+
+#[test]
+fn test_puts_new_versions_of_documents_when_expected_version_matches() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let document_service = DefinitionDocumentService::new(definition_service.clone());
+    let json = r#"{
+        "id": 4,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#;
+
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::VersionMismatch(4, Option::None)),
+               document_service.put(&4, json, Option::Some(&[1])));
+    assert_eq!(Result::Ok(1), document_service.put(&4, json, Option::None));
+    assert_eq!(Result::Ok(2), document_service.put(&4, json, Option::Some(&[1])));
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::VersionMismatch(4, Option::Some(2))),
+               document_service.put(&4, json, Option::Some(&[1])));
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::TreeIdMismatch(5, 4)),
+               document_service.put(&5, json, Option::None));
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::VersionMismatch(4, Option::Some(2))),
+               document_service.put(&4, json, Option::Some(&[NO_VERSION])));
+
+    let document = document_service.get(&4).unwrap();
+
+    assert_eq!(&2, document.get_version());
+    assert_eq!(json, document.get_json());
+    assert!(definition_service.activate(&4, &2).is_ok());
+}
+
+#[test]
+fn test_lists_documents_page_by_page() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let document_service = DefinitionDocumentService::new(definition_service.clone());
+
+    for (id, name, tag) in [(1, "Adults only", "age"), (2, "Weekend offer", "time"),
+                       (3, "Teenagers", "age"), (4, "Night offer", "time")] {
+        document_service.put(&id, &format!(r#"{{
+            "id": {}, "name": "{}", "tags": ["{}"],
+            "root": {{ "type": "OneOff", "id": 1, "child_id": 2 }},
+            "nodes": [{{ "type": "PrintLog", "id": 2, "message": "hello" }}]
+        }}"#, id, name, tag), Option::None).unwrap();
+    }
+    definition_service.activate(&3, &1).unwrap();
+
+    let get_ids = |query: DefinitionDocumentQuery| document_service.list(&query)
+        .get_items()
+        .iter()
+        .map(|summary| *summary.get_id())
+        .collect::<Vec<i32>>();
+
+    let first_page = document_service.list(&DefinitionDocumentQuery::default().with_limit(3));
+
+    assert_eq!(&Option::Some(3), first_page.get_next_cursor());
+    assert_eq!(vec![4], get_ids(DefinitionDocumentQuery::default().with_limit(3).with_cursor(3)));
+    assert_eq!(vec![1, 3], get_ids(DefinitionDocumentQuery::default().with_tag("age".to_owned())));
+    assert_eq!(vec![3], get_ids(DefinitionDocumentQuery::default().with_status(DefinitionStatus::Active)));
+    assert_eq!(vec![2, 4], get_ids(DefinitionDocumentQuery::default().with_search("OFFER".to_owned())));
+}
+
+#[test]
+fn test_requires_configured_metadata() {
+    let document_service = DefinitionDocumentService::new(Arc::new(BehaviorTreeDefinitionService::default()))
+        .with_required_metadata(vec![MetadataField::Owner, MetadataField::Team]);
+    let get_json = |metadata: &str| format!(r#"{{
+        "id": 6, "metadata": {},
+        "root": {{ "type": "OneOff", "id": 1, "child_id": 2 }},
+        "nodes": [{{ "type": "PrintLog", "id": 2, "message": "hello" }}]
+    }}"#, metadata);
+
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::MissingMetadata(6, vec![MetadataField::Team])),
+               document_service.put(&6, &get_json(r#"{ "owner": "jane" }"#), Option::None));
+    assert_eq!(Result::Ok(1),
+               document_service.put(&6, &get_json(r#"{ "owner": "jane", "team": "risk" }"#), Option::None));
+    assert_eq!(1, document_service
+        .list(&DefinitionDocumentQuery::default().with_team("risk".to_owned()))
+        .get_items()
+        .len());
+    assert!(document_service
+        .list(&DefinitionDocumentQuery::default().with_owner("john".to_owned()))
+        .get_items()
+        .is_empty());
+}
+
+#[test]
+fn test_moves_definitions_through_their_lifecycle() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let document_service = DefinitionDocumentService::new(definition_service.clone());
+
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::TreeOfGivenIdNotFound(7)),
+               document_service.transition(&7, DefinitionStatus::Deprecated));
+    assert_eq!(Result::Ok(Option::None), document_service.check_evaluation(&7));
+
+    document_service.put(&7, r#"{
+        "id": 7,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#, Option::None).unwrap();
+
+    assert_eq!(Option::Some(DefinitionStatus::Draft), document_service.get_status(&7));
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::InvalidStatusTransition(
+        7, DefinitionStatus::Draft, DefinitionStatus::Deprecated)),
+               document_service.transition(&7, DefinitionStatus::Deprecated));
+
+    definition_service.activate(&7, &1).unwrap();
+
+    assert_eq!(Result::Ok(DefinitionStatus::Deprecated),
+               document_service.transition(&7, DefinitionStatus::Deprecated));
+    assert_eq!(Result::Ok(Option::Some(DefinitionStatus::Deprecated)), document_service.check_evaluation(&7));
+    assert_eq!(Option::Some(&1), document_service.get_usage_metrics().get_deprecated_evaluations().get(&7));
+    assert_eq!(Result::Ok(DefinitionStatus::Archived),
+               document_service.transition(&7, DefinitionStatus::Archived));
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::TreeIsArchived(7)), document_service.check_evaluation(&7));
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::InvalidStatusTransition(
+        7, DefinitionStatus::Archived, DefinitionStatus::Active)),
+               document_service.transition(&7, DefinitionStatus::Active));
+    assert_eq!(Result::Ok(DefinitionStatus::Deprecated),
+               document_service.transition(&7, DefinitionStatus::Deprecated));
+    assert_eq!(Result::Ok(DefinitionStatus::Active),
+               document_service.transition(&7, DefinitionStatus::Active));
+}
+
+#[test]
+fn test_refuses_evaluations_of_disabled_trees() {
+    let document_service = DefinitionDocumentService::new(Arc::new(BehaviorTreeDefinitionService::default()));
+
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::TreeOfGivenIdNotFound(7)),
+               document_service.set_disabled(&7, true));
+    assert!(!document_service.is_disabled(&7));
+
+    document_service.put(&7, r#"{
+        "id": 7,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#, Option::None).unwrap();
+    document_service.set_disabled(&7, true).unwrap();
+
+    assert!(document_service.is_disabled(&7));
+    assert_eq!(Result::Err(DefinitionDocumentServiceError::TreeIsDisabled(7)), document_service.check_evaluation(&7));
+
+    document_service.set_disabled(&7, false).unwrap();
+
+    assert_eq!(Result::Ok(Option::Some(DefinitionStatus::Draft)), document_service.check_evaluation(&7));
+}
+
+#[test]
+fn test_refuses_changes_while_read_only() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let document_service = DefinitionDocumentService::new(definition_service.clone());
+    let json = r#"{
+        "id": 7,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#;
+
+    document_service.put(&7, json, Option::None).unwrap();
+    definition_service.set_read_only(true);
+
+    let read_only = DefinitionDocumentServiceError::BehaviorTreeBuildingError(BehaviorTreeBuildingError::ReadOnly);
+
+    assert_eq!(Result::Err(read_only.clone()), document_service.put(&7, json, Option::None));
+    assert_eq!(Result::Err(BehaviorTreeBuildingError::ReadOnly), definition_service.activate(&7, &1));
+    assert_eq!(Result::Err(read_only), document_service.transition(&7, DefinitionStatus::Deprecated));
+    assert_eq!(&1, document_service.get(&7).unwrap().get_version());
+
+    definition_service.set_read_only(false);
+
+    assert_eq!(Result::Ok(Option::None), definition_service.activate(&7, &1));
+}
@@ -0,0 +1,55 @@
+use buttercup_api::bts::BehaviorTreeBuildingService;
+use buttercup_api::document::{BehaviorTreeDocument, NodeDefinitionDocument};
+use buttercup_api::dsl::DslNode;
+use buttercup_api::tree;
+
+#[test]
+fn test_builds_documents_with_numbered_nodes() {
+    let document = tree! {
+        7 => sequence [
+            when (amount > 100 && tier == "gold" && second < third) -> log("gold"),
+            invert(signal("cancel")),
+            fallback [subtree(10), wait(250)],
+            parallel(1, [log("left"), when (flag == true) -> log("right")]),
+            set(done = 3),
+            node(DslNode::leaf(|id| NodeDefinitionDocument::ReceiveMessage { id, topic: "orders".to_owned() }))
+        ]
+    };
+
+    let expected = BehaviorTreeDocument::from_json(r#"{
+        "id": 7,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "PrintLog", "id": 4, "message": "gold" },
+            { "type": "Condition", "id": 3, "child_id": 4, "expression": { "LogicalExpression": { "And": [
+                { "RelationExpression": { "GreaterThan": { "specification": { "NameAndLiteral": ["amount", { "Integer": [1, [100]] }] } } } },
+                { "RelationExpression": { "Equals": { "specification": { "NameAndLiteral": ["tier", { "String": "gold" }] } } } },
+                { "RelationExpression": { "LessThan": { "specification": { "NameAndName": ["second", "third"] } } } }
+            ] } } },
+            { "type": "WaitForSignal", "id": 6, "name": "cancel" },
+            { "type": "Invert", "id": 5, "child_id": 6 },
+            { "type": "ExecuteSubTree", "id": 8, "tree_id": 10 },
+            { "type": "WaitDuration", "id": 9, "duration": { "Literal": { "secs": 0, "nanos": 250000000 } } },
+            { "type": "Fallback", "id": 7, "children_ids": [8, 9] },
+            { "type": "PrintLog", "id": 11, "message": "left" },
+            { "type": "PrintLog", "id": 13, "message": "right" },
+            { "type": "Condition", "id": 12, "child_id": 13, "expression": { "RelationExpression": { "Equals": {
+                "specification": { "NameAndLiteral": ["flag", { "Boolean": true }] } } } } },
+            { "type": "Parallel", "id": 10, "children_ids": [11, 12], "num_successes_to_succeed": 1 },
+            { "type": "SetValue", "id": 14, "value_name": "done", "value": { "Integer": [1, [3]] } },
+            { "type": "ReceiveMessage", "id": 15, "topic": "orders" },
+            { "type": "Sequence", "id": 2, "children_ids": [3, 5, 7, 10, 14, 15] }
+        ]
+    }"#).unwrap();
+
+    assert_eq!(serde_json::to_value(expected).unwrap(), serde_json::to_value(&document).unwrap());
+}
+
+#[test]
+fn test_builds_trees_from_documents() {
+    let document = tree! { 8 => when (count >= 3) -> invert(log("few")) };
+
+    BehaviorTreeBuildingService::default()
+        .build_definition(&document.into())
+        .expect("Expected the build to succeed!");
+}
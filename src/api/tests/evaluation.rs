@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use buttercup_api::bts::{BehaviorTreeBuildingError, BehaviorTreeDefinitionService};
+use buttercup_api::engine::{ButtercupEngine, EngineError};
+use buttercup_bts::budget::{EvaluationBudget, EvaluationResource};
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_bts::tree::BehaviorTreeService;
+use buttercup_values::ValuesPayload;
+
+// Visits three nodes, one more than the budget of the engines below allows.
+fn get_json(fallback_tree_id: Option<i32>) -> String {
+    format!(r#"{{
+        "id": 1,
+        "root": {{ "type": "OneOff", "id": 1, "child_id": 2 }},
+        "nodes": [
+            {{ "type": "Invert", "id": 2, "child_id": 3 }},
+            {{ "type": "PrintLog", "id": 3, "message": "too far" }}
+        ],
+        "fallback_tree_id": {}
+    }}"#, fallback_tree_id.map_or("null".to_owned(), |id| id.to_string()))
+}
+
+const FALLBACK: &str = r#"{
+    "id": 2,
+    "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+    "nodes": [{ "type": "PrintLog", "id": 2, "message": "fallback" }]
+}"#;
+
+fn new_engine() -> ButtercupEngine {
+    ButtercupEngine::default()
+        .with_evaluation_budget(EvaluationBudget::default().with_max_nodes_visited(2))
+}
+
+#[actix_rt::test]
+async fn test_evaluates_fallback_tree_on_failure() {
+    let engine = new_engine();
+    engine.load_definition(FALLBACK, 1).await.unwrap();
+    engine.load_definition(&get_json(Option::Some(2)), 1).await.unwrap();
+
+    let evaluation = engine.evaluate_with_fallback(&1, &ValuesPayload::empty()).await.unwrap();
+    let fallback = engine.evaluate_with_fallback(&2, &ValuesPayload::empty()).await.unwrap();
+
+    assert_eq!(&2, evaluation.get_tree_id());
+    assert_eq!(&TickStatus::Success, evaluation.get_result());
+    assert_eq!(&Option::Some(TickError::BudgetExceeded(3, EvaluationResource::NodesVisited)),
+               evaluation.get_fallback_reason());
+    assert_eq!(&Option::None, fallback.get_fallback_reason());
+    assert_eq!(Option::Some(&1), engine.get_fallback_counts().get(&1));
+    assert_eq!(Option::None, engine.get_fallback_counts().get(&2));
+}
+
+#[actix_rt::test]
+async fn test_returns_errors_without_fallback_tree() {
+    let engine = new_engine();
+    engine.load_definition(&get_json(Option::None), 1).await.unwrap();
+
+    assert_eq!(Result::Err(EngineError::TickError(TickError::BudgetExceeded(3, EvaluationResource::NodesVisited))),
+               engine.evaluate_with_fallback(&1, &ValuesPayload::empty()).await);
+    assert!(engine.get_fallback_counts().is_empty());
+}
+
+#[actix_rt::test]
+async fn test_rejects_definitions_with_missing_fallback_tree() {
+    let engine = new_engine();
+
+    assert_eq!(Result::Err(EngineError::BehaviorTreeBuildingError(BehaviorTreeBuildingError::CouldNotFindFallbackTreeWithId(2))),
+               engine.load_definition(&get_json(Option::Some(2)), 1).await);
+}
+
+#[actix_rt::test]
+async fn test_returns_errors_of_tree_when_fallback_tree_is_not_built() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let engine = ButtercupEngine::new(Arc::new(BehaviorTreeService::default()), definition_service.clone())
+        .with_evaluation_budget(EvaluationBudget::default().with_max_nodes_visited(2));
+    ButtercupEngine::new(Arc::new(BehaviorTreeService::default()), definition_service)
+        .load_definition(FALLBACK, 1)
+        .await
+        .unwrap();
+    engine.load_definition(&get_json(Option::Some(2)), 1).await.unwrap();
+
+    assert_eq!(Result::Err(EngineError::TickError(TickError::BudgetExceeded(3, EvaluationResource::NodesVisited))),
+               engine.evaluate_with_fallback(&1, &ValuesPayload::empty()).await);
+    assert!(engine.get_fallback_counts().is_empty());
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use buttercup_api::bts::{BehaviorTreeBuildingService, BehaviorTreeDefinitionService};
+use buttercup_api::document::{DefinitionDocumentService, DefinitionStatus};
+use buttercup_api::events::{DefinitionEvent, DefinitionEventService};
+use buttercup_bts::tree::BehaviorTreeService;
+
+const DOCUMENT: &str = r#"{
+    "id": 5,
+    "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+    "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+}"#;
+
+#[actix_rt::test]
+async fn test_publishes_definition_changes() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let events = Arc::new(DefinitionEventService::default());
+    let document_service = DefinitionDocumentService::new(definition_service.clone())
+        .with_events(events.clone());
+    let building_service = BehaviorTreeBuildingService::new(Arc::new(BehaviorTreeService::default()),
+                                                            definition_service)
+        .with_events(events.clone());
+    let mut receiver = events.subscribe();
+
+    document_service.put(&5, DOCUMENT, Option::None).unwrap();
+    building_service.activate(&5, &1).await.unwrap();
+    document_service.put(&5, DOCUMENT, Option::None).unwrap();
+    building_service.activate(&5, &2).await.unwrap();
+    building_service.activate(&5, &1).await.unwrap();
+    document_service.transition(&5, DefinitionStatus::Deprecated).unwrap();
+    document_service.transition(&5, DefinitionStatus::Deprecated).unwrap();
+
+    let mut published = Vec::new();
+    while let Ok(Some(event)) = receiver.try_next() {
+        published.push(event);
+    }
+
+    assert_eq!(vec![
+        DefinitionEvent::Created { tree_id: 5, version: 1 },
+        DefinitionEvent::Activated { tree_id: 5, version: 1 },
+        DefinitionEvent::Updated { tree_id: 5, version: 2 },
+        DefinitionEvent::Activated { tree_id: 5, version: 2 },
+        DefinitionEvent::RolledBack { tree_id: 5, version: 1 },
+        DefinitionEvent::StatusChanged { tree_id: 5, status: DefinitionStatus::Deprecated }
+    ], published);
+}
+
+#[test]
+fn test_drops_closed_subscribers() {
+    let events = DefinitionEventService::default();
+    let receiver = events.subscribe();
+    let _open = events.subscribe();
+
+    drop(receiver);
+    events.publish(DefinitionEvent::Created { tree_id: 1, version: 1 });
+
+    assert_eq!(1, events.get_subscribers_count());
+}
+
+#[actix_rt::test]
+async fn test_publishes_failed_self_tests() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let events = Arc::new(DefinitionEventService::default());
+    let document_service = DefinitionDocumentService::new(definition_service.clone());
+    let building_service = BehaviorTreeBuildingService::new(Arc::new(BehaviorTreeService::default()),
+                                                            definition_service)
+        .with_events(events.clone());
+
+    document_service.put(&5, r#"{
+        "id": 5,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }],
+        "fixtures": [{ "payload": { "values": {}, "keys": [] }, "expected_status": "Failure" }]
+    }"#, Option::None).unwrap();
+
+    let mut receiver = events.subscribe();
+
+    assert!(building_service.activate(&5, &1).await.is_err());
+    assert_eq!(Some(DefinitionEvent::SelfTestFailed { tree_id: 5, version: 1, failed_fixtures: 1 }),
+               receiver.try_next().unwrap());
+}
@@ -0,0 +1,37 @@
+use buttercup_api::bts::builder::{Fallback, Invert, PrintLog, Subtree, Tree};
+
+mod common;
+
+#[test]
+fn test_builds_fallback_node_correctly() {
+    common::check_builds_ok(Tree::new(1, Fallback::new().child(PrintLog::new("I'm a fallback!"))).build().unwrap());
+}
+
+#[test]
+fn test_builds_fallback_node_of_subtrees_correctly() {
+    let (first_subtree_id, second_subtree_id) = (10, 11);
+
+    let tree_definition = Tree::new(1, Fallback::new()
+        .child(Subtree(first_subtree_id))
+        .child(Subtree(second_subtree_id)))
+        .build()
+        .unwrap();
+
+    common::build_with_subtrees(tree_definition,
+                                vec![
+                                    Tree::new(first_subtree_id, Invert::new(PrintLog::new("I'm a first subtree!"))).build().unwrap(),
+                                    Tree::new(second_subtree_id, PrintLog::new("I'm a second subtree!")).build().unwrap()
+                                ])
+        .expect("Expected the build to succeed!");
+}
+
+#[test]
+fn test_builds_nested_fallback_nodes_correctly() {
+    common::check_builds_ok(Tree::new(1, Fallback::new()
+        .child(Fallback::new()
+            .child(Invert::new(PrintLog::new("first")))
+            .child(PrintLog::new("second")))
+        .child(PrintLog::new("third")))
+        .build()
+        .unwrap());
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use buttercup_api::document::parse_payload;
+use buttercup_api::engine::{ButtercupEngine, EngineError};
+use buttercup_blackboards::LocalBlackboardError;
+use buttercup_bts::faults::FaultInjector;
+use buttercup_bts::tick::{TickError, TickStatus};
+
+const DEFINITION: &str = r#"{
+    "id": 1,
+    "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+    "nodes": [
+        { "type": "Condition", "id": 2, "child_id": 3, "expression": { "RelationExpression": {
+            "GreaterThan": { "specification": { "NameAndLiteral": ["age", { "Integer": [1, [18]] }] } }
+        } } },
+        { "type": "PrintLog", "id": 3, "message": "adult" }
+    ]
+}"#;
+
+async fn tick(fault_injector: FaultInjector) -> Result<TickStatus, EngineError> {
+    let engine = ButtercupEngine::default().with_fault_injector(Arc::new(fault_injector));
+    engine.load_definition(DEFINITION, 1).await.unwrap();
+    engine.put_values(&1, &parse_payload(r#"{ "age": { "Integer": [1, [30]] } }"#).unwrap()).unwrap();
+
+    engine.tick(&1).await
+}
+
+#[actix_rt::test]
+async fn test_fails_ticks_on_poisoned_lookups() {
+    assert!(matches!(tick(FaultInjector::default().with_poisoned_value("age")).await,
+                     Err(EngineError::TickError(TickError::BlackboardError(2, LocalBlackboardError::LockPoisonedError(_))))));
+}
+
+#[actix_rt::test]
+async fn test_fails_ticks_on_aborted_lookups() {
+    assert!(matches!(tick(FaultInjector::default().with_aborted_after(0)).await,
+                     Err(EngineError::TickError(TickError::BlackboardError(2, LocalBlackboardError::AccessError(_))))));
+}
+
+#[actix_rt::test]
+async fn test_evaluates_conditions_with_malformed_values() {
+    let minor = parse_payload(r#"{ "age": { "Integer": [1, [12]] } }"#).unwrap().get(&"age".to_owned()).unwrap().clone();
+
+    assert_eq!(Result::Ok(TickStatus::Failure),
+               tick(FaultInjector::default().with_malformed_value("age", minor)).await);
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use buttercup_api::bts::{BehaviorTreeBuildingService, BehaviorTreeDefinitionService};
+use buttercup_api::document::{DefinitionDocumentService, DefinitionDocumentServiceError};
+use buttercup_api::files::{DefinitionFileError, DefinitionFileLoader};
+use buttercup_bts::tree::BehaviorTreeService;
+
+fn get_json(message: &str) -> String {
+    format!(r#"{{
+        "id": 8,
+        "root": {{ "type": "OneOff", "id": 1, "child_id": 2 }},
+        "nodes": [{{ "type": "PrintLog", "id": 2, "message": "{}" }}]
+    }}"#, message)
+}
+
+// Modification times are set explicitly, so that rewrites are seen on any file system.
+fn write(path: &Path,
+         json: &str,
+         modified_at: SystemTime) {
+    fs::write(path, json).unwrap();
+    fs::File::options().write(true).open(path).unwrap().set_modified(modified_at).unwrap();
+}
+
+#[actix_rt::test]
+async fn test_reloads_changed_definition_files() {
+    let directory = std::env::temp_dir().join(format!("buttercup-definitions-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("greeting.json");
+    let started_at = SystemTime::now();
+
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let tree_service = Arc::new(BehaviorTreeService::default());
+    let document_service = Arc::new(DefinitionDocumentService::new(definition_service.clone()));
+    let building_service = Arc::new(BehaviorTreeBuildingService::new(tree_service.clone(),
+                                                                     definition_service.clone()));
+    let loader = DefinitionFileLoader::new(&directory, document_service.clone(), building_service);
+
+    write(&path, &get_json("hello"), started_at);
+    fs::write(directory.join("notes.txt"), "not a definition").unwrap();
+
+    let loaded = loader.reload().await.unwrap();
+    let unchanged = loader.reload().await.unwrap();
+
+    write(&path, &get_json("hello again"), started_at + Duration::from_secs(1));
+    let reloaded = loader.reload().await.unwrap();
+
+    write(&path, "{ \"id\": 8 }", started_at + Duration::from_secs(2));
+    let broken = loader.reload().await.unwrap();
+    let retried = loader.reload().await.unwrap();
+
+    fs::remove_file(&path).unwrap();
+    let removed = loader.reload().await.unwrap();
+    let disabled = document_service.is_disabled(&8);
+
+    write(&path, &get_json("hello once more"), started_at + Duration::from_secs(3));
+    let restored = loader.reload().await.unwrap();
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(vec![(path.clone(), Result::Ok(1))], loaded);
+    assert!(unchanged.is_empty());
+    assert_eq!(vec![(path.clone(), Result::Ok(2))], reloaded);
+    assert!(matches!(broken.as_slice(),
+                     [(_, Result::Err(DefinitionFileError::DefinitionDocumentServiceError(
+                         DefinitionDocumentServiceError::DefinitionDocumentError(_))))]));
+    assert_eq!(broken, retried);
+    assert_eq!(vec![(path.clone(), Result::Err(DefinitionFileError::FileRemoved(8)))], removed);
+    assert!(disabled);
+    assert_eq!(vec![(path.clone(), Result::Ok(3))], restored);
+    assert!(!document_service.is_disabled(&8));
+    assert_eq!(Option::Some(3), definition_service.get_active_version(&8));
+    assert!(tree_service.get_by_id(&8).is_some());
+}
+
+#[actix_rt::test]
+async fn test_reports_missing_directories() {
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    let loader = DefinitionFileLoader::new(
+        Path::new("/nonexistent/buttercup-definitions"),
+        Arc::new(DefinitionDocumentService::new(definition_service.clone())),
+        Arc::new(BehaviorTreeBuildingService::new(Arc::new(BehaviorTreeService::default()), definition_service)));
+
+    assert!(loader.reload().await.is_err());
+}
@@ -0,0 +1,31 @@
+use buttercup_api::engine::ButtercupEngine;
+
+#[actix_rt::test]
+async fn test_reports_footprint_of_compiled_trees() {
+    let engine = ButtercupEngine::default();
+
+    engine.load_definition(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "Condition", "id": 2, "child_id": 3, "expression": { "RelationExpression": {
+                "GreaterThan": { "specification": { "NameAndLiteral": ["age", { "Integer": [1, [18]] }] } }
+            } } },
+            { "type": "PrintLog", "id": 3, "message": "adult" }
+        ]
+    }"#, 1).await.unwrap();
+    engine.load_definition(r#"{
+        "id": 2,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#, 1).await.unwrap();
+
+    let footprint = engine.get_footprint(&1).unwrap();
+    let smaller_footprint = engine.get_footprint(&2).unwrap();
+
+    assert_eq!(&3, footprint.get_node_count());
+    assert_eq!(&1, footprint.get_expression_count());
+    assert_eq!(&2, smaller_footprint.get_node_count());
+    assert_eq!(&0, smaller_footprint.get_expression_count());
+    assert!(footprint.get_estimated_bytes() > smaller_footprint.get_estimated_bytes());
+}
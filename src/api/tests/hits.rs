@@ -0,0 +1,31 @@
+use buttercup_api::document::parse_payload;
+use buttercup_api::engine::ButtercupEngine;
+use buttercup_bts::hits::{ConditionHitCounts, NodeHitCounts};
+
+#[actix_rt::test]
+async fn test_counts_node_and_condition_outcomes() {
+    let engine = ButtercupEngine::default();
+
+    engine.load_definition(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "Condition", "id": 2, "child_id": 3, "expression": { "RelationExpression": {
+                "GreaterThan": { "specification": { "NameAndLiteral": ["age", { "Integer": [1, [18]] }] } }
+            } } },
+            { "type": "PrintLog", "id": 3, "message": "adult" }
+        ],
+        "fixtures": [{ "payload": { "values": {}, "keys": [] }, "expected_status": "Failure" }]
+    }"#, 1).await.unwrap();
+
+    for age in &[30, 10, 40] {
+        let payload = parse_payload(&format!(r#"{{ "age": {{ "Integer": [1, [{}]] }} }}"#, age)).unwrap();
+        engine.evaluate(&1, &payload).await.unwrap();
+    }
+
+    let hits = engine.get_hit_counts(&1).unwrap();
+
+    assert_eq!(Option::Some(&ConditionHitCounts::new(2, 1)), hits.get_conditions().get(&2));
+    assert_eq!(Option::Some(&NodeHitCounts::new(2, 1, 0)), hits.get_nodes().get(&2));
+    assert_eq!(Option::Some(&NodeHitCounts::new(2, 0, 0)), hits.get_nodes().get(&3));
+}
@@ -0,0 +1,41 @@
+use buttercup_api::engine::ButtercupEngine;
+use buttercup_api::mutation::SurvivingMutant;
+
+fn get_definition(fixtures: &str) -> String {
+    format!(r#"{{
+        "id": 1,
+        "root": {{ "type": "OneOff", "id": 1, "child_id": 2 }},
+        "nodes": [
+            {{ "type": "Condition", "id": 2, "child_id": 3, "expression": {{ "RelationExpression": {{
+                "GreaterThan": {{ "specification": {{ "NameAndLiteral": ["age", {{ "Integer": [1, [18]] }}] }} }}
+            }} }} }},
+            {{ "type": "PrintLog", "id": 3, "message": "adult" }}
+        ],
+        "fixtures": [{}]
+    }}"#, fixtures)
+}
+
+fn get_fixture(age: u32, expected_status: &str) -> String {
+    format!(r#"{{ "payload": {{ "values": {{ "age": {{ "Integer": [1, [{}]] }} }}, "keys": ["age"] }},
+                  "expected_status": "{}" }}"#, age, expected_status)
+}
+
+#[actix_rt::test]
+async fn test_reports_mutants_not_killed_by_fixtures() {
+    let engine = ButtercupEngine::default();
+    let definition = get_definition(&[get_fixture(30, "Success"), get_fixture(10, "Failure")].join(","));
+
+    assert_eq!(Result::Ok(vec![
+        SurvivingMutant::new(2, "GreaterThan replaced with GreaterThanOrEquals".to_owned()),
+        SurvivingMutant::new(2, "18 replaced with 17".to_owned()),
+        SurvivingMutant::new(2, "18 replaced with 19".to_owned())
+    ]), engine.find_surviving_mutants(&definition).await);
+}
+
+#[actix_rt::test]
+async fn test_boundary_fixtures_kill_mutants() {
+    let engine = ButtercupEngine::default();
+    let definition = get_definition(&[get_fixture(19, "Success"), get_fixture(18, "Failure")].join(","));
+
+    assert_eq!(Result::Ok(Vec::new()), engine.find_surviving_mutants(&definition).await);
+}
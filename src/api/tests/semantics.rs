@@ -0,0 +1,52 @@
+use buttercup_api::engine::{ButtercupEngine, EngineError};
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_values::ValuesPayload;
+
+fn get_json(id: i32,
+            semantics_version: Option<&str>) -> String {
+    get_json_with_condition(id, "Condition", semantics_version)
+}
+
+fn get_json_with_condition(id: i32,
+                           condition_type: &str,
+                           semantics_version: Option<&str>) -> String {
+    format!(r#"{{
+        "id": {},
+        "root": {{ "type": "OneOff", "id": 1, "child_id": 2 }},
+        "nodes": [
+            {{
+                "type": "{}",
+                "id": 2,
+                "child_id": 3,
+                "expression": {{ "RelationExpression": {{ "Equals": {{ "specification": {{ "NameAndName": ["country", "home_country"] }} }} }} }}
+            }},
+            {{ "type": "PrintLog", "id": 3, "message": "at home" }}
+        ]{}
+    }}"#, id, condition_type, semantics_version.map_or(String::new(), |version| format!(r#", "semantics_version": "{}""#, version)))
+}
+
+#[actix_rt::test]
+async fn test_evaluates_definitions_by_their_semantics_version() {
+    let engine = ButtercupEngine::default();
+    engine.load_definition(&get_json(1, Option::None), 1).await.unwrap();
+    engine.load_definition(&get_json(2, Option::Some("V1")), 1).await.unwrap();
+    engine.load_definition(&get_json(3, Option::Some("V2")), 1).await.unwrap();
+    let payload = ValuesPayload::singleton("country".to_owned(), "PL".into());
+
+    assert_eq!(Result::Ok(TickStatus::Failure), engine.evaluate(&1, &payload).await);
+    assert_eq!(Result::Ok(TickStatus::Failure), engine.evaluate(&2, &payload).await);
+    assert_eq!(Result::Err(EngineError::TickError(TickError::MissingValues(2, vec!["home_country".to_owned()]))),
+               engine.evaluate(&3, &payload).await);
+}
+
+#[actix_rt::test]
+async fn test_reports_missing_values_of_reactive_conditions() {
+    let engine = ButtercupEngine::default();
+    engine.load_definition(&get_json_with_condition(1, "ReactiveCondition", Option::Some("V1")), 1).await.unwrap();
+    engine.load_definition(&get_json_with_condition(2, "ReactiveCondition", Option::Some("V2")), 1).await.unwrap();
+    let payload = ValuesPayload::singleton("country".to_owned(), "PL".into());
+
+    assert_eq!(Result::Ok(TickStatus::Failure), engine.evaluate(&1, &payload).await);
+    assert_eq!(Result::Err(EngineError::TickError(TickError::MissingValues(2, vec!["home_country".to_owned()]))),
+               engine.evaluate(&2, &payload).await);
+}
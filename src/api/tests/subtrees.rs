@@ -1,33 +1,27 @@
-use buttercup_api::bts::action::logging::PrintLogActionNodeDefinition;
-use buttercup_api::bts::action::subtree::ExecuteSubTreeActionNodeDefinition;
-use buttercup_api::bts::composite::sequence::SequenceCompositeNodeDefinition;
 use std::sync::Arc;
 
+use buttercup_api::bts::action::subtree::ExecuteSubTreeActionNodeDefinition;
+use buttercup_api::bts::builder::{Node, PrintLog, Sequence, Subtree, Tree};
+
 mod common;
 
 #[test]
 fn test_builds_subtree_node_correctly() {
     let subtree_id = 10;
 
-    let tree_definition =
-        common::one_off_root_tree(2,
-                                  vec![
-                                      Arc::new(
-                                          ExecuteSubTreeActionNodeDefinition::new(
-                                              2, subtree_id))
-                                  ]);
+    common::build_with_subtrees(Tree::new(1, Subtree(subtree_id)).build().unwrap(),
+                                vec![Tree::new(subtree_id, PrintLog::new("I'm a subtree!")).build().unwrap()])
+        .expect("Expected the build to succeed!");
+}
 
-    common::build_with_subtrees(tree_definition,
-                                vec![
-                                    common::one_off_root_tree_with_id(
-                                        2,
-                                        vec![
-                                            Arc::new(
-                                                PrintLogActionNodeDefinition::new(
-                                                2, "I'm a subtree!".to_owned())
-                                            )
-                                        ],
-                                        subtree_id)])
+#[test]
+fn test_builds_scoped_subtree_node_correctly() {
+    let subtree_id = 10;
+    let scoped_subtree = Node::new(move |id, _|
+        Arc::new(ExecuteSubTreeActionNodeDefinition::new(id, subtree_id).with_namespace("sub".to_owned())));
+
+    common::build_with_subtrees(Tree::new(1, scoped_subtree).build().unwrap(),
+                                vec![Tree::new(subtree_id, PrintLog::new("I'm a scoped subtree!")).build().unwrap()])
         .expect("Expected the build to succeed!");
 }
 
@@ -35,53 +29,18 @@ fn test_builds_subtree_node_correctly() {
 fn test_builds_multiple_subtree_nodes_correctly() {
     let (first_subtree_id, second_subtree_id, third_subtree_id) = (10, 11, 12);
 
-    let (definitions, sequence_node_id) = common::sequence_node(
-        vec![
-            Arc::new(
-                ExecuteSubTreeActionNodeDefinition::new(
-                    2, first_subtree_id)),
-            Arc::new(
-                ExecuteSubTreeActionNodeDefinition::new(
-                    2, second_subtree_id)),
-            Arc::new(
-                ExecuteSubTreeActionNodeDefinition::new(
-                    2, third_subtree_id))
-        ]
-    );
-
-    let tree_definition =
-        common::one_off_root_tree(sequence_node_id, definitions);
+    let tree_definition = Tree::new(1, Sequence::new()
+        .child(Subtree(first_subtree_id))
+        .child(Subtree(second_subtree_id))
+        .child(Subtree(third_subtree_id)))
+        .build()
+        .unwrap();
 
     common::build_with_subtrees(tree_definition,
                                 vec![
-                                    common::one_off_root_tree_with_id(
-                                        2,
-                                        vec![
-                                            Arc::new(
-                                                PrintLogActionNodeDefinition::new(
-                                                    2, "I'm a first subtree!".to_owned())
-                                            )
-                                        ],
-                                        first_subtree_id),
-                                    common::one_off_root_tree_with_id(
-                                        2,
-                                        vec![
-                                            Arc::new(
-                                                PrintLogActionNodeDefinition::new(
-                                                    2, "I'm a second subtree!".to_owned())
-                                            )
-                                        ],
-                                        second_subtree_id),
-                                    common::one_off_root_tree_with_id(
-                                        2,
-                                        vec![
-                                            Arc::new(
-                                                PrintLogActionNodeDefinition::new(
-                                                    2, "I'm a third subtree!".to_owned())
-                                            )
-                                        ],
-                                        third_subtree_id)
-                                ]
-    )
+                                    Tree::new(first_subtree_id, PrintLog::new("I'm a first subtree!")).build().unwrap(),
+                                    Tree::new(second_subtree_id, PrintLog::new("I'm a second subtree!")).build().unwrap(),
+                                    Tree::new(third_subtree_id, PrintLog::new("I'm a third subtree!")).build().unwrap()
+                                ])
         .expect("Expected the build to succeed!");
-}
\ No newline at end of file
+}
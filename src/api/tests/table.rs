@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use buttercup_api::document::BehaviorTreeDocument;
+use buttercup_api::engine::ButtercupEngine;
+use buttercup_api::table::{DecisionRule, DecisionTable, DecisionTableError, HitPolicy};
+use buttercup_bts::tick::TickStatus;
+use buttercup_conditions::{RelationalExpression, RelationalExpressionSpecification};
+use buttercup_conditions::relational::{EqualsRelationalExpression, GreaterThanOrEqualsRelationalExpression, LessThanRelationalExpression};
+use buttercup_values::{ValueHolder, ValuesPayload, ValueType};
+use buttercup_values::extractors::{ValueExtractionPolicy, ValueExtractorInput, ValueExtractorService};
+
+fn integer(value: u32) -> ValueHolder {
+    serde_json::from_str(&format!(r#"{{ "Integer": [1, [{}]] }}"#, value)).unwrap()
+}
+
+fn name_and_literal(name: &str,
+                    literal: u32) -> RelationalExpressionSpecification {
+    RelationalExpressionSpecification::NameAndLiteral(name.to_owned(), integer(literal))
+}
+
+fn discount_table() -> DecisionTable {
+    DecisionTable::new(vec!["age".to_owned(), "country".to_owned()], "discount".to_owned())
+        .with_rule(DecisionRule::new(
+            vec![vec![RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(name_and_literal("age", 18))),
+                      RelationalExpression::LessThan(LessThanRelationalExpression::new(name_and_literal("age", 26)))],
+                 vec![RelationalExpression::Equals(EqualsRelationalExpression::new(
+                     RelationalExpressionSpecification::NameAndLiteral("country".to_owned(), "PL".to_owned().into())))]],
+            integer(20)))
+        .with_rule(DecisionRule::new(
+            vec![vec![RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(name_and_literal("age", 65)))],
+                 vec![]],
+            integer(10)))
+        .with_rule(DecisionRule::new(vec![vec![], vec![]], integer(0)))
+}
+
+#[actix_rt::test]
+async fn test_compiles_tables_to_trees_and_back() {
+    let document = discount_table().to_document(7).unwrap();
+
+    assert_eq!(Result::Ok(discount_table()), DecisionTable::from_document(&document));
+
+    let engine = ButtercupEngine::default();
+    engine.load_definition(&serde_json::to_string(&document).unwrap(), 1).await.unwrap();
+
+    let payload = ValuesPayload::new(vec![("age".to_owned(), integer(70)), ("country".to_owned(), "PL".into())]
+        .into_iter()
+        .collect::<HashMap<String, ValueHolder>>());
+
+    assert_eq!(Result::Ok(TickStatus::Success), engine.evaluate(&7, &payload).await);
+}
+
+#[test]
+fn test_reads_tables_from_documents() {
+    let document = BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "Fallback", "id": 2, "children_ids": [3] },
+            { "type": "Condition", "id": 3, "child_id": 4, "expression": { "RelationExpression": { "Equals": { "specification": { "NameAndLiteral": ["tier", { "String": "gold" }] } } } } },
+            { "type": "SetValue", "id": 4, "value_name": "discount", "value": { "Boolean": true } }
+        ]
+    }"#).unwrap();
+    let table = DecisionTable::from_document(&document).unwrap();
+
+    assert_eq!(&vec!["tier".to_owned()], table.get_inputs());
+    assert_eq!("discount", table.get_output());
+    assert_eq!(1, table.get_rules()[0].get_cells()[0].len());
+}
+
+#[test]
+fn test_rejects_trees_which_are_not_tables() {
+    let document = BehaviorTreeDocument::from_json(r#"{
+        "id": 1,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "Fallback", "id": 2, "children_ids": [3, 5] },
+            { "type": "Condition", "id": 3, "child_id": 4, "expression": { "ConstantExpression": true } },
+            { "type": "SetValue", "id": 4, "value_name": "discount", "value": { "Boolean": true } },
+            { "type": "PrintLog", "id": 5, "message": "hello" }
+        ]
+    }"#).unwrap();
+
+    assert_eq!(Result::Err(DecisionTableError::UnexpectedNode(5)), DecisionTable::from_document(&document));
+    assert_eq!(Result::Err(DecisionTableError::CellOutOfColumn(0, "age".to_owned())),
+               DecisionTable::new(vec!["age".to_owned()], "discount".to_owned())
+                   .with_rule(DecisionRule::new(
+                       vec![vec![RelationalExpression::Equals(EqualsRelationalExpression::new(name_and_literal("tier", 1)))]],
+                       integer(0)))
+                   .to_document(1)
+                   .map(|_| ()));
+}
+
+const DISCOUNTS_CSV: &str = "age,country,discount\r
+>= 18,PL,20\r
+\r
+>= 65,-,10\r
+< 18,\"\"\"US\"\"\",5\r
+";
+
+#[actix_rt::test]
+async fn test_imports_tables_from_csv() {
+    let table = DecisionTable::from_csv(DISCOUNTS_CSV, HitPolicy::First).unwrap();
+
+    assert_eq!(&vec!["age".to_owned(), "country".to_owned()], table.get_inputs());
+    assert_eq!("discount", table.get_output());
+    assert_eq!(3, table.get_rules().len());
+    assert!(table.get_rules()[1].get_cells()[1].is_empty());
+    assert_eq!(&integer(5), table.get_rules()[2].get_output());
+
+    let engine = ButtercupEngine::default();
+    engine.load_definition(&serde_json::to_string(&table.to_document(3).unwrap()).unwrap(), 1).await.unwrap();
+
+    let payload = ValuesPayload::new(vec![("age".to_owned(), integer(12)), ("country".to_owned(), "US".into())]
+        .into_iter()
+        .collect::<HashMap<String, ValueHolder>>());
+
+    assert_eq!(Result::Ok(TickStatus::Success), engine.evaluate(&3, &payload).await);
+}
+
+#[test]
+fn test_rejects_invalid_csv() {
+    assert_eq!(Result::Err(DecisionTableError::MissingHeader), DecisionTable::from_csv("\n\n", HitPolicy::First));
+    assert_eq!(Result::Err(DecisionTableError::CellsCountMismatch(1, 3)),
+               DecisionTable::from_csv("age,discount\n1,2,3", HitPolicy::First).map(|_| ()));
+    assert_eq!(Result::Err(DecisionTableError::UnterminatedQuote(1)),
+               DecisionTable::from_csv("age,discount\n\"1,2", HitPolicy::First).map(|_| ()));
+    assert_eq!(Result::Err(DecisionTableError::InvalidCell(1, 0, "missing literal".to_owned())),
+               DecisionTable::from_csv("age,discount\n>=,2", HitPolicy::First).map(|_| ()));
+}
+
+#[test]
+fn test_checks_hit_policies() {
+    let overlapping = "age,discount\n>= 18,10\n< 30,10\n";
+    let disjoint = "age,discount\n>= 18,10\n< 18,20\n";
+
+    assert_eq!(Result::Err(DecisionTableError::OverlappingRules(0, 1)),
+               DecisionTable::from_csv(overlapping, HitPolicy::Unique).unwrap().to_document(1).map(|_| ()));
+    assert!(DecisionTable::from_csv(overlapping, HitPolicy::Any).unwrap().to_document(1).is_ok());
+    assert!(DecisionTable::from_csv(disjoint, HitPolicy::Unique).unwrap().to_document(1).is_ok());
+    assert_eq!(Result::Err(DecisionTableError::OverlappingRules(0, 1)),
+               DecisionTable::from_csv("age,discount\n>= 18,10\n18,20\n", HitPolicy::Any)
+                   .unwrap()
+                   .to_document(1)
+                   .map(|_| ()));
+}
+
+#[actix_rt::test]
+async fn test_reads_string_literals_as_declared_input_types() {
+    let table = DecisionTable::from_csv("day,discount\n\"\"\"Monday\"\"\",10\n", HitPolicy::First)
+        .unwrap()
+        .with_input_type("day".to_owned(), ValueType::DayOfWeek);
+    let monday = ValueExtractorService::extract(&ValueExtractorInput::new(
+        &serde_json::Value::String("Monday".to_owned()), &ValueType::DayOfWeek, &ValueExtractionPolicy::Lax)).unwrap();
+
+    let engine = ButtercupEngine::default();
+    engine.load_definition(&serde_json::to_string(&table.to_document(7).unwrap()).unwrap(), 1).await.unwrap();
+
+    assert_eq!(Result::Ok(TickStatus::Success),
+               engine.evaluate(&7, &ValuesPayload::singleton("day".to_owned(), monday)).await);
+    assert_eq!(Result::Ok(TickStatus::Failure),
+               engine.evaluate(&7, &ValuesPayload::singleton("day".to_owned(), "Monday".into())).await);
+}
+
+#[test]
+fn test_rejects_inputs_of_mixed_types() {
+    assert_eq!(Result::Err(DecisionTableError::InputTypeMismatch(1, "day".to_owned())),
+               DecisionTable::from_csv("day,discount\n\"\"\"Monday\"\"\",10\n1,20\n", HitPolicy::First)
+                   .unwrap()
+                   .to_document(1)
+                   .map(|_| ()));
+    assert_eq!(Result::Err(DecisionTableError::InputTypeMismatch(0, "day".to_owned())),
+               DecisionTable::from_csv("day,discount\n\"\"\"Someday\"\"\",10\n", HitPolicy::First)
+                   .unwrap()
+                   .with_input_type("day".to_owned(), ValueType::DayOfWeek)
+                   .to_document(1)
+                   .map(|_| ()));
+}
+
+#[actix_rt::test]
+async fn test_imports_feel_cells() {
+    let table = DecisionTable::from_csv("age,country,discount
+[18..26),\"\"\"PL\"\", \"\"CZ\"\"\",20
+\"(65..120]\",\"not(\"\"US\"\", \"\"CA\"\")\",10
+not(< 18),-,5
+", HitPolicy::First).unwrap();
+
+    assert_eq!(2, table.get_rules()[0].get_cells()[0].len());
+    assert_eq!(2, table.get_rules()[1].get_cells()[1].len());
+    assert_eq!(1, table.get_rules()[2].get_cells()[0].len());
+
+    let engine = ButtercupEngine::default();
+    engine.load_definition(&serde_json::to_string(&table.to_document(4).unwrap()).unwrap(), 1).await.unwrap();
+
+    let evaluate = |age: u32, country: &str| {
+        let payload = ValuesPayload::new(vec![("age".to_owned(), integer(age)), ("country".to_owned(), country.into())]
+            .into_iter()
+            .collect::<HashMap<String, ValueHolder>>());
+        let engine = &engine;
+
+        async move { engine.evaluate(&4, &payload).await }
+    };
+
+    assert_eq!(Result::Ok(TickStatus::Success), evaluate(20, "CZ").await);
+    assert_eq!(Result::Ok(TickStatus::Success), evaluate(70, "DE").await);
+    assert_eq!(Result::Ok(TickStatus::Success), evaluate(40, "US").await);
+    assert_eq!(Result::Ok(TickStatus::Failure), evaluate(12, "US").await);
+}
+
+#[test]
+fn test_checks_hit_policies_of_feel_cells() {
+    assert!(DecisionTable::from_csv("age,discount\n[18..26),10\n[26..65],20\n", HitPolicy::Unique)
+        .unwrap()
+        .to_document(1)
+        .is_ok());
+    assert!(DecisionTable::from_csv("tier,discount\n\"\"\"gold\"\", \"\"silver\"\"\",10\nnot(\"silver\"),20\n",
+                                    HitPolicy::Unique)
+        .unwrap()
+        .to_document(1)
+        .is_err());
+    assert!(DecisionTable::from_csv("tier,discount\n\"\"\"gold\"\", \"\"silver\"\"\",10\nbronze,20\n", HitPolicy::Unique)
+        .unwrap()
+        .to_document(1)
+        .is_ok());
+    assert_eq!(Result::Err(DecisionTableError::InvalidCell(1, 0, "unsupported negation of [1..2]".to_owned())),
+               DecisionTable::from_csv("age,discount\nnot([1..2]),10\n", HitPolicy::First).map(|_| ()));
+}
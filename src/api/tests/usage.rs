@@ -0,0 +1,71 @@
+use buttercup_api::usage::DefinitionUsageService;
+
+#[test]
+fn test_counts_evaluations_per_caller() {
+    let usage_service = DefinitionUsageService::default();
+
+    usage_service.record(&1, Option::Some("checkout"));
+    usage_service.record(&1, Option::Some("reporting"));
+    usage_service.record(&1, Option::Some("checkout"));
+    usage_service.record(&1, Option::None);
+
+    let stats = usage_service.get_stats(&1).unwrap();
+
+    assert_eq!(&4, stats.get_total_evaluations());
+    assert_eq!(&vec![(DefinitionUsageService::fingerprint("checkout"), 2),
+                     (DefinitionUsageService::fingerprint("reporting"), 1),
+                     ("anonymous".to_owned(), 1)],
+               stats.get_top_callers());
+    assert_eq!(vec![&4], stats.get_evaluations_per_day().values().collect::<Vec<&u64>>());
+    assert_eq!(Option::None, usage_service.get_stats(&2));
+}
+
+#[test]
+fn test_never_keeps_api_keys() {
+    let path = std::env::temp_dir().join(format!("buttercup-usage-keys-{}.json", std::process::id()));
+    let usage_service = DefinitionUsageService::default();
+
+    usage_service.record(&1, Option::Some("secret-api-key"));
+    usage_service.save(&path).unwrap();
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(16, DefinitionUsageService::fingerprint("secret-api-key").len());
+    assert!(!saved.contains("secret-api-key"));
+    assert!(saved.contains(&DefinitionUsageService::fingerprint("secret-api-key")));
+    assert!(usage_service.get_stats(&1).unwrap()
+        .get_top_callers()
+        .iter()
+        .all(|(caller, _)| caller != "secret-api-key"));
+}
+
+#[test]
+fn test_caps_number_of_callers() {
+    let usage_service = DefinitionUsageService::default();
+
+    for caller in 0..1100 {
+        usage_service.record(&1, Option::Some(&caller.to_string()));
+    }
+    usage_service.record(&1, Option::Some("0"));
+
+    let stats = usage_service.get_stats(&1).unwrap();
+
+    assert_eq!(&1101, stats.get_total_evaluations());
+    assert_eq!(&("other".to_owned(), 100), &stats.get_top_callers()[0]);
+    assert_eq!(&(DefinitionUsageService::fingerprint("0"), 2), &stats.get_top_callers()[1]);
+}
+
+#[test]
+fn test_saves_and_loads_usages() {
+    let path = std::env::temp_dir().join(format!("buttercup-usage-{}.json", std::process::id()));
+    let usage_service = DefinitionUsageService::default();
+
+    usage_service.record(&1, Option::Some("checkout"));
+    usage_service.save(&path).unwrap();
+
+    let loaded = DefinitionUsageService::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(usage_service.get_stats(&1), loaded.get_stats(&1));
+}
@@ -0,0 +1,50 @@
+use buttercup_api::xml::{XmlElement, XmlError, MAX_DEPTH};
+
+#[test]
+fn test_parses_elements() {
+    let root = XmlElement::parse(r#"<?xml version="1.0"?>
+        <!-- definitions -->
+        <bt:root xmlns:bt="urn:bt" main='tree &amp; more'>
+            <Sequence name="a&lt;b"><Action/><![CDATA[<raw>]]> &#x41;&#66; </Sequence>
+        </bt:root>"#).unwrap();
+
+    assert_eq!("bt:root", root.get_name());
+    assert_eq!("root", root.get_local_name());
+    assert_eq!(Option::Some("tree & more"), root.get_attribute("main"));
+    assert_eq!(Option::Some("urn:bt"), root.get_attribute("bt"));
+
+    let sequence = root.get_child("Sequence").unwrap();
+    assert_eq!(Option::Some("a<b"), sequence.get_attribute("name"));
+    assert_eq!(1, sequence.get_children().len());
+    assert_eq!("<raw> AB", sequence.get_text());
+}
+
+#[test]
+fn test_writes_elements_back() {
+    let root = XmlElement::new("root")
+        .with_attribute("name", "\"quoted\" & <tagged>")
+        .with_child(XmlElement::new("Empty"))
+        .with_child(XmlElement::new("Text").with_text("a < b"));
+    let xml = root.to_xml();
+
+    assert_eq!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<root name=\"&quot;quoted&quot; &amp; &lt;tagged&gt;\">
+  <Empty/>
+  <Text>a &lt; b</Text>
+</root>
+", xml);
+    assert_eq!(Result::Ok(root), XmlElement::parse(&xml));
+}
+
+#[test]
+fn test_rejects_malformed_xml() {
+    assert_eq!(Result::Err(XmlError::MissingRoot), XmlElement::parse("<?xml version=\"1.0\"?>"));
+    assert_eq!(Result::Err(XmlError::UnexpectedEnd), XmlElement::parse("<root><child/>"));
+    assert_eq!(Result::Err(XmlError::InvalidEntity(6, "nbsp".to_owned())), XmlElement::parse("<root>&nbsp;</root>"));
+    assert_eq!(Result::Err(XmlError::UnexpectedChar(7, '<')), XmlElement::parse("<root/><other/>"));
+
+    let nested = |depth: usize| "<a>".repeat(depth) + &"</a>".repeat(depth);
+    assert!(XmlElement::parse(&nested(MAX_DEPTH)).is_ok());
+    assert_eq!(Result::Err(XmlError::TooDeep(3 * MAX_DEPTH)), XmlElement::parse(&nested(MAX_DEPTH + 1)));
+    assert_eq!(Result::Err(XmlError::TooDeep(3 * MAX_DEPTH)), XmlElement::parse(&nested(100_000)));
+}
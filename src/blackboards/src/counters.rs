@@ -0,0 +1,105 @@
+use std::convert::TryInto;
+use std::ffi::OsString;
+use std::sync::Mutex;
+
+use rocksdb::{DB, Options};
+
+use crate::LocalBlackboardError;
+
+// Counters of fixed time windows which survive restarts. Every key holds the end of its
+// window, in seconds since the unix epoch, followed by the count, both big-endian. The
+// count of a key starts over once its window ended.
+pub struct PersistentCounters {
+
+    db: Mutex<DB>
+
+}
+
+impl PersistentCounters {
+
+    pub fn open(path: OsString) -> Result<PersistentCounters, LocalBlackboardError> {
+        Result::Ok(
+            PersistentCounters {
+                db: Mutex::new(DB::open_default(path)?)
+            }
+        )
+    }
+
+    pub fn destroy(path: OsString) -> Result<(), LocalBlackboardError> {
+        DB::destroy(
+            &Options::default(),
+            path)?;
+
+        Result::Ok(())
+    }
+
+    // Increments the count of the key unless it already reached the limit in the window
+    // of now_secs, returns whether it was incremented.
+    pub fn try_increment_at(&self,
+                            key: &str,
+                            limit: u64,
+                            window_secs: u64,
+                            now_secs: u64) -> Result<bool, LocalBlackboardError> {
+        let window_secs = window_secs.max(1);
+        let window_ends_at = (now_secs / window_secs + 1).saturating_mul(window_secs);
+
+        let db = self.db
+            .lock()
+            .map_err(|err| LocalBlackboardError::LockPoisonedError(err.to_string()))?;
+        let count = match db.get(key)? {
+            Some(value) if value.len() == 16 => {
+                let (counter_window_ends_at, count) = value.split_at(8);
+                match u64::from_be_bytes(counter_window_ends_at.try_into().unwrap()) {
+                    counter_window_ends_at if counter_window_ends_at == window_ends_at =>
+                        u64::from_be_bytes(count.try_into().unwrap()),
+                    _ => 0
+                }
+            },
+            Some(_) => return Result::Err(
+                LocalBlackboardError::DeserializeError(format!("Invalid counter {}.", key))),
+            None => 0
+        };
+
+        if count >= limit {
+            return Result::Ok(false);
+        }
+
+        let mut value = window_ends_at.to_be_bytes().to_vec();
+        value.extend_from_slice(&(count + 1).to_be_bytes());
+        db.put(key, value)?;
+
+        Result::Ok(true)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::LocalBlackboard;
+
+    use super::*;
+
+    #[test]
+    fn test_keeps_counts_when_reopened() {
+        let path = LocalBlackboard::get_temporary_path(&Uuid::new_v4());
+        let day = 86_400;
+        {
+            let counters = PersistentCounters::open(path.clone()).unwrap();
+
+            assert_eq!(Result::Ok(true), counters.try_increment_at("user:1", 2, day, 100));
+            assert_eq!(Result::Ok(true), counters.try_increment_at("user:1", 2, day, 200));
+            assert_eq!(Result::Ok(true), counters.try_increment_at("user:2", 2, day, 200));
+        }
+        {
+            let counters = PersistentCounters::open(path.clone()).unwrap();
+
+            assert_eq!(Result::Ok(false), counters.try_increment_at("user:1", 2, day, 300));
+            assert_eq!(Result::Ok(true), counters.try_increment_at("user:2", 2, day, 300));
+            assert_eq!(Result::Ok(true), counters.try_increment_at("user:1", 2, day, day));
+        }
+        PersistentCounters::destroy(path).unwrap();
+    }
+
+}
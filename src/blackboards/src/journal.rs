@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::RwLock;
+
+use chrono::{NaiveDateTime, Utc};
+use rocksdb::{DB, Direction, IteratorMode, WriteBatch};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+use crate::LocalBlackboardError;
+
+const DEFAULT_RETAINED_SNAPSHOTS: usize = 64;
+
+// The journal is kept in the db of the blackboard, the 0xff byte never starts a value name
+// as it is not valid in UTF-8, so its keys come after all the values.
+pub(crate) const JOURNAL_PREFIX: &[u8] = b"\xffjournal/";
+const MUTATION_PREFIX: &[u8] = b"\xffjournal/mutation/";
+const SNAPSHOT_PREFIX: &[u8] = b"\xffjournal/snapshot/";
+const COMPACTED_SEQUENCE_KEY: &[u8] = b"\xffjournal/compacted_sequence";
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct BlackboardMutation {
+
+    sequence: u64,
+    at_utc: NaiveDateTime,
+    values: ValuesPayload
+
+}
+
+impl BlackboardMutation {
+
+    pub fn get_sequence(&self) -> &u64 {
+        &self.sequence
+    }
+
+    pub fn get_at_utc(&self) -> &NaiveDateTime {
+        &self.at_utc
+    }
+
+    pub fn get_values(&self) -> &ValuesPayload {
+        &self.values
+    }
+
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlackboardSnapshot {
+
+    sequence: u64,
+    at_utc: NaiveDateTime,
+    values: HashMap<String, ValueHolder>
+
+}
+
+// Mutations up to compacted_sequence were dropped, the oldest snapshot is then the one of
+// that sequence.
+#[derive(Default)]
+struct JournalState {
+
+    compacted_sequence: u64,
+    mutations: Vec<BlackboardMutation>,
+    snapshots: Vec<BlackboardSnapshot>
+
+}
+
+impl JournalState {
+
+    fn get_sequence(&self) -> u64 {
+        self.compacted_sequence + self.mutations.len() as u64
+    }
+
+}
+
+// Append-only log of every put to a blackboard. Mutations are numbered from 1, every
+// snapshot_interval mutations the whole state is snapshotted, so reconstructing the
+// values at any point replays at most snapshot_interval mutations. Written to the db of
+// the blackboard in the same batch as the values and loaded back when it is reopened,
+// only the latest retained_snapshots snapshots and the mutations since the oldest of them
+// are kept, older values cannot be reconstructed anymore.
+pub struct BlackboardJournal {
+
+    snapshot_interval: u64,
+    retained_snapshots: usize,
+    state: RwLock<JournalState>
+
+}
+
+impl BlackboardJournal {
+
+    pub fn new(snapshot_interval: u64) -> BlackboardJournal {
+        BlackboardJournal {
+            snapshot_interval: snapshot_interval.max(1),
+            retained_snapshots: DEFAULT_RETAINED_SNAPSHOTS,
+            state: RwLock::new(JournalState::default())
+        }
+    }
+
+    pub fn with_retained_snapshots(mut self,
+                                   retained_snapshots: usize) -> BlackboardJournal {
+        self.retained_snapshots = retained_snapshots.max(1);
+        self
+    }
+
+    pub(crate) fn load(&self,
+                       db: &DB) -> Result<(), LocalBlackboardError> {
+        let compacted_sequence = match db.get(COMPACTED_SEQUENCE_KEY)? {
+            Some(value) => u64::from_be_bytes(
+                value.as_slice()
+                    .try_into()
+                    .map_err(|_| LocalBlackboardError::DeserializeError("Invalid compacted sequence.".to_owned()))?),
+            None => 0
+        };
+        let mutations = BlackboardJournal::load_entries(db, MUTATION_PREFIX)?;
+        let snapshots = BlackboardJournal::load_entries(db, SNAPSHOT_PREFIX)?;
+
+        *self.state.write().unwrap() = JournalState { compacted_sequence, mutations, snapshots };
+        Result::Ok(())
+    }
+
+    // Adds the mutation, and the snapshot and compaction it may lead to, to the batch
+    // putting the values, the journal is only updated once the batch was written.
+    pub(crate) fn record(&self,
+                         db: &DB,
+                         mut batch: WriteBatch,
+                         values: &ValuesPayload) -> Result<u64, LocalBlackboardError> {
+        let mut state = self.state.write().unwrap();
+        let sequence = state.get_sequence() + 1;
+        let at_utc = Utc::now().naive_utc();
+
+        let mutation = BlackboardMutation {
+            sequence,
+            at_utc,
+            values: values.clone()
+        };
+        batch.put(BlackboardJournal::get_key(MUTATION_PREFIX, sequence), BlackboardJournal::serialize(&mutation)?);
+
+        let mut snapshot = Option::None;
+        let mut compacted_sequence = Option::None;
+        if sequence.is_multiple_of(self.snapshot_interval) {
+            let mut snapshot_values = BlackboardJournal::replay(&state, sequence - 1).unwrap_or_default();
+            snapshot_values.extend(values.get_values().clone());
+            let new_snapshot = BlackboardSnapshot { sequence, at_utc, values: snapshot_values };
+            batch.put(BlackboardJournal::get_key(SNAPSHOT_PREFIX, sequence), BlackboardJournal::serialize(&new_snapshot)?);
+
+            if state.snapshots.len() >= self.retained_snapshots {
+                batch.delete(BlackboardJournal::get_key(SNAPSHOT_PREFIX, state.snapshots[0].sequence));
+
+                let compacted = state.snapshots.get(1).map_or(sequence, |snapshot| snapshot.sequence);
+                for compacted_mutation in (state.compacted_sequence + 1)..=compacted {
+                    batch.delete(BlackboardJournal::get_key(MUTATION_PREFIX, compacted_mutation));
+                }
+                batch.put(COMPACTED_SEQUENCE_KEY, compacted.to_be_bytes());
+                compacted_sequence = Option::Some(compacted);
+            }
+            snapshot = Option::Some(new_snapshot);
+        }
+
+        db.write(batch)?;
+
+        state.mutations.push(mutation);
+        if let Some(snapshot) = snapshot {
+            state.snapshots.push(snapshot);
+        }
+        if let Some(compacted_sequence) = compacted_sequence {
+            state.snapshots.remove(0);
+
+            let compacted_mutations = (compacted_sequence - state.compacted_sequence) as usize;
+            state.mutations.drain(..compacted_mutations);
+            state.compacted_sequence = compacted_sequence;
+        }
+
+        Result::Ok(sequence)
+    }
+
+    pub fn get_sequence(&self) -> u64 {
+        self.state.read().unwrap().get_sequence()
+    }
+
+    // Mutations which were compacted are left out.
+    pub fn get_mutations_since(&self,
+                               sequence: u64) -> Vec<BlackboardMutation> {
+        let state = self.state.read().unwrap();
+        let start = (sequence.saturating_sub(state.compacted_sequence) as usize).min(state.mutations.len());
+
+        state.mutations[start..].to_vec()
+    }
+
+    // The values right after the mutation of the given sequence, 0 being the empty
+    // blackboard. None once the sequence was compacted.
+    pub fn get_values_at(&self,
+                         sequence: u64) -> Option<ValuesPayload> {
+        BlackboardJournal::replay(&self.state.read().unwrap(), sequence).map(ValuesPayload::new)
+    }
+
+    pub fn get_values_at_time(&self,
+                              at_utc: &NaiveDateTime) -> Option<ValuesPayload> {
+        let state = self.state.read().unwrap();
+        let retained = state.mutations.partition_point(|mutation| mutation.at_utc <= *at_utc) as u64;
+
+        // Before the oldest retained mutation, only the time of the oldest snapshot tells
+        // whether the values were already those of the snapshot.
+        if retained == 0 && state.snapshots.first().is_some_and(|snapshot|
+            snapshot.sequence == state.compacted_sequence && snapshot.at_utc > *at_utc) {
+            return Option::None;
+        }
+
+        BlackboardJournal::replay(&state, state.compacted_sequence + retained).map(ValuesPayload::new)
+    }
+
+    // Sequences are big-endian, so the entries are iterated in their order.
+    fn get_key(prefix: &[u8],
+               sequence: u64) -> Vec<u8> {
+        let mut key = prefix.to_vec();
+        key.extend_from_slice(&sequence.to_be_bytes());
+        key
+    }
+
+    fn serialize<T: Serialize>(entry: &T) -> Result<Vec<u8>, LocalBlackboardError> {
+        bincode::serialize(entry)
+            .map_err(|e| LocalBlackboardError::SerializeError(format!("{}", e)))
+    }
+
+    fn load_entries<T: DeserializeOwned>(db: &DB,
+                                         prefix: &[u8]) -> Result<Vec<T>, LocalBlackboardError> {
+        let mut entries = Vec::new();
+        for (key, value) in db.iterator(IteratorMode::From(prefix, Direction::Forward)) {
+            if !key.starts_with(prefix) {
+                break;
+            }
+            entries.push(bincode::deserialize(&value)
+                .map_err(|e| LocalBlackboardError::DeserializeError(format!("{}", e)))?);
+        }
+        Result::Ok(entries)
+    }
+
+    fn replay(state: &JournalState,
+              sequence: u64) -> Option<HashMap<String, ValueHolder>> {
+        let sequence = sequence.min(state.get_sequence());
+        let snapshot = state.snapshots
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.sequence <= sequence);
+        let (mut values, start) = match snapshot {
+            None if state.compacted_sequence > 0 => return Option::None,
+            None => (HashMap::new(), 0),
+            Some(snapshot) => (snapshot.values.clone(), snapshot.sequence)
+        };
+
+        let start = (start - state.compacted_sequence) as usize;
+        let end = (sequence - state.compacted_sequence) as usize;
+
+        for mutation in &state.mutations[start..end] {
+            values.extend(mutation.values.get_values().clone());
+        }
+        Option::Some(values)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::ffi::OsString;
+    use std::sync::Arc;
+
+    use uuid::Uuid;
+
+    use crate::LocalBlackboard;
+
+    use super::*;
+
+    fn payload(name: &str,
+               value: &str) -> ValuesPayload {
+        ValuesPayload::singleton(name.to_owned(), ValueHolder::String(Arc::new(value.to_owned())))
+    }
+
+    fn open(path: &OsString,
+            journal: BlackboardJournal) -> (LocalBlackboard, Arc<BlackboardJournal>) {
+        let journal = Arc::new(journal);
+        let blackboard = LocalBlackboard::new(path.clone())
+            .unwrap()
+            .with_journal(journal.clone())
+            .unwrap();
+        (blackboard, journal)
+    }
+
+    #[test]
+    fn test_reconstructs_values_at_any_sequence() {
+        let path = LocalBlackboard::get_temporary_path(&Uuid::new_v4());
+        {
+            let (blackboard, journal) = open(&path, BlackboardJournal::new(2));
+
+            blackboard.put_values(&payload("a", "1")).unwrap();
+            blackboard.put_values(&payload("b", "2")).unwrap();
+            blackboard.put_values(&payload("a", "3")).unwrap();
+
+            assert_eq!(3, journal.get_sequence());
+            assert_eq!(Option::Some(ValuesPayload::empty()), journal.get_values_at(0));
+            assert_eq!(Option::Some(payload("a", "1")), journal.get_values_at(1));
+            assert_eq!(Option::Some(&ValueHolder::String(Arc::new("1".to_owned()))),
+                       journal.get_values_at(2).unwrap().get(&"a".to_owned()));
+            assert_eq!(Option::Some(&ValueHolder::String(Arc::new("3".to_owned()))),
+                       journal.get_values_at(3).unwrap().get(&"a".to_owned()));
+            assert_eq!(2, journal.get_values_at(10).unwrap().get_values().len());
+            assert_eq!(vec![3], journal.get_mutations_since(2)
+                .iter()
+                .map(|mutation| *mutation.get_sequence())
+                .collect::<Vec<_>>());
+            assert_eq!(2, blackboard.get_all_values().unwrap().get_values().len());
+        }
+        LocalBlackboard::destroy(path).unwrap();
+    }
+
+    #[test]
+    fn test_compacts_mutations_older_than_retained_snapshots() {
+        let path = LocalBlackboard::get_temporary_path(&Uuid::new_v4());
+        {
+            let (blackboard, journal) = open(&path, BlackboardJournal::new(2).with_retained_snapshots(2));
+
+            for index in 1..=7 {
+                blackboard.put_values(&payload(&format!("v{}", index), "set")).unwrap();
+            }
+
+            assert_eq!(7, journal.get_sequence());
+            assert_eq!(Option::None, journal.get_values_at(0));
+            assert_eq!(Option::None, journal.get_values_at(3));
+            assert_eq!(4, journal.get_values_at(4).unwrap().get_values().len());
+            assert_eq!(7, journal.get_values_at(7).unwrap().get_values().len());
+            assert_eq!(vec![5, 6, 7], journal.get_mutations_since(0)
+                .iter()
+                .map(|mutation| *mutation.get_sequence())
+                .collect::<Vec<_>>());
+            let day = chrono::Duration::days(1);
+
+            assert_eq!(Option::None, journal.get_values_at_time(&(Utc::now().naive_utc() - day)));
+            assert_eq!(7, journal.get_values_at_time(&(Utc::now().naive_utc() + day)).unwrap().get_values().len());
+        }
+        LocalBlackboard::destroy(path).unwrap();
+    }
+
+    #[test]
+    fn test_keeps_the_journal_when_the_blackboard_is_reopened() {
+        let path = LocalBlackboard::get_temporary_path(&Uuid::new_v4());
+        {
+            let (blackboard, _) = open(&path, BlackboardJournal::new(2).with_retained_snapshots(2));
+
+            for index in 1..=7 {
+                blackboard.put_values(&payload(&format!("v{}", index), "set")).unwrap();
+            }
+        }
+        {
+            let (blackboard, journal) = open(&path, BlackboardJournal::new(2).with_retained_snapshots(2));
+
+            assert_eq!(7, journal.get_sequence());
+            assert_eq!(Option::None, journal.get_values_at(3));
+            assert_eq!(4, journal.get_values_at(4).unwrap().get_values().len());
+            assert_eq!(vec![5, 6, 7], journal.get_mutations_since(0)
+                .iter()
+                .map(|mutation| *mutation.get_sequence())
+                .collect::<Vec<_>>());
+
+            blackboard.put_values(&payload("v8", "set")).unwrap();
+
+            assert_eq!(8, journal.get_sequence());
+            assert_eq!(Option::None, journal.get_values_at(5));
+            assert_eq!(8, journal.get_values_at(8).unwrap().get_values().len());
+            assert_eq!(8, blackboard.get_all_values().unwrap().get_values().len());
+        }
+        LocalBlackboard::destroy(path).unwrap();
+    }
+
+}
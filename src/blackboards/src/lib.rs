@@ -6,16 +6,23 @@ use std::ffi::OsString;
 use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use dashmap::DashMap;
-use rocksdb::{DB, Error, Options};
+use rocksdb::{DB, Error, IteratorMode, Options, WriteBatch};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use buttercup_values::{ValueHolder, ValuesPayload};
 
+use crate::journal::{BlackboardJournal, JOURNAL_PREFIX};
+
+pub mod counters;
+pub mod journal;
+pub mod outbox;
+
 #[derive(Default)]
 pub struct LocalBlackboardService {
 
-    local_blackboards: DashMap<Uuid, Arc<LocalBlackboard>>
+    local_blackboards: DashMap<Uuid, Arc<LocalBlackboard>>,
+    journal_snapshot_interval: Option<u64>
 
 }
 
@@ -23,14 +30,26 @@ impl LocalBlackboardService {
 
     pub fn new(local_blackboards: DashMap<Uuid, Arc<LocalBlackboard>>) -> LocalBlackboardService {
         LocalBlackboardService {
-            local_blackboards
+            local_blackboards,
+            journal_snapshot_interval: Option::None
         }
     }
 
+    // Every blackboard created from now on journals its mutations.
+    pub fn with_journals(mut self,
+                         snapshot_interval: u64) -> LocalBlackboardService {
+        self.journal_snapshot_interval = Option::Some(snapshot_interval);
+        self
+    }
+
     pub fn create(&self,
                   blackboard_id: &Uuid,
                   path: OsString) -> Result<Arc<LocalBlackboard>, LocalBlackboardError> {
-        let blackboard = Arc::new(LocalBlackboard::new(path)?);
+        let blackboard = LocalBlackboard::new(path)?;
+        let blackboard = Arc::new(match self.journal_snapshot_interval {
+            None => blackboard,
+            Some(snapshot_interval) => blackboard.with_journal(Arc::new(BlackboardJournal::new(snapshot_interval)))?
+        });
 
         self.local_blackboards.insert(blackboard_id.clone(),
                                       blackboard.clone());
@@ -106,7 +125,8 @@ impl From<rocksdb::Error> for LocalBlackboardError {
 
 pub struct LocalBlackboard {
 
-    db: Arc<RwLock<DB>>
+    db: Arc<RwLock<DB>>,
+    journal: Option<Arc<BlackboardJournal>>
 
 }
 
@@ -115,11 +135,25 @@ impl LocalBlackboard {
     pub fn new(path: OsString) -> Result<LocalBlackboard, LocalBlackboardError>  {
         Result::Ok(
             LocalBlackboard {
-                db: Arc::new(RwLock::new(DB::open_default(path)?))
+                db: Arc::new(RwLock::new(DB::open_default(path)?)),
+                journal: Option::None
             }
         )
     }
 
+    // Loads what the journal already recorded in the db, so the history of a reopened
+    // blackboard is kept.
+    pub fn with_journal(mut self,
+                        journal: Arc<BlackboardJournal>) -> Result<LocalBlackboard, LocalBlackboardError> {
+        journal.load(&*self.db.as_ref().read()?)?;
+        self.journal = Option::Some(journal);
+        Result::Ok(self)
+    }
+
+    pub fn get_journal(&self) -> &Option<Arc<BlackboardJournal>> {
+        &self.journal
+    }
+
     pub fn destroy(path: OsString) -> Result<(), LocalBlackboardError> {
         DB::destroy(
             &Options::default(),
@@ -128,6 +162,14 @@ impl LocalBlackboard {
         Result::Ok(())
     }
 
+    // Throwaway blackboards, such as the ones fixtures are evaluated on, are kept in the
+    // temp directory instead of the working directory of the process.
+    pub fn get_temporary_path(blackboard_id: &Uuid) -> OsString {
+        std::env::temp_dir()
+            .join(format!("buttercup-{}.bb", blackboard_id))
+            .into_os_string()
+    }
+
     pub fn get_path(&self) -> Result<OsString, LocalBlackboardError> {
         Result::Ok(self.db.as_ref().read()?.path().to_path_buf().into_os_string())
     }
@@ -144,7 +186,31 @@ impl LocalBlackboard {
 
     pub fn put_values(&self,
                       payload: &ValuesPayload) -> Result<(), LocalBlackboardError> {
-        LocalBlackboard::do_put_values(self.db.as_ref().write()?, payload)
+        let db = self.db.as_ref().write()?;
+        let mut batch = WriteBatch::default();
+        LocalBlackboard::do_put_values(&mut batch, payload)?;
+
+        // Recorded while the lock is held, so the journal has the same order as the db.
+        match &self.journal {
+            Some(journal) => journal.record(&db, batch, payload).map(|_| ()),
+            None => Result::Ok(db.write(batch)?)
+        }
+    }
+
+    pub fn get_all_values(&self) -> Result<ValuesPayload, LocalBlackboardError> {
+        let db = self.db.as_ref().read()?;
+        let mut ret: HashMap<String, ValueHolder> = HashMap::new();
+        for (key, value) in db.iterator(IteratorMode::Start) {
+            if key.starts_with(JOURNAL_PREFIX) {
+                break;
+            }
+            let value_name = String::from_utf8(key.to_vec())
+                .map_err(|e| LocalBlackboardError::DeserializeError(format!("{}", e)))?;
+            let value_holder = bincode::deserialize(&value)
+                .map_err(|e| LocalBlackboardError::DeserializeError(format!("{}", e)))?;
+            ret.insert(value_name, value_holder);
+        }
+        Result::Ok(ValuesPayload::new(ret))
     }
 
     #[inline(always)]
@@ -184,16 +250,11 @@ impl LocalBlackboard {
         }
     }
     #[inline(always)]
-    fn do_put_values(db: RwLockWriteGuard<DB>,
+    fn do_put_values(batch: &mut WriteBatch,
                      payload: &ValuesPayload) -> Result<(), LocalBlackboardError> {
         for kv in payload.get_values().iter() {
             match bincode::serialize(kv.1) {
-                Ok(value) =>
-                    match db.put(kv.0, value) {
-                        Ok(_) => {}
-                        Err(e) =>
-                            return Result::Err(LocalBlackboardError::AccessError(e.into_string()))
-                    }
+                Ok(value) => batch.put(kv.0, value),
                 Err(e) =>
                     return Result::Err(
                         LocalBlackboardError::SerializeError(format!("{}", e)))
@@ -241,6 +302,20 @@ mod tests {
        };
     }
 
+    #[test]
+    fn test_keeps_temporary_blackboards_in_the_temp_directory() {
+        let path = LocalBlackboard::get_temporary_path(&Uuid::new_v4());
+        {
+            let blackboard = LocalBlackboard::new(path.clone()).unwrap();
+            blackboard.put_values(&ValuesPayload::singleton(SOME_KEY.to_owned(), SOME_VALUE.to_owned().into()))
+                .unwrap();
+        }
+        LocalBlackboard::destroy(path.clone()).unwrap();
+
+        assert!(std::path::Path::new(&path).starts_with(std::env::temp_dir()));
+        assert!(!std::path::Path::new(&path).exists());
+    }
+
     #[test]
     #[ignore]
     fn test_puts_and_gets_values_from_db() {
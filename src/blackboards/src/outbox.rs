@@ -0,0 +1,191 @@
+use std::convert::TryInto;
+use std::ffi::OsString;
+use std::sync::Mutex;
+
+use rocksdb::{DB, Direction, IteratorMode, Options, WriteBatch};
+use serde::{Deserialize, Serialize};
+
+use crate::LocalBlackboardError;
+
+const MESSAGE_PREFIX: &[u8] = b"message/";
+const NEXT_SEQUENCE_KEY: &[u8] = b"next_sequence";
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct OutboxMessage {
+
+    sequence: u64,
+    destination: String,
+    name: String,
+    body: Vec<u8>
+
+}
+
+impl OutboxMessage {
+
+    pub fn get_sequence(&self) -> &u64 {
+        &self.sequence
+    }
+
+    pub fn get_destination(&self) -> &String {
+        &self.destination
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_body(&self) -> &Vec<u8> {
+        &self.body
+    }
+
+    fn get_key(&self) -> Vec<u8> {
+        let mut key = MESSAGE_PREFIX.to_vec();
+        key.extend_from_slice(&self.sequence.to_be_bytes());
+        key.extend_from_slice(self.destination.as_bytes());
+        key
+    }
+
+}
+
+// Side effects are staged here before they are sent, and stay until they are acknowledged,
+// so that they survive restarts. The messages of one staging share the sequence, which is
+// never given out twice and can be used by the receivers to drop repeated deliveries.
+pub struct Outbox {
+
+    db: DB,
+    next_sequence: Mutex<u64>
+
+}
+
+impl Outbox {
+
+    pub fn open(path: OsString) -> Result<Outbox, LocalBlackboardError> {
+        let db = DB::open_default(path)?;
+        let next_sequence = match db.get(NEXT_SEQUENCE_KEY)? {
+            Some(value) => u64::from_be_bytes(
+                value.as_slice()
+                    .try_into()
+                    .map_err(|_| LocalBlackboardError::DeserializeError("Invalid next sequence.".to_owned()))?),
+            None => 0
+        };
+
+        Result::Ok(
+            Outbox {
+                db,
+                next_sequence: Mutex::new(next_sequence)
+            }
+        )
+    }
+
+    pub fn destroy(path: OsString) -> Result<(), LocalBlackboardError> {
+        DB::destroy(
+            &Options::default(),
+            path)?;
+
+        Result::Ok(())
+    }
+
+    // Writes a message for every destination in one batch, either all of them are staged
+    // or none is.
+    pub fn stage(&self,
+                 name: &str,
+                 body: &[u8],
+                 destinations: &[String]) -> Result<u64, LocalBlackboardError> {
+        let mut next_sequence = self.next_sequence
+            .lock()
+            .map_err(|err| LocalBlackboardError::LockPoisonedError(err.to_string()))?;
+        let sequence = *next_sequence;
+
+        let mut batch = WriteBatch::default();
+        for destination in destinations {
+            let message = OutboxMessage {
+                sequence,
+                destination: destination.clone(),
+                name: name.to_owned(),
+                body: body.to_vec()
+            };
+            let value = bincode::serialize(&message)
+                .map_err(|e| LocalBlackboardError::SerializeError(format!("{}", e)))?;
+            batch.put(message.get_key(), value);
+        }
+        batch.put(NEXT_SEQUENCE_KEY, (sequence + 1).to_be_bytes());
+        self.db.write(batch)?;
+
+        *next_sequence = sequence + 1;
+        Result::Ok(sequence)
+    }
+
+    // In the order they were staged.
+    pub fn get_pending(&self) -> Result<Vec<OutboxMessage>, LocalBlackboardError> {
+        let mut messages = Vec::new();
+        for (key, value) in self.db.iterator(IteratorMode::From(MESSAGE_PREFIX, Direction::Forward)) {
+            if !key.starts_with(MESSAGE_PREFIX) {
+                break;
+            }
+            messages.push(bincode::deserialize(&value)
+                .map_err(|e| LocalBlackboardError::DeserializeError(format!("{}", e)))?);
+        }
+        Result::Ok(messages)
+    }
+
+    pub fn acknowledge(&self,
+                       message: &OutboxMessage) -> Result<(), LocalBlackboardError> {
+        Result::Ok(self.db.delete(message.get_key())?)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outbox_path(name: &str) -> OsString {
+        std::env::temp_dir()
+            .join(format!("buttercup-outbox-{}-{}", name, std::process::id()))
+            .into_os_string()
+    }
+
+    #[test]
+    fn test_keeps_messages_until_acknowledged() {
+        let path = outbox_path("acknowledged");
+        {
+            let outbox = Outbox::open(path.clone()).unwrap();
+            let destinations = vec!["http://a".to_owned(), "http://b".to_owned()];
+
+            assert_eq!(0, outbox.stage("activated", b"first", &destinations).unwrap());
+            assert_eq!(1, outbox.stage("rolled_back", b"second", &destinations[..1]).unwrap());
+
+            let pending = outbox.get_pending().unwrap();
+            assert_eq!(vec![(0, "http://a"), (0, "http://b"), (1, "http://a")],
+                       pending.iter()
+                           .map(|message| (*message.get_sequence(), message.get_destination().as_str()))
+                           .collect::<Vec<_>>());
+            assert_eq!(b"second".to_vec(), *pending[2].get_body());
+
+            outbox.acknowledge(&pending[0]).unwrap();
+            assert_eq!(pending[1..].to_vec(), outbox.get_pending().unwrap());
+        }
+
+        Outbox::destroy(path).unwrap();
+    }
+
+    #[test]
+    fn test_does_not_reuse_sequences_after_reopening() {
+        let path = outbox_path("reopened");
+        {
+            let outbox = Outbox::open(path.clone()).unwrap();
+            outbox.stage("activated", b"body", &["http://a".to_owned()]).unwrap();
+            for message in outbox.get_pending().unwrap() {
+                outbox.acknowledge(&message).unwrap();
+            }
+        }
+        {
+            let outbox = Outbox::open(path.clone()).unwrap();
+            assert_eq!(1, outbox.stage("activated", b"body", &["http://a".to_owned()]).unwrap());
+            assert_eq!(1, outbox.get_pending().unwrap().len());
+        }
+
+        Outbox::destroy(path).unwrap();
+    }
+
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::command::{CommandSelection, FrequencyCap};
+use crate::quota::CounterStore;
+
+// How a command competes with the others selected for the same subject. Commands of a
+// category share its cap, e.g. at most 2 promotions per user per day, whichever tree
+// selected them.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct CommandPolicy {
+
+    priority: u32,
+    category: Option<String>
+
+}
+
+impl CommandPolicy {
+
+    pub fn new(priority: u32) -> CommandPolicy {
+        CommandPolicy {
+            priority,
+            category: Option::None
+        }
+    }
+
+    pub fn with_category(mut self,
+                         category: &str) -> CommandPolicy {
+        self.category = Option::Some(category.to_owned());
+        self
+    }
+
+    pub fn get_priority(&self) -> &u32 {
+        &self.priority
+    }
+
+    pub fn get_category(&self) -> &Option<String> {
+        &self.category
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum ArbitrationOutcome {
+
+    Accepted,
+    SuppressedByPriority,
+    SuppressedByCategoryCap(String)
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ArbitrationDecision {
+
+    subject: String,
+    selection: CommandSelection,
+    priority: u32,
+    outcome: ArbitrationOutcome
+
+}
+
+impl ArbitrationDecision {
+
+    pub fn get_subject(&self) -> &String {
+        &self.subject
+    }
+
+    pub fn get_selection(&self) -> &CommandSelection {
+        &self.selection
+    }
+
+    pub fn get_priority(&self) -> &u32 {
+        &self.priority
+    }
+
+    pub fn get_outcome(&self) -> &ArbitrationOutcome {
+        &self.outcome
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.outcome == ArbitrationOutcome::Accepted
+    }
+
+}
+
+// Decides which of the commands selected for a subject by several trees are run. They are
+// ranked by the priority of their policies, commands without a policy come last, and the
+// ones over max accepted are suppressed. Accepted commands are counted towards the cap of
+// their category in the counter store, so the cap holds over separate arbitrations within
+// its window, a command over the cap gives its place to the next one.
+pub struct CommandArbiter {
+
+    policies: HashMap<u32, CommandPolicy>,
+    category_caps: HashMap<String, FrequencyCap>,
+    max_accepted: usize,
+    counter_store: Arc<dyn CounterStore>
+
+}
+
+impl CommandArbiter {
+
+    pub fn new(counter_store: Arc<dyn CounterStore>) -> CommandArbiter {
+        CommandArbiter {
+            policies: HashMap::new(),
+            category_caps: HashMap::new(),
+            max_accepted: 1,
+            counter_store
+        }
+    }
+
+    pub fn with_policy(mut self,
+                       command_id: u32,
+                       policy: CommandPolicy) -> CommandArbiter {
+        self.policies.insert(command_id, policy);
+        self
+    }
+
+    pub fn with_category_cap(mut self,
+                             category: &str,
+                             cap: FrequencyCap) -> CommandArbiter {
+        self.category_caps.insert(category.to_owned(), cap);
+        self
+    }
+
+    pub fn with_max_accepted(mut self,
+                             max_accepted: usize) -> CommandArbiter {
+        self.max_accepted = max_accepted;
+        self
+    }
+
+    // Decisions are returned from the highest priority down, selections of the same
+    // priority keep their order.
+    pub fn arbitrate(&self,
+                     subject: &str,
+                     selections: Vec<CommandSelection>) -> Vec<ArbitrationDecision> {
+        let default_policy = CommandPolicy::default();
+        let mut ranked: Vec<(CommandSelection, &CommandPolicy)> = selections.into_iter()
+            .map(|selection| {
+                let policy = self.policies
+                    .get(selection.get_address().get_command_id())
+                    .unwrap_or(&default_policy);
+
+                (selection, policy)
+            })
+            .collect();
+
+        ranked.sort_by(|(_, first), (_, second)| second.priority.cmp(&first.priority));
+
+        let mut accepted = 0;
+
+        ranked.into_iter()
+            .map(|(selection, policy)| {
+                let outcome = if accepted >= self.max_accepted {
+                    ArbitrationOutcome::SuppressedByPriority
+                } else if !self.try_count(subject, policy) {
+                    ArbitrationOutcome::SuppressedByCategoryCap(policy.category.clone().unwrap_or_default())
+                } else {
+                    accepted += 1;
+                    ArbitrationOutcome::Accepted
+                };
+
+                ArbitrationDecision {
+                    subject: subject.to_owned(),
+                    selection,
+                    priority: policy.priority,
+                    outcome
+                }
+            })
+            .collect()
+    }
+
+    fn try_count(&self,
+                 subject: &str,
+                 policy: &CommandPolicy) -> bool {
+        match policy.category.as_ref().and_then(|category| self.category_caps.get_key_value(category)) {
+            None => true,
+            Some((category, cap)) =>
+                self.counter_store.try_increment(&CommandArbiter::get_counter_key(category, subject),
+                                                 *cap.get_limit(),
+                                                 cap.get_window())
+        }
+    }
+
+    // Prefixed with the length of the category for the same reason as the keys of the
+    // command runner.
+    fn get_counter_key(category: &str,
+                       subject: &str) -> String {
+        format!("category/{}:{}:{}", category.len(), category, subject)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::command::ContentCommandAddress;
+    use crate::quota::InMemoryCounterStore;
+
+    use super::*;
+
+    fn selection(tree_id: i32,
+                 command_id: u32) -> CommandSelection {
+        CommandSelection::new(tree_id, 1, ContentCommandAddress::new(command_id, 0))
+    }
+
+    fn outcomes(decisions: &[ArbitrationDecision]) -> Vec<(i32, ArbitrationOutcome)> {
+        decisions.iter()
+            .map(|decision| (*decision.get_selection().get_tree_id(), decision.get_outcome().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_accepts_highest_priority_and_suppresses_the_rest() {
+        let arbiter = CommandArbiter::new(Arc::new(InMemoryCounterStore::default()))
+            .with_policy(7, CommandPolicy::new(10))
+            .with_policy(8, CommandPolicy::new(20));
+
+        let decisions = arbiter.arbitrate("alice", vec![selection(1, 7), selection(2, 9), selection(3, 8)]);
+
+        assert_eq!(vec![(3, ArbitrationOutcome::Accepted),
+                        (1, ArbitrationOutcome::SuppressedByPriority),
+                        (2, ArbitrationOutcome::SuppressedByPriority)],
+                   outcomes(&decisions));
+        assert_eq!(vec![20, 10, 0], decisions.iter().map(|decision| *decision.get_priority()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_gives_place_of_capped_category_to_next_command() {
+        let arbiter = CommandArbiter::new(Arc::new(InMemoryCounterStore::default()))
+            .with_policy(7, CommandPolicy::new(20).with_category("promotions"))
+            .with_policy(8, CommandPolicy::new(10).with_category("reminders"))
+            .with_category_cap("promotions", FrequencyCap::new(1, Duration::from_secs(86_400)));
+
+        assert_eq!(vec![(1, ArbitrationOutcome::Accepted), (2, ArbitrationOutcome::SuppressedByPriority)],
+                   outcomes(&arbiter.arbitrate("alice", vec![selection(1, 7), selection(2, 8)])));
+        assert_eq!(vec![(1, ArbitrationOutcome::SuppressedByCategoryCap("promotions".to_owned())),
+                        (2, ArbitrationOutcome::Accepted)],
+                   outcomes(&arbiter.arbitrate("alice", vec![selection(1, 7), selection(2, 8)])));
+        assert_eq!(vec![(1, ArbitrationOutcome::Accepted)],
+                   outcomes(&arbiter.arbitrate("bob", vec![selection(1, 7)])));
+    }
+
+    #[test]
+    fn test_accepts_up_to_max_accepted() {
+        let arbiter = CommandArbiter::new(Arc::new(InMemoryCounterStore::default()))
+            .with_max_accepted(2);
+
+        assert_eq!(vec![(1, ArbitrationOutcome::Accepted),
+                        (2, ArbitrationOutcome::Accepted),
+                        (3, ArbitrationOutcome::SuppressedByPriority)],
+                   outcomes(&arbiter.arbitrate("alice", vec![selection(1, 7), selection(2, 7), selection(3, 7)])));
+    }
+
+}
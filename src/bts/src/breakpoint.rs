@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use futures::channel::oneshot;
+
+// Node ids before which the ticks of a single instance pause. A paused tick waits until
+// it is resumed, nodes ticked in parallel may be paused at the same time.
+#[derive(Default)]
+pub struct Breakpoints {
+
+    node_ids: Mutex<HashSet<i32>>,
+    paused: Mutex<Vec<(i32, oneshot::Sender<()>)>>
+
+}
+
+impl Breakpoints {
+
+    pub fn set(&self,
+               node_ids: HashSet<i32>) {
+        *self.node_ids.lock().unwrap() = node_ids;
+    }
+
+    pub fn get_node_ids(&self) -> Vec<i32> {
+        let mut node_ids: Vec<i32> = self.node_ids.lock().unwrap().iter().copied().collect();
+        node_ids.sort_unstable();
+        node_ids
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.node_ids.lock().unwrap().is_empty()
+    }
+
+    pub fn get_paused_node_ids(&self) -> Vec<i32> {
+        self.paused.lock().unwrap().iter().map(|(node_id, _)| *node_id).collect()
+    }
+
+    pub async fn wait(&self,
+                      node_id: &i32) {
+        if !self.node_ids.lock().unwrap().contains(node_id) {
+            return;
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        self.paused.lock().unwrap().push((*node_id, sender));
+
+        let _ = receiver.await;
+    }
+
+    // Resumes all the paused ticks, returns the ids of the nodes they were paused at.
+    pub fn resume(&self) -> Vec<i32> {
+        self.paused.lock().unwrap()
+            .drain(..)
+            .map(|(node_id, sender)| {
+                let _ = sender.send(());
+                node_id
+            })
+            .collect()
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_pauses_until_resumed() {
+        let breakpoints = Breakpoints::default();
+        breakpoints.set([2].iter().copied().collect());
+
+        breakpoints.wait(&1).await;
+        assert!(breakpoints.get_paused_node_ids().is_empty());
+
+        let mut waiting = Box::pin(breakpoints.wait(&2));
+
+        assert!(futures::poll!(&mut waiting).is_pending());
+        assert_eq!(vec![2], breakpoints.get_paused_node_ids());
+        assert_eq!(vec![2], breakpoints.resume());
+
+        waiting.await;
+        assert!(breakpoints.get_paused_node_ids().is_empty());
+    }
+
+}
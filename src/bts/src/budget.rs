@@ -0,0 +1,168 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::tick::TickError;
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum EvaluationResource {
+
+    ConditionsEvaluated,
+    Lookups,
+    NodesVisited
+
+}
+
+// Caps on the work a single tick or evaluation may do, no cap by default.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct EvaluationBudget {
+
+    max_nodes_visited: Option<u64>,
+    max_conditions_evaluated: Option<u64>,
+    max_lookups: Option<u64>
+
+}
+
+impl EvaluationBudget {
+
+    pub fn with_max_nodes_visited(mut self,
+                                  max_nodes_visited: u64) -> EvaluationBudget {
+        self.max_nodes_visited = Option::Some(max_nodes_visited);
+        self
+    }
+
+    pub fn with_max_conditions_evaluated(mut self,
+                                         max_conditions_evaluated: u64) -> EvaluationBudget {
+        self.max_conditions_evaluated = Option::Some(max_conditions_evaluated);
+        self
+    }
+
+    pub fn with_max_lookups(mut self,
+                            max_lookups: u64) -> EvaluationBudget {
+        self.max_lookups = Option::Some(max_lookups);
+        self
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_nodes_visited.is_none() && self.max_conditions_evaluated.is_none() && self.max_lookups.is_none()
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct EvaluationUsage {
+
+    nodes_visited: u64,
+    conditions_evaluated: u64,
+    lookups: u64,
+    exceeded: Option<EvaluationResource>
+
+}
+
+impl EvaluationUsage {
+
+    pub fn get_nodes_visited(&self) -> &u64 {
+        &self.nodes_visited
+    }
+
+    pub fn get_conditions_evaluated(&self) -> &u64 {
+        &self.conditions_evaluated
+    }
+
+    pub fn get_lookups(&self) -> &u64 {
+        &self.lookups
+    }
+
+    pub fn get_exceeded(&self) -> &Option<EvaluationResource> {
+        &self.exceeded
+    }
+
+}
+
+// Counts the work done by a single tick or evaluation. Lookups are the values read from
+// the blackboard. The budget is checked every time a node is about to be visited, so an
+// evaluation over its budget is aborted before its next node.
+#[derive(Default)]
+pub struct EvaluationMeter {
+
+    budget: EvaluationBudget,
+    nodes_visited: AtomicU64,
+    conditions_evaluated: AtomicU64,
+    lookups: AtomicU64
+
+}
+
+impl EvaluationMeter {
+
+    pub fn new(budget: EvaluationBudget) -> EvaluationMeter {
+        EvaluationMeter {
+            budget,
+            ..Default::default()
+        }
+    }
+
+    pub fn visit_node(&self,
+                      node_id: &i32) -> Result<(), TickError> {
+        let nodes_visited = self.nodes_visited.fetch_add(1, Ordering::Relaxed) + 1;
+
+        match self.find_exceeded(nodes_visited) {
+            None => Result::Ok(()),
+            Some(resource) => Result::Err(TickError::BudgetExceeded(*node_id, resource))
+        }
+    }
+
+    pub fn record_condition(&self) {
+        self.conditions_evaluated.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_lookups(&self,
+                          count: usize) {
+        self.lookups.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub fn get_usage(&self) -> EvaluationUsage {
+        let nodes_visited = self.nodes_visited.load(Ordering::Relaxed);
+
+        EvaluationUsage {
+            nodes_visited,
+            conditions_evaluated: self.conditions_evaluated.load(Ordering::Relaxed),
+            lookups: self.lookups.load(Ordering::Relaxed),
+            exceeded: self.find_exceeded(nodes_visited)
+        }
+    }
+
+    fn find_exceeded(&self,
+                     nodes_visited: u64) -> Option<EvaluationResource> {
+        let exceeds = |max: &Option<u64>, actual: u64| max.is_some_and(|max| actual > max);
+
+        if exceeds(&self.budget.max_nodes_visited, nodes_visited) {
+            Option::Some(EvaluationResource::NodesVisited)
+        } else if exceeds(&self.budget.max_conditions_evaluated, self.conditions_evaluated.load(Ordering::Relaxed)) {
+            Option::Some(EvaluationResource::ConditionsEvaluated)
+        } else if exceeds(&self.budget.max_lookups, self.lookups.load(Ordering::Relaxed)) {
+            Option::Some(EvaluationResource::Lookups)
+        } else {
+            Option::None
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_aborts_over_budget() {
+        let meter = EvaluationMeter::new(EvaluationBudget::default().with_max_nodes_visited(2).with_max_lookups(3));
+
+        assert_eq!(Result::Ok(()), meter.visit_node(&1));
+        meter.record_lookups(4);
+        assert_eq!(Result::Err(TickError::BudgetExceeded(2, EvaluationResource::Lookups)), meter.visit_node(&2));
+        assert_eq!(Result::Err(TickError::BudgetExceeded(3, EvaluationResource::NodesVisited)), meter.visit_node(&3));
+        assert_eq!(&3, meter.get_usage().get_nodes_visited());
+        assert_eq!(&4, meter.get_usage().get_lookups());
+    }
+
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use buttercup_values::calendar::BusinessCalendar;
+
+// Business calendars by name, e.g. one per tenant or region, shared by all the contexts
+// and replaced without rebuilding the trees which refer to them.
+#[derive(Default)]
+pub struct BusinessCalendars {
+
+    calendars: DashMap<String, Arc<BusinessCalendar>>
+
+}
+
+impl BusinessCalendars {
+
+    pub fn insert(&self,
+                  name: String,
+                  calendar: BusinessCalendar) {
+        self.calendars.insert(name, Arc::new(calendar));
+    }
+
+    pub fn get_by_name(&self,
+                       name: &str) -> Option<Arc<BusinessCalendar>> {
+        self.calendars.get(name).map(|entry| entry.value().clone())
+    }
+
+    pub fn get_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.calendars.iter().map(|entry| entry.key().clone()).collect();
+        names.sort();
+        names
+    }
+
+    pub fn remove(&self,
+                  name: &str) -> bool {
+        self.calendars.remove(name).is_some()
+    }
+
+}
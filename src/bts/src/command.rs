@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::ValuesPayload;
+
+use crate::quota::CounterStore;
+
+// Something done for a subject outside of the trees, such as sending a notification.
+#[async_trait]
+pub trait Command: Send + Sync {
+
+    async fn execute(&self,
+                     subject: &str,
+                     payload: &ValuesPayload) -> Result<(), CommandError>;
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum CommandError {
+
+    CommandOfGivenNameNotFound(String),
+    ExecutionFailed(String, String)
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum CommandStatus {
+
+    Executed,
+    SuppressedByCap
+
+}
+
+// Points at a piece of content of a command, e.g. the second template of a notification.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub struct ContentCommandAddress {
+
+    command_id: u32,
+    content_index: u32
+
+}
+
+impl ContentCommandAddress {
+
+    pub fn new(command_id: u32,
+               content_index: u32) -> ContentCommandAddress {
+        ContentCommandAddress {
+            command_id,
+            content_index
+        }
+    }
+
+    pub fn get_command_id(&self) -> &u32 {
+        &self.command_id
+    }
+
+    pub fn get_content_index(&self) -> &u32 {
+        &self.content_index
+    }
+
+}
+
+// Names of the content commands, so that definitions can select them by name instead of
+// by their addresses.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct CommandRegistry {
+
+    addresses: HashMap<String, ContentCommandAddress>
+
+}
+
+impl CommandRegistry {
+
+    pub fn with_command(mut self,
+                        name: &str,
+                        address: ContentCommandAddress) -> CommandRegistry {
+        self.addresses.insert(name.to_owned(), address);
+        self
+    }
+
+    pub fn get_address(&self,
+                       name: &str) -> Option<&ContentCommandAddress> {
+        self.addresses.get(name)
+    }
+
+}
+
+// A command selected by a node of a tree during a tick, to be arbitrated against the
+// commands other trees selected for the same subject.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CommandSelection {
+
+    tree_id: i32,
+    node_id: i32,
+    address: ContentCommandAddress
+
+}
+
+impl CommandSelection {
+
+    pub fn new(tree_id: i32,
+               node_id: i32,
+               address: ContentCommandAddress) -> CommandSelection {
+        CommandSelection {
+            tree_id,
+            node_id,
+            address
+        }
+    }
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_node_id(&self) -> &i32 {
+        &self.node_id
+    }
+
+    pub fn get_address(&self) -> &ContentCommandAddress {
+        &self.address
+    }
+
+}
+
+// At most limit runs of a command for the same subject within every window.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct FrequencyCap {
+
+    limit: u64,
+    window: Duration
+
+}
+
+impl FrequencyCap {
+
+    pub fn new(limit: u64,
+               window: Duration) -> FrequencyCap {
+        FrequencyCap {
+            limit,
+            window
+        }
+    }
+
+    pub fn get_limit(&self) -> &u64 {
+        &self.limit
+    }
+
+    pub fn get_window(&self) -> &Duration {
+        &self.window
+    }
+
+}
+
+// Runs the commands by their names, the ones with a cap only as often as it allows for
+// each subject. Runs are counted in the counter store before the command executes, so
+// failed runs count towards the cap as well and a persistent store keeps the counts
+// over restarts.
+pub struct CommandRunner {
+
+    commands: HashMap<String, Arc<dyn Command>>,
+    caps: HashMap<String, FrequencyCap>,
+    counter_store: Arc<dyn CounterStore>
+
+}
+
+impl CommandRunner {
+
+    pub fn new(counter_store: Arc<dyn CounterStore>) -> CommandRunner {
+        CommandRunner {
+            commands: HashMap::new(),
+            caps: HashMap::new(),
+            counter_store
+        }
+    }
+
+    pub fn with_command(mut self,
+                        name: &str,
+                        command: Arc<dyn Command>) -> CommandRunner {
+        self.commands.insert(name.to_owned(), command);
+        self
+    }
+
+    pub fn with_cap(mut self,
+                    name: &str,
+                    cap: FrequencyCap) -> CommandRunner {
+        self.caps.insert(name.to_owned(), cap);
+        self
+    }
+
+    pub fn get_cap(&self,
+                   name: &str) -> Option<&FrequencyCap> {
+        self.caps.get(name)
+    }
+
+    pub async fn run(&self,
+                     name: &str,
+                     subject: &str,
+                     payload: &ValuesPayload) -> Result<CommandStatus, CommandError> {
+        let command = self.commands
+            .get(name)
+            .ok_or_else(|| CommandError::CommandOfGivenNameNotFound(name.to_owned()))?;
+
+        if let Some(cap) = self.caps.get(name) {
+            if !self.counter_store.try_increment(&CommandRunner::get_counter_key(name, subject),
+                                                 cap.limit,
+                                                 &cap.window) {
+                return Result::Ok(CommandStatus::SuppressedByCap);
+            }
+        }
+
+        command.execute(subject, payload).await?;
+        Result::Ok(CommandStatus::Executed)
+    }
+
+    // The name is prefixed with its length, as both the name and the subject may contain
+    // the separator and command a:b for subject c must not share a count with a for b:c.
+    fn get_counter_key(name: &str,
+                       subject: &str) -> String {
+        format!("command/{}:{}:{}", name.len(), name, subject)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures::executor::block_on;
+    use uuid::Uuid;
+
+    use buttercup_blackboards::counters::PersistentCounters;
+    use buttercup_blackboards::LocalBlackboard;
+
+    use crate::quota::InMemoryCounterStore;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingCommand {
+
+        subjects: Mutex<Vec<String>>
+
+    }
+
+    #[async_trait]
+    impl Command for RecordingCommand {
+
+        async fn execute(&self,
+                         subject: &str,
+                         _payload: &ValuesPayload) -> Result<(), CommandError> {
+            self.subjects.lock().unwrap().push(subject.to_owned());
+            Result::Ok(())
+        }
+
+    }
+
+    #[test]
+    fn test_suppresses_runs_over_the_cap_per_subject() {
+        let notify = Arc::new(RecordingCommand::default());
+        let log = Arc::new(RecordingCommand::default());
+        let runner = CommandRunner::new(Arc::new(InMemoryCounterStore::default()))
+            .with_command("notify", notify.clone())
+            .with_command("log", log.clone())
+            .with_cap("notify", FrequencyCap::new(2, Duration::from_secs(86_400)));
+        let payload = ValuesPayload::empty();
+
+        assert_eq!(Result::Ok(CommandStatus::Executed), block_on(runner.run("notify", "alice", &payload)));
+        assert_eq!(Result::Ok(CommandStatus::Executed), block_on(runner.run("notify", "alice", &payload)));
+        assert_eq!(Result::Ok(CommandStatus::SuppressedByCap), block_on(runner.run("notify", "alice", &payload)));
+        assert_eq!(Result::Ok(CommandStatus::Executed), block_on(runner.run("notify", "bob", &payload)));
+
+        for _ in 0..3 {
+            assert_eq!(Result::Ok(CommandStatus::Executed), block_on(runner.run("log", "alice", &payload)));
+        }
+
+        assert_eq!(vec!["alice", "alice", "bob"], *notify.subjects.lock().unwrap());
+        assert_eq!(3, log.subjects.lock().unwrap().len());
+        assert_eq!(Result::Err(CommandError::CommandOfGivenNameNotFound("email".to_owned())),
+                   block_on(runner.run("email", "alice", &payload)));
+    }
+
+    #[test]
+    fn test_counts_runs_of_names_and_subjects_with_separators_apart() {
+        let runner = CommandRunner::new(Arc::new(InMemoryCounterStore::default()))
+            .with_command("a:b", Arc::new(RecordingCommand::default()))
+            .with_command("a", Arc::new(RecordingCommand::default()))
+            .with_cap("a:b", FrequencyCap::new(1, Duration::from_secs(86_400)))
+            .with_cap("a", FrequencyCap::new(1, Duration::from_secs(86_400)));
+        let payload = ValuesPayload::empty();
+
+        assert_eq!(Result::Ok(CommandStatus::Executed), block_on(runner.run("a:b", "c", &payload)));
+        assert_eq!(Result::Ok(CommandStatus::Executed), block_on(runner.run("a", "b:c", &payload)));
+        assert_eq!(Result::Ok(CommandStatus::SuppressedByCap), block_on(runner.run("a", "b:c", &payload)));
+    }
+
+    #[test]
+    fn test_keeps_caps_over_restarts() {
+        let path = LocalBlackboard::get_temporary_path(&Uuid::new_v4());
+        let cap = FrequencyCap::new(1, Duration::from_secs(86_400));
+        let payload = ValuesPayload::empty();
+        {
+            let runner = CommandRunner::new(Arc::new(PersistentCounters::open(path.clone()).unwrap()))
+                .with_command("notify", Arc::new(RecordingCommand::default()))
+                .with_cap("notify", cap.clone());
+
+            assert_eq!(Result::Ok(CommandStatus::Executed), block_on(runner.run("notify", "alice", &payload)));
+        }
+        {
+            let runner = CommandRunner::new(Arc::new(PersistentCounters::open(path.clone()).unwrap()))
+                .with_command("notify", Arc::new(RecordingCommand::default()))
+                .with_cap("notify", cap);
+
+            assert_eq!(Result::Ok(CommandStatus::SuppressedByCap), block_on(runner.run("notify", "alice", &payload)));
+        }
+        PersistentCounters::destroy(path).unwrap();
+    }
+
+}
@@ -1,21 +1,34 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use log::info;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use buttercup_blackboards::{LocalBlackboard, LocalBlackboardError, LocalBlackboardService};
+use buttercup_blackboards::journal::BlackboardJournal;
 use buttercup_values::{ValueHolder, ValuesPayload};
 use buttercup_variables::{VariableName, VariableService, VariableServiceErrorReport, VariableValueAccessError};
 
+use crate::context::hooks::ValueChangesHooks;
 use crate::context::reactive::ReactiveContext;
+use crate::calendar::BusinessCalendars;
+use crate::dedup::{FingerprintStore, InMemoryFingerprintStore};
+#[cfg(feature = "fault-injection")]
+use crate::faults::FaultInjector;
+use crate::messages::{Mailbox, MessageBus};
+use crate::quota::{CounterStore, InMemoryCounterStore};
+use crate::signal::Signals;
+use crate::task::HumanTasks;
+use crate::context::snapshot::BTNodeContextSnapshot;
 use crate::node::BTNode;
 use buttercup_endpoints::endpoints::EndpointService;
-use crate::events::{BTNodeExecutionEndedEvent, BTNodeExecutionStartedEvent};
+use crate::events::{AnalyticsSink, BTNodeExecutionEndedEvent, BTNodeExecutionStartedEvent, LoggingAnalyticsSink};
 
+pub mod hooks;
 pub mod reactive;
+pub mod snapshot;
 
 pub struct BTNodeExecutionContextHolder {
 
@@ -28,20 +41,22 @@ pub struct BTNodeExecutionContextHolder {
 impl BTNodeExecutionContextHolder {
 
     pub fn new(id: Uuid,
-               local_blackboard: Arc<LocalBlackboard>,
-               reactive_service: Arc<ReactiveContext>) -> BTNodeExecutionContextHolder {
-        let context =
-            Arc::new(
-                BTNodeExecutionContext::new(
-                    local_blackboard,
-                    reactive_service.clone()));
+               context: BTNodeExecutionContext) -> BTNodeExecutionContextHolder {
+        let value_changes_hooks = context.get_value_changes_hooks().clone();
+        let context = Arc::new(context);
+
+        // The hooks live in the context, so they only hold on to it weakly.
+        let weak_context = Arc::downgrade(&context);
+        value_changes_hooks.add(Arc::new(move |changed|
+            if let Some(context) = weak_context.upgrade() {
+                context.get_reactive_service().handle_value_changes(context.as_ref(), changed)
+            }));
 
         BTNodeExecutionContextHolder {
             id,
-            context: context.clone(),
+            context,
             value_changes_listener: Arc::new(move |changed|
-                reactive_service.handle_value_changes(
-                    context.as_ref(), changed))
+                value_changes_hooks.notify(changed))
         }
     }
 
@@ -63,6 +78,19 @@ pub struct BTNodeExecutionContext {
 
     local_blackboard: Arc<LocalBlackboard>,
     reactive_service: Arc<ReactiveContext>,
+    analytics_sink: Arc<dyn AnalyticsSink>,
+    message_bus: Arc<MessageBus>,
+    mailbox: Arc<Mailbox>,
+    calendars: Arc<BusinessCalendars>,
+    counter_store: Arc<dyn CounterStore>,
+    fingerprint_store: Arc<dyn FingerprintStore>,
+    #[cfg(feature = "fault-injection")]
+    fault_injector: Option<Arc<FaultInjector>>,
+    signals: Arc<Signals>,
+    human_tasks: Arc<HumanTasks>,
+    value_changes_hooks: Arc<ValueChangesHooks>,
+    namespace: Option<String>,
+    scopes: DashMap<String, Arc<BTNodeExecutionContext>>
 
 }
 
@@ -72,10 +100,99 @@ impl BTNodeExecutionContext {
                reactive_service: Arc<ReactiveContext>) -> BTNodeExecutionContext {
         BTNodeExecutionContext {
             local_blackboard,
-            reactive_service
+            reactive_service,
+            analytics_sink: Arc::new(LoggingAnalyticsSink),
+            message_bus: Arc::new(MessageBus::default()),
+            mailbox: Arc::new(Mailbox::default()),
+            calendars: Arc::new(BusinessCalendars::default()),
+            counter_store: Arc::new(InMemoryCounterStore::default()),
+            fingerprint_store: Arc::new(InMemoryFingerprintStore::default()),
+            #[cfg(feature = "fault-injection")]
+            fault_injector: Option::None,
+            signals: Arc::new(Signals::default()),
+            human_tasks: Arc::new(HumanTasks::default()),
+            value_changes_hooks: Arc::new(ValueChangesHooks::default()),
+            namespace: Option::None,
+            scopes: DashMap::new()
         }
     }
 
+    pub fn with_analytics_sink(mut self,
+                               analytics_sink: Arc<dyn AnalyticsSink>) -> BTNodeExecutionContext {
+        self.analytics_sink = analytics_sink;
+        self
+    }
+
+    pub fn get_analytics_sink(&self) -> &Arc<dyn AnalyticsSink> {
+        &self.analytics_sink
+    }
+
+    pub fn with_message_bus(mut self,
+                            message_bus: Arc<MessageBus>) -> BTNodeExecutionContext {
+        self.message_bus = message_bus;
+        self
+    }
+
+    pub fn get_message_bus(&self) -> &MessageBus {
+        &self.message_bus
+    }
+
+    pub fn get_mailbox(&self) -> &Arc<Mailbox> {
+        &self.mailbox
+    }
+
+    pub fn with_calendars(mut self,
+                          calendars: Arc<BusinessCalendars>) -> BTNodeExecutionContext {
+        self.calendars = calendars;
+        self
+    }
+
+    pub fn get_calendars(&self) -> &BusinessCalendars {
+        &self.calendars
+    }
+
+    pub fn with_counter_store(mut self,
+                              counter_store: Arc<dyn CounterStore>) -> BTNodeExecutionContext {
+        self.counter_store = counter_store;
+        self
+    }
+
+    pub fn get_counter_store(&self) -> &dyn CounterStore {
+        self.counter_store.as_ref()
+    }
+
+    pub fn with_fingerprint_store(mut self,
+                                  fingerprint_store: Arc<dyn FingerprintStore>) -> BTNodeExecutionContext {
+        self.fingerprint_store = fingerprint_store;
+        self
+    }
+
+    pub fn get_fingerprint_store(&self) -> &dyn FingerprintStore {
+        self.fingerprint_store.as_ref()
+    }
+
+    #[cfg(feature = "fault-injection")]
+    pub fn with_fault_injector(mut self,
+                               fault_injector: Arc<FaultInjector>) -> BTNodeExecutionContext {
+        self.fault_injector = Option::Some(fault_injector);
+        self
+    }
+
+    #[cfg(feature = "fault-injection")]
+    pub async fn inject_tick_delay(&self) {
+        if let Some(fault_injector) = &self.fault_injector {
+            fault_injector.delay().await;
+        }
+    }
+
+    pub fn get_signals(&self) -> &Signals {
+        &self.signals
+    }
+
+    pub fn get_human_tasks(&self) -> &HumanTasks {
+        &self.human_tasks
+    }
+
     pub async fn consume_execution_started_event(&self,
                                                  event: BTNodeExecutionStartedEvent<'_>) {
         info!("{:?}", event)
@@ -90,23 +207,153 @@ impl BTNodeExecutionContext {
         &self.reactive_service
     }
 
+    pub fn get_value_changes_hooks(&self) -> &Arc<ValueChangesHooks> {
+        &self.value_changes_hooks
+    }
+
+    pub fn get_namespace(&self) -> &Option<String> {
+        &self.namespace
+    }
+
+    // Scopes keep the values of subtrees apart, under names prefixed with their namespace,
+    // in the same blackboard, so that snapshots of the context include them. Nested scopes
+    // join their namespaces with dots. Reactive nodes of a scope are told of the changes
+    // to its values by their unprefixed names.
+    pub fn get_scope(&self,
+                     namespace: &str) -> Arc<BTNodeExecutionContext> {
+        if let Some(scope) = self.scopes.get(namespace) {
+            return scope.clone();
+        }
+
+        let qualified_namespace = self.qualify(namespace);
+        self.scopes
+            .entry(namespace.to_owned())
+            .or_insert_with(|| {
+                let scope = Arc::new(BTNodeExecutionContext {
+                    local_blackboard: self.local_blackboard.clone(),
+                    reactive_service: Arc::new(ReactiveContext::new()),
+                    analytics_sink: self.analytics_sink.clone(),
+                    message_bus: self.message_bus.clone(),
+                    mailbox: self.mailbox.clone(),
+                    calendars: self.calendars.clone(),
+                    counter_store: self.counter_store.clone(),
+                    fingerprint_store: self.fingerprint_store.clone(),
+                    #[cfg(feature = "fault-injection")]
+                    fault_injector: self.fault_injector.clone(),
+                    signals: self.signals.clone(),
+                    human_tasks: self.human_tasks.clone(),
+                    value_changes_hooks: self.value_changes_hooks.clone(),
+                    namespace: Option::Some(qualified_namespace.clone()),
+                    scopes: DashMap::new()
+                });
+
+                let prefix = format!("{}.", qualified_namespace);
+                let weak_scope: Weak<BTNodeExecutionContext> = Arc::downgrade(&scope);
+                self.value_changes_hooks.add(Arc::new(move |changed| {
+                    let changed: HashSet<String> = changed
+                        .iter()
+                        .filter_map(|name| name.strip_prefix(&prefix))
+                        .map(str::to_owned)
+                        .collect();
+
+                    if let (false, Some(scope)) = (changed.is_empty(), weak_scope.upgrade()) {
+                        scope.get_reactive_service().handle_value_changes(scope.as_ref(), &changed);
+                    }
+                }));
+
+                scope
+            })
+            .clone()
+    }
+
+    fn qualify(&self,
+               value_name: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}.{}", namespace, value_name),
+            None => value_name.to_owned()
+        }
+    }
+
     pub fn get_values(&self,
                       value_names: &HashSet<String>) -> Result<ValuesPayload, LocalBlackboardError> {
         if value_names.is_empty() {
             return Result::Ok(ValuesPayload::empty());
         }
 
-        self.local_blackboard.get_values(value_names)
+        #[cfg(feature = "fault-injection")]
+        if let Some(fault_injector) = &self.fault_injector {
+            return fault_injector.inject(value_names, || self.lookup_values(value_names));
+        }
+
+        self.lookup_values(value_names)
+    }
+
+    fn lookup_values(&self,
+                     value_names: &HashSet<String>) -> Result<ValuesPayload, LocalBlackboardError> {
+        let namespace = match &self.namespace {
+            Some(namespace) => namespace,
+            None => return self.local_blackboard.get_values(value_names)
+        };
+
+        let qualified_names = value_names
+            .iter()
+            .map(|value_name| self.qualify(value_name))
+            .collect();
+        let values = self.local_blackboard
+            .get_values(&qualified_names)?
+            .get_values()
+            .iter()
+            .map(|(name, value)| (name[namespace.len() + 1..].to_owned(), value.clone()))
+            .collect();
+
+        Result::Ok(ValuesPayload::new(values))
     }
 
     pub fn get_value(&self,
                      value_name: &String) -> Result<Option<ValueHolder>, LocalBlackboardError> {
-        self.local_blackboard.get_value(value_name)
+        #[cfg(feature = "fault-injection")]
+        if let Some(fault_injector) = &self.fault_injector {
+            let value_names = std::iter::once(value_name.clone()).collect();
+            let payload = fault_injector.inject(&value_names, || self.lookup_values(&value_names))?;
+
+            return Result::Ok(payload.get(value_name).cloned());
+        }
+
+        self.local_blackboard.get_value(&self.qualify(value_name))
     }
 
     pub fn put_values(&self,
                       payload: &ValuesPayload) -> Result<(), LocalBlackboardError> {
-        self.local_blackboard.put_values(payload)
+        let payload = match &self.namespace {
+            Some(_) => ValuesPayload::new(
+                payload.get_values()
+                    .iter()
+                    .map(|(name, value)| (self.qualify(name), value.clone()))
+                    .collect()),
+            None => payload.clone()
+        };
+
+        self.local_blackboard.put_values(&payload)?;
+        self.value_changes_hooks.notify(payload.get_keys());
+
+        Result::Ok(())
+    }
+
+    pub fn get_all_values(&self) -> Result<ValuesPayload, LocalBlackboardError> {
+        let namespace = match &self.namespace {
+            Some(namespace) => namespace,
+            None => return self.local_blackboard.get_all_values()
+        };
+
+        let prefix = format!("{}.", namespace);
+        let values: HashMap<String, ValueHolder> = self.local_blackboard
+            .get_all_values()?
+            .get_values()
+            .iter()
+            .filter_map(|(name, value)| name.strip_prefix(&prefix).map(|name| (name.to_owned(), value.clone())))
+            .collect();
+
+        Result::Ok(ValuesPayload::new(values))
     }
 
     fn map_err(err: LocalBlackboardError) -> VariableValueAccessError {
@@ -121,8 +368,7 @@ impl VariableService for BTNodeExecutionContext {
     fn get_variable_value_by_name(&self,
                                   name: &VariableName)
                                   -> Result<Option<ValueHolder>, VariableValueAccessError> {
-        self.local_blackboard
-            .get_value(name.get_value())
+        self.get_value(name.get_value())
             .map_err(BTNodeExecutionContext::map_err)
     }
 }
@@ -135,18 +381,31 @@ impl Default for BTNodeExecutionContext {
             Arc::new(Default::default()))
     }
 }
-#[derive(Default)]
 pub struct BTNodeContextService {
 
     contexts: DashMap<Uuid, Arc<BTNodeExecutionContextHolder>>,
     endpoint_service: Arc<EndpointService>,
-    local_blackboard_service: Arc<LocalBlackboardService>
+    local_blackboard_service: Arc<LocalBlackboardService>,
+    analytics_sink: Option<Arc<dyn AnalyticsSink>>,
+    message_bus: Arc<MessageBus>,
+    calendars: Arc<BusinessCalendars>,
+    counter_store: Arc<dyn CounterStore>,
+    fingerprint_store: Arc<dyn FingerprintStore>,
+    static_values: Option<Arc<ValuesPayload>>,
+    listener_ids: DashMap<Uuid, Uuid>
+
+}
 
+impl Default for BTNodeContextService {
+    fn default() -> Self {
+        BTNodeContextService::new(Default::default(), Default::default())
+    }
 }
 
 #[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
 pub enum BTNodeContextServiceError {
 
+    ContextNotFound(Uuid),
     LocalBlackboardError(LocalBlackboardError)
 
 }
@@ -164,22 +423,106 @@ impl BTNodeContextService {
         BTNodeContextService {
             contexts: DashMap::new(),
             endpoint_service,
-            local_blackboard_service
+            local_blackboard_service,
+            analytics_sink: Option::None,
+            message_bus: Arc::new(MessageBus::default()),
+            calendars: Arc::new(BusinessCalendars::default()),
+            counter_store: Arc::new(InMemoryCounterStore::default()),
+            fingerprint_store: Arc::new(InMemoryFingerprintStore::default()),
+            static_values: Option::None,
+            listener_ids: DashMap::new()
         }
     }
 
+    pub fn with_analytics_sink(mut self,
+                               analytics_sink: Arc<dyn AnalyticsSink>) -> BTNodeContextService {
+        self.analytics_sink = Option::Some(analytics_sink);
+        self
+    }
+
+    // All the contexts built by the service share the bus, contexts built separately
+    // have buses of their own.
+    pub fn with_message_bus(mut self,
+                            message_bus: Arc<MessageBus>) -> BTNodeContextService {
+        self.message_bus = message_bus;
+        self
+    }
+
+    pub fn with_calendars(mut self,
+                          calendars: Arc<BusinessCalendars>) -> BTNodeContextService {
+        self.calendars = calendars;
+        self
+    }
+
+    pub fn with_counter_store(mut self,
+                              counter_store: Arc<dyn CounterStore>) -> BTNodeContextService {
+        self.counter_store = counter_store;
+        self
+    }
+
+    pub fn with_fingerprint_store(mut self,
+                                  fingerprint_store: Arc<dyn FingerprintStore>) -> BTNodeContextService {
+        self.fingerprint_store = fingerprint_store;
+        self
+    }
+
+    // Put into every context the service builds, before anything else.
+    pub fn with_static_values(mut self,
+                              static_values: Arc<ValuesPayload>) -> BTNodeContextService {
+        self.static_values = Option::Some(static_values);
+        self
+    }
+
     pub fn build_new(&self) -> Result<BTNodeExecutionContextHolder, BTNodeContextServiceError> {
-        let uuid = Uuid::new_v4();
+        self.build(Uuid::new_v4())
+    }
+
+    pub fn snapshot(&self,
+                    id: &Uuid) -> Result<BTNodeContextSnapshot, BTNodeContextServiceError> {
+        let holder = self.get_by_id(id)
+            .ok_or(BTNodeContextServiceError::ContextNotFound(*id))?;
+
+        Result::Ok(
+            BTNodeContextSnapshot::new(
+                *id,
+                holder.get_context().local_blackboard.get_all_values()?))
+    }
+
+    pub fn restore(&self,
+                   snapshot: &BTNodeContextSnapshot) -> Result<BTNodeExecutionContextHolder, BTNodeContextServiceError> {
+        let holder = self.build(*snapshot.get_id())?;
+
+        holder.get_context().put_values(snapshot.get_values())?;
+
+        Result::Ok(holder)
+    }
+
+    fn build(&self,
+             uuid: Uuid) -> Result<BTNodeExecutionContextHolder, BTNodeContextServiceError> {
         let blackboard_service =
             self.local_blackboard_service.create(
                 &uuid, format!("{}.bb", &uuid).into())?;
 
         let holder = BTNodeExecutionContextHolder::new(
             uuid,
-            blackboard_service,
-            Arc::new(ReactiveContext::new()));
+            BTNodeExecutionContext::new(
+                blackboard_service,
+                Arc::new(ReactiveContext::new()))
+                .with_analytics_sink(
+                    self.analytics_sink
+                        .clone()
+                        .unwrap_or_else(|| Arc::new(LoggingAnalyticsSink)))
+                .with_message_bus(self.message_bus.clone())
+                .with_calendars(self.calendars.clone())
+                .with_counter_store(self.counter_store.clone())
+                .with_fingerprint_store(self.fingerprint_store.clone()));
+
+        if let Some(static_values) = &self.static_values {
+            holder.get_context().put_values(static_values)?;
+        }
 
-        self.endpoint_service.add_listener(holder.get_value_changes_listener());
+        let listener_id = self.endpoint_service.add_listener(holder.get_value_changes_listener());
+        self.listener_ids.insert(uuid, listener_id);
 
         Result::Ok(holder)
     }
@@ -189,6 +532,25 @@ impl BTNodeContextService {
         self.contexts.insert(context.id, Arc::new(context));
     }
 
+    pub fn destroy(&self,
+                   id: &Uuid) -> Result<(), BTNodeContextServiceError> {
+        self.contexts.remove(id);
+        if let Option::Some((_, listener_id)) = self.listener_ids.remove(id) {
+            self.endpoint_service.remove_listener(&listener_id);
+        }
+        self.local_blackboard_service.destroy(id)?;
+
+        Result::Ok(())
+    }
+
+    pub fn get_journal(&self,
+                       id: &Uuid) -> Option<Arc<BlackboardJournal>> {
+        self.local_blackboard_service
+            .get(id)
+            .ok()
+            .and_then(|blackboard| blackboard.get_journal().clone())
+    }
+
     pub fn get_by_id(&self,
                      id: &Uuid) -> Option<Arc<BTNodeExecutionContextHolder>> {
         self.contexts
@@ -218,3 +580,74 @@ pub mod test_utils {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn string(value: &str) -> ValueHolder {
+        ValueHolder::String(Arc::new(value.to_owned()))
+    }
+
+    fn singleton(name: &str,
+                 value: &str) -> ValuesPayload {
+        ValuesPayload::singleton(name.to_owned(), string(value))
+    }
+
+    #[test]
+    fn test_scopes_keep_values_apart() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let scope = context.get_scope("sub");
+            let nested_scope = scope.get_scope("nested");
+
+            context.put_values(&singleton("name", "root")).unwrap();
+            scope.put_values(&singleton("name", "scoped")).unwrap();
+            nested_scope.put_values(&singleton("other", "nested")).unwrap();
+
+            assert_eq!(Option::Some(string("root")), context.get_value(&"name".to_owned()).unwrap());
+            assert_eq!(Option::Some(string("scoped")), scope.get_value(&"name".to_owned()).unwrap());
+            assert_eq!(Option::Some(string("scoped")), context.get_value(&"sub.name".to_owned()).unwrap());
+            assert_eq!(Option::Some(string("nested")), context.get_value(&"sub.nested.other".to_owned()).unwrap());
+            assert_eq!(Option::None, nested_scope.get_value(&"name".to_owned()).unwrap());
+
+            let names = vec!["name".to_owned()].into_iter().collect();
+            assert_eq!(singleton("name", "scoped"), scope.get_values(&names).unwrap());
+
+            let mut scoped_values = HashMap::new();
+            scoped_values.insert("name".to_owned(), string("scoped"));
+            scoped_values.insert("nested.other".to_owned(), string("nested"));
+            assert_eq!(ValuesPayload::new(scoped_values), scope.get_all_values().unwrap());
+            assert_eq!(3, context.get_all_values().unwrap().get_values().len());
+
+            assert!(Arc::ptr_eq(&scope, &context.get_scope("sub")));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[test]
+    fn test_notifies_hooks_of_qualified_names() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let notified = Arc::new(Mutex::new(Vec::new()));
+            let recorded = notified.clone();
+            context.get_value_changes_hooks().add(Arc::new(move |changed|
+                recorded.lock().unwrap().extend(changed.iter().cloned())));
+
+            context.put_values(&singleton("name", "root")).unwrap();
+            context.get_scope("sub").put_values(&singleton("name", "scoped")).unwrap();
+
+            assert_eq!(vec!["name".to_owned(), "sub.name".to_owned()], *notified.lock().unwrap());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
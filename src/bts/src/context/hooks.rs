@@ -0,0 +1,73 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use uuid::Uuid;
+
+pub type ValueChangesHook = Arc<dyn Fn(&HashSet<String>) + Send + Sync>;
+
+// Called with the names of the values put onto the blackboard of a context, by its nodes
+// or from outside, so that reactive nodes and scopes of subtrees can act on them.
+#[derive(Default)]
+pub struct ValueChangesHooks {
+
+    hooks: DashMap<Uuid, ValueChangesHook>
+
+}
+
+impl ValueChangesHooks {
+
+    pub fn add(&self,
+               hook: ValueChangesHook) -> Uuid {
+        let id = Uuid::new_v4();
+
+        self.hooks.insert(id, hook);
+        id
+    }
+
+    pub fn remove(&self,
+                  id: &Uuid) {
+        self.hooks.remove(id);
+    }
+
+    // Hooks are called outside of the map, as they may add hooks of their own.
+    pub fn notify(&self,
+                  changed_value_names: &HashSet<String>) {
+        if changed_value_names.is_empty() {
+            return;
+        }
+
+        let hooks: Vec<ValueChangesHook> = self.hooks
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+
+        for hook in hooks {
+            hook(changed_value_names);
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_notifies_hooks_until_removed() {
+        let hooks = ValueChangesHooks::default();
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let recorded = notified.clone();
+        let id = hooks.add(Arc::new(move |changed| recorded.lock().unwrap().push(changed.len())));
+
+        hooks.notify(&vec!["a".to_owned(), "b".to_owned()].into_iter().collect());
+        hooks.notify(&HashSet::new());
+        hooks.remove(&id);
+        hooks.notify(&vec!["a".to_owned()].into_iter().collect());
+
+        assert_eq!(vec![2], *notified.lock().unwrap());
+    }
+
+}
@@ -0,0 +1,146 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use buttercup_values::ValuesPayload;
+
+// Nodes keep no memory between ticks, every tick runs to completion, so the blackboard
+// is the whole runtime state of an execution context. Reactive nodes register again on
+// the first tick after restore.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct BTNodeContextSnapshot {
+
+    id: Uuid,
+    values: ValuesPayload
+
+}
+
+impl BTNodeContextSnapshot {
+
+    pub fn new(id: Uuid,
+               values: ValuesPayload) -> BTNodeContextSnapshot {
+        BTNodeContextSnapshot {
+            id,
+            values
+        }
+    }
+
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_values(&self) -> &ValuesPayload {
+        &self.values
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum SnapshotStorageError {
+
+    DeserializeError(String),
+    IoError(String),
+    SerializeError(String)
+
+}
+
+pub trait SnapshotStorage: Send + Sync {
+
+    fn save(&self,
+            snapshot: &BTNodeContextSnapshot) -> Result<(), SnapshotStorageError>;
+
+    fn load(&self,
+            id: &Uuid) -> Result<Option<BTNodeContextSnapshot>, SnapshotStorageError>;
+
+    fn remove(&self,
+              id: &Uuid) -> Result<(), SnapshotStorageError>;
+
+}
+
+pub struct FileSnapshotStorage {
+
+    directory: PathBuf
+
+}
+
+impl FileSnapshotStorage {
+
+    pub fn new(directory: PathBuf) -> FileSnapshotStorage {
+        FileSnapshotStorage {
+            directory
+        }
+    }
+
+    fn get_path(&self,
+                id: &Uuid) -> PathBuf {
+        self.directory.join(format!("{}.snapshot.json", id))
+    }
+
+}
+
+impl SnapshotStorage for FileSnapshotStorage {
+
+    fn save(&self,
+            snapshot: &BTNodeContextSnapshot) -> Result<(), SnapshotStorageError> {
+        let serialized = serde_json::to_vec(snapshot)
+            .map_err(|err| SnapshotStorageError::SerializeError(err.to_string()))?;
+
+        fs::create_dir_all(&self.directory)
+            .and_then(|_| fs::write(self.get_path(snapshot.get_id()), serialized))
+            .map_err(|err| SnapshotStorageError::IoError(err.to_string()))
+    }
+
+    fn load(&self,
+            id: &Uuid) -> Result<Option<BTNodeContextSnapshot>, SnapshotStorageError> {
+        match fs::read(self.get_path(id)) {
+            Ok(serialized) => serde_json::from_slice(&serialized)
+                .map(Option::Some)
+                .map_err(|err| SnapshotStorageError::DeserializeError(err.to_string())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Result::Ok(Option::None),
+            Err(err) => Result::Err(SnapshotStorageError::IoError(err.to_string()))
+        }
+    }
+
+    fn remove(&self,
+              id: &Uuid) -> Result<(), SnapshotStorageError> {
+        match fs::remove_file(self.get_path(id)) {
+            Err(err) if err.kind() != ErrorKind::NotFound =>
+                Result::Err(SnapshotStorageError::IoError(err.to_string())),
+            _ => Result::Ok(())
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use buttercup_values::ValueHolder;
+
+    use super::*;
+
+    #[test]
+    fn test_saves_and_loads_snapshots() {
+        let directory: PathBuf = format!("{}.snapshots", Uuid::new_v4()).into();
+        let storage = FileSnapshotStorage::new(directory.clone());
+        let snapshot = BTNodeContextSnapshot::new(
+            Uuid::new_v4(),
+            ValuesPayload::singleton(
+                "name".to_owned(), ValueHolder::String(Arc::new("value".to_owned()))));
+
+        assert_eq!(Result::Ok(Option::None), storage.load(snapshot.get_id()));
+
+        storage.save(&snapshot).unwrap();
+        assert_eq!(Result::Ok(Option::Some(snapshot.clone())), storage.load(snapshot.get_id()));
+
+        storage.remove(snapshot.get_id()).unwrap();
+        assert_eq!(Result::Ok(Option::None), storage.load(snapshot.get_id()));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+}
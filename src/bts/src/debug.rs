@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tick::{TickError, TickStatus};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum DebugEvent {
+
+    NodeEntered(i32),
+    NodeExited(i32, Result<TickStatus, TickError>),
+    ConditionEvaluated(i32, bool)
+
+}
+
+// Records everything which happens during a single evaluation, in order, so that it can
+// be stepped through afterwards.
+#[derive(Default)]
+pub struct DebugRecorder {
+
+    events: Mutex<Vec<DebugEvent>>
+
+}
+
+impl DebugRecorder {
+
+    pub fn record(&self,
+                  event: DebugEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    pub fn get_events(&self) -> Vec<DebugEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DebugState {
+
+    step: usize,
+    event: Option<DebugEvent>,
+    current_node: Option<i32>,
+    pending_edges: Vec<(i32, i32)>,
+    condition_results: BTreeMap<i32, bool>,
+    finished: bool
+
+}
+
+impl DebugState {
+
+    pub fn get_step(&self) -> &usize {
+        &self.step
+    }
+
+    pub fn get_event(&self) -> &Option<DebugEvent> {
+        &self.event
+    }
+
+    pub fn get_current_node(&self) -> &Option<i32> {
+        &self.current_node
+    }
+
+    pub fn get_pending_edges(&self) -> &Vec<(i32, i32)> {
+        &self.pending_edges
+    }
+
+    pub fn get_condition_results(&self) -> &BTreeMap<i32, bool> {
+        &self.condition_results
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+}
+
+// Steps through a recorded evaluation one event at a time. The nodes which were entered
+// but not exited yet form the path from the root to the current node, its edges are the
+// pending ones.
+pub struct DebugSession {
+
+    events: Vec<DebugEvent>,
+    position: usize,
+    path: Vec<i32>,
+    condition_results: BTreeMap<i32, bool>
+
+}
+
+impl DebugSession {
+
+    pub fn new(events: Vec<DebugEvent>) -> DebugSession {
+        DebugSession {
+            events,
+            position: 0,
+            path: Vec::new(),
+            condition_results: BTreeMap::new()
+        }
+    }
+
+    pub fn step(&mut self) -> DebugState {
+        let event = self.events.get(self.position).cloned();
+
+        match &event {
+            Some(DebugEvent::NodeEntered(node_id)) => self.path.push(*node_id),
+            Some(DebugEvent::NodeExited(_, _)) => { self.path.pop(); },
+            Some(DebugEvent::ConditionEvaluated(node_id, passed)) => {
+                self.condition_results.insert(*node_id, *passed);
+            },
+            None => {}
+        }
+
+        if event.is_some() {
+            self.position += 1;
+        }
+
+        self.get_state(event)
+    }
+
+    pub fn get_state(&self,
+                     event: Option<DebugEvent>) -> DebugState {
+        DebugState {
+            step: self.position,
+            current_node: match &event {
+                Some(DebugEvent::NodeExited(node_id, _)) => Option::Some(*node_id),
+                _ => self.path.last().copied()
+            },
+            event,
+            pending_edges: self.path
+                .windows(2)
+                .map(|edge| (edge[0], edge[1]))
+                .collect(),
+            condition_results: self.condition_results.clone(),
+            finished: self.position == self.events.len()
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct VisitedNode {
+
+    node_id: i32,
+    parent_id: Option<i32>,
+    condition_passed: Option<bool>,
+    result: Option<Result<TickStatus, TickError>>
+
+}
+
+impl VisitedNode {
+
+    pub fn get_node_id(&self) -> &i32 {
+        &self.node_id
+    }
+
+    pub fn get_parent_id(&self) -> &Option<i32> {
+        &self.parent_id
+    }
+
+    pub fn get_condition_passed(&self) -> &Option<bool> {
+        &self.condition_passed
+    }
+
+    pub fn get_result(&self) -> &Option<Result<TickStatus, TickError>> {
+        &self.result
+    }
+
+}
+
+// The nodes visited by a recorded evaluation, in the order in which they were entered,
+// each with the edge from its parent it was entered through, the outcome of its condition
+// if it checks one, and its result. Nodes left without a result were still running when
+// the evaluation was aborted.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct EvaluationPath {
+
+    visited_nodes: Vec<VisitedNode>
+
+}
+
+impl EvaluationPath {
+
+    pub fn new(events: &[DebugEvent]) -> EvaluationPath {
+        let mut visited_nodes: Vec<VisitedNode> = Vec::new();
+        let mut path: Vec<usize> = Vec::new();
+
+        for event in events {
+            match event {
+                DebugEvent::NodeEntered(node_id) => {
+                    visited_nodes.push(VisitedNode {
+                        node_id: *node_id,
+                        parent_id: path.last().map(|parent| visited_nodes[*parent].node_id),
+                        condition_passed: Option::None,
+                        result: Option::None
+                    });
+                    path.push(visited_nodes.len() - 1);
+                },
+                DebugEvent::NodeExited(_, result) => if let Some(visited) = path.pop() {
+                    visited_nodes[visited].result = Option::Some(result.clone());
+                },
+                DebugEvent::ConditionEvaluated(node_id, passed) => {
+                    if let Some(visited) = path.iter().rev().find(|visited| visited_nodes[**visited].node_id == *node_id) {
+                        visited_nodes[*visited].condition_passed = Option::Some(*passed);
+                    }
+                }
+            }
+        }
+
+        EvaluationPath {
+            visited_nodes
+        }
+    }
+
+    pub fn get_visited_nodes(&self) -> &Vec<VisitedNode> {
+        &self.visited_nodes
+    }
+
+    pub fn get_edges(&self) -> Vec<(i32, i32)> {
+        self.visited_nodes
+            .iter()
+            .filter_map(|visited| visited.parent_id.map(|parent_id| (parent_id, visited.node_id)))
+            .collect()
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_steps_through_recorded_events() {
+        let mut session = DebugSession::new(vec![
+            DebugEvent::NodeEntered(1),
+            DebugEvent::NodeEntered(2),
+            DebugEvent::ConditionEvaluated(2, true),
+            DebugEvent::NodeEntered(3),
+            DebugEvent::NodeExited(3, Result::Ok(TickStatus::Success)),
+            DebugEvent::NodeExited(2, Result::Ok(TickStatus::Success)),
+            DebugEvent::NodeExited(1, Result::Ok(TickStatus::Success))
+        ]);
+
+        session.step();
+        session.step();
+        session.step();
+        let state = session.step();
+
+        assert_eq!(&Option::Some(3), state.get_current_node());
+        assert_eq!(&vec![(1, 2), (2, 3)], state.get_pending_edges());
+        assert_eq!(Option::Some(&true), state.get_condition_results().get(&2));
+        assert!(!state.is_finished());
+
+        let state = session.step();
+
+        assert_eq!(&Option::Some(3), state.get_current_node());
+        assert_eq!(&vec![(1, 2)], state.get_pending_edges());
+
+        session.step();
+        assert!(session.step().is_finished());
+        assert_eq!(&Option::None, session.step().get_event());
+    }
+
+    #[test]
+    fn test_builds_path_of_recorded_events() {
+        let path = EvaluationPath::new(&[
+            DebugEvent::NodeEntered(1),
+            DebugEvent::NodeEntered(2),
+            DebugEvent::ConditionEvaluated(2, false),
+            DebugEvent::NodeExited(2, Result::Ok(TickStatus::Failure)),
+            DebugEvent::NodeEntered(3),
+            DebugEvent::NodeEntered(4)
+        ]);
+
+        assert_eq!(&vec![(1, 2), (1, 3), (3, 4)], &path.get_edges());
+        assert_eq!(&Option::Some(false), path.get_visited_nodes()[1].get_condition_passed());
+        assert_eq!(&Option::Some(Result::Ok(TickStatus::Failure)), path.get_visited_nodes()[1].get_result());
+        assert_eq!(&Option::None, path.get_visited_nodes()[0].get_condition_passed());
+        assert_eq!(&Option::None, path.get_visited_nodes()[3].get_result());
+    }
+
+}
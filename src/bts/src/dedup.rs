@@ -0,0 +1,106 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+const MIN_FINGERPRINTS_SWEEP: usize = 1024;
+
+// Fingerprints of the content which already passed, shared by all the trees. Each of
+// them is remembered for the ttl given when it was seen.
+pub trait FingerprintStore: Send + Sync {
+
+    // Remembers the key unless it was seen within its ttl, returns whether it is new.
+    fn insert_if_unseen(&self,
+                        key: &str,
+                        ttl: &Duration) -> bool;
+
+}
+
+pub struct InMemoryFingerprintStore {
+
+    expirations: DashMap<String, Instant>,
+    next_sweep_len: AtomicUsize
+
+}
+
+impl Default for InMemoryFingerprintStore {
+    fn default() -> Self {
+        InMemoryFingerprintStore {
+            expirations: DashMap::new(),
+            next_sweep_len: AtomicUsize::new(MIN_FINGERPRINTS_SWEEP)
+        }
+    }
+}
+
+impl InMemoryFingerprintStore {
+
+    fn insert_if_unseen_at(&self,
+                           key: &str,
+                           ttl: &Duration,
+                           now: Instant) -> bool {
+        let inserted = {
+            let mut expiration = self.expirations.entry(key.to_owned()).or_insert(now);
+
+            if *expiration > now {
+                false
+            } else {
+                *expiration = now + *ttl;
+                true
+            }
+        };
+
+        // Expired fingerprints are dropped once the store doubles, so the sweeps take
+        // amortized constant time per insert.
+        if inserted && self.expirations.len() >= self.next_sweep_len.load(Ordering::Relaxed) {
+            self.expirations.retain(|_, expiration| *expiration > now);
+            self.next_sweep_len.store((2 * self.expirations.len()).max(MIN_FINGERPRINTS_SWEEP), Ordering::Relaxed);
+        }
+
+        inserted
+    }
+
+}
+
+impl FingerprintStore for InMemoryFingerprintStore {
+
+    fn insert_if_unseen(&self,
+                        key: &str,
+                        ttl: &Duration) -> bool {
+        self.insert_if_unseen_at(key, ttl, Instant::now())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_remembers_fingerprints_for_ttl() {
+        let store = InMemoryFingerprintStore::default();
+        let ttl = Duration::from_secs(60);
+        let now = Instant::now();
+
+        assert!(store.insert_if_unseen_at("alice:1", &ttl, now));
+        assert!(!store.insert_if_unseen_at("alice:1", &ttl, now + Duration::from_secs(59)));
+        assert!(store.insert_if_unseen_at("bob:1", &ttl, now + Duration::from_secs(59)));
+        assert!(store.insert_if_unseen_at("alice:1", &ttl, now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_sweeps_expired_fingerprints() {
+        let store = InMemoryFingerprintStore::default();
+        let ttl = Duration::from_secs(1);
+        let now = Instant::now();
+
+        for index in 0..MIN_FINGERPRINTS_SWEEP - 1 {
+            store.insert_if_unseen_at(&index.to_string(), &ttl, now);
+        }
+
+        assert_eq!(MIN_FINGERPRINTS_SWEEP - 1, store.expirations.len());
+        assert!(store.insert_if_unseen_at("last", &ttl, now + ttl));
+        assert_eq!(1, store.expirations.len());
+    }
+
+}
@@ -1,6 +1,12 @@
+use std::collections::BTreeMap;
+
 use chrono::{NaiveDateTime, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use buttercup_values::ValuesPayload;
+
 use crate::tick::{TickError, TickHeader, TickStatus};
 
 #[derive(Debug)]
@@ -93,4 +99,124 @@ impl<'e> BTNodeExecutionStartedEvent<'e> {
         }
     }
 
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum MetricKind {
+
+    Counter,
+    Histogram
+
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct MetricEvent {
+
+    name: String,
+    kind: MetricKind,
+    value: f64,
+    labels: BTreeMap<String, String>,
+
+    correlation_id: Uuid,
+    tree_id: i32
+
+}
+
+impl MetricEvent {
+
+    pub fn new(name: String,
+               kind: MetricKind,
+               value: f64,
+               labels: BTreeMap<String, String>,
+               tick_header: &TickHeader) -> MetricEvent {
+        MetricEvent {
+            name,
+            kind,
+            value,
+            labels,
+            correlation_id: *tick_header.get_correlation_id(),
+            tree_id: *tick_header.get_tree_id()
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_kind(&self) -> &MetricKind {
+        &self.kind
+    }
+
+    pub fn get_value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn get_labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct BusinessEvent {
+
+    id: Uuid,
+    name: String,
+    created_at: NaiveDateTime,
+    values: ValuesPayload,
+
+    correlation_id: Uuid,
+    tree_id: i32
+
+}
+
+impl BusinessEvent {
+
+    pub fn new(name: String,
+               values: ValuesPayload,
+               tick_header: &TickHeader) -> BusinessEvent {
+        BusinessEvent {
+            id: Uuid::new_v4(),
+            name,
+            created_at: Utc::now().naive_utc(),
+            values,
+            correlation_id: *tick_header.get_correlation_id(),
+            tree_id: *tick_header.get_tree_id()
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_values(&self) -> &ValuesPayload {
+        &self.values
+    }
+
+}
+
+pub trait AnalyticsSink: Send + Sync {
+
+    fn record_metric(&self,
+                     metric: MetricEvent);
+
+    fn publish_event(&self,
+                     event: BusinessEvent);
+
+}
+
+pub struct LoggingAnalyticsSink;
+
+impl AnalyticsSink for LoggingAnalyticsSink {
+
+    fn record_metric(&self,
+                     metric: MetricEvent) {
+        info!("{:?}", metric)
+    }
+
+    fn publish_event(&self,
+                     event: BusinessEvent) {
+        info!("{:?}", event)
+    }
+
+}
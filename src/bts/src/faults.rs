@@ -0,0 +1,143 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_std::task;
+use buttercup_blackboards::LocalBlackboardError;
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+// Failures injected into the value lookups of a context, so that the error handling of
+// trees can be checked end to end. Only built with the fault-injection feature, which
+// only tests should enable.
+#[derive(Default)]
+pub struct FaultInjector {
+
+    tick_delay: Option<Duration>,
+    poisoned_values: HashSet<String>,
+    malformed_values: HashMap<String, ValueHolder>,
+    aborted_after: Option<usize>,
+    lookups: AtomicUsize
+
+}
+
+impl FaultInjector {
+
+    // Ticks of nodes are delayed, the same way a slow blackboard would delay them, but
+    // without blocking the thread.
+    pub fn with_tick_delay(mut self,
+                           tick_delay: Duration) -> FaultInjector {
+        self.tick_delay = Option::Some(tick_delay);
+        self
+    }
+
+    // Lookups of the value fail as if the lock of the blackboard was poisoned. No lock is
+    // poisoned, the error is made up by the injector.
+    pub fn with_poisoned_value(mut self,
+                               value_name: &str) -> FaultInjector {
+        self.poisoned_values.insert(value_name.to_owned());
+        self
+    }
+
+    // Lookups of the value return the given one instead, whether the value is there or not.
+    pub fn with_malformed_value(mut self,
+                                value_name: &str,
+                                value: ValueHolder) -> FaultInjector {
+        self.malformed_values.insert(value_name.to_owned(), value);
+        self
+    }
+
+    // Lookups fail once there were that many, as if the evaluation was aborted midway.
+    // Nothing is aborted, the error is made up by the injector.
+    pub fn with_aborted_after(mut self,
+                              lookups: usize) -> FaultInjector {
+        self.aborted_after = Option::Some(lookups);
+        self
+    }
+
+    pub fn get_lookups(&self) -> usize {
+        self.lookups.load(Ordering::Relaxed)
+    }
+
+    pub async fn delay(&self) {
+        if let Some(tick_delay) = self.tick_delay {
+            task::sleep(tick_delay).await;
+        }
+    }
+
+    pub fn inject<F>(&self,
+                     value_names: &HashSet<String>,
+                     lookup: F) -> Result<ValuesPayload, LocalBlackboardError>
+        where F: FnOnce() -> Result<ValuesPayload, LocalBlackboardError> {
+        let lookups = self.lookups.fetch_add(1, Ordering::Relaxed);
+        if matches!(self.aborted_after, Some(aborted_after) if lookups >= aborted_after) {
+            return Result::Err(LocalBlackboardError::AccessError(
+                format!("Lookup {} aborted by fault injection", lookups + 1)));
+        }
+
+        if let Some(value_name) = value_names.iter().find(|value_name| self.poisoned_values.contains(*value_name)) {
+            return Result::Err(LocalBlackboardError::LockPoisonedError(
+                format!("Value {} poisoned by fault injection", value_name)));
+        }
+
+        let mut values = lookup()?.get_values().clone();
+        for value_name in value_names {
+            if let Some(value) = self.malformed_values.get(value_name) {
+                values.insert(value_name.clone(), value.clone());
+            }
+        }
+
+        Result::Ok(ValuesPayload::new(values))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::Instant;
+
+    use futures::future;
+
+    use super::*;
+
+    fn names(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| (*name).to_owned()).collect()
+    }
+
+    fn lookup() -> Result<ValuesPayload, LocalBlackboardError> {
+        Result::Ok(ValuesPayload::singleton("name".to_owned(), ValueHolder::from("John")))
+    }
+
+    #[test]
+    fn test_injects_configured_faults() {
+        let injector = FaultInjector::default()
+            .with_poisoned_value("secret")
+            .with_malformed_value("name", ValueHolder::Boolean(true))
+            .with_aborted_after(2);
+
+        assert_eq!(Option::Some(&ValueHolder::Boolean(true)),
+                   injector.inject(&names(&["name"]), lookup).unwrap().get(&"name".to_owned()));
+        assert!(matches!(injector.inject(&names(&["name", "secret"]), lookup),
+                         Err(LocalBlackboardError::LockPoisonedError(_))));
+        assert!(matches!(injector.inject(&names(&["name"]), lookup),
+                         Err(LocalBlackboardError::AccessError(_))));
+        assert_eq!(3, injector.get_lookups());
+    }
+
+    #[actix_rt::test]
+    async fn test_delays_ticks_without_blocking_the_thread() {
+        let injector = FaultInjector::default().with_tick_delay(Duration::from_millis(50));
+        let started_at = Instant::now();
+
+        future::join(injector.delay(), injector.delay()).await;
+
+        assert!(started_at.elapsed() >= Duration::from_millis(50));
+        assert!(started_at.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_passes_lookups_through_by_default() {
+        assert_eq!(lookup(), FaultInjector::default().inject(&names(&["name"]), lookup));
+    }
+
+}
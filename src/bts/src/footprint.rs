@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+// Approximate memory taken by a compiled tree. Nodes are counted with their own size,
+// expressions with the names of the values they read, other heap allocations are left
+// out, so the numbers are meant for comparing trees rather than for exact accounting.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct TreeFootprint {
+
+    tree_id: i32,
+    node_count: usize,
+    expression_count: usize,
+    estimated_bytes: usize
+
+}
+
+impl TreeFootprint {
+
+    pub fn new(tree_id: i32) -> TreeFootprint {
+        TreeFootprint {
+            tree_id,
+            node_count: 0,
+            expression_count: 0,
+            estimated_bytes: 0
+        }
+    }
+
+    pub fn add_node(&mut self,
+                    bytes: usize) {
+        self.node_count += 1;
+        self.estimated_bytes += bytes;
+    }
+
+    pub fn add_expressions(&mut self,
+                           count: usize,
+                           bytes: usize) {
+        self.expression_count += count;
+        self.estimated_bytes += bytes;
+    }
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_node_count(&self) -> &usize {
+        &self.node_count
+    }
+
+    pub fn get_expression_count(&self) -> &usize {
+        &self.expression_count
+    }
+
+    pub fn get_estimated_bytes(&self) -> &usize {
+        &self.estimated_bytes
+    }
+
+}
+
+pub fn estimate_strings<'a>(strings: impl IntoIterator<Item = &'a String>) -> usize {
+    strings.into_iter()
+        .map(|string| std::mem::size_of::<String>() + string.capacity())
+        .sum()
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::tick::{TickError, TickStatus};
+
+#[derive(Default)]
+struct NodeHits {
+
+    successes: AtomicU64,
+    failures: AtomicU64,
+    errors: AtomicU64
+
+}
+
+#[derive(Default)]
+struct ConditionHits {
+
+    passed: AtomicU64,
+    failed: AtomicU64
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct NodeHitCounts {
+
+    successes: u64,
+    failures: u64,
+    errors: u64
+
+}
+
+impl NodeHitCounts {
+
+    pub fn new(successes: u64,
+               failures: u64,
+               errors: u64) -> NodeHitCounts {
+        NodeHitCounts {
+            successes,
+            failures,
+            errors
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct ConditionHitCounts {
+
+    passed: u64,
+    failed: u64
+
+}
+
+impl ConditionHitCounts {
+
+    pub fn new(passed: u64,
+               failed: u64) -> ConditionHitCounts {
+        ConditionHitCounts {
+            passed,
+            failed
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct HitCountsSnapshot {
+
+    nodes: BTreeMap<i32, NodeHitCounts>,
+    conditions: BTreeMap<i32, ConditionHitCounts>
+
+}
+
+impl HitCountsSnapshot {
+
+    pub fn get_nodes(&self) -> &BTreeMap<i32, NodeHitCounts> {
+        &self.nodes
+    }
+
+    pub fn get_conditions(&self) -> &BTreeMap<i32, ConditionHitCounts> {
+        &self.conditions
+    }
+
+}
+
+// Counts the outcomes of every node of a tree, i.e. how often the edge from its parent
+// passed or failed, and of every condition. There is one entry per node id at most, so
+// the memory is bounded by the size of the tree.
+#[derive(Default)]
+pub struct HitCounters {
+
+    nodes: DashMap<i32, NodeHits>,
+    conditions: DashMap<i32, ConditionHits>
+
+}
+
+impl HitCounters {
+
+    pub fn record_node(&self,
+                       node_id: &i32,
+                       result: &Result<TickStatus, TickError>) {
+        let hits = self.nodes.entry(*node_id).or_default();
+        let counter = match result {
+            Ok(TickStatus::Success) => &hits.successes,
+            Ok(TickStatus::Failure) => &hits.failures,
+            Err(_) => &hits.errors
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_condition(&self,
+                            node_id: &i32,
+                            passed: bool) {
+        let hits = self.conditions.entry(*node_id).or_default();
+        let counter = if passed { &hits.passed } else { &hits.failed };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get_snapshot(&self) -> HitCountsSnapshot {
+        HitCountsSnapshot {
+            nodes: self.nodes
+                .iter()
+                .map(|entry| (*entry.key(), NodeHitCounts::new(entry.successes.load(Ordering::Relaxed),
+                                                              entry.failures.load(Ordering::Relaxed),
+                                                              entry.errors.load(Ordering::Relaxed))))
+                .collect(),
+            conditions: self.conditions
+                .iter()
+                .map(|entry| (*entry.key(), ConditionHitCounts::new(entry.passed.load(Ordering::Relaxed),
+                                                                   entry.failed.load(Ordering::Relaxed))))
+                .collect()
+        }
+    }
+
+    pub fn reset(&self) {
+        self.nodes.clear();
+        self.conditions.clear();
+    }
+
+}
@@ -1,9 +1,27 @@
 #[macro_use]
 extern crate derivative;
 
+pub mod arbitration;
+pub mod breakpoint;
+pub mod budget;
+pub mod calendar;
+pub mod command;
 pub mod context;
+pub mod debug;
+pub mod dedup;
 pub mod events;
+#[cfg(feature = "fault-injection")]
+pub mod faults;
+pub mod footprint;
+pub mod hits;
+pub mod messages;
 pub mod node;
+pub mod outcomes;
+pub mod quota;
+pub mod semantics;
+pub mod signal;
+pub mod task;
 pub mod tick;
+pub mod trace;
 pub mod tree;
 
@@ -0,0 +1,152 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex, Weak};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::ValuesPayload;
+
+const MAX_MAILBOX_MESSAGES: usize = 1024;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct TreeMessage {
+
+    topic: String,
+    values: ValuesPayload
+
+}
+
+impl TreeMessage {
+
+    pub fn new(topic: String,
+               values: ValuesPayload) -> TreeMessage {
+        TreeMessage {
+            topic,
+            values
+        }
+    }
+
+    pub fn get_topic(&self) -> &String {
+        &self.topic
+    }
+
+    pub fn get_values(&self) -> &ValuesPayload {
+        &self.values
+    }
+
+}
+
+// Messages delivered to a single context, waiting for its next tick. When it is full the
+// oldest messages are dropped.
+#[derive(Default)]
+pub struct Mailbox {
+
+    messages: Mutex<VecDeque<TreeMessage>>,
+    topics: Mutex<HashSet<String>>
+
+}
+
+impl Mailbox {
+
+    fn deliver(&self,
+               message: TreeMessage) {
+        let mut messages = self.messages.lock().unwrap();
+        if messages.len() >= MAX_MAILBOX_MESSAGES {
+            messages.pop_front();
+        }
+        messages.push_back(message);
+    }
+
+    pub fn take(&self,
+                topic: &str) -> Option<TreeMessage> {
+        let mut messages = self.messages.lock().unwrap();
+        let position = messages.iter().position(|message| message.topic == topic)?;
+
+        messages.remove(position)
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+}
+
+// In-process publish/subscribe between the contexts of tree instances. Mailboxes are
+// held weakly, so the subscriptions of removed instances go away with them.
+#[derive(Default)]
+pub struct MessageBus {
+
+    subscriptions: DashMap<String, Vec<Weak<Mailbox>>>
+
+}
+
+impl MessageBus {
+
+    // Subscribing a mailbox to a topic more than once has no effect.
+    pub fn subscribe(&self,
+                     topic: &str,
+                     mailbox: &Arc<Mailbox>) {
+        if mailbox.topics.lock().unwrap().insert(topic.to_owned()) {
+            self.subscriptions
+                .entry(topic.to_owned())
+                .or_default()
+                .push(Arc::downgrade(mailbox));
+        }
+    }
+
+    // Delivers the message to every subscriber of its topic but the sender, returns the
+    // number of subscribers it was delivered to.
+    pub fn publish(&self,
+                   sender: &Arc<Mailbox>,
+                   message: TreeMessage) -> usize {
+        let mut subscribers = match self.subscriptions.get_mut(&message.topic) {
+            None => return 0,
+            Some(subscribers) => subscribers
+        };
+
+        subscribers.retain(|subscriber| subscriber.strong_count() > 0);
+
+        let mut delivered = 0;
+
+        for subscriber in subscribers.iter().filter_map(Weak::upgrade) {
+            if !Arc::ptr_eq(&subscriber, sender) {
+                subscriber.deliver(message.clone());
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_delivers_messages_to_other_subscribers() {
+        let bus = MessageBus::default();
+        let (sender, receiver, other) = (Arc::new(Mailbox::default()), Arc::new(Mailbox::default()), Arc::new(Mailbox::default()));
+
+        bus.subscribe("orders", &sender);
+        bus.subscribe("orders", &receiver);
+        bus.subscribe("orders", &receiver);
+        bus.subscribe("payments", &other);
+
+        assert_eq!(1, bus.publish(&sender, TreeMessage::new("orders".to_owned(), ValuesPayload::empty())));
+        assert!(sender.is_empty());
+        assert!(other.is_empty());
+        assert_eq!(Option::None, receiver.take("payments"));
+        assert!(receiver.take("orders").is_some());
+        assert!(receiver.is_empty());
+
+        drop(receiver);
+        assert_eq!(0, bus.publish(&sender, TreeMessage::new("orders".to_owned(), ValuesPayload::empty())));
+    }
+
+}
@@ -11,12 +11,15 @@ use buttercup_blackboards::{LocalBlackboard, LocalBlackboardError};
 use buttercup_values::ValuesPayload;
 
 use crate::context::BTNodeExecutionContext;
+use crate::debug::DebugEvent;
 use crate::events::{BTNodeExecutionEndedEvent, BTNodeExecutionStartedEvent};
+use crate::footprint::TreeFootprint;
 use crate::node::action::ActionBTNode;
 use crate::node::composite::CompositeBTNode;
 use crate::node::decorator::DecoratorBTNode;
 use crate::node::decorator::reactive::{DataChangeHandlingError, DataChangeHandlingStatus};
 use crate::tick::{TickError, TickHeader, TickStatus};
+use crate::trace::NodeTiming;
 
 pub mod action;
 pub mod composite;
@@ -42,10 +45,23 @@ pub trait BehaviorTreeNode {
 
     fn get_id(&self) -> &i32;
 
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+    }
+
     async fn tick(&self,
                   header: &TickHeader,
                   context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
         let node_id = self.get_id();
+
+        if let Some(breakpoints) = header.get_breakpoints() {
+            breakpoints.wait(node_id).await;
+        }
+
+        #[cfg(feature = "fault-injection")]
+        context.inject_tick_delay().await;
+
         let node_tick_id = Uuid::new_v4();
 
         let started_at = Utc::now().naive_utc();
@@ -55,11 +71,32 @@ pub trait BehaviorTreeNode {
                 &node_id, &node_tick_id, &started_at, header)
         ).await;
 
-        let result = self.do_tick(header, context).await;
+        if let Some(debug_recorder) = header.get_debug_recorder() {
+            debug_recorder.record(DebugEvent::NodeEntered(*node_id));
+        }
+
+        let result = match header.get_meter().visit_node(node_id) {
+            Ok(_) => self.do_tick(header, context).await,
+            Err(err) => Result::Err(err)
+        };
+
+        if let Some(debug_recorder) = header.get_debug_recorder() {
+            debug_recorder.record(DebugEvent::NodeExited(*node_id, result.clone()));
+        }
+
+        header.get_hit_counters().record_node(node_id, &result);
 
         let ended_at = Utc::now().naive_utc();
         let took_ms = ended_at.signed_duration_since(started_at).num_milliseconds();
 
+        if let Some(trace) = header.get_trace() {
+            trace.record(NodeTiming::new(*header.get_tree_id(),
+                                         *node_id,
+                                         ended_at.signed_duration_since(started_at).to_std().unwrap_or_default())
+                .with_started_micros((started_at.timestamp() * 1_000_000
+                    + started_at.timestamp_subsec_micros() as i64) as u64));
+        }
+
         context.consume_execution_ended_event(
             BTNodeExecutionEndedEvent::new(
                 &ended_at, &node_id, &node_tick_id, &result, &started_at, header, took_ms
@@ -91,6 +128,15 @@ impl BehaviorTreeNode for BTNode {
             BTNode::Decorator(node) => node.get_id(),
         }
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        match self {
+            BTNode::Action(node) => node.add_footprint(footprint),
+            BTNode::Composite(node) => node.add_footprint(footprint),
+            BTNode::Decorator(node) => node.add_footprint(footprint),
+        }
+    }
 }
 
 impl From<ActionBTNode> for BTNode {
@@ -3,23 +3,46 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::action::analytics::{EmitEventActionNode, EmitMetricActionNode};
+use crate::node::action::command::SelectCommandActionNode;
 use crate::node::action::logging::PrintLogActionNode;
+use crate::node::action::messages::{PublishMessageActionNode, ReceiveMessageActionNode};
+use crate::node::action::quota::QuotaActionNode;
 use crate::node::action::subtree::ExecuteSubTreeActionNode;
-use crate::node::action::wait::WaitDurationActionNode;
+use crate::node::action::task::HumanTaskActionNode;
+use crate::node::action::values::{SetValueActionNode, TransformValueActionNode};
+use crate::node::action::wait::{WaitDurationActionNode, WaitForSignalActionNode};
 use crate::tick::{TickError, TickHeader, TickStatus};
 
+pub mod analytics;
+pub mod command;
 pub mod logging;
+pub mod messages;
+pub mod quota;
 pub mod subtree;
+pub mod task;
+pub mod values;
 pub mod wait;
 
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub enum ActionBTNode {
 
+    EmitEvent(EmitEventActionNode),
+    EmitMetric(EmitMetricActionNode),
     ExecuteSubTree(ExecuteSubTreeActionNode),
+    HumanTask(HumanTaskActionNode),
     PrintLog(PrintLogActionNode),
-    WaitDuration(WaitDurationActionNode)
+    PublishMessage(PublishMessageActionNode),
+    Quota(QuotaActionNode),
+    ReceiveMessage(ReceiveMessageActionNode),
+    SelectCommand(SelectCommandActionNode),
+    SetValue(SetValueActionNode),
+    TransformValue(TransformValueActionNode),
+    WaitDuration(WaitDurationActionNode),
+    WaitForSignal(WaitForSignalActionNode)
 
 }
 
@@ -30,20 +53,69 @@ impl BehaviorTreeNode for ActionBTNode {
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
         match self {
+            ActionBTNode::EmitEvent(node) =>
+                node.do_tick(header, context).await,
+            ActionBTNode::EmitMetric(node) =>
+                node.do_tick(header, context).await,
             ActionBTNode::ExecuteSubTree(node) =>
                 node.do_tick(header, context).await,
+            ActionBTNode::HumanTask(node) =>
+                node.do_tick(header, context).await,
             ActionBTNode::PrintLog(node) =>
                 node.do_tick(header, context).await,
+            ActionBTNode::PublishMessage(node) =>
+                node.do_tick(header, context).await,
+            ActionBTNode::Quota(node) =>
+                node.do_tick(header, context).await,
+            ActionBTNode::ReceiveMessage(node) =>
+                node.do_tick(header, context).await,
+            ActionBTNode::SelectCommand(node) =>
+                node.do_tick(header, context).await,
+            ActionBTNode::SetValue(node) =>
+                node.do_tick(header, context).await,
+            ActionBTNode::TransformValue(node) =>
+                node.do_tick(header, context).await,
             ActionBTNode::WaitDuration(node) =>
                 node.do_tick(header, context).await,
+            ActionBTNode::WaitForSignal(node) =>
+                node.do_tick(header, context).await,
         }
     }
 
     fn get_id(&self) -> &i32 {
         match self {
+            ActionBTNode::EmitEvent(node) => node.get_id(),
+            ActionBTNode::EmitMetric(node) => node.get_id(),
             ActionBTNode::ExecuteSubTree(node) => node.get_id(),
+            ActionBTNode::HumanTask(node) => node.get_id(),
             ActionBTNode::PrintLog(node) => node.get_id(),
+            ActionBTNode::PublishMessage(node) => node.get_id(),
+            ActionBTNode::Quota(node) => node.get_id(),
+            ActionBTNode::ReceiveMessage(node) => node.get_id(),
+            ActionBTNode::SelectCommand(node) => node.get_id(),
+            ActionBTNode::SetValue(node) => node.get_id(),
+            ActionBTNode::TransformValue(node) => node.get_id(),
             ActionBTNode::WaitDuration(node) => node.get_id(),
+            ActionBTNode::WaitForSignal(node) => node.get_id(),
+        }
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        match self {
+            ActionBTNode::EmitEvent(node) => node.add_footprint(footprint),
+            ActionBTNode::EmitMetric(node) => node.add_footprint(footprint),
+            ActionBTNode::ExecuteSubTree(node) => node.add_footprint(footprint),
+            ActionBTNode::HumanTask(node) => node.add_footprint(footprint),
+            ActionBTNode::PrintLog(node) => node.add_footprint(footprint),
+            ActionBTNode::PublishMessage(node) => node.add_footprint(footprint),
+            ActionBTNode::Quota(node) => node.add_footprint(footprint),
+            ActionBTNode::ReceiveMessage(node) => node.add_footprint(footprint),
+            ActionBTNode::SelectCommand(node) => node.add_footprint(footprint),
+            ActionBTNode::SetValue(node) => node.add_footprint(footprint),
+            ActionBTNode::TransformValue(node) => node.add_footprint(footprint),
+            ActionBTNode::WaitDuration(node) => node.add_footprint(footprint),
+            ActionBTNode::WaitForSignal(node) => node.add_footprint(footprint),
         }
     }
 }
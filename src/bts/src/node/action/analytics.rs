@@ -0,0 +1,295 @@
+use std::collections::{BTreeMap, HashSet};
+
+use async_trait::async_trait;
+use num::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+use crate::context::BTNodeExecutionContext;
+use crate::events::{BusinessEvent, MetricEvent, MetricKind};
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::action::ActionBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum MetricOperation {
+
+    Increment(u64),
+    Observe(String)
+
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct EmitMetricActionNode {
+
+    id: i32,
+    name: String,
+    operation: MetricOperation,
+    labels: Vec<(String, String)>
+
+}
+
+impl EmitMetricActionNode {
+
+    pub fn new(id: i32,
+               name: String,
+               operation: MetricOperation,
+               labels: Vec<(String, String)>) -> EmitMetricActionNode {
+        EmitMetricActionNode {
+            id,
+            name,
+            operation,
+            labels
+        }
+    }
+
+    fn get_value_names(&self) -> HashSet<String> {
+        let mut value_names: HashSet<String> = self.labels
+            .iter()
+            .map(|(_, value_name)| value_name.clone())
+            .collect();
+
+        if let MetricOperation::Observe(value_name) = &self.operation {
+            value_names.insert(value_name.clone());
+        }
+
+        value_names
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for EmitMetricActionNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let value_names = self.get_value_names();
+
+        header.record_lookups(value_names.len());
+
+        let values = context.get_values(&value_names)
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        let (kind, value) = match &self.operation {
+            MetricOperation::Increment(by) => (MetricKind::Counter, *by as f64),
+            MetricOperation::Observe(value_name) =>
+                match values.get(value_name).and_then(to_f64) {
+                    Option::Some(value) => (MetricKind::Histogram, value),
+                    Option::None => return Result::Ok(TickStatus::Failure)
+                }
+        };
+
+        let mut labels = BTreeMap::new();
+
+        for (label, value_name) in &self.labels {
+            match values.get(value_name) {
+                Option::Some(value) => labels.insert(label.clone(), render_label(value)),
+                Option::None => return Result::Ok(TickStatus::Failure)
+            };
+        }
+
+        context.get_analytics_sink()
+            .record_metric(MetricEvent::new(self.name.clone(), kind, value, labels, header));
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<EmitMetricActionNode> for BTNode {
+    fn from(node: EmitMetricActionNode) -> Self {
+        BTNode::Action(ActionBTNode::EmitMetric(node))
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct EmitEventActionNode {
+
+    id: i32,
+    name: String,
+    value_names: HashSet<String>
+
+}
+
+impl EmitEventActionNode {
+
+    pub fn new(id: i32,
+               name: String,
+               value_names: HashSet<String>) -> EmitEventActionNode {
+        EmitEventActionNode {
+            id,
+            name,
+            value_names
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for EmitEventActionNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        header.record_lookups(self.value_names.len());
+
+        let values: ValuesPayload = context.get_values(&self.value_names)
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        if self.value_names.iter().any(|name| values.get(name).is_none()) {
+            return Result::Ok(TickStatus::Failure);
+        }
+
+        context.get_analytics_sink()
+            .publish_event(BusinessEvent::new(self.name.clone(), values, header));
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<EmitEventActionNode> for BTNode {
+    fn from(node: EmitEventActionNode) -> Self {
+        BTNode::Action(ActionBTNode::EmitEvent(node))
+    }
+}
+
+fn to_f64(value: &ValueHolder) -> Option<f64> {
+    match value {
+        ValueHolder::Decimal(value) =>
+            value.numer().to_f64().zip(value.denom().to_f64()).map(|(numer, denom)| numer / denom),
+        ValueHolder::Integer(value) => value.to_f64(),
+        _ => Option::None
+    }
+}
+
+fn render_label(value: &ValueHolder) -> String {
+    match value {
+        ValueHolder::Boolean(value) => value.to_string(),
+        ValueHolder::Decimal(value) => value.to_string(),
+        ValueHolder::Integer(value) => value.to_string(),
+        ValueHolder::String(value) => value.to_string(),
+        ValueHolder::Symbol(symbol) => symbol.get_name().clone(),
+        _ => serde_json::to_string(value).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use num::BigInt;
+
+    use crate::context::test_utils;
+    use crate::events::AnalyticsSink;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingAnalyticsSink {
+        metrics: Mutex<Vec<MetricEvent>>,
+        events: Mutex<Vec<BusinessEvent>>
+    }
+
+    impl AnalyticsSink for RecordingAnalyticsSink {
+        fn record_metric(&self, metric: MetricEvent) {
+            self.metrics.lock().unwrap().push(metric);
+        }
+
+        fn publish_event(&self, event: BusinessEvent) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    fn context(sink: Arc<RecordingAnalyticsSink>) -> BTNodeExecutionContext {
+        let context = BTNodeExecutionContext::default().with_analytics_sink(sink);
+        let mut values = std::collections::HashMap::new();
+        values.insert("amount".to_owned(), ValueHolder::Integer(BigInt::from(42)));
+        values.insert("country".to_owned(), ValueHolder::String(Arc::new("PL".to_owned())));
+        context.put_values(&ValuesPayload::new(values)).unwrap();
+        context
+    }
+
+    #[actix_rt::test]
+    async fn test_observes_histogram_with_labels() {
+        let sink = Arc::new(RecordingAnalyticsSink::default());
+        let path = {
+            let context = context(sink.clone());
+            let node = EmitMetricActionNode::new(
+                1,
+                "order_amount".to_owned(),
+                MetricOperation::Observe("amount".to_owned()),
+                vec![("country".to_owned(), "country".to_owned())]);
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+
+        let metrics = sink.metrics.lock().unwrap();
+        assert_eq!(1, metrics.len());
+        assert_eq!(&MetricKind::Histogram, metrics[0].get_kind());
+        assert_eq!(42.0, metrics[0].get_value());
+        assert_eq!(Option::Some(&"PL".to_owned()), metrics[0].get_labels().get("country"));
+    }
+
+    #[actix_rt::test]
+    async fn test_fails_on_missing_label_value() {
+        let sink = Arc::new(RecordingAnalyticsSink::default());
+        let path = {
+            let context = context(sink.clone());
+            let node = EmitMetricActionNode::new(
+                1,
+                "orders".to_owned(),
+                MetricOperation::Increment(1),
+                vec![("segment".to_owned(), "segment".to_owned())]);
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+
+        assert!(sink.metrics.lock().unwrap().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_publishes_event_with_values() {
+        let sink = Arc::new(RecordingAnalyticsSink::default());
+        let path = {
+            let context = context(sink.clone());
+            let node = EmitEventActionNode::new(
+                1,
+                "order_placed".to_owned(),
+                vec!["amount".to_owned(), "country".to_owned()].into_iter().collect());
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(1, events.len());
+        assert_eq!("order_placed", events[0].get_name());
+        assert_eq!(2, events[0].get_values().get_values().len());
+    }
+
+}
@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+
+use crate::command::{CommandSelection, ContentCommandAddress};
+use crate::context::BTNodeExecutionContext;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::action::ActionBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+// Selects a command for the subject of the tick, it is not run by the node but arbitrated
+// against the commands other trees selected, so it only ends up in the trace.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct SelectCommandActionNode {
+
+    id: i32,
+    address: ContentCommandAddress
+
+}
+
+impl SelectCommandActionNode {
+
+    pub fn new(id: i32,
+               address: ContentCommandAddress) -> SelectCommandActionNode {
+        SelectCommandActionNode {
+            id,
+            address
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for SelectCommandActionNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     _: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        if let Some(trace) = header.get_trace() {
+            trace.record_command_selection(CommandSelection::new(*header.get_tree_id(), self.id, self.address));
+        }
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<SelectCommandActionNode> for BTNode {
+    fn from(node: SelectCommandActionNode) -> Self {
+        BTNode::Action(ActionBTNode::SelectCommand(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use uuid::Uuid;
+
+    use crate::context::test_utils;
+    use crate::trace::TickTrace;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_records_selection_in_trace() {
+        let node = SelectCommandActionNode::new(4, ContentCommandAddress::new(7, 1));
+        let trace = Arc::new(TickTrace::default());
+        let header = TickHeader::new(Uuid::new_v4(), Uuid::new_v4(), 2, Uuid::new_v4())
+            .with_trace(Option::Some(trace.clone()));
+
+        let path = {
+            let context = BTNodeExecutionContext::default();
+
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&header, &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        assert_eq!(vec![CommandSelection::new(2, 4, ContentCommandAddress::new(7, 1))],
+                   trace.get_command_selections());
+
+        test_utils::destroy(path);
+    }
+
+}
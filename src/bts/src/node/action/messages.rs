@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+
+use crate::context::BTNodeExecutionContext;
+use crate::messages::TreeMessage;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::action::ActionBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+// Publishes the given values from the blackboard to the other instances listening on the
+// topic.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct PublishMessageActionNode {
+
+    id: i32,
+    topic: String,
+    value_names: HashSet<String>
+
+}
+
+impl PublishMessageActionNode {
+
+    pub fn new(id: i32,
+               topic: String,
+               value_names: HashSet<String>) -> PublishMessageActionNode {
+        PublishMessageActionNode {
+            id,
+            topic,
+            value_names
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for PublishMessageActionNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        header.record_lookups(self.value_names.len());
+
+        let values = context.get_values(&self.value_names)
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        context.get_message_bus()
+            .publish(context.get_mailbox(), TreeMessage::new(self.topic.clone(), values));
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<PublishMessageActionNode> for BTNode {
+    fn from(node: PublishMessageActionNode) -> Self {
+        BTNode::Action(ActionBTNode::PublishMessage(node))
+    }
+}
+
+// Succeeds when a message on the topic arrived since the previous tick, its values are
+// put onto the blackboard. The instance listens on the topic from the first tick of the
+// node, messages published before are not received.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ReceiveMessageActionNode {
+
+    id: i32,
+    topic: String
+
+}
+
+impl ReceiveMessageActionNode {
+
+    pub fn new(id: i32,
+               topic: String) -> ReceiveMessageActionNode {
+        ReceiveMessageActionNode {
+            id,
+            topic
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for ReceiveMessageActionNode {
+
+    async fn do_tick(&self,
+                     _: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        context.get_message_bus().subscribe(&self.topic, context.get_mailbox());
+
+        match context.get_mailbox().take(&self.topic) {
+            None => Result::Ok(TickStatus::Failure),
+            Some(message) => {
+                context.put_values(message.get_values())
+                    .map_err(|err| TickError::BlackboardError(self.id, err))?;
+                Result::Ok(TickStatus::Success)
+            }
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<ReceiveMessageActionNode> for BTNode {
+    fn from(node: ReceiveMessageActionNode) -> Self {
+        BTNode::Action(ActionBTNode::ReceiveMessage(node))
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::context::BTNodeExecutionContext;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::action::ActionBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+// Counts the ticks of the node in the counter store and fails once the limit of the
+// window is reached, e.g. max 3 notifications per user per day. Without a subject the
+// counter is global, a subject value which is missing fails.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct QuotaActionNode {
+
+    id: i32,
+    counter: String,
+    subject_value_name: Option<String>,
+    limit: u64,
+    window: Duration
+
+}
+
+impl QuotaActionNode {
+
+    pub fn new(id: i32,
+               counter: String,
+               subject_value_name: Option<String>,
+               limit: u64,
+               window: Duration) -> QuotaActionNode {
+        QuotaActionNode {
+            id,
+            counter,
+            subject_value_name,
+            limit,
+            window
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for QuotaActionNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let key = match &self.subject_value_name {
+            None => self.counter.clone(),
+            Some(subject_value_name) => {
+                header.record_lookups(1);
+
+                let subject = context.get_value(subject_value_name)
+                    .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+                match subject.and_then(|subject| serde_json::to_string(&subject).ok()) {
+                    None => return Result::Ok(TickStatus::Failure),
+                    Some(subject) => format!("{}:{}", self.counter, subject)
+                }
+            }
+        };
+
+        if context.get_counter_store().try_increment(&key, self.limit, &self.window) {
+            Result::Ok(TickStatus::Success)
+        } else {
+            Result::Ok(TickStatus::Failure)
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<QuotaActionNode> for BTNode {
+    fn from(node: QuotaActionNode) -> Self {
+        BTNode::Action(ActionBTNode::Quota(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buttercup_values::{ValueHolder, ValuesPayload};
+
+    use crate::context::test_utils;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_fails_over_limit_per_subject() {
+        let node = QuotaActionNode::new(1,
+                                        "notifications".to_owned(),
+                                        Option::Some("user".to_owned()),
+                                        2,
+                                        Duration::from_secs(86_400));
+        let user = |name: &str| ValuesPayload::singleton("user".to_owned(), ValueHolder::from(name));
+
+        let path = {
+            let context = BTNodeExecutionContext::default();
+
+            assert_eq!(Result::Ok(TickStatus::Failure), node.do_tick(&TickHeader::default(), &context).await);
+
+            context.put_values(&user("alice")).unwrap();
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(TickStatus::Failure), node.do_tick(&TickHeader::default(), &context).await);
+
+            context.put_values(&user("bob")).unwrap();
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
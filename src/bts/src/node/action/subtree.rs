@@ -17,7 +17,9 @@ pub struct ExecuteSubTreeActionNode {
     id: i32,
 
     #[derivative(Debug(format_with="ExecuteSubTreeActionNode::fmt"))]
-    tree: Arc<BehaviorTree>
+    tree: Arc<BehaviorTree>,
+
+    namespace: Option<String>
 
 }
 
@@ -33,11 +35,20 @@ impl ExecuteSubTreeActionNode {
         Result::Ok(
             ExecuteSubTreeActionNode {
                 id,
-                tree
+                tree,
+                namespace: Option::None
             }
         )
     }
 
+    // Values of the subtree are kept in a scope of their own, rather than shared with the
+    // tree executing it.
+    pub fn with_namespace(mut self,
+                          namespace: String) -> ExecuteSubTreeActionNode {
+        self.namespace = Option::Some(namespace);
+        self
+    }
+
     fn fmt(tree: &Arc<BehaviorTree>,
            formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         formatter.write_str(format!("id: {}", tree.get_id()).as_str());
@@ -52,7 +63,10 @@ impl BehaviorTreeNode for ExecuteSubTreeActionNode {
     async fn do_tick(&self,
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
-        self.tree.subtree_tick(header, context).await
+        match &self.namespace {
+            Some(namespace) => self.tree.subtree_tick(header, context.get_scope(namespace).as_ref()).await,
+            None => self.tree.subtree_tick(header, context).await
+        }
     }
 
     fn get_id(&self) -> &i32 {
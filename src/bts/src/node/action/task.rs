@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use buttercup_endpoints::ArgumentDefinition;
+
+use crate::context::BTNodeExecutionContext;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::action::ActionBTNode;
+use crate::task::HumanTask;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+// Opens a task for a person to fill in the form made of the given arguments, the branch
+// waits until the task is completed and the form values are put onto the blackboard.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct HumanTaskActionNode {
+
+    id: i32,
+    title: String,
+    assignee: Option<String>,
+
+    #[derivative(Debug="ignore")]
+    form: HashMap<String, ArgumentDefinition>
+
+}
+
+impl HumanTaskActionNode {
+
+    pub fn new(id: i32,
+               title: String,
+               assignee: Option<String>,
+               form: HashMap<String, ArgumentDefinition>) -> HumanTaskActionNode {
+        HumanTaskActionNode {
+            id,
+            title,
+            assignee,
+            form
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for HumanTaskActionNode {
+
+    async fn do_tick(&self,
+                     _: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let task = HumanTask::new(self.id, self.title.clone(), self.assignee.clone(), &self.form);
+        let values = context.get_human_tasks().wait(task, self.form.clone()).await;
+
+        if !values.get_values().is_empty() {
+            context.put_values(&values)
+                .map_err(|err| TickError::BlackboardError(self.id, err))?;
+        }
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<HumanTaskActionNode> for BTNode {
+    fn from(node: HumanTaskActionNode) -> Self {
+        BTNode::Action(ActionBTNode::HumanTask(node))
+    }
+}
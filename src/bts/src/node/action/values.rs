@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use buttercup_transformations::Transformer;
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+use crate::context::BTNodeExecutionContext;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::action::ActionBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct SetValueActionNode {
+
+    id: i32,
+    value_name: String,
+    value: ValueHolder
+
+}
+
+impl SetValueActionNode {
+
+    pub fn new(id: i32,
+               value_name: String,
+               value: ValueHolder) -> SetValueActionNode {
+        SetValueActionNode {
+            id,
+            value_name,
+            value
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for SetValueActionNode {
+
+    async fn do_tick(&self,
+                     _: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        context.put_values(&ValuesPayload::singleton(self.value_name.clone(), self.value.clone()))
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<SetValueActionNode> for BTNode {
+    fn from(node: SetValueActionNode) -> Self {
+        BTNode::Action(ActionBTNode::SetValue(node))
+    }
+}
+
+// Only the results of the transformation requests are written back, so inputs
+// are never rewritten and do not trigger reactive nodes watching them.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct TransformValueActionNode {
+
+    id: i32,
+    input_names: HashSet<String>,
+    #[derivative(Debug = "ignore")]
+    transformer: Arc<Transformer>
+
+}
+
+impl TransformValueActionNode {
+
+    pub fn new(id: i32,
+               input_names: HashSet<String>,
+               transformer: Arc<Transformer>) -> TransformValueActionNode {
+        TransformValueActionNode {
+            id,
+            input_names,
+            transformer
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for TransformValueActionNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        header.record_lookups(self.input_names.len());
+
+        let inputs = context.get_values(&self.input_names)
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        let transformed = self.transformer.transform(&inputs)
+            .map_err(|err| TickError::TransformationError(self.id, format!("{:?}", err)))?;
+
+        let results: HashMap<String, ValueHolder> = self.transformer.get_result_value_names()
+            .into_iter()
+            .filter_map(|name| transformed.get(&name)
+                .cloned()
+                .map(|value| (name, value)))
+            .collect();
+
+        context.put_values(&ValuesPayload::new(results))
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<TransformValueActionNode> for BTNode {
+    fn from(node: TransformValueActionNode) -> Self {
+        BTNode::Action(ActionBTNode::TransformValue(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigInt;
+
+    use buttercup_transformations::mono::MonoInputTransformation;
+    use buttercup_transformations::transformer::{SingleInputTransformationDefinition, TransformationDefinition, TransformationRequest, TransformationType};
+
+    use crate::context::test_utils;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_sets_value() {
+        let path = {
+            let context = Default::default();
+            let node = SetValueActionNode::new(
+                1, "answer".to_owned(), ValueHolder::Integer(BigInt::from(42)));
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(Option::Some(ValueHolder::Integer(BigInt::from(42)))),
+                       context.get_value(&"answer".to_owned()));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_writes_transformation_results() {
+        let path = {
+            let context: BTNodeExecutionContext = Default::default();
+            context.put_values(
+                &ValuesPayload::singleton(
+                    "visited_at".to_owned(),
+                    ValueHolder::LocalDateTime(
+                        chrono::NaiveDate::from_ymd(2020, 3, 18).and_hms(12, 0, 0))))
+                .unwrap();
+            let node = TransformValueActionNode::new(
+                1,
+                vec!["visited_at".to_owned()].into_iter().collect(),
+                Arc::new(Transformer::new(vec![
+                    TransformationRequest::new_mono(
+                        TransformationDefinition::new(
+                            1, TransformationType::SingleInput, "visited_on".to_owned()),
+                        SingleInputTransformationDefinition::new(
+                            1,
+                            "visited_at".to_owned(),
+                            MonoInputTransformation::DayOfWeekFromDateTimeRetrieval))])));
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.do_tick(&TickHeader::default(), &context).await);
+            assert!(context.get_value(&"visited_on".to_owned()).unwrap().is_some());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
@@ -75,3 +75,53 @@ impl From<WaitDurationActionNode> for BTNode {
         BTNode::Action(ActionBTNode::WaitDuration(node))
     }
 }
+
+// Waits until the instance is sent the signal of the given name, the payload of the
+// signal is put onto the blackboard.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct WaitForSignalActionNode {
+
+    id: i32,
+    name: String
+
+}
+
+impl WaitForSignalActionNode {
+
+    pub fn new(id: i32,
+               name: String) -> WaitForSignalActionNode {
+        WaitForSignalActionNode {
+            id,
+            name
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for WaitForSignalActionNode {
+
+    async fn do_tick(&self,
+                     _: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let payload = context.get_signals().wait(&self.name).await;
+
+        if !payload.get_values().is_empty() {
+            context.put_values(&payload)
+                .map_err(|err| TickError::BlackboardError(self.id, err))?;
+        }
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<WaitForSignalActionNode> for BTNode {
+    fn from(node: WaitForSignalActionNode) -> Self {
+        BTNode::Action(ActionBTNode::WaitForSignal(node))
+    }
+}
@@ -3,23 +3,30 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::composite::compensating::CompensatingSequenceCompositeNode;
 use crate::node::composite::fallback::FallbackCompositeNode;
 use crate::node::composite::parallel::ParallelCompositeNode;
 use crate::node::composite::sequence::SequenceCompositeNode;
+use crate::node::composite::utility::UtilityCompositeNode;
 use crate::tick::{TickError, TickHeader, TickStatus};
 
+pub mod compensating;
 pub mod parallel;
 pub mod fallback;
 pub mod sequence;
+pub mod utility;
 
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub enum CompositeBTNode {
 
+    CompensatingSequence(CompensatingSequenceCompositeNode),
     Parallel(ParallelCompositeNode),
     Fallback(FallbackCompositeNode),
-    Sequence(SequenceCompositeNode)
+    Sequence(SequenceCompositeNode),
+    Utility(UtilityCompositeNode)
 
 }
 
@@ -30,20 +37,37 @@ impl BehaviorTreeNode for CompositeBTNode {
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
         match self {
+            CompositeBTNode::CompensatingSequence(node) =>
+                node.do_tick(header, context).await,
             CompositeBTNode::Parallel(node) =>
                 node.do_tick(header, context).await,
             CompositeBTNode::Fallback(node) =>
                 node.do_tick(header, context).await,
             CompositeBTNode::Sequence(node) =>
                 node.do_tick(header, context).await,
+            CompositeBTNode::Utility(node) =>
+                node.do_tick(header, context).await,
         }
     }
 
     fn get_id(&self) -> &i32 {
         match self {
+            CompositeBTNode::CompensatingSequence(node) => node.get_id(),
             CompositeBTNode::Parallel(node) => node.get_id(),
             CompositeBTNode::Fallback(node) => node.get_id(),
             CompositeBTNode::Sequence(node) => node.get_id(),
+            CompositeBTNode::Utility(node) => node.get_id(),
+        }
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        match self {
+            CompositeBTNode::CompensatingSequence(node) => node.add_footprint(footprint),
+            CompositeBTNode::Parallel(node) => node.add_footprint(footprint),
+            CompositeBTNode::Fallback(node) => node.add_footprint(footprint),
+            CompositeBTNode::Sequence(node) => node.add_footprint(footprint),
+            CompositeBTNode::Utility(node) => node.add_footprint(footprint),
         }
     }
 }
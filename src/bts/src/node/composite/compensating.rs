@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::composite::CompositeBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct CompensatingSequenceCompositeNode {
+
+    id: i32,
+    steps: Vec<CompensatedStep>
+
+}
+
+impl CompensatingSequenceCompositeNode {
+
+    pub fn new(id: i32,
+               steps: Vec<CompensatedStep>) -> CompensatingSequenceCompositeNode {
+        CompensatingSequenceCompositeNode {
+            id,
+            steps
+        }
+    }
+
+    async fn compensate(&self,
+                        num_completed_steps: usize,
+                        header: &TickHeader,
+                        context: &BTNodeExecutionContext) -> Vec<(i32, Result<TickStatus, TickError>)> {
+        let mut failed_compensations = Vec::new();
+
+        for step in self.steps[..num_completed_steps].iter().rev() {
+            if let Option::Some(compensation) = &step.compensation {
+                let result = compensation.tick(header, context).await;
+
+                if result != Result::Ok(TickStatus::Success) {
+                    failed_compensations.push((*compensation.get_id(), result));
+                }
+            }
+        }
+
+        failed_compensations
+    }
+
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct CompensatedStep {
+
+    action: BTNode,
+    compensation: Option<BTNode>
+
+}
+
+impl CompensatedStep {
+
+    pub fn new(action: BTNode,
+               compensation: Option<BTNode>) -> CompensatedStep {
+        CompensatedStep {
+            action,
+            compensation
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for CompensatingSequenceCompositeNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        for (index, step) in self.steps.iter().enumerate() {
+            let result = step.action.tick(header, context).await;
+
+            if result == Result::Ok(TickStatus::Success) {
+                continue;
+            }
+
+            let failed_compensations =
+                self.compensate(index, header, context).await;
+
+            if failed_compensations.is_empty() {
+                return result;
+            }
+
+            return Result::Err(
+                TickError::CompensationError(self.id, Arc::new(failed_compensations)));
+        }
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+
+        for step in &self.steps {
+            step.action.add_footprint(footprint);
+            if let Some(compensation) = &step.compensation {
+                compensation.add_footprint(footprint);
+            }
+        }
+    }
+}
+
+impl From<CompensatingSequenceCompositeNode> for BTNode {
+    fn from(node: CompensatingSequenceCompositeNode) -> Self {
+        BTNode::Composite(CompositeBTNode::CompensatingSequence(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::decorator::invert::InvertDecoratorNode;
+
+    use super::*;
+
+    fn failing_node(id: i32) -> BTNode {
+        InvertDecoratorNode::new(
+            id,
+            Box::new(PrintLogActionNode::new(id + 100, "Failing".to_string()).into()))
+            .into()
+    }
+
+    #[actix_rt::test]
+    async fn test_returns_step_failure_when_compensations_succeed() {
+        let path = {
+            let context = Default::default();
+            let node = CompensatingSequenceCompositeNode::new(
+                1,
+                vec![
+                    CompensatedStep::new(
+                        PrintLogActionNode::new(2, "Step one.".to_string()).into(),
+                        Option::Some(PrintLogActionNode::new(3, "Undo one.".to_string()).into())),
+                    CompensatedStep::new(failing_node(4), Option::None)
+                ]);
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_reports_failed_compensations_in_reverse_order() {
+        let path = {
+            let context = Default::default();
+            let node = CompensatingSequenceCompositeNode::new(
+                1,
+                vec![
+                    CompensatedStep::new(
+                        PrintLogActionNode::new(2, "Step one.".to_string()).into(),
+                        Option::Some(failing_node(3))),
+                    CompensatedStep::new(
+                        PrintLogActionNode::new(4, "Step two.".to_string()).into(),
+                        Option::Some(failing_node(5))),
+                    CompensatedStep::new(failing_node(6), Option::Some(failing_node(7)))
+                ]);
+
+            assert_eq!(Result::Err(
+                TickError::CompensationError(
+                    1,
+                    Arc::new(vec![
+                        (5, Result::Ok(TickStatus::Failure)),
+                        (3, Result::Ok(TickStatus::Failure))]))),
+                       node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::composite::CompositeBTNode;
 use crate::tick::{TickError, TickStatus, TickHeader};
@@ -56,10 +57,70 @@ impl BehaviorTreeNode for FallbackCompositeNode {
     fn get_id(&self) -> &i32 {
         &self.id
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+
+        for child in &self.children {
+            child.add_footprint(footprint);
+        }
+    }
 }
 
 impl From<FallbackCompositeNode> for BTNode {
     fn from(node: FallbackCompositeNode) -> Self {
         BTNode::Composite(CompositeBTNode::Fallback(node))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use buttercup_values::ValueHolder;
+
+    use crate::context::test_utils;
+    use crate::node::action::quota::QuotaActionNode;
+    use crate::node::action::values::SetValueActionNode;
+
+    use super::*;
+
+    fn exhausted_quota(id: i32) -> BTNode {
+        QuotaActionNode::new(id, format!("exhausted{}", id), Option::None, 0, Duration::from_secs(60)).into()
+    }
+
+    #[actix_rt::test]
+    async fn test_stops_at_first_successful_child() {
+        let (first, second) = ("first".to_owned(), "second".to_owned());
+        let path = {
+            let context: BTNodeExecutionContext = Default::default();
+            let node = FallbackCompositeNode::new(4, vec![
+                exhausted_quota(1),
+                SetValueActionNode::new(2, first.clone(), ValueHolder::Boolean(true)).into(),
+                SetValueActionNode::new(3, second.clone(), ValueHolder::Boolean(true)).into()]);
+
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Option::Some(ValueHolder::Boolean(true)), context.get_value(&first).unwrap());
+            assert_eq!(Option::None, context.get_value(&second).unwrap());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_fails_when_all_children_fail() {
+        let path = {
+            let context: BTNodeExecutionContext = Default::default();
+            let node = FallbackCompositeNode::new(3, vec![exhausted_quota(1), exhausted_quota(2)]);
+
+            assert_eq!(Result::Ok(TickStatus::Failure), node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
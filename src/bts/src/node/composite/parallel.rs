@@ -4,12 +4,42 @@ use std::sync::Arc;
 use actix_rt::Arbiter;
 use async_trait::async_trait;
 use futures::future::select_all;
+use serde::{Deserialize, Serialize};
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::composite::CompositeBTNode;
 use crate::tick::{TickError, TickStatus, TickHeader};
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum ParallelPolicy {
+
+    // Succeeds once all children have succeeded, fails as soon as any child fails.
+    RequireAll,
+
+    // Succeeds as soon as any child succeeds, fails once all children have failed.
+    RequireOne,
+
+    // Succeeds once the given number of children have succeeded, fails as soon as that
+    // number can no longer be reached.
+    RequireN(usize)
+
+}
+
+impl ParallelPolicy {
+
+    pub fn get_num_successes_to_succeed(&self,
+                                        num_children: usize) -> usize {
+        match self {
+            ParallelPolicy::RequireAll => num_children,
+            ParallelPolicy::RequireOne => 1,
+            ParallelPolicy::RequireN(num_successes) => *num_successes
+        }
+    }
+
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct ParallelCompositeNode {
@@ -27,6 +57,14 @@ impl ParallelCompositeNode {
                children: Vec<BTNode>,
                num_successes_to_succeed: usize)
         -> Result<ParallelCompositeNode, ParallelCompositeNodeBuildingError> {
+        ParallelCompositeNode::with_policy(id, children, &ParallelPolicy::RequireN(num_successes_to_succeed))
+    }
+
+    pub fn with_policy(id: i32,
+                       children: Vec<BTNode>,
+                       policy: &ParallelPolicy)
+        -> Result<ParallelCompositeNode, ParallelCompositeNodeBuildingError> {
+        let num_successes_to_succeed = policy.get_num_successes_to_succeed(children.len());
         if num_successes_to_succeed > children.len() {
             return Result::Err(
                 ParallelCompositeNodeBuildingError::NumOfSuccessesIsGreaterThanNumOfChildren);
@@ -104,6 +142,15 @@ impl BehaviorTreeNode for ParallelCompositeNode {
     fn get_id(&self) -> &i32 {
         &self.id
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+
+        for child in &self.children {
+            child.add_footprint(footprint);
+        }
+    }
 }
 
 impl From<ParallelCompositeNode> for BTNode {
@@ -121,6 +168,7 @@ mod tests {
 
     use crate::context::test_utils;
     use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::action::quota::QuotaActionNode;
     use crate::node::action::wait::WaitDurationActionNode;
 
     use super::*;
@@ -151,4 +199,62 @@ mod tests {
         test_utils::destroy(path);
     }
 
+    #[actix_rt::test]
+    async fn test_requires_all_children_to_succeed() {
+        let path = {
+            let context = Default::default();
+            let children: Vec<BTNode> = vec![
+                PrintLogActionNode::new(1, "I am one.".to_string()).into(),
+                exhausted_quota(2),
+                WaitDurationActionNode::new(3,
+                                            Duration::from_millis(10).into()).into()];
+            let status = ParallelCompositeNode::with_policy(4, children, &ParallelPolicy::RequireAll)
+                .unwrap()
+                .do_tick(&TickHeader::default(), &context)
+                .await
+                .unwrap();
+
+            assert_eq!(TickStatus::Failure, status);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_requires_one_child_to_succeed() {
+        let path = {
+            let context = Default::default();
+            let children: Vec<BTNode> = vec![
+                exhausted_quota(1),
+                WaitDurationActionNode::new(2,
+                                            Duration::from_millis(10).into()).into(),
+                exhausted_quota(3)];
+            let status = ParallelCompositeNode::with_policy(4, children, &ParallelPolicy::RequireOne)
+                .unwrap()
+                .do_tick(&TickHeader::default(), &context)
+                .await
+                .unwrap();
+
+            assert_eq!(TickStatus::Success, status);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    fn exhausted_quota(id: i32) -> BTNode {
+        QuotaActionNode::new(id, format!("exhausted{}", id), Option::None, 0, Duration::from_secs(60)).into()
+    }
+
+    #[test]
+    fn test_rejects_more_required_successes_than_children() {
+        let children: Vec<BTNode> = vec![
+            PrintLogActionNode::new(1, "I am one.".to_string()).into()];
+
+        assert!(ParallelCompositeNode::with_policy(2, children, &ParallelPolicy::RequireN(2)).is_err());
+    }
+
 }
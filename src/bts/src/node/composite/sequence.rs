@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::composite::CompositeBTNode;
 use crate::tick::{TickError, TickStatus, TickHeader};
@@ -53,6 +54,15 @@ impl BehaviorTreeNode for SequenceCompositeNode {
     fn get_id(&self) -> &i32 {
         &self.id
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+
+        for child in &self.children {
+            child.add_footprint(footprint);
+        }
+    }
 }
 
 impl From<SequenceCompositeNode> for BTNode {
@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use num::{BigRational, One, Zero};
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::{estimate_strings, TreeFootprint};
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::composite::CompositeBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ScoreExpression {
+
+    Constant(BigRational),
+    Product(Vec<ScoreExpression>),
+    Sum(Vec<ScoreExpression>),
+    Value(String)
+
+}
+
+impl ScoreExpression {
+
+    pub fn evaluate(&self,
+                    payload: &ValuesPayload) -> Option<BigRational> {
+        match self {
+            ScoreExpression::Constant(score) => Option::Some(score.clone()),
+            ScoreExpression::Product(factors) => factors
+                .iter()
+                .try_fold(BigRational::one(), |product, factor|
+                    factor.evaluate(payload).map(|factor| product * factor)),
+            ScoreExpression::Sum(terms) => terms
+                .iter()
+                .try_fold(BigRational::zero(), |sum, term|
+                    term.evaluate(payload).map(|term| sum + term)),
+            ScoreExpression::Value(name) => match payload.get(name) {
+                Option::Some(ValueHolder::Decimal(value)) => Option::Some(value.clone()),
+                Option::Some(ValueHolder::Integer(value)) =>
+                    Option::Some(BigRational::from_integer(value.clone())),
+                _ => Option::None
+            }
+        }
+    }
+
+    fn collect_value_names(&self,
+                           value_names: &mut HashSet<String>) {
+        match self {
+            ScoreExpression::Constant(_) => {},
+            ScoreExpression::Product(expressions) | ScoreExpression::Sum(expressions) =>
+                for expression in expressions {
+                    expression.collect_value_names(value_names);
+                },
+            ScoreExpression::Value(name) => {
+                value_names.insert(name.clone());
+            }
+        }
+    }
+
+}
+
+// Behaves like a fallback over children ordered by their scores, re-evaluated on every
+// tick. Children with equal scores keep their declared order, children whose score
+// cannot be evaluated are not ticked at all.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct UtilityCompositeNode {
+
+    id: i32,
+    children: Vec<(ScoreExpression, BTNode)>,
+    value_names: HashSet<String>
+
+}
+
+impl UtilityCompositeNode {
+
+    pub fn new(id: i32,
+               children: Vec<(ScoreExpression, BTNode)>) -> UtilityCompositeNode {
+        let mut value_names = HashSet::new();
+        for (score, _) in &children {
+            score.collect_value_names(&mut value_names);
+        }
+
+        UtilityCompositeNode {
+            id,
+            children,
+            value_names
+        }
+    }
+
+    fn rank(&self,
+            payload: &ValuesPayload) -> Vec<&BTNode> {
+        let mut scored: Vec<(BigRational, &BTNode)> = self.children
+            .iter()
+            .filter_map(|(score, child)| score.evaluate(payload).map(|score| (score, child)))
+            .collect();
+
+        scored.sort_by(|(first, _), (second, _)| second.cmp(first));
+        scored.into_iter().map(|(_, child)| child).collect()
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for UtilityCompositeNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        header.record_lookups(self.value_names.len());
+
+        let payload = context.get_values(&self.value_names)
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        let mut errs = Vec::new();
+
+        for child in self.rank(&payload) {
+            match child.tick(header, context).await {
+                Ok(TickStatus::Success) => return Result::Ok(TickStatus::Success),
+                Ok(TickStatus::Failure) => {},
+                Err(err) => errs.push((*err.get_node_id(), err)),
+            }
+        }
+
+        if errs.is_empty() {
+            return Result::Ok(TickStatus::Failure);
+        }
+
+        Result::Err(TickError::CompositeError(self.id, Arc::new(errs)))
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        footprint.add_expressions(self.children.len(), estimate_strings(&self.value_names));
+
+        for (_, child) in &self.children {
+            child.add_footprint(footprint);
+        }
+    }
+}
+
+impl From<UtilityCompositeNode> for BTNode {
+    fn from(node: UtilityCompositeNode) -> Self {
+        BTNode::Composite(CompositeBTNode::Utility(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use num::BigInt;
+
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+
+    use super::*;
+
+    fn constant(score: i64) -> ScoreExpression {
+        ScoreExpression::Constant(BigRational::from_integer(BigInt::from(score)))
+    }
+
+    #[test]
+    fn test_ranks_children_by_descending_score() {
+        let node = UtilityCompositeNode::new(
+            1,
+            vec![
+                (constant(1), PrintLogActionNode::new(2, "Low.".to_string()).into()),
+                (ScoreExpression::Sum(vec![constant(1), ScoreExpression::Value("bonus".to_owned())]),
+                 PrintLogActionNode::new(3, "Bonus.".to_string()).into()),
+                (ScoreExpression::Value("missing".to_owned()),
+                 PrintLogActionNode::new(4, "Missing.".to_string()).into()),
+                (constant(2), PrintLogActionNode::new(5, "High.".to_string()).into())
+            ]);
+
+        let mut values = HashMap::new();
+        values.insert("bonus".to_owned(), ValueHolder::Integer(BigInt::from(5)));
+
+        assert_eq!(vec![3, 5, 2],
+                   node.rank(&ValuesPayload::new(values))
+                       .into_iter()
+                       .map(|child| *child.get_id())
+                       .collect::<Vec<_>>());
+    }
+
+    #[actix_rt::test]
+    async fn test_succeeds_with_best_scored_child() {
+        let path = {
+            let context = Default::default();
+            let node = UtilityCompositeNode::new(
+                1,
+                vec![(constant(1), PrintLogActionNode::new(2, "Chosen.".to_string()).into())]);
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
@@ -3,23 +3,45 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::calendar::BusinessHoursDecoratorNode;
 use crate::node::decorator::condition::ConditionDecoratorNode;
+use crate::node::decorator::dedup::DeduplicateDecoratorNode;
+use crate::node::decorator::escalation::EscalationDecoratorNode;
+use crate::node::decorator::guard::GuardDecoratorNode;
 use crate::node::decorator::invert::InvertDecoratorNode;
 use crate::node::decorator::reactive::ReactiveConditionDecoratorNode;
+use crate::node::decorator::repeat::RepeatDecoratorNode;
+use crate::node::decorator::retry::RetryDecoratorNode;
+use crate::node::decorator::timeout::TimeoutDecoratorNode;
 use crate::tick::{TickError, TickHeader, TickStatus};
 
+pub mod calendar;
 pub mod condition;
+pub mod dedup;
+pub mod escalation;
+pub mod guard;
 pub mod invert;
 pub mod reactive;
+pub mod repeat;
+pub mod retry;
+pub mod timeout;
 
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub enum DecoratorBTNode {
 
+    BusinessHours(BusinessHoursDecoratorNode),
     Condition(ConditionDecoratorNode),
+    Deduplicate(DeduplicateDecoratorNode),
+    Escalation(EscalationDecoratorNode),
+    Guard(GuardDecoratorNode),
     Invert(InvertDecoratorNode),
-    ReactiveCondition(ReactiveConditionDecoratorNode)
+    ReactiveCondition(ReactiveConditionDecoratorNode),
+    Repeat(RepeatDecoratorNode),
+    Retry(RetryDecoratorNode),
+    Timeout(TimeoutDecoratorNode)
 
 }
 
@@ -30,20 +52,57 @@ impl BehaviorTreeNode for DecoratorBTNode {
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
         match self {
+            DecoratorBTNode::BusinessHours(node) =>
+                node.do_tick(header, context).await,
             DecoratorBTNode::Condition(node) =>
                 node.do_tick(header, context).await,
+            DecoratorBTNode::Deduplicate(node) =>
+                node.do_tick(header, context).await,
+            DecoratorBTNode::Escalation(node) =>
+                node.do_tick(header, context).await,
+            DecoratorBTNode::Guard(node) =>
+                node.do_tick(header, context).await,
             DecoratorBTNode::Invert(node) =>
                 node.do_tick(header, context).await,
             DecoratorBTNode::ReactiveCondition(node) =>
+                node.do_tick(header, context).await,
+            DecoratorBTNode::Repeat(node) =>
+                node.do_tick(header, context).await,
+            DecoratorBTNode::Retry(node) =>
+                node.do_tick(header, context).await,
+            DecoratorBTNode::Timeout(node) =>
                 node.do_tick(header, context).await
         }
     }
 
     fn get_id(&self) -> &i32 {
         match self {
+            DecoratorBTNode::BusinessHours(node) => node.get_id(),
             DecoratorBTNode::Condition(node) => node.get_id(),
+            DecoratorBTNode::Deduplicate(node) => node.get_id(),
+            DecoratorBTNode::Escalation(node) => node.get_id(),
+            DecoratorBTNode::Guard(node) => node.get_id(),
             DecoratorBTNode::Invert(node) => node.get_id(),
             DecoratorBTNode::ReactiveCondition(node) => node.get_id(),
+            DecoratorBTNode::Repeat(node) => node.get_id(),
+            DecoratorBTNode::Retry(node) => node.get_id(),
+            DecoratorBTNode::Timeout(node) => node.get_id(),
+        }
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        match self {
+            DecoratorBTNode::BusinessHours(node) => node.add_footprint(footprint),
+            DecoratorBTNode::Condition(node) => node.add_footprint(footprint),
+            DecoratorBTNode::Deduplicate(node) => node.add_footprint(footprint),
+            DecoratorBTNode::Escalation(node) => node.add_footprint(footprint),
+            DecoratorBTNode::Guard(node) => node.add_footprint(footprint),
+            DecoratorBTNode::Invert(node) => node.add_footprint(footprint),
+            DecoratorBTNode::ReactiveCondition(node) => node.add_footprint(footprint),
+            DecoratorBTNode::Repeat(node) => node.add_footprint(footprint),
+            DecoratorBTNode::Retry(node) => node.add_footprint(footprint),
+            DecoratorBTNode::Timeout(node) => node.add_footprint(footprint),
         }
     }
 }
@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+// Ticks the child only within the working hours of the calendar, at the time taken from
+// the given value or now. A value which is missing or is not a point in time fails.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct BusinessHoursDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+    calendar: String,
+    time_value_name: Option<String>
+
+}
+
+impl BusinessHoursDecoratorNode {
+
+    pub fn new(id: i32,
+               child: BTNode,
+               calendar: String,
+               time_value_name: Option<String>) -> BusinessHoursDecoratorNode {
+        BusinessHoursDecoratorNode {
+            id,
+            child: Box::new(child),
+            calendar,
+            time_value_name
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for BusinessHoursDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let calendar = context.get_calendars()
+            .get_by_name(&self.calendar)
+            .ok_or_else(|| TickError::CalendarNotFound(self.id, self.calendar.clone()))?;
+        let open = match &self.time_value_name {
+            None => calendar.is_open_now(),
+            Some(time_value_name) => {
+                header.record_lookups(1);
+                context.get_value(time_value_name)
+                    .map_err(|err| TickError::BlackboardError(self.id, err))?
+                    .and_then(|value| calendar.is_open_at(&value))
+                    .unwrap_or(false)
+            }
+        };
+
+        header.record_condition(&self.id, open);
+
+        if open {
+            self.child.tick(header, context).await
+        } else {
+            Result::Ok(TickStatus::Failure)
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        self.child.add_footprint(footprint);
+    }
+}
+
+impl From<BusinessHoursDecoratorNode> for BTNode {
+    fn from(node: BusinessHoursDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::BusinessHours(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime, Weekday};
+    use chrono_tz::UTC;
+
+    use buttercup_values::calendar::{BusinessCalendar, WorkingHours};
+    use buttercup_values::{ValueHolder, ValuesPayload};
+
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+
+    use std::sync::Arc;
+
+    use crate::calendar::BusinessCalendars;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_ticks_child_within_working_hours() {
+        let calendars = Arc::new(BusinessCalendars::default());
+        calendars.insert("office".to_owned(),
+                         BusinessCalendar::new(UTC)
+                             .with_working_hours(Weekday::Tue,
+                                                 WorkingHours::new(NaiveTime::from_hms(9, 0, 0),
+                                                                   NaiveTime::from_hms(17, 0, 0))));
+        let node = |calendar: &str| BusinessHoursDecoratorNode::new(
+            1,
+            PrintLogActionNode::new(2, "Open.".to_owned()).into(),
+            calendar.to_owned(),
+            Option::Some("sent_at".to_owned()));
+        let sent_at = |hour| ValuesPayload::singleton(
+            "sent_at".to_owned(),
+            ValueHolder::LocalDateTime(NaiveDate::from_ymd(2021, 5, 4).and_hms(hour, 0, 0)));
+
+        let path = {
+            let context = BTNodeExecutionContext::default().with_calendars(calendars);
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node("office").do_tick(&TickHeader::default(), &context).await);
+
+            context.put_values(&sent_at(10)).unwrap();
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node("office").do_tick(&TickHeader::default(), &context).await);
+
+            context.put_values(&sent_at(18)).unwrap();
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node("office").do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Err(TickError::CalendarNotFound(1, "shop".to_owned())),
+                       node("shop").do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
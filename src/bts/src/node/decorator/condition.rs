@@ -11,6 +11,7 @@ use buttercup_conditions::ConditionExpressionWrapper;
 use buttercup_values::ValuesPayload;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::{estimate_strings, TreeFootprint};
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::decorator::DecoratorBTNode;
 use crate::tick::{TickError, TickStatus, TickHeader};
@@ -51,9 +52,17 @@ impl BehaviorTreeNode for ConditionDecoratorNode {
     async fn do_tick(&self,
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        header.record_lookups(self.value_names.len());
+
         match context.get_values(&self.value_names) {
             Ok(payload) => {
-                if self.predicate.deref()(&payload) {
+                check_missing_values(&self.id, &self.value_names, &payload, header)?;
+
+                let passed = self.predicate.deref()(&payload);
+
+                header.record_condition(&self.id, passed);
+
+                if passed {
                     return self.child.tick(header, context).await;
                 }
                 return Result::Ok(TickStatus::Failure);
@@ -65,6 +74,36 @@ impl BehaviorTreeNode for ConditionDecoratorNode {
     fn get_id(&self) -> &i32 {
         &self.id
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        footprint.add_expressions(1, estimate_strings(&self.value_names));
+        self.child.add_footprint(footprint);
+    }
+}
+
+// Shared by every node evaluating a condition, so that they all follow the semantics
+// version of the tree.
+pub(crate) fn check_missing_values(id: &i32,
+                                   value_names: &HashSet<String>,
+                                   payload: &ValuesPayload,
+                                   header: &TickHeader) -> Result<(), TickError> {
+    if !header.get_semantics_version().reports_missing_values() {
+        return Result::Ok(());
+    }
+
+    let mut missing: Vec<String> = value_names.iter()
+        .filter(|value_name| payload.get(value_name).is_none())
+        .cloned()
+        .collect();
+
+    if missing.is_empty() {
+        return Result::Ok(());
+    }
+
+    missing.sort();
+    Result::Err(TickError::MissingValues(*id, missing))
 }
 
 impl From<ConditionDecoratorNode> for BTNode {
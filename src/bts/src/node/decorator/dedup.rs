@@ -0,0 +1,154 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashSet};
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::{estimate_strings, TreeFootprint};
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+// Ticks the child only if the same content, the fingerprint of the given values, did not
+// pass for the same subject within the ttl. A subject value which is missing fails.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct DeduplicateDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+    value_names: BTreeSet<String>,
+    subject_value_name: Option<String>,
+    ttl: Duration
+
+}
+
+impl DeduplicateDecoratorNode {
+
+    pub fn new(id: i32,
+               child: BTNode,
+               value_names: HashSet<String>,
+               subject_value_name: Option<String>,
+               ttl: Duration) -> DeduplicateDecoratorNode {
+        DeduplicateDecoratorNode {
+            id,
+            child: Box::new(child),
+            value_names: value_names.into_iter().collect(),
+            subject_value_name,
+            ttl
+        }
+    }
+
+    // Values are hashed in the order of their names, missing ones count as well.
+    fn get_fingerprint(&self,
+                       context: &BTNodeExecutionContext) -> Result<u64, TickError> {
+        let mut hasher = DefaultHasher::new();
+
+        for value_name in &self.value_names {
+            value_name.hash(&mut hasher);
+            context.get_value(value_name)
+                .map_err(|err| TickError::BlackboardError(self.id, err))?
+                .hash(&mut hasher);
+        }
+
+        Result::Ok(hasher.finish())
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for DeduplicateDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        header.record_lookups(self.value_names.len() + self.subject_value_name.iter().count());
+
+        let subject = match &self.subject_value_name {
+            None => String::new(),
+            Some(subject_value_name) => match context.get_value(subject_value_name)
+                .map_err(|err| TickError::BlackboardError(self.id, err))?
+                .and_then(|subject| serde_json::to_string(&subject).ok()) {
+                None => return Result::Ok(TickStatus::Failure),
+                Some(subject) => subject
+            }
+        };
+        let key = format!("{}:{}:{}:{:016x}",
+                          header.get_tree_id(),
+                          self.id,
+                          subject,
+                          self.get_fingerprint(context)?);
+        let unseen = context.get_fingerprint_store().insert_if_unseen(&key, &self.ttl);
+
+        header.record_condition(&self.id, unseen);
+
+        if unseen {
+            self.child.tick(header, context).await
+        } else {
+            Result::Ok(TickStatus::Failure)
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        footprint.add_expressions(1, estimate_strings(&self.value_names));
+        self.child.add_footprint(footprint);
+    }
+}
+
+impl From<DeduplicateDecoratorNode> for BTNode {
+    fn from(node: DeduplicateDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::Deduplicate(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buttercup_values::{ValueHolder, ValuesPayload};
+
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_passes_the_same_content_once_per_subject() {
+        let node = DeduplicateDecoratorNode::new(
+            1,
+            PrintLogActionNode::new(2, "Sent.".to_owned()).into(),
+            ["message".to_owned()].iter().cloned().collect(),
+            Option::Some("user".to_owned()),
+            Duration::from_secs(60));
+        let values = |user: &str, message: &str| ValuesPayload::new(
+            vec![("user".to_owned(), ValueHolder::from(user)),
+                 ("message".to_owned(), ValueHolder::from(message))].into_iter().collect());
+
+        let path = {
+            let context = BTNodeExecutionContext::default();
+
+            assert_eq!(Result::Ok(TickStatus::Failure), node.do_tick(&TickHeader::default(), &context).await);
+
+            context.put_values(&values("alice", "hello")).unwrap();
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(TickStatus::Failure), node.do_tick(&TickHeader::default(), &context).await);
+
+            context.put_values(&values("alice", "bye")).unwrap();
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&TickHeader::default(), &context).await);
+
+            context.put_values(&values("bob", "hello")).unwrap();
+            assert_eq!(Result::Ok(TickStatus::Success), node.do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use async_std::task;
+use async_trait::async_trait;
+use futures::future::{self, Either};
+use serde::{Deserialize, Serialize};
+
+use buttercup_variables::VariableSpecification;
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum EscalationPolicy {
+
+    // The child keeps running, its status is the status of the node.
+    Keep,
+
+    // The child is dropped, the status of the escalation is the status of the node.
+    Abort
+
+}
+
+// Ticks the escalation once the child has been running for longer than the sla.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct EscalationDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+    escalation: Box<BTNode>,
+
+    #[derivative(Debug="ignore")]
+    sla: VariableSpecification<Duration>,
+
+    policy: EscalationPolicy
+
+}
+
+impl EscalationDecoratorNode {
+
+    pub fn new(id: i32,
+               child: BTNode,
+               escalation: BTNode,
+               sla: VariableSpecification<Duration>,
+               policy: EscalationPolicy) -> EscalationDecoratorNode {
+        EscalationDecoratorNode {
+            id,
+            child: Box::new(child),
+            escalation: Box::new(escalation),
+            sla,
+            policy
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for EscalationDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let sla = *self.sla.get_value(context)
+            .map_err(|err| TickError::VariableValueAccessError(self.id, err))?;
+        let child = self.child.tick(header, context);
+        let deadline = Box::pin(task::sleep(sla));
+
+        match future::select(child, deadline).await {
+            Either::Left((result, _)) => result,
+            Either::Right((_, child)) => match self.policy {
+                EscalationPolicy::Abort =>
+                    self.escalation.tick(header, context).await,
+                EscalationPolicy::Keep => {
+                    let (escalated, result) = future::join(self.escalation.tick(header, context), child).await;
+                    escalated?;
+                    result
+                }
+            }
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        self.child.add_footprint(footprint);
+        self.escalation.add_footprint(footprint);
+    }
+}
+
+impl From<EscalationDecoratorNode> for BTNode {
+    fn from(node: EscalationDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::Escalation(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use buttercup_values::ValueHolder;
+
+    use crate::context::test_utils;
+    use crate::node::action::values::SetValueActionNode;
+    use crate::node::action::wait::WaitDurationActionNode;
+    use crate::node::decorator::invert::InvertDecoratorNode;
+
+    use super::*;
+
+    fn node(child_millis: u64,
+            policy: EscalationPolicy) -> EscalationDecoratorNode {
+        EscalationDecoratorNode::new(
+            1,
+            InvertDecoratorNode::new(
+                2,
+                Box::new(WaitDurationActionNode::new(
+                    3,
+                    VariableSpecification::Literal(Arc::new(Duration::from_millis(child_millis)))).into()))
+                .into(),
+            SetValueActionNode::new(4, "escalated".to_owned(), ValueHolder::Boolean(true)).into(),
+            VariableSpecification::Literal(Arc::new(Duration::from_millis(20))),
+            policy)
+    }
+
+    #[actix_rt::test]
+    async fn test_escalates_long_running_children() {
+        let escalated = "escalated".to_owned();
+        let path = {
+            let context: BTNodeExecutionContext = Default::default();
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node(1, EscalationPolicy::Keep).do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Option::None, context.get_value(&escalated).unwrap());
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node(60, EscalationPolicy::Keep).do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Option::Some(ValueHolder::Boolean(true)), context.get_value(&escalated).unwrap());
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node(60_000, EscalationPolicy::Abort).do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
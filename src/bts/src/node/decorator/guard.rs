@@ -0,0 +1,190 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use buttercup_endpoints::ArgumentDefinition;
+use buttercup_endpoints::validation::{ArgumentValidator, ArgumentViolation};
+use buttercup_values::ValuesPayload;
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::{estimate_strings, TreeFootprint};
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct GuardReport {
+
+    node_id: i32,
+    tree_id: i32,
+    violations: Vec<ArgumentViolation>
+
+}
+
+impl GuardReport {
+
+    pub fn get_node_id(&self) -> &i32 {
+        &self.node_id
+    }
+
+    pub fn get_violations(&self) -> &Vec<ArgumentViolation> {
+        &self.violations
+    }
+
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct GuardDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+
+    #[derivative(Debug="ignore")]
+    requirements: HashMap<String, ArgumentDefinition>,
+
+    value_names: HashSet<String>
+
+}
+
+impl GuardDecoratorNode {
+
+    pub fn new(id: i32,
+               child: BTNode,
+               requirements: HashMap<String, ArgumentDefinition>) -> GuardDecoratorNode {
+        let value_names = requirements.keys().cloned().collect();
+        GuardDecoratorNode {
+            id,
+            child: Box::new(child),
+            requirements,
+            value_names
+        }
+    }
+
+    pub fn check(&self,
+                 header: &TickHeader,
+                 payload: &ValuesPayload) -> Result<(), GuardReport> {
+        let violations = ArgumentValidator::validate(&self.requirements, payload);
+
+        if violations.is_empty() {
+            return Result::Ok(());
+        }
+
+        Result::Err(
+            GuardReport {
+                node_id: self.id,
+                tree_id: *header.get_tree_id(),
+                violations
+            })
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for GuardDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        header.record_lookups(self.value_names.len());
+
+        let payload = context.get_values(&self.value_names)
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        match self.check(header, &payload) {
+            Ok(()) => self.child.tick(header, context).await,
+            Err(report) => {
+                warn!("{:?}", report);
+
+                if let Some(trace) = header.get_trace() {
+                    trace.record_guard_report(report);
+                }
+
+                Result::Ok(TickStatus::Failure)
+            }
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        footprint.add_expressions(self.requirements.len(), estimate_strings(&self.value_names));
+        self.child.add_footprint(footprint);
+    }
+}
+
+impl From<GuardDecoratorNode> for BTNode {
+    fn from(node: GuardDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::Guard(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buttercup_values::{ValueHolder, ValueType};
+    use buttercup_values::extractors::ValueExtractionPolicy;
+
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+
+    use super::*;
+
+    fn node() -> GuardDecoratorNode {
+        let mut requirements = HashMap::new();
+        requirements.insert(
+            "active".to_owned(),
+            ArgumentDefinition::new(1,
+                                    "active".to_owned(),
+                                    ValueType::Boolean,
+                                    ValueExtractionPolicy::Strict,
+                                    1));
+
+        GuardDecoratorNode::new(
+            1,
+            PrintLogActionNode::new(2, "Guarded.".to_string()).into(),
+            requirements)
+    }
+
+    #[actix_rt::test]
+    async fn test_ticks_child_when_values_are_valid() {
+        let path = {
+            let context: BTNodeExecutionContext = Default::default();
+            context.put_values(
+                &ValuesPayload::singleton("active".to_owned(), ValueHolder::Boolean(true)))
+                .unwrap();
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node().do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_fails_when_values_are_missing() {
+        let path = {
+            let context = Default::default();
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node().do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+
+        assert_eq!(&vec![ArgumentViolation::MissingArgument("active".to_owned())],
+                   node().check(&TickHeader::default(), &ValuesPayload::empty())
+                       .unwrap_err()
+                       .get_violations());
+    }
+
+}
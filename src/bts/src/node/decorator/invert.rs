@@ -3,6 +3,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::decorator::DecoratorBTNode;
 use crate::tick::{TickError, TickStatus, TickHeader};
@@ -48,6 +49,12 @@ impl BehaviorTreeNode for InvertDecoratorNode {
     fn get_id(&self) -> &i32 {
         &self.id
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        self.child.add_footprint(footprint);
+    }
 }
 
 impl From<InvertDecoratorNode> for BTNode {
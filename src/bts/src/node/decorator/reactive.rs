@@ -15,8 +15,10 @@ use buttercup_values::ValuesPayload;
 
 use crate::context::BTNodeExecutionContext;
 use crate::context::reactive::ReactiveContextError;
+use crate::footprint::{estimate_strings, TreeFootprint};
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::decorator::DecoratorBTNode;
+use crate::node::decorator::condition::check_missing_values;
 use crate::tick::{TickError, TickStatus, TickHeader};
 
 #[derive(Debug)]
@@ -65,7 +67,13 @@ impl BehaviorTreeNode for ReactiveConditionDecoratorNode {
     async fn do_tick(&self,
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
-        match self.inner.register_abortable(&self.inner, context)? {
+        header.record_lookups(self.inner.get_value_names().len());
+
+        let abort_registration = self.inner.register_abortable(&self.inner, header, context)?;
+
+        header.record_condition(self.get_id(), abort_registration.is_some());
+
+        match abort_registration {
             None => Result::Ok(TickStatus::Failure),
             Some(abort_registration) =>
                 match Abortable::new(self.child.tick(header, context),
@@ -79,6 +87,13 @@ impl BehaviorTreeNode for ReactiveConditionDecoratorNode {
     fn get_id(&self) -> &i32 {
         self.inner.get_id()
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self) + std::mem::size_of_val(self.inner.as_ref()));
+        footprint.add_expressions(1, estimate_strings(self.inner.get_value_names()));
+        self.child.add_footprint(footprint);
+    }
 }
 
 impl From<ReactiveConditionDecoratorNode> for BTNode {
@@ -148,10 +163,13 @@ impl ReactiveConditionInnerNode {
 
     fn register_abortable(&self,
                           inner: &Arc<ReactiveConditionInnerNode>,
+                          header: &TickHeader,
                           context: &BTNodeExecutionContext)
                           -> Result<Option<AbortRegistration>, TickError> {
         match context.get_values(&self.value_names) {
             Ok(payload) => {
+                check_missing_values(&self.id, &self.value_names, &payload, header)?;
+
                 if self.predicate.deref()(&payload) {
                     let (abort_handle, abort_registration) =
                         AbortHandle::new_pair();
@@ -0,0 +1,125 @@
+use async_std::task;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum RepeatPolicy {
+
+    // Succeeds once the child has succeeded the given number of times in a row, fails as
+    // soon as the child does.
+    Times(u32),
+
+    // Succeeds once the child fails.
+    UntilFailure
+
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct RepeatDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+    policy: RepeatPolicy
+
+}
+
+impl RepeatDecoratorNode {
+
+    pub fn new(id: i32,
+               child: BTNode,
+               policy: RepeatPolicy) -> RepeatDecoratorNode {
+        RepeatDecoratorNode {
+            id,
+            child: Box::new(child),
+            policy
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for RepeatDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let mut successes = 0;
+
+        loop {
+            match (&self.policy, self.child.tick(header, context).await?) {
+                (RepeatPolicy::Times(_), TickStatus::Failure) => return Result::Ok(TickStatus::Failure),
+                (RepeatPolicy::UntilFailure, TickStatus::Failure) => return Result::Ok(TickStatus::Success),
+                (RepeatPolicy::Times(times), TickStatus::Success) => {
+                    successes += 1;
+                    if successes >= *times {
+                        return Result::Ok(TickStatus::Success);
+                    }
+                },
+                (RepeatPolicy::UntilFailure, TickStatus::Success) => {}
+            }
+
+            // Children which do not wait would otherwise keep the executor to themselves.
+            task::yield_now().await;
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        self.child.add_footprint(footprint);
+    }
+}
+
+impl From<RepeatDecoratorNode> for BTNode {
+    fn from(node: RepeatDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::Repeat(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::context::test_utils;
+    use crate::node::action::quota::QuotaActionNode;
+
+    use super::*;
+
+    fn node(counter: &str,
+            policy: RepeatPolicy) -> RepeatDecoratorNode {
+        RepeatDecoratorNode::new(
+            1,
+            QuotaActionNode::new(2, counter.to_owned(), Option::None, 3, Duration::from_secs(60)).into(),
+            policy)
+    }
+
+    #[actix_rt::test]
+    async fn test_repeats_children() {
+        let path = {
+            let context: BTNodeExecutionContext = Default::default();
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node("times", RepeatPolicy::Times(2)).do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node("times", RepeatPolicy::Times(2)).do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node("until", RepeatPolicy::UntilFailure).do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
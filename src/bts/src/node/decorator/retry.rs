@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use async_std::task;
+use async_trait::async_trait;
+
+use buttercup_variables::VariableSpecification;
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+// Ticks the child again while it fails, up to the given number of retries, waiting the
+// delay before the first retry and twice as long before each of the next ones. Errors
+// are not retried.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct RetryDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+    max_retries: u32,
+
+    #[derivative(Debug="ignore")]
+    delay: VariableSpecification<Duration>
+
+}
+
+impl RetryDecoratorNode {
+
+    pub fn new(id: i32,
+               child: BTNode,
+               max_retries: u32,
+               delay: VariableSpecification<Duration>) -> RetryDecoratorNode {
+        RetryDecoratorNode {
+            id,
+            child: Box::new(child),
+            max_retries,
+            delay
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for RetryDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let mut delay = *self.delay.get_value(context)
+            .map_err(|err| TickError::VariableValueAccessError(self.id, err))?;
+
+        for retry in 0..=self.max_retries {
+            if retry > 0 {
+                task::sleep(delay).await;
+                delay *= 2;
+            }
+
+            if self.child.tick(header, context).await? == TickStatus::Success {
+                return Result::Ok(TickStatus::Success);
+            }
+        }
+
+        Result::Ok(TickStatus::Failure)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        self.child.add_footprint(footprint);
+    }
+}
+
+impl From<RetryDecoratorNode> for BTNode {
+    fn from(node: RetryDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::Retry(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::context::test_utils;
+    use crate::node::action::quota::QuotaActionNode;
+    use crate::node::decorator::invert::InvertDecoratorNode;
+
+    use super::*;
+
+    // Fails the first two times it is ticked.
+    fn node(counter: &str,
+            max_retries: u32) -> RetryDecoratorNode {
+        RetryDecoratorNode::new(
+            1,
+            InvertDecoratorNode::new(
+                2,
+                Box::new(QuotaActionNode::new(3, counter.to_owned(), Option::None, 2, Duration::from_secs(60)).into()))
+                .into(),
+            max_retries,
+            VariableSpecification::Literal(Arc::new(Duration::from_millis(1))))
+    }
+
+    #[actix_rt::test]
+    async fn test_retries_failing_children() {
+        let path = {
+            let context: BTNodeExecutionContext = Default::default();
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node("once", 1).do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node("twice", 2).do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
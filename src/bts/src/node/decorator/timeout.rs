@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use async_std::task;
+use async_trait::async_trait;
+use futures::future::{self, Either};
+
+use buttercup_variables::VariableSpecification;
+
+use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+// Fails once the child has been running for longer than the duration, the child is
+// dropped then.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct TimeoutDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+
+    #[derivative(Debug="ignore")]
+    duration: VariableSpecification<Duration>
+
+}
+
+impl TimeoutDecoratorNode {
+
+    pub fn new(id: i32,
+               child: BTNode,
+               duration: VariableSpecification<Duration>) -> TimeoutDecoratorNode {
+        TimeoutDecoratorNode {
+            id,
+            child: Box::new(child),
+            duration
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for TimeoutDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let duration = *self.duration.get_value(context)
+            .map_err(|err| TickError::VariableValueAccessError(self.id, err))?;
+        let child = self.child.tick(header, context);
+        let deadline = Box::pin(task::sleep(duration));
+
+        match future::select(child, deadline).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Result::Ok(TickStatus::Failure)
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        self.child.add_footprint(footprint);
+    }
+}
+
+impl From<TimeoutDecoratorNode> for BTNode {
+    fn from(node: TimeoutDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::Timeout(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::context::test_utils;
+    use crate::node::action::wait::WaitDurationActionNode;
+
+    use super::*;
+
+    fn node(child_millis: u64) -> TimeoutDecoratorNode {
+        TimeoutDecoratorNode::new(
+            1,
+            WaitDurationActionNode::new(
+                2,
+                VariableSpecification::Literal(Arc::new(Duration::from_millis(child_millis)))).into(),
+            VariableSpecification::Literal(Arc::new(Duration::from_millis(20))))
+    }
+
+    #[actix_rt::test]
+    async fn test_fails_children_running_too_long() {
+        let path = {
+            let context: BTNodeExecutionContext = Default::default();
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node(1).do_tick(&TickHeader::default(), &context).await);
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       node(60_000).do_tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::BehaviorTreeNode;
 use crate::node::root::one_off::OneOffRootBTNode;
 use crate::node::root::reactive::ReactiveRootBTNode;
@@ -53,6 +54,17 @@ impl BehaviorTreeNode for RootBTNode {
         }
     }
 
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        match self {
+            RootBTNode::OneOff(node) => node.add_footprint(footprint),
+            RootBTNode::Reactive(node) => node.add_footprint(footprint),
+            RootBTNode::ToFirstError(node) => node.add_footprint(footprint),
+            RootBTNode::ToFirstFailure(node) => node.add_footprint(footprint),
+            RootBTNode::UntilStopped(node) => node.add_footprint(footprint),
+        }
+    }
+
 }
 
 impl RootBTNode {
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use uuid::Uuid;
 
 use crate::context::BTNodeExecutionContext;
+use crate::footprint::TreeFootprint;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::root::RootBTNode;
 use crate::tick::{TickError, TickHeader, TickStatus};
@@ -56,6 +57,12 @@ impl BehaviorTreeNode for ToFirstFailureRootBTNode {
     fn get_id(&self) -> &i32 {
         &self.id
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        self.child.add_footprint(footprint);
+    }
 }
 
 pub struct ToFirstErrorRootBTNode {
@@ -92,5 +99,11 @@ impl BehaviorTreeNode for ToFirstErrorRootBTNode {
     fn get_id(&self) -> &i32 {
         &self.id
     }
+
+    fn add_footprint(&self,
+                     footprint: &mut TreeFootprint) {
+        footprint.add_node(std::mem::size_of_val(self));
+        self.child.add_footprint(footprint);
+    }
 }
 
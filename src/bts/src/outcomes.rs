@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::tick::{TickError, TickStatus};
+
+// Ticks of a tree and how many of them ended with an error, counted from the last take.
+#[derive(Default)]
+pub struct TickOutcomes {
+
+    ticks: AtomicU64,
+    errors: AtomicU64
+
+}
+
+impl TickOutcomes {
+
+    pub fn record(&self,
+                  result: &Result<TickStatus, TickError>) {
+        self.ticks.fetch_add(1, Ordering::Relaxed);
+
+        if result.is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // Returns the ticks and the errors, and starts counting them from zero again.
+    pub fn take(&self) -> (u64, u64) {
+        (self.ticks.swap(0, Ordering::Relaxed), self.errors.swap(0, Ordering::Relaxed))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_counts_ticks_and_errors_until_taken() {
+        let outcomes = TickOutcomes::default();
+
+        outcomes.record(&Result::Ok(TickStatus::Success));
+        outcomes.record(&Result::Ok(TickStatus::Failure));
+        outcomes.record(&Result::Err(TickError::MissingValues(1, Vec::new())));
+
+        assert_eq!((3, 1), outcomes.take());
+        assert_eq!((0, 0), outcomes.take());
+    }
+
+}
@@ -0,0 +1,137 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use chrono::Utc;
+use dashmap::DashMap;
+use log::warn;
+
+use buttercup_blackboards::counters::PersistentCounters;
+
+const MIN_COUNTERS_SWEEP: usize = 1024;
+
+// Counters shared by all the trees, e.g. kept in memory or in an external store so that
+// every server sees the same counts. Windows are fixed, aligned to the unix epoch.
+pub trait CounterStore: Send + Sync {
+
+    // Increments the counter of the key in the current window unless it already reached
+    // the limit, returns whether it was incremented.
+    fn try_increment(&self,
+                     key: &str,
+                     limit: u64,
+                     window: &Duration) -> bool;
+
+}
+
+// Counters are kept with the end of their window, in seconds since the unix epoch.
+pub struct InMemoryCounterStore {
+
+    counters: DashMap<String, (u64, u64)>,
+    next_sweep_len: AtomicUsize
+
+}
+
+impl Default for InMemoryCounterStore {
+    fn default() -> Self {
+        InMemoryCounterStore {
+            counters: DashMap::new(),
+            next_sweep_len: AtomicUsize::new(MIN_COUNTERS_SWEEP)
+        }
+    }
+}
+
+impl InMemoryCounterStore {
+
+    fn try_increment_at(&self,
+                        key: &str,
+                        limit: u64,
+                        window: &Duration,
+                        now_secs: u64) -> bool {
+        let window_secs = window.as_secs().max(1);
+        let window_ends_at = (now_secs / window_secs + 1).saturating_mul(window_secs);
+        let incremented = {
+            let mut counter = self.counters.entry(key.to_owned()).or_insert((window_ends_at, 0));
+            let (counter_window_ends_at, count) = counter.value_mut();
+
+            if *counter_window_ends_at != window_ends_at {
+                *counter_window_ends_at = window_ends_at;
+                *count = 0;
+            }
+
+            if *count >= limit {
+                false
+            } else {
+                *count += 1;
+                true
+            }
+        };
+
+        // Counters of past windows are dropped once the store doubles, as fingerprints are.
+        if self.counters.len() >= self.next_sweep_len.load(Ordering::Relaxed) {
+            self.counters.retain(|_, (counter_window_ends_at, _)| *counter_window_ends_at > now_secs);
+            self.next_sweep_len.store((2 * self.counters.len()).max(MIN_COUNTERS_SWEEP), Ordering::Relaxed);
+        }
+
+        incremented
+    }
+
+}
+
+impl CounterStore for InMemoryCounterStore {
+
+    fn try_increment(&self,
+                     key: &str,
+                     limit: u64,
+                     window: &Duration) -> bool {
+        self.try_increment_at(key, limit, window, Utc::now().timestamp().max(0) as u64)
+    }
+
+}
+
+// Counts which cannot be read or written are taken as over the limit.
+impl CounterStore for PersistentCounters {
+
+    fn try_increment(&self,
+                     key: &str,
+                     limit: u64,
+                     window: &Duration) -> bool {
+        self.try_increment_at(key, limit, window.as_secs(), Utc::now().timestamp().max(0) as u64)
+            .unwrap_or_else(|err| {
+                warn!("Could not increment counter {}: {:?}", key, err);
+                false
+            })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_limits_counts_per_window() {
+        let store = InMemoryCounterStore::default();
+        let day = Duration::from_secs(86_400);
+
+        assert!(store.try_increment_at("user:1", 2, &day, 100));
+        assert!(store.try_increment_at("user:1", 2, &day, 200));
+        assert!(!store.try_increment_at("user:1", 2, &day, 300));
+        assert!(store.try_increment_at("user:2", 2, &day, 300));
+        assert!(store.try_increment_at("user:1", 2, &day, 86_400));
+    }
+
+    #[test]
+    fn test_sweeps_counters_of_past_windows() {
+        let store = InMemoryCounterStore::default();
+        let minute = Duration::from_secs(60);
+
+        for index in 0..MIN_COUNTERS_SWEEP - 1 {
+            store.try_increment_at(&index.to_string(), 1, &minute, 0);
+        }
+
+        assert_eq!(MIN_COUNTERS_SWEEP - 1, store.counters.len());
+        assert!(store.try_increment_at("last", 1, &minute, 60));
+        assert_eq!(1, store.counters.len());
+    }
+
+}
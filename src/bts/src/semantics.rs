@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+// The rules trees are evaluated by. Each definition is pinned to the version it was
+// written for, definitions which do not name one keep the first, so that changing the
+// rules never changes how stored definitions behave. New rules come with a new version
+// and a method below the nodes dispatch on.
+#[derive(Serialize, Deserialize, Default, Eq, Hash, PartialEq, Ord, PartialOrd, Debug, Clone, Copy)]
+pub enum SemanticsVersion {
+
+    #[default]
+    V1,
+    V2
+
+}
+
+impl SemanticsVersion {
+
+    pub fn latest() -> SemanticsVersion {
+        SemanticsVersion::V2
+    }
+
+    // Since V2 conditions with missing values fail with MissingValues, before they were
+    // not met.
+    pub fn reports_missing_values(&self) -> bool {
+        *self >= SemanticsVersion::V2
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_to_the_first_version() {
+        assert_eq!(SemanticsVersion::V1, SemanticsVersion::default());
+        assert!(!SemanticsVersion::default().reports_missing_values());
+        assert!(SemanticsVersion::latest().reports_missing_values());
+    }
+
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures::channel::oneshot;
+
+use buttercup_values::ValuesPayload;
+
+#[derive(Default)]
+struct SignalsState {
+
+    pending: HashMap<String, ValuesPayload>,
+    waiting: HashMap<String, Vec<oneshot::Sender<ValuesPayload>>>
+
+}
+
+// Named signals sent to a single instance from the outside, e.g. by a person approving
+// a step. A signal sent while no node waits for it is kept until one does, a later signal
+// of the same name replaces it.
+#[derive(Default)]
+pub struct Signals {
+
+    state: Mutex<SignalsState>
+
+}
+
+impl Signals {
+
+    // Returns whether any node was waiting for the signal.
+    pub fn send(&self,
+                name: &str,
+                payload: ValuesPayload) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        match state.waiting.remove(name) {
+            Some(senders) if !senders.is_empty() => {
+                for sender in senders {
+                    let _ = sender.send(payload.clone());
+                }
+                true
+            },
+            _ => {
+                state.pending.insert(name.to_owned(), payload);
+                false
+            }
+        }
+    }
+
+    pub fn get_waiting_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.state.lock().unwrap()
+            .waiting
+            .iter()
+            .filter(|(_, senders)| !senders.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub async fn wait(&self,
+                      name: &str) -> ValuesPayload {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+
+            if let Some(payload) = state.pending.remove(name) {
+                return payload;
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            state.waiting.entry(name.to_owned()).or_default().push(sender);
+            receiver
+        };
+
+        receiver.await.unwrap_or_else(|_| ValuesPayload::empty())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use buttercup_values::ValueHolder;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_waits_until_signalled() {
+        let signals = Signals::default();
+        let approval = ValuesPayload::singleton("approved".to_owned(), ValueHolder::Boolean(true));
+
+        assert!(!signals.send("approve", approval.clone()));
+        assert_eq!(approval, signals.wait("approve").await);
+
+        let mut waiting = Box::pin(signals.wait("approve"));
+
+        assert!(futures::poll!(&mut waiting).is_pending());
+        assert_eq!(vec!["approve".to_owned()], signals.get_waiting_names());
+        assert!(signals.send("approve", ValuesPayload::empty()));
+        assert_eq!(ValuesPayload::empty(), waiting.await);
+        assert!(signals.get_waiting_names().is_empty());
+    }
+
+}
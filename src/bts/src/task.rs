@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{NaiveDateTime, Utc};
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use buttercup_endpoints::ArgumentDefinition;
+use buttercup_endpoints::extraction::{ArgumentsExtractionInput, ArgumentValuesExtractionService};
+use buttercup_values::{ValuesPayload, ValueType};
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum HumanTaskError {
+
+    InvalidForm(Uuid, String),
+    TaskOfGivenIdNotFound(Uuid)
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct HumanTaskFormField {
+
+    name: String,
+    field_type: ValueType,
+    symbols: Vec<String>
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct HumanTask {
+
+    id: Uuid,
+    node_id: i32,
+    title: String,
+    assignee: Option<String>,
+    form: Vec<HumanTaskFormField>,
+    created_at_utc: NaiveDateTime
+
+}
+
+impl HumanTask {
+
+    // The form is made of the argument definitions, ordered by name.
+    pub fn new(node_id: i32,
+               title: String,
+               assignee: Option<String>,
+               definitions: &HashMap<String, ArgumentDefinition>) -> HumanTask {
+        let mut form: Vec<HumanTaskFormField> = definitions.iter()
+            .map(|(name, definition)| HumanTaskFormField {
+                name: name.clone(),
+                field_type: definition.get_argument_type().clone(),
+                symbols: definition.get_symbols().clone()
+            })
+            .collect();
+        form.sort_by(|first, second| first.name.cmp(&second.name));
+
+        HumanTask {
+            id: Uuid::new_v4(),
+            node_id,
+            title,
+            assignee,
+            form,
+            created_at_utc: Utc::now().naive_utc()
+        }
+    }
+
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_node_id(&self) -> &i32 {
+        &self.node_id
+    }
+
+    pub fn get_assignee(&self) -> &Option<String> {
+        &self.assignee
+    }
+
+    pub fn get_form(&self) -> &Vec<HumanTaskFormField> {
+        &self.form
+    }
+
+}
+
+struct OpenHumanTask {
+
+    task: HumanTask,
+    definitions: HashMap<String, ArgumentDefinition>,
+    sender: oneshot::Sender<ValuesPayload>
+
+}
+
+// Tasks opened by the nodes of a single instance, each of them pauses its branch until
+// the submitted form values are extracted successfully.
+#[derive(Default)]
+pub struct HumanTasks {
+
+    tasks: Mutex<HashMap<Uuid, OpenHumanTask>>
+
+}
+
+impl HumanTasks {
+
+    pub async fn wait(&self,
+                      task: HumanTask,
+                      definitions: HashMap<String, ArgumentDefinition>) -> ValuesPayload {
+        let (sender, receiver) = oneshot::channel();
+        let task_id = task.id;
+
+        {
+            let mut tasks = self.tasks.lock().unwrap();
+            tasks.retain(|_, open| !open.sender.is_canceled());
+            tasks.insert(task_id, OpenHumanTask { task, definitions, sender });
+        }
+
+        let values = receiver.await;
+        self.tasks.lock().unwrap().remove(&task_id);
+        values.unwrap_or_else(|_| ValuesPayload::empty())
+    }
+
+    pub fn get_tasks(&self) -> Vec<HumanTask> {
+        // Tasks of ticks which were dropped are never completed.
+        let mut tasks: Vec<HumanTask> = self.tasks.lock().unwrap()
+            .values()
+            .filter(|open| !open.sender.is_canceled())
+            .map(|open| open.task.clone())
+            .collect();
+        tasks.sort_by_key(|task| task.created_at_utc);
+        tasks
+    }
+
+    pub fn assign(&self,
+                  task_id: &Uuid,
+                  assignee: Option<String>) -> Result<HumanTask, HumanTaskError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let open = tasks.get_mut(task_id)
+            .ok_or(HumanTaskError::TaskOfGivenIdNotFound(*task_id))?;
+
+        open.task.assignee = assignee;
+
+        Result::Ok(open.task.clone())
+    }
+
+    // An invalid form leaves the task open, so that it can be submitted again.
+    pub fn complete(&self,
+                    task_id: &Uuid,
+                    form_values: &Value) -> Result<ValuesPayload, HumanTaskError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let values = {
+            let open = tasks.get(task_id)
+                .ok_or(HumanTaskError::TaskOfGivenIdNotFound(*task_id))?;
+
+            ArgumentValuesExtractionService::process(ArgumentsExtractionInput::new(&open.definitions, form_values))
+                .map_err(|err| HumanTaskError::InvalidForm(*task_id, format!("{:?}", err)))?
+        };
+
+        if let Some(open) = tasks.remove(task_id) {
+            let _ = open.sender.send(values.clone());
+        }
+
+        Result::Ok(values)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use buttercup_values::extractors::ValueExtractionPolicy;
+    use buttercup_values::ValueHolder;
+    use serde_json::json;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_waits_until_completed_with_valid_form() {
+        let tasks = HumanTasks::default();
+        let definitions: HashMap<String, ArgumentDefinition> = vec![
+            ("approved".to_owned(),
+             ArgumentDefinition::new(1, "approved".to_owned(), ValueType::Boolean, ValueExtractionPolicy::Strict, 1))
+        ].into_iter().collect();
+        let task = HumanTask::new(2, "Approve".to_owned(), Option::Some("alice".to_owned()), &definitions);
+        let task_id = *task.get_id();
+
+        let mut waiting = Box::pin(tasks.wait(task, definitions));
+
+        assert!(futures::poll!(&mut waiting).is_pending());
+        assert_eq!(vec![task_id], tasks.get_tasks().iter().map(|task| *task.get_id()).collect::<Vec<_>>());
+        assert_eq!(Option::Some("bob".to_owned()),
+                   *tasks.assign(&task_id, Option::Some("bob".to_owned())).unwrap().get_assignee());
+        assert!(matches!(tasks.complete(&task_id, &json!({ "approved": "yes" })),
+                         Err(HumanTaskError::InvalidForm(_, _))));
+
+        let values = ValuesPayload::singleton("approved".to_owned(), ValueHolder::Boolean(true));
+
+        assert_eq!(Result::Ok(values.clone()), tasks.complete(&task_id, &json!({ "approved": true })));
+        assert_eq!(values, waiting.await);
+        assert!(tasks.get_tasks().is_empty());
+        assert_eq!(Result::Err(HumanTaskError::TaskOfGivenIdNotFound(task_id)),
+                   tasks.complete(&task_id, &json!({ "approved": true })));
+    }
+
+}
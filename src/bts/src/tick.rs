@@ -10,7 +10,13 @@ use uuid::Uuid;
 use buttercup_blackboards::LocalBlackboardError;
 use buttercup_variables::VariableValueAccessError;
 
+use crate::breakpoint::Breakpoints;
+use crate::budget::{EvaluationMeter, EvaluationResource};
 use crate::context::reactive::ReactiveContextError;
+use crate::debug::{DebugEvent, DebugRecorder};
+use crate::hits::HitCounters;
+use crate::semantics::SemanticsVersion;
+use crate::trace::TickTrace;
 
 #[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
 pub enum TickStatus {
@@ -25,8 +31,13 @@ pub enum TickError {
 
     AbortedExecution(i32),
     BlackboardError(i32, LocalBlackboardError),
+    BudgetExceeded(i32, EvaluationResource),
+    CalendarNotFound(i32, String),
+    CompensationError(i32, Arc<Vec<(i32, Result<TickStatus, TickError>)>>),
     CompositeError(i32, Arc<Vec<(i32, TickError)>>),
+    MissingValues(i32, Vec<String>),
     ReactiveServiceError(i32, ReactiveContextError),
+    TransformationError(i32, String),
     VariableValueAccessError(i32, VariableValueAccessError)
 
 }
@@ -37,8 +48,13 @@ impl TickError {
         match self {
             TickError::AbortedExecution(id) => id,
             TickError::BlackboardError(id, _) => id,
+            TickError::BudgetExceeded(id, _) => id,
+            TickError::CalendarNotFound(id, _) => id,
+            TickError::CompensationError(id, _) => id,
             TickError::CompositeError(id, _) => id,
+            TickError::MissingValues(id, _) => id,
             TickError::ReactiveServiceError(id, _) => id,
+            TickError::TransformationError(id, _) => id,
             TickError::VariableValueAccessError(id, _) => id
         }
     }
@@ -53,7 +69,14 @@ pub struct TickHeader {
     root_tick_id: Uuid,
 
     tree_id: i32,
-    tree_tick_id: Uuid
+    tree_tick_id: Uuid,
+
+    hit_counters: Arc<HitCounters>,
+    meter: Arc<EvaluationMeter>,
+    trace: Option<Arc<TickTrace>>,
+    debug_recorder: Option<Arc<DebugRecorder>>,
+    breakpoints: Option<Arc<Breakpoints>>,
+    semantics_version: SemanticsVersion
 
 }
 
@@ -67,10 +90,52 @@ impl TickHeader {
             correlation_id,
             root_tick_id,
             tree_id,
-            tree_tick_id
+            tree_tick_id,
+            hit_counters: Arc::new(HitCounters::default()),
+            meter: Arc::new(EvaluationMeter::default()),
+            trace: Option::None,
+            debug_recorder: Option::None,
+            breakpoints: Option::None,
+            semantics_version: SemanticsVersion::default()
         }
     }
 
+    pub fn with_hit_counters(mut self,
+                             hit_counters: Arc<HitCounters>) -> TickHeader {
+        self.hit_counters = hit_counters;
+        self
+    }
+
+    pub fn with_meter(mut self,
+                      meter: Arc<EvaluationMeter>) -> TickHeader {
+        self.meter = meter;
+        self
+    }
+
+    pub fn with_trace(mut self,
+                      trace: Option<Arc<TickTrace>>) -> TickHeader {
+        self.trace = trace;
+        self
+    }
+
+    pub fn with_debug_recorder(mut self,
+                               debug_recorder: Option<Arc<DebugRecorder>>) -> TickHeader {
+        self.debug_recorder = debug_recorder;
+        self
+    }
+
+    pub fn with_breakpoints(mut self,
+                            breakpoints: Option<Arc<Breakpoints>>) -> TickHeader {
+        self.breakpoints = breakpoints;
+        self
+    }
+
+    pub fn with_semantics_version(mut self,
+                                  semantics_version: SemanticsVersion) -> TickHeader {
+        self.semantics_version = semantics_version;
+        self
+    }
+
     pub fn get_correlation_id(&self) -> &Uuid {
         &self.correlation_id
     }
@@ -87,9 +152,55 @@ impl TickHeader {
         &self.tree_tick_id
     }
 
+    pub fn get_hit_counters(&self) -> &HitCounters {
+        &self.hit_counters
+    }
+
+    pub fn get_meter(&self) -> &Arc<EvaluationMeter> {
+        &self.meter
+    }
+
+    pub fn get_trace(&self) -> &Option<Arc<TickTrace>> {
+        &self.trace
+    }
+
+    pub fn get_debug_recorder(&self) -> &Option<Arc<DebugRecorder>> {
+        &self.debug_recorder
+    }
+
+    pub fn get_breakpoints(&self) -> &Option<Arc<Breakpoints>> {
+        &self.breakpoints
+    }
+
+    pub fn get_semantics_version(&self) -> &SemanticsVersion {
+        &self.semantics_version
+    }
+
+    pub fn record_condition(&self,
+                            node_id: &i32,
+                            passed: bool) {
+        self.hit_counters.record_condition(node_id, passed);
+        self.meter.record_condition();
+
+        if let Some(debug_recorder) = &self.debug_recorder {
+            debug_recorder.record(DebugEvent::ConditionEvaluated(*node_id, passed));
+        }
+    }
+
+    pub fn record_lookups(&self,
+                          count: usize) {
+        self.meter.record_lookups(count);
+    }
+
     pub fn with_new_root_tick_id(&self,
                                  new_root_tick_id: Uuid) -> TickHeader {
         TickHeader::new(self.correlation_id, new_root_tick_id, self.tree_id, self.tree_tick_id)
+            .with_hit_counters(self.hit_counters.clone())
+            .with_meter(self.meter.clone())
+            .with_trace(self.trace.clone())
+            .with_debug_recorder(self.debug_recorder.clone())
+            .with_breakpoints(self.breakpoints.clone())
+            .with_semantics_version(self.semantics_version)
     }
 
 }
@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::arbitration::ArbitrationDecision;
+use crate::command::CommandSelection;
+use crate::node::decorator::guard::GuardReport;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct NodeTiming {
+
+    tree_id: i32,
+    node_id: i32,
+    started_micros: u64,
+    took_micros: u64
+
+}
+
+impl NodeTiming {
+
+    pub fn new(tree_id: i32,
+               node_id: i32,
+               took: Duration) -> NodeTiming {
+        NodeTiming {
+            tree_id,
+            node_id,
+            started_micros: 0,
+            took_micros: took.as_micros() as u64
+        }
+    }
+
+    pub fn with_started_micros(mut self,
+                               started_micros: u64) -> NodeTiming {
+        self.started_micros = started_micros;
+        self
+    }
+
+    pub fn get_node_id(&self) -> &i32 {
+        &self.node_id
+    }
+
+    pub fn get_started_micros(&self) -> &u64 {
+        &self.started_micros
+    }
+
+    pub fn get_took_micros(&self) -> &u64 {
+        &self.took_micros
+    }
+
+}
+
+// Timings of all the nodes ticked during a single tick of a tree, in the order in which
+// they ended, so children always come before their parents. Commands selected during
+// the tick are kept along with what the arbitration decided about them, and so are the
+// reports of the guards which failed the tick.
+#[derive(Default)]
+pub struct TickTrace {
+
+    timings: Mutex<Vec<NodeTiming>>,
+    command_selections: Mutex<Vec<CommandSelection>>,
+    arbitration_decisions: Mutex<Vec<ArbitrationDecision>>,
+    guard_reports: Mutex<Vec<GuardReport>>
+
+}
+
+impl TickTrace {
+
+    pub fn record(&self,
+                  timing: NodeTiming) {
+        self.timings.lock().unwrap().push(timing);
+    }
+
+    pub fn record_command_selection(&self,
+                                    selection: CommandSelection) {
+        self.command_selections.lock().unwrap().push(selection);
+    }
+
+    pub fn record_arbitration_decision(&self,
+                                       decision: ArbitrationDecision) {
+        self.arbitration_decisions.lock().unwrap().push(decision);
+    }
+
+    pub fn get_timings(&self) -> Vec<NodeTiming> {
+        self.timings.lock().unwrap().clone()
+    }
+
+    pub fn get_command_selections(&self) -> Vec<CommandSelection> {
+        self.command_selections.lock().unwrap().clone()
+    }
+
+    pub fn get_arbitration_decisions(&self) -> Vec<ArbitrationDecision> {
+        self.arbitration_decisions.lock().unwrap().clone()
+    }
+
+    pub fn record_guard_report(&self,
+                               guard_report: GuardReport) {
+        self.guard_reports.lock().unwrap().push(guard_report);
+    }
+
+    pub fn get_guard_reports(&self) -> Vec<GuardReport> {
+        self.guard_reports.lock().unwrap().clone()
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct SlowTickReport {
+
+    tree_id: i32,
+    took_micros: u64,
+    threshold_micros: u64,
+    branch: Vec<NodeTiming>,
+    timings: Vec<NodeTiming>
+
+}
+
+impl SlowTickReport {
+
+    // The offending branch is made of the nodes which took longer than the threshold. As
+    // parents end after their children, the first of them is the deepest one, the branch
+    // is listed from the root down to it.
+    pub fn new(tree_id: i32,
+               took: Duration,
+               threshold: Duration,
+               timings: Vec<NodeTiming>) -> SlowTickReport {
+        let threshold_micros = threshold.as_micros() as u64;
+        let mut branch: Vec<NodeTiming> = timings.iter()
+            .filter(|timing| timing.took_micros >= threshold_micros)
+            .cloned()
+            .collect();
+
+        branch.reverse();
+
+        SlowTickReport {
+            tree_id,
+            took_micros: took.as_micros() as u64,
+            threshold_micros,
+            branch,
+            timings
+        }
+    }
+
+    pub fn get_branch(&self) -> &Vec<NodeTiming> {
+        &self.branch
+    }
+
+    pub fn get_offending_node(&self) -> Option<&NodeTiming> {
+        self.branch.last()
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ChromeTraceEvent {
+
+    name: String,
+    cat: String,
+    ph: String,
+    ts: u64,
+    dur: u64,
+    pid: i32,
+    tid: i32,
+    args: BTreeMap<String, i32>
+
+}
+
+// Node timings in the Chrome trace-event format, which can be opened in Perfetto or
+// chrome://tracing. Every node is a complete event, the spans of children and subtrees
+// nest in the spans of their parents as they start later and end earlier.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChromeTrace {
+
+    trace_events: Vec<ChromeTraceEvent>,
+    display_time_unit: String
+
+}
+
+impl ChromeTrace {
+
+    pub fn new(timings: &[NodeTiming]) -> ChromeTrace {
+        let started_micros = timings.iter()
+            .map(|timing| timing.started_micros)
+            .min()
+            .unwrap_or_default();
+        let mut trace_events: Vec<ChromeTraceEvent> = timings.iter()
+            .map(|timing| ChromeTraceEvent {
+                name: format!("node {}", timing.node_id),
+                cat: "node".to_owned(),
+                ph: "X".to_owned(),
+                ts: timing.started_micros - started_micros,
+                dur: timing.took_micros,
+                pid: timing.tree_id,
+                tid: 1,
+                args: vec![("node_id".to_owned(), timing.node_id)].into_iter().collect()
+            })
+            .collect();
+
+        // Parents first, so that viewers nest events which start at the same time.
+        trace_events.sort_by(|first, second| first.ts.cmp(&second.ts).then_with(|| second.dur.cmp(&first.dur)));
+
+        ChromeTrace {
+            trace_events,
+            display_time_unit: "ms".to_owned()
+        }
+    }
+
+    pub fn get_trace_events(&self) -> &Vec<ChromeTraceEvent> {
+        &self.trace_events
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_finds_offending_branch() {
+        let timing = |node_id, took_millis| NodeTiming::new(1, node_id, Duration::from_millis(took_millis));
+        let report = SlowTickReport::new(1,
+                                         Duration::from_millis(130),
+                                         Duration::from_millis(100),
+                                         vec![timing(4, 2), timing(5, 110), timing(3, 115), timing(2, 120),
+                                              timing(1, 125)]);
+
+        assert_eq!(&vec![timing(1, 125), timing(2, 120), timing(3, 115), timing(5, 110)], report.get_branch());
+        assert_eq!(Option::Some(&timing(5, 110)), report.get_offending_node());
+    }
+
+    #[test]
+    fn test_exports_chrome_trace() {
+        let timing = |node_id, started_micros, took_micros|
+            NodeTiming::new(1, node_id, Duration::from_micros(took_micros)).with_started_micros(started_micros);
+        let trace = ChromeTrace::new(&[timing(3, 1010, 20), timing(2, 1000, 40), timing(1, 1000, 50)]);
+        let json = serde_json::to_value(&trace).unwrap();
+
+        assert_eq!(vec![(1, 0, 50), (2, 0, 40), (3, 10, 20)],
+                   trace.get_trace_events()
+                       .iter()
+                       .map(|event| (event.args["node_id"], event.ts, event.dur))
+                       .collect::<Vec<_>>());
+        assert_eq!("X", json["traceEvents"][0]["ph"]);
+        assert_eq!("ms", json["displayTimeUnit"]);
+    }
+
+}
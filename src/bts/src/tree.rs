@@ -1,18 +1,38 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
+use log::warn;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use buttercup_blackboards::LocalBlackboard;
+use buttercup_values::ValuesPayload;
+
+use crate::breakpoint::Breakpoints;
+use crate::budget::{EvaluationBudget, EvaluationMeter, EvaluationUsage};
 use crate::context::BTNodeExecutionContext;
+use crate::debug::{DebugEvent, DebugRecorder};
+use crate::footprint::TreeFootprint;
+use crate::hits::HitCounters;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::root::RootBTNode;
+use crate::outcomes::TickOutcomes;
+use crate::semantics::SemanticsVersion;
 use crate::tick::{TickError, TickHeader, TickStatus};
+use crate::trace::{NodeTiming, SlowTickReport, TickTrace};
 
 pub struct BehaviorTree {
 
     id: i32,
-    root: RootBTNode
+    root: RootBTNode,
+    hit_counters: Arc<HitCounters>,
+    tick_outcomes: TickOutcomes,
+    slow_tick_threshold: Option<Duration>,
+    evaluation_budget: EvaluationBudget,
+    static_values: Option<Arc<ValuesPayload>>,
+    fallback_tree_id: Option<i32>,
+    semantics_version: SemanticsVersion
 
 }
 
@@ -22,35 +42,261 @@ impl BehaviorTree {
                root: RootBTNode) -> BehaviorTree {
         BehaviorTree {
             id,
-            root
+            root,
+            hit_counters: Arc::new(HitCounters::default()),
+            tick_outcomes: TickOutcomes::default(),
+            slow_tick_threshold: Option::None,
+            evaluation_budget: EvaluationBudget::default(),
+            static_values: Option::None,
+            fallback_tree_id: Option::None,
+            semantics_version: SemanticsVersion::default()
         }
     }
 
+    // Ticks which take longer are logged with the timings of all their nodes.
+    pub fn with_slow_tick_threshold(mut self,
+                                    slow_tick_threshold: Duration) -> BehaviorTree {
+        self.slow_tick_threshold = Option::Some(slow_tick_threshold);
+        self
+    }
+
+    // Ticks and evaluations which go over the budget are aborted with BudgetExceeded.
+    pub fn with_evaluation_budget(mut self,
+                                  evaluation_budget: EvaluationBudget) -> BehaviorTree {
+        self.evaluation_budget = evaluation_budget;
+        self
+    }
+
+    // Put into the throwaway contexts of evaluations before the payload, which can
+    // override them.
+    pub fn with_static_values(mut self,
+                              static_values: Arc<ValuesPayload>) -> BehaviorTree {
+        self.static_values = Option::Some(static_values);
+        self
+    }
+
+    // The tree evaluated in place of this one when its evaluation fails.
+    pub fn with_fallback_tree_id(mut self,
+                                 fallback_tree_id: Option<i32>) -> BehaviorTree {
+        self.fallback_tree_id = fallback_tree_id;
+        self
+    }
+
+    pub fn with_semantics_version(mut self,
+                                  semantics_version: SemanticsVersion) -> BehaviorTree {
+        self.semantics_version = semantics_version;
+        self
+    }
+
     pub async fn tick(&self,
                       correlation_id: Uuid,
                       context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
-        self.root.tick(
-            &TickHeader::new(
-                correlation_id, Uuid::new_v4(),self.id, Uuid::new_v4()),
-            context).await
+        self.tick_with_header(self.new_header(correlation_id), context).await
+    }
+
+    // Same as tick, but pauses before ticking any of the nodes with a breakpoint.
+    pub async fn tick_with_breakpoints(&self,
+                                       correlation_id: Uuid,
+                                       context: &BTNodeExecutionContext,
+                                       breakpoints: Arc<Breakpoints>) -> Result<TickStatus, TickError> {
+        self.tick_with_header(self.new_header(correlation_id).with_breakpoints(Option::Some(breakpoints)),
+                              context).await
+    }
+
+    fn new_header(&self,
+                  correlation_id: Uuid) -> TickHeader {
+        TickHeader::new(correlation_id, Uuid::new_v4(), self.id, Uuid::new_v4())
+            .with_hit_counters(self.hit_counters.clone())
+            .with_meter(Arc::new(EvaluationMeter::new(self.evaluation_budget.clone())))
+            .with_trace(self.slow_tick_threshold.map(|_| Arc::new(TickTrace::default())))
+            .with_semantics_version(self.semantics_version)
+    }
+
+    async fn tick_with_header(&self,
+                              header: TickHeader,
+                              context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let started_at = Instant::now();
+
+        let result = self.root.tick(&header, context).await;
+
+        self.tick_outcomes.record(&result);
+
+        if let (Some(threshold), Some(trace)) = (self.slow_tick_threshold, header.get_trace()) {
+            let took = started_at.elapsed();
+
+            if took >= threshold {
+                warn!("{:?}", SlowTickReport::new(self.id, took, threshold, trace.get_timings()));
+            }
+        }
+
+        result
+    }
+
+    pub async fn evaluate_fixture(&self,
+                                  fixture: &BehaviorTreeFixture) -> Result<TickStatus, TickError> {
+        self.evaluate(fixture.get_payload()).await
+    }
+
+    // Ticks the tree once in a throwaway context which starts with the given payload.
+    pub async fn evaluate(&self,
+                          payload: &ValuesPayload) -> Result<TickStatus, TickError> {
+        self.evaluate_with_header(payload, self.new_header(Uuid::new_v4())).await
+    }
+
+    // Same as evaluate, but returns the trace of the tick and the work the evaluation did as
+    // well. Everything which happens is recorded by the debug recorder, if there is one.
+    pub async fn evaluate_traced(&self,
+                                 payload: &ValuesPayload,
+                                 debug_recorder: Option<Arc<DebugRecorder>>) -> (Result<TickStatus, TickError>, Arc<TickTrace>, EvaluationUsage) {
+        let trace = Arc::new(TickTrace::default());
+        let meter = Arc::new(EvaluationMeter::new(self.evaluation_budget.clone()));
+        let header = self.new_header(Uuid::new_v4())
+            .with_trace(Option::Some(trace.clone()))
+            .with_meter(meter.clone())
+            .with_debug_recorder(debug_recorder);
+        let result = self.evaluate_with_header(payload, header).await;
+
+        (result, trace, meter.get_usage())
+    }
+
+    // Same as evaluate, but records the timings and the selected commands into the given
+    // trace, so that the decisions made about the commands can be recorded next to them.
+    pub async fn evaluate_into_trace(&self,
+                                     payload: &ValuesPayload,
+                                     trace: Arc<TickTrace>) -> Result<TickStatus, TickError> {
+        self.evaluate_with_header(payload, self.new_header(Uuid::new_v4()).with_trace(Option::Some(trace))).await
+    }
+
+    // Same as evaluate, but records every node entered and left and every condition
+    // checked, so that the evaluation can be stepped through.
+    pub async fn evaluate_debugged(&self,
+                                   payload: &ValuesPayload) -> (Result<TickStatus, TickError>, Vec<DebugEvent>) {
+        let debug_recorder = Arc::new(DebugRecorder::default());
+        let header = self.new_header(Uuid::new_v4()).with_debug_recorder(Option::Some(debug_recorder.clone()));
+        let result = self.evaluate_with_header(payload, header).await;
+
+        (result, debug_recorder.get_events())
+    }
+
+    // Same as evaluate, but returns the work the evaluation did as well.
+    pub async fn evaluate_metered(&self,
+                                  payload: &ValuesPayload) -> (Result<TickStatus, TickError>, EvaluationUsage) {
+        let meter = Arc::new(EvaluationMeter::new(self.evaluation_budget.clone()));
+        let header = self.new_header(Uuid::new_v4()).with_meter(meter.clone());
+        let result = self.evaluate_with_header(payload, header).await;
+
+        (result, meter.get_usage())
+    }
+
+    async fn evaluate_with_header(&self,
+                                  payload: &ValuesPayload,
+                                  header: TickHeader) -> Result<TickStatus, TickError> {
+        let path = LocalBlackboard::get_temporary_path(&Uuid::new_v4());
+
+        let result = {
+            let local_blackboard = LocalBlackboard::new(path.clone())
+                .map_err(|err| TickError::BlackboardError(self.id, err))?;
+            let context = BTNodeExecutionContext::new(
+                Arc::new(local_blackboard),
+                Arc::new(Default::default()));
+
+            let put_values = match &self.static_values {
+                None => context.put_values(payload),
+                Some(static_values) => context.put_values(static_values)
+                    .and_then(|_| context.put_values(payload))
+            };
+
+            match put_values {
+                Ok(_) => self.tick_with_header(header, &context).await,
+                Err(err) => Result::Err(TickError::BlackboardError(self.id, err))
+            }
+        };
+
+        LocalBlackboard::destroy(path)
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        result
     }
 
+    // Subtrees are evaluated by the rules of their own definitions.
     pub async fn subtree_tick(&self,
                               header: &TickHeader,
                               context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
-        self.root.tick(header, context).await
+        let header = TickHeader::new(*header.get_correlation_id(),
+                                     *header.get_root_tick_id(),
+                                     *header.get_tree_id(),
+                                     *header.get_tree_tick_id())
+            .with_hit_counters(self.hit_counters.clone())
+            .with_meter(header.get_meter().clone())
+            .with_trace(header.get_trace().clone())
+            .with_debug_recorder(header.get_debug_recorder().clone())
+            .with_breakpoints(header.get_breakpoints().clone())
+            .with_semantics_version(self.semantics_version);
+
+        self.root.tick(&header, context).await
     }
 
     pub fn get_id(&self) -> &i32 {
         &self.id
     }
 
+    pub fn get_fallback_tree_id(&self) -> &Option<i32> {
+        &self.fallback_tree_id
+    }
+
+    pub fn get_semantics_version(&self) -> &SemanticsVersion {
+        &self.semantics_version
+    }
+
+    pub fn get_hit_counters(&self) -> &HitCounters {
+        &self.hit_counters
+    }
+
+    pub fn get_tick_outcomes(&self) -> &TickOutcomes {
+        &self.tick_outcomes
+    }
+
+    // Subtrees are shared between trees, so they are not included.
+    pub fn get_footprint(&self) -> TreeFootprint {
+        let mut footprint = TreeFootprint::new(self.id);
+        self.root.add_footprint(&mut footprint);
+        footprint
+    }
+
     pub fn can_be_subtree(&self) -> bool {
         self.root.can_be_subtree_root()
     }
 
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct BehaviorTreeFixture {
+
+    payload: ValuesPayload,
+    expected_status: TickStatus
+
+}
+
+impl BehaviorTreeFixture {
+
+    pub fn new(payload: ValuesPayload,
+               expected_status: TickStatus) -> BehaviorTreeFixture {
+        BehaviorTreeFixture {
+            payload,
+            expected_status
+        }
+    }
+
+    pub fn get_payload(&self) -> &ValuesPayload {
+        &self.payload
+    }
+
+    pub fn get_expected_status(&self) -> &TickStatus {
+        &self.expected_status
+    }
+
+}
+
 pub struct BehaviorTreeDefinition {
 
     id: i32,
@@ -81,6 +327,27 @@ impl BehaviorTreeService {
         self.trees.get(id).map(|tree_arc| tree_arc.clone())
     }
 
+    pub fn remove(&self,
+                  id: &i32) -> Option<Arc<BehaviorTree>> {
+        self.trees.remove(id).map(|(_, tree)| tree)
+    }
+
+    pub fn get_all(&self) -> Vec<Arc<BehaviorTree>> {
+        self.trees.iter().map(|tree| tree.clone()).collect()
+    }
+
+    // The largest trees come first.
+    pub fn get_footprints(&self) -> Vec<TreeFootprint> {
+        let mut footprints: Vec<TreeFootprint> = self.trees
+            .iter()
+            .map(|tree| tree.get_footprint())
+            .collect();
+
+        footprints.sort_by(|first, second| second.get_estimated_bytes().cmp(first.get_estimated_bytes())
+            .then_with(|| first.get_tree_id().cmp(second.get_tree_id())));
+        footprints
+    }
+
 }
 
 
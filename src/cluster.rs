@@ -0,0 +1,721 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use futures::lock::Mutex as AsyncMutex;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use buttercup_api::bts::{BehaviorTreeBuildingError, BehaviorTreeBuildingService};
+use buttercup_api::document::{DefinitionDocumentService, DefinitionDocumentServiceError, DefinitionStatus};
+use buttercup_bts::tree::BehaviorTreeService;
+use buttercup_values::ValuesPayload;
+
+const CHANGES_KEY: &str = "buttercup:changes";
+const LEADER_KEY: &str = "buttercup:leader";
+const INSTANCE_KEY_PREFIX: &str = "buttercup:instances:";
+const FIRED_KEY_PREFIX: &str = "buttercup:fired:";
+
+// Renews the lock when it is already held by the owner, so that the leader keeps its
+// lease, otherwise takes it only when nobody holds it.
+const TRY_LOCK_SCRIPT: &str = r"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'PX', ARGV[2]) then
+    return 1
+end
+return 0
+";
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum ClusterError {
+
+    ChangeOfGivenIndexNotFound(u64),
+    InvalidChange(String),
+    InvalidSchedule(String),
+    RedisError(String)
+
+}
+
+impl From<redis::RedisError> for ClusterError {
+    fn from(err: redis::RedisError) -> Self {
+        ClusterError::RedisError(err.to_string())
+    }
+}
+
+// The state shared by all the servers of a cluster. Changes are appended to a list which
+// every server replays in the same order, locks expire unless renewed by their owner.
+pub trait SharedStore: Send + Sync {
+
+    fn append(&self,
+              key: &str,
+              value: &str) -> Result<u64, ClusterError>;
+
+    fn get_range(&self,
+                 key: &str,
+                 start: u64) -> Result<Vec<String>, ClusterError>;
+
+    fn get(&self,
+           key: &str) -> Result<Option<String>, ClusterError>;
+
+    fn put(&self,
+           key: &str,
+           value: &str,
+           ttl: &Duration) -> Result<(), ClusterError>;
+
+    fn try_lock(&self,
+                key: &str,
+                owner: &str,
+                ttl: &Duration) -> Result<bool, ClusterError>;
+
+}
+
+pub struct RedisSharedStore {
+
+    client: redis::Client,
+    connection: Mutex<Option<redis::Connection>>
+
+}
+
+impl RedisSharedStore {
+
+    pub fn open(url: &str) -> Result<RedisSharedStore, ClusterError> {
+        Result::Ok(
+            RedisSharedStore {
+                client: redis::Client::open(url)?,
+                connection: Mutex::new(Option::None)
+            })
+    }
+
+    // The connection is opened again on the next call once a command failed, as the
+    // server may have gone away in the meantime.
+    fn with_connection<T>(&self,
+                          command: impl FnOnce(&mut redis::Connection) -> redis::RedisResult<T>)
+        -> Result<T, ClusterError> {
+        let mut connection = self.connection.lock().unwrap();
+
+        if connection.is_none() {
+            *connection = Option::Some(self.client.get_connection()?);
+        }
+
+        let result = command(connection.as_mut().unwrap());
+        if result.is_err() {
+            *connection = Option::None;
+        }
+
+        result.map_err(ClusterError::from)
+    }
+
+}
+
+impl SharedStore for RedisSharedStore {
+
+    fn append(&self,
+              key: &str,
+              value: &str) -> Result<u64, ClusterError> {
+        self.with_connection(|connection| redis::cmd("RPUSH").arg(key).arg(value).query(connection))
+    }
+
+    fn get_range(&self,
+                 key: &str,
+                 start: u64) -> Result<Vec<String>, ClusterError> {
+        self.with_connection(|connection| redis::cmd("LRANGE").arg(key).arg(start).arg(-1).query(connection))
+    }
+
+    fn get(&self,
+           key: &str) -> Result<Option<String>, ClusterError> {
+        self.with_connection(|connection| redis::cmd("GET").arg(key).query(connection))
+    }
+
+    fn put(&self,
+           key: &str,
+           value: &str,
+           ttl: &Duration) -> Result<(), ClusterError> {
+        self.with_connection(|connection| redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query(connection))
+    }
+
+    fn try_lock(&self,
+                key: &str,
+                owner: &str,
+                ttl: &Duration) -> Result<bool, ClusterError> {
+        self.with_connection(|connection| redis::Script::new(TRY_LOCK_SCRIPT)
+            .key(key)
+            .arg(owner)
+            .arg(ttl.as_millis() as u64)
+            .invoke::<i64>(connection)
+            .map(|locked| locked == 1))
+    }
+
+}
+
+// Used when the server runs on its own, it is then the only member of its cluster.
+#[derive(Default)]
+pub struct InMemorySharedStore {
+
+    lists: Mutex<HashMap<String, Vec<String>>>,
+    values: Mutex<HashMap<String, (String, Instant)>>
+
+}
+
+impl InMemorySharedStore {
+
+    fn get_unexpired(values: &HashMap<String, (String, Instant)>,
+                     key: &str) -> Option<String> {
+        values.get(key)
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(value, _)| value.clone())
+    }
+
+}
+
+impl SharedStore for InMemorySharedStore {
+
+    fn append(&self,
+              key: &str,
+              value: &str) -> Result<u64, ClusterError> {
+        let mut lists = self.lists.lock().unwrap();
+        let list = lists.entry(key.to_owned()).or_default();
+
+        list.push(value.to_owned());
+        Result::Ok(list.len() as u64)
+    }
+
+    fn get_range(&self,
+                 key: &str,
+                 start: u64) -> Result<Vec<String>, ClusterError> {
+        Result::Ok(self.lists.lock().unwrap()
+            .get(key)
+            .map(|list| list.iter().skip(start as usize).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn get(&self,
+           key: &str) -> Result<Option<String>, ClusterError> {
+        Result::Ok(InMemorySharedStore::get_unexpired(&self.values.lock().unwrap(), key))
+    }
+
+    fn put(&self,
+           key: &str,
+           value: &str,
+           ttl: &Duration) -> Result<(), ClusterError> {
+        self.values.lock().unwrap().insert(key.to_owned(), (value.to_owned(), Instant::now() + *ttl));
+        Result::Ok(())
+    }
+
+    fn try_lock(&self,
+                key: &str,
+                owner: &str,
+                ttl: &Duration) -> Result<bool, ClusterError> {
+        let mut values = self.values.lock().unwrap();
+
+        match InMemorySharedStore::get_unexpired(&values, key) {
+            Some(current_owner) if current_owner != owner => Result::Ok(false),
+            _ => {
+                values.insert(key.to_owned(), (owner.to_owned(), Instant::now() + *ttl));
+                Result::Ok(true)
+            }
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ClusterConfig {
+
+    redis_url: String,
+    node_id: String,
+    advertised_url: Option<String>,
+    lease_secs: u64,
+    sync_millis: u64
+
+}
+
+impl ClusterConfig {
+
+    pub fn new(redis_url: String) -> ClusterConfig {
+        ClusterConfig {
+            redis_url,
+            node_id: Uuid::new_v4().to_string(),
+            advertised_url: Option::None,
+            lease_secs: 10,
+            sync_millis: 1000
+        }
+    }
+
+    pub fn with_node_id(mut self,
+                        node_id: String) -> ClusterConfig {
+        self.node_id = node_id;
+        self
+    }
+
+    pub fn with_advertised_url(mut self,
+                               advertised_url: String) -> ClusterConfig {
+        self.advertised_url = Option::Some(advertised_url);
+        self
+    }
+
+    pub fn with_lease_secs(mut self,
+                           lease_secs: u64) -> ClusterConfig {
+        self.lease_secs = lease_secs;
+        self
+    }
+
+    pub fn with_sync_millis(mut self,
+                            sync_millis: u64) -> ClusterConfig {
+        self.sync_millis = sync_millis;
+        self
+    }
+
+    pub fn get_redis_url(&self) -> &String {
+        &self.redis_url
+    }
+
+    pub fn get_node_id(&self) -> &String {
+        &self.node_id
+    }
+
+    pub fn get_advertised_url(&self) -> &Option<String> {
+        &self.advertised_url
+    }
+
+    pub fn get_lease(&self) -> Duration {
+        Duration::from_secs(self.lease_secs)
+    }
+
+    pub fn get_sync_interval(&self) -> Duration {
+        Duration::from_millis(self.sync_millis)
+    }
+
+}
+
+// A tree evaluated with an empty payload every time the cron expression, with seconds,
+// fires, e.g. 0 */5 * * * * for every five minutes.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ScheduledTree {
+
+    tree_id: i32,
+    expression: String
+
+}
+
+impl ScheduledTree {
+
+    pub fn new(tree_id: i32,
+               expression: String) -> Result<ScheduledTree, ClusterError> {
+        Schedule::from_str(&expression)
+            .map_err(|_| ClusterError::InvalidSchedule(expression.clone()))?;
+
+        Result::Ok(
+            ScheduledTree {
+                tree_id,
+                expression
+            })
+    }
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_expression(&self) -> &String {
+        &self.expression
+    }
+
+    // The times the schedule fires after since, up to and including until.
+    pub fn get_fire_times(&self,
+                          since: &DateTime<Utc>,
+                          until: &DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        match Schedule::from_str(&self.expression) {
+            Err(_) => Vec::new(),
+            Ok(schedule) => schedule
+                .after(since)
+                .take_while(|fire_time| fire_time <= until)
+                .collect()
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum ClusterChange {
+
+    DefinitionPut { tree_id: i32, json: String, expected_versions: Option<Vec<u32>> },
+    Activated { tree_id: i32, version: u32 },
+    StatusChanged { tree_id: i32, status: DefinitionStatus }
+
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum AppliedChange {
+
+    DefinitionPut(Result<u32, DefinitionDocumentServiceError>),
+    Activated(Result<(), BehaviorTreeBuildingError>),
+    StatusChanged(Result<DefinitionStatus, DefinitionDocumentServiceError>)
+
+}
+
+// Keeps the definitions of every server of the cluster the same. A change is appended
+// to the shared list first and applied only when it is replayed, so that all servers
+// apply the changes in the same order and end up with the same versions. Instances
+// stay on the server which created them, the others only know where to find them.
+pub struct ClusterService {
+
+    node_id: String,
+    advertised_url: Option<String>,
+    lease: Duration,
+    store: Arc<dyn SharedStore>,
+    document_service: Arc<DefinitionDocumentService>,
+    building_service: Arc<BehaviorTreeBuildingService>,
+    applied_changes: AsyncMutex<u64>,
+    leader: AtomicBool
+
+}
+
+impl ClusterService {
+
+    pub fn new(node_id: String,
+               store: Arc<dyn SharedStore>,
+               document_service: Arc<DefinitionDocumentService>,
+               building_service: Arc<BehaviorTreeBuildingService>) -> ClusterService {
+        ClusterService {
+            node_id,
+            advertised_url: Option::None,
+            lease: Duration::from_secs(10),
+            store,
+            document_service,
+            building_service,
+            applied_changes: AsyncMutex::new(0),
+            leader: AtomicBool::new(false)
+        }
+    }
+
+    pub fn with_advertised_url(mut self,
+                               advertised_url: Option<String>) -> ClusterService {
+        self.advertised_url = advertised_url;
+        self
+    }
+
+    pub fn with_lease(mut self,
+                      lease: Duration) -> ClusterService {
+        self.lease = lease;
+        self
+    }
+
+    pub fn get_node_id(&self) -> &String {
+        &self.node_id
+    }
+
+    pub fn get_lease(&self) -> &Duration {
+        &self.lease
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::SeqCst)
+    }
+
+    // Called every third of the lease, the leader renews its lease and the others take
+    // over once it has expired. A server which cannot reach the store steps down.
+    pub fn renew_leadership(&self) -> bool {
+        let leader = match self.store.try_lock(LEADER_KEY, &self.node_id, &self.lease) {
+            Ok(leader) => leader,
+            Err(err) => {
+                warn!("Could not renew the leadership of {}: {:?}", self.node_id, err);
+                false
+            }
+        };
+
+        if leader != self.leader.swap(leader, Ordering::SeqCst) {
+            info!("Node {} is {} the leader", self.node_id, if leader { "now" } else { "no longer" });
+        }
+
+        leader
+    }
+
+    pub async fn put_definition(&self,
+                                tree_id: &i32,
+                                json: &str,
+                                expected_versions: Option<&[u32]>)
+        -> Result<Result<u32, DefinitionDocumentServiceError>, ClusterError> {
+        let change = ClusterChange::DefinitionPut {
+            tree_id: *tree_id,
+            json: json.to_owned(),
+            expected_versions: expected_versions.map(|versions| versions.to_vec())
+        };
+
+        match self.publish(&change).await? {
+            AppliedChange::DefinitionPut(result) => Result::Ok(result),
+            applied => Result::Err(ClusterError::InvalidChange(format!("{:?}", applied)))
+        }
+    }
+
+    pub async fn activate(&self,
+                          tree_id: &i32,
+                          version: &u32) -> Result<Result<(), BehaviorTreeBuildingError>, ClusterError> {
+        match self.publish(&ClusterChange::Activated { tree_id: *tree_id, version: *version }).await? {
+            AppliedChange::Activated(result) => Result::Ok(result),
+            applied => Result::Err(ClusterError::InvalidChange(format!("{:?}", applied)))
+        }
+    }
+
+    pub async fn transition(&self,
+                            tree_id: &i32,
+                            status: DefinitionStatus)
+        -> Result<Result<DefinitionStatus, DefinitionDocumentServiceError>, ClusterError> {
+        match self.publish(&ClusterChange::StatusChanged { tree_id: *tree_id, status }).await? {
+            AppliedChange::StatusChanged(result) => Result::Ok(result),
+            applied => Result::Err(ClusterError::InvalidChange(format!("{:?}", applied)))
+        }
+    }
+
+    async fn publish(&self,
+                     change: &ClusterChange) -> Result<AppliedChange, ClusterError> {
+        let json = serde_json::to_string(change)
+            .map_err(|err| ClusterError::InvalidChange(err.to_string()))?;
+        let index = self.store.append(CHANGES_KEY, &json)? - 1;
+
+        self.sync_until(Option::Some(index))
+            .await?
+            .ok_or(ClusterError::ChangeOfGivenIndexNotFound(index))
+    }
+
+    // Replays the changes appended by every server since the last sync.
+    pub async fn sync(&self) -> Result<(), ClusterError> {
+        self.sync_until(Option::None).await.map(|_| ())
+    }
+
+    async fn sync_until(&self,
+                        index: Option<u64>) -> Result<Option<AppliedChange>, ClusterError> {
+        let mut applied_changes = self.applied_changes.lock().await;
+        let mut result = Option::None;
+
+        for json in self.store.get_range(CHANGES_KEY, *applied_changes)? {
+            let change: ClusterChange = serde_json::from_str(&json)
+                .map_err(|err| ClusterError::InvalidChange(err.to_string()))?;
+            let applied = self.apply(change).await;
+
+            if index == Option::Some(*applied_changes) {
+                result = Option::Some(applied);
+            }
+
+            *applied_changes += 1;
+        }
+
+        Result::Ok(result)
+    }
+
+    async fn apply(&self,
+                   change: ClusterChange) -> AppliedChange {
+        match change {
+            ClusterChange::DefinitionPut { tree_id, json, expected_versions } =>
+                AppliedChange::DefinitionPut(
+                    self.document_service.put(&tree_id, &json, expected_versions.as_deref())),
+            ClusterChange::Activated { tree_id, version } =>
+                AppliedChange::Activated(
+                    self.building_service.activate(&tree_id, &version).await.map(|_| ())),
+            ClusterChange::StatusChanged { tree_id, status } =>
+                AppliedChange::StatusChanged(self.document_service.transition(&tree_id, status))
+        }
+    }
+
+    // Instances are registered only when the server has an url the others can reach it
+    // at, for as long as the instance lives without being ticked.
+    pub fn register_instance(&self,
+                             instance_id: &Uuid,
+                             ttl: &Duration) -> Result<(), ClusterError> {
+        match &self.advertised_url {
+            None => Result::Ok(()),
+            Some(url) => self.store.put(&format!("{}{}", INSTANCE_KEY_PREFIX, instance_id), url, ttl)
+        }
+    }
+
+    // The url of the server owning the instance, unless it is this one.
+    pub fn get_instance_owner(&self,
+                              instance_id: &Uuid) -> Result<Option<String>, ClusterError> {
+        let owner = self.store.get(&format!("{}{}", INSTANCE_KEY_PREFIX, instance_id))?;
+
+        Result::Ok(owner.filter(|url| Option::Some(url) != self.advertised_url.as_ref()))
+    }
+
+    // Evaluates the trees whose schedules fired after since, up to and including until.
+    // Only the leader fires the schedules, and every fire time is locked as well, so
+    // that a leader which has just lost its lease cannot fire it a second time.
+    pub async fn fire_schedules(&self,
+                                schedules: &[ScheduledTree],
+                                tree_service: &BehaviorTreeService,
+                                since: &DateTime<Utc>,
+                                until: &DateTime<Utc>) -> Vec<(i32, DateTime<Utc>)> {
+        let mut fired = Vec::new();
+
+        if !self.is_leader() {
+            return fired;
+        }
+
+        for schedule in schedules {
+            for fire_time in schedule.get_fire_times(since, until) {
+                let key = format!("{}{}:{}", FIRED_KEY_PREFIX, schedule.get_tree_id(), fire_time.timestamp());
+
+                // Locked with a new owner every time, which the lock cannot be renewed for.
+                match self.store.try_lock(&key, &Uuid::new_v4().to_string(), &Duration::from_secs(24 * 60 * 60)) {
+                    Ok(true) => {},
+                    Ok(false) => continue,
+                    Err(err) => {
+                        warn!("Could not lock schedule of tree {}: {:?}", schedule.get_tree_id(), err);
+                        continue;
+                    }
+                }
+
+                match tree_service.get_by_id(schedule.get_tree_id()) {
+                    None => warn!("Scheduled tree {} is not served", schedule.get_tree_id()),
+                    Some(tree) => {
+                        if let Err(err) = tree.evaluate(&ValuesPayload::empty()).await {
+                            warn!("Scheduled evaluation of tree {} failed: {:?}", schedule.get_tree_id(), err);
+                        }
+                    }
+                }
+
+                fired.push((*schedule.get_tree_id(), fire_time));
+            }
+        }
+
+        fired
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use chrono::{TimeZone, Utc};
+
+    use buttercup_api::bts::{BehaviorTreeBuildingService, BehaviorTreeDefinitionService};
+    use buttercup_api::document::{DefinitionDocumentService, DefinitionStatus};
+    use buttercup_bts::tree::BehaviorTreeService;
+
+    use crate::cluster::{ClusterService, InMemorySharedStore, ScheduledTree, SharedStore};
+
+    const DEFINITION: &str = r#"{
+        "id": 1,
+        "root": {"type": "OneOff", "id": 2, "child_id": 1},
+        "nodes": [{"type": "PrintLog", "id": 1, "message": "Hello!"}]
+    }"#;
+
+    struct Node {
+
+        cluster: ClusterService,
+        document_service: Arc<DefinitionDocumentService>,
+        tree_service: Arc<BehaviorTreeService>
+
+    }
+
+    fn node(node_id: &str,
+            store: Arc<dyn SharedStore>) -> Node {
+        let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        let document_service = Arc::new(DefinitionDocumentService::new(definition_service.clone()));
+        let building_service = Arc::new(
+            BehaviorTreeBuildingService::new(tree_service.clone(), definition_service));
+
+        Node {
+            cluster: ClusterService::new(node_id.to_owned(), store, document_service.clone(), building_service)
+                .with_advertised_url(Option::Some(format!("http://{}", node_id)))
+                .with_lease(Duration::from_millis(50)),
+            document_service,
+            tree_service
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_replays_definition_changes_on_every_node() {
+        let store: Arc<dyn SharedStore> = Arc::new(InMemorySharedStore::default());
+        let first = node("first", store.clone());
+        let second = node("second", store);
+
+        assert_eq!(Ok(Ok(1)), first.cluster.put_definition(&1, DEFINITION, Option::None).await);
+        assert_eq!(Ok(Ok(())), first.cluster.activate(&1, &1).await);
+        assert!(second.document_service.get(&1).is_none());
+
+        second.cluster.sync().await.unwrap();
+
+        assert_eq!(&1, second.document_service.get(&1).unwrap().get_version());
+        assert!(second.tree_service.get_by_id(&1).is_some());
+        assert_eq!(Ok(Ok(2)), second.cluster.put_definition(&1, DEFINITION, Option::Some(&[1])).await);
+        assert_eq!(Ok(Ok(DefinitionStatus::Deprecated)),
+                   second.cluster.transition(&1, DefinitionStatus::Deprecated).await);
+
+        first.cluster.sync().await.unwrap();
+
+        assert_eq!(&2, first.document_service.get(&1).unwrap().get_version());
+        assert_eq!(Option::Some(DefinitionStatus::Deprecated), first.document_service.get_status(&1));
+    }
+
+    #[actix_rt::test]
+    async fn test_elects_a_single_leader_until_its_lease_expires() {
+        let store: Arc<dyn SharedStore> = Arc::new(InMemorySharedStore::default());
+        let first = node("first", store.clone());
+        let second = node("second", store);
+
+        assert!(first.cluster.renew_leadership());
+        assert!(!second.cluster.renew_leadership());
+        assert!(first.cluster.renew_leadership());
+
+        actix_rt::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(second.cluster.renew_leadership());
+        assert!(!first.cluster.renew_leadership());
+        assert!(!first.cluster.is_leader());
+    }
+
+    #[actix_rt::test]
+    async fn test_fires_every_schedule_once() {
+        let store: Arc<dyn SharedStore> = Arc::new(InMemorySharedStore::default());
+        let first = node("first", store.clone());
+        let second = node("second", store);
+        let schedules = vec![ScheduledTree::new(1, "0 */5 * * * *".to_owned()).unwrap()];
+        let since = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let until = Utc.ymd(2021, 1, 1).and_hms(12, 10, 0);
+
+        first.cluster.put_definition(&1, DEFINITION, Option::None).await.unwrap().unwrap();
+        first.cluster.activate(&1, &1).await.unwrap().unwrap();
+        second.cluster.sync().await.unwrap();
+
+        assert!(second.cluster.fire_schedules(&schedules, &second.tree_service, &since, &until).await.is_empty());
+
+        first.cluster.renew_leadership();
+
+        assert_eq!(vec![(1, Utc.ymd(2021, 1, 1).and_hms(12, 5, 0)),
+                        (1, Utc.ymd(2021, 1, 1).and_hms(12, 10, 0))],
+                   first.cluster.fire_schedules(&schedules, &first.tree_service, &since, &until).await);
+        assert!(first.cluster.fire_schedules(&schedules, &first.tree_service, &since, &until).await.is_empty());
+    }
+
+    #[test]
+    fn test_finds_the_owners_of_instances_on_other_nodes() {
+        let store: Arc<dyn SharedStore> = Arc::new(InMemorySharedStore::default());
+        let first = node("first", store.clone());
+        let second = node("second", store);
+        let instance_id = uuid::Uuid::new_v4();
+
+        first.cluster.register_instance(&instance_id, &Duration::from_secs(60)).unwrap();
+
+        assert_eq!(Ok(Option::None), first.cluster.get_instance_owner(&instance_id));
+        assert_eq!(Ok(Option::Some("http://first".to_owned())), second.cluster.get_instance_owner(&instance_id));
+    }
+
+    #[test]
+    fn test_rejects_invalid_schedules() {
+        assert!(ScheduledTree::new(1, "every minute".to_owned()).is_err());
+    }
+
+}
@@ -1,13 +1,19 @@
 #[macro_use]
 extern crate lazy_static;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
 use buttercup_values::{ValueHolder, ValuesPayload, ValueType};
+use buttercup_values::quantity::{QuantityValueError, Unit};
+use num::rational::BigRational;
+use num::Signed;
 
-use crate::relational::{ContainsRelationalExpression, EndsWithRelationalExpression, EqualsRelationalExpression, GreaterThanOrEqualsRelationalExpression, GreaterThanRelationalExpression, IsInRelationalExpression, LessThanOrEqualsRelationalExpression, LessThanRelationalExpression, NotEqualsRelationalExpression, StartsWithRelationalExpression};
+use crate::pattern::{MatchesRelationalExpression, NotMatchesRelationalExpression};
+use crate::relational::{ContainsRelationalExpression, EndsWithRelationalExpression, EqualsRelationalExpression, EqualsWithToleranceRelationalExpression, GreaterThanOrEqualsRelationalExpression, GreaterThanRelationalExpression, HasAllFlagsRelationalExpression, HasAnyFlagRelationalExpression, HasFlagRelationalExpression, IsInRelationalExpression, LengthEqualsRelationalExpression, LengthGreaterThanRelationalExpression, LengthLessThanRelationalExpression, LessThanOrEqualsRelationalExpression, LessThanRelationalExpression, NormalizedContainsRelationalExpression, NormalizedEndsWithRelationalExpression, NormalizedEqualsRelationalExpression, NormalizedStartsWithRelationalExpression, NotEqualsRelationalExpression, NotEqualsWithToleranceRelationalExpression, NotInRelationalExpression, StartsWithRelationalExpression, WithinRadiusRelationalExpression};
 
+pub mod mutation;
+pub mod pattern;
 pub mod relational;
 
 use serde::{Deserialize, Serialize};
@@ -21,6 +27,19 @@ pub enum ConditionExpression {
 
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+pub enum ConditionExpressionError {
+
+    InvalidPattern(String, String),
+    NegativeTolerance(BigRational),
+    PatternLiteralExpected,
+    PatternTooLong(usize, usize),
+    SymbolExpected(String),
+    UnitMismatch(Unit, Unit),
+    UnknownSymbol(String, String)
+
+}
+
 pub struct ConditionExpressionWrapper {
 
     predicate: Box<dyn Fn(&ValuesPayload) -> bool + Send + Sync>,
@@ -37,6 +56,14 @@ impl ConditionExpressionWrapper {
         }
     }
 
+    // Compiles the patterns of the condition first, so that the ones which do not fit
+    // the budget are rejected when the tree is built, together with negative tolerances.
+    pub fn try_new(condition: ConditionExpression) -> Result<ConditionExpressionWrapper, ConditionExpressionError> {
+        condition.verify_patterns()?;
+        condition.verify_tolerances()?;
+        Result::Ok(ConditionExpressionWrapper::new(condition))
+    }
+
     pub fn new(condition: ConditionExpression) -> ConditionExpressionWrapper {
         let value_names = HashSet::from_iter(condition.get_value_names());
         ConditionExpressionWrapper {
@@ -70,29 +97,184 @@ pub enum RelationalExpression {
     Contains(ContainsRelationalExpression),
     EndsWith(EndsWithRelationalExpression),
     Equals(EqualsRelationalExpression),
+    EqualsWithTolerance(EqualsWithToleranceRelationalExpression),
     GreaterThan(GreaterThanRelationalExpression),
     GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression),
+    HasAllFlags(HasAllFlagsRelationalExpression),
+    HasAnyFlag(HasAnyFlagRelationalExpression),
+    HasFlag(HasFlagRelationalExpression),
     IsIn(IsInRelationalExpression),
+    LengthEquals(LengthEqualsRelationalExpression),
+    LengthGreaterThan(LengthGreaterThanRelationalExpression),
+    LengthLessThan(LengthLessThanRelationalExpression),
     LessThan(LessThanRelationalExpression),
     LessThanOrEquals(LessThanOrEqualsRelationalExpression),
+    Matches(MatchesRelationalExpression),
+    NormalizedContains(NormalizedContainsRelationalExpression),
+    NormalizedEndsWith(NormalizedEndsWithRelationalExpression),
+    NormalizedEquals(NormalizedEqualsRelationalExpression),
+    NormalizedStartsWith(NormalizedStartsWithRelationalExpression),
     NotEquals(NotEqualsRelationalExpression),
-    StartsWith(StartsWithRelationalExpression)
+    NotEqualsWithTolerance(NotEqualsWithToleranceRelationalExpression),
+    NotIn(NotInRelationalExpression),
+    NotMatches(NotMatchesRelationalExpression),
+    StartsWith(StartsWithRelationalExpression),
+    WithinRadius(WithinRadiusRelationalExpression)
 
 }
 
 lazy_static! {
 
     static ref LISTS_AND_STRINGS: Vec<ValueType> = vec![ValueType::String, ValueType::List];
+    static ref FLAGS_ONLY: Vec<ValueType> = vec![ValueType::Flags];
+    static ref GEO_COORDINATES_ONLY: Vec<ValueType> = vec![ValueType::GeoCoordinates];
+    static ref LENGTH_MEASURABLE: Vec<ValueType> =
+        vec![ValueType::Bytes, ValueType::List, ValueType::String];
     static ref STRING_ONLY: Vec<ValueType> = vec![ValueType::String];
+    static ref STRINGS_AND_UUIDS: Vec<ValueType> = vec![ValueType::String, ValueType::Uuid];
+
+}
+
+impl ConditionExpression {
+
+    pub fn verify_symbols(&self,
+                          symbols: &HashMap<String, Vec<String>>) -> Result<(), ConditionExpressionError> {
+        match self {
+            ConditionExpression::ConstantExpression(_) => Result::Ok(()),
+            ConditionExpression::RelationExpression(expr) =>
+                expr.get_specification().verify_symbols(symbols),
+            ConditionExpression::LogicalExpression(expr) => match expr.as_ref() {
+                LogicalExpression::And(expressions) | LogicalExpression::Or(expressions) =>
+                    expressions.iter()
+                        .try_for_each(|expr| expr.verify_symbols(symbols)),
+                LogicalExpression::Not(expr) => expr.verify_symbols(symbols)
+            }
+        }
+    }
+
+    // Levels of nesting, a single comparison or constant has the depth of one.
+    pub fn get_depth(&self) -> usize {
+        match self {
+            ConditionExpression::ConstantExpression(_)
+            | ConditionExpression::RelationExpression(_) => 1,
+            ConditionExpression::LogicalExpression(expr) => 1 + match expr.as_ref() {
+                LogicalExpression::And(expressions) | LogicalExpression::Or(expressions) =>
+                    expressions.iter()
+                        .map(ConditionExpression::get_depth)
+                        .max()
+                        .unwrap_or(0),
+                LogicalExpression::Not(expr) => expr.get_depth()
+            }
+        }
+    }
+
+    pub fn get_relations_count(&self) -> usize {
+        match self {
+            ConditionExpression::ConstantExpression(_) => 0,
+            ConditionExpression::RelationExpression(_) => 1,
+            ConditionExpression::LogicalExpression(expr) => match expr.as_ref() {
+                LogicalExpression::And(expressions) | LogicalExpression::Or(expressions) =>
+                    expressions.iter()
+                        .map(ConditionExpression::get_relations_count)
+                        .sum(),
+                LogicalExpression::Not(expr) => expr.get_relations_count()
+            }
+        }
+    }
+
+    pub fn verify_patterns(&self) -> Result<(), ConditionExpressionError> {
+        match self {
+            ConditionExpression::ConstantExpression(_) => Result::Ok(()),
+            ConditionExpression::RelationExpression(RelationalExpression::Matches(expr)) =>
+                expr.compile().map(|_| ()),
+            ConditionExpression::RelationExpression(RelationalExpression::NotMatches(expr)) =>
+                expr.compile().map(|_| ()),
+            ConditionExpression::RelationExpression(_) => Result::Ok(()),
+            ConditionExpression::LogicalExpression(expr) => match expr.as_ref() {
+                LogicalExpression::And(expressions) | LogicalExpression::Or(expressions) =>
+                    expressions.iter()
+                        .try_for_each(|expr| expr.verify_patterns()),
+                LogicalExpression::Not(expr) => expr.verify_patterns()
+            }
+        }
+    }
+
+    // A negative tolerance would make equals never hold and not equals always hold.
+    pub fn verify_tolerances(&self) -> Result<(), ConditionExpressionError> {
+        let tolerance = match self {
+            ConditionExpression::RelationExpression(RelationalExpression::EqualsWithTolerance(expr)) =>
+                expr.get_tolerance(),
+            ConditionExpression::RelationExpression(RelationalExpression::NotEqualsWithTolerance(expr)) =>
+                expr.get_tolerance(),
+            ConditionExpression::LogicalExpression(expr) => return match expr.as_ref() {
+                LogicalExpression::And(expressions) | LogicalExpression::Or(expressions) =>
+                    expressions.iter()
+                        .try_for_each(|expr| expr.verify_tolerances()),
+                LogicalExpression::Not(expr) => expr.verify_tolerances()
+            },
+            _ => return Result::Ok(())
+        };
+
+        match tolerance.is_negative() {
+            true => Result::Err(ConditionExpressionError::NegativeTolerance(tolerance.clone())),
+            false => Result::Ok(())
+        }
+    }
 
 }
 
 impl RelationalExpression {
 
+    pub fn get_specification(&self) -> &RelationalExpressionSpecification {
+        match self {
+            RelationalExpression::Contains(expr) => expr.get_specification(),
+            RelationalExpression::EndsWith(expr) => expr.get_specification(),
+            RelationalExpression::Equals(expr) => expr.get_specification(),
+            RelationalExpression::EqualsWithTolerance(expr) => expr.get_specification(),
+            RelationalExpression::GreaterThan(expr) => expr.get_specification(),
+            RelationalExpression::GreaterThanOrEquals(expr) => expr.get_specification(),
+            RelationalExpression::HasAllFlags(expr) => expr.get_specification(),
+            RelationalExpression::HasAnyFlag(expr) => expr.get_specification(),
+            RelationalExpression::HasFlag(expr) => expr.get_specification(),
+            RelationalExpression::IsIn(expr) => expr.get_specification(),
+            RelationalExpression::LengthEquals(expr) => expr.get_specification(),
+            RelationalExpression::LengthGreaterThan(expr) => expr.get_specification(),
+            RelationalExpression::LengthLessThan(expr) => expr.get_specification(),
+            RelationalExpression::LessThan(expr) => expr.get_specification(),
+            RelationalExpression::LessThanOrEquals(expr) => expr.get_specification(),
+            RelationalExpression::Matches(expr) => expr.get_specification(),
+            RelationalExpression::NormalizedContains(expr) => expr.get_specification(),
+            RelationalExpression::NormalizedEndsWith(expr) => expr.get_specification(),
+            RelationalExpression::NormalizedEquals(expr) => expr.get_specification(),
+            RelationalExpression::NormalizedStartsWith(expr) => expr.get_specification(),
+            RelationalExpression::NotEquals(expr) => expr.get_specification(),
+            RelationalExpression::NotEqualsWithTolerance(expr) => expr.get_specification(),
+            RelationalExpression::NotIn(expr) => expr.get_specification(),
+            RelationalExpression::NotMatches(expr) => expr.get_specification(),
+            RelationalExpression::StartsWith(expr) => expr.get_specification(),
+            RelationalExpression::WithinRadius(expr) => expr.get_specification()
+        }
+    }
+
     pub fn get_allowed_value_types(&self) -> &Vec<ValueType> {
         match self {
-            RelationalExpression::Contains(_) | RelationalExpression::IsIn(_) => &LISTS_AND_STRINGS,
-            RelationalExpression::StartsWith(_) | RelationalExpression::EndsWith(_) => &STRING_ONLY,
+            RelationalExpression::Contains(_)
+            | RelationalExpression::IsIn(_)
+            | RelationalExpression::NotIn(_)
+            | RelationalExpression::NormalizedContains(_) => &LISTS_AND_STRINGS,
+            RelationalExpression::StartsWith(_) => &STRINGS_AND_UUIDS,
+            RelationalExpression::EndsWith(_)
+            | RelationalExpression::Matches(_)
+            | RelationalExpression::NormalizedEndsWith(_)
+            | RelationalExpression::NormalizedStartsWith(_)
+            | RelationalExpression::NotMatches(_) => &STRING_ONLY,
+            RelationalExpression::HasAllFlags(_)
+            | RelationalExpression::HasAnyFlag(_)
+            | RelationalExpression::HasFlag(_) => &FLAGS_ONLY,
+            RelationalExpression::LengthEquals(_)
+            | RelationalExpression::LengthGreaterThan(_)
+            | RelationalExpression::LengthLessThan(_) => &LENGTH_MEASURABLE,
+            RelationalExpression::WithinRadius(_) => &GEO_COORDINATES_ONLY,
             _ => ValueType::all_value_types()
         }
     }
@@ -121,6 +303,54 @@ impl RelationalExpressionSpecification {
         }
     }
 
+    pub fn verify_symbols(&self,
+                          symbols: &HashMap<String, Vec<String>>) -> Result<(), ConditionExpressionError> {
+        match self {
+            RelationalExpressionSpecification::NameAndName(_, _) => Result::Ok(()),
+            RelationalExpressionSpecification::NameAndLiteral(name, literal)
+            | RelationalExpressionSpecification::LiteralAndName(literal, name) =>
+                match symbols.get(name) {
+                    Option::Some(allowed) =>
+                        RelationalExpressionSpecification::verify_symbol(name, literal, allowed),
+                    Option::None => Result::Ok(())
+                }
+        }
+    }
+
+    pub fn verify_units(&self,
+                        payload: &ValuesPayload) -> Result<(), ConditionExpressionError> {
+        let values = match self {
+            RelationalExpressionSpecification::NameAndName(first, second) =>
+                payload.get(first).zip(payload.get(second)),
+            RelationalExpressionSpecification::NameAndLiteral(name, literal) =>
+                payload.get(name).map(|value| (value, literal)),
+            RelationalExpressionSpecification::LiteralAndName(literal, name) =>
+                payload.get(name).map(|value| (literal, value))
+        };
+        match values {
+            Option::Some((left, right)) => match left.check_units(right) {
+                Result::Err(QuantityValueError::UnitMismatch(left_unit, right_unit)) =>
+                    Result::Err(ConditionExpressionError::UnitMismatch(left_unit, right_unit)),
+                _ => Result::Ok(())
+            },
+            Option::None => Result::Ok(())
+        }
+    }
+
+    fn verify_symbol(name: &String,
+                     literal: &ValueHolder,
+                     allowed: &[String]) -> Result<(), ConditionExpressionError> {
+        match literal {
+            ValueHolder::Symbol(symbol) if allowed.contains(symbol.get_name()) => Result::Ok(()),
+            ValueHolder::Symbol(symbol) => Result::Err(
+                ConditionExpressionError::UnknownSymbol(name.clone(), symbol.get_name().clone())),
+            ValueHolder::List(list) => list.get_elements().iter()
+                .try_for_each(|element|
+                    RelationalExpressionSpecification::verify_symbol(name, element, allowed)),
+            _ => Result::Err(ConditionExpressionError::SymbolExpected(name.clone()))
+        }
+    }
+
 }
 
 pub trait ValuesPayloadPredicateSupplier {
@@ -211,19 +441,51 @@ impl ValuesPayloadPredicateSupplier for RelationalExpression {
                 expr.get_predicate(),
             RelationalExpression::Equals(expr) =>
                 expr.get_predicate(),
+            RelationalExpression::EqualsWithTolerance(expr) =>
+                expr.get_predicate(),
             RelationalExpression::GreaterThan(expr) =>
                 expr.get_predicate(),
             RelationalExpression::GreaterThanOrEquals(expr) =>
                 expr.get_predicate(),
+            RelationalExpression::HasAllFlags(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::HasAnyFlag(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::HasFlag(expr) =>
+                expr.get_predicate(),
             RelationalExpression::IsIn(expr) =>
                 expr.get_predicate(),
+            RelationalExpression::LengthEquals(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::LengthGreaterThan(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::LengthLessThan(expr) =>
+                expr.get_predicate(),
             RelationalExpression::LessThan(expr) =>
                 expr.get_predicate(),
             RelationalExpression::LessThanOrEquals(expr) =>
                 expr.get_predicate(),
+            RelationalExpression::Matches(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::NormalizedContains(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::NormalizedEndsWith(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::NormalizedEquals(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::NormalizedStartsWith(expr) =>
+                expr.get_predicate(),
             RelationalExpression::NotEquals(expr) =>
                 expr.get_predicate(),
+            RelationalExpression::NotEqualsWithTolerance(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::NotIn(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::NotMatches(expr) =>
+                expr.get_predicate(),
             RelationalExpression::StartsWith(expr) =>
+                expr.get_predicate(),
+            RelationalExpression::WithinRadius(expr) =>
                 expr.get_predicate()
         }
     }
@@ -236,24 +498,152 @@ impl ValuesPayloadPredicateSupplier for RelationalExpression {
                 expr.get_value_names(),
             RelationalExpression::Equals(expr) =>
                 expr.get_value_names(),
+            RelationalExpression::EqualsWithTolerance(expr) =>
+                expr.get_value_names(),
             RelationalExpression::GreaterThan(expr) =>
                 expr.get_value_names(),
             RelationalExpression::GreaterThanOrEquals(expr) =>
                 expr.get_value_names(),
+            RelationalExpression::HasAllFlags(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::HasAnyFlag(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::HasFlag(expr) =>
+                expr.get_value_names(),
             RelationalExpression::IsIn(expr) =>
                 expr.get_value_names(),
+            RelationalExpression::LengthEquals(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::LengthGreaterThan(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::LengthLessThan(expr) =>
+                expr.get_value_names(),
             RelationalExpression::LessThan(expr) =>
                 expr.get_value_names(),
             RelationalExpression::LessThanOrEquals(expr) =>
                 expr.get_value_names(),
+            RelationalExpression::Matches(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::NormalizedContains(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::NormalizedEndsWith(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::NormalizedEquals(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::NormalizedStartsWith(expr) =>
+                expr.get_value_names(),
             RelationalExpression::NotEquals(expr) =>
                 expr.get_value_names(),
+            RelationalExpression::NotEqualsWithTolerance(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::NotIn(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::NotMatches(expr) =>
+                expr.get_value_names(),
             RelationalExpression::StartsWith(expr) =>
+                expr.get_value_names(),
+            RelationalExpression::WithinRadius(expr) =>
                 expr.get_value_names()
         }
     }
 }
 
+pub trait UnitsVerifier {
+
+    fn verify_units(&self,
+                    payload: &ValuesPayload) -> Result<(), ConditionExpressionError>;
+
+}
+
+impl UnitsVerifier for ConditionExpression {
+
+    fn verify_units(&self,
+                    payload: &ValuesPayload) -> Result<(), ConditionExpressionError> {
+        match self {
+            ConditionExpression::ConstantExpression(_) => Result::Ok(()),
+            ConditionExpression::RelationExpression(expr) => expr.verify_units(payload),
+            ConditionExpression::LogicalExpression(expr) => expr.verify_units(payload)
+        }
+    }
+
+}
+
+impl UnitsVerifier for LogicalExpression {
+
+    fn verify_units(&self,
+                    payload: &ValuesPayload) -> Result<(), ConditionExpressionError> {
+        match self {
+            LogicalExpression::And(expressions) | LogicalExpression::Or(expressions) =>
+                expressions.iter()
+                    .try_for_each(|expr| expr.verify_units(payload)),
+            LogicalExpression::Not(expr) => expr.verify_units(payload)
+        }
+    }
+
+}
+
+impl UnitsVerifier for RelationalExpression {
+
+    fn verify_units(&self,
+                    payload: &ValuesPayload) -> Result<(), ConditionExpressionError> {
+        match self {
+            RelationalExpression::Contains(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::EndsWith(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::Equals(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::EqualsWithTolerance(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::GreaterThan(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::GreaterThanOrEquals(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::HasAllFlags(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::HasAnyFlag(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::HasFlag(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::IsIn(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::LengthEquals(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::LengthGreaterThan(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::LengthLessThan(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::LessThan(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::LessThanOrEquals(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::Matches(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::NormalizedContains(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::NormalizedEndsWith(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::NormalizedEquals(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::NormalizedStartsWith(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::NotEquals(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::NotEqualsWithTolerance(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::NotIn(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::NotMatches(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::StartsWith(expr) =>
+                expr.verify_units(payload),
+            RelationalExpression::WithinRadius(expr) =>
+                expr.verify_units(payload)
+        }
+    }
+
+}
+
 fn to_predicates(expressions: Vec<ConditionExpression>)
                  -> Vec<Box<dyn Fn(&ValuesPayload) -> bool + Send + Sync>> {
     let mut ret = Vec::new();
@@ -275,8 +665,16 @@ fn to_value_names(expressions: &Vec<ConditionExpression>) -> Vec<String> {
 mod tests {
 
     use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::str::FromStr;
+    use std::time::Duration;
 
     use buttercup_values::ValueHolder;
+    use buttercup_values::flags::Flags;
+    use buttercup_values::geolocation::GeoCoordinates;
+    use buttercup_values::lists::ValueHoldersList;
+    use buttercup_values::quantity::Quantity;
+    use buttercup_values::symbol::Symbol;
     use num::bigint::BigInt;
     use num::FromPrimitive;
 
@@ -373,6 +771,300 @@ mod tests {
         assert_eq!(predicate(&second_values_payload()), false);
     }
 
+    #[test]
+    fn test_evaluates_correctly_for_normalized_expressions() {
+        let equals = ConditionExpressionWrapper::new(
+            ConditionExpression::RelationExpression(
+                RelationalExpression::NormalizedEquals(
+                    NormalizedEqualsRelationalExpression::new(
+                        RelationalExpressionSpecification::NameAndLiteral(
+                            FIRST_VALUE_NAME.to_owned(), "cafe\u{301}".into()
+                        )
+                    )
+                )
+            )
+        ).unpack();
+        let contains = ConditionExpressionWrapper::new(
+            ConditionExpression::RelationExpression(
+                RelationalExpression::NormalizedContains(
+                    NormalizedContainsRelationalExpression::new(
+                        RelationalExpressionSpecification::NameAndLiteral(
+                            FIRST_VALUE_NAME.to_owned(), "cafe".into()
+                        )
+                    )
+                )
+            )
+        ).unpack();
+
+        let composed = ValuesPayload::singleton(FIRST_VALUE_NAME.to_owned(), "caf\u{e9}".into());
+        let decomposed = ValuesPayload::singleton(FIRST_VALUE_NAME.to_owned(), "cafe\u{301}s".into());
+
+        assert!(equals(&composed));
+        assert!(!contains(&decomposed));
+        assert!(contains(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(), "cafeteria".into())));
+    }
+
+    #[test]
+    fn test_evaluates_correctly_for_expressions_with_tolerance() {
+        let tolerance = BigRational::new(BigInt::from(1), BigInt::from(1000));
+        let equals = ConditionExpressionWrapper::new(
+            ConditionExpression::RelationExpression(
+                RelationalExpression::EqualsWithTolerance(
+                    EqualsWithToleranceRelationalExpression::new(
+                        RelationalExpressionSpecification::NameAndLiteral(
+                            FIRST_VALUE_NAME.to_owned(),
+                            ValueHolder::Decimal(BigRational::from_f64(0.3).unwrap())
+                        ),
+                        tolerance.clone()
+                    )
+                )
+            )
+        ).unpack();
+        let not_equals = ConditionExpressionWrapper::new(
+            ConditionExpression::RelationExpression(
+                RelationalExpression::NotEqualsWithTolerance(
+                    NotEqualsWithToleranceRelationalExpression::new(
+                        RelationalExpressionSpecification::NameAndLiteral(
+                            FIRST_VALUE_NAME.to_owned(),
+                            ValueHolder::Integer(BigInt::from(1))
+                        ),
+                        tolerance
+                    )
+                )
+            )
+        ).unpack();
+
+        let close = ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(),
+            ValueHolder::Decimal(BigRational::from_f64(0.1 + 0.2).unwrap()));
+        let far = ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(),
+            ValueHolder::Decimal(BigRational::from_f64(1.01).unwrap()));
+
+        assert!(equals(&close));
+        assert!(!equals(&far));
+        assert!(not_equals(&far));
+        assert!(!not_equals(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(),
+            ValueHolder::Decimal(BigRational::from_f64(1.0005).unwrap()))));
+    }
+
+    #[test]
+    fn test_rejects_negative_tolerance() {
+        let tolerance = BigRational::new(BigInt::from(-1), BigInt::from(1000));
+        let condition = ConditionExpression::LogicalExpression(
+            Box::new(
+                LogicalExpression::Not(
+                    ConditionExpression::RelationExpression(
+                        RelationalExpression::NotEqualsWithTolerance(
+                            NotEqualsWithToleranceRelationalExpression::new(
+                                RelationalExpressionSpecification::NameAndLiteral(
+                                    FIRST_VALUE_NAME.to_owned(),
+                                    ValueHolder::Integer(BigInt::from(1))
+                                ),
+                                tolerance.clone()
+                            )
+                        )
+                    )
+                )
+            )
+        );
+
+        assert_eq!(Some(ConditionExpressionError::NegativeTolerance(tolerance)),
+                   ConditionExpressionWrapper::try_new(condition).err());
+        assert!(ConditionExpressionWrapper::try_new(
+            ConditionExpression::RelationExpression(
+                RelationalExpression::EqualsWithTolerance(
+                    EqualsWithToleranceRelationalExpression::new(
+                        RelationalExpressionSpecification::NameAndLiteral(
+                            FIRST_VALUE_NAME.to_owned(),
+                            ValueHolder::Integer(BigInt::from(1))
+                        ),
+                        BigRational::from_integer(BigInt::from(0))
+                    )
+                )
+            )
+        ).is_ok());
+    }
+
+    #[test]
+    fn test_reports_unit_mismatch() {
+        let condition = ConditionExpression::LogicalExpression(
+            Box::new(
+                LogicalExpression::Not(
+                    ConditionExpression::RelationExpression(
+                        RelationalExpression::LessThan(
+                            LessThanRelationalExpression::new(
+                                RelationalExpressionSpecification::NameAndLiteral(
+                                    FIRST_VALUE_NAME.to_owned(),
+                                    ValueHolder::Quantity(Quantity::new(
+                                        BigRational::from_integer(BigInt::from(100)),
+                                        Unit::Currency("USD".to_owned())))
+                                )
+                            )
+                        )
+                    )
+                )
+            )
+        );
+        let euros = ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(),
+            ValueHolder::Quantity(Quantity::new(
+                BigRational::from_integer(BigInt::from(150)),
+                Unit::Currency("EUR".to_owned()))));
+        let dollars = ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(),
+            ValueHolder::Quantity(Quantity::new(
+                BigRational::from_integer(BigInt::from(150)),
+                Unit::Currency("USD".to_owned()))));
+
+        assert_eq!(Result::Err(ConditionExpressionError::UnitMismatch(
+            Unit::Currency("EUR".to_owned()), Unit::Currency("USD".to_owned()))),
+                   condition.verify_units(&euros));
+        assert_eq!(Result::Ok(()), condition.verify_units(&dollars));
+        assert!(ConditionExpressionWrapper::new(condition).unpack()(&dollars));
+    }
+
+    #[test]
+    fn test_verifies_symbol_literals() {
+        let status = |literal: ValueHolder| ConditionExpression::RelationExpression(
+            RelationalExpression::Equals(
+                EqualsRelationalExpression::new(
+                    RelationalExpressionSpecification::NameAndLiteral(
+                        FIRST_VALUE_NAME.to_owned(), literal
+                    )
+                )
+            )
+        );
+        let mut symbols = HashMap::new();
+        symbols.insert(FIRST_VALUE_NAME.to_owned(), vec!["ACTIVE".to_owned()]);
+
+        assert_eq!(Result::Ok(()),
+                   status(ValueHolder::Symbol(Symbol::new("ACTIVE").unwrap()))
+                       .verify_symbols(&symbols));
+        assert_eq!(Result::Err(ConditionExpressionError::UnknownSymbol(
+            FIRST_VALUE_NAME.to_owned(), "ACTIVATED".to_owned())),
+                   status(ValueHolder::Symbol(Symbol::new("ACTIVATED").unwrap()))
+                       .verify_symbols(&symbols));
+        assert_eq!(Result::Err(ConditionExpressionError::SymbolExpected(FIRST_VALUE_NAME.to_owned())),
+                   status("ACTIVE".into()).verify_symbols(&symbols));
+    }
+
+    #[test]
+    fn test_evaluates_correctly_for_flag_expressions() {
+        let flag_names = |names: Vec<&str>| ValueHolder::List(Arc::new(
+            ValueHoldersList::new(names.into_iter().map(ValueHolder::from).collect(),
+                                  ValueType::String).unwrap()));
+        let predicate = |expression: RelationalExpression|
+            ConditionExpressionWrapper::new(ConditionExpression::RelationExpression(expression))
+                .unpack();
+        let specification = |literal: ValueHolder|
+            RelationalExpressionSpecification::NameAndLiteral(FIRST_VALUE_NAME.to_owned(), literal);
+
+        let has_write = predicate(RelationalExpression::HasFlag(
+            HasFlagRelationalExpression::new(specification("write".into()))));
+        let has_any = predicate(RelationalExpression::HasAnyFlag(
+            HasAnyFlagRelationalExpression::new(specification(flag_names(vec!["write", "admin"])))));
+        let has_all = predicate(RelationalExpression::HasAllFlags(
+            HasAllFlagsRelationalExpression::new(specification(flag_names(vec!["read", "admin"])))));
+
+        let payload = ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(),
+            ValueHolder::Flags(Flags::new(
+                Arc::new(vec!["read".to_owned(), "write".to_owned(), "admin".to_owned()]),
+                0b101).unwrap()));
+
+        assert!(!has_write(&payload));
+        assert!(has_any(&payload));
+        assert!(has_all(&payload));
+    }
+
+    #[test]
+    fn test_evaluates_correctly_for_length_expressions() {
+        let shorter_than = ConditionExpressionWrapper::new(
+            ConditionExpression::RelationExpression(
+                RelationalExpression::LengthLessThan(
+                    LengthLessThanRelationalExpression::new(
+                        RelationalExpressionSpecification::NameAndLiteral(
+                            FIRST_VALUE_NAME.to_owned(), ValueHolder::Integer(BigInt::from(4))
+                        )
+                    )
+                )
+            )
+        ).unpack();
+
+        assert!(shorter_than(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(), ValueHolder::Bytes(Arc::new(vec![1, 2, 3])))));
+        assert!(!shorter_than(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(), ValueHolder::Bytes(Arc::new(vec![1, 2, 3, 4])))));
+    }
+
+    #[test]
+    fn test_evaluates_correctly_for_membership_expressions() {
+        let list = ValueHolder::List(Arc::new(
+            ValueHoldersList::new(vec!["gold".into(), "platinum".into()], ValueType::String).unwrap()));
+        let predicate = |expression: RelationalExpression|
+            ConditionExpressionWrapper::new(ConditionExpression::RelationExpression(expression))
+                .unpack();
+        let specification = || RelationalExpressionSpecification::NameAndLiteral(FIRST_VALUE_NAME.to_owned(), list.clone());
+        let payload = |value: ValueHolder| ValuesPayload::singleton(FIRST_VALUE_NAME.to_owned(), value);
+
+        let is_in = predicate(RelationalExpression::IsIn(IsInRelationalExpression::new(specification())));
+        let is_not_in = predicate(RelationalExpression::NotIn(NotInRelationalExpression::new(specification())));
+
+        assert!(is_in(&payload("gold".into())));
+        assert!(!is_not_in(&payload("gold".into())));
+        assert!(!is_in(&payload("silver".into())));
+        assert!(is_not_in(&payload("silver".into())));
+        assert!(is_not_in(&payload(ValueHolder::Integer(BigInt::from(1)))));
+        assert!(!is_not_in(&ValuesPayload::empty()));
+    }
+
+    #[test]
+    fn test_evaluates_correctly_for_duration_expressions() {
+        let longer_than = ConditionExpressionWrapper::new(
+            ConditionExpression::RelationExpression(
+                RelationalExpression::GreaterThan(
+                    GreaterThanRelationalExpression::new(
+                        RelationalExpressionSpecification::NameAndLiteral(
+                            FIRST_VALUE_NAME.to_owned(), ValueHolder::Duration(Duration::from_secs(1_800))
+                        )
+                    )
+                )
+            )
+        ).unpack();
+
+        assert!(longer_than(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(), ValueHolder::Duration(Duration::from_secs(1_801)))));
+        assert!(!longer_than(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(), ValueHolder::Duration(Duration::from_secs(1_800)))));
+    }
+
+    #[test]
+    fn test_evaluates_correctly_for_within_radius_expressions() {
+        let coordinates = |text: &str| ValueHolder::GeoCoordinates(GeoCoordinates::from_str(text).unwrap());
+        let within_radius = ConditionExpressionWrapper::new(
+            ConditionExpression::RelationExpression(
+                RelationalExpression::WithinRadius(
+                    WithinRadiusRelationalExpression::new(
+                        RelationalExpressionSpecification::NameAndLiteral(
+                            FIRST_VALUE_NAME.to_owned(), coordinates("52.2297,21.0122")
+                        ),
+                        BigRational::from_integer(BigInt::from(10_000))
+                    )
+                )
+            )
+        ).unpack();
+
+        assert!(within_radius(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(), coordinates("52.2319,20.9838"))));
+        assert!(!within_radius(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(), coordinates("50.0647,19.9450"))));
+        assert!(!within_radius(&ValuesPayload::singleton(
+            FIRST_VALUE_NAME.to_owned(), "52.2319,20.9838".into())));
+    }
+
     fn first_values_payload() -> ValuesPayload {
         let mut values = HashMap::new();
         values.insert(
@@ -1,41 +1,48 @@
 
 use quote::quote;
-use syn::{Attribute, Meta, NestedMeta, Ident};
+use syn::{Attribute, Data, Fields, Meta, NestedMeta, Ident, Type};
 use proc_macro::TokenStream;
 
 pub fn impl_macro_derive(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
     let value_holders_predicate = get_predicate(ast);
+    let (argument_names, argument_types) = get_predicate_arguments(ast);
     let gen = quote! {
 
         impl #name {
-            pub fn new(specification: RelationalExpressionSpecification) -> #name {
-                #name { specification }
+            pub fn get_specification(&self) -> &RelationalExpressionSpecification {
+                &self.specification
+            }
+
+            pub fn new(specification: RelationalExpressionSpecification
+                       #(, #argument_names: #argument_types)*) -> #name {
+                #name { specification #(, #argument_names)* }
             }
         }
 
         impl ValuesPayloadPredicateSupplier for #name {
             fn get_predicate(self) -> Box<dyn Fn(&ValuesPayload) -> bool + Send + Sync> {
-                match self.specification {
+                let #name { specification #(, #argument_names)* } = self;
+                match specification {
                     RelationalExpressionSpecification::NameAndName(first, second) =>
                         Box::new(move |payload|
                             match (payload.get(&first), payload.get(&second)) {
                                 (Some(left), Some(right)) =>
-                                    left.#value_holders_predicate(right),
+                                    left.#value_holders_predicate(right #(, &#argument_names)*),
                                 (_, _) => false
                         }),
                     RelationalExpressionSpecification::NameAndLiteral(name, right) =>
                         Box::new(move |payload|
                             match payload.get(&name) {
                                 Some(left) =>
-                                    left.#value_holders_predicate(&right),
+                                    left.#value_holders_predicate(&right #(, &#argument_names)*),
                                 _ => false
                         }),
                     RelationalExpressionSpecification::LiteralAndName(left, name) =>
                         Box::new(move |payload|
                             match payload.get(&name) {
                                 Some(right) =>
-                                    (&left).#value_holders_predicate(right),
+                                    (&left).#value_holders_predicate(right #(, &#argument_names)*),
                                 _ => false
                         }),
                 }
@@ -45,10 +52,30 @@ pub fn impl_macro_derive(ast: &syn::DeriveInput) -> TokenStream {
                 self.specification.get_value_names()
             }
         }
+
+        impl UnitsVerifier for #name {
+            fn verify_units(&self,
+                            payload: &ValuesPayload) -> Result<(), ConditionExpressionError> {
+                self.specification.verify_units(payload)
+            }
+        }
     };
     gen.into()
 }
 
+fn get_predicate_arguments(ast: &syn::DeriveInput) -> (Vec<Ident>, Vec<Type>) {
+    match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter()
+                .filter(|field| field.ident.as_ref().unwrap() != "specification")
+                .map(|field| (field.ident.clone().unwrap(), field.ty.clone()))
+                .unzip(),
+            _ => panic!("Expected named fields for RelationalExpression: {:?}", ast.ident)
+        },
+        _ => panic!("Expected struct to derive RelationalExpression, got: {:?}", ast.ident)
+    }
+}
+
 fn get_predicate(ast: &syn::DeriveInput) -> Ident {
     let predicate = ast.attrs.iter()
         .filter(|attr| attr.path.segments[0].ident == "predicate")
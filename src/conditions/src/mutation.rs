@@ -0,0 +1,297 @@
+use num::bigint::BigInt;
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::ValueHolder;
+
+use crate::{ConditionExpression, LogicalExpression, RelationalExpression, RelationalExpressionSpecification};
+use crate::pattern::{MatchesRelationalExpression, NotMatchesRelationalExpression};
+use crate::relational::{EqualsRelationalExpression, EqualsWithToleranceRelationalExpression, GreaterThanOrEqualsRelationalExpression, GreaterThanRelationalExpression, IsInRelationalExpression, LessThanOrEqualsRelationalExpression, LessThanRelationalExpression, NotEqualsRelationalExpression, NotEqualsWithToleranceRelationalExpression, NotInRelationalExpression};
+
+// A single, small change of an expression, e.g. > replaced with >= or a literal moved
+// by one. A mutant which evaluates the same way as the original for every known payload
+// points at a rule which is not fully covered.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ConditionMutant {
+
+    description: String,
+    expression: ConditionExpression
+
+}
+
+impl ConditionMutant {
+
+    pub fn new(description: String,
+               expression: ConditionExpression) -> ConditionMutant {
+        ConditionMutant {
+            description,
+            expression
+        }
+    }
+
+    pub fn get_description(&self) -> &String {
+        &self.description
+    }
+
+    pub fn get_expression(&self) -> &ConditionExpression {
+        &self.expression
+    }
+
+    pub fn unpack(self) -> (String, ConditionExpression) {
+        (self.description, self.expression)
+    }
+
+}
+
+impl ConditionExpression {
+
+    pub fn get_mutants(&self) -> Vec<ConditionMutant> {
+        match self {
+            ConditionExpression::ConstantExpression(value) =>
+                vec![ConditionMutant::new(format!("{} replaced with {}", value, !value),
+                                          ConditionExpression::ConstantExpression(!value))],
+            ConditionExpression::RelationExpression(expr) => expr.get_mutants()
+                .into_iter()
+                .map(|(description, expr)|
+                    ConditionMutant::new(description, ConditionExpression::RelationExpression(expr)))
+                .collect(),
+            ConditionExpression::LogicalExpression(expr) => expr.get_mutants()
+                .into_iter()
+                .map(|(description, expr)| ConditionMutant::new(description, expr))
+                .collect()
+        }
+    }
+
+}
+
+impl LogicalExpression {
+
+    fn get_mutants(&self) -> Vec<(String, ConditionExpression)> {
+        match self {
+            LogicalExpression::And(expressions) => {
+                let mut mutants = vec![("And replaced with Or".to_owned(),
+                                        LogicalExpression::Or(expressions.clone()).into())];
+                mutants.extend(LogicalExpression::get_children_mutants(expressions)
+                    .into_iter()
+                    .map(|(description, expressions)| (description, LogicalExpression::And(expressions).into())));
+                mutants
+            },
+            LogicalExpression::Or(expressions) => {
+                let mut mutants = vec![("Or replaced with And".to_owned(),
+                                        LogicalExpression::And(expressions.clone()).into())];
+                mutants.extend(LogicalExpression::get_children_mutants(expressions)
+                    .into_iter()
+                    .map(|(description, expressions)| (description, LogicalExpression::Or(expressions).into())));
+                mutants
+            },
+            LogicalExpression::Not(expr) => {
+                let mut mutants = vec![("Not removed".to_owned(), expr.clone())];
+                mutants.extend(expr.get_mutants()
+                    .into_iter()
+                    .map(|mutant| {
+                        let (description, expr) = mutant.unpack();
+                        (description, LogicalExpression::Not(expr).into())
+                    }));
+                mutants
+            }
+        }
+    }
+
+    fn get_children_mutants(expressions: &[ConditionExpression]) -> Vec<(String, Vec<ConditionExpression>)> {
+        expressions.iter()
+            .enumerate()
+            .flat_map(|(index, expr)| expr.get_mutants()
+                .into_iter()
+                .map(move |mutant| {
+                    let (description, expr) = mutant.unpack();
+                    let mut expressions = expressions.to_vec();
+                    expressions[index] = expr;
+                    (description, expressions)
+                }))
+            .collect()
+    }
+
+}
+
+impl From<LogicalExpression> for ConditionExpression {
+    fn from(expr: LogicalExpression) -> Self {
+        ConditionExpression::LogicalExpression(Box::new(expr))
+    }
+}
+
+impl RelationalExpression {
+
+    fn get_mutants(&self) -> Vec<(String, RelationalExpression)> {
+        let specification = self.get_specification();
+        let mut mutants: Vec<(String, RelationalExpression)> = self.get_replacement_operators()
+            .into_iter()
+            .map(|expr| (format!("{} replaced with {}",
+                                 self.get_operator_name(),
+                                 expr.get_operator_name()),
+                         expr))
+            .collect();
+
+        if self.has_literal_mutants() {
+            mutants.extend(specification.get_mutants()
+                .into_iter()
+                .map(|(description, specification)|
+                    (description, self.with_specification(specification))));
+        }
+        mutants
+    }
+
+    fn get_replacement_operators(&self) -> Vec<RelationalExpression> {
+        let specification = self.get_specification().clone();
+        match self {
+            RelationalExpression::Equals(_) =>
+                vec![RelationalExpression::NotEquals(NotEqualsRelationalExpression::new(specification))],
+            RelationalExpression::NotEquals(_) =>
+                vec![RelationalExpression::Equals(EqualsRelationalExpression::new(specification))],
+            RelationalExpression::EqualsWithTolerance(expr) =>
+                vec![RelationalExpression::NotEqualsWithTolerance(
+                    NotEqualsWithToleranceRelationalExpression::new(specification, expr.get_tolerance().clone()))],
+            RelationalExpression::NotEqualsWithTolerance(expr) =>
+                vec![RelationalExpression::EqualsWithTolerance(
+                    EqualsWithToleranceRelationalExpression::new(specification, expr.get_tolerance().clone()))],
+            RelationalExpression::GreaterThan(_) =>
+                vec![RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(specification.clone())),
+                     RelationalExpression::LessThan(LessThanRelationalExpression::new(specification))],
+            RelationalExpression::GreaterThanOrEquals(_) =>
+                vec![RelationalExpression::GreaterThan(GreaterThanRelationalExpression::new(specification.clone())),
+                     RelationalExpression::LessThanOrEquals(LessThanOrEqualsRelationalExpression::new(specification))],
+            RelationalExpression::LessThan(_) =>
+                vec![RelationalExpression::LessThanOrEquals(LessThanOrEqualsRelationalExpression::new(specification.clone())),
+                     RelationalExpression::GreaterThan(GreaterThanRelationalExpression::new(specification))],
+            RelationalExpression::LessThanOrEquals(_) =>
+                vec![RelationalExpression::LessThan(LessThanRelationalExpression::new(specification.clone())),
+                     RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(specification))],
+            RelationalExpression::IsIn(_) =>
+                vec![RelationalExpression::NotIn(NotInRelationalExpression::new(specification))],
+            RelationalExpression::NotIn(_) =>
+                vec![RelationalExpression::IsIn(IsInRelationalExpression::new(specification))],
+            RelationalExpression::Matches(_) =>
+                vec![RelationalExpression::NotMatches(NotMatchesRelationalExpression::new(specification))],
+            RelationalExpression::NotMatches(_) =>
+                vec![RelationalExpression::Matches(MatchesRelationalExpression::new(specification))],
+            _ => Vec::new()
+        }
+    }
+
+    pub fn with_specification(&self,
+                          specification: RelationalExpressionSpecification) -> RelationalExpression {
+        match self {
+            RelationalExpression::Equals(_) =>
+                RelationalExpression::Equals(EqualsRelationalExpression::new(specification)),
+            RelationalExpression::NotEquals(_) =>
+                RelationalExpression::NotEquals(NotEqualsRelationalExpression::new(specification)),
+            RelationalExpression::GreaterThan(_) =>
+                RelationalExpression::GreaterThan(GreaterThanRelationalExpression::new(specification)),
+            RelationalExpression::GreaterThanOrEquals(_) =>
+                RelationalExpression::GreaterThanOrEquals(GreaterThanOrEqualsRelationalExpression::new(specification)),
+            RelationalExpression::LessThan(_) =>
+                RelationalExpression::LessThan(LessThanRelationalExpression::new(specification)),
+            RelationalExpression::LessThanOrEquals(_) =>
+                RelationalExpression::LessThanOrEquals(LessThanOrEqualsRelationalExpression::new(specification)),
+            _ => self.clone()
+        }
+    }
+
+    fn get_operator_name(&self) -> &'static str {
+        match self {
+            RelationalExpression::Equals(_) => "Equals",
+            RelationalExpression::EqualsWithTolerance(_) => "EqualsWithTolerance",
+            RelationalExpression::GreaterThan(_) => "GreaterThan",
+            RelationalExpression::GreaterThanOrEquals(_) => "GreaterThanOrEquals",
+            RelationalExpression::IsIn(_) => "IsIn",
+            RelationalExpression::LessThan(_) => "LessThan",
+            RelationalExpression::LessThanOrEquals(_) => "LessThanOrEquals",
+            RelationalExpression::Matches(_) => "Matches",
+            RelationalExpression::NotEquals(_) => "NotEquals",
+            RelationalExpression::NotEqualsWithTolerance(_) => "NotEqualsWithTolerance",
+            RelationalExpression::NotIn(_) => "NotIn",
+            RelationalExpression::NotMatches(_) => "NotMatches",
+            RelationalExpression::WithinRadius(_) => "WithinRadius",
+            _ => "Other"
+        }
+    }
+
+    // Only the comparisons have literal mutants, for the other operators moving a
+    // literal by one rarely says anything about the rule.
+    fn has_literal_mutants(&self) -> bool {
+        matches!(self,
+            RelationalExpression::Equals(_)
+            | RelationalExpression::NotEquals(_)
+            | RelationalExpression::GreaterThan(_)
+            | RelationalExpression::GreaterThanOrEquals(_)
+            | RelationalExpression::LessThan(_)
+            | RelationalExpression::LessThanOrEquals(_))
+    }
+
+}
+
+impl RelationalExpressionSpecification {
+
+    fn get_mutants(&self) -> Vec<(String, RelationalExpressionSpecification)> {
+        match self {
+            RelationalExpressionSpecification::NameAndName(_, _) => Vec::new(),
+            RelationalExpressionSpecification::NameAndLiteral(name, literal) =>
+                get_literal_mutants(literal)
+                    .into_iter()
+                    .map(|(description, literal)|
+                        (description, RelationalExpressionSpecification::NameAndLiteral(name.clone(), literal)))
+                    .collect(),
+            RelationalExpressionSpecification::LiteralAndName(literal, name) =>
+                get_literal_mutants(literal)
+                    .into_iter()
+                    .map(|(description, literal)|
+                        (description, RelationalExpressionSpecification::LiteralAndName(literal, name.clone())))
+                    .collect()
+        }
+    }
+
+}
+
+fn get_literal_mutants(literal: &ValueHolder) -> Vec<(String, ValueHolder)> {
+    match literal {
+        ValueHolder::Boolean(value) =>
+            vec![(format!("{} replaced with {}", value, !value), ValueHolder::Boolean(!value))],
+        ValueHolder::Integer(value) => vec![
+            (format!("{} replaced with {}", value, value - 1), ValueHolder::Integer(value - BigInt::from(1))),
+            (format!("{} replaced with {}", value, value + 1), ValueHolder::Integer(value + BigInt::from(1)))
+        ],
+        _ => Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_mutates_logical_expressions() {
+        let expression: ConditionExpression = LogicalExpression::And(vec![
+            ConditionExpression::ConstantExpression(true),
+            LogicalExpression::Not(ConditionExpression::ConstantExpression(false)).into()
+        ]).into();
+
+        assert_eq!(vec![
+            ConditionMutant::new("And replaced with Or".to_owned(), LogicalExpression::Or(vec![
+                ConditionExpression::ConstantExpression(true),
+                LogicalExpression::Not(ConditionExpression::ConstantExpression(false)).into()
+            ]).into()),
+            ConditionMutant::new("true replaced with false".to_owned(), LogicalExpression::And(vec![
+                ConditionExpression::ConstantExpression(false),
+                LogicalExpression::Not(ConditionExpression::ConstantExpression(false)).into()
+            ]).into()),
+            ConditionMutant::new("Not removed".to_owned(), LogicalExpression::And(vec![
+                ConditionExpression::ConstantExpression(true),
+                ConditionExpression::ConstantExpression(false)
+            ]).into()),
+            ConditionMutant::new("false replaced with true".to_owned(), LogicalExpression::And(vec![
+                ConditionExpression::ConstantExpression(true),
+                LogicalExpression::Not(ConditionExpression::ConstantExpression(true)).into()
+            ]).into())
+        ], expression.get_mutants());
+    }
+
+}
@@ -0,0 +1,182 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+use crate::{ConditionExpressionError, RelationalExpressionSpecification, UnitsVerifier, ValuesPayloadPredicateSupplier};
+
+const MAX_PATTERN_LENGTH: usize = 1024;
+const MAX_COMPILED_PATTERN_BYTES: usize = 1 << 20;
+
+// Matches strings against a regular expression. The regex crate runs in linear time, so
+// only the size of the pattern has to be limited, and the pattern has to be a literal,
+// so it is compiled once, when the tree is built.
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+pub struct MatchesRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+impl MatchesRelationalExpression {
+
+    pub fn new(specification: RelationalExpressionSpecification) -> MatchesRelationalExpression {
+        MatchesRelationalExpression {
+            specification
+        }
+    }
+
+    pub fn get_specification(&self) -> &RelationalExpressionSpecification {
+        &self.specification
+    }
+
+    pub fn compile(&self) -> Result<Regex, ConditionExpressionError> {
+        compile(&self.specification)
+    }
+
+}
+
+impl ValuesPayloadPredicateSupplier for MatchesRelationalExpression {
+
+    // Patterns are verified before the predicates are built, one which does not compile
+    // never matches.
+    fn get_predicate(self) -> Box<dyn Fn(&ValuesPayload) -> bool + Send + Sync> {
+        get_predicate(self.specification, true)
+    }
+
+    fn get_value_names(&self) -> Vec<String> {
+        self.specification.get_value_names()
+    }
+
+}
+
+impl UnitsVerifier for MatchesRelationalExpression {
+
+    fn verify_units(&self,
+                    payload: &ValuesPayload) -> Result<(), ConditionExpressionError> {
+        self.specification.verify_units(payload)
+    }
+
+}
+
+// The negation of the matches expression for present string values, a missing value or
+// a value of another type is not a string which does not match.
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+pub struct NotMatchesRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+impl NotMatchesRelationalExpression {
+
+    pub fn new(specification: RelationalExpressionSpecification) -> NotMatchesRelationalExpression {
+        NotMatchesRelationalExpression {
+            specification
+        }
+    }
+
+    pub fn get_specification(&self) -> &RelationalExpressionSpecification {
+        &self.specification
+    }
+
+    pub fn compile(&self) -> Result<Regex, ConditionExpressionError> {
+        compile(&self.specification)
+    }
+
+}
+
+impl ValuesPayloadPredicateSupplier for NotMatchesRelationalExpression {
+
+    fn get_predicate(self) -> Box<dyn Fn(&ValuesPayload) -> bool + Send + Sync> {
+        get_predicate(self.specification, false)
+    }
+
+    fn get_value_names(&self) -> Vec<String> {
+        self.specification.get_value_names()
+    }
+
+}
+
+impl UnitsVerifier for NotMatchesRelationalExpression {
+
+    fn verify_units(&self,
+                    payload: &ValuesPayload) -> Result<(), ConditionExpressionError> {
+        self.specification.verify_units(payload)
+    }
+
+}
+
+fn compile(specification: &RelationalExpressionSpecification) -> Result<Regex, ConditionExpressionError> {
+    let pattern = match specification {
+        RelationalExpressionSpecification::NameAndLiteral(_, ValueHolder::String(pattern)) => pattern,
+        _ => return Result::Err(ConditionExpressionError::PatternLiteralExpected)
+    };
+
+    if pattern.len() > MAX_PATTERN_LENGTH {
+        return Result::Err(ConditionExpressionError::PatternTooLong(pattern.len(), MAX_PATTERN_LENGTH));
+    }
+
+    RegexBuilder::new(pattern)
+        .size_limit(MAX_COMPILED_PATTERN_BYTES)
+        .dfa_size_limit(MAX_COMPILED_PATTERN_BYTES)
+        .build()
+        .map_err(|err| ConditionExpressionError::InvalidPattern(pattern.as_ref().clone(), err.to_string()))
+}
+
+// The regex is compiled here, once per predicate, and moved into the closure.
+fn get_predicate(specification: RelationalExpressionSpecification,
+                 expected: bool) -> Box<dyn Fn(&ValuesPayload) -> bool + Send + Sync> {
+    match (compile(&specification), specification) {
+        (Ok(regex), RelationalExpressionSpecification::NameAndLiteral(name, _)) =>
+            Box::new(move |payload| match payload.get(&name) {
+                Some(ValueHolder::String(value)) => regex.is_match(value.as_str()) == expected,
+                _ => false
+            }),
+        (_, _) => Box::new(|_| false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn matches(pattern: &str) -> MatchesRelationalExpression {
+        MatchesRelationalExpression::new(
+            RelationalExpressionSpecification::NameAndLiteral("name".to_owned(), ValueHolder::from(pattern)))
+    }
+
+    #[test]
+    fn test_matches_compiled_pattern() {
+        let predicate = matches("^[a-z]+@example\\.com$").get_predicate();
+
+        assert!(predicate(&ValuesPayload::singleton("name".to_owned(), ValueHolder::from("john@example.com"))));
+        assert!(!predicate(&ValuesPayload::singleton("name".to_owned(), ValueHolder::from("john@example.org"))));
+        assert!(!predicate(&ValuesPayload::empty()));
+    }
+
+    #[test]
+    fn test_does_not_match_compiled_pattern() {
+        let predicate = NotMatchesRelationalExpression::new(matches("^[a-z]+@example\\.com$").specification)
+            .get_predicate();
+
+        assert!(!predicate(&ValuesPayload::singleton("name".to_owned(), ValueHolder::from("john@example.com"))));
+        assert!(predicate(&ValuesPayload::singleton("name".to_owned(), ValueHolder::from("john@example.org"))));
+        assert!(!predicate(&ValuesPayload::singleton("name".to_owned(), ValueHolder::Boolean(true))));
+        assert!(!predicate(&ValuesPayload::empty()));
+    }
+
+    #[test]
+    fn test_rejects_patterns_over_budget() {
+        assert!(matches!(matches("(").compile(), Err(ConditionExpressionError::InvalidPattern(_, _))));
+        assert_eq!(Some(ConditionExpressionError::PatternTooLong(1025, MAX_PATTERN_LENGTH)),
+                   matches(&"a".repeat(1025)).compile().err());
+        assert!(matches!(matches("\\w{1000}\\w{1000}").compile(), Err(ConditionExpressionError::InvalidPattern(_, _))));
+        assert_eq!(Some(ConditionExpressionError::PatternLiteralExpected),
+                   MatchesRelationalExpression::new(
+                       RelationalExpressionSpecification::NameAndName("name".to_owned(), "pattern".to_owned()))
+                       .compile().err());
+    }
+
+}
@@ -1,7 +1,8 @@
 use buttercup_conditions_macros::RelationalExpression;
 use buttercup_values::{ValueHolder, ValuesPayload};
+use num::rational::BigRational;
 
-use crate::{RelationalExpressionSpecification, ValuesPayloadPredicateSupplier};
+use crate::{ConditionExpressionError, RelationalExpressionSpecification, UnitsVerifier, ValuesPayloadPredicateSupplier};
 
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +33,24 @@ pub struct EqualsRelationalExpression {
 
 }
 
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(eq_with_tolerance)]
+pub struct EqualsWithToleranceRelationalExpression {
+
+    specification: RelationalExpressionSpecification,
+    tolerance: BigRational
+
+}
+
+impl EqualsWithToleranceRelationalExpression {
+
+    pub fn get_tolerance(&self) -> &BigRational {
+        &self.tolerance
+    }
+
+}
+
 #[derive(RelationalExpression, Serialize, Deserialize,
         Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
 #[predicate(ge)]
@@ -50,6 +69,33 @@ pub struct GreaterThanRelationalExpression {
 
 }
 
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(has_all_flags)]
+pub struct HasAllFlagsRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(has_any_flag)]
+pub struct HasAnyFlagRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(has_flag)]
+pub struct HasFlagRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
 #[derive(RelationalExpression, Serialize, Deserialize,
         Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
 #[predicate(is_in)]
@@ -68,6 +114,33 @@ pub struct LessThanOrEqualsRelationalExpression {
 
 }
 
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(length_eq)]
+pub struct LengthEqualsRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(length_gt)]
+pub struct LengthGreaterThanRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(length_lt)]
+pub struct LengthLessThanRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
 #[derive(RelationalExpression, Serialize, Deserialize,
         Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
 #[predicate(lt)]
@@ -77,6 +150,42 @@ pub struct LessThanRelationalExpression {
 
 }
 
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(normalized_contains)]
+pub struct NormalizedContainsRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(normalized_ends_with)]
+pub struct NormalizedEndsWithRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(normalized_eq)]
+pub struct NormalizedEqualsRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(normalized_starts_with)]
+pub struct NormalizedStartsWithRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
 #[derive(RelationalExpression, Serialize, Deserialize,
         Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
 #[predicate(ne)]
@@ -86,6 +195,33 @@ pub struct NotEqualsRelationalExpression {
 
 }
 
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(is_not_in)]
+pub struct NotInRelationalExpression {
+
+    specification: RelationalExpressionSpecification
+
+}
+
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(ne_with_tolerance)]
+pub struct NotEqualsWithToleranceRelationalExpression {
+
+    specification: RelationalExpressionSpecification,
+    tolerance: BigRational
+
+}
+
+impl NotEqualsWithToleranceRelationalExpression {
+
+    pub fn get_tolerance(&self) -> &BigRational {
+        &self.tolerance
+    }
+
+}
+
 #[derive(RelationalExpression, Serialize, Deserialize,
         Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
 #[predicate(starts_with)]
@@ -94,3 +230,22 @@ pub struct StartsWithRelationalExpression {
     specification: RelationalExpressionSpecification
 
 }
+
+// Radius in meters.
+#[derive(RelationalExpression, Serialize, Deserialize,
+        Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+#[predicate(is_within_radius)]
+pub struct WithinRadiusRelationalExpression {
+
+    specification: RelationalExpressionSpecification,
+    radius: BigRational
+
+}
+
+impl WithinRadiusRelationalExpression {
+
+    pub fn get_radius(&self) -> &BigRational {
+        &self.radius
+    }
+
+}
@@ -0,0 +1,819 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use buttercup_api::bts::ErrorRateRollback;
+use buttercup_api::complexity::DefinitionLimits;
+use buttercup_api::document::MetadataField;
+use buttercup_bts::budget::EvaluationBudget;
+use buttercup_values::ValueHolder;
+
+use crate::cluster::{ClusterConfig, ScheduledTree};
+use crate::tls::{SniCertificate, TlsConfig};
+
+const ENV_PREFIX: &str = "BUTTERCUP_";
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum ConfigError {
+
+    InvalidFile(String, String),
+    InvalidValue(String, String),
+    MissingValue(String)
+
+}
+
+// Options are read from the toml file in BUTTERCUP_CONFIG_PATH, if it is set, with the
+// fields left out of it keeping their defaults, e.g. workers = 8 and log_level = "debug".
+// Files named *.json are read as json instead.
+// Every option can be overridden with the environment variable named after its field:
+//
+// - BUTTERCUP_BIND_ADDRESS, BUTTERCUP_TCP_ENABLED: the tcp listener, false leaves the unix
+//   socket as the only one.
+// - BUTTERCUP_UNIX_SOCKET_PATH: adds a plain http listener on a unix domain socket.
+// - BUTTERCUP_WORKERS, BUTTERCUP_KEEP_ALIVE_SECS (0 disables keep-alive),
+//   BUTTERCUP_CLIENT_TIMEOUT_MILLIS, BUTTERCUP_MAX_CONNECTIONS, BUTTERCUP_MAX_PAYLOAD_BYTES.
+// - BUTTERCUP_LOG_LEVEL: the same filters as RUST_LOG, e.g. info,actix_web=warn.
+// - BUTTERCUP_TLS_CERT_PATH with BUTTERCUP_TLS_KEY_PATH: enables tls.
+//   BUTTERCUP_TLS_CLIENT_CA_PATH requires client certificates, BUTTERCUP_TLS_SNI_CERTIFICATES
+//   takes server_name=cert_path:key_path entries separated by commas.
+// - BUTTERCUP_REQUIRED_METADATA: the metadata fields, e.g. owner,team, every definition
+//   document has to fill in.
+// - BUTTERCUP_USAGE_STATS_PATH: keeps usage stats of the trees in the file instead of in
+//   memory only, saved every BUTTERCUP_USAGE_STATS_FLUSH_SECS.
+// - BUTTERCUP_DEFINITIONS_PATH: json documents loaded and activated at startup, reloaded
+//   every BUTTERCUP_DEFINITIONS_RELOAD_SECS when their files change.
+// - BUTTERCUP_SLOW_TICK_THRESHOLD_MILLIS: slower ticks are logged with node timings.
+// - BUTTERCUP_MAX_DEFINITION_NODES, BUTTERCUP_MAX_DEFINITION_EDGES,
+//   BUTTERCUP_MAX_DEFINITION_EXPRESSION_DEPTH, BUTTERCUP_MAX_DEFINITION_CONDITIONS_PER_EDGE:
+//   limits of submitted definitions.
+// - BUTTERCUP_MAX_EVALUATION_NODES, BUTTERCUP_MAX_EVALUATION_CONDITIONS,
+//   BUTTERCUP_MAX_EVALUATION_LOOKUPS: ticks and evaluations going over them are aborted,
+//   there are no such limits by default.
+// - BUTTERCUP_BLACKBOARD_SNAPSHOT_INTERVAL: journals every mutation of the blackboards of
+//   tree instances, with a snapshot taken every that many mutations.
+// - BUTTERCUP_WEBHOOK_URLS: activations, rollbacks and failed self-tests are posted there,
+//   signed with BUTTERCUP_WEBHOOK_SECRET and retried up to BUTTERCUP_WEBHOOK_MAX_RETRIES times.
+// - BUTTERCUP_WEBHOOK_OUTBOX_PATH: stages webhooks in the file, so that the ones not taken
+//   by their receivers are sent again after restarts.
+// - BUTTERCUP_STATIC_VALUES: e.g. environment=prod,region=eu, put into every context before
+//   the values sent by clients, which can override them. Given in the environment they are
+//   strings, the config file can give them any type.
+// - BUTTERCUP_ROLLBACK_MAX_ERROR_PERCENT: rolls back versions whose ticks end with an error
+//   more often, over at least BUTTERCUP_ROLLBACK_MIN_TICKS ticks within the last
+//   BUTTERCUP_ROLLBACK_CHECK_SECS. Trees without a previous version keep serving.
+// - BUTTERCUP_READ_ONLY: starts the server with definitions frozen, which can be lifted at
+//   runtime.
+// - BUTTERCUP_INSTANCE_TTL_SECS, BUTTERCUP_INSTANCE_EXECUTOR_SHARDS,
+//   BUTTERCUP_INSTANCE_EXECUTOR_BATCH_SIZE, BUTTERCUP_INSTANCE_EXECUTOR_MAX_PENDING,
+//   BUTTERCUP_INSTANCE_EXECUTOR_RETRY_AFTER_SECS: tree instances and their executor.
+// - BUTTERCUP_CLUSTER_REDIS_URL: runs the server as part of a cluster sharing its state
+//   through redis, under BUTTERCUP_CLUSTER_NODE_ID, a random id by default, with instances
+//   reachable through BUTTERCUP_CLUSTER_ADVERTISED_URL. The leader holds its lease for
+//   BUTTERCUP_CLUSTER_LEASE_SECS and changes are synced every BUTTERCUP_CLUSTER_SYNC_MILLIS.
+// - BUTTERCUP_SCHEDULES: tree_id=cron expression entries separated by semicolons, e.g.
+//   1=0 */5 * * * *, the trees are evaluated by the leader only.
+//
+// The intervals, BUTTERCUP_INSTANCE_TTL_SECS, BUTTERCUP_USAGE_STATS_FLUSH_SECS,
+// BUTTERCUP_DEFINITIONS_RELOAD_SECS and BUTTERCUP_ROLLBACK_CHECK_SECS, cannot be 0.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct ServerConfig {
+
+    bind_address: String,
+    tcp_enabled: bool,
+    unix_socket_path: Option<String>,
+    workers: usize,
+    keep_alive_secs: usize,
+    client_timeout_millis: u64,
+    max_connections: usize,
+    max_payload_bytes: usize,
+    log_level: String,
+    tls: Option<TlsConfig>,
+    required_metadata: Vec<MetadataField>,
+    usage_stats_path: Option<String>,
+    usage_stats_flush_secs: u64,
+    definitions_path: Option<String>,
+    definitions_reload_secs: u64,
+    slow_tick_threshold_millis: Option<u64>,
+    max_definition_nodes: usize,
+    max_definition_edges: usize,
+    max_definition_expression_depth: usize,
+    max_definition_conditions_per_edge: usize,
+    max_evaluation_nodes: Option<u64>,
+    max_evaluation_conditions: Option<u64>,
+    max_evaluation_lookups: Option<u64>,
+    cluster: Option<ClusterConfig>,
+    schedules: Vec<ScheduledTree>,
+    blackboard_snapshot_interval: Option<u64>,
+    webhook_urls: Vec<String>,
+    webhook_secret: Option<String>,
+    webhook_max_retries: u32,
+    webhook_outbox_path: Option<String>,
+    static_values: HashMap<String, ValueHolder>,
+    rollback_max_error_percent: Option<u64>,
+    rollback_min_ticks: u64,
+    rollback_check_secs: u64,
+    read_only: bool,
+
+    instance_ttl_secs: u64,
+    instance_executor_shards: usize,
+    instance_executor_batch_size: usize,
+    instance_executor_max_pending: usize,
+    instance_executor_retry_after_secs: u64
+
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind_address: "127.0.0.1:7777".to_owned(),
+            tcp_enabled: true,
+            unix_socket_path: Option::None,
+            workers: num_cpus(),
+            keep_alive_secs: 5,
+            client_timeout_millis: 5000,
+            max_connections: 25_000,
+            max_payload_bytes: 256 * 1024,
+            log_level: "info".to_owned(),
+            tls: Option::None,
+            required_metadata: Vec::new(),
+            usage_stats_path: Option::None,
+            usage_stats_flush_secs: 60,
+            definitions_path: Option::None,
+            definitions_reload_secs: 5,
+            slow_tick_threshold_millis: Option::None,
+            max_definition_nodes: 10_000,
+            max_definition_edges: 10_000,
+            max_definition_expression_depth: 32,
+            max_definition_conditions_per_edge: 256,
+            max_evaluation_nodes: Option::None,
+            max_evaluation_conditions: Option::None,
+            max_evaluation_lookups: Option::None,
+            cluster: Option::None,
+            schedules: Vec::new(),
+            blackboard_snapshot_interval: Option::None,
+            webhook_urls: Vec::new(),
+            webhook_secret: Option::None,
+            webhook_max_retries: 3,
+            webhook_outbox_path: Option::None,
+            static_values: HashMap::new(),
+            rollback_max_error_percent: Option::None,
+            rollback_min_ticks: 100,
+            rollback_check_secs: 60,
+            read_only: false,
+            instance_ttl_secs: 3600,
+            instance_executor_shards: 4,
+            instance_executor_batch_size: 64,
+            instance_executor_max_pending: 1024,
+            instance_executor_retry_after_secs: 1
+        }
+    }
+}
+
+impl ServerConfig {
+
+    pub fn from_env() -> Result<ServerConfig, ConfigError> {
+        ServerConfig::from_lookup(|name| env::var(format!("{}{}", ENV_PREFIX, name)).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<ServerConfig, ConfigError> {
+        let defaults = match lookup("CONFIG_PATH") {
+            None => ServerConfig::default(),
+            Some(path) => ServerConfig::from_file(Path::new(&path))?
+        };
+        let tcp_enabled = parse(&lookup, "TCP_ENABLED", defaults.tcp_enabled)?;
+        let unix_socket_path = lookup("UNIX_SOCKET_PATH").or(defaults.unix_socket_path);
+
+        if !tcp_enabled && unix_socket_path.is_none() {
+            return Result::Err(ConfigError::MissingValue(format!("{}UNIX_SOCKET_PATH", ENV_PREFIX)));
+        }
+
+        let config = ServerConfig {
+            bind_address: lookup("BIND_ADDRESS").unwrap_or(defaults.bind_address),
+            tcp_enabled,
+            unix_socket_path,
+            workers: parse(&lookup, "WORKERS", defaults.workers)?,
+            keep_alive_secs: parse(&lookup, "KEEP_ALIVE_SECS", defaults.keep_alive_secs)?,
+            client_timeout_millis:
+                parse(&lookup, "CLIENT_TIMEOUT_MILLIS", defaults.client_timeout_millis)?,
+            max_connections: parse(&lookup, "MAX_CONNECTIONS", defaults.max_connections)?,
+            max_payload_bytes: parse(&lookup, "MAX_PAYLOAD_BYTES", defaults.max_payload_bytes)?,
+            log_level: lookup("LOG_LEVEL").unwrap_or(defaults.log_level),
+            tls: parse_tls(&lookup)?.or(defaults.tls),
+            required_metadata: parse_list(&lookup, "REQUIRED_METADATA", defaults.required_metadata)?,
+            usage_stats_path: lookup("USAGE_STATS_PATH").or(defaults.usage_stats_path),
+            usage_stats_flush_secs: parse(&lookup, "USAGE_STATS_FLUSH_SECS", defaults.usage_stats_flush_secs)?,
+            definitions_path: lookup("DEFINITIONS_PATH").or(defaults.definitions_path),
+            definitions_reload_secs:
+                parse(&lookup, "DEFINITIONS_RELOAD_SECS", defaults.definitions_reload_secs)?,
+            slow_tick_threshold_millis: match lookup("SLOW_TICK_THRESHOLD_MILLIS") {
+                None => defaults.slow_tick_threshold_millis,
+                Some(_) => Option::Some(parse(&lookup, "SLOW_TICK_THRESHOLD_MILLIS", 0)?)
+            },
+            max_definition_nodes:
+                parse(&lookup, "MAX_DEFINITION_NODES", defaults.max_definition_nodes)?,
+            max_definition_edges:
+                parse(&lookup, "MAX_DEFINITION_EDGES", defaults.max_definition_edges)?,
+            max_definition_expression_depth:
+                parse(&lookup, "MAX_DEFINITION_EXPRESSION_DEPTH", defaults.max_definition_expression_depth)?,
+            max_definition_conditions_per_edge:
+                parse(&lookup,
+                      "MAX_DEFINITION_CONDITIONS_PER_EDGE",
+                      defaults.max_definition_conditions_per_edge)?,
+            max_evaluation_nodes: parse_optional(&lookup, "MAX_EVALUATION_NODES")?.or(defaults.max_evaluation_nodes),
+            max_evaluation_conditions: parse_optional(&lookup, "MAX_EVALUATION_CONDITIONS")?.or(defaults.max_evaluation_conditions),
+            max_evaluation_lookups: parse_optional(&lookup, "MAX_EVALUATION_LOOKUPS")?.or(defaults.max_evaluation_lookups),
+            cluster: parse_cluster(&lookup)?.or(defaults.cluster),
+            schedules: parse_schedules(&lookup, defaults.schedules)?,
+            blackboard_snapshot_interval: parse_optional(&lookup, "BLACKBOARD_SNAPSHOT_INTERVAL")?.or(defaults.blackboard_snapshot_interval),
+            webhook_urls: parse_list(&lookup, "WEBHOOK_URLS", defaults.webhook_urls)?,
+            webhook_secret: lookup("WEBHOOK_SECRET").or(defaults.webhook_secret),
+            webhook_max_retries: parse(&lookup, "WEBHOOK_MAX_RETRIES", defaults.webhook_max_retries)?,
+            webhook_outbox_path: lookup("WEBHOOK_OUTBOX_PATH").or(defaults.webhook_outbox_path),
+            static_values: parse_static_values(&lookup, defaults.static_values)?,
+            rollback_max_error_percent:
+                parse_optional(&lookup, "ROLLBACK_MAX_ERROR_PERCENT")?.or(defaults.rollback_max_error_percent),
+            rollback_min_ticks: parse(&lookup, "ROLLBACK_MIN_TICKS", defaults.rollback_min_ticks)?,
+            rollback_check_secs: parse(&lookup, "ROLLBACK_CHECK_SECS", defaults.rollback_check_secs)?,
+            read_only: parse(&lookup, "READ_ONLY", defaults.read_only)?,
+            instance_ttl_secs: parse(&lookup, "INSTANCE_TTL_SECS", defaults.instance_ttl_secs)?,
+            instance_executor_shards:
+                parse(&lookup, "INSTANCE_EXECUTOR_SHARDS", defaults.instance_executor_shards)?,
+            instance_executor_batch_size:
+                parse(&lookup, "INSTANCE_EXECUTOR_BATCH_SIZE", defaults.instance_executor_batch_size)?,
+            instance_executor_max_pending:
+                parse(&lookup, "INSTANCE_EXECUTOR_MAX_PENDING", defaults.instance_executor_max_pending)?,
+            instance_executor_retry_after_secs:
+                parse(&lookup,
+                      "INSTANCE_EXECUTOR_RETRY_AFTER_SECS",
+                      defaults.instance_executor_retry_after_secs)?
+        };
+
+        // The loops waiting for them would spin.
+        let intervals = [("INSTANCE_TTL_SECS", config.instance_ttl_secs),
+                         ("USAGE_STATS_FLUSH_SECS", config.usage_stats_flush_secs),
+                         ("DEFINITIONS_RELOAD_SECS", config.definitions_reload_secs),
+                         ("ROLLBACK_CHECK_SECS", config.rollback_check_secs)];
+
+        match intervals.iter().find(|(_, secs)| *secs == 0) {
+            None => Result::Ok(config),
+            Some((name, _)) => Result::Err(ConfigError::InvalidValue(format!("{}{}", ENV_PREFIX, name), "0".to_owned()))
+        }
+    }
+
+    fn from_file(path: &Path) -> Result<ServerConfig, ConfigError> {
+        let is_json = path.extension().and_then(|extension| extension.to_str()) == Option::Some("json");
+
+        fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| match is_json {
+                true => serde_json::from_slice(&bytes).map_err(|err| err.to_string()),
+                false => toml::from_slice(&bytes).map_err(|err| err.to_string())
+            })
+            .map_err(|err| ConfigError::InvalidFile(path.display().to_string(), err))
+    }
+
+    pub fn get_bind_address(&self) -> &String {
+        &self.bind_address
+    }
+
+    pub fn is_tcp_enabled(&self) -> bool {
+        self.tcp_enabled
+    }
+
+    pub fn get_unix_socket_path(&self) -> &Option<String> {
+        &self.unix_socket_path
+    }
+
+    pub fn get_workers(&self) -> usize {
+        self.workers
+    }
+
+    pub fn get_keep_alive_secs(&self) -> usize {
+        self.keep_alive_secs
+    }
+
+    pub fn get_client_timeout_millis(&self) -> u64 {
+        self.client_timeout_millis
+    }
+
+    pub fn get_max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    pub fn get_max_payload_bytes(&self) -> usize {
+        self.max_payload_bytes
+    }
+
+    pub fn get_log_level(&self) -> &String {
+        &self.log_level
+    }
+
+    pub fn get_tls(&self) -> &Option<TlsConfig> {
+        &self.tls
+    }
+
+    pub fn get_required_metadata(&self) -> &Vec<MetadataField> {
+        &self.required_metadata
+    }
+
+    pub fn get_usage_stats_path(&self) -> &Option<String> {
+        &self.usage_stats_path
+    }
+
+    pub fn get_usage_stats_flush_interval(&self) -> Duration {
+        Duration::from_secs(self.usage_stats_flush_secs)
+    }
+
+    pub fn get_definitions_path(&self) -> &Option<String> {
+        &self.definitions_path
+    }
+
+    pub fn get_definitions_reload_interval(&self) -> Duration {
+        Duration::from_secs(self.definitions_reload_secs)
+    }
+
+    pub fn get_slow_tick_threshold(&self) -> Option<Duration> {
+        self.slow_tick_threshold_millis.map(Duration::from_millis)
+    }
+
+    pub fn get_definition_limits(&self) -> DefinitionLimits {
+        DefinitionLimits::default()
+            .with_max_nodes(self.max_definition_nodes)
+            .with_max_edges(self.max_definition_edges)
+            .with_max_expression_depth(self.max_definition_expression_depth)
+            .with_max_conditions_per_edge(self.max_definition_conditions_per_edge)
+    }
+
+    pub fn get_evaluation_budget(&self) -> EvaluationBudget {
+        let budget = EvaluationBudget::default();
+        let budget = match self.max_evaluation_nodes {
+            None => budget,
+            Some(max_nodes) => budget.with_max_nodes_visited(max_nodes)
+        };
+        let budget = match self.max_evaluation_conditions {
+            None => budget,
+            Some(max_conditions) => budget.with_max_conditions_evaluated(max_conditions)
+        };
+
+        match self.max_evaluation_lookups {
+            None => budget,
+            Some(max_lookups) => budget.with_max_lookups(max_lookups)
+        }
+    }
+
+    pub fn get_cluster(&self) -> &Option<ClusterConfig> {
+        &self.cluster
+    }
+
+    pub fn get_schedules(&self) -> &Vec<ScheduledTree> {
+        &self.schedules
+    }
+
+    pub fn get_blackboard_snapshot_interval(&self) -> Option<u64> {
+        self.blackboard_snapshot_interval
+    }
+
+    pub fn get_webhook_urls(&self) -> &Vec<String> {
+        &self.webhook_urls
+    }
+
+    pub fn get_webhook_secret(&self) -> &Option<String> {
+        &self.webhook_secret
+    }
+
+    pub fn get_webhook_max_retries(&self) -> u32 {
+        self.webhook_max_retries
+    }
+
+    pub fn get_webhook_outbox_path(&self) -> &Option<String> {
+        &self.webhook_outbox_path
+    }
+
+    pub fn get_static_values(&self) -> &HashMap<String, ValueHolder> {
+        &self.static_values
+    }
+
+    pub fn get_error_rate_rollback(&self) -> Option<ErrorRateRollback> {
+        self.rollback_max_error_percent
+            .map(|max_error_percent| ErrorRateRollback::new(max_error_percent, self.rollback_min_ticks))
+    }
+
+    pub fn get_rollback_check_interval(&self) -> Duration {
+        Duration::from_secs(self.rollback_check_secs)
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn get_instance_ttl(&self) -> Duration {
+        Duration::from_secs(self.instance_ttl_secs)
+    }
+
+    pub fn get_instance_executor_shards(&self) -> usize {
+        self.instance_executor_shards
+    }
+
+    pub fn get_instance_executor_batch_size(&self) -> usize {
+        self.instance_executor_batch_size
+    }
+
+    pub fn get_instance_executor_max_pending(&self) -> usize {
+        self.instance_executor_max_pending
+    }
+
+    pub fn get_instance_executor_retry_after_secs(&self) -> u64 {
+        self.instance_executor_retry_after_secs
+    }
+
+}
+
+fn parse<T: FromStr>(lookup: &impl Fn(&str) -> Option<String>,
+                     name: &str,
+                     default: T) -> Result<T, ConfigError> {
+    match lookup(name) {
+        None => Result::Ok(default),
+        Some(value) => value.trim()
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue(format!("{}{}", ENV_PREFIX, name), value))
+    }
+}
+
+fn parse_optional<T: FromStr>(lookup: &impl Fn(&str) -> Option<String>,
+                              name: &str) -> Result<Option<T>, ConfigError> {
+    lookup(name)
+        .map(|value| value.trim()
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue(format!("{}{}", ENV_PREFIX, name), value.clone())))
+        .transpose()
+}
+
+fn parse_list<T: FromStr>(lookup: &impl Fn(&str) -> Option<String>,
+                          name: &str,
+                          default: Vec<T>) -> Result<Vec<T>, ConfigError> {
+    match lookup(name) {
+        None => Result::Ok(default),
+        Some(value) => value.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue(format!("{}{}", ENV_PREFIX, name), entry.to_owned())))
+            .collect()
+    }
+}
+
+fn parse_static_values(lookup: &impl Fn(&str) -> Option<String>,
+                       default: HashMap<String, ValueHolder>) -> Result<HashMap<String, ValueHolder>, ConfigError> {
+    match lookup("STATIC_VALUES") {
+        None => Result::Ok(default),
+        Some(value) => value.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry.split_once('=')
+                .map(|(name, value)| (name.trim().to_owned(), value.trim().into()))
+                .ok_or_else(|| ConfigError::InvalidValue(format!("{}STATIC_VALUES", ENV_PREFIX), entry.to_owned())))
+            .collect()
+    }
+}
+
+fn parse_tls(lookup: &impl Fn(&str) -> Option<String>) -> Result<Option<TlsConfig>, ConfigError> {
+    let (cert_path, key_path) = match (lookup("TLS_CERT_PATH"), lookup("TLS_KEY_PATH")) {
+        (None, None) => return Result::Ok(Option::None),
+        (Some(cert_path), Some(key_path)) => (cert_path, key_path),
+        (None, Some(_)) => return Result::Err(ConfigError::MissingValue(format!("{}TLS_CERT_PATH", ENV_PREFIX))),
+        (Some(_), None) => return Result::Err(ConfigError::MissingValue(format!("{}TLS_KEY_PATH", ENV_PREFIX)))
+    };
+
+    let mut tls = TlsConfig::new(cert_path, key_path);
+    if let Some(client_ca_path) = lookup("TLS_CLIENT_CA_PATH") {
+        tls = tls.with_client_ca_path(client_ca_path);
+    }
+    if let Some(sni_certificates) = lookup("TLS_SNI_CERTIFICATES") {
+        tls = tls.with_sni_certificates(
+            sni_certificates.split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| parse_sni_certificate(entry)
+                    .ok_or_else(|| ConfigError::InvalidValue(
+                        format!("{}TLS_SNI_CERTIFICATES", ENV_PREFIX), entry.to_owned())))
+                .collect::<Result<Vec<_>, _>>()?);
+    }
+
+    Result::Ok(Option::Some(tls))
+}
+
+fn parse_cluster(lookup: &impl Fn(&str) -> Option<String>) -> Result<Option<ClusterConfig>, ConfigError> {
+    let redis_url = match lookup("CLUSTER_REDIS_URL") {
+        None => return Result::Ok(Option::None),
+        Some(redis_url) => redis_url
+    };
+
+    let mut cluster = ClusterConfig::new(redis_url);
+    if let Some(node_id) = lookup("CLUSTER_NODE_ID") {
+        cluster = cluster.with_node_id(node_id);
+    }
+    if let Some(advertised_url) = lookup("CLUSTER_ADVERTISED_URL") {
+        cluster = cluster.with_advertised_url(advertised_url);
+    }
+    if let Some(lease_secs) = parse_optional(lookup, "CLUSTER_LEASE_SECS")? {
+        cluster = cluster.with_lease_secs(lease_secs);
+    }
+    if let Some(sync_millis) = parse_optional(lookup, "CLUSTER_SYNC_MILLIS")? {
+        cluster = cluster.with_sync_millis(sync_millis);
+    }
+
+    Result::Ok(Option::Some(cluster))
+}
+
+fn parse_schedules(lookup: &impl Fn(&str) -> Option<String>,
+                   default: Vec<ScheduledTree>) -> Result<Vec<ScheduledTree>, ConfigError> {
+    match lookup("SCHEDULES") {
+        None => Result::Ok(default),
+        Some(value) => value.split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| parse_schedule(entry)
+                .ok_or_else(|| ConfigError::InvalidValue(format!("{}SCHEDULES", ENV_PREFIX), entry.to_owned())))
+            .collect()
+    }
+}
+
+fn parse_schedule(entry: &str) -> Option<ScheduledTree> {
+    let (tree_id, expression) = entry.split_once('=')?;
+
+    ScheduledTree::new(tree_id.trim().parse().ok()?, expression.trim().to_owned()).ok()
+}
+
+fn parse_sni_certificate(entry: &str) -> Option<SniCertificate> {
+    let (server_name, paths) = entry.split_once('=')?;
+    let (cert_path, key_path) = paths.split_once(':')?;
+
+    Option::Some(SniCertificate::new(server_name.to_owned(), cert_path.to_owned(), key_path.to_owned()))
+}
+
+fn num_cpus() -> usize {
+    std::thread::available_parallelism()
+        .map(|parallelism| parallelism.get())
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_overrides_defaults() {
+        let config = ServerConfig::from_lookup(
+            lookup(&[("WORKERS", "2"), ("KEEP_ALIVE_SECS", "0"), ("BIND_ADDRESS", "0.0.0.0:80")]))
+            .unwrap();
+
+        assert_eq!(2, config.get_workers());
+        assert_eq!(0, config.get_keep_alive_secs());
+        assert_eq!("0.0.0.0:80", config.get_bind_address());
+        assert_eq!(ServerConfig::default().get_max_connections(), config.get_max_connections());
+    }
+
+    #[test]
+    fn test_reads_options_from_file_before_overrides() {
+        let path = std::env::temp_dir().join(format!("buttercup-config-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{ "workers": 3, "log_level": "debug", "webhook_urls": ["http://a"] }"#).unwrap();
+
+        let config = ServerConfig::from_lookup(
+            lookup(&[("CONFIG_PATH", path.to_str().unwrap()), ("LOG_LEVEL", "warn")]));
+        std::fs::remove_file(&path).unwrap();
+        let config = config.unwrap();
+
+        assert_eq!(3, config.get_workers());
+        assert_eq!("warn", config.get_log_level());
+        assert_eq!(&vec!["http://a".to_owned()], config.get_webhook_urls());
+        assert_eq!(ServerConfig::default().get_bind_address(), config.get_bind_address());
+    }
+
+    #[test]
+    fn test_reads_options_from_toml_file() {
+        let path = std::env::temp_dir().join(format!("buttercup-config-{}.toml", std::process::id()));
+        std::fs::write(&path, r#"
+            workers = 3
+            log_level = "debug"
+            webhook_urls = ["http://a"]
+
+            [static_values]
+            region = { String = "eu" }
+        "#).unwrap();
+
+        let config = ServerConfig::from_lookup(lookup(&[("CONFIG_PATH", path.to_str().unwrap())]));
+        std::fs::remove_file(&path).unwrap();
+        let config = config.unwrap();
+
+        assert_eq!(3, config.get_workers());
+        assert_eq!("debug", config.get_log_level());
+        assert_eq!(&vec!["http://a".to_owned()], config.get_webhook_urls());
+        assert_eq!(Option::Some(&ValueHolder::from("eu")), config.get_static_values().get("region"));
+    }
+
+    #[test]
+    fn test_reads_static_values() {
+        let config = ServerConfig::from_lookup(lookup(&[("STATIC_VALUES", "environment=prod, region=eu")])).unwrap();
+
+        assert_eq!(Option::Some(&ValueHolder::from("prod")), config.get_static_values().get("environment"));
+        assert_eq!(Option::Some(&ValueHolder::from("eu")), config.get_static_values().get("region"));
+        assert_eq!(Result::Err(ConfigError::InvalidValue("BUTTERCUP_STATIC_VALUES".to_owned(), "prod".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("STATIC_VALUES", "prod")])));
+    }
+
+    #[test]
+    fn test_rejects_invalid_files() {
+        let path = std::env::temp_dir().join(format!("buttercup-invalid-config-{}.toml", std::process::id()));
+        std::fs::write(&path, r#"{ "workers": 3 }"#).unwrap();
+
+        let config = ServerConfig::from_lookup(lookup(&[("CONFIG_PATH", path.to_str().unwrap())]));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(config, Result::Err(ConfigError::InvalidFile(_, _))));
+        assert!(matches!(ServerConfig::from_lookup(lookup(&[("CONFIG_PATH", "/nonexistent/buttercup.toml")])),
+                         Result::Err(ConfigError::InvalidFile(_, _))));
+    }
+
+    #[test]
+    fn test_rejects_invalid_values() {
+        assert_eq!(Result::Err(ConfigError::InvalidValue("BUTTERCUP_WORKERS".to_owned(), "many".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("WORKERS", "many")])));
+    }
+
+    #[test]
+    fn test_requires_a_listener() {
+        let config = ServerConfig::from_lookup(
+            lookup(&[("TCP_ENABLED", "false"), ("UNIX_SOCKET_PATH", "/tmp/buttercup.sock")]))
+            .unwrap();
+
+        assert!(!config.is_tcp_enabled());
+        assert_eq!(&Option::Some("/tmp/buttercup.sock".to_owned()), config.get_unix_socket_path());
+        assert_eq!(Result::Err(ConfigError::MissingValue("BUTTERCUP_UNIX_SOCKET_PATH".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("TCP_ENABLED", "false")])));
+    }
+
+    #[test]
+    fn test_reads_tls_options() {
+        assert_eq!(&Option::None, ServerConfig::from_lookup(lookup(&[])).unwrap().get_tls());
+        assert_eq!(&Option::Some(
+            TlsConfig::new("cert.pem".to_owned(), "key.pem".to_owned())
+                .with_client_ca_path("ca.pem".to_owned())
+                .with_sni_certificates(vec![
+                    SniCertificate::new("a.example.com".to_owned(), "a.pem".to_owned(), "a.key".to_owned())])),
+                   ServerConfig::from_lookup(
+                       lookup(&[("TLS_CERT_PATH", "cert.pem"),
+                                ("TLS_KEY_PATH", "key.pem"),
+                                ("TLS_CLIENT_CA_PATH", "ca.pem"),
+                                ("TLS_SNI_CERTIFICATES", "a.example.com=a.pem:a.key")]))
+                       .unwrap()
+                       .get_tls());
+        assert_eq!(Result::Err(ConfigError::MissingValue("BUTTERCUP_TLS_KEY_PATH".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("TLS_CERT_PATH", "cert.pem")])));
+        assert_eq!(Result::Err(ConfigError::InvalidValue("BUTTERCUP_TLS_SNI_CERTIFICATES".to_owned(),
+                                                         "a.example.com".to_owned())),
+                   ServerConfig::from_lookup(
+                       lookup(&[("TLS_CERT_PATH", "cert.pem"),
+                                ("TLS_KEY_PATH", "key.pem"),
+                                ("TLS_SNI_CERTIFICATES", "a.example.com")])));
+    }
+
+    #[test]
+    fn test_reads_usage_stats_options() {
+        let config = ServerConfig::from_lookup(
+            lookup(&[("USAGE_STATS_PATH", "/var/lib/buttercup/usage.json"), ("USAGE_STATS_FLUSH_SECS", "30")]))
+            .unwrap();
+
+        assert_eq!(&Option::Some("/var/lib/buttercup/usage.json".to_owned()), config.get_usage_stats_path());
+        assert_eq!(Duration::from_secs(30), config.get_usage_stats_flush_interval());
+    }
+
+    #[test]
+    fn test_reads_webhook_outbox_path() {
+        let config = ServerConfig::from_lookup(
+            lookup(&[("WEBHOOK_URLS", "http://a"), ("WEBHOOK_OUTBOX_PATH", "/var/lib/buttercup/outbox")]))
+            .unwrap();
+
+        assert_eq!(&Option::Some("/var/lib/buttercup/outbox".to_owned()), config.get_webhook_outbox_path());
+        assert_eq!(&Option::None, ServerConfig::default().get_webhook_outbox_path());
+    }
+
+    fn test_reads_definitions_options() {
+        let defaults = ServerConfig::from_lookup(lookup(&[])).unwrap();
+        let config = ServerConfig::from_lookup(
+            lookup(&[("DEFINITIONS_PATH", "/etc/buttercup/trees"), ("DEFINITIONS_RELOAD_SECS", "1")]))
+            .unwrap();
+
+        assert_eq!(&Option::None, defaults.get_definitions_path());
+        assert_eq!(&Option::Some("/etc/buttercup/trees".to_owned()), config.get_definitions_path());
+        assert_eq!(Duration::from_secs(1), config.get_definitions_reload_interval());
+    }
+
+    #[test]
+    fn test_reads_definition_limits() {
+        assert_eq!(DefinitionLimits::default()
+                       .with_max_nodes(100)
+                       .with_max_edges(10_000)
+                       .with_max_expression_depth(4)
+                       .with_max_conditions_per_edge(256),
+                   ServerConfig::from_lookup(
+                       lookup(&[("MAX_DEFINITION_NODES", "100"), ("MAX_DEFINITION_EXPRESSION_DEPTH", "4")]))
+                       .unwrap()
+                       .get_definition_limits());
+    }
+
+    #[test]
+    fn test_reads_evaluation_budget() {
+        assert_eq!(EvaluationBudget::default(), ServerConfig::from_lookup(lookup(&[])).unwrap().get_evaluation_budget());
+        assert_eq!(EvaluationBudget::default()
+                       .with_max_nodes_visited(500)
+                       .with_max_lookups(50),
+                   ServerConfig::from_lookup(
+                       lookup(&[("MAX_EVALUATION_NODES", "500"), ("MAX_EVALUATION_LOOKUPS", "50")]))
+                       .unwrap()
+                       .get_evaluation_budget());
+    }
+
+    #[test]
+    fn test_reads_slow_tick_threshold() {
+        assert_eq!(Option::None, ServerConfig::from_lookup(lookup(&[])).unwrap().get_slow_tick_threshold());
+        assert_eq!(Option::Some(Duration::from_millis(250)),
+                   ServerConfig::from_lookup(lookup(&[("SLOW_TICK_THRESHOLD_MILLIS", "250")]))
+                       .unwrap()
+                       .get_slow_tick_threshold());
+    }
+
+    #[test]
+    fn test_reads_required_metadata() {
+        assert_eq!(&vec![MetadataField::Owner, MetadataField::Team],
+                   ServerConfig::from_lookup(lookup(&[("REQUIRED_METADATA", "owner, Team")]))
+                       .unwrap()
+                       .get_required_metadata());
+        assert_eq!(Result::Err(ConfigError::InvalidValue("BUTTERCUP_REQUIRED_METADATA".to_owned(),
+                                                         "cost".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("REQUIRED_METADATA", "owner,cost")])));
+    }
+
+    #[test]
+    fn test_reads_cluster_options() {
+        assert_eq!(&Option::None, ServerConfig::from_lookup(lookup(&[])).unwrap().get_cluster());
+        assert_eq!(&Option::Some(
+            ClusterConfig::new("redis://127.0.0.1/".to_owned())
+                .with_node_id("first".to_owned())
+                .with_advertised_url("http://10.0.0.1:7777".to_owned())
+                .with_lease_secs(5)),
+                   ServerConfig::from_lookup(
+                       lookup(&[("CLUSTER_REDIS_URL", "redis://127.0.0.1/"),
+                                ("CLUSTER_NODE_ID", "first"),
+                                ("CLUSTER_ADVERTISED_URL", "http://10.0.0.1:7777"),
+                                ("CLUSTER_LEASE_SECS", "5")]))
+                       .unwrap()
+                       .get_cluster());
+    }
+
+    #[test]
+    fn test_reads_schedules() {
+        assert_eq!(&vec![ScheduledTree::new(1, "0 */5 * * * *".to_owned()).unwrap(),
+                         ScheduledTree::new(2, "0 0 12 * * Mon-Fri".to_owned()).unwrap()],
+                   ServerConfig::from_lookup(lookup(&[("SCHEDULES", "1=0 */5 * * * *; 2=0 0 12 * * Mon-Fri")]))
+                       .unwrap()
+                       .get_schedules());
+        assert_eq!(Result::Err(ConfigError::InvalidValue("BUTTERCUP_SCHEDULES".to_owned(),
+                                                         "1=often".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("SCHEDULES", "1=often")])));
+    }
+
+    fn test_reads_error_rate_rollback() {
+        assert_eq!(Option::None, ServerConfig::from_lookup(lookup(&[])).unwrap().get_error_rate_rollback());
+        assert_eq!(Option::Some(ErrorRateRollback::new(20, 100)),
+                   ServerConfig::from_lookup(lookup(&[("ROLLBACK_MAX_ERROR_PERCENT", "20")]))
+                       .unwrap()
+                       .get_error_rate_rollback());
+    }
+
+    #[test]
+    fn test_rejects_zero_intervals() {
+        assert_eq!(Result::Err(ConfigError::InvalidValue("BUTTERCUP_INSTANCE_TTL_SECS".to_owned(), "0".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("INSTANCE_TTL_SECS", "0")])));
+        assert_eq!(Result::Err(ConfigError::InvalidValue("BUTTERCUP_USAGE_STATS_FLUSH_SECS".to_owned(), "0".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("USAGE_STATS_FLUSH_SECS", "0")])));
+        assert_eq!(Result::Err(ConfigError::InvalidValue("BUTTERCUP_DEFINITIONS_RELOAD_SECS".to_owned(), "0".to_owned())),
+                   ServerConfig::from_lookup(lookup(&[("DEFINITIONS_RELOAD_SECS", "0")])));
+    }
+
+}
@@ -78,8 +78,15 @@ impl EndpointService {
     }
 
     pub fn add_listener(&self,
-                        listener: Arc<dyn Fn(&HashSet<String>) + Send + Sync>) {
-        self.listeners.insert(Uuid::new_v4(), listener);
+                        listener: Listener) -> Uuid {
+        let listener_id = Uuid::new_v4();
+        self.listeners.insert(listener_id, listener);
+        listener_id
+    }
+
+    pub fn remove_listener(&self,
+                           listener_id: &Uuid) {
+        self.listeners.remove(listener_id);
     }
 
 }
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use buttercup_values::{ValueHolder, ValueType};
+use buttercup_values::extractors::{ValueExtractorInput, ValueExtractorService};
+
+use crate::ArgumentDefinition;
+use crate::extraction::{ArgumentsExtractionInput, ArgumentValueExtractorError, ArgumentValuesExtractionService};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExamplePayloads {
+
+    valid: Vec<Value>,
+    invalid: Vec<InvalidExamplePayload>,
+    unsupported_arguments: Vec<String>
+
+}
+
+impl ExamplePayloads {
+
+    pub fn get_valid(&self) -> &Vec<Value> {
+        &self.valid
+    }
+
+    pub fn get_invalid(&self) -> &Vec<InvalidExamplePayload> {
+        &self.invalid
+    }
+
+    pub fn get_unsupported_arguments(&self) -> &Vec<String> {
+        &self.unsupported_arguments
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InvalidExamplePayload {
+
+    argument_name: String,
+    payload: Value,
+    expected_error: ArgumentValueExtractorError
+
+}
+
+impl InvalidExamplePayload {
+
+    pub fn get_argument_name(&self) -> &String {
+        &self.argument_name
+    }
+
+    pub fn get_payload(&self) -> &Value {
+        &self.payload
+    }
+
+    pub fn get_expected_error(&self) -> &ArgumentValueExtractorError {
+        &self.expected_error
+    }
+
+}
+
+pub struct ExamplePayloadsGenerator;
+
+impl ExamplePayloadsGenerator {
+
+    pub fn generate(definitions: &HashMap<String, ArgumentDefinition>) -> ExamplePayloads {
+        let mut valid_payload = Map::new();
+        let mut unsupported_arguments = Vec::new();
+
+        for (name, definition) in definitions {
+            let valid_value = ExamplePayloadsGenerator::candidates(definition)
+                .0
+                .into_iter()
+                .find(|value| ExamplePayloadsGenerator::is_extractable(definition, value));
+
+            match valid_value {
+                None => unsupported_arguments.push(name.clone()),
+                Some(value) => {
+                    valid_payload.insert(name.clone(), value);
+                }
+            }
+        }
+
+        unsupported_arguments.sort();
+
+        if !unsupported_arguments.is_empty() {
+            return ExamplePayloads {
+                valid: Vec::new(),
+                invalid: Vec::new(),
+                unsupported_arguments
+            };
+        }
+
+        let mut names: Vec<&String> = definitions.keys().collect();
+        names.sort();
+
+        let mut invalid = Vec::new();
+
+        for name in names {
+            let mut without_argument = valid_payload.clone();
+            without_argument.remove(name);
+
+            let mut payloads = vec![Value::Object(without_argument)];
+
+            for candidate in ExamplePayloadsGenerator::candidates(&definitions[name]).1 {
+                let mut with_invalid_argument = valid_payload.clone();
+                with_invalid_argument.insert(name.clone(), candidate);
+
+                payloads.push(Value::Object(with_invalid_argument));
+            }
+
+            for payload in payloads {
+                if let Err(expected_error) = ArgumentValuesExtractionService::process(
+                    ArgumentsExtractionInput::new(definitions, &payload)) {
+                    invalid.push(
+                        InvalidExamplePayload {
+                            argument_name: name.clone(),
+                            payload,
+                            expected_error
+                        });
+                }
+            }
+        }
+
+        ExamplePayloads {
+            valid: vec![Value::Object(valid_payload)],
+            invalid,
+            unsupported_arguments
+        }
+    }
+
+    fn is_extractable(definition: &ArgumentDefinition,
+                      value: &Value) -> bool {
+        match ValueExtractorService::extract(
+            &ValueExtractorInput::new(
+                value,
+                definition.get_argument_type(),
+                definition.get_extraction_policy())) {
+            Ok(ValueHolder::Symbol(symbol)) =>
+                definition.get_symbols().is_empty()
+                    || definition.get_symbols().contains(symbol.get_name()),
+            Ok(_) => true,
+            Err(_) => false
+        }
+    }
+
+    fn candidates(definition: &ArgumentDefinition) -> (Vec<Value>, Vec<Value>) {
+        let (valid, invalid) = match definition.get_argument_type() {
+            ValueType::Boolean => (vec![json!(true)], vec![json!(1)]),
+            ValueType::Bytes => (vec![json!("ZXhhbXBsZQ==")], vec![json!("not base64!")]),
+            ValueType::Country => (vec![json!("POL"), json!("PL")], vec![json!("XYZ")]),
+            ValueType::DayOfWeek => (vec![json!(1)], vec![json!(9)]),
+            ValueType::Decimal => (vec![json!(1.5), json!(2)], vec![json!("one")]),
+            ValueType::Duration => (vec![json!(1000)], vec![json!(-1)]),
+            ValueType::Email => (vec![json!("john.doe@example.com")], vec![json!("john.doe")]),
+            ValueType::Flags =>
+                (vec![json!(definition.get_symbols().iter().take(1).collect::<Vec<_>>())],
+                 vec![json!(["undeclared_flag"]), json!(1)]),
+            ValueType::GeoCoordinates =>
+                (vec![json!({"lat": 52.2297, "long": 21.0122})], vec![json!({"lat": 52.2297})]),
+            ValueType::Integer => (vec![json!(42)], vec![json!("forty two")]),
+            ValueType::IpAddress => (vec![json!("127.0.0.1")], vec![json!("127.0.0")]),
+            ValueType::Language => (vec![json!("pol"), json!("pl")], vec![json!("xx")]),
+            ValueType::List => (vec![], vec![]),
+            ValueType::LocalDate => (vec![json!("2020-03-18")], vec![json!("2020-13-18")]),
+            ValueType::LocalDateTime =>
+                (vec![json!("2020-03-18T12:33:34")], vec![json!("2020-03-18 12:33")]),
+            ValueType::LocalTime => (vec![json!("12:33:34")], vec![json!("25:33:34")]),
+            ValueType::Object => (vec![json!({"name": "example"})], vec![json!(["example"])]),
+            ValueType::Quantity =>
+                (vec![json!({"amount": 12.5, "unit": "USD"}), json!("12.5 USD")],
+                 vec![json!({"amount": 12.5, "unit": "dollars"})]),
+            ValueType::String => (vec![json!("example")], vec![json!(1)]),
+            ValueType::Symbol if definition.get_symbols().is_empty() =>
+                (vec![json!("EXAMPLE")], vec![json!("not a symbol")]),
+            ValueType::Symbol =>
+                (definition.get_symbols().iter().map(|symbol| json!(symbol)).collect(),
+                 vec![json!("undeclared_symbol"), json!("not a symbol")]),
+            ValueType::TimeZone => (vec![json!("Europe/Warsaw")], vec![json!("Europe/Nowhere")]),
+            ValueType::Uuid =>
+                (vec![json!("67e55044-10b1-426f-9247-bb680e5fe0c8")], vec![json!("67e55044")]),
+            ValueType::ZonedDateTime =>
+                (vec![json!({"date_time": "2020-03-18T12:33:34", "zone": "Europe/Warsaw"})],
+                 vec![json!({"date_time": "2020-03-18T12:33:34"})])
+        };
+
+        let mut invalid = invalid;
+        invalid.push(Value::Null);
+
+        (valid, invalid)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use buttercup_values::extractors::ValueExtractionPolicy;
+
+    use crate::ArgumentsExtractor;
+
+    use super::*;
+
+    fn definitions() -> HashMap<String, ArgumentDefinition> {
+        let mut definitions = HashMap::new();
+
+        for (id, (name, argument_type, policy)) in vec![
+            ("active", ValueType::Boolean, ValueExtractionPolicy::Strict),
+            ("country", ValueType::Country, ValueExtractionPolicy::Lax),
+            ("location", ValueType::GeoCoordinates, ValueExtractionPolicy::Strict),
+            ("name", ValueType::String, ValueExtractionPolicy::Lax),
+            ("since", ValueType::ZonedDateTime, ValueExtractionPolicy::Strict)
+        ].into_iter().enumerate() {
+            definitions.insert(
+                name.to_owned(),
+                ArgumentDefinition::new(id as i32, name.to_owned(), argument_type, policy, 1));
+        }
+
+        definitions
+    }
+
+    #[test]
+    fn test_generated_examples_match_extraction() {
+        let definitions = definitions();
+        let examples = ExamplePayloadsGenerator::generate(&definitions);
+        let extractor = ArgumentsExtractor::new(definitions);
+
+        assert!(examples.get_unsupported_arguments().is_empty());
+        assert_eq!(1, examples.get_valid().len());
+
+        for payload in examples.get_valid() {
+            assert!(extractor.extract(payload).is_ok());
+        }
+
+        assert!(!examples.get_invalid().is_empty());
+
+        for example in examples.get_invalid() {
+            assert!(extractor.extract(example.get_payload()).is_err());
+        }
+
+        assert!(examples.get_invalid()
+            .iter()
+            .any(|example| example.get_argument_name() == "name"
+                && matches!(example.get_expected_error(),
+                            ArgumentValueExtractorError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_reports_arguments_without_examples() {
+        let mut definitions = definitions();
+        definitions.insert(
+            "tags".to_owned(),
+            ArgumentDefinition::new(10,
+                                    "tags".to_owned(),
+                                    ValueType::List,
+                                    ValueExtractionPolicy::Strict,
+                                    1));
+
+        let examples = ExamplePayloadsGenerator::generate(&definitions);
+
+        assert_eq!(&vec!["tags".to_owned()], examples.get_unsupported_arguments());
+        assert!(examples.get_valid().is_empty());
+    }
+
+}
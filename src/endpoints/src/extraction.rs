@@ -1,11 +1,14 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use buttercup_values::{ValueHolder, ValuesPayload};
-use buttercup_values::extractors::{ValueExtractionError, ValueExtractorInput, ValueExtractorService};
+use buttercup_values::{ValueHolder, ValuesPayload, ValueType};
+use buttercup_values::extractors::{ValueExtractionError, ValueExtractionPolicy, ValueExtractorInput, ValueExtractorService};
+use buttercup_values::flags::Flags;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
-use crate::ArgumentDefinition;
+use crate::{ArgumentDefinition, ArgumentLimits, MissingArgumentPolicy, OversizePolicy};
 
 pub struct ArgumentsExtractionInput<'a> {
 
@@ -30,8 +33,21 @@ impl<'a> ArgumentsExtractionInput<'a> {
 pub enum ArgumentValueExtractorError {
 
     MissingArgument(String),
-    ExtractionFailure(String, Value, ValueExtractionError),
-    InvalidJsonInput
+    ExtractionFailure(String, Box<Value>, ValueExtractionError),
+    InvalidJsonInput,
+    NotAnObject(String),
+    ArgumentTooLarge(String),
+    PayloadTooLarge(usize),
+    UnknownSymbol(String, String)
+
+}
+
+// Missing and scalar segments are given by their paths, e.g. user.address.
+enum Lookup<'a> {
+
+    Found(&'a Value),
+    Missing(String),
+    NotAnObject(String)
 
 }
 
@@ -53,19 +69,37 @@ impl ArgumentValuesExtractionService {
                   -> Result<ValuesPayload, ArgumentValueExtractorError> {
         let mut response: HashMap<String, ValueHolder> = HashMap::new();
         for (name, description) in definitions.iter() {
-            let opt_argument = payload.get(name);
-            match opt_argument {
-                None => return Result::Err(
+            match ArgumentValuesExtractionService::lookup(payload, name) {
+                Lookup::NotAnObject(path) =>
+                    return Result::Err(ArgumentValueExtractorError::NotAnObject(path)),
+                Lookup::Missing(path)
+                if *description.get_missing_policy_of(&path) == MissingArgumentPolicy::Skip => continue,
+                Lookup::Missing(_) => return Result::Err(
                     ArgumentValueExtractorError::MissingArgument(name.clone())),
-                Some(value) => {
-                    match ArgumentValuesExtractionService::handle(description, value) {
-                        Ok(holder) => {
+                Lookup::Found(value) => {
+                    let value = ArgumentSanitizer::sanitize(description.get_limits(), value)
+                        .ok_or_else(|| ArgumentValueExtractorError::ArgumentTooLarge(name.clone()))?;
+
+                    let extracted = ArgumentValuesExtractionService::handle(description, &value)
+                        .map(|holder| ArgumentSanitizer::sanitize_holder(description.get_limits(), holder));
+
+                    match extracted {
+                        Ok(Option::None) =>
+                            return Result::Err(
+                                ArgumentValueExtractorError::ArgumentTooLarge(name.clone())),
+                        Ok(Option::Some(ValueHolder::Symbol(symbol)))
+                        if !description.get_symbols().is_empty()
+                            && !description.get_symbols().contains(symbol.get_name()) =>
+                            return Result::Err(
+                                ArgumentValueExtractorError::UnknownSymbol(
+                                    name.clone(), symbol.get_name().clone())),
+                        Ok(Option::Some(holder)) => {
                             response.insert(name.clone(), holder);
                         },
                         Err(error) =>
                             return Result::Err(
                                 ArgumentValueExtractorError::ExtractionFailure(
-                                    name.clone(), value.clone(), error)),
+                                    name.clone(), Box::new(value.into_owned()), error)),
                     }
                 },
             }
@@ -73,14 +107,403 @@ impl ArgumentValuesExtractionService {
         return Result::Ok(ValuesPayload::new(response));
     }
 
+    // Names are looked up as they are first, then as dotted paths into nested objects, so
+    // that user.address.city can be sent either flat or nested. Every segment but the last
+    // one has to be an object, if it is there at all.
+    fn lookup<'a>(payload: &'a Map<String, Value>,
+                  name: &str) -> Lookup<'a> {
+        if let Some(value) = payload.get(name) {
+            return Lookup::Found(value);
+        }
+
+        let mut entries = payload;
+        let mut path_length = 0;
+        let mut segments = name.split('.').peekable();
+
+        while let Some(segment) = segments.next() {
+            path_length += segment.len();
+            let value = match entries.get(segment) {
+                None => return Lookup::Missing(name[..path_length].to_owned()),
+                Some(value) => value
+            };
+
+            if segments.peek().is_none() {
+                return Lookup::Found(value);
+            }
+
+            match value {
+                Value::Object(nested) => entries = nested,
+                _ => return Lookup::NotAnObject(name[..path_length].to_owned())
+            }
+            path_length += 1;
+        }
+
+        Lookup::Missing(name.to_owned())
+    }
+
     fn handle(definition: &ArgumentDefinition,
               value: &Value)
               -> Result<ValueHolder, ValueExtractionError> {
-        ValueExtractorService::extract(
-            &ValueExtractorInput::new(
-                value,
-                definition.get_argument_type(),
-                definition.get_extraction_policy()))
+        let policy = definition.get_extraction_policy();
+        let declared = definition.get_symbols();
+
+        if *definition.get_argument_type() == ValueType::Flags && !declared.is_empty() {
+            let flags = match (value, policy) {
+                (Value::Number(bits), ValueExtractionPolicy::Lax) => match bits.as_u64() {
+                    Some(bits) => Flags::new(Arc::new(declared.clone()), bits),
+                    None => return Result::Err(
+                        ValueExtractionError::InvalidValueTypeError(policy.clone()))
+                },
+                _ => match ValueExtractorService::extract(
+                    &ValueExtractorInput::new(value, &ValueType::Flags, policy))? {
+                    ValueHolder::Flags(flags) => Flags::from_set_flags(
+                        Arc::new(declared.clone()), &flags.get_set_flags()),
+                    _ => return Result::Err(
+                        ValueExtractionError::InvalidValueTypeError(policy.clone()))
+                }
+            };
+            return flags
+                .map(ValueHolder::Flags)
+                .map_err(|err| ValueExtractionError::FlagsValueError(policy.clone(), err));
+        }
+
+        let input = ValueExtractorInput::new(value, definition.get_argument_type(), policy);
+
+        match definition.get_limits().get_max_number_digits() {
+            Some(max_number_digits) =>
+                ValueExtractorService::extract(&input.with_max_number_digits(*max_number_digits)),
+            None => ValueExtractorService::extract(&input)
+        }
+    }
+
+}
+
+struct ArgumentSanitizer;
+
+impl ArgumentSanitizer {
+
+    fn sanitize<'a>(limits: &ArgumentLimits,
+                    value: &'a Value) -> Option<Cow<'a, Value>> {
+        if limits.is_unlimited() {
+            return Option::Some(Cow::Borrowed(value));
+        }
+
+        ArgumentSanitizer::do_sanitize(limits, value).map(Cow::Owned)
+    }
+
+    fn sanitize_holder(limits: &ArgumentLimits,
+                       holder: ValueHolder) -> Option<ValueHolder> {
+        match (holder, limits.get_max_bytes_length()) {
+            (ValueHolder::Bytes(bytes), Some(max_length)) if bytes.len() > *max_length =>
+                match limits.get_oversize_policy() {
+                    OversizePolicy::Reject => Option::None,
+                    OversizePolicy::Truncate =>
+                        Option::Some(ValueHolder::Bytes(Arc::new(bytes[..*max_length].to_vec())))
+                },
+            (holder, _) => Option::Some(holder)
+        }
+    }
+
+    fn do_sanitize(limits: &ArgumentLimits,
+                   value: &Value) -> Option<Value> {
+        match value {
+            Value::String(str_val) => match limits.get_max_string_length() {
+                Some(max_length) if str_val.chars().count() > *max_length =>
+                    match limits.get_oversize_policy() {
+                        OversizePolicy::Reject => Option::None,
+                        OversizePolicy::Truncate =>
+                            Option::Some(Value::String(str_val.chars().take(*max_length).collect()))
+                    },
+                _ => Option::Some(value.clone())
+            },
+            Value::Array(elements) => {
+                let elements = match limits.get_max_array_size() {
+                    Some(max_size) if elements.len() > *max_size =>
+                        match limits.get_oversize_policy() {
+                            OversizePolicy::Reject => return Option::None,
+                            OversizePolicy::Truncate => &elements[..*max_size]
+                        },
+                    _ => &elements[..]
+                };
+
+                let mut sanitized = Vec::with_capacity(elements.len());
+                for element in elements {
+                    sanitized.push(ArgumentSanitizer::do_sanitize(limits, element)?);
+                }
+
+                Option::Some(Value::Array(sanitized))
+            },
+            Value::Object(entries) => {
+                let mut sanitized = Map::new();
+                for (key, entry) in entries {
+                    sanitized.insert(key.clone(), ArgumentSanitizer::do_sanitize(limits, entry)?);
+                }
+
+                Option::Some(Value::Object(sanitized))
+            },
+            _ => Option::Some(value.clone())
+        }
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use buttercup_values::flags::FlagsValueError;
+
+    use crate::ArgumentsExtractor;
+
+    use super::*;
+
+    fn extractor(argument_type: ValueType,
+                 limits: ArgumentLimits) -> ArgumentsExtractor {
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "arg".to_owned(),
+            ArgumentDefinition::new(1,
+                                    "arg".to_owned(),
+                                    argument_type,
+                                    ValueExtractionPolicy::Lax,
+                                    1)
+                .with_limits(limits));
+
+        ArgumentsExtractor::new(definitions)
+    }
+
+    #[test]
+    fn test_truncates_oversized_strings() {
+        let payload = extractor(ValueType::String,
+                                ArgumentLimits::new(Option::Some(4), Option::None, OversizePolicy::Truncate))
+            .extract(&json!({"arg": "żółwik"}))
+            .unwrap();
+
+        assert_eq!(Option::Some(&ValueHolder::from("żółw".to_owned())),
+                   payload.get(&"arg".to_owned()));
+    }
+
+    #[test]
+    fn test_rejects_oversized_arrays() {
+        let result = extractor(ValueType::String,
+                               ArgumentLimits::new(Option::None, Option::Some(2), OversizePolicy::Reject))
+            .extract(&json!({"arg": [1, 2, 3]}));
+
+        match result {
+            Err(ArgumentValueExtractorError::ArgumentTooLarge(name)) => assert_eq!("arg", name),
+            _ => panic!("Expected argument to be rejected.")
+        }
+    }
+
+    #[test]
+    fn test_accepts_only_declared_symbols() {
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "status".to_owned(),
+            ArgumentDefinition::new(1,
+                                    "status".to_owned(),
+                                    ValueType::Symbol,
+                                    ValueExtractionPolicy::Strict,
+                                    1)
+                .with_symbols(vec!["ACTIVE".to_owned(), "SUSPENDED".to_owned()]));
+        let extractor = ArgumentsExtractor::new(definitions);
+
+        assert!(extractor.extract(&json!({"status": "ACTIVE"})).is_ok());
+
+        match extractor.extract(&json!({"status": "DELETED"})) {
+            Err(ArgumentValueExtractorError::UnknownSymbol(name, symbol)) => {
+                assert_eq!("status", name);
+                assert_eq!("DELETED", symbol);
+            },
+            _ => panic!("Expected undeclared symbol to be rejected.")
+        }
+    }
+
+    #[test]
+    fn test_extracts_declared_flags() {
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "permissions".to_owned(),
+            ArgumentDefinition::new(1,
+                                    "permissions".to_owned(),
+                                    ValueType::Flags,
+                                    ValueExtractionPolicy::Lax,
+                                    1)
+                .with_symbols(vec!["read".to_owned(), "write".to_owned(), "admin".to_owned()]));
+        let extractor = ArgumentsExtractor::new(definitions);
+
+        for payload in &[json!({"permissions": ["admin", "read"]}), json!({"permissions": 5})] {
+            match extractor.extract(payload).unwrap().get(&"permissions".to_owned()) {
+                Some(ValueHolder::Flags(flags)) => assert_eq!(5, flags.get_bits()),
+                other => panic!("Unexpected value: {:?}", other)
+            }
+        }
+
+        match extractor.extract(&json!({"permissions": ["owner"]})) {
+            Err(ArgumentValueExtractorError::ExtractionFailure(
+                    _, _, ValueExtractionError::FlagsValueError(_, FlagsValueError::UnknownFlag(flag)))) =>
+                assert_eq!("owner", flag),
+            _ => panic!("Expected undeclared flag to be rejected.")
+        }
+    }
+
+    #[test]
+    fn test_rejects_flags_with_more_symbols_than_bits() {
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "permissions".to_owned(),
+            ArgumentDefinition::new(1,
+                                    "permissions".to_owned(),
+                                    ValueType::Flags,
+                                    ValueExtractionPolicy::Lax,
+                                    1)
+                .with_symbols((0..65).map(|position| format!("flag{}", position)).collect()));
+        let extractor = ArgumentsExtractor::new(definitions);
+
+        for payload in &[json!({"permissions": ["flag64"]}), json!({"permissions": 1})] {
+            match extractor.extract(payload) {
+                Err(ArgumentValueExtractorError::ExtractionFailure(
+                        _, _, ValueExtractionError::FlagsValueError(_, FlagsValueError::TooManyFlags(count)))) =>
+                    assert_eq!(65, count),
+                other => panic!("Unexpected result: {:?}", other)
+            }
+        }
+    }
+
+    #[test]
+    fn test_limits_decoded_bytes() {
+        let limits = ArgumentLimits::new(Option::None, Option::None, OversizePolicy::Truncate)
+            .with_max_bytes_length(2);
+        let payload = extractor(ValueType::Bytes, limits)
+            .extract(&json!({"arg": "AQID"}))
+            .unwrap();
+
+        assert_eq!(Option::Some(&ValueHolder::Bytes(Arc::new(vec![1, 2]))),
+                   payload.get(&"arg".to_owned()));
+
+        let limits = ArgumentLimits::new(Option::None, Option::None, OversizePolicy::Reject)
+            .with_max_bytes_length(2);
+
+        match extractor(ValueType::Bytes, limits).extract(&json!({"arg": "AQID"})) {
+            Err(ArgumentValueExtractorError::ArgumentTooLarge(name)) => assert_eq!("arg", name),
+            _ => panic!("Expected argument to be rejected.")
+        }
+    }
+
+    #[test]
+    fn test_limits_number_digits() {
+        let limits = ArgumentLimits::default().with_max_number_digits(5);
+
+        assert!(extractor(ValueType::Integer, limits.clone()).extract(&json!({"arg": "12345"})).is_ok());
+
+        match extractor(ValueType::Integer, limits).extract(&json!({"arg": "123456"})) {
+            Err(ArgumentValueExtractorError::ExtractionFailure(
+                    _, _, ValueExtractionError::NumberTooLong(_, max_number_digits))) =>
+                assert_eq!(5, max_number_digits),
+            _ => panic!("Expected number to be rejected.")
+        }
+    }
+
+    #[test]
+    fn test_extracts_dotted_paths_from_nested_objects() {
+        let mut definitions = HashMap::new();
+        for (id, name) in [(1, "user.address.city"), (2, "user.name"), (3, "user.age")].iter() {
+            definitions.insert(
+                (*name).to_owned(),
+                ArgumentDefinition::new(*id,
+                                        (*name).to_owned(),
+                                        ValueType::String,
+                                        ValueExtractionPolicy::Lax,
+                                        1));
+        }
+        definitions.insert(
+            "user.nickname".to_owned(),
+            ArgumentDefinition::new(4,
+                                    "user.nickname".to_owned(),
+                                    ValueType::String,
+                                    ValueExtractionPolicy::Lax,
+                                    1)
+                .with_missing_policy(MissingArgumentPolicy::Skip));
+        let extractor = ArgumentsExtractor::new(definitions);
+
+        let payload = extractor
+            .extract(&json!({"user": {"address": {"city": "Warsaw"}, "name": "Alice"}, "user.age": "30"}))
+            .unwrap();
+
+        assert_eq!(Option::Some(&ValueHolder::from("Warsaw".to_owned())),
+                   payload.get(&"user.address.city".to_owned()));
+        assert_eq!(Option::Some(&ValueHolder::from("Alice".to_owned())),
+                   payload.get(&"user.name".to_owned()));
+        assert_eq!(Option::Some(&ValueHolder::from("30".to_owned())),
+                   payload.get(&"user.age".to_owned()));
+        assert_eq!(3, payload.get_keys().len());
+
+        match extractor.extract(&json!({"user": {"address": {}, "name": "Alice", "age": "30"}})) {
+            Err(ArgumentValueExtractorError::MissingArgument(name)) => assert_eq!("user.address.city", name),
+            _ => panic!("Expected missing argument to be rejected.")
+        }
+
+        match extractor.extract(&json!({"user": {"address": "Warsaw", "name": "Alice", "age": "30"}})) {
+            Err(ArgumentValueExtractorError::NotAnObject(path)) => assert_eq!("user.address", path),
+            _ => panic!("Expected scalar segment to be rejected.")
+        }
+    }
+
+    #[test]
+    fn test_applies_missing_policies_per_segment() {
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "user.address.city".to_owned(),
+            ArgumentDefinition::new(1,
+                                    "user.address.city".to_owned(),
+                                    ValueType::String,
+                                    ValueExtractionPolicy::Lax,
+                                    1)
+                .with_segment_missing_policy("user.address".to_owned(), MissingArgumentPolicy::Skip));
+        let extractor = ArgumentsExtractor::new(definitions);
+
+        assert_eq!(0, extractor.extract(&json!({"user": {"name": "Alice"}})).unwrap().get_keys().len());
+
+        match extractor.extract(&json!({"guest": {"name": "Alice"}})) {
+            Err(ArgumentValueExtractorError::MissingArgument(name)) => assert_eq!("user.address.city", name),
+            _ => panic!("Expected missing user to be rejected.")
+        }
+
+        match extractor.extract(&json!({"user": {"address": {"street": "Mokotowska"}}})) {
+            Err(ArgumentValueExtractorError::MissingArgument(name)) => assert_eq!("user.address.city", name),
+            _ => panic!("Expected missing city to be rejected.")
+        }
+    }
+
+    #[test]
+    fn test_extracts_nested_objects() {
+        let extractor = extractor(ValueType::Object, ArgumentLimits::default());
+
+        match extractor.extract(&json!({"arg": {"address": {"city": "Warsaw"}, "age": 30}})) {
+            Ok(payload) => match payload.get(&"arg".to_owned()) {
+                Some(ValueHolder::Object(user)) => {
+                    assert_eq!(Option::Some(&ValueHolder::from("Warsaw")), user.get_path("address.city"));
+                    assert_eq!(2, user.get_entries().len());
+                },
+                other => panic!("Expected an object, got {:?}", other)
+            },
+            Err(err) => panic!("Expected the object to be extracted, got {:?}", err)
+        }
+
+        assert!(matches!(extractor.extract(&json!({"arg": "Warsaw"})),
+                         Err(ArgumentValueExtractorError::ExtractionFailure(..))));
+    }
+
+    #[test]
+    fn test_rejects_oversized_payloads() {
+        let extractor = extractor(ValueType::String, ArgumentLimits::default()).with_max_payload_size(20);
+
+        match extractor.extract_raw(br#"{"arg":"too long to fit"}"#) {
+            Err(ArgumentValueExtractorError::PayloadTooLarge(size)) => assert_eq!(25, size),
+            _ => panic!("Expected payload to be rejected.")
+        }
+        assert!(extractor.extract_raw(br#"{"arg":"fits"}"#).is_ok());
+        assert!(matches!(extractor.extract_raw(br#"{"arg":"#), Err(ArgumentValueExtractorError::InvalidJsonInput)));
+    }
+
+}
@@ -8,7 +8,9 @@ use serde_json::Value;
 use crate::extraction::{ArgumentsExtractionInput, ArgumentValueExtractorError, ArgumentValuesExtractionService};
 
 pub mod endpoints;
+pub mod examples;
 pub mod extraction;
+pub mod validation;
 
 pub struct ArgumentSetDefinition {
 
@@ -18,14 +20,26 @@ pub struct ArgumentSetDefinition {
 
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ArgumentDefinition {
 
     id: i32,
     name: String,
     argument_type: ValueType,
     extraction_policy: ValueExtractionPolicy,
-    argument_set_definition_id: i32
+    argument_set_definition_id: i32,
+
+    #[serde(default)]
+    limits: ArgumentLimits,
+
+    #[serde(default)]
+    symbols: Vec<String>,
+
+    #[serde(default)]
+    missing_policy: MissingArgumentPolicy,
+
+    #[serde(default)]
+    segment_missing_policies: HashMap<String, MissingArgumentPolicy>
 
 }
 
@@ -41,10 +55,41 @@ impl ArgumentDefinition {
             name,
             argument_type,
             extraction_policy,
-            argument_set_definition_id
+            argument_set_definition_id,
+            limits: ArgumentLimits::default(),
+            symbols: Vec::new(),
+            missing_policy: MissingArgumentPolicy::default(),
+            segment_missing_policies: HashMap::new()
         }
     }
 
+    pub fn with_limits(mut self,
+                       limits: ArgumentLimits) -> ArgumentDefinition {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_symbols(mut self,
+                        symbols: Vec<String>) -> ArgumentDefinition {
+        self.symbols = symbols;
+        self
+    }
+
+    pub fn with_missing_policy(mut self,
+                               missing_policy: MissingArgumentPolicy) -> ArgumentDefinition {
+        self.missing_policy = missing_policy;
+        self
+    }
+
+    // Overrides the missing policy when the segment of the given path, such as user.address
+    // of user.address.city, is the one missing.
+    pub fn with_segment_missing_policy(mut self,
+                                       segment_path: String,
+                                       missing_policy: MissingArgumentPolicy) -> ArgumentDefinition {
+        self.segment_missing_policies.insert(segment_path, missing_policy);
+        self
+    }
+
     pub fn get_name(&self) -> &String {
         &self.name
     }
@@ -61,12 +106,135 @@ impl ArgumentDefinition {
         &self.argument_set_definition_id
     }
 
+    pub fn get_limits(&self) -> &ArgumentLimits {
+        &self.limits
+    }
+
+    pub fn get_symbols(&self) -> &Vec<String> {
+        &self.symbols
+    }
+
+    pub fn get_missing_policy(&self) -> &MissingArgumentPolicy {
+        &self.missing_policy
+    }
+
+    pub fn get_segment_missing_policies(&self) -> &HashMap<String, MissingArgumentPolicy> {
+        &self.segment_missing_policies
+    }
+
+    pub fn get_missing_policy_of(&self,
+                                 segment_path: &str) -> &MissingArgumentPolicy {
+        self.segment_missing_policies
+            .get(segment_path)
+            .unwrap_or(&self.missing_policy)
+    }
+
+    // Whether the argument may be left out of the extracted values, at any segment.
+    pub fn may_be_missing(&self) -> bool {
+        self.missing_policy == MissingArgumentPolicy::Skip
+            || self.segment_missing_policies.values().any(|policy| *policy == MissingArgumentPolicy::Skip)
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct ArgumentLimits {
+
+    max_string_length: Option<usize>,
+    max_array_size: Option<usize>,
+    oversize_policy: OversizePolicy,
+
+    #[serde(default)]
+    max_bytes_length: Option<usize>,
+
+    #[serde(default)]
+    max_number_digits: Option<usize>
+
+}
+
+impl ArgumentLimits {
+
+    pub fn new(max_string_length: Option<usize>,
+               max_array_size: Option<usize>,
+               oversize_policy: OversizePolicy) -> ArgumentLimits {
+        ArgumentLimits {
+            max_string_length,
+            max_array_size,
+            oversize_policy,
+            max_bytes_length: Option::None,
+            max_number_digits: Option::None
+        }
+    }
+
+    pub fn with_max_bytes_length(mut self,
+                                 max_bytes_length: usize) -> ArgumentLimits {
+        self.max_bytes_length = Option::Some(max_bytes_length);
+        self
+    }
+
+    // Overrides the default limit of digits of numbers given as strings, longer ones are
+    // rejected whatever the oversize policy.
+    pub fn with_max_number_digits(mut self,
+                                  max_number_digits: usize) -> ArgumentLimits {
+        self.max_number_digits = Option::Some(max_number_digits);
+        self
+    }
+
+    pub fn get_max_string_length(&self) -> &Option<usize> {
+        &self.max_string_length
+    }
+
+    pub fn get_max_array_size(&self) -> &Option<usize> {
+        &self.max_array_size
+    }
+
+    pub fn get_oversize_policy(&self) -> &OversizePolicy {
+        &self.oversize_policy
+    }
+
+    pub fn get_max_bytes_length(&self) -> &Option<usize> {
+        &self.max_bytes_length
+    }
+
+    pub fn get_max_number_digits(&self) -> &Option<usize> {
+        &self.max_number_digits
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_string_length.is_none()
+            && self.max_array_size.is_none()
+            && self.max_bytes_length.is_none()
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub enum OversizePolicy {
+
+    #[default]
+    Reject,
+    Truncate
+
+}
+
+// Applies to arguments missing at any segment of their dotted paths unless the segment has
+// a policy of its own, skipped arguments are left out of the extracted values.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub enum MissingArgumentPolicy {
+
+    #[default]
+    Reject,
+    Skip
+
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct ArgumentsExtractor {
 
-    argument_definitions: HashMap<String, ArgumentDefinition>
+    argument_definitions: HashMap<String, ArgumentDefinition>,
+
+    #[serde(default)]
+    max_payload_size: Option<usize>
 
 }
 
@@ -74,10 +242,39 @@ impl ArgumentsExtractor {
 
     pub fn new(argument_definitions: HashMap<String, ArgumentDefinition>) -> ArgumentsExtractor {
         ArgumentsExtractor {
-            argument_definitions
+            argument_definitions,
+            max_payload_size: Option::None
         }
     }
 
+    pub fn with_max_payload_size(mut self,
+                                 max_payload_size: usize) -> ArgumentsExtractor {
+        self.max_payload_size = Option::Some(max_payload_size);
+        self
+    }
+
+    pub fn get_declared_symbols(&self) -> HashMap<String, Vec<String>> {
+        self.argument_definitions.iter()
+            .filter(|(_, definition)| !definition.symbols.is_empty())
+            .map(|(name, definition)| (name.clone(), definition.symbols.clone()))
+            .collect()
+    }
+
+    // Checks the size of the body before parsing it, payloads which were already parsed
+    // are given to extract.
+    pub fn extract_raw(&self, body: &[u8]) -> Result<ValuesPayload, ArgumentValueExtractorError> {
+        if let Some(max_payload_size) = self.max_payload_size {
+            if body.len() > max_payload_size {
+                return Result::Err(ArgumentValueExtractorError::PayloadTooLarge(body.len()));
+            }
+        }
+
+        let payload = serde_json::from_slice(body)
+            .map_err(|_| ArgumentValueExtractorError::InvalidJsonInput)?;
+
+        self.extract(&payload)
+    }
+
     pub fn extract(&self, payload: &Value) -> Result<ValuesPayload, ArgumentValueExtractorError> {
         ArgumentValuesExtractionService::process(
             ArgumentsExtractionInput::new(
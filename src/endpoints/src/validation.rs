@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use buttercup_values::{ValueHolder, ValuesPayload, ValueType};
+use buttercup_values::flags::MAX_FLAGS;
+use serde::{Deserialize, Serialize};
+
+use crate::ArgumentDefinition;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ArgumentViolation {
+
+    ArgumentTooLarge(String),
+    MissingArgument(String),
+    TypeMismatch(String, ValueType),
+    UnknownSymbol(String, String),
+    TooManySymbols(String, usize)
+
+}
+
+// Validates values which were already extracted, e.g. the ones found on a blackboard.
+// Oversized values are always reported, since there is nothing left to truncate.
+pub struct ArgumentValidator;
+
+impl ArgumentValidator {
+
+    pub fn validate(definitions: &HashMap<String, ArgumentDefinition>,
+                    payload: &ValuesPayload) -> Vec<ArgumentViolation> {
+        let mut names: Vec<&String> = definitions.keys().collect();
+        names.sort();
+
+        names.into_iter()
+            .filter_map(|name|
+                ArgumentValidator::validate_value(name, &definitions[name], payload.get(name)))
+            .collect()
+    }
+
+    fn validate_value(name: &str,
+                      definition: &ArgumentDefinition,
+                      value: Option<&ValueHolder>) -> Option<ArgumentViolation> {
+        // Each symbol of a flags argument takes one bit of a u64.
+        if definition.get_argument_type() == &ValueType::Flags && definition.get_symbols().len() > MAX_FLAGS {
+            return Option::Some(
+                ArgumentViolation::TooManySymbols(name.to_owned(), definition.get_symbols().len()));
+        }
+
+        let value = match value {
+            Option::None if definition.may_be_missing() => return Option::None,
+            Option::None => return Option::Some(ArgumentViolation::MissingArgument(name.to_owned())),
+            Option::Some(value) => value
+        };
+
+        if !definition.get_argument_type().matches(value) {
+            return Option::Some(
+                ArgumentViolation::TypeMismatch(
+                    name.to_owned(), definition.get_argument_type().clone()));
+        }
+
+        let limits = definition.get_limits();
+        let max_length = match value {
+            ValueHolder::Bytes(_) => limits.get_max_bytes_length(),
+            ValueHolder::List(_) => limits.get_max_array_size(),
+            ValueHolder::String(_) => limits.get_max_string_length(),
+            _ => &Option::None
+        };
+
+        match (value, max_length, value.get_length()) {
+            (_, Option::Some(max_length), Option::Some(length)) if length > *max_length =>
+                Option::Some(ArgumentViolation::ArgumentTooLarge(name.to_owned())),
+            (ValueHolder::Symbol(symbol), _, _)
+            if !definition.get_symbols().is_empty()
+                && !definition.get_symbols().contains(symbol.get_name()) =>
+                Option::Some(
+                    ArgumentViolation::UnknownSymbol(name.to_owned(), symbol.get_name().clone())),
+            (_, _, _) => Option::None
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use buttercup_values::extractors::ValueExtractionPolicy;
+    use buttercup_values::symbol::Symbol;
+
+    use crate::{ArgumentLimits, OversizePolicy};
+
+    use super::*;
+
+    fn definitions() -> HashMap<String, ArgumentDefinition> {
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "name".to_owned(),
+            ArgumentDefinition::new(1, "name".to_owned(), ValueType::String, ValueExtractionPolicy::Strict, 1)
+                .with_limits(ArgumentLimits::new(Option::Some(3), Option::None, OversizePolicy::Truncate)));
+        definitions.insert(
+            "adult".to_owned(),
+            ArgumentDefinition::new(2, "adult".to_owned(), ValueType::Boolean, ValueExtractionPolicy::Strict, 1));
+        definitions.insert(
+            "tier".to_owned(),
+            ArgumentDefinition::new(3, "tier".to_owned(), ValueType::Symbol, ValueExtractionPolicy::Strict, 1)
+                .with_symbols(vec!["GOLD".to_owned()]));
+        definitions
+    }
+
+    #[test]
+    fn test_accepts_valid_values() {
+        let mut values = HashMap::new();
+        values.insert("name".to_owned(), ValueHolder::String(Arc::new("Ann".to_owned())));
+        values.insert("adult".to_owned(), ValueHolder::Boolean(true));
+        values.insert("tier".to_owned(), ValueHolder::Symbol(Symbol::new("GOLD").unwrap()));
+
+        assert!(ArgumentValidator::validate(&definitions(), &ValuesPayload::new(values)).is_empty());
+    }
+
+    #[test]
+    fn test_reports_all_violations() {
+        let mut values = HashMap::new();
+        values.insert("name".to_owned(), ValueHolder::String(Arc::new("Annabel".to_owned())));
+        values.insert("tier".to_owned(), ValueHolder::Symbol(Symbol::new("SILVER").unwrap()));
+
+        assert_eq!(vec![
+            ArgumentViolation::MissingArgument("adult".to_owned()),
+            ArgumentViolation::ArgumentTooLarge("name".to_owned()),
+            ArgumentViolation::UnknownSymbol("tier".to_owned(), "SILVER".to_owned())],
+                   ArgumentValidator::validate(&definitions(), &ValuesPayload::new(values)));
+
+        assert_eq!(vec![
+            ArgumentViolation::MissingArgument("adult".to_owned()),
+            ArgumentViolation::TypeMismatch("name".to_owned(), ValueType::String),
+            ArgumentViolation::MissingArgument("tier".to_owned())],
+                   ArgumentValidator::validate(
+                       &definitions(),
+                       &ValuesPayload::singleton("name".to_owned(), ValueHolder::Uuid(Default::default()))));
+    }
+
+    #[test]
+    fn test_reports_flags_with_more_symbols_than_bits() {
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "permissions".to_owned(),
+            ArgumentDefinition::new(1, "permissions".to_owned(), ValueType::Flags, ValueExtractionPolicy::Strict, 1)
+                .with_symbols((0..65).map(|position| format!("flag{}", position)).collect()));
+
+        assert_eq!(vec![ArgumentViolation::TooManySymbols("permissions".to_owned(), 65)],
+                   ArgumentValidator::validate(&definitions, &ValuesPayload::new(HashMap::new())));
+    }
+
+}
@@ -0,0 +1,28 @@
+#ifndef BUTTERCUP_H
+#define BUTTERCUP_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct ButtercupEngine ButtercupEngine;
+
+ButtercupEngine *buttercup_engine_new(void);
+
+void buttercup_engine_free(ButtercupEngine *engine);
+
+/* Returns {"Ok": tree_id} or {"Err": "..."}, release it with buttercup_string_free. */
+char *buttercup_load_definition(const ButtercupEngine *engine, const char *json, uint32_t version);
+
+/* Returns {"Ok": "Success" | "Failure"} or {"Err": "..."}, release it with buttercup_string_free. */
+char *buttercup_evaluate(const ButtercupEngine *engine, int32_t tree_id, const char *payload);
+
+void buttercup_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,148 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+use futures::executor::block_on;
+use serde::Serialize;
+
+use buttercup_api::document::parse_payload;
+use buttercup_api::engine::ButtercupEngine;
+
+// Every call returns a json document, {"Ok": ...} or {"Err": "..."}, which the caller
+// owns and releases with buttercup_string_free.
+#[derive(Serialize)]
+enum FfiResult<T: Serialize> {
+
+    Ok(T),
+    Err(String)
+
+}
+
+impl<T: Serialize> From<Result<T, String>> for FfiResult<T> {
+    fn from(result: Result<T, String>) -> Self {
+        match result {
+            Ok(value) => FfiResult::Ok(value),
+            Err(err) => FfiResult::Err(err)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn buttercup_engine_new() -> *mut ButtercupEngine {
+    Box::into_raw(Box::new(ButtercupEngine::default()))
+}
+
+/// # Safety
+///
+/// The engine has to come from buttercup_engine_new and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn buttercup_engine_free(engine: *mut ButtercupEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// # Safety
+///
+/// The engine has to come from buttercup_engine_new, json has to be a nul terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn buttercup_load_definition(engine: *const ButtercupEngine,
+                                                   json: *const c_char,
+                                                   version: u32) -> *mut c_char {
+    to_c_string(
+        get_engine(engine)
+            .and_then(|engine| {
+                let json = to_str(json)?;
+                block_on(engine.load_definition(json, version))
+                    .map_err(|err| format!("{:?}", err))
+            }))
+}
+
+/// # Safety
+///
+/// The engine has to come from buttercup_engine_new, payload has to be a nul terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn buttercup_evaluate(engine: *const ButtercupEngine,
+                                            tree_id: i32,
+                                            payload: *const c_char) -> *mut c_char {
+    to_c_string(
+        get_engine(engine)
+            .and_then(|engine| {
+                let payload = parse_payload(to_str(payload)?)
+                    .map_err(|err| format!("{:?}", err))?;
+                block_on(engine.evaluate(&tree_id, &payload))
+                    .map_err(|err| format!("{:?}", err))
+            }))
+}
+
+/// # Safety
+///
+/// The string has to come from one of the buttercup functions and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn buttercup_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+unsafe fn get_engine<'a>(engine: *const ButtercupEngine) -> Result<&'a ButtercupEngine, String> {
+    engine.as_ref().ok_or_else(|| "engine is null".to_owned())
+}
+
+unsafe fn to_str<'a>(string: *const c_char) -> Result<&'a str, String> {
+    if string.is_null() {
+        return Result::Err("string is null".to_owned());
+    }
+
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|err| err.to_string())
+}
+
+fn to_c_string<T: Serialize>(result: Result<T, String>) -> *mut c_char {
+    let json = serde_json::to_string(&FfiResult::from(result))
+        .unwrap_or_else(|err| format!("{{\"Err\":\"{}\"}}", err));
+
+    CString::new(json)
+        .unwrap_or_default()
+        .into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn take(string: *mut c_char) -> String {
+        let result = CStr::from_ptr(string).to_str().unwrap().to_owned();
+        buttercup_string_free(string);
+        result
+    }
+
+    #[test]
+    fn test_loads_and_evaluates_definitions() {
+        let definition = CString::new(r#"{
+            "id": 3,
+            "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+            "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+        }"#).unwrap();
+        let payload = CString::new(r#"{ "name": { "Boolean": true } }"#).unwrap();
+        let malformed = CString::new("[]").unwrap();
+
+        unsafe {
+            let engine = buttercup_engine_new();
+
+            assert_eq!(r#"{"Ok":3}"#, take(buttercup_load_definition(engine, definition.as_ptr(), 1)));
+            assert_eq!(r#"{"Ok":"Success"}"#, take(buttercup_evaluate(engine, 3, payload.as_ptr())));
+            assert_eq!(r#"{"Err":"TreeOfGivenIdNotFound(4)"}"#,
+                       take(buttercup_evaluate(engine, 4, payload.as_ptr())));
+            assert!(take(buttercup_evaluate(engine, 3, malformed.as_ptr())).starts_with(r#"{"Err":"#));
+            assert_eq!(r#"{"Err":"engine is null"}"#,
+                       take(buttercup_evaluate(std::ptr::null(), 3, payload.as_ptr())));
+
+            buttercup_engine_free(engine);
+        }
+    }
+
+}
@@ -0,0 +1,357 @@
+use std::sync::Arc;
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Enum, Error, ID, Json, Object, Schema};
+use chrono::NaiveDateTime;
+use serde_json::Value;
+use uuid::Uuid;
+
+use buttercup_agents::AgentExecution;
+use buttercup_agents::instances::{TreeInstanceInfo, TreeInstanceService};
+use buttercup_agents::service::AgentService;
+use buttercup_api::bts::BehaviorTreeDefinitionService;
+use buttercup_api::document::{DefinitionDocumentPage, DefinitionDocumentQuery, DefinitionDocumentService, DefinitionDocumentSummary, DefinitionMetadata, DefinitionStatus};
+use buttercup_api::usage::{DefinitionUsageService, DefinitionUsageStats};
+use buttercup_blackboards::journal::BlackboardMutation;
+use buttercup_bts::hits::HitCountsSnapshot;
+use buttercup_bts::tree::BehaviorTreeService;
+use buttercup_values::ValuesPayload;
+
+pub type ManagementSchema = Schema<ManagementQuery, EmptyMutation, EmptySubscription>;
+
+// The services the resolvers read, shared with the REST endpoints.
+pub struct ManagementServices {
+
+    pub document_service: Arc<DefinitionDocumentService>,
+    pub definition_service: Arc<BehaviorTreeDefinitionService>,
+    pub tree_service: Arc<BehaviorTreeService>,
+    pub usage_service: Arc<DefinitionUsageService>,
+    pub instance_service: Arc<TreeInstanceService>,
+    pub agent_service: Arc<AgentService>
+
+}
+
+// Queries of the authoring ui, which reads a tree together with its versions, hits,
+// usage and instances in one request instead of one REST call per resource. Nested
+// objects are resolved only when they are selected.
+pub fn build_schema(services: ManagementServices) -> ManagementSchema {
+    Schema::build(ManagementQuery, EmptyMutation, EmptySubscription)
+        .data(services)
+        .finish()
+}
+
+#[derive(Enum, Eq, PartialEq, Copy, Clone)]
+#[graphql(remote = "DefinitionStatus", rename_items = "PascalCase")]
+enum TreeStatus {
+
+    Draft,
+    Active,
+    Deprecated,
+    Archived
+
+}
+
+pub struct ManagementQuery;
+
+#[Object]
+impl ManagementQuery {
+
+    #[allow(clippy::too_many_arguments)]
+    async fn trees(&self,
+                   ctx: &Context<'_>,
+                   cursor: Option<i32>,
+                   limit: Option<usize>,
+                   tag: Option<String>,
+                   owner: Option<String>,
+                   team: Option<String>,
+                   status: Option<TreeStatus>,
+                   search: Option<String>) -> TreePage {
+        let mut query = DefinitionDocumentQuery::default();
+
+        if let Some(cursor) = cursor {
+            query = query.with_cursor(cursor);
+        }
+        if let Some(limit) = limit {
+            query = query.with_limit(limit);
+        }
+        if let Some(tag) = tag {
+            query = query.with_tag(tag);
+        }
+        if let Some(owner) = owner {
+            query = query.with_owner(owner);
+        }
+        if let Some(team) = team {
+            query = query.with_team(team);
+        }
+        if let Some(status) = status {
+            query = query.with_status(status.into());
+        }
+        if let Some(search) = search {
+            query = query.with_search(search);
+        }
+
+        TreePage(get_services(ctx).document_service.list(&query))
+    }
+
+    async fn tree(&self,
+                  ctx: &Context<'_>,
+                  id: i32) -> Option<Tree> {
+        get_tree(ctx, &id)
+    }
+
+    async fn instance(&self,
+                      ctx: &Context<'_>,
+                      id: ID) -> Result<Option<Instance>, Error> {
+        Result::Ok(get_services(ctx).instance_service
+            .get_by_id(&parse_uuid(&id)?)
+            .map(|instance| Instance(instance.get_info())))
+    }
+
+    async fn execution(&self,
+                       ctx: &Context<'_>,
+                       id: ID) -> Result<Option<Json<AgentExecution>>, Error> {
+        Result::Ok(get_services(ctx).agent_service
+            .get_execution_by_id(&parse_uuid(&id)?)
+            .map(Json))
+    }
+
+}
+
+struct TreePage(DefinitionDocumentPage);
+
+#[Object]
+impl TreePage {
+
+    async fn items(&self) -> Vec<Tree> {
+        self.0.get_items().iter().cloned().map(Tree).collect()
+    }
+
+    async fn next_cursor(&self) -> Option<i32> {
+        *self.0.get_next_cursor()
+    }
+
+}
+
+struct Tree(DefinitionDocumentSummary);
+
+#[Object]
+impl Tree {
+
+    async fn id(&self) -> i32 {
+        *self.0.get_id()
+    }
+
+    async fn name(&self) -> &Option<String> {
+        self.0.get_name()
+    }
+
+    async fn status(&self) -> TreeStatus {
+        (*self.0.get_status()).into()
+    }
+
+    // The version of the latest document put, which may not be active yet.
+    async fn version(&self) -> u32 {
+        *self.0.get_version()
+    }
+
+    async fn metadata(&self) -> Json<DefinitionMetadata> {
+        Json(self.0.get_metadata().clone())
+    }
+
+    async fn active_version(&self,
+                            ctx: &Context<'_>) -> Option<u32> {
+        get_services(ctx).definition_service.get_active_version(self.0.get_id())
+    }
+
+    async fn versions(&self,
+                      ctx: &Context<'_>) -> Vec<u32> {
+        get_services(ctx).definition_service.get_versions(self.0.get_id())
+    }
+
+    async fn definition(&self,
+                        ctx: &Context<'_>) -> Result<Option<Json<Value>>, Error> {
+        match get_services(ctx).document_service.get(self.0.get_id()) {
+            None => Result::Ok(Option::None),
+            Some(document) => Result::Ok(Option::Some(Json(serde_json::from_str(document.get_json())?)))
+        }
+    }
+
+    async fn hits(&self,
+                  ctx: &Context<'_>) -> Option<Json<HitCountsSnapshot>> {
+        get_services(ctx).tree_service
+            .get_by_id(self.0.get_id())
+            .map(|tree| Json(tree.get_hit_counters().get_snapshot()))
+    }
+
+    async fn stats(&self,
+                   ctx: &Context<'_>) -> Option<Json<DefinitionUsageStats>> {
+        get_services(ctx).usage_service.get_stats(self.0.get_id()).map(Json)
+    }
+
+    async fn instances(&self,
+                       ctx: &Context<'_>) -> Vec<Instance> {
+        get_services(ctx).instance_service
+            .list_instances(self.0.get_id())
+            .into_iter()
+            .map(Instance)
+            .collect()
+    }
+
+}
+
+struct Instance(TreeInstanceInfo);
+
+#[Object]
+impl Instance {
+
+    async fn id(&self) -> ID {
+        ID(self.0.get_id().to_string())
+    }
+
+    async fn tree_id(&self) -> i32 {
+        *self.0.get_tree_id()
+    }
+
+    async fn created_at(&self) -> Json<NaiveDateTime> {
+        Json(*self.0.get_created_at_utc())
+    }
+
+    async fn last_active_at(&self) -> Json<NaiveDateTime> {
+        Json(*self.0.get_last_active_at_utc())
+    }
+
+    async fn tree(&self,
+                  ctx: &Context<'_>) -> Option<Tree> {
+        get_tree(ctx, self.0.get_tree_id())
+    }
+
+    // Mutations of the blackboard of the instance after the given sequence.
+    async fn history(&self,
+                     ctx: &Context<'_>,
+                     #[graphql(default)] since: u64) -> Result<Vec<Mutation>, Error> {
+        get_services(ctx).instance_service
+            .get_history(self.0.get_id(), since)
+            .map(|mutations| mutations.into_iter().map(Mutation).collect())
+            .map_err(|err| Error::new(format!("{:?}", err)))
+    }
+
+}
+
+struct Mutation(BlackboardMutation);
+
+#[Object]
+impl Mutation {
+
+    async fn sequence(&self) -> u64 {
+        *self.0.get_sequence()
+    }
+
+    async fn at(&self) -> Json<NaiveDateTime> {
+        Json(*self.0.get_at_utc())
+    }
+
+    async fn values(&self) -> Json<ValuesPayload> {
+        Json(self.0.get_values().clone())
+    }
+
+}
+
+fn get_services<'a>(ctx: &Context<'a>) -> &'a ManagementServices {
+    ctx.data_unchecked::<ManagementServices>()
+}
+
+fn get_tree(ctx: &Context<'_>,
+            tree_id: &i32) -> Option<Tree> {
+    get_services(ctx).document_service
+        .get(tree_id)
+        .map(|document| Tree(document.get_summary().clone()))
+}
+
+fn parse_uuid(id: &ID) -> Result<Uuid, Error> {
+    Uuid::parse_str(id).map_err(|err| Error::new(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use actix_rt::System;
+    use serde_json::json;
+
+    use buttercup_api::bts::BehaviorTreeBuildingService;
+    use buttercup_bts::context::BTNodeContextService;
+
+    use super::*;
+
+    const DEFINITION: &str = r#"{
+        "id": 5,
+        "root": {"type": "OneOff", "id": 2, "child_id": 1},
+        "nodes": [{"type": "PrintLog", "id": 1, "message": "Hello!"}]
+    }"#;
+
+    #[test]
+    fn test_queries_trees_with_their_versions_and_instances_in_one_request() {
+        let system = System::new();
+        let context_service = Arc::new(BTNodeContextService::default());
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        // The agent service owns a runtime, which can not be dropped inside the one running the
+        // test, so it outlives the block.
+        let agent_service = Arc::new(AgentService::new(context_service.clone(), tree_service.clone()).unwrap());
+
+        system.block_on(async {
+            let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+            let document_service = Arc::new(DefinitionDocumentService::new(definition_service.clone()));
+            let building_service = BehaviorTreeBuildingService::new(tree_service.clone(), definition_service.clone());
+
+            document_service.put(&5, DEFINITION, Option::None).unwrap();
+            building_service.activate(&5, &1).await.unwrap();
+            document_service.put(&5, DEFINITION, Option::Some(&[1])).unwrap();
+
+            let instance_service = Arc::new(TreeInstanceService::new(context_service.clone(),
+                                                                     tree_service.clone(),
+                                                                     Duration::from_secs(60)));
+            let instance_id = instance_service.create_instance(&5).unwrap();
+            let schema = build_schema(ManagementServices {
+                document_service,
+                definition_service,
+                tree_service: tree_service.clone(),
+                usage_service: Arc::new(DefinitionUsageService::default()),
+                instance_service,
+                agent_service: agent_service.clone()
+            });
+
+            let response = schema.execute(r#"{
+                trees(status: Active) {
+                    items { id version activeVersion versions instances { id tree { id } } }
+                    nextCursor
+                }
+                missing: tree(id: 7) { id }
+                execution(id: "00000000-0000-0000-0000-000000000000")
+            }"#).await;
+
+            assert!(response.errors.is_empty(), "{:?}", response.errors);
+            assert_eq!(json!({
+                "trees": {
+                    "items": [{
+                        "id": 5,
+                        "version": 2,
+                        "activeVersion": 1,
+                        "versions": [1, 2],
+                        "instances": [{ "id": instance_id.to_string(), "tree": { "id": 5 } }]
+                    }],
+                    "nextCursor": null
+                },
+                "missing": null,
+                "execution": null
+            }), response.data.into_json().unwrap());
+
+            let response = schema.execute(format!(r#"{{ instance(id: "{}") {{ history {{ sequence }} }} }}"#,
+                                                  instance_id)).await;
+
+            assert_eq!(json!({ "instance": null }), response.data.into_json().unwrap());
+            assert_eq!(vec![format!("HistoryNotRecorded({})", instance_id)],
+                       response.errors.iter().map(|err| err.message.clone()).collect::<Vec<_>>());
+            assert_eq!(1, schema.execute(r#"{ instance(id: "not an id") { id } }"#).await.errors.len());
+        });
+    }
+
+}
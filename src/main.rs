@@ -1,23 +1,59 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use actix::{Actor, Addr, Arbiter};
 use actix_web::{App, http, HttpRequest, HttpServer, middleware};
-use actix_web::{post, Responder, web};
-use actix_web::web::{Data, get, resource};
+use actix_web::{delete, get, post, put, HttpResponse, Responder, web};
+use actix_web::dev::HttpResponseBuilder;
+use actix_web::http::header::{EntityTag, Header, HttpDate, IfMatch, IfNoneMatch};
+use actix_web::web::{Bytes, Data};
 use dashmap::DashMap;
 use env_logger;
+use futures::StreamExt;
+use log::warn;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use buttercup_agents::executor::{InstanceExecutorError, ShardedInstanceExecutor};
+use buttercup_agents::instances::{TreeInstanceService, TreeInstanceServiceError};
 use buttercup_agents::service::AgentService;
+use buttercup_api::bpmn::{BpmnImportError, BpmnImporter};
+use buttercup_api::btcpp::{BtCppError, BtCppExporter, BtCppImporter};
+use buttercup_api::bts::{BehaviorTreeBuildingError, BehaviorTreeBuildingService, BehaviorTreeDefinitionService};
+use buttercup_api::debug::{self, DebugSessionService};
+use buttercup_api::usage::DefinitionUsageService;
+use buttercup_api::document::{BehaviorTreeDocument, DefinitionDocumentQuery, DefinitionDocumentService, DefinitionDocumentServiceError, DefinitionStatus, StoredDefinitionDocument};
+use buttercup_api::engine::{ButtercupEngine, EngineError};
+use buttercup_api::events::DefinitionEventService;
+use buttercup_api::files::{DefinitionFileError, DefinitionFileLoader};
+use buttercup_api::table::{DecisionTable, HitPolicy};
 use buttercup_blackboards::LocalBlackboardService;
+use buttercup_blackboards::outbox::Outbox;
+use buttercup_bts::calendar::BusinessCalendars;
 use buttercup_bts::context::{BTNodeContextService, BTNodeExecutionContextHolder};
+use buttercup_bts::task::HumanTaskError;
+use buttercup_bts::tree::BehaviorTreeService;
+use buttercup_endpoints::ArgumentDefinition;
 use buttercup_endpoints::endpoints::EndpointService;
-use buttercup_values::ValuesPayload;
+use buttercup_endpoints::examples::ExamplePayloadsGenerator;
+use buttercup_values::{ValueHolder, ValuesPayload};
+use buttercup_values::calendar::BusinessCalendar;
 
+use crate::cluster::{ClusterService, InMemorySharedStore, RedisSharedStore, SharedStore};
+use crate::config::ServerConfig;
+use crate::graphql::{ManagementSchema, ManagementServices};
+use crate::webhooks::WebhookNotifier;
+
+pub mod cluster;
+pub mod config;
+pub mod graphql;
 pub mod test_utils;
+pub mod tls;
+pub mod webhooks;
 
+const API_KEY_HEADER: &str = "X-Api-Key";
 
 #[post("/values/{name}/{value}")]
 async fn add_variable_value(
@@ -30,6 +66,81 @@ async fn add_variable_value(
         .unwrap()
 }
 
+#[post("/arguments/examples")]
+async fn generate_argument_examples(
+    definitions: web::Json<HashMap<String, ArgumentDefinition>>) -> impl Responder {
+    HttpResponse::Ok().json(ExamplePayloadsGenerator::generate(&definitions.0))
+}
+
+#[derive(Serialize, Deserialize)]
+struct AdhocEvaluationRequest {
+
+    definition: serde_json::Value,
+    payload: HashMap<String, ValueHolder>
+
+}
+
+// Ad-hoc definitions are built in sandboxes of their own, so the static values are added
+// to their payloads instead.
+fn with_static_values(config: &ServerConfig,
+                      payload: HashMap<String, ValueHolder>) -> ValuesPayload {
+    let mut values = config.get_static_values().clone();
+    values.extend(payload);
+    ValuesPayload::new(values)
+}
+
+#[derive(Serialize, Deserialize)]
+struct AdhocEvaluationOptions {
+
+    #[serde(default)]
+    trace: bool
+
+}
+
+// Lets authoring tools try out a definition without storing it anywhere. With ?trace=true
+// the nodes visited, the edges taken and the outcomes of the conditions are returned too.
+#[post("/evaluate:adhoc")]
+async fn evaluate_adhoc(config: Data<ServerConfig>,
+                        options: web::Query<AdhocEvaluationOptions>,
+                        request: web::Json<AdhocEvaluationRequest>) -> impl Responder {
+    let AdhocEvaluationRequest { definition, payload } = request.0;
+    let payload = with_static_values(&config, payload);
+    let evaluation = match options.trace {
+        false => ButtercupEngine::evaluate_adhoc(&definition.to_string(),
+                                                 &payload,
+                                                 &config.get_definition_limits(),
+                                                 &config.get_evaluation_budget()).await,
+        true => ButtercupEngine::explain_adhoc(&definition.to_string(),
+                                               &payload,
+                                               &config.get_definition_limits(),
+                                               &config.get_evaluation_budget()).await
+    };
+
+    match evaluation {
+        Ok(evaluation) => HttpResponse::Ok().json(evaluation),
+        Err(EngineError::TooComplex(report)) => HttpResponse::UnprocessableEntity().json(report),
+        Err(err) => HttpResponse::BadRequest().body(format!("{:?}", err))
+    }
+}
+
+// Same as evaluate:adhoc, but returns only the node timings, in the Chrome trace-event
+// format.
+#[post("/evaluate:trace")]
+async fn evaluate_trace(config: Data<ServerConfig>,
+                        request: web::Json<AdhocEvaluationRequest>) -> impl Responder {
+    let AdhocEvaluationRequest { definition, payload } = request.0;
+    let payload = with_static_values(&config, payload);
+
+    match ButtercupEngine::evaluate_adhoc(&definition.to_string(),
+                                          &payload,
+                                          &config.get_definition_limits(),
+                                          &config.get_evaluation_budget()).await {
+        Ok(evaluation) => HttpResponse::Ok().json(evaluation.get_chrome_trace()),
+        Err(EngineError::TooComplex(report)) => HttpResponse::UnprocessableEntity().json(report),
+        Err(err) => HttpResponse::BadRequest().body(format!("{:?}", err))
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 struct TreeId {
 
@@ -39,10 +150,16 @@ struct TreeId {
 
 #[post("/agents")]
 async fn build_new_agent(agent_service: Data<Arc<AgentService>>,
+                         document_service: Data<Arc<DefinitionDocumentService>>,
+                         usage_service: Data<Arc<DefinitionUsageService>>,
+                         request: HttpRequest,
                          tree_id: web::Json<TreeId>) -> impl Responder {
-    format!("{:?}", agent_service
-        .build_new_agent(&tree_id.0.id)
-        .map(|id| id.to_string()))
+    match check_evaluation(&document_service, &usage_service, &request, &tree_id.0.id) {
+        Err(response) => response,
+        Ok(mut response) => response.body(format!("{:?}", agent_service
+            .build_new_agent(&tree_id.0.id)
+            .map(|id| id.to_string())))
+    }
 }
 
 #[post("/agents/{agent_id}/start")]
@@ -50,7 +167,7 @@ async fn start_agent(agent_service: Data<Arc<AgentService>>,
                      agent_id: web::Path<Uuid>) -> impl Responder {
     format!("{:?}", agent_service
         .start_agent_by_id(&agent_id.0)
-    )
+        .map(|id| id.to_string()))
 }
 
 #[post("/agents/{agent_id}/stop")]
@@ -61,39 +178,1283 @@ async fn stop_agent(agent_service: Data<Arc<AgentService>>,
     )
 }
 
+#[derive(Serialize, Deserialize)]
+struct TreeVersion {
+
+    version: u32
+
+}
+
+#[post("/trees/{tree_id}/activate")]
+async fn activate_tree(cluster: Data<Arc<ClusterService>>,
+                       tree_id: web::Path<i32>,
+                       tree_version: web::Query<TreeVersion>) -> impl Responder {
+    match cluster.activate(&tree_id.0, &tree_version.version).await {
+        Ok(result) => HttpResponse::Ok().body(format!("{:?}", result)),
+        Err(err) => HttpResponse::ServiceUnavailable().json(err)
+    }
+}
+
+// Activates the latest version older than the active one.
+#[post("/trees/{tree_id}/rollback")]
+async fn rollback_tree(building_service: Data<Arc<BehaviorTreeBuildingService>>,
+                       tree_id: web::Path<i32>) -> impl Responder {
+    match building_service.rollback(&tree_id.0).await {
+        Ok(version) => HttpResponse::Ok().json(TreeVersion { version }),
+        Err(err @ BehaviorTreeBuildingError::ReadOnly) => HttpResponse::ServiceUnavailable().body(format!("{:?}", err)),
+        Err(err) => HttpResponse::Conflict().body(format!("{:?}", err))
+    }
+}
+
+#[post("/trees/{tree_id}/selftest")]
+async fn self_test_tree(building_service: Data<Arc<BehaviorTreeBuildingService>>,
+                        tree_id: web::Path<i32>) -> impl Responder {
+    format!("{:?}", building_service
+        .self_test(&tree_id.0)
+        .await)
+}
+
+#[derive(Serialize, Deserialize)]
+struct TreeStatus {
+
+    status: DefinitionStatus
+
+}
+
+#[post("/trees/{tree_id}/status")]
+async fn transition_tree(cluster: Data<Arc<ClusterService>>,
+                         tree_id: web::Path<i32>,
+                         tree_status: web::Json<TreeStatus>) -> impl Responder {
+    let result = match cluster.transition(&tree_id.0, tree_status.0.status).await {
+        Ok(result) => result,
+        Err(err) => return HttpResponse::ServiceUnavailable().json(err)
+    };
+
+    match result {
+        Ok(status) => HttpResponse::Ok().json(TreeStatus { status }),
+        Err(err @ DefinitionDocumentServiceError::TreeOfGivenIdNotFound(_)) =>
+            HttpResponse::NotFound().body(format!("{:?}", err)),
+        Err(err @ DefinitionDocumentServiceError::BehaviorTreeBuildingError(BehaviorTreeBuildingError::ReadOnly)) =>
+            HttpResponse::ServiceUnavailable().body(format!("{:?}", err)),
+        Err(err) => HttpResponse::Conflict().body(format!("{:?}", err))
+    }
+}
+
+// Kill switch for incidents, evaluations of a disabled tree are refused with 503 until it
+// is enabled again.
+#[post("/trees/{tree_id}/disable")]
+async fn disable_tree(document_service: Data<Arc<DefinitionDocumentService>>,
+                      tree_id: web::Path<i32>) -> impl Responder {
+    match document_service.set_disabled(&tree_id.0, true) {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().body(format!("{:?}", err))
+    }
+}
+
+#[post("/trees/{tree_id}/enable")]
+async fn enable_tree(document_service: Data<Arc<DefinitionDocumentService>>,
+                     tree_id: web::Path<i32>) -> impl Responder {
+    match document_service.set_disabled(&tree_id.0, false) {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().body(format!("{:?}", err))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReadOnlyMode {
+
+    read_only: bool
+
+}
+
+#[get("/read-only")]
+async fn get_read_only_mode(definition_service: Data<Arc<BehaviorTreeDefinitionService>>) -> impl Responder {
+    HttpResponse::Ok().json(ReadOnlyMode { read_only: definition_service.is_read_only() })
+}
+
+// While read-only, definitions can be neither put, activated nor transitioned, they can
+// still be evaluated.
+#[put("/read-only")]
+async fn set_read_only_mode(definition_service: Data<Arc<BehaviorTreeDefinitionService>>,
+                            mode: web::Json<ReadOnlyMode>) -> impl Responder {
+    definition_service.set_read_only(mode.read_only);
+    HttpResponse::Ok().json(mode.0)
+}
+
+#[get("/trees/metrics")]
+async fn get_tree_usage_metrics(document_service: Data<Arc<DefinitionDocumentService>>) -> impl Responder {
+    HttpResponse::Ok().json(document_service.get_usage_metrics())
+}
+
+#[get("/trees/footprints")]
+async fn get_tree_footprints(tree_service: Data<Arc<BehaviorTreeService>>) -> impl Responder {
+    HttpResponse::Ok().json(tree_service.get_footprints())
+}
+
+#[get("/trees/{tree_id}/hits")]
+async fn get_tree_hits(tree_service: Data<Arc<BehaviorTreeService>>,
+                       tree_id: web::Path<i32>) -> impl Responder {
+    match tree_service.get_by_id(&tree_id.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(tree) => HttpResponse::Ok().json(tree.get_hit_counters().get_snapshot())
+    }
+}
+
+#[delete("/trees/{tree_id}/hits")]
+async fn reset_tree_hits(tree_service: Data<Arc<BehaviorTreeService>>,
+                         tree_id: web::Path<i32>) -> impl Responder {
+    match tree_service.get_by_id(&tree_id.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(tree) => {
+            tree.get_hit_counters().reset();
+            HttpResponse::NoContent().finish()
+        }
+    }
+}
+
+#[get("/trees/{tree_id}/stats")]
+async fn get_tree_usage_stats(usage_service: Data<Arc<DefinitionUsageService>>,
+                              tree_id: web::Path<i32>) -> impl Responder {
+    match usage_service.get_stats(&tree_id.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(stats) => HttpResponse::Ok().json(stats)
+    }
+}
+
+// Disabled trees are refused with 503, archived ones with 410 Gone, responses for
+// deprecated trees carry a warning header. Every other evaluation is counted in the usage stats of the tree.
+fn check_evaluation(document_service: &DefinitionDocumentService,
+                    usage_service: &DefinitionUsageService,
+                    request: &HttpRequest,
+                    tree_id: &i32) -> Result<HttpResponseBuilder, HttpResponse> {
+    let check = document_service.check_evaluation(tree_id);
+
+    if check.is_ok() {
+        usage_service.record(tree_id,
+                             request.headers()
+                                 .get(API_KEY_HEADER)
+                                 .and_then(|api_key| api_key.to_str().ok()));
+    }
+
+    match check {
+        Err(err @ DefinitionDocumentServiceError::TreeIsDisabled(_)) =>
+            Result::Err(HttpResponse::ServiceUnavailable().body(format!("{:?}", err))),
+        Err(err) => Result::Err(HttpResponse::Gone().body(format!("{:?}", err))),
+        Ok(Some(DefinitionStatus::Deprecated)) => {
+            let mut response = HttpResponse::Ok();
+            response.header(http::header::WARNING,
+                            format!("299 - \"tree {} is deprecated\"", tree_id));
+            Result::Ok(response)
+        },
+        Ok(_) => Result::Ok(HttpResponse::Ok())
+    }
+}
+
+// Server-sent events, one per change of a definition, named after the kind of the change.
+#[get("/events/definitions")]
+async fn stream_definition_events(events: Data<Arc<DefinitionEventService>>) -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header(http::header::CACHE_CONTROL, "no-cache")
+        .streaming(events
+            .subscribe()
+            .map(|event| serde_json::to_string(&event)
+                .map(|json| Bytes::from(format!("event: {}\ndata: {}\n\n", event.get_name(), json)))
+                .map_err(actix_web::Error::from)))
+}
+
+#[get("/trees")]
+async fn list_trees(document_service: Data<Arc<DefinitionDocumentService>>,
+                    query: web::Query<DefinitionDocumentQuery>) -> impl Responder {
+    HttpResponse::Ok().json(document_service.list(&query.0))
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreatedTree {
+
+    id: i32,
+    version: u32
+
+}
+
+// Puts the first version of a new tree under an id chosen by the server.
+#[post("/trees")]
+async fn create_tree(document_service: Data<Arc<DefinitionDocumentService>>,
+                     body: String) -> impl Responder {
+    match document_service.create(&body) {
+        Ok((id, version)) => HttpResponse::Created()
+            .header(http::header::LOCATION, format!("/trees/{}/definition", id))
+            .header(http::header::ETAG, EntityTag::strong(version.to_string()).to_string())
+            .json(CreatedTree { id, version }),
+        Err(DefinitionDocumentServiceError::TooComplex(_, report)) =>
+            HttpResponse::UnprocessableEntity().json(report),
+        Err(err @ DefinitionDocumentServiceError::BehaviorTreeBuildingError(BehaviorTreeBuildingError::ReadOnly)) =>
+            HttpResponse::ServiceUnavailable().body(format!("{:?}", err)),
+        Err(err) => HttpResponse::BadRequest().body(format!("{:?}", err))
+    }
+}
+
+#[get("/trees/{tree_id}/definition")]
+async fn get_tree_definition(document_service: Data<Arc<DefinitionDocumentService>>,
+                             request: HttpRequest,
+                             tree_id: web::Path<i32>) -> impl Responder {
+    match document_service.get(&tree_id.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(document) => {
+            let etag = get_etag(&document);
+            let not_modified = match IfNoneMatch::parse(&request) {
+                Ok(IfNoneMatch::Any) => true,
+                Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+                Err(_) => false
+            };
+            let mut response = if not_modified {
+                HttpResponse::NotModified()
+            } else {
+                HttpResponse::Ok()
+            };
+
+            response
+                .header(http::header::ETAG, etag.to_string())
+                .header(http::header::LAST_MODIFIED,
+                        HttpDate::from(*document.get_modified_at()).to_string());
+
+            if not_modified {
+                response.finish()
+            } else {
+                response
+                    .content_type("application/json")
+                    .body(document.get_json().clone())
+            }
+        }
+    }
+}
+
+// Puts a new standby version of the definition, with If-Match only when the latest
+// version is still the one the client has seen.
+#[put("/trees/{tree_id}/definition")]
+async fn put_tree_definition(document_service: Data<Arc<DefinitionDocumentService>>,
+                             cluster: Data<Arc<ClusterService>>,
+                             request: HttpRequest,
+                             tree_id: web::Path<i32>,
+                             body: String) -> impl Responder {
+    // Without the header the put is unconditional, parsing yields no items then.
+    let expected_versions: Option<Vec<u32>> = match IfMatch::parse(&request) {
+        _ if !request.headers().contains_key(http::header::IF_MATCH) => Option::None,
+        Ok(IfMatch::Any) => Option::Some(document_service
+            .get(&tree_id.0)
+            .map(|document| vec![*document.get_version()])
+            .unwrap_or_default()),
+        Ok(IfMatch::Items(tags)) if tags.is_empty() => Option::None,
+        Ok(IfMatch::Items(tags)) => Option::Some(tags.iter()
+            .filter(|tag| !tag.weak)
+            .filter_map(|tag| tag.tag().parse().ok())
+            .collect()),
+        Err(_) => Option::None
+    };
+
+    let result = match cluster.put_definition(&tree_id.0, &body, expected_versions.as_deref()).await {
+        Ok(result) => result,
+        Err(err) => return HttpResponse::ServiceUnavailable().json(err)
+    };
+
+    match result {
+        Ok(version) => HttpResponse::Ok()
+            .header(http::header::ETAG, EntityTag::strong(version.to_string()).to_string())
+            .json(TreeVersion { version }),
+        Err(err @ DefinitionDocumentServiceError::VersionMismatch(_, _)) =>
+            HttpResponse::PreconditionFailed().body(format!("{:?}", err)),
+        Err(DefinitionDocumentServiceError::TooComplex(_, report)) =>
+            HttpResponse::UnprocessableEntity().json(report),
+        Err(err @ DefinitionDocumentServiceError::BehaviorTreeBuildingError(BehaviorTreeBuildingError::ReadOnly)) =>
+            HttpResponse::ServiceUnavailable().body(format!("{:?}", err)),
+        Err(err) => HttpResponse::BadRequest().body(format!("{:?}", err))
+    }
+}
+
+#[get("/trees/{tree_id}/table")]
+async fn get_tree_table(document_service: Data<Arc<DefinitionDocumentService>>,
+                        tree_id: web::Path<i32>) -> impl Responder {
+    match document_service.get(&tree_id.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(document) => match BehaviorTreeDocument::from_json(document.get_json())
+            .map_err(|err| format!("{:?}", err))
+            .and_then(|document| DecisionTable::from_document(&document).map_err(|err| format!("{:?}", err))) {
+            Ok(table) => HttpResponse::Ok().json(table),
+            Err(err) => HttpResponse::UnprocessableEntity().body(err)
+        }
+    }
+}
+
+#[get("/trees/{tree_id}/btcpp")]
+async fn get_tree_btcpp(document_service: Data<Arc<DefinitionDocumentService>>,
+                        tree_id: web::Path<i32>) -> impl Responder {
+    match document_service.get(&tree_id.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(document) => match BehaviorTreeDocument::from_json(document.get_json())
+            .map_err(|err| format!("{:?}", err))
+            .and_then(|document| BtCppExporter::export(&document).map_err(|err| format!("{:?}", err))) {
+            Ok(xml) => HttpResponse::Ok().content_type("application/xml").body(xml),
+            Err(err) => HttpResponse::UnprocessableEntity().body(err)
+        }
+    }
+}
+
+// Puts the table as a new standby version of the tree, which keeps the name, tags and
+// metadata of the latest version.
+#[put("/trees/{tree_id}/table")]
+async fn put_tree_table(document_service: Data<Arc<DefinitionDocumentService>>,
+                        tree_id: web::Path<i32>,
+                        table: web::Json<DecisionTable>) -> impl Responder {
+    put_table(&document_service, &tree_id.0, &table)
+}
+
+#[derive(Serialize, Deserialize)]
+struct TableCsvQuery {
+
+    hit_policy: Option<HitPolicy>
+
+}
+
+// Same as the json table, with a header line naming the inputs and then the output.
+#[put("/trees/{tree_id}/table/csv")]
+async fn put_tree_table_csv(document_service: Data<Arc<DefinitionDocumentService>>,
+                            tree_id: web::Path<i32>,
+                            query: web::Query<TableCsvQuery>,
+                            csv: String) -> impl Responder {
+    match DecisionTable::from_csv(&csv, query.hit_policy.unwrap_or_default()) {
+        Ok(table) => put_table(&document_service, &tree_id.0, &table),
+        Err(err) => HttpResponse::BadRequest().body(format!("{:?}", err))
+    }
+}
+
+fn put_table(document_service: &DefinitionDocumentService,
+             tree_id: &i32,
+             table: &DecisionTable) -> HttpResponse {
+    match table.to_document(*tree_id) {
+        Ok(document) => put_document(document_service, tree_id, document),
+        Err(err) => HttpResponse::UnprocessableEntity().body(format!("{:?}", err))
+    }
+}
+
+// Imports the process as a new standby version of the tree, like tables are.
+#[put("/trees/{tree_id}/bpmn")]
+async fn put_tree_bpmn(document_service: Data<Arc<DefinitionDocumentService>>,
+                       tree_id: web::Path<i32>,
+                       bpmn: String) -> impl Responder {
+    match BpmnImporter::import(tree_id.0, &bpmn) {
+        Ok(document) => put_document(&document_service, &tree_id.0, document),
+        Err(err @ BpmnImportError::InvalidXml(_)) => HttpResponse::BadRequest().body(format!("{:?}", err)),
+        Err(err) => HttpResponse::UnprocessableEntity().body(format!("{:?}", err))
+    }
+}
+
+// Unsupported constructs are all listed, so that they can be fixed in one go.
+#[put("/trees/{tree_id}/btcpp")]
+async fn put_tree_btcpp(document_service: Data<Arc<DefinitionDocumentService>>,
+                        tree_id: web::Path<i32>,
+                        xml: String) -> impl Responder {
+    match BtCppImporter::import(tree_id.0, &xml) {
+        Ok(document) => put_document(&document_service, &tree_id.0, document),
+        Err(err @ BtCppError::InvalidXml(_)) => HttpResponse::BadRequest().body(format!("{:?}", err)),
+        Err(BtCppError::UnsupportedConstructs(constructs)) => HttpResponse::UnprocessableEntity().json(constructs),
+        Err(err) => HttpResponse::UnprocessableEntity().body(format!("{:?}", err))
+    }
+}
+
+fn put_document(document_service: &DefinitionDocumentService,
+                tree_id: &i32,
+                document: BehaviorTreeDocument) -> HttpResponse {
+    let document = match document_service
+        .get(tree_id)
+        .and_then(|latest| BehaviorTreeDocument::from_json(latest.get_json()).ok()) {
+        None => document,
+        Some(latest) => document.with_details_of(latest)
+    };
+
+    let json = match serde_json::to_string(&document) {
+        Ok(json) => json,
+        Err(err) => return HttpResponse::InternalServerError().body(err.to_string())
+    };
+
+    match document_service.put(tree_id, &json, Option::None) {
+        Ok(version) => HttpResponse::Ok().json(TreeVersion { version }),
+        Err(DefinitionDocumentServiceError::TooComplex(_, report)) =>
+            HttpResponse::UnprocessableEntity().json(report),
+        Err(err @ DefinitionDocumentServiceError::BehaviorTreeBuildingError(BehaviorTreeBuildingError::ReadOnly)) =>
+            HttpResponse::ServiceUnavailable().body(format!("{:?}", err)),
+        Err(err) => HttpResponse::BadRequest().body(format!("{:?}", err))
+    }
+}
+
+fn get_etag(document: &StoredDefinitionDocument) -> EntityTag {
+    EntityTag::strong(document.get_version().to_string())
+}
+
+#[derive(Serialize, Deserialize)]
+struct PinnedVersion {
+
+    #[serde(default)]
+    version: Option<u32>
+
+}
+// Evaluates the active version of the tree, a failed evaluation is handed over to the
+// fallback tree of the definition.
+#[post("/trees/{tree_id}/evaluate")]
+async fn evaluate_tree(engine: Data<Arc<ButtercupEngine>>,
+                       document_service: Data<Arc<DefinitionDocumentService>>,
+                       usage_service: Data<Arc<DefinitionUsageService>>,
+                       request: HttpRequest,
+                       tree_id: web::Path<i32>,
+                       payload: web::Json<HashMap<String, ValueHolder>>) -> impl Responder {
+    let mut response = match check_evaluation(&document_service, &usage_service, &request, &tree_id.0) {
+        Err(response) => return response,
+        Ok(response) => response
+    };
+
+    match engine.evaluate_with_fallback(&tree_id.0, &ValuesPayload::new(payload.0)).await {
+        Ok(evaluation) => response.json(evaluation),
+        Err(err @ EngineError::TreeOfGivenIdNotFound(_)) =>
+            response.status(http::StatusCode::NOT_FOUND).body(format!("{:?}", err)),
+        Err(err) => response.status(http::StatusCode::UNPROCESSABLE_ENTITY).body(format!("{:?}", err))
+    }
+}
+
+// Number of evaluations handed over to the fallback tree, by the id of the tree which failed.
+#[get("/trees/fallbacks/metrics")]
+async fn get_tree_fallback_metrics(engine: Data<Arc<ButtercupEngine>>) -> impl Responder {
+    HttpResponse::Ok().json(engine.get_fallback_counts())
+}
+
+// Instances run the active version of the tree, unless another version is pinned.
+#[post("/trees/{tree_id}/instances")]
+async fn create_tree_instance(instance_service: Data<Arc<TreeInstanceService>>,
+                              building_service: Data<Arc<BehaviorTreeBuildingService>>,
+                              document_service: Data<Arc<DefinitionDocumentService>>,
+                              usage_service: Data<Arc<DefinitionUsageService>>,
+                              cluster: Data<Arc<ClusterService>>,
+                              config: Data<ServerConfig>,
+                              request: HttpRequest,
+                              tree_id: web::Path<i32>,
+                              pinned: web::Query<PinnedVersion>) -> impl Responder {
+    let mut response = match check_evaluation(&document_service, &usage_service, &request, &tree_id.0) {
+        Err(response) => return response,
+        Ok(response) => response
+    };
+
+    let created = match pinned.version {
+        None => instance_service.create_instance(&tree_id.0),
+        Some(version) => match building_service.build_version(&tree_id.0, &version) {
+            Ok(tree) => instance_service.create_instance_of(Arc::new(tree)),
+            Err(err) => return response.status(http::StatusCode::NOT_FOUND).body(format!("{:?}", err))
+        }
+    };
+
+    match created {
+        Ok(instance_id) => {
+            if let Err(err) = cluster.register_instance(&instance_id, &config.get_instance_ttl()) {
+                warn!("Could not register instance {} with the cluster: {:?}", instance_id, err);
+            }
+            response.status(http::StatusCode::CREATED).json(instance_id)
+        },
+        Err(err) => response.status(http::StatusCode::NOT_FOUND).json(err)
+    }
+}
+
+#[get("/trees/{tree_id}/instances")]
+async fn list_tree_instances(instance_service: Data<Arc<TreeInstanceService>>,
+                             tree_id: web::Path<i32>) -> impl Responder {
+    HttpResponse::Ok().json(instance_service.list_instances(&tree_id.0))
+}
+
+// Instances live on the server which created them, requests for instances of other
+// servers of the cluster are redirected there.
+fn redirect_to_owner(cluster: &ClusterService,
+                     request: &HttpRequest,
+                     instance_id: &Uuid) -> Option<HttpResponse> {
+    match cluster.get_instance_owner(instance_id) {
+        Ok(owner) => owner.map(|url| HttpResponse::TemporaryRedirect()
+            .header(http::header::LOCATION, format!("{}{}", url.trim_end_matches('/'), request.uri()))
+            .finish()),
+        Err(err) => {
+            warn!("Could not look up the owner of instance {}: {:?}", instance_id, err);
+            Option::None
+        }
+    }
+}
+
+#[post("/instances/{instance_id}/tick")]
+async fn tick_tree_instance(executor: Data<Arc<ShardedInstanceExecutor>>,
+                            instance_service: Data<Arc<TreeInstanceService>>,
+                            document_service: Data<Arc<DefinitionDocumentService>>,
+                            usage_service: Data<Arc<DefinitionUsageService>>,
+                            cluster: Data<Arc<ClusterService>>,
+                            config: Data<ServerConfig>,
+                            request: HttpRequest,
+                            instance_id: web::Path<Uuid>) -> impl Responder {
+    let tree_id = instance_service
+        .get_by_id(&instance_id.0)
+        .map(|instance| *instance.get_info().get_tree_id());
+
+    if tree_id.is_none() {
+        if let Some(redirect) = redirect_to_owner(&cluster, &request, &instance_id.0) {
+            return redirect;
+        }
+    } else if let Err(err) = cluster.register_instance(&instance_id.0, &config.get_instance_ttl()) {
+        warn!("Could not register instance {} with the cluster: {:?}", instance_id.0, err);
+    }
+
+    let mut response = match tree_id
+        .map(|tree_id| check_evaluation(&document_service, &usage_service, &request, &tree_id)) {
+        Some(Err(response)) => return response,
+        Some(Ok(response)) => response,
+        None => HttpResponse::Ok()
+    };
+
+    match executor.tick(&instance_id.0).await {
+        Err(InstanceExecutorError::Saturated) => response
+            .status(http::StatusCode::SERVICE_UNAVAILABLE)
+            .header(http::header::RETRY_AFTER, config.get_instance_executor_retry_after_secs().to_string())
+            .json(InstanceExecutorError::Saturated),
+        Ok(Err(err @ TreeInstanceServiceError::InstanceOfGivenIdNotFound(_))) =>
+            response.status(http::StatusCode::NOT_FOUND).json(err),
+        result => response.json(result)
+    }
+}
+
+#[put("/instances/{instance_id}/breakpoints")]
+async fn set_instance_breakpoints(instance_service: Data<Arc<TreeInstanceService>>,
+                                  cluster: Data<Arc<ClusterService>>,
+                                  request: HttpRequest,
+                                  instance_id: web::Path<Uuid>,
+                                  node_ids: web::Json<HashSet<i32>>) -> impl Responder {
+    match instance_service.set_breakpoints(&instance_id.0, node_ids.0) {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => redirect_to_owner(&cluster, &request, &instance_id.0)
+            .unwrap_or_else(|| HttpResponse::NotFound().json(err))
+    }
+}
+
+#[get("/instances/{instance_id}/breakpoints")]
+async fn get_instance_breakpoints(instance_service: Data<Arc<TreeInstanceService>>,
+                                  cluster: Data<Arc<ClusterService>>,
+                                  request: HttpRequest,
+                                  instance_id: web::Path<Uuid>) -> impl Responder {
+    match instance_service.get_breakpoint_state(&instance_id.0) {
+        Ok(state) => HttpResponse::Ok().json(state),
+        Err(err @ TreeInstanceServiceError::InstanceOfGivenIdNotFound(_)) =>
+            redirect_to_owner(&cluster, &request, &instance_id.0)
+                .unwrap_or_else(|| HttpResponse::NotFound().json(err)),
+        Err(err) => HttpResponse::InternalServerError().json(err)
+    }
+}
+
+#[post("/instances/{instance_id}/resume")]
+async fn resume_tree_instance(instance_service: Data<Arc<TreeInstanceService>>,
+                              cluster: Data<Arc<ClusterService>>,
+                              request: HttpRequest,
+                              instance_id: web::Path<Uuid>) -> impl Responder {
+    match instance_service.resume_instance(&instance_id.0) {
+        Ok(node_ids) => HttpResponse::Ok().json(node_ids),
+        Err(err) => redirect_to_owner(&cluster, &request, &instance_id.0)
+            .unwrap_or_else(|| HttpResponse::NotFound().json(err))
+    }
+}
+
+// The payload is optional, a signal without one only unblocks the waiting nodes.
+#[post("/instances/{instance_id}/signals/{name}")]
+async fn signal_tree_instance(instance_service: Data<Arc<TreeInstanceService>>,
+                              web::Path((instance_id, name)): web::Path<(Uuid, String)>,
+                              payload: Option<web::Json<ValuesPayload>>) -> impl Responder {
+    let payload = payload.map(|payload| payload.0).unwrap_or_else(ValuesPayload::empty);
+
+    match instance_service.signal_instance(&instance_id, &name, payload) {
+        Ok(delivered) => HttpResponse::Ok().json(delivered),
+        Err(err) => HttpResponse::NotFound().json(err)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TasksQuery {
+
+    assignee: Option<String>
+
+}
+
+#[get("/instances/{instance_id}/tasks")]
+async fn list_instance_tasks(instance_service: Data<Arc<TreeInstanceService>>,
+                             instance_id: web::Path<Uuid>,
+                             query: web::Query<TasksQuery>) -> impl Responder {
+    match instance_service.list_tasks(&instance_id.0, query.assignee.as_deref()) {
+        Ok(tasks) => HttpResponse::Ok().json(tasks),
+        Err(err) => HttpResponse::NotFound().json(err)
+    }
+}
+
+#[put("/instances/{instance_id}/tasks/{task_id}/assignee")]
+async fn assign_instance_task(instance_service: Data<Arc<TreeInstanceService>>,
+                              web::Path((instance_id, task_id)): web::Path<(Uuid, Uuid)>,
+                              assignee: web::Json<Option<String>>) -> impl Responder {
+    match instance_service.assign_task(&instance_id, &task_id, assignee.0) {
+        Ok(task) => HttpResponse::Ok().json(task),
+        Err(err) => HttpResponse::NotFound().json(err)
+    }
+}
+
+#[post("/instances/{instance_id}/tasks/{task_id}/complete")]
+async fn complete_instance_task(instance_service: Data<Arc<TreeInstanceService>>,
+                                web::Path((instance_id, task_id)): web::Path<(Uuid, Uuid)>,
+                                form_values: web::Json<serde_json::Value>) -> impl Responder {
+    match instance_service.complete_task(&instance_id, &task_id, &form_values.0) {
+        Ok(values) => HttpResponse::Ok().json(values),
+        Err(err @ TreeInstanceServiceError::HumanTaskError(HumanTaskError::InvalidForm(_, _))) =>
+            HttpResponse::UnprocessableEntity().json(err),
+        Err(err) => HttpResponse::NotFound().json(err)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HistoryQuery {
+
+    since: Option<u64>
+
+}
+
+#[get("/instances/{instance_id}/history")]
+async fn get_instance_history(instance_service: Data<Arc<TreeInstanceService>>,
+                              instance_id: web::Path<Uuid>,
+                              query: web::Query<HistoryQuery>) -> impl Responder {
+    match instance_service.get_history(&instance_id.0, query.since.unwrap_or(0)) {
+        Ok(mutations) => HttpResponse::Ok().json(mutations),
+        Err(err) => HttpResponse::NotFound().json(err)
+    }
+}
+
+#[get("/instances/{instance_id}/history/{sequence}")]
+async fn get_instance_values_at(instance_service: Data<Arc<TreeInstanceService>>,
+                                web::Path((instance_id, sequence)): web::Path<(Uuid, u64)>) -> impl Responder {
+    match instance_service.get_values_at(&instance_id, sequence) {
+        Ok(values) => HttpResponse::Ok().json(values),
+        Err(err @ TreeInstanceServiceError::HistoryCompacted(_, _)) => HttpResponse::Gone().json(err),
+        Err(err) => HttpResponse::NotFound().json(err)
+    }
+}
+
+#[get("/instances/executor/metrics")]
+async fn get_executor_metrics(executor: Data<Arc<ShardedInstanceExecutor>>) -> impl Responder {
+    HttpResponse::Ok().json(executor.get_metrics())
+}
+
+#[derive(Serialize, Deserialize)]
+struct DebugSessionRequest {
+
+    tree_id: i32,
+    payload: HashMap<String, ValueHolder>
+
+}
+
+#[post("/debug")]
+async fn start_debug_session(tree_service: Data<Arc<BehaviorTreeService>>,
+                             debug_service: Data<Arc<DebugSessionService>>,
+                             request: web::Json<DebugSessionRequest>) -> impl Responder {
+    let DebugSessionRequest { tree_id, payload } = request.0;
+    let tree = match tree_service.get_by_id(&tree_id) {
+        None => return HttpResponse::NotFound().finish(),
+        Some(tree) => tree
+    };
+
+    match debug_service.start(&tree, &ValuesPayload::new(payload)).await {
+        Ok(session) => HttpResponse::Created().json(session),
+        Err(err) => HttpResponse::ServiceUnavailable().json(err)
+    }
+}
+
+// Tells which way the active version of the tree goes for the payload, without keeping
+// anything the evaluation does. Trees which cannot be evaluated cannot be explained either.
+#[post("/trees/{tree_id}/explain")]
+async fn explain_tree(tree_service: Data<Arc<BehaviorTreeService>>,
+                      document_service: Data<Arc<DefinitionDocumentService>>,
+                      usage_service: Data<Arc<DefinitionUsageService>>,
+                      request: HttpRequest,
+                      tree_id: web::Path<i32>,
+                      payload: web::Json<HashMap<String, ValueHolder>>) -> impl Responder {
+    let mut response = match check_evaluation(&document_service, &usage_service, &request, &tree_id.0) {
+        Err(response) => return response,
+        Ok(response) => response
+    };
+
+    match tree_service.get_by_id(&tree_id.0) {
+        None => response.status(http::StatusCode::NOT_FOUND).finish(),
+        Some(tree) => response.json(debug::explain(&tree, &ValuesPayload::new(payload.0)).await)
+    }
+}
+
+#[post("/debug/{session_id}/step")]
+async fn step_debug_session(debug_service: Data<Arc<DebugSessionService>>,
+                            session_id: web::Path<Uuid>) -> impl Responder {
+    match debug_service.step(&session_id.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(state) => HttpResponse::Ok().json(state)
+    }
+}
+
+#[delete("/debug/{session_id}")]
+async fn close_debug_session(debug_service: Data<Arc<DebugSessionService>>,
+                             session_id: web::Path<Uuid>) -> impl Responder {
+    match debug_service.close(&session_id.0) {
+        false => HttpResponse::NotFound().finish(),
+        true => HttpResponse::NoContent().finish()
+    }
+}
+
+#[get("/calendars")]
+async fn list_calendars(calendars: Data<Arc<BusinessCalendars>>) -> impl Responder {
+    HttpResponse::Ok().json(calendars.get_names())
+}
+
+#[get("/calendars/{name}")]
+async fn get_calendar(calendars: Data<Arc<BusinessCalendars>>,
+                      name: web::Path<String>) -> impl Responder {
+    match calendars.get_by_name(&name.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(calendar) => HttpResponse::Ok().json(calendar.as_ref())
+    }
+}
+
+// Trees refer to calendars by name, a calendar can be replaced while they run.
+#[put("/calendars/{name}")]
+async fn put_calendar(calendars: Data<Arc<BusinessCalendars>>,
+                      name: web::Path<String>,
+                      calendar: web::Json<BusinessCalendar>) -> impl Responder {
+    calendars.insert(name.0, calendar.0);
+    HttpResponse::NoContent().finish()
+}
+
+#[delete("/calendars/{name}")]
+async fn delete_calendar(calendars: Data<Arc<BusinessCalendars>>,
+                         name: web::Path<String>) -> impl Responder {
+    match calendars.remove(&name.0) {
+        false => HttpResponse::NotFound().finish(),
+        true => HttpResponse::NoContent().finish()
+    }
+}
+
+#[get("/executions/{execution_id}")]
+async fn get_execution(agent_service: Data<Arc<AgentService>>,
+                       execution_id: web::Path<Uuid>) -> impl Responder {
+    match agent_service.get_execution_by_id(&execution_id.0) {
+        None => HttpResponse::NotFound().finish(),
+        Some(execution) => HttpResponse::Ok().json(execution)
+    }
+}
+
+// Errors of the query are reported next to the data, as GraphQL clients expect them.
+#[post("/graphql")]
+async fn query_graphql(schema: Data<ManagementSchema>,
+                       request: web::Json<async_graphql::Request>) -> impl Responder {
+    HttpResponse::Ok().json(schema.execute(request.into_inner()).await)
+}
+
+async fn reload_definitions(loader: &DefinitionFileLoader,
+                            path: &str) {
+    match loader.reload().await {
+        Ok(results) => for (file, result) in results {
+            match result {
+                Ok(_) => {},
+                Err(DefinitionFileError::FileRemoved(tree_id)) =>
+                    warn!("Disabled tree {}, its definition file {} was removed", tree_id, file.display()),
+                Err(err) => warn!("Could not load the definition in {}: {:?}", file.display(), err)
+            }
+        },
+        Err(err) => warn!("Could not read definitions from {}: {}", path, err)
+    }
+}
 
 #[actix_rt::main]
 async fn main() -> std::io::Result<()> {
-    std::env::set_var("RUST_LOG", "info");
+    let config = ServerConfig::from_env()
+        .map_err(|err| std::io::Error::other(format!("{:?}", err)))?;
+
+    std::env::set_var("RUST_LOG", config.get_log_level());
 
     env_logger::init();
 
     let blackboard_service: Arc<LocalBlackboardService> =
-        Arc::new(LocalBlackboardService::default());
+        Arc::new(match config.get_blackboard_snapshot_interval() {
+            None => LocalBlackboardService::default(),
+            Some(snapshot_interval) => LocalBlackboardService::default().with_journals(snapshot_interval)
+        });
     let endpoint_service = Arc::new(EndpointService::new(
         Arbiter::new(),
         blackboard_service.clone()
     ));
 
+    let calendars = Arc::new(BusinessCalendars::default());
+    let static_values = Arc::new(ValuesPayload::new(config.get_static_values().clone()));
     let context_service =
         Arc::new(BTNodeContextService::new(endpoint_service.clone(),
-                                           blackboard_service.clone()));
+                                           blackboard_service.clone())
+            .with_calendars(calendars.clone())
+            .with_static_values(static_values.clone()));
+
+    let tree_service = Arc::new(BehaviorTreeService::default());
 
     let agent_service =
-        test_utils::build_test_agent_service(context_service.clone());
+        test_utils::build_test_agent_service(context_service.clone(), tree_service.clone());
+
+    let instance_service = Arc::new(
+        TreeInstanceService::new(context_service.clone(),
+                                 tree_service.clone(),
+                                 config.get_instance_ttl()));
+
+    let executor = Arc::new(
+        ShardedInstanceExecutor::new(instance_service.clone(),
+                                     config.get_instance_executor_shards(),
+                                     config.get_instance_executor_batch_size())
+            .map(|executor| executor.with_max_pending(config.get_instance_executor_max_pending()))
+            .map_err(|err| std::io::Error::other(format!("{:?}", err)))?);
+
+    let expired_instances_service = instance_service.clone();
+    let instance_ttl = config.get_instance_ttl();
+    actix_rt::spawn(async move {
+        loop {
+            actix_rt::time::sleep(instance_ttl).await;
+            expired_instances_service.collect_expired();
+        }
+    });
+
+    let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+    definition_service.set_read_only(config.is_read_only());
+    let definition_events = Arc::new(DefinitionEventService::default());
+    let building_service = BehaviorTreeBuildingService::new(
+        tree_service.clone(),
+        definition_service.clone())
+        .with_evaluation_budget(config.get_evaluation_budget())
+        .with_static_values(static_values)
+        .with_events(definition_events.clone());
+    let building_service = match config.get_slow_tick_threshold() {
+        None => building_service,
+        Some(threshold) => building_service.with_slow_tick_threshold(threshold)
+    };
+    let building_service = match config.get_error_rate_rollback() {
+        None => building_service,
+        Some(error_rate_rollback) => building_service.with_error_rate_rollback(error_rate_rollback)
+    };
+    let document_service = DefinitionDocumentService::new(definition_service.clone())
+        .with_required_metadata(config.get_required_metadata().clone())
+        .with_limits(config.get_definition_limits())
+        .with_events(definition_events.clone());
+
+    if !config.get_webhook_urls().is_empty() {
+        let notifier = WebhookNotifier::new(config.get_webhook_urls().clone(),
+                                            config.get_webhook_secret().as_deref(),
+                                            config.get_webhook_max_retries());
+        let notifier = match config.get_webhook_outbox_path() {
+            None => notifier,
+            Some(path) => notifier.with_outbox(
+                Outbox::open(path.into())
+                    .map_err(|err| std::io::Error::other(format!("{:?}", err)))?)
+        };
+        notifier.spawn(definition_events.clone())?;
+    }
+
+    let usage_service = Arc::new(
+        match config.get_usage_stats_path() {
+            Some(path) if Path::new(path).exists() => DefinitionUsageService::load(Path::new(path))?,
+            _ => DefinitionUsageService::default()
+        });
+
+    if let Some(path) = config.get_usage_stats_path().clone() {
+        let flushed_usage_service = usage_service.clone();
+        let flush_interval = config.get_usage_stats_flush_interval();
+        actix_rt::spawn(async move {
+            loop {
+                actix_rt::time::sleep(flush_interval).await;
+                if let Err(err) = flushed_usage_service.save(Path::new(&path)) {
+                    warn!("Could not save usage stats to {}: {}", path, err);
+                }
+            }
+        });
+    }
+
+    let building_service = Arc::new(building_service);
+    let document_service = Arc::new(document_service);
+
+    // On its own the server is the only member of its cluster, it is then always the
+    // leader and fires every schedule itself.
+    let (store, node_id): (Arc<dyn SharedStore>, String) = match config.get_cluster() {
+        None => (Arc::new(InMemorySharedStore::default()), Uuid::new_v4().to_string()),
+        Some(cluster) => (
+            Arc::new(RedisSharedStore::open(cluster.get_redis_url())
+                .map_err(|err| std::io::Error::other(format!("{:?}", err)))?),
+            cluster.get_node_id().clone())
+    };
+    let cluster = ClusterService::new(node_id, store, document_service.clone(), building_service.clone());
+    let cluster = match config.get_cluster() {
+        None => cluster,
+        Some(cluster_config) => cluster
+            .with_advertised_url(cluster_config.get_advertised_url().clone())
+            .with_lease(cluster_config.get_lease())
+    };
+    let cluster = Arc::new(cluster);
+
+    cluster.sync().await
+        .map_err(|err| std::io::Error::other(format!("{:?}", err)))?;
+
+    if let Some(cluster_config) = config.get_cluster() {
+        let synced_cluster = cluster.clone();
+        let sync_interval = cluster_config.get_sync_interval();
+        actix_rt::spawn(async move {
+            loop {
+                actix_rt::time::sleep(sync_interval).await;
+                if let Err(err) = synced_cluster.sync().await {
+                    warn!("Could not sync with the cluster: {:?}", err);
+                }
+            }
+        });
+    }
+
+    if config.get_error_rate_rollback().is_some() {
+        let rollback_service = building_service.clone();
+        let check_interval = config.get_rollback_check_interval();
+        actix_rt::spawn(async move {
+            loop {
+                actix_rt::time::sleep(check_interval).await;
+                for (tree_id, rollback) in rollback_service.rollback_error_spikes().await {
+                    match rollback {
+                        Ok(version) => warn!("Rolled back tree {} to version {} after an error spike", tree_id, version),
+                        Err(err) => warn!("Could not roll back tree {} after an error spike: {:?}", tree_id, err)
+                    }
+                }
+            }
+        });
+    }
+
+    if let Some(path) = config.get_definitions_path().clone() {
+        let loader = DefinitionFileLoader::new(Path::new(&path),
+                                               document_service.clone(),
+                                               building_service.clone());
+        reload_definitions(&loader, &path).await;
 
-    let agent_service_data = Data::new(Arc::new(agent_service));
+        let reload_interval = config.get_definitions_reload_interval();
+        actix_rt::spawn(async move {
+            loop {
+                actix_rt::time::sleep(reload_interval).await;
+                reload_definitions(&loader, &path).await;
+            }
+        });
+    }
+
+    for (tree_id, err) in building_service.self_test_served().await {
+        match err {
+            BehaviorTreeBuildingError::SelfTestFailed(_, mismatches) =>
+                warn!("Tree {} does not match {} of its fixtures, it is not served", tree_id, mismatches.len()),
+            err => warn!("Tree {} is not served: {:?}", tree_id, err)
+        }
+    }
+
+    let leader_cluster = cluster.clone();
+    actix_rt::spawn(async move {
+        loop {
+            leader_cluster.renew_leadership();
+            actix_rt::time::sleep(*leader_cluster.get_lease() / 3).await;
+        }
+    });
+
+    if !config.get_schedules().is_empty() {
+        let scheduling_cluster = cluster.clone();
+        let scheduled_tree_service = tree_service.clone();
+        let schedules = config.get_schedules().clone();
+        actix_rt::spawn(async move {
+            let mut since = chrono::Utc::now();
+            loop {
+                actix_rt::time::sleep(Duration::from_secs(1)).await;
+                let until = chrono::Utc::now();
+                scheduling_cluster.fire_schedules(&schedules, &scheduled_tree_service, &since, &until).await;
+                since = until;
+            }
+        });
+    }
+
+    let agent_service = Arc::new(agent_service);
+    let schema_data = Data::new(graphql::build_schema(ManagementServices {
+        document_service: document_service.clone(),
+        definition_service: definition_service.clone(),
+        tree_service: tree_service.clone(),
+        usage_service: usage_service.clone(),
+        instance_service: instance_service.clone(),
+        agent_service: agent_service.clone()
+    }));
+
+    let agent_service_data = Data::new(agent_service);
+    let building_service_data = Data::new(building_service);
+    let document_service_data = Data::new(document_service);
+    let cluster_data = Data::new(cluster);
+    let engine_data = Data::new(Arc::new(ButtercupEngine::new(tree_service.clone(),
+                                                              definition_service.clone())));
+    let tree_service_data = Data::new(tree_service);
+    let definition_service_data = Data::new(definition_service);
+    let usage_service_data = Data::new(usage_service);
+    let debug_service_data = Data::new(Arc::new(DebugSessionService::default()));
+    let calendars_data = Data::new(calendars);
+    let definition_events_data = Data::new(definition_events);
     let endpoints_service_data = Data::new(endpoint_service);
+    let instance_service_data = Data::new(instance_service);
+    let executor_data = Data::new(executor);
+    let config_data = Data::new(config.clone());
 
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         App::new()
             .app_data(endpoints_service_data.clone())
             .app_data(agent_service_data.clone())
+            .app_data(building_service_data.clone())
+            .app_data(document_service_data.clone())
+            .app_data(usage_service_data.clone())
+            .app_data(debug_service_data.clone())
+            .app_data(calendars_data.clone())
+            .app_data(definition_events_data.clone())
+            .app_data(engine_data.clone())
+            .app_data(tree_service_data.clone())
+            .app_data(definition_service_data.clone())
+            .app_data(instance_service_data.clone())
+            .app_data(executor_data.clone())
+            .app_data(cluster_data.clone())
+            .app_data(schema_data.clone())
+            .app_data(config_data.clone())
+            .app_data(web::JsonConfig::default().limit(config_data.get_max_payload_bytes()))
+            .app_data(web::PayloadConfig::new(config_data.get_max_payload_bytes()))
             .service(add_variable_value)
+            .service(generate_argument_examples)
+            .service(evaluate_adhoc)
+            .service(evaluate_trace)
             .service(build_new_agent)
             .service(start_agent)
             .service(stop_agent)
+            .service(get_execution)
+            .service(activate_tree)
+            .service(rollback_tree)
+            .service(self_test_tree)
+            .service(stream_definition_events)
+            .service(list_trees)
+            .service(create_tree)
+            .service(get_tree_usage_metrics)
+            .service(get_tree_footprints)
+            .service(transition_tree)
+            .service(disable_tree)
+            .service(enable_tree)
+            .service(get_read_only_mode)
+            .service(set_read_only_mode)
+            .service(get_tree_usage_stats)
+            .service(get_tree_hits)
+            .service(reset_tree_hits)
+            .service(get_tree_definition)
+            .service(put_tree_definition)
+            .service(get_tree_table)
+            .service(put_tree_table)
+            .service(put_tree_table_csv)
+            .service(put_tree_bpmn)
+            .service(get_tree_btcpp)
+            .service(put_tree_btcpp)
+            .service(evaluate_tree)
+            .service(get_tree_fallback_metrics)
+            .service(create_tree_instance)
+            .service(list_tree_instances)
+            .service(tick_tree_instance)
+            .service(set_instance_breakpoints)
+            .service(get_instance_breakpoints)
+            .service(resume_tree_instance)
+            .service(signal_tree_instance)
+            .service(list_instance_tasks)
+            .service(assign_instance_task)
+            .service(complete_instance_task)
+            .service(get_instance_history)
+            .service(get_instance_values_at)
+            .service(get_executor_metrics)
+            .service(start_debug_session)
+            .service(step_debug_session)
+            .service(explain_tree)
+            .service(close_debug_session)
+            .service(list_calendars)
+            .service(get_calendar)
+            .service(put_calendar)
+            .service(delete_calendar)
+            .service(query_graphql)
             .wrap(middleware::Logger::default())
     })
-        .bind("127.0.0.1:7777")?.run().await
+        .workers(config.get_workers())
+        .keep_alive(config.get_keep_alive_secs())
+        .client_timeout(config.get_client_timeout_millis())
+        .max_connections(config.get_max_connections());
+
+    let server = match (config.is_tcp_enabled(), config.get_tls()) {
+        (false, _) => server,
+        (true, None) => server.bind(config.get_bind_address())?,
+        (true, Some(tls)) => server.bind_rustls(
+            config.get_bind_address(),
+            tls.build().map_err(|err| std::io::Error::other(format!("{:?}", err)))?)?
+    };
+
+    #[cfg(unix)]
+    let server = match config.get_unix_socket_path() {
+        None => server,
+        Some(path) => server.bind_uds(path)?
+    };
+
+    #[cfg(not(unix))]
+    if let Some(path) = config.get_unix_socket_path() {
+        return Result::Err(std::io::Error::other(format!("unix domain sockets are not supported: {}", path)));
+    }
+
+    server.run().await
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, App};
+
+    use buttercup_bts::budget::EvaluationBudget;
+
+    use super::*;
+
+    const DEFINITION: &str = r#"{
+        "id": 5,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [{ "type": "PrintLog", "id": 2, "message": "hello" }]
+    }"#;
+
+    // A cluster of the server alone, as when no redis url is configured.
+    fn standalone_cluster(document_service: Arc<DefinitionDocumentService>) -> Arc<ClusterService> {
+        Arc::new(ClusterService::new("test".to_owned(),
+                                     Arc::new(InMemorySharedStore::default()),
+                                     document_service,
+                                     Arc::new(BehaviorTreeBuildingService::default())))
+    }
+
+    fn put(if_match: Option<&str>) -> test::TestRequest {
+        let request = test::TestRequest::put()
+            .uri("/trees/5/definition")
+            .set_payload(DEFINITION);
+        match if_match {
+            None => request,
+            Some(if_match) => request.header(http::header::IF_MATCH, if_match)
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_puts_definitions_with_and_without_if_match() {
+        let document_service = Arc::new(DefinitionDocumentService::new(
+            Arc::new(BehaviorTreeDefinitionService::default())));
+        let mut app = test::init_service(App::new()
+            .app_data(Data::new(document_service.clone()))
+            .app_data(Data::new(standalone_cluster(document_service)))
+            .service(put_tree_definition)).await;
+
+        let unconditional = test::call_service(&mut app, put(Option::None).to_request()).await;
+        let matching = test::call_service(&mut app, put(Option::Some("\"1\"")).to_request()).await;
+        let stale = test::call_service(&mut app, put(Option::Some("\"1\"")).to_request()).await;
+        let any = test::call_service(&mut app, put(Option::Some("*")).to_request()).await;
+
+        assert_eq!(http::StatusCode::OK, unconditional.status());
+        assert_eq!(Option::Some("\"1\""), unconditional.headers().get(http::header::ETAG)
+            .and_then(|etag| etag.to_str().ok()));
+        assert_eq!(http::StatusCode::OK, matching.status());
+        assert_eq!(http::StatusCode::PRECONDITION_FAILED, stale.status());
+        assert_eq!(http::StatusCode::OK, any.status());
+    }
+
+    // Visits three nodes, one more than the budget of the engine below allows.
+    const FAILING_DEFINITION: &str = r#"{
+        "id": 6,
+        "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+        "nodes": [
+            { "type": "Invert", "id": 2, "child_id": 3 },
+            { "type": "PrintLog", "id": 3, "message": "too far" }
+        ],
+        "fallback_tree_id": 5
+    }"#;
+
+    #[actix_rt::test]
+    async fn test_evaluates_trees_with_fallback() {
+        let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+        let engine = Arc::new(ButtercupEngine::new(Arc::new(BehaviorTreeService::default()),
+                                                   definition_service.clone())
+            .with_evaluation_budget(EvaluationBudget::default().with_max_nodes_visited(2)));
+        engine.load_definition(DEFINITION, 1).await.unwrap();
+        engine.load_definition(FAILING_DEFINITION, 1).await.unwrap();
+        let mut app = test::init_service(App::new()
+            .app_data(Data::new(engine))
+            .app_data(Data::new(Arc::new(DefinitionDocumentService::new(definition_service))))
+            .app_data(Data::new(Arc::new(DefinitionUsageService::default())))
+            .service(evaluate_tree)
+            .service(get_tree_fallback_metrics)).await;
+
+        let evaluation: serde_json::Value = test::read_body_json(test::call_service(
+            &mut app,
+            test::TestRequest::post().uri("/trees/6/evaluate").set_json(&serde_json::json!({})).to_request()).await).await;
+        let missing = test::call_service(
+            &mut app,
+            test::TestRequest::post().uri("/trees/7/evaluate").set_json(&serde_json::json!({})).to_request()).await;
+        let metrics: HashMap<i32, u64> = test::read_body_json(test::call_service(
+            &mut app,
+            test::TestRequest::get().uri("/trees/fallbacks/metrics").to_request()).await).await;
+
+        assert_eq!(serde_json::json!(5), evaluation["tree_id"]);
+        assert_eq!(serde_json::json!("Success"), evaluation["result"]);
+        assert_eq!(http::StatusCode::NOT_FOUND, missing.status());
+        assert_eq!(Option::Some(&1), metrics.get(&6));
+    }
+
+    #[actix_rt::test]
+    async fn test_disables_only_known_trees_and_refuses_to_explain_them() {
+        let definition_service = Arc::new(BehaviorTreeDefinitionService::default());
+        let tree_service = Arc::new(BehaviorTreeService::default());
+        ButtercupEngine::new(tree_service.clone(), definition_service.clone())
+            .load_definition(DEFINITION, 1).await.unwrap();
+        let mut app = test::init_service(App::new()
+            .app_data(Data::new(tree_service))
+            .app_data(Data::new(Arc::new(DefinitionDocumentService::new(definition_service))))
+            .app_data(Data::new(Arc::new(DefinitionUsageService::default())))
+            .service(disable_tree)
+            .service(enable_tree)
+            .service(explain_tree)).await;
+        let explain = || test::TestRequest::post().uri("/trees/5/explain").set_json(&serde_json::json!({}));
+
+        let unknown = test::call_service(
+            &mut app, test::TestRequest::post().uri("/trees/7/disable").to_request()).await;
+        let disabled = test::call_service(
+            &mut app, test::TestRequest::post().uri("/trees/5/disable").to_request()).await;
+        let explained_while_disabled = test::call_service(&mut app, explain().to_request()).await;
+        let enabled = test::call_service(
+            &mut app, test::TestRequest::post().uri("/trees/5/enable").to_request()).await;
+        let explained = test::call_service(&mut app, explain().to_request()).await;
+
+        assert_eq!(http::StatusCode::NOT_FOUND, unknown.status());
+        assert_eq!(http::StatusCode::NO_CONTENT, disabled.status());
+        assert_eq!(http::StatusCode::SERVICE_UNAVAILABLE, explained_while_disabled.status());
+        assert_eq!(http::StatusCode::NO_CONTENT, enabled.status());
+        assert_eq!(http::StatusCode::OK, explained.status());
+    }
+
+    #[actix_rt::test]
+    async fn test_ticks_missing_instances_with_not_found() {
+        let instance_service = Arc::new(TreeInstanceService::new(Arc::new(BTNodeContextService::default()),
+                                                                 Arc::new(BehaviorTreeService::default()),
+                                                                 Duration::from_secs(60)));
+        let document_service = Arc::new(DefinitionDocumentService::new(
+            Arc::new(BehaviorTreeDefinitionService::default())));
+        let mut app = test::init_service(App::new()
+            .app_data(Data::new(Arc::new(ShardedInstanceExecutor::new(instance_service.clone(), 1, 1).unwrap())))
+            .app_data(Data::new(instance_service))
+            .app_data(Data::new(document_service.clone()))
+            .app_data(Data::new(standalone_cluster(document_service)))
+            .app_data(Data::new(Arc::new(DefinitionUsageService::default())))
+            .app_data(Data::new(ServerConfig::default()))
+            .service(tick_tree_instance)).await;
+
+        let response = test::call_service(
+            &mut app,
+            test::TestRequest::post().uri(&format!("/instances/{}/tick", Uuid::new_v4())).to_request()).await;
+
+        assert_eq!(http::StatusCode::NOT_FOUND, response.status());
+    }
+
 }
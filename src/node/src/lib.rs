@@ -0,0 +1,62 @@
+#![cfg(feature = "node")]
+
+use futures::executor::block_on;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use buttercup_api::document::parse_payload;
+use buttercup_api::engine::ButtercupEngine;
+use buttercup_bts::tick::TickStatus;
+
+// Payloads and definitions are the same json documents the server and the test vectors
+// use, so the gateway can check both before sending them on.
+#[napi(js_name = "Engine")]
+pub struct NodeEngine {
+
+    engine: ButtercupEngine
+
+}
+
+#[napi]
+impl NodeEngine {
+
+    #[napi(constructor)]
+    pub fn new() -> NodeEngine {
+        NodeEngine {
+            engine: ButtercupEngine::default()
+        }
+    }
+
+    #[napi]
+    pub fn load_definition(&self,
+                           json: String,
+                           version: u32) -> Result<i32> {
+        block_on(self.engine.load_definition(&json, version)).map_err(to_napi_error)
+    }
+
+    // Returns the reason the definition does not build, or null when it does.
+    #[napi]
+    pub fn validate_definition(&self,
+                               json: String) -> Option<String> {
+        self.engine
+            .validate_definition(&json)
+            .err()
+            .map(|err| format!("{:?}", err))
+    }
+
+    #[napi]
+    pub fn evaluate(&self,
+                    tree_id: i32,
+                    payload: String) -> Result<bool> {
+        let payload = parse_payload(&payload).map_err(to_napi_error)?;
+
+        block_on(self.engine.evaluate(&tree_id, &payload))
+            .map(|status| status == TickStatus::Success)
+            .map_err(to_napi_error)
+    }
+
+}
+
+fn to_napi_error(err: impl std::fmt::Debug) -> Error {
+    Error::from_reason(format!("{:?}", err))
+}
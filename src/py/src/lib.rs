@@ -0,0 +1,109 @@
+#![cfg(feature = "python")]
+
+use std::collections::HashMap;
+
+use futures::executor::block_on;
+use num::{BigInt, BigRational};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyLong, PyString};
+
+use buttercup_api::engine::{ButtercupEngine, EngineError};
+use buttercup_bts::tick::TickStatus;
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+// Evaluates trees with the same engine the server runs, a data frame is evaluated row by
+// row with engine.evaluate_records(tree_id, frame.to_dict("records")).
+#[pyclass(name = "Engine", frozen)]
+#[derive(Default)]
+struct PyEngine {
+
+    engine: ButtercupEngine
+
+}
+
+#[pymethods]
+impl PyEngine {
+
+    #[new]
+    fn new() -> PyEngine {
+        PyEngine::default()
+    }
+
+    fn load_definition(&self,
+                       json: &str,
+                       version: u32) -> PyResult<i32> {
+        block_on(self.engine.load_definition(json, version)).map_err(to_py_err)
+    }
+
+    fn self_test(&self,
+                 tree_id: i32) -> PyResult<Vec<usize>> {
+        Result::Ok(
+            block_on(self.engine.self_test(&tree_id))
+                .map_err(to_py_err)?
+                .iter()
+                .map(|mismatch| *mismatch.get_fixture_index())
+                .collect())
+    }
+
+    fn evaluate(&self,
+                tree_id: i32,
+                payload: &Bound<'_, PyDict>) -> PyResult<bool> {
+        let payload = to_payload(payload)?;
+
+        block_on(self.engine.evaluate(&tree_id, &payload))
+            .map(|status| status == TickStatus::Success)
+            .map_err(to_py_err)
+    }
+
+    fn evaluate_records(&self,
+                        tree_id: i32,
+                        records: Vec<Bound<'_, PyDict>>) -> PyResult<Vec<bool>> {
+        records.iter()
+            .map(|record| self.evaluate(tree_id, record))
+            .collect()
+    }
+
+}
+
+fn to_payload(dict: &Bound<'_, PyDict>) -> PyResult<ValuesPayload> {
+    let mut values = HashMap::new();
+
+    for (name, value) in dict.iter() {
+        if let Some(value) = to_value_holder(&value)? {
+            values.insert(name.str()?.to_string(), value);
+        }
+    }
+
+    Result::Ok(ValuesPayload::new(values))
+}
+
+// None and NaN, which pandas uses for missing cells, leave the value out of the payload.
+fn to_value_holder(value: &Bound<'_, PyAny>) -> PyResult<Option<ValueHolder>> {
+    if value.is_none() {
+        Result::Ok(Option::None)
+    } else if value.is_instance_of::<PyBool>() {
+        Result::Ok(Option::Some(ValueHolder::Boolean(value.extract()?)))
+    } else if value.is_instance_of::<PyLong>() {
+        value.str()?
+            .to_str()?
+            .parse::<BigInt>()
+            .map(|value| Option::Some(ValueHolder::Integer(value)))
+            .map_err(|err| PyTypeError::new_err(err.to_string()))
+    } else if value.is_instance_of::<PyFloat>() {
+        Result::Ok(BigRational::from_float(value.extract::<f64>()?).map(ValueHolder::Decimal))
+    } else if value.is_instance_of::<PyString>() {
+        Result::Ok(Option::Some(ValueHolder::from(value.extract::<String>()?)))
+    } else {
+        Result::Err(PyTypeError::new_err(format!("unsupported value type: {}", value.get_type().name()?)))
+    }
+}
+
+fn to_py_err(err: EngineError) -> PyErr {
+    PyRuntimeError::new_err(format!("{:?}", err))
+}
+
+#[pymodule]
+fn buttercup(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyEngine>()
+}
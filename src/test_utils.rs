@@ -16,9 +16,8 @@ use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper, Logi
 use buttercup_conditions::relational::{EndsWithRelationalExpression, StartsWithRelationalExpression};
 use buttercup_endpoints::endpoints::EndpointService;
 
-pub fn build_test_agent_service(context_service: Arc<BTNodeContextService>) -> AgentService {
-    let tree_service = Arc::new(BehaviorTreeService::default());
-
+pub fn build_test_agent_service(context_service: Arc<BTNodeContextService>,
+                                tree_service: Arc<BehaviorTreeService>) -> AgentService {
     add_test_trees(tree_service.as_ref());
 
     AgentService::new(context_service, tree_service).unwrap()
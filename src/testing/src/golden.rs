@@ -0,0 +1,34 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+pub const UPDATE_GOLDEN_VAR: &str = "BUTTERCUP_UPDATE_GOLDEN";
+
+// Compares the pretty printed json of the value with the golden file. Missing files are
+// written, as are all files when BUTTERCUP_UPDATE_GOLDEN is set, so a changed output is
+// accepted by running the tests once with it and reviewing the diff.
+pub fn assert_golden<T: Serialize>(path: impl AsRef<Path>,
+                                   value: &T) {
+    let path = path.as_ref();
+    let actual = serde_json::to_string_pretty(value)
+        .unwrap_or_else(|err| panic!("Could not serialize value for {}: {}", path.display(), err));
+
+    if env::var_os(UPDATE_GOLDEN_VAR).is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .unwrap_or_else(|err| panic!("Could not create {}: {}", parent.display(), err));
+        }
+        fs::write(path, actual + "\n")
+            .unwrap_or_else(|err| panic!("Could not write {}: {}", path.display(), err));
+        return;
+    }
+
+    let expected = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("Could not read {}: {}", path.display(), err));
+
+    assert_eq!(expected.trim_end(), actual,
+               "{} does not match, rerun with {} set to accept the change",
+               path.display(), UPDATE_GOLDEN_VAR);
+}
@@ -0,0 +1,137 @@
+use futures::executor::block_on;
+
+use buttercup_api::bts::BehaviorTreeBuildingService;
+use buttercup_api::document::BehaviorTreeDocument;
+use buttercup_bts::debug::DebugEvent;
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_bts::tree::BehaviorTree;
+use buttercup_values::ValuesPayload;
+
+pub mod golden;
+pub mod payload;
+
+#[macro_export]
+macro_rules! assert_evaluates {
+    ($tree:expr, $payload:expr, $status:expr) => {
+        assert_eq!(Result::Ok($status),
+                   $crate::evaluate(&$tree, &$payload),
+                   "tree {} evaluated with {:?}", $tree.get_id(), $payload)
+    };
+}
+
+#[macro_export]
+macro_rules! assert_selects {
+    ($tree:expr, $payload:expr, [$($node_id:expr),* $(,)?]) => {
+        {
+            let expected: Vec<i32> = vec![$($node_id),*];
+            assert_eq!(Result::Ok(expected),
+                       $crate::select(&$tree, &$payload),
+                       "tree {} evaluated with {:?}", $tree.get_id(), $payload)
+        }
+    };
+}
+
+// Builds the tree the same way activation does, subtrees are not available.
+pub fn load_tree(json: &str) -> BehaviorTree {
+    let document = BehaviorTreeDocument::from_json(json)
+        .unwrap_or_else(|err| panic!("Could not read tree definition: {:?}", err));
+
+    BehaviorTreeBuildingService::default()
+        .build_definition(&document.into())
+        .unwrap_or_else(|err| panic!("Could not build tree definition: {:?}", err))
+}
+
+pub fn evaluate(tree: &BehaviorTree,
+                payload: &ValuesPayload) -> Result<TickStatus, TickError> {
+    block_on(tree.evaluate(payload))
+}
+
+// What the tree chose, the ids of the nodes which succeeded without ticking any child
+// themselves, in the order they were ticked. Fails with the error of the evaluation.
+pub fn select(tree: &BehaviorTree,
+              payload: &ValuesPayload) -> Result<Vec<i32>, TickError> {
+    let (result, events) = block_on(tree.evaluate_debugged(payload));
+    result?;
+
+    // Entered nodes, with whether they ticked any child.
+    let mut entered: Vec<bool> = Vec::new();
+    let mut selected = Vec::new();
+    for event in events {
+        match event {
+            DebugEvent::NodeEntered(_) => {
+                if let Some(has_children) = entered.last_mut() {
+                    *has_children = true;
+                }
+                entered.push(false);
+            },
+            DebugEvent::NodeExited(node_id, status) => {
+                if entered.pop() == Option::Some(false) && status == Result::Ok(TickStatus::Success) {
+                    selected.push(node_id);
+                }
+            },
+            DebugEvent::ConditionEvaluated(_, _) => {}
+        }
+    }
+    Result::Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::golden::assert_golden;
+    use crate::payload::PayloadBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_evaluates_loaded_trees() {
+        let tree = load_tree(r#"{
+            "id": 1,
+            "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+            "nodes": [{ "type": "Invert", "id": 2, "child_id": 3 },
+                      { "type": "PrintLog", "id": 3, "message": "hello" }]
+        }"#);
+        let payload = PayloadBuilder::new()
+            .with_boolean("flag", true)
+            .with_integer("count", 3)
+            .build();
+
+        assert_evaluates!(tree, payload, TickStatus::Failure);
+    }
+
+    #[test]
+    fn test_asserts_what_the_tree_selects() {
+        let tree = load_tree(r#"{
+            "id": 1,
+            "root": { "type": "OneOff", "id": 1, "child_id": 2 },
+            "nodes": [
+                { "type": "Fallback", "id": 2, "children_ids": [3, 5] },
+                { "type": "Condition", "id": 3, "child_id": 4, "expression": { "RelationExpression": { "Equals": { "specification": { "NameAndLiteral": ["tier", { "String": "gold" }] } } } } },
+                { "type": "SetValue", "id": 4, "value_name": "discount", "value": { "Boolean": true } },
+                { "type": "SetValue", "id": 5, "value_name": "discount", "value": { "Boolean": false } }
+            ]
+        }"#);
+
+        assert_selects!(tree, PayloadBuilder::new().with_string("tier", "gold").build(), [4]);
+        assert_selects!(tree, PayloadBuilder::new().with_string("tier", "silver").build(), [5]);
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(||
+            assert_selects!(tree, PayloadBuilder::new().with_string("tier", "gold").build(), [5]))).is_err());
+    }
+
+    #[test]
+    fn test_writes_and_compares_golden_files() {
+        let directory = std::env::temp_dir().join(format!("buttercup-golden-{}", std::process::id()));
+        let path = directory.join("payload.json");
+        let payload = PayloadBuilder::new().with_string("name", "value").build();
+
+        assert_golden(&path, &payload);
+        assert_golden(&path, &payload);
+
+        let changed = PayloadBuilder::new().with_decimal("name", 1, 2).build();
+        assert!(std::panic::catch_unwind(|| assert_golden(&path, &changed)).is_err());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use num::{BigInt, BigRational};
+
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+#[derive(Default)]
+pub struct PayloadBuilder {
+
+    values: HashMap<String, ValueHolder>
+
+}
+
+impl PayloadBuilder {
+
+    pub fn new() -> PayloadBuilder {
+        PayloadBuilder::default()
+    }
+
+    pub fn with_value(mut self,
+                      name: &str,
+                      value: ValueHolder) -> PayloadBuilder {
+        self.values.insert(name.to_owned(), value);
+        self
+    }
+
+    pub fn with_boolean(self,
+                        name: &str,
+                        value: bool) -> PayloadBuilder {
+        self.with_value(name, ValueHolder::Boolean(value))
+    }
+
+    pub fn with_decimal(self,
+                        name: &str,
+                        numerator: i64,
+                        denominator: i64) -> PayloadBuilder {
+        self.with_value(name,
+                        ValueHolder::Decimal(
+                            BigRational::new(BigInt::from(numerator), BigInt::from(denominator))))
+    }
+
+    pub fn with_integer(self,
+                        name: &str,
+                        value: i64) -> PayloadBuilder {
+        self.with_value(name, ValueHolder::Integer(BigInt::from(value)))
+    }
+
+    pub fn with_string(self,
+                       name: &str,
+                       value: &str) -> PayloadBuilder {
+        self.with_value(name, ValueHolder::String(Arc::new(value.to_owned())))
+    }
+
+    pub fn build(self) -> ValuesPayload {
+        ValuesPayload::new(self.values)
+    }
+
+}
@@ -0,0 +1,70 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use buttercup_api::document::BehaviorTreeDocument;
+use buttercup_bts::tick::{TickError, TickStatus};
+use buttercup_test::payload::PayloadBuilder;
+use buttercup_test::{evaluate, load_tree};
+use buttercup_values::ValuesPayload;
+
+// Every directory of the corpus holds the definitions as they were stored by one version
+// of the document format, fields added since are filled in by their defaults. A file has
+// to evaluate the same in all of the versions it is in, as read and once written back.
+fn get_corpus() -> Vec<PathBuf> {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut paths = Vec::new();
+
+    for version in fs::read_dir(&directory).unwrap() {
+        for entry in fs::read_dir(version.unwrap().path()).unwrap() {
+            paths.push(entry.unwrap().path());
+        }
+    }
+
+    paths.sort();
+    paths
+}
+
+fn get_payloads() -> Vec<ValuesPayload> {
+    vec![
+        PayloadBuilder::new().with_integer("age", 30).with_string("country", "PL").build(),
+        PayloadBuilder::new().with_integer("age", 18).with_string("country", "PL").build(),
+        PayloadBuilder::new().with_integer("age", 17).with_string("country", "PL").build(),
+        PayloadBuilder::new().with_integer("age", 30).with_string("country", "DE").build(),
+        PayloadBuilder::new().with_string("age", "30").with_string("country", "PL").build(),
+        PayloadBuilder::new().with_string("country", "PL").build()
+    ]
+}
+
+fn evaluate_all(path: &Path,
+                json: &str) -> Vec<Result<TickStatus, TickError>> {
+    let tree = load_tree(json);
+    assert_eq!(&1, tree.get_id(), "{}", path.display());
+
+    get_payloads().iter().map(|payload| evaluate(&tree, payload)).collect()
+}
+
+#[test]
+fn test_evaluates_every_version_of_the_corpus_identically() {
+    let corpus = get_corpus();
+    assert!(corpus.len() >= 3);
+
+    // Adults from Poland only, ages have to be sent as integers.
+    let expected = vec![
+        Result::Ok(TickStatus::Success),
+        Result::Ok(TickStatus::Success),
+        Result::Ok(TickStatus::Failure),
+        Result::Ok(TickStatus::Failure),
+        Result::Ok(TickStatus::Failure),
+        Result::Ok(TickStatus::Failure)
+    ];
+
+    for path in corpus {
+        let json = fs::read_to_string(&path).unwrap();
+        let document = BehaviorTreeDocument::from_json(&json)
+            .unwrap_or_else(|err| panic!("Could not deserialize {}: {:?}", path.display(), err));
+        let rewritten = serde_json::to_string(&document).unwrap();
+
+        assert_eq!(expected, evaluate_all(&path, &json), "{}", path.display());
+        assert_eq!(expected, evaluate_all(&path, &rewritten), "{} written back", path.display());
+    }
+}
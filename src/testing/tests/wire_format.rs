@@ -0,0 +1,34 @@
+use std::fs;
+use std::path::PathBuf;
+
+use buttercup_api::document::{BehaviorTreeDocument, RootDefinitionDocument};
+use buttercup_test::golden::assert_golden;
+
+// The golden files are stored definitions, which have to be read and written back
+// unchanged, so that a change of the wire format shows up in either direction. Sets and
+// maps in them have a single entry each, their order is not deterministic.
+fn read_golden(name: &str) -> (PathBuf, String) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name);
+    let json = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("Could not read {}: {}", path.display(), err));
+
+    (path, json)
+}
+
+#[test]
+fn test_keeps_wire_format_of_definitions() {
+    let (path, json) = read_golden("definition.json");
+    let document = BehaviorTreeDocument::from_json(&json)
+        .unwrap_or_else(|err| panic!("Could not deserialize {}: {:?}", path.display(), err));
+
+    assert_golden(&path, &document);
+}
+
+#[test]
+fn test_keeps_wire_format_of_roots() {
+    let (path, json) = read_golden("roots.json");
+    let roots: Vec<RootDefinitionDocument> = serde_json::from_str(&json)
+        .unwrap_or_else(|err| panic!("Could not deserialize {}: {}", path.display(), err));
+
+    assert_golden(&path, &roots);
+}
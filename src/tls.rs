@@ -0,0 +1,158 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+
+use rustls::{AllowAnyAuthenticatedClient, Certificate, NoClientAuth, PrivateKey, ResolvesServerCertUsingSNI, RootCertStore};
+use rustls::internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
+use rustls::sign::{any_supported_type, CertifiedKey};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum TlsConfigError {
+
+    InvalidCertificate(String),
+    InvalidClientCa(String),
+    InvalidPrivateKey(String),
+    InvalidSniEntry(String),
+    IOError(String, String)
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct SniCertificate {
+
+    server_name: String,
+    cert_path: String,
+    key_path: String
+
+}
+
+impl SniCertificate {
+
+    pub fn new(server_name: String,
+               cert_path: String,
+               key_path: String) -> SniCertificate {
+        SniCertificate {
+            server_name,
+            cert_path,
+            key_path
+        }
+    }
+
+}
+
+// Without sni certificates the default certificate is served for every connection,
+// with them the default certificate is not used and unknown server names are refused.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct TlsConfig {
+
+    cert_path: String,
+    key_path: String,
+    client_ca_path: Option<String>,
+    sni_certificates: Vec<SniCertificate>
+
+}
+
+impl TlsConfig {
+
+    pub fn new(cert_path: String,
+               key_path: String) -> TlsConfig {
+        TlsConfig {
+            cert_path,
+            key_path,
+            client_ca_path: Option::None,
+            sni_certificates: Vec::new()
+        }
+    }
+
+    pub fn with_client_ca_path(mut self,
+                               client_ca_path: String) -> TlsConfig {
+        self.client_ca_path = Option::Some(client_ca_path);
+        self
+    }
+
+    pub fn with_sni_certificates(mut self,
+                                 sni_certificates: Vec<SniCertificate>) -> TlsConfig {
+        self.sni_certificates = sni_certificates;
+        self
+    }
+
+    pub fn build(&self) -> Result<rustls::ServerConfig, TlsConfigError> {
+        let mut config = match &self.client_ca_path {
+            None => rustls::ServerConfig::new(NoClientAuth::new()),
+            Some(client_ca_path) => {
+                let mut roots = RootCertStore::empty();
+                let (valid, _) = roots.add_pem_file(&mut open(client_ca_path)?)
+                    .map_err(|_| TlsConfigError::InvalidClientCa(client_ca_path.clone()))?;
+                if valid == 0 {
+                    return Result::Err(TlsConfigError::InvalidClientCa(client_ca_path.clone()));
+                }
+                rustls::ServerConfig::new(AllowAnyAuthenticatedClient::new(roots))
+            }
+        };
+
+        if self.sni_certificates.is_empty() {
+            config.set_single_cert(load_certificates(&self.cert_path)?,
+                                   load_private_key(&self.key_path)?)
+                .map_err(|err| TlsConfigError::InvalidPrivateKey(err.to_string()))?;
+        } else {
+            let mut resolver = ResolvesServerCertUsingSNI::new();
+            for sni in &self.sni_certificates {
+                let key = any_supported_type(&load_private_key(&sni.key_path)?)
+                    .map_err(|_| TlsConfigError::InvalidPrivateKey(sni.key_path.clone()))?;
+                resolver.add(&sni.server_name,
+                             CertifiedKey::new(load_certificates(&sni.cert_path)?, Arc::new(key)))
+                    .map_err(|err| TlsConfigError::InvalidSniEntry(err.to_string()))?;
+            }
+            config.cert_resolver = Arc::new(resolver);
+        }
+
+        Result::Ok(config)
+    }
+
+}
+
+fn open(path: &str) -> Result<BufReader<File>, TlsConfigError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|err| TlsConfigError::IOError(path.to_owned(), err.to_string()))
+}
+
+fn load_certificates(path: &str) -> Result<Vec<Certificate>, TlsConfigError> {
+    match certs(&mut open(path)?) {
+        Ok(certificates) if !certificates.is_empty() => Result::Ok(certificates),
+        _ => Result::Err(TlsConfigError::InvalidCertificate(path.to_owned()))
+    }
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKey, TlsConfigError> {
+    let mut keys = pkcs8_private_keys(&mut open(path)?).unwrap_or_default();
+    if keys.is_empty() {
+        keys = rsa_private_keys(&mut open(path)?).unwrap_or_default();
+    }
+
+    keys.into_iter()
+        .next()
+        .ok_or_else(|| TlsConfigError::InvalidPrivateKey(path.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_missing_and_invalid_files() {
+        assert!(matches!(TlsConfig::new("missing.pem".to_owned(), "missing.key".to_owned()).build(),
+                         Result::Err(TlsConfigError::IOError(path, _)) if path == "missing.pem"));
+        assert_eq!(Result::Err(TlsConfigError::InvalidCertificate("Cargo.toml".to_owned())),
+                   TlsConfig::new("Cargo.toml".to_owned(), "Cargo.toml".to_owned())
+                       .build()
+                       .map(|_| ()));
+        assert_eq!(Result::Err(TlsConfigError::InvalidClientCa("Cargo.toml".to_owned())),
+                   TlsConfig::new("Cargo.toml".to_owned(), "Cargo.toml".to_owned())
+                       .with_client_ca_path("Cargo.toml".to_owned())
+                       .build()
+                       .map(|_| ()));
+    }
+
+}
@@ -0,0 +1,60 @@
+use buttercup_values::ValueHolder;
+use num::BigInt;
+
+use crate::transformer::{InputOrder, TransformationError};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+pub struct HashBucketAssignment;
+
+impl HashBucketAssignment {
+
+    pub fn assign(value: &ValueHolder,
+                  num_buckets: u32) -> Result<ValueHolder, TransformationError> {
+        if num_buckets == 0 {
+            return Result::Err(TransformationError::InvalidBucketCount(num_buckets));
+        }
+        match value {
+            ValueHolder::Uuid(uuid) => {
+                let hash = HashBucketAssignment::fnv1a(uuid.as_bytes());
+                Result::Ok(ValueHolder::Integer(BigInt::from(hash % u64::from(num_buckets))))
+            },
+            _ => Result::Err(
+                TransformationError::InvalidInputType(Box::new(value.clone()), InputOrder::First))
+        }
+    }
+
+    // FNV-1a is used instead of the std hasher as bucket assignments have to
+    // stay the same across releases and platforms.
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        bytes.iter()
+            .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_assigns_stable_buckets() {
+        let uuid = ValueHolder::Uuid(Uuid::from_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap());
+
+        assert_eq!(HashBucketAssignment::fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(HashBucketAssignment::assign(&uuid, 100).unwrap(),
+                   HashBucketAssignment::assign(&uuid, 100).unwrap());
+        match HashBucketAssignment::assign(&uuid, 10).unwrap() {
+            ValueHolder::Integer(bucket) => assert!(bucket < BigInt::from(10)),
+            other => panic!("Unexpected bucket: {:?}", other)
+        }
+        assert!(matches!(HashBucketAssignment::assign(&uuid, 0),
+                         Result::Err(TransformationError::InvalidBucketCount(0))));
+    }
+
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use buttercup_values::ValueHolder;
+use buttercup_values::quantity::{Quantity, Unit};
+use num::{BigRational, FromPrimitive, One, Zero};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::transformer::{InputOrder, TransformationError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ExchangeRateError {
+
+    InvalidResponse(String),
+    MissingRate(String, String),
+    RatesExpired,
+    RatesNotLoaded,
+    RequestFailed(String)
+
+}
+
+pub trait ExchangeRateProvider: Send + Sync {
+
+    fn get_rate(&self,
+                from: &str,
+                to: &str) -> Result<BigRational, ExchangeRateError>;
+
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StaticExchangeRateProvider {
+
+    rates: HashMap<(String, String), BigRational>
+
+}
+
+impl StaticExchangeRateProvider {
+
+    pub fn new() -> StaticExchangeRateProvider {
+        Default::default()
+    }
+
+    pub fn with_rate(mut self,
+                     from: &str,
+                     to: &str,
+                     rate: BigRational) -> StaticExchangeRateProvider {
+        self.rates.insert((from.to_owned(), to.to_owned()), rate);
+        self
+    }
+
+}
+
+impl ExchangeRateProvider for StaticExchangeRateProvider {
+
+    fn get_rate(&self,
+                from: &str,
+                to: &str) -> Result<BigRational, ExchangeRateError> {
+        if from == to {
+            return Result::Ok(BigRational::one());
+        }
+        if let Option::Some(rate) = self.rates.get(&(from.to_owned(), to.to_owned())) {
+            return Result::Ok(rate.clone());
+        }
+        match self.rates.get(&(to.to_owned(), from.to_owned())) {
+            Option::Some(rate) if !rate.is_zero() => Result::Ok(rate.recip()),
+            _ => Result::Err(ExchangeRateError::MissingRate(from.to_owned(), to.to_owned()))
+        }
+    }
+
+}
+
+// Rates are served from the cache, so transformations never block on the network.
+// Nothing refreshes them on its own: the owner of the provider has to call refresh
+// more often than max age, rates older than that are refused as expired. The source
+// is expected to respond with {"base": "EUR", "rates": {"USD": 1.08, ...}}.
+pub struct HttpExchangeRateProvider {
+
+    url: String,
+    max_age: Duration,
+    cached: RwLock<Option<CachedExchangeRates>>
+
+}
+
+struct CachedExchangeRates {
+
+    fetched_at: Instant,
+    base: String,
+    rates: HashMap<String, BigRational>
+
+}
+
+impl HttpExchangeRateProvider {
+
+    pub fn new(url: String,
+               max_age: Duration) -> HttpExchangeRateProvider {
+        HttpExchangeRateProvider {
+            url,
+            max_age,
+            cached: RwLock::new(Option::None)
+        }
+    }
+
+    pub async fn refresh(&self) -> Result<(), ExchangeRateError> {
+        let mut response = awc::Client::default()
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|err| ExchangeRateError::RequestFailed(err.to_string()))?;
+        let body: Value = response.json()
+            .await
+            .map_err(|err| ExchangeRateError::InvalidResponse(err.to_string()))?;
+        let rates = HttpExchangeRateProvider::parse(&body)?;
+        *self.cached.write().unwrap() = Option::Some(rates);
+        Result::Ok(())
+    }
+
+    fn parse(body: &Value) -> Result<CachedExchangeRates, ExchangeRateError> {
+        let base = body.get("base")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ExchangeRateError::InvalidResponse("Missing base currency.".to_owned()))?;
+        let rates = body.get("rates")
+            .and_then(Value::as_object)
+            .ok_or_else(|| ExchangeRateError::InvalidResponse("Missing rates.".to_owned()))?
+            .iter()
+            .filter_map(|(currency, rate)| rate.as_f64()
+                .and_then(BigRational::from_f64)
+                .map(|rate| (currency.clone(), rate)))
+            .collect();
+        Result::Ok(CachedExchangeRates {
+            fetched_at: Instant::now(),
+            base: base.to_owned(),
+            rates
+        })
+    }
+
+}
+
+impl CachedExchangeRates {
+
+    fn get_base_rate(&self,
+                     currency: &str) -> Option<BigRational> {
+        if currency == self.base {
+            return Option::Some(BigRational::one());
+        }
+        self.rates.get(currency)
+            .filter(|rate| !rate.is_zero())
+            .cloned()
+    }
+
+}
+
+impl ExchangeRateProvider for HttpExchangeRateProvider {
+
+    fn get_rate(&self,
+                from: &str,
+                to: &str) -> Result<BigRational, ExchangeRateError> {
+        let cached = self.cached.read().unwrap();
+        match cached.as_ref() {
+            Option::None => Result::Err(ExchangeRateError::RatesNotLoaded),
+            Option::Some(cached) if cached.fetched_at.elapsed() > self.max_age =>
+                Result::Err(ExchangeRateError::RatesExpired),
+            Option::Some(cached) =>
+                match (cached.get_base_rate(from), cached.get_base_rate(to)) {
+                    (Option::Some(from_rate), Option::Some(to_rate)) =>
+                        Result::Ok(to_rate / from_rate),
+                    (_, _) =>
+                        Result::Err(ExchangeRateError::MissingRate(from.to_owned(), to.to_owned()))
+                }
+        }
+    }
+
+}
+
+pub struct CurrencyConversion;
+
+impl CurrencyConversion {
+
+    pub fn convert(value: &ValueHolder,
+                   target_currency: &str,
+                   provider: &dyn ExchangeRateProvider) -> Result<ValueHolder, TransformationError> {
+        match value {
+            ValueHolder::Quantity(quantity) => match quantity.get_unit() {
+                Unit::Currency(currency) => provider.get_rate(currency, target_currency)
+                    .map(|rate| ValueHolder::Quantity(
+                        Quantity::new(quantity.get_amount() * rate,
+                                      Unit::Currency(target_currency.to_owned()))))
+                    .map_err(TransformationError::ExchangeRateError),
+                unit => Result::Err(
+                    TransformationError::UnitMismatch(
+                        unit.clone(), Unit::Currency(target_currency.to_owned())))
+            },
+            _ => Result::Err(
+                TransformationError::InvalidInputType(Box::new(value.clone()), InputOrder::First))
+        }
+    }
+
+}
+
+pub(crate) fn no_exchange_rates() -> Arc<dyn ExchangeRateProvider> {
+    Arc::new(StaticExchangeRateProvider::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigInt;
+    use serde_json::json;
+
+    use super::*;
+
+    fn rational(numerator: i64, denominator: i64) -> BigRational {
+        BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    #[test]
+    fn test_static_rates() {
+        let provider = StaticExchangeRateProvider::new()
+            .with_rate("EUR", "USD", rational(11, 10));
+
+        assert_eq!(Result::Ok(rational(11, 10)), provider.get_rate("EUR", "USD"));
+        assert_eq!(Result::Ok(rational(10, 11)), provider.get_rate("USD", "EUR"));
+        assert_eq!(Result::Err(ExchangeRateError::MissingRate("USD".to_owned(), "PLN".to_owned())),
+                   provider.get_rate("USD", "PLN"));
+    }
+
+    #[test]
+    fn test_converts_currency_quantities_only() {
+        let provider = StaticExchangeRateProvider::new()
+            .with_rate("EUR", "PLN", rational(43, 10));
+        let euros = ValueHolder::Quantity(
+            Quantity::new(BigRational::from_integer(BigInt::from(10)),
+                          Unit::Currency("EUR".to_owned())));
+        let percent = ValueHolder::Quantity(
+            Quantity::new(BigRational::from_integer(BigInt::from(10)), Unit::Percent));
+
+        assert_eq!(ValueHolder::Quantity(
+            Quantity::new(BigRational::from_integer(BigInt::from(43)),
+                          Unit::Currency("PLN".to_owned()))),
+                   CurrencyConversion::convert(&euros, "PLN", &provider).unwrap());
+        assert!(matches!(CurrencyConversion::convert(&percent, "PLN", &provider),
+                         Result::Err(TransformationError::UnitMismatch(Unit::Percent, _))));
+    }
+
+    #[test]
+    fn test_http_provider_serves_cross_rates_from_cache() {
+        let provider = HttpExchangeRateProvider::new(
+            "http://localhost/rates".to_owned(), Duration::from_secs(60));
+
+        assert_eq!(Result::Err(ExchangeRateError::RatesNotLoaded), provider.get_rate("USD", "PLN"));
+
+        *provider.cached.write().unwrap() = Option::Some(
+            HttpExchangeRateProvider::parse(
+                &json!({"base": "EUR", "rates": {"USD": 1.25, "PLN": 5}})).unwrap());
+
+        assert_eq!(Result::Ok(rational(4, 1)), provider.get_rate("USD", "PLN"));
+        assert_eq!(Result::Ok(rational(4, 5)), provider.get_rate("USD", "EUR"));
+    }
+
+}
@@ -34,9 +34,9 @@ impl Astro {
                     zdt.get_zone(),
                     &coordinates)),
             (ValueHolder::ZonedDateTime(_), _) =>  Result::Err(
-                TransformationError::InvalidInputType(second.clone(), InputOrder::Second)),
+                TransformationError::InvalidInputType(Box::new(second.clone()), InputOrder::Second)),
             (_, _ )=> Result::Err(
-                TransformationError::InvalidInputType(first.clone(), InputOrder::First))
+                TransformationError::InvalidInputType(Box::new(first.clone()), InputOrder::First))
         };
     }
 
@@ -25,10 +25,10 @@ impl DiInputTransformer for LocalToZonedDateTime {
                             ZonedDateTime::new(*date_time, *time_zone.get()))
                     ),
                 _ => Result::Err(
-                    TransformationError::InvalidInputType(first.clone(), InputOrder::Second))
+                    TransformationError::InvalidInputType(Box::new(first.clone()), InputOrder::Second))
             },
             _ => Result::Err(
-                TransformationError::InvalidInputType(first.clone(), InputOrder::First))
+                TransformationError::InvalidInputType(Box::new(first.clone()), InputOrder::First))
         };
     }
 
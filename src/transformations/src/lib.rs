@@ -1,8 +1,14 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use buttercup_values::ValuesPayload;
 use serde::{Deserialize, Serialize};
 
+use crate::currency::ExchangeRateProvider;
 use crate::transformer::{TransformationError, TransformationRequest, TransformationService};
 
+pub mod bucket;
+pub mod currency;
 pub mod transformer;
 pub mod mono;
 pub mod di;
@@ -10,7 +16,9 @@ pub mod di;
 #[derive(Serialize, Deserialize)]
 pub struct Transformer {
 
-    requests: Vec<TransformationRequest>
+    requests: Vec<TransformationRequest>,
+    #[serde(skip, default = "currency::no_exchange_rates")]
+    exchange_rates: Arc<dyn ExchangeRateProvider>
 
 }
 
@@ -18,13 +26,27 @@ impl Transformer {
 
     pub fn new(requests: Vec<TransformationRequest>) -> Transformer {
         Transformer {
-            requests
+            requests,
+            exchange_rates: currency::no_exchange_rates()
         }
     }
 
+    pub fn with_exchange_rates(mut self,
+                               exchange_rates: Arc<dyn ExchangeRateProvider>) -> Transformer {
+        self.exchange_rates = exchange_rates;
+        self
+    }
+
     pub fn transform(&self,
                      payload: &ValuesPayload) -> Result<ValuesPayload, TransformationError> {
-        TransformationService::transform(payload, &self.requests)
+        TransformationService::transform(payload, &self.requests, self.exchange_rates.as_ref())
+    }
+
+    pub fn get_result_value_names(&self) -> HashSet<String> {
+        self.requests
+            .iter()
+            .map(|request| request.get_result_value_name().clone())
+            .collect()
     }
 
 }
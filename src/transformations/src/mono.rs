@@ -1,28 +1,51 @@
+use std::sync::Arc;
+
 use buttercup_values::{ValueHolder, ValueType};
+use buttercup_values::lists::ValueHoldersList;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use crate::mono::day_of_week::DayOfWeekFromDateTimeRetrieval;
 use crate::mono::geolocation::FindTimeZoneFromGeoCoordinates;
-use crate::transformer::TransformationError;
+use crate::mono::quantity::{PercentToFraction, QuantityAmountRetrieval, QuantityToBytes};
+use crate::transformer::{InputOrder, TransformationError};
 
 pub mod day_of_week;
 pub mod geolocation;
+pub mod quantity;
 
 #[derive(EnumIter, Serialize, Deserialize)]
 pub enum MonoInputTransformation {
 
     DayOfWeekFromDateTimeRetrieval,
-    FindTimeZoneFromGeoCoordinates
+    FindTimeZoneFromGeoCoordinates,
+    PercentToFraction,
+    QuantityAmountRetrieval,
+    QuantityToBytes
 
 }
 
 impl MonoInputTransformation {
 
+    // Lists are transformed element by element, into lists of the result type.
     pub fn transform(&self,
                      value: &ValueHolder) -> Result<ValueHolder, TransformationError> {
-        self.get_transformer().transform(value)
+        let transformer = self.get_transformer();
+
+        match value {
+            ValueHolder::List(list) => {
+                let mut elements = Vec::with_capacity(list.get_elements().len());
+                for element in list.get_elements() {
+                    elements.push(transformer.transform(element)?);
+                }
+
+                ValueHoldersList::new(elements, transformer.get_result_type().clone())
+                    .map(|list| ValueHolder::List(Arc::new(list)))
+                    .map_err(|_| TransformationError::InvalidInputType(Box::new(value.clone()), InputOrder::First))
+            },
+            _ => transformer.transform(value)
+        }
     }
 
     pub fn get_input_types(&self) -> &[ValueType] {
@@ -31,7 +54,7 @@ impl MonoInputTransformation {
 
     pub fn is_input_type_ok(&self,
                             input_type: &ValueType) -> bool {
-        self.get_input_types().contains(input_type)
+        *input_type == ValueType::List || self.get_input_types().contains(input_type)
     }
 
     pub fn initialize() {
@@ -45,7 +68,13 @@ impl MonoInputTransformation {
             MonoInputTransformation::DayOfWeekFromDateTimeRetrieval
             => DayOfWeekFromDateTimeRetrieval::instance(),
             MonoInputTransformation::FindTimeZoneFromGeoCoordinates
-            => FindTimeZoneFromGeoCoordinates::instance()
+            => FindTimeZoneFromGeoCoordinates::instance(),
+            MonoInputTransformation::PercentToFraction
+            => PercentToFraction::instance(),
+            MonoInputTransformation::QuantityAmountRetrieval
+            => QuantityAmountRetrieval::instance(),
+            MonoInputTransformation::QuantityToBytes
+            => QuantityToBytes::instance()
         };
     }
 
@@ -59,4 +88,37 @@ pub trait MonoInputTransformer {
     fn get_input_types(&self) -> &'static [ValueType];
     fn get_result_type(&self) -> &'static ValueType;
     
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use buttercup_values::quantity::{Quantity, Unit};
+    use num::{BigInt, BigRational};
+
+    use super::*;
+
+    fn list(elements: Vec<ValueHolder>,
+            value_type: ValueType) -> ValueHolder {
+        ValueHolder::List(Arc::new(ValueHoldersList::new(elements, value_type).unwrap()))
+    }
+
+    fn quantity(amount: i32,
+                unit: Unit) -> ValueHolder {
+        ValueHolder::Quantity(Quantity::new(BigRational::from_integer(BigInt::from(amount)), unit))
+    }
+
+    #[test]
+    fn test_transforms_lists_element_by_element() {
+        let percents = list(vec![quantity(25, Unit::Percent), quantity(50, Unit::Percent)], ValueType::Quantity);
+        let fractions = list(vec![ValueHolder::Decimal(BigRational::new(BigInt::from(1), BigInt::from(4))),
+                                  ValueHolder::Decimal(BigRational::new(BigInt::from(1), BigInt::from(2)))],
+                             ValueType::Decimal);
+
+        assert_eq!(fractions, MonoInputTransformation::PercentToFraction.transform(&percents).unwrap());
+        assert!(matches!(MonoInputTransformation::PercentToFraction.transform(
+            &list(vec![quantity(25, Unit::Percent), quantity(1, Unit::Bytes)], ValueType::Quantity)),
+                         Result::Err(TransformationError::UnitMismatch(Unit::Bytes, Unit::Percent))));
+        assert!(MonoInputTransformation::PercentToFraction.is_input_type_ok(&ValueType::List));
+    }
+
+}
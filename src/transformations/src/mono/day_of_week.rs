@@ -32,7 +32,7 @@ impl MonoInputTransformer for DayOfWeekFromDateTimeRetrieval {
             ValueHolder::ZonedDateTime(zdt) =>
                 DayOfWeekFromDateTimeRetrieval::ok(zdt.get_date_time().weekday()),
             _ => Result::Err(
-                TransformationError::InvalidInputType(value.clone(), InputOrder::First))
+                TransformationError::InvalidInputType(Box::new(value.clone()), InputOrder::First))
         }
     }
 
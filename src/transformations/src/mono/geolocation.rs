@@ -26,7 +26,7 @@ impl MonoInputTransformer for FindTimeZoneFromGeoCoordinates {
             ValueHolder::GeoCoordinates(coordinates) =>
                 FindTimeZoneFromGeoCoordinates::find_time_zone(&coordinates),
             _ => Result::Err(
-                TransformationError::InvalidInputType(value.clone(), InputOrder::First))
+                TransformationError::InvalidInputType(Box::new(value.clone()), InputOrder::First))
         }
     }
 
@@ -59,7 +59,7 @@ impl FindTimeZoneFromGeoCoordinates {
                                 TzWrapper::new(tz))),
                     Err(_) => Result::Err(TransformationError::UnknownTimezone(tz_str)),
                 },
-            None => Result::Err(TransformationError::CouldNotFindTimezone(coordinates.clone()))
+            None => Result::Err(TransformationError::CouldNotFindTimezone(Box::new(coordinates.clone())))
         };
     }
 
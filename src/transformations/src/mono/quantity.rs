@@ -0,0 +1,150 @@
+use buttercup_values::{ValueHolder, ValueType};
+use buttercup_values::quantity::Unit;
+use num::{BigInt, BigRational};
+
+use crate::mono::MonoInputTransformer;
+use crate::transformer::{InputOrder, TransformationError};
+
+pub struct QuantityAmountRetrieval;
+
+pub struct PercentToFraction;
+
+pub struct QuantityToBytes;
+
+const INPUT_TYPE: [ValueType; 1] = [ValueType::Quantity];
+const DECIMAL_RESULT_TYPE: ValueType = ValueType::Decimal;
+const QUANTITY_RESULT_TYPE: ValueType = ValueType::Quantity;
+
+impl MonoInputTransformer for QuantityAmountRetrieval {
+
+    fn transform(&self,
+                 value: &ValueHolder) -> Result<ValueHolder, TransformationError> {
+        match value {
+            ValueHolder::Quantity(quantity) =>
+                Result::Ok(ValueHolder::Decimal(quantity.get_amount().clone())),
+            _ => Result::Err(
+                TransformationError::InvalidInputType(Box::new(value.clone()), InputOrder::First))
+        }
+    }
+
+    fn get_input_types(&self) -> &'static [ValueType] {
+        &INPUT_TYPE
+    }
+
+    fn get_result_type(&self) -> &'static ValueType {
+        &DECIMAL_RESULT_TYPE
+    }
+
+}
+
+impl MonoInputTransformer for PercentToFraction {
+
+    fn transform(&self,
+                 value: &ValueHolder) -> Result<ValueHolder, TransformationError> {
+        match value {
+            ValueHolder::Quantity(quantity) => match quantity.get_unit() {
+                Unit::Percent => Result::Ok(
+                    ValueHolder::Decimal(
+                        quantity.get_amount() / BigRational::from_integer(BigInt::from(100)))),
+                unit => Result::Err(
+                    TransformationError::UnitMismatch(unit.clone(), Unit::Percent))
+            },
+            _ => Result::Err(
+                TransformationError::InvalidInputType(Box::new(value.clone()), InputOrder::First))
+        }
+    }
+
+    fn get_input_types(&self) -> &'static [ValueType] {
+        &INPUT_TYPE
+    }
+
+    fn get_result_type(&self) -> &'static ValueType {
+        &DECIMAL_RESULT_TYPE
+    }
+
+}
+
+impl MonoInputTransformer for QuantityToBytes {
+
+    fn transform(&self,
+                 value: &ValueHolder) -> Result<ValueHolder, TransformationError> {
+        match value {
+            ValueHolder::Quantity(quantity) => quantity.convert(&Unit::Bytes)
+                .map(ValueHolder::Quantity)
+                .map_err(|_| TransformationError::UnitMismatch(
+                    quantity.get_unit().clone(), Unit::Bytes)),
+            _ => Result::Err(
+                TransformationError::InvalidInputType(Box::new(value.clone()), InputOrder::First))
+        }
+    }
+
+    fn get_input_types(&self) -> &'static [ValueType] {
+        &INPUT_TYPE
+    }
+
+    fn get_result_type(&self) -> &'static ValueType {
+        &QUANTITY_RESULT_TYPE
+    }
+
+}
+
+impl QuantityAmountRetrieval {
+
+    const INSTANCE: QuantityAmountRetrieval = QuantityAmountRetrieval{};
+
+    pub fn instance() -> &'static QuantityAmountRetrieval {
+        &QuantityAmountRetrieval::INSTANCE
+    }
+
+}
+
+impl PercentToFraction {
+
+    const INSTANCE: PercentToFraction = PercentToFraction{};
+
+    pub fn instance() -> &'static PercentToFraction {
+        &PercentToFraction::INSTANCE
+    }
+
+}
+
+impl QuantityToBytes {
+
+    const INSTANCE: QuantityToBytes = QuantityToBytes{};
+
+    pub fn instance() -> &'static QuantityToBytes {
+        &QuantityToBytes::INSTANCE
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use buttercup_values::quantity::Quantity;
+
+    use super::*;
+
+    #[test]
+    fn test_percent_to_fraction() {
+        let percent = ValueHolder::Quantity(
+            Quantity::new(BigRational::from_integer(BigInt::from(25)), Unit::Percent));
+        let bytes = ValueHolder::Quantity(
+            Quantity::new(BigRational::from_integer(BigInt::from(25)), Unit::Bytes));
+
+        assert_eq!(ValueHolder::Decimal(BigRational::new(BigInt::from(1), BigInt::from(4))),
+                   PercentToFraction::instance().transform(&percent).unwrap());
+        assert!(matches!(PercentToFraction::instance().transform(&bytes),
+                         Result::Err(TransformationError::UnitMismatch(Unit::Bytes, Unit::Percent))));
+    }
+
+    #[test]
+    fn test_quantity_to_bytes() {
+        let mebibytes = ValueHolder::Quantity(
+            Quantity::new(BigRational::from_integer(BigInt::from(2)), Unit::Mebibytes));
+
+        assert_eq!(ValueHolder::Quantity(
+            Quantity::new(BigRational::from_integer(BigInt::from(2 * 1024 * 1024)), Unit::Bytes)),
+                   QuantityToBytes::instance().transform(&mebibytes).unwrap());
+    }
+
+}
@@ -2,8 +2,11 @@ use std::collections::HashMap;
 
 use buttercup_values::{ValueHolder, ValuesPayload};
 use buttercup_values::geolocation::GeoCoordinates;
+use buttercup_values::quantity::Unit;
 use serde::{Deserialize, Serialize};
 
+use crate::bucket::HashBucketAssignment;
+use crate::currency::{CurrencyConversion, ExchangeRateError, ExchangeRateProvider};
 use crate::di::DiInputTransformation;
 use crate::mono::MonoInputTransformation;
 
@@ -18,9 +21,12 @@ pub enum InputOrder {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TransformationError {
 
-    InvalidInputType(ValueHolder, InputOrder),
+    InvalidInputType(Box<ValueHolder>, InputOrder),
     CouldNotFindValue(String),
-    CouldNotFindTimezone(GeoCoordinates),
+    CouldNotFindTimezone(Box<GeoCoordinates>),
+    ExchangeRateError(ExchangeRateError),
+    InvalidBucketCount(u32),
+    UnitMismatch(Unit, Unit),
     UnknownTimezone(String)
 
 }
@@ -105,11 +111,59 @@ impl DoubleInputTransformationDefinition {
 
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct CurrencyConversionDefinition {
+
+    transformation_definition_id: i32,
+    input_name: String,
+    target_currency: String
+
+}
+
+impl CurrencyConversionDefinition {
+
+    pub fn new(transformation_definition_id: i32,
+               input_name: String,
+               target_currency: String) -> CurrencyConversionDefinition {
+        CurrencyConversionDefinition {
+            transformation_definition_id,
+            input_name,
+            target_currency
+        }
+    }
+
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HashBucketDefinition {
+
+    transformation_definition_id: i32,
+    input_name: String,
+    num_buckets: u32
+
+}
+
+impl HashBucketDefinition {
+
+    pub fn new(transformation_definition_id: i32,
+               input_name: String,
+               num_buckets: u32) -> HashBucketDefinition {
+        HashBucketDefinition {
+            transformation_definition_id,
+            input_name,
+            num_buckets
+        }
+    }
+
+}
+
 #[derive(Serialize, Deserialize)]
 pub enum Transformation {
 
     Mono(SingleInputTransformationDefinition),
-    Bi(DoubleInputTransformationDefinition)
+    Bi(DoubleInputTransformationDefinition),
+    Currency(CurrencyConversionDefinition),
+    HashBucket(HashBucketDefinition)
 
 }
 
@@ -143,6 +197,22 @@ impl TransformationRequest {
         TransformationRequest::new(definition, Transformation::Bi(transformation))
     }
 
+    pub fn new_currency(definition: TransformationDefinition,
+                        transformation: CurrencyConversionDefinition)
+                        -> TransformationRequest {
+        TransformationRequest::new(definition, Transformation::Currency(transformation))
+    }
+
+    pub fn new_hash_bucket(definition: TransformationDefinition,
+                           transformation: HashBucketDefinition)
+                           -> TransformationRequest {
+        TransformationRequest::new(definition, Transformation::HashBucket(transformation))
+    }
+
+    pub fn get_result_value_name(&self) -> &String {
+        &self.definition.result_value_name
+    }
+
 }
 
 pub struct TransformationService;
@@ -150,7 +220,8 @@ pub struct TransformationService;
 impl TransformationService {
 
     pub fn transform(payload: &ValuesPayload,
-                     transformation_requests: &Vec<TransformationRequest>)
+                     transformation_requests: &Vec<TransformationRequest>,
+                     exchange_rates: &dyn ExchangeRateProvider)
                      -> Result<ValuesPayload, TransformationError> {
         let values = payload.get_values();
         let mut new_values: HashMap<String, ValueHolder> = values.clone();
@@ -161,6 +232,10 @@ impl TransformationService {
                 Transformation::Bi(
                     def)
                 => TransformationService::handle_double(def, &new_values),
+                Transformation::Currency(def)
+                => TransformationService::handle_currency(def, &new_values, exchange_rates),
+                Transformation::HashBucket(def)
+                => TransformationService::handle_hash_bucket(def, &new_values),
             };
             match result {
                 Ok(new_value) =>
@@ -193,6 +268,30 @@ impl TransformationService {
         };
     }
 
+    fn handle_currency(definition: &CurrencyConversionDefinition,
+                       values: &HashMap<String, ValueHolder>,
+                       exchange_rates: &dyn ExchangeRateProvider)
+                       -> Result<ValueHolder, TransformationError> {
+        let value_name = &definition.input_name;
+        match values.get(value_name) {
+            Some(value) =>
+                CurrencyConversion::convert(value, &definition.target_currency, exchange_rates),
+            None => Result::Err(
+                TransformationError::CouldNotFindValue(value_name.clone())),
+        }
+    }
+
+    fn handle_hash_bucket(definition: &HashBucketDefinition,
+                          values: &HashMap<String, ValueHolder>)
+                          -> Result<ValueHolder, TransformationError> {
+        let value_name = &definition.input_name;
+        match values.get(value_name) {
+            Some(value) => HashBucketAssignment::assign(value, definition.num_buckets),
+            None => Result::Err(
+                TransformationError::CouldNotFindValue(value_name.clone())),
+        }
+    }
+
     fn handle_double(definition: &DoubleInputTransformationDefinition,
                      values: &HashMap<String, ValueHolder>)
                      -> Result<ValueHolder, TransformationError> {
@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::ValueHolder;
+
+// Times from the start, inclusive, to the end, exclusive. Hours which end before they
+// start span midnight.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct WorkingHours {
+
+    from: NaiveTime,
+    to: NaiveTime
+
+}
+
+impl WorkingHours {
+
+    pub fn new(from: NaiveTime,
+               to: NaiveTime) -> WorkingHours {
+        WorkingHours {
+            from,
+            to
+        }
+    }
+
+    fn contains(&self,
+                time: &NaiveTime) -> bool {
+        if self.from <= self.to {
+            self.from <= *time && *time < self.to
+        } else {
+            self.from <= *time || *time < self.to
+        }
+    }
+
+}
+
+// Working hours per day of the week and holidays, in the time zone of the calendar. Days
+// without working hours are closed all day.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BusinessCalendar {
+
+    zone: Tz,
+    working_hours: HashMap<Weekday, Vec<WorkingHours>>,
+
+    #[serde(default)]
+    holidays: HashSet<NaiveDate>
+
+}
+
+impl BusinessCalendar {
+
+    pub fn new(zone: Tz) -> BusinessCalendar {
+        BusinessCalendar {
+            zone,
+            working_hours: HashMap::new(),
+            holidays: HashSet::new()
+        }
+    }
+
+    pub fn with_working_hours(mut self,
+                              weekday: Weekday,
+                              working_hours: WorkingHours) -> BusinessCalendar {
+        self.working_hours.entry(weekday).or_default().push(working_hours);
+        self
+    }
+
+    pub fn with_holiday(mut self,
+                        holiday: NaiveDate) -> BusinessCalendar {
+        self.holidays.insert(holiday);
+        self
+    }
+
+    pub fn get_zone(&self) -> &Tz {
+        &self.zone
+    }
+
+    pub fn is_open_at_utc(&self,
+                          date_time: &NaiveDateTime) -> bool {
+        self.is_open_at_local(&self.zone.from_utc_datetime(date_time).naive_local())
+    }
+
+    // Local date time of the calendar's time zone.
+    pub fn is_open_at_local(&self,
+                            date_time: &NaiveDateTime) -> bool {
+        if self.holidays.contains(&date_time.date()) {
+            return false;
+        }
+
+        self.working_hours
+            .get(&date_time.weekday())
+            .is_some_and(|hours| hours.iter().any(|hours| hours.contains(&date_time.time())))
+    }
+
+    // Zoned date times are moved to the time zone of the calendar, local date times are
+    // taken as utc. Other values are not points in time.
+    pub fn is_open_at(&self,
+                      value: &ValueHolder) -> Option<bool> {
+        match value {
+            ValueHolder::ZonedDateTime(date_time) => date_time.get_zone()
+                .from_local_datetime(date_time.get_date_time())
+                .earliest()
+                .map(|date_time| self.is_open_at_utc(&date_time.naive_utc())),
+            ValueHolder::LocalDateTime(date_time) =>
+                Option::Some(self.is_open_at_utc(date_time)),
+            _ => Option::None
+        }
+    }
+
+    pub fn is_open_now(&self) -> bool {
+        self.is_open_at_utc(&Utc::now().naive_utc())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Europe::Warsaw;
+
+    use crate::zoned_date_time::ZonedDateTime;
+
+    use super::*;
+
+    fn calendar() -> BusinessCalendar {
+        let office_hours = WorkingHours::new(NaiveTime::from_hms(9, 0, 0), NaiveTime::from_hms(17, 0, 0));
+
+        BusinessCalendar::new(Warsaw)
+            .with_working_hours(Weekday::Mon, office_hours.clone())
+            .with_working_hours(Weekday::Tue, office_hours)
+            .with_holiday(NaiveDate::from_ymd(2021, 5, 3))
+    }
+
+    #[test]
+    fn test_checks_working_hours_and_holidays() {
+        let calendar = calendar();
+        let at = |day, hour| NaiveDate::from_ymd(2021, 5, day).and_hms(hour, 0, 0);
+
+        assert!(calendar.is_open_at_local(&at(4, 9)));
+        assert!(!calendar.is_open_at_local(&at(4, 17)));
+        assert!(!calendar.is_open_at_local(&at(3, 12)));
+        assert!(!calendar.is_open_at_local(&at(5, 12)));
+        assert_eq!(Option::Some(false), calendar.is_open_at(&ValueHolder::LocalDateTime(at(4, 15))));
+        assert_eq!(Option::Some(true), calendar.is_open_at(&ValueHolder::LocalDateTime(at(4, 14))));
+        assert_eq!(Option::Some(true),
+                   calendar.is_open_at(&ValueHolder::ZonedDateTime(ZonedDateTime::new(at(4, 9), Warsaw))));
+        assert_eq!(Option::None, calendar.is_open_at(&ValueHolder::Boolean(true)));
+    }
+
+    #[test]
+    fn test_deserializes_calendars() {
+        let json = r#"{
+            "zone": "Europe/Warsaw",
+            "working_hours": {
+                "Mon": [{ "from": "09:00:00", "to": "17:00:00" }],
+                "Tue": [{ "from": "09:00:00", "to": "17:00:00" }]
+            },
+            "holidays": ["2021-05-03"]
+        }"#;
+
+        assert_eq!(calendar(), serde_json::from_str(json).unwrap());
+    }
+
+}
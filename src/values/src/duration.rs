@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+// Durations of ISO 8601, in weeks, days, hours, minutes and seconds, such as PT1H30M.
+pub fn parse_iso_8601(text: &str) -> Option<Duration> {
+    let text = text.strip_prefix('P')?;
+    let (date, time) = text.split_once('T').unwrap_or((text, ""));
+    let mut seconds = 0.0;
+
+    let date_units: &[(char, f64)] = &[('W', 604_800.0), ('D', 86_400.0)];
+    let time_units: &[(char, f64)] = &[('H', 3_600.0), ('M', 60.0), ('S', 1.0)];
+
+    for (part, units) in [(date, date_units), (time, time_units)] {
+        let mut rest = part;
+
+        for (unit, unit_seconds) in units {
+            if let Some((number, tail)) = rest.split_once(*unit) {
+                let number = number.parse::<f64>().ok().filter(|number| number.is_finite() && *number >= 0.0)?;
+                seconds += number * unit_seconds;
+                rest = tail;
+            }
+        }
+
+        if !rest.is_empty() {
+            return Option::None;
+        }
+    }
+
+    if text.is_empty() || text.ends_with('T') || !seconds.is_finite() {
+        return Option::None;
+    }
+    Option::Some(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_iso_8601_durations() {
+        assert_eq!(Option::Some(Duration::from_secs(1_800)), parse_iso_8601("PT30M"));
+        assert_eq!(Option::Some(Duration::from_secs(5_400)), parse_iso_8601("PT1H30M"));
+        assert_eq!(Option::Some(Duration::from_secs(694_800)), parse_iso_8601("P1W1DT1H"));
+        assert_eq!(Option::Some(Duration::from_millis(1_500)), parse_iso_8601("PT1.5S"));
+        assert_eq!(Option::Some(Duration::from_secs(0)), parse_iso_8601("PT0S"));
+    }
+
+    #[test]
+    fn test_rejects_invalid_durations() {
+        for text in ["", "P", "PT", "30M", "PT30", "PT-1S", "PT1M1H", "P1H"].iter() {
+            assert_eq!(Option::None, parse_iso_8601(text), "{}", text);
+        }
+    }
+
+}
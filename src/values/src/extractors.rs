@@ -2,34 +2,48 @@ use isocountry::CountryCodeParseErr;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{ValueHolder, ValueType};
+use crate::{DEFAULT_MAX_NUMBER_DIGITS, ValueHolder, ValueType};
 use crate::extractors::boolean::BooleanExtractor;
+use crate::extractors::bytes::BytesExtractor;
 use crate::extractors::country::CountryValueExtractor;
 use crate::extractors::date_time::day_of_week::DayOfWeekExtractor;
 use crate::extractors::date_time::local::{LocalDateExtractor, LocalDateTimeExtractor, LocalTimeExtractor};
 use crate::extractors::date_time::zoned::{TimezoneExtractor, ZonedDateTimeExtractor};
 use crate::extractors::email::EmailValueExtractor;
+use crate::extractors::flags::FlagsExtractor;
 use crate::extractors::geolocation::GeoCoordinatesExtractor;
 use crate::extractors::ip::IpAddressValueExtractor;
 use crate::extractors::language::LanguageValueExtractor;
 use crate::extractors::lists::ListExtractor;
 use crate::extractors::number::{DecimalExtractor, IntegerExtractor};
+use crate::extractors::objects::ObjectExtractor;
+use crate::extractors::quantity::QuantityExtractor;
 use crate::extractors::string::StringExtractor;
+use crate::extractors::symbol::SymbolExtractor;
+use crate::extractors::uuid::UuidExtractor;
+use crate::flags::FlagsValueError;
 use crate::geolocation::GeoCoordinatesValueError;
 use crate::lists::ValueHoldersListError;
+use crate::quantity::QuantityValueError;
 use crate::zoned_date_time::ZonedDateTimeParsingError;
 use crate::extractors::date_time::duration::DurationExtractor;
 
 pub(crate) mod boolean;
+pub(crate) mod bytes;
 pub(crate) mod country;
 pub(crate) mod date_time;
 pub(crate) mod email;
+pub(crate) mod flags;
 pub(crate) mod geolocation;
 pub(crate) mod ip;
 pub(crate) mod language;
 pub(crate) mod lists;
 pub(crate) mod number;
+pub(crate) mod objects;
+pub(crate) mod quantity;
 pub(crate) mod string;
+pub(crate) mod symbol;
+pub(crate) mod uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ValueExtractionPolicy {
@@ -51,7 +65,11 @@ pub enum ValueExtractionError {
     ZonedDateTimeParsingError(ValueExtractionPolicy, ZonedDateTimeParsingError),
     CountryCodeParsingError(ValueExtractionPolicy, CountryCodeParsingError),
     EmailParsingError(ValueExtractionPolicy, String),
+    FlagsValueError(ValueExtractionPolicy, FlagsValueError),
+    QuantityValueError(ValueExtractionPolicy, QuantityValueError),
+    NumberTooLong(ValueExtractionPolicy, usize),
     InvalidInputTypeForList,
+    ObjectEntryError(String, Box<ValueExtractionError>),
     ValueIsNull
 
 }
@@ -102,7 +120,8 @@ pub struct ValueExtractorInput<'a> {
 
     value: &'a Value,
     argument_type: &'a ValueType,
-    policy: &'a ValueExtractionPolicy
+    policy: &'a ValueExtractionPolicy,
+    max_number_digits: usize
 
 }
 
@@ -114,9 +133,26 @@ impl<'a> ValueExtractorInput<'a> {
         ValueExtractorInput {
             value,
             argument_type,
-            policy
+            policy,
+            max_number_digits: DEFAULT_MAX_NUMBER_DIGITS
         }
     }
+
+    pub fn with_max_number_digits(mut self,
+                                  max_number_digits: usize) -> ValueExtractorInput<'a> {
+        self.max_number_digits = max_number_digits;
+        self
+    }
+
+    // Numbers given as strings are rejected before parsing when they have more digits.
+    pub(crate) fn check_number_digits(&self,
+                                      value: &str) -> Result<(), ValueExtractionError> {
+        if value.chars().filter(char::is_ascii_digit).count() > self.max_number_digits {
+            return Result::Err(
+                ValueExtractionError::NumberTooLong(self.policy.clone(), self.max_number_digits));
+        }
+        Result::Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -151,6 +187,7 @@ impl ValueExtractorService {
         }
         return match &input.argument_type {
             ValueType::Boolean => BooleanExtractor::extract(input),
+            ValueType::Bytes => BytesExtractor::extract(input),
             ValueType::String => StringExtractor::extract(input),
             ValueType::Decimal => DecimalExtractor::extract(input),
             ValueType::Integer => IntegerExtractor::extract(input),
@@ -164,9 +201,14 @@ impl ValueExtractorService {
             ValueType::Language => LanguageValueExtractor::extract(input),
             ValueType::Country => CountryValueExtractor::extract(input),
             ValueType::Email => EmailValueExtractor::extract(input),
+            ValueType::Flags => FlagsExtractor::extract(input),
             ValueType::IpAddress => IpAddressValueExtractor::extract(input),
             ValueType::Duration => DurationExtractor::extract(input),
+            ValueType::Quantity => QuantityExtractor::extract(input),
+            ValueType::Symbol => SymbolExtractor::extract(input),
+            ValueType::Uuid => UuidExtractor::extract(input),
             ValueType::List => Result::Err(ValueExtractionError::InvalidInputTypeForList),
+            ValueType::Object => ObjectExtractor::extract(input),
         };
     }
 
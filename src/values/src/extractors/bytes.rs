@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::extractors::{ValueExtractionError, ValueExtractionPolicy, ValueExtractor, ValueExtractorInput};
+use crate::ValueHolder;
+
+pub struct BytesExtractor;
+
+impl BytesExtractor {
+
+    fn decode(value: &str,
+              config: base64::Config,
+              policy: ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        match base64::decode_config(value, config) {
+            Ok(bytes) => Result::Ok(ValueHolder::Bytes(Arc::new(bytes))),
+            Err(err) => Result::Err(
+                ValueExtractionError::InvalidValueError(policy, err.to_string()))
+        }
+    }
+
+}
+
+impl ValueExtractor for BytesExtractor {
+
+    fn strict_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(str_val) =>
+                BytesExtractor::decode(str_val, base64::STANDARD, ValueExtractionPolicy::Strict),
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Strict))
+        }
+    }
+
+    fn lax_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(str_val) => {
+                let trimmed = str_val.trim().trim_end_matches('=');
+                let config = if trimmed.contains(&['-', '_'][..]) {
+                    base64::URL_SAFE_NO_PAD
+                } else {
+                    base64::STANDARD_NO_PAD
+                };
+                BytesExtractor::decode(trimmed, config, ValueExtractionPolicy::Lax)
+            },
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Lax))
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::ValueType;
+
+    use super::*;
+
+    fn extract(value: &str,
+               policy: ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        let input_value = Value::from_str(value).unwrap();
+        BytesExtractor::extract(&ValueExtractorInput::new(&input_value, &ValueType::Bytes, &policy))
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(Result::Ok(ValueHolder::Bytes(Arc::new(vec![0xfb, 0xff, 0x01]))),
+                   extract(r#""+/8B""#, ValueExtractionPolicy::Strict));
+        assert!(extract(r#""-_8B""#, ValueExtractionPolicy::Strict).is_err());
+        assert!(extract("[251, 255, 1]", ValueExtractionPolicy::Strict).is_err());
+    }
+
+    #[test]
+    fn test_lax() {
+        assert_eq!(Result::Ok(ValueHolder::Bytes(Arc::new(vec![0xfb, 0xff, 0x01]))),
+                   extract(r#"" -_8B ""#, ValueExtractionPolicy::Lax));
+        assert_eq!(Result::Ok(ValueHolder::Bytes(Arc::new(b"ab".to_vec()))),
+                   extract(r#""YWI""#, ValueExtractionPolicy::Lax));
+    }
+
+}
@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use serde_json::{Number, Value};
 
+use crate::duration::parse_iso_8601;
 use crate::extractors::{ParsingValueSource, ValueExtractionError, ValueExtractionPolicy, ValueExtractor, ValueExtractorInput};
 use crate::ValueHolder;
 
@@ -42,13 +43,63 @@ impl ValueExtractor for DurationExtractor {
         return match input.value {
             Value::Number(number) =>
                 DurationExtractor::from_milli(number),
+            Value::String(val) => match parse_iso_8601(val) {
+                Some(duration) => Result::Ok(ValueHolder::Duration(duration)),
+                None => Result::Err(
+                    ValueExtractionError::ParsingError(
+                        ValueExtractionPolicy::Strict, ParsingValueSource::String))
+            },
             _ => Result::Err(
                 ValueExtractionError::InvalidValueTypeError(
                     ValueExtractionPolicy::Strict))
         }
     }
 
-    fn lax_extract(_: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
-        Result::Err(ValueExtractionError::PolicyNotSupported(ValueExtractionPolicy::Lax))
+    // Milliseconds can be sent as strings as well.
+    fn lax_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(val) => match val.trim().parse::<u64>() {
+                Ok(millis) => Result::Ok(ValueHolder::Duration(Duration::from_millis(millis))),
+                Err(_) => Result::Err(
+                    ValueExtractionError::ParsingError(
+                        ValueExtractionPolicy::Lax, ParsingValueSource::String))
+            },
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Lax))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::ValueType;
+
+    use super::*;
+
+    fn extract(value: &str,
+               policy: ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        let input_value = Value::from_str(value).unwrap();
+        DurationExtractor::extract(&ValueExtractorInput::new(&input_value, &ValueType::Duration, &policy))
+    }
+
+    #[test]
+    fn test_strict() {
+        let thirty_minutes = Result::Ok(ValueHolder::Duration(Duration::from_secs(1_800)));
+
+        assert_eq!(thirty_minutes, extract("1800000", ValueExtractionPolicy::Strict));
+        assert_eq!(thirty_minutes, extract(r#""PT30M""#, ValueExtractionPolicy::Strict));
+        assert!(extract(r#""1800000""#, ValueExtractionPolicy::Strict).is_err());
+        assert!(extract("-1", ValueExtractionPolicy::Strict).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_lax() {
+        assert_eq!(Result::Ok(ValueHolder::Duration(Duration::from_secs(1_800))),
+                   extract(r#"" 1800000 ""#, ValueExtractionPolicy::Lax));
+        assert!(extract(r#""30 minutes""#, ValueExtractionPolicy::Lax).is_err());
+    }
+
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::extractors::{ValueExtractionError, ValueExtractionPolicy, ValueExtractor, ValueExtractorInput};
+use crate::flags::{Flags, FlagsValueError, MAX_FLAGS};
+use crate::ValueHolder;
+
+pub struct FlagsExtractor;
+
+impl FlagsExtractor {
+
+    fn from_names(mut names: Vec<String>,
+                  policy: ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        names.sort();
+        names.dedup();
+        if names.len() > MAX_FLAGS {
+            return Result::Err(ValueExtractionError::FlagsValueError(
+                policy, FlagsValueError::TooManyFlags(names.len())));
+        }
+        let bits = Flags::mask(names.len());
+        match Flags::new(Arc::new(names), bits) {
+            Ok(flags) => Result::Ok(ValueHolder::Flags(flags)),
+            Err(err) => Result::Err(ValueExtractionError::FlagsValueError(policy, err))
+        }
+    }
+
+}
+
+impl ValueExtractor for FlagsExtractor {
+
+    fn strict_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::Array(elements) => {
+                let mut names = Vec::with_capacity(elements.len());
+                for element in elements {
+                    match element {
+                        Value::String(name) => names.push(name.clone()),
+                        _ => return Result::Err(
+                            ValueExtractionError::InvalidValueTypeError(
+                                ValueExtractionPolicy::Strict))
+                    }
+                }
+                FlagsExtractor::from_names(names, ValueExtractionPolicy::Strict)
+            },
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Strict))
+        }
+    }
+
+    fn lax_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(str_val) => FlagsExtractor::from_names(
+                str_val.split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_owned)
+                    .collect(),
+                ValueExtractionPolicy::Lax),
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Lax))
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::ValueType;
+
+    use super::*;
+
+    #[test]
+    fn test_extracts_set_flags() {
+        for (value, policy) in &[(r#"["write", "read", "write"]"#, ValueExtractionPolicy::Strict),
+                                 (r#"" read, write ""#, ValueExtractionPolicy::Lax)] {
+            let input_value = Value::from_str(value).unwrap();
+            match FlagsExtractor::extract(
+                &ValueExtractorInput::new(&input_value, &ValueType::Flags, policy)) {
+                Ok(ValueHolder::Flags(flags)) =>
+                    assert_eq!(vec!["read", "write"], flags.get_set_flags()),
+                other => panic!("Unexpected extraction result: {:?}", other)
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_too_many_flags() {
+        let names = |count: usize| Value::Array((0..count).map(|index| Value::String(format!("flag_{}", index))).collect());
+
+        assert!(FlagsExtractor::extract(
+            &ValueExtractorInput::new(&names(MAX_FLAGS), &ValueType::Flags, &ValueExtractionPolicy::Strict)).is_ok());
+        assert_eq!(Result::Err(ValueExtractionError::FlagsValueError(
+            ValueExtractionPolicy::Strict, FlagsValueError::TooManyFlags(MAX_FLAGS + 1))),
+                   FlagsExtractor::extract(
+                       &ValueExtractorInput::new(&names(MAX_FLAGS + 1), &ValueType::Flags, &ValueExtractionPolicy::Strict)));
+    }
+
+}
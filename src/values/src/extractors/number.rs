@@ -42,9 +42,19 @@ impl ValueExtractor for DecimalExtractor {
                                 ValueExtractionPolicy::Lax, ParsingValueSource::I64))
                     };
                 }
+                // Newer serde_json reads -0 as a float.
+                if num_val.is_f64() {
+                    return match num_val.as_f64().and_then(BigRational::from_f64) {
+                        Some(v) => Result::Ok(ValueHolder::Decimal(v)),
+                        None => Result::Err(
+                            ValueExtractionError::ParsingError(
+                                ValueExtractionPolicy::Lax, ParsingValueSource::F64))
+                    };
+                }
                 Result::Err(ValueExtractionError::InvalidValueTypeError(ValueExtractionPolicy::Lax))
             },
             Value::String(str_val) => {
+                input.check_number_digits(str_val)?;
                 return match str_val
                     .parse::<f64>() {
                     Ok(f64_val) => match BigRational::from_f64(f64_val) {
@@ -111,6 +121,7 @@ impl ValueExtractor for IntegerExtractor {
                     ValueExtractionError::InvalidValueTypeError(
                         ValueExtractionPolicy::Strict))},
             Value::String(str_val) => {
+                input.check_number_digits(str_val)?;
                 return match str_val.parse::<BigInt>() {
                     Ok(v) => Result::Ok(ValueHolder::Integer(v)),
                     Err(_) => Result::Err(
@@ -220,6 +231,19 @@ mod tests {
                                  BigInt::from(-101232131123123100000000 as i128)));
     }
 
+    #[test]
+    fn test_rejects_too_long_numbers() {
+        let input_value = Value::String("1".repeat(21));
+        let input = ValueExtractorInput::new(&input_value, &ValueType::Integer, &ValueExtractionPolicy::Lax)
+            .with_max_number_digits(20);
+
+        assert_eq!(Result::Err(ValueExtractionError::NumberTooLong(ValueExtractionPolicy::Lax, 20)),
+                   IntegerExtractor::extract(&input));
+        assert_eq!(Result::Err(ValueExtractionError::NumberTooLong(ValueExtractionPolicy::Lax, 20)),
+                   DecimalExtractor::extract(&input));
+        assert!(IntegerExtractor::extract(&input.with_max_number_digits(21)).is_ok());
+    }
+
     fn extract<F>(value: &str,
                   extraction: F,
                   value_type: &ValueType,
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+
+use crate::extractors::{ValueExtractionError, ValueExtractionPolicy, ValueExtractor, ValueExtractorInput, ValueExtractorService};
+use crate::lists::ValueHoldersList;
+use crate::objects::ValueHoldersObject;
+use crate::{ValueHolder, ValueType};
+
+pub struct ObjectExtractor;
+
+impl ObjectExtractor {
+
+    // The type of every entry is taken from its json, null entries are left out and
+    // arrays become lists of the type of their first element.
+    fn do_extract(input: &ValueExtractorInput,
+                  entries: &Map<String, Value>) -> Result<ValueHolder, ValueExtractionError> {
+        let mut extracted = HashMap::new();
+        for (name, value) in entries {
+            if value.is_null() {
+                continue;
+            }
+            let holder = ObjectExtractor::extract_entry(input, value)
+                .map_err(|err| ValueExtractionError::ObjectEntryError(name.clone(), Box::new(err)))?;
+            extracted.insert(name.clone(), holder);
+        }
+        Result::Ok(ValueHolder::Object(Arc::new(ValueHoldersObject::new(extracted))))
+    }
+
+    fn extract_entry(input: &ValueExtractorInput,
+                     value: &Value) -> Result<ValueHolder, ValueExtractionError> {
+        let value_type = match value {
+            Value::Array(elements) => return ObjectExtractor::extract_elements(input, elements),
+            Value::Bool(_) => ValueType::Boolean,
+            Value::Number(number) if number.is_f64() => ValueType::Decimal,
+            Value::Number(_) => ValueType::Integer,
+            Value::Object(entries) => return ObjectExtractor::do_extract(input, entries),
+            _ => ValueType::String
+        };
+
+        ValueExtractorService::extract(
+            &ValueExtractorInput::new(value, &value_type, &ValueExtractionPolicy::Strict)
+                .with_max_number_digits(input.max_number_digits))
+    }
+
+    fn extract_elements(input: &ValueExtractorInput,
+                        elements: &[Value]) -> Result<ValueHolder, ValueExtractionError> {
+        let mut holders = Vec::with_capacity(elements.len());
+        for element in elements {
+            holders.push(ObjectExtractor::extract_entry(input, element)?);
+        }
+
+        // Empty arrays have no element to take the type from.
+        let value_type = holders.first().map_or(ValueType::String, ValueHolder::get_value_type);
+        ValueHoldersList::new(holders, value_type)
+            .map(|list| ValueHolder::List(Arc::new(list)))
+            .map_err(|_| ValueExtractionError::InvalidValueTypeError(input.policy.clone()))
+    }
+
+}
+
+impl ValueExtractor for ObjectExtractor {
+
+    fn strict_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::Object(entries) => ObjectExtractor::do_extract(input, entries),
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Strict))
+        }
+    }
+
+    fn lax_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::Object(entries) => ObjectExtractor::do_extract(input, entries),
+            Value::String(str_val) => match serde_json::from_str(str_val) {
+                Ok(Value::Object(entries)) => ObjectExtractor::do_extract(input, &entries),
+                Ok(_) => Result::Err(
+                    ValueExtractionError::InvalidValueTypeError(
+                        ValueExtractionPolicy::Lax)),
+                Err(err) => Result::Err(
+                    ValueExtractionError::JsonDeserializationError(
+                        ValueExtractionPolicy::Lax, err.to_string()))
+            },
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Lax))
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigInt;
+    use serde_json::json;
+
+    use super::*;
+
+    fn extract(value: &Value,
+               policy: &ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        ValueExtractorService::extract(&ValueExtractorInput::new(value, &ValueType::Object, policy))
+    }
+
+    #[test]
+    fn test_extracts_nested_objects() {
+        let value = json!({
+            "name": "Alice",
+            "age": 30,
+            "tags": ["a", "b"],
+            "nickname": null,
+            "address": {"city": "Warsaw", "verified": true}
+        });
+
+        match extract(&value, &ValueExtractionPolicy::Strict).unwrap() {
+            ValueHolder::Object(user) => {
+                assert_eq!(4, user.get_entries().len());
+                assert_eq!(Option::Some(&ValueHolder::Integer(BigInt::from(30))), user.get("age"));
+                assert_eq!(Option::Some(&"Warsaw".into()), user.get_path("address.city"));
+                assert_eq!(Option::Some(&ValueHolder::Boolean(true)), user.get_path("address.verified"));
+                assert_eq!(Option::None, user.get("nickname"));
+                assert!(matches!(user.get("tags"), Some(ValueHolder::List(tags)) if tags.get_elements().len() == 2));
+            },
+            other => panic!("Expected an object, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_objects() {
+        assert_eq!(Result::Err(ValueExtractionError::InvalidValueTypeError(ValueExtractionPolicy::Strict)),
+                   extract(&json!(r#"{"city": "Warsaw"}"#), &ValueExtractionPolicy::Strict));
+        assert!(extract(&json!(r#"{"city": "Warsaw"}"#), &ValueExtractionPolicy::Lax).is_ok());
+        assert_eq!(Result::Err(
+            ValueExtractionError::ObjectEntryError(
+                "tags".to_owned(),
+                Box::new(ValueExtractionError::InvalidValueTypeError(ValueExtractionPolicy::Strict)))),
+                   extract(&json!({"tags": ["a", 1]}), &ValueExtractionPolicy::Strict));
+    }
+
+}
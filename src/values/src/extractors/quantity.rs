@@ -0,0 +1,91 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::extractors::{ValueExtractionError, ValueExtractionPolicy, ValueExtractor, ValueExtractorInput};
+use crate::quantity::Quantity;
+use crate::ValueHolder;
+
+pub struct QuantityExtractor;
+
+impl ValueExtractor for QuantityExtractor {
+
+    fn strict_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::Object(map) => match Quantity::try_from(map) {
+                Ok(quantity) =>
+                    Result::Ok(ValueHolder::Quantity(quantity)),
+                Err(err) =>
+                    Result::Err(
+                        ValueExtractionError::QuantityValueError(
+                            ValueExtractionPolicy::Strict, err)),
+            },
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Strict))
+        }
+    }
+
+    fn lax_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(str_value) => {
+                input.check_number_digits(str_value)?;
+                match Quantity::from_str(str_value.as_str()) {
+                    Ok(quantity) =>
+                        Result::Ok(ValueHolder::Quantity(quantity)),
+                    Err(err) => Result::Err(
+                        ValueExtractionError::QuantityValueError(
+                            ValueExtractionPolicy::Lax, err)),
+                }
+            },
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Lax))
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use num::FromPrimitive;
+    use num_rational::BigRational;
+
+    use crate::quantity::{QuantityValueError, Unit};
+    use crate::ValueType;
+
+    use super::*;
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(Result::Ok(ValueHolder::Quantity(
+            Quantity::new(BigRational::from_f64(512.0).unwrap(), Unit::Mebibytes))),
+                   extract(r#"{"amount": 512, "unit": "MiB"}"#, ValueExtractionPolicy::Strict));
+        assert_eq!(Result::Err(ValueExtractionError::QuantityValueError(
+            ValueExtractionPolicy::Strict, QuantityValueError::MissingUnit)),
+                   extract(r#"{"amount": 512}"#, ValueExtractionPolicy::Strict));
+        assert_eq!(Result::Err(ValueExtractionError::InvalidValueTypeError(
+            ValueExtractionPolicy::Strict)),
+                   extract(r#""512 MiB""#, ValueExtractionPolicy::Strict));
+    }
+
+    #[test]
+    fn test_lax() {
+        assert_eq!(Result::Ok(ValueHolder::Quantity(
+            Quantity::new(BigRational::from_f64(9.99).unwrap(), Unit::Currency("EUR".to_owned())))),
+                   extract(r#""9.99 EUR""#, ValueExtractionPolicy::Lax));
+        assert_eq!(Result::Err(ValueExtractionError::QuantityValueError(
+            ValueExtractionPolicy::Lax, QuantityValueError::InvalidAmount)),
+                   extract(r#""many EUR""#, ValueExtractionPolicy::Lax));
+    }
+
+    fn extract(value: &str,
+               policy: ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        let input_value = Value::from_str(value)
+            .unwrap();
+        QuantityExtractor::extract(
+            &ValueExtractorInput::new(&input_value, &ValueType::Quantity, &policy))
+    }
+
+}
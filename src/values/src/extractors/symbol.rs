@@ -0,0 +1,44 @@
+use serde_json::Value;
+
+use crate::extractors::{ValueExtractionError, ValueExtractionPolicy, ValueExtractor, ValueExtractorInput};
+use crate::symbol::Symbol;
+use crate::ValueHolder;
+
+pub struct SymbolExtractor;
+
+impl SymbolExtractor {
+
+    fn extract_symbol(name: &str,
+                      policy: ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        match Symbol::new(name) {
+            Some(symbol) => Result::Ok(ValueHolder::Symbol(symbol)),
+            None => Result::Err(
+                ValueExtractionError::InvalidValueError(policy, name.to_owned()))
+        }
+    }
+
+}
+
+impl ValueExtractor for SymbolExtractor {
+
+    fn strict_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(str_val) =>
+                SymbolExtractor::extract_symbol(str_val, ValueExtractionPolicy::Strict),
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Strict))
+        }
+    }
+
+    fn lax_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(str_val) =>
+                SymbolExtractor::extract_symbol(str_val.trim(), ValueExtractionPolicy::Lax),
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Lax))
+        }
+    }
+
+}
@@ -0,0 +1,88 @@
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::extractors::{ValueExtractionError, ValueExtractionPolicy, ValueExtractor, ValueExtractorInput};
+use crate::ValueHolder;
+
+pub struct UuidExtractor;
+
+impl UuidExtractor {
+
+    fn parse(value: &str,
+             policy: ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        match Uuid::parse_str(value) {
+            Ok(uuid) => Result::Ok(ValueHolder::Uuid(uuid)),
+            Err(_) => Result::Err(
+                ValueExtractionError::InvalidValueError(policy, value.to_owned()))
+        }
+    }
+
+}
+
+impl ValueExtractor for UuidExtractor {
+
+    fn strict_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(str_val) =>
+                UuidExtractor::parse(str_val, ValueExtractionPolicy::Strict),
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Strict))
+        }
+    }
+
+    fn lax_extract(input: &ValueExtractorInput) -> Result<ValueHolder, ValueExtractionError> {
+        match input.value {
+            Value::String(str_val) => {
+                let trimmed = str_val.trim();
+                let unwrapped = trimmed.strip_prefix("urn:uuid:")
+                    .or_else(|| trimmed.strip_prefix('{')
+                        .and_then(|value| value.strip_suffix('}')))
+                    .unwrap_or(trimmed);
+                UuidExtractor::parse(unwrapped, ValueExtractionPolicy::Lax)
+            },
+            _ => Result::Err(
+                ValueExtractionError::InvalidValueTypeError(
+                    ValueExtractionPolicy::Lax))
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::ValueType;
+
+    use super::*;
+
+    const UUID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    fn extract(value: &str,
+               policy: ValueExtractionPolicy) -> Result<ValueHolder, ValueExtractionError> {
+        let input_value = Value::from_str(value).unwrap();
+        UuidExtractor::extract(&ValueExtractorInput::new(&input_value, &ValueType::Uuid, &policy))
+    }
+
+    #[test]
+    fn test_strict() {
+        let expected = Result::Ok(ValueHolder::Uuid(Uuid::parse_str(UUID).unwrap()));
+
+        assert_eq!(expected, extract(&format!("\"{}\"", UUID), ValueExtractionPolicy::Strict));
+        assert_eq!(expected, extract(&format!("\"{}\"", UUID.to_uppercase()),
+                                     ValueExtractionPolicy::Strict));
+        assert!(extract(&format!("\"{{{}}}\"", UUID), ValueExtractionPolicy::Strict).is_err());
+        assert!(extract("42", ValueExtractionPolicy::Strict).is_err());
+    }
+
+    #[test]
+    fn test_lax() {
+        let expected = Result::Ok(ValueHolder::Uuid(Uuid::parse_str(UUID).unwrap()));
+
+        assert_eq!(expected, extract(&format!("\" {{{}}} \"", UUID), ValueExtractionPolicy::Lax));
+        assert_eq!(expected, extract(&format!("\"urn:uuid:{}\"", UUID), ValueExtractionPolicy::Lax));
+        assert!(extract("\"67e55044-10b1\"", ValueExtractionPolicy::Lax).is_err());
+    }
+
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+pub const MAX_FLAGS: usize = 64;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+pub enum FlagsValueError {
+
+    TooManyFlags(usize),
+    UndeclaredBits(u64),
+    UnknownFlag(String)
+
+}
+
+// Flag names are declared up front, the position of a name in the declaration
+// is the position of its bit in the set.
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub struct Flags {
+
+    names: Arc<Vec<String>>,
+    bits: u64
+
+}
+
+impl Flags {
+
+    pub fn new(names: Arc<Vec<String>>,
+               bits: u64) -> Result<Flags, FlagsValueError> {
+        if names.len() > MAX_FLAGS {
+            return Result::Err(FlagsValueError::TooManyFlags(names.len()));
+        }
+        let undeclared_bits = bits & !Flags::mask(names.len());
+        if undeclared_bits != 0 {
+            return Result::Err(FlagsValueError::UndeclaredBits(undeclared_bits));
+        }
+        Result::Ok(Flags {
+            names,
+            bits
+        })
+    }
+
+    pub fn from_set_flags(names: Arc<Vec<String>>,
+                          set_flags: &[&String]) -> Result<Flags, FlagsValueError> {
+        // Checked before the bits are set, a flag past the last bit would overflow the shift.
+        if names.len() > MAX_FLAGS {
+            return Result::Err(FlagsValueError::TooManyFlags(names.len()));
+        }
+        let mut bits = 0;
+        for flag in set_flags {
+            match names.iter().position(|name| name == *flag) {
+                Some(position) => bits |= 1 << position,
+                None => return Result::Err(FlagsValueError::UnknownFlag(flag.to_string()))
+            }
+        }
+        Flags::new(names, bits)
+    }
+
+    pub fn get_bits(&self) -> u64 {
+        self.bits
+    }
+
+    pub fn get_set_flags(&self) -> Vec<&String> {
+        self.names.iter()
+            .enumerate()
+            .filter(|(position, _)| self.bits & (1 << position) != 0)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    pub fn has_flag(&self,
+                    flag: &str) -> bool {
+        match self.names.iter().position(|name| name == flag) {
+            Some(position) => self.bits & (1 << position) != 0,
+            None => false
+        }
+    }
+
+    pub(crate) fn mask(num_flags: usize) -> u64 {
+        if num_flags == MAX_FLAGS {
+            u64::MAX
+        } else {
+            (1 << num_flags) - 1
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Arc<Vec<String>> {
+        Arc::new(vec!["read".to_owned(), "write".to_owned(), "admin".to_owned()])
+    }
+
+    #[test]
+    fn test_flags_from_bits_and_names() {
+        let flags = Flags::new(names(), 0b101).unwrap();
+
+        assert!(flags.has_flag("read"));
+        assert!(!flags.has_flag("write"));
+        assert!(flags.has_flag("admin"));
+        assert!(!flags.has_flag("unknown"));
+        assert_eq!(vec!["read", "admin"], flags.get_set_flags());
+        assert_eq!(flags, Flags::from_set_flags(
+            names(), &[&"admin".to_owned(), &"read".to_owned()]).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_undeclared_flags() {
+        assert_eq!(Result::Err(FlagsValueError::UndeclaredBits(0b1000)),
+                   Flags::new(names(), 0b1001));
+        assert_eq!(Result::Err(FlagsValueError::UnknownFlag("owner".to_owned())),
+                   Flags::from_set_flags(names(), &[&"owner".to_owned()]));
+    }
+
+    #[test]
+    fn test_rejects_more_names_than_bits() {
+        let names: Arc<Vec<String>> = Arc::new((0..65).map(|position| format!("flag{}", position)).collect());
+
+        assert_eq!(Result::Err(FlagsValueError::TooManyFlags(65)), Flags::new(names.clone(), 0));
+        assert_eq!(Result::Err(FlagsValueError::TooManyFlags(65)),
+                   Flags::from_set_flags(names, &[&"flag64".to_owned()]));
+    }
+
+}
@@ -6,6 +6,9 @@ use num::{BigInt, BigRational, FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
+// Mean radius of the earth, in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
 // Note: this implementation can have consistency issues as Eq and Hash are
 // based on big rational implementation and actual use, e.g. geo location to
 // time zone is based on a crude f64 approximation.
@@ -38,6 +41,27 @@ impl GeoCoordinates {
         GeoCoordinates::rational_to_f64(&self.longitude).unwrap()
     }
 
+    // Great circle distance in meters, by the haversine formula. The earth is taken for a
+    // sphere, which is off by up to half a percent.
+    pub fn get_distance_to(&self,
+                           other: &GeoCoordinates) -> f64 {
+        let latitude = self.get_latitude_as_f64().to_radians();
+        let other_latitude = other.get_latitude_as_f64().to_radians();
+        let latitude_delta = other_latitude - latitude;
+        let longitude_delta = (other.get_longitude_as_f64() - self.get_longitude_as_f64()).to_radians();
+
+        let haversine = (latitude_delta / 2.0).sin().powi(2)
+            + latitude.cos() * other_latitude.cos() * (longitude_delta / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * haversine.sqrt().min(1.0).asin()
+    }
+
+    pub fn is_within_radius(&self,
+                            other: &GeoCoordinates,
+                            radius: &BigRational) -> bool {
+        BigRational::from_f64(self.get_distance_to(other))
+            .is_some_and(|distance| distance <= *radius)
+    }
+
     pub fn is_valid(&self) -> bool {
         GeoCoordinates::is_valid_longitude(&self.latitude)
             && GeoCoordinates::is_valid_latitude(&self.longitude)
@@ -1,6 +1,7 @@
 #[macro_use]
 extern crate lazy_static;
 
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::net::IpAddr;
@@ -10,34 +11,52 @@ use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use isocountry::CountryCode;
 use num::bigint::BigInt;
 use num::rational::BigRational;
+use num::Signed;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, EnumIter, EnumVariantNames};
+use uuid::Uuid;
 
 use crate::email::Email;
+use crate::flags::Flags;
 use crate::geolocation::GeoCoordinates;
 use crate::lists::ValueHoldersList;
+use crate::objects::ValueHoldersObject;
+use crate::quantity::{Quantity, QuantityValueError};
+use crate::symbol::Symbol;
 use crate::wrappers::{LanguageWrapper, TzWrapper, WeekdayWrapper};
 use crate::zoned_date_time::ZonedDateTime;
 use std::sync::Arc;
 
+pub mod calendar;
+pub mod duration;
 pub mod email;
 pub mod extractors;
+pub mod flags;
 pub mod geolocation;
 pub mod lists;
+pub mod objects;
+pub mod quantity;
+pub mod symbol;
+mod unicode;
 pub mod wrappers;
 pub mod zoned_date_time;
 
+// Parsing and comparing longer numbers gets too expensive to do on every request.
+pub const DEFAULT_MAX_NUMBER_DIGITS: usize = 1000;
+
 #[derive(Serialize, Deserialize, AsRefStr, EnumVariantNames, Eq, Hash, PartialEq, PartialOrd,
 Debug, Clone)]
 pub enum ValueHolder {
 
     Boolean(bool),
+    Bytes(Arc<Vec<u8>>),
     Country(CountryCode),
     DayOfWeek(WeekdayWrapper),
     Decimal(BigRational),
     Duration(Duration),
     Email(Email),
+    Flags(Flags),
     GeoCoordinates(GeoCoordinates),
     Integer(BigInt),
     IpAddress(IpAddr),
@@ -46,14 +65,28 @@ pub enum ValueHolder {
     LocalDate(NaiveDate),
     LocalDateTime(NaiveDateTime),
     LocalTime(NaiveTime),
+    Quantity(Quantity),
+    Symbol(Symbol),
     TimeZone(TzWrapper),
     String(Arc<String>),
+    Uuid(Uuid),
     ZonedDateTime(ZonedDateTime),
+    // Appended, so that the values serialized before keep the indices of their variants.
+    Object(Arc<ValueHoldersObject>),
 
 }
 
 impl ValueHolder {
 
+    pub fn check_units(&self,
+                       other: &ValueHolder) -> Result<(), QuantityValueError> {
+        match (self, other) {
+            (ValueHolder::Quantity(this), ValueHolder::Quantity(other)) =>
+                this.check_unit(other),
+            (_, _) => Result::Ok(())
+        }
+    }
+
     pub fn contains(&self,
                     other: &ValueHolder) -> bool {
         match (self, other) {
@@ -77,20 +110,201 @@ impl ValueHolder {
         }
     }
 
+    pub fn eq_with_tolerance(&self,
+                             other: &ValueHolder,
+                             tolerance: &BigRational) -> bool {
+        match (self.as_rational(), other.as_rational()) {
+            (Option::Some(this), Option::Some(other)) => (this - other).abs() <= *tolerance,
+            (_, _) => self == other
+        }
+    }
+
+    // Radius in meters, only coordinates are within any.
+    pub fn is_within_radius(&self,
+                            other: &ValueHolder,
+                            radius: &BigRational) -> bool {
+        match (self, other) {
+            (ValueHolder::GeoCoordinates(this), ValueHolder::GeoCoordinates(other)) =>
+                this.is_within_radius(other, radius),
+            (_, _) => false
+        }
+    }
+
+    pub fn get_value_type(&self) -> ValueType {
+        ValueType::all_value_types()
+            .iter()
+            .find(|value_type| value_type.matches(self))
+            .cloned()
+            .expect("Every value holder has a value type")
+    }
+
+    pub fn get_length(&self) -> Option<usize> {
+        match self {
+            ValueHolder::Bytes(bytes) => Option::Some(bytes.len()),
+            ValueHolder::List(list) => Option::Some(list.get_elements().len()),
+            ValueHolder::String(string) => Option::Some(string.chars().count()),
+            _ => Option::None
+        }
+    }
+
+    // Approximate count of decimal digits, of the longer part for decimals and of the
+    // longest element for lists.
+    pub fn get_number_digits(&self) -> Option<usize> {
+        match self {
+            ValueHolder::Integer(value) => Option::Some(count_digits(value)),
+            ValueHolder::Decimal(value) => Option::Some(count_rational_digits(value)),
+            ValueHolder::Quantity(quantity) => Option::Some(count_rational_digits(quantity.get_amount())),
+            ValueHolder::List(list) => list.get_elements()
+                .iter()
+                .filter_map(ValueHolder::get_number_digits)
+                .max(),
+            _ => Option::None
+        }
+    }
+
+    pub fn has_all_flags(&self,
+                         other: &ValueHolder) -> bool {
+        match (self, other.as_flag_names()) {
+            (ValueHolder::Flags(flags), Option::Some(names)) =>
+                names.iter().all(|name| flags.has_flag(name)),
+            (_, _) => false
+        }
+    }
+
+    pub fn has_any_flag(&self,
+                        other: &ValueHolder) -> bool {
+        match (self, other.as_flag_names()) {
+            (ValueHolder::Flags(flags), Option::Some(names)) =>
+                names.iter().any(|name| flags.has_flag(name)),
+            (_, _) => false
+        }
+    }
+
+    pub fn has_flag(&self,
+                    other: &ValueHolder) -> bool {
+        match (self, other) {
+            (ValueHolder::Flags(flags), ValueHolder::String(name)) => flags.has_flag(name),
+            (ValueHolder::Flags(flags), ValueHolder::Symbol(symbol)) =>
+                flags.has_flag(symbol.get_name()),
+            (_, _) => false
+        }
+    }
+
     pub fn is_in(&self,
                  other:&ValueHolder) -> bool {
         other.contains(self)
     }
 
+    // Only lists and strings hold other values, no value is out of anything else.
+    pub fn is_not_in(&self,
+                     other: &ValueHolder) -> bool {
+        matches!(other, ValueHolder::List(_) | ValueHolder::String(_)) && !other.contains(self)
+    }
+
+    pub fn length_eq(&self,
+                     other: &ValueHolder) -> bool {
+        self.compare_length(other) == Option::Some(Ordering::Equal)
+    }
+
+    pub fn length_gt(&self,
+                     other: &ValueHolder) -> bool {
+        self.compare_length(other) == Option::Some(Ordering::Greater)
+    }
+
+    pub fn length_lt(&self,
+                     other: &ValueHolder) -> bool {
+        self.compare_length(other) == Option::Some(Ordering::Less)
+    }
+
+    pub fn ne_with_tolerance(&self,
+                             other: &ValueHolder,
+                             tolerance: &BigRational) -> bool {
+        !self.eq_with_tolerance(other, tolerance)
+    }
+
+    pub fn normalized_contains(&self,
+                               other: &ValueHolder) -> bool {
+        match (self, other) {
+            (ValueHolder::String(this), ValueHolder::String(other)) =>
+                unicode::grapheme_contains(this, other),
+            (_, _) => self.contains(other)
+        }
+    }
+
+    pub fn normalized_ends_with(&self,
+                                other: &ValueHolder) -> bool {
+        match (self, other) {
+            (ValueHolder::String(this), ValueHolder::String(other)) =>
+                unicode::grapheme_ends_with(this, other),
+            (_, _) => false
+        }
+    }
+
+    pub fn normalized_eq(&self,
+                         other: &ValueHolder) -> bool {
+        match (self, other) {
+            (ValueHolder::String(this), ValueHolder::String(other)) =>
+                unicode::normalized_eq(this, other),
+            (_, _) => self == other
+        }
+    }
+
+    pub fn normalized_starts_with(&self,
+                                  other: &ValueHolder) -> bool {
+        match (self, other) {
+            (ValueHolder::String(this), ValueHolder::String(other)) =>
+                unicode::grapheme_starts_with(this, other),
+            (_, _) => false
+        }
+    }
+
     pub fn starts_with(&self,
                        other: &ValueHolder) -> bool {
         match (self, other) {
             (ValueHolder::String(this), ValueHolder::String(other)) =>
                 this.as_ref().starts_with(other.as_ref()),
+            (ValueHolder::Uuid(this), ValueHolder::String(other)) =>
+                this.to_hyphenated_ref()
+                    .to_string()
+                    .starts_with(other.to_ascii_lowercase().as_str()),
             (_, _) => false
         }
     }
 
+    fn compare_length(&self,
+                      other: &ValueHolder) -> Option<Ordering> {
+        match (self.get_length(), other) {
+            (Option::Some(length), ValueHolder::Integer(expected)) =>
+                BigInt::from(length).partial_cmp(expected),
+            (_, _) => Option::None
+        }
+    }
+
+    fn as_flag_names(&self) -> Option<Vec<&String>> {
+        match self {
+            ValueHolder::Flags(flags) => Option::Some(flags.get_set_flags()),
+            ValueHolder::List(list) => list.get_elements()
+                .iter()
+                .map(|element| match element {
+                    ValueHolder::String(name) => Option::Some(name.as_ref()),
+                    ValueHolder::Symbol(symbol) => Option::Some(symbol.get_name()),
+                    _ => Option::None
+                })
+                .collect(),
+            ValueHolder::String(name) => Option::Some(vec![name.as_ref()]),
+            ValueHolder::Symbol(symbol) => Option::Some(vec![symbol.get_name()]),
+            _ => Option::None
+        }
+    }
+
+    fn as_rational(&self) -> Option<BigRational> {
+        match self {
+            ValueHolder::Decimal(value) => Option::Some(value.clone()),
+            ValueHolder::Integer(value) => Option::Some(BigRational::from_integer(value.clone())),
+            _ => Option::None
+        }
+    }
+
 }
 
 impl TryFrom<ValueHolder> for Duration {
@@ -121,11 +335,13 @@ Debug, Clone, Hash, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum ValueType {
 
     Boolean,
+    Bytes,
     Country,
     DayOfWeek,
     Decimal,
     Duration,
     Email,
+    Flags,
     GeoCoordinates,
     Integer,
     IpAddress,
@@ -134,9 +350,13 @@ pub enum ValueType {
     LocalDate,
     LocalDateTime,
     LocalTime,
+    Quantity,
+    Symbol,
     TimeZone,
     String,
+    Uuid,
     ZonedDateTime,
+    Object,
 
 }
 
@@ -150,13 +370,38 @@ impl ValueType {
         &ALL_VALUE_TYPES
     }
 
-    fn matches(&self,
-               value_holder: &ValueHolder) -> bool {
+    pub fn matches(&self,
+                   value_holder: &ValueHolder) -> bool {
         self.as_ref() == value_holder.as_ref()
     }
 
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum TypeMismatch {
+
+    MissingValue(String),
+    UnexpectedType(String, ValueType, ValueType)
+
+}
+
+// Which of two values of the same name is kept when payloads are merged.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+pub enum MergePolicy {
+
+    PreferLeft,
+    PreferRight,
+    ErrorOnConflict
+
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum MergeError {
+
+    ConflictingValues(String)
+
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct ValuesPayload {
 
@@ -197,6 +442,97 @@ impl ValuesPayload {
         self.values.get(key)
     }
 
+    pub fn get_bool(&self,
+                    key: &str) -> Result<&bool, TypeMismatch> {
+        self.get_typed(key, ValueType::Boolean, |value| match value {
+            ValueHolder::Boolean(value) => Option::Some(value),
+            _ => Option::None
+        })
+    }
+
+    pub fn get_string(&self,
+                      key: &str) -> Result<&String, TypeMismatch> {
+        self.get_typed(key, ValueType::String, |value| match value {
+            ValueHolder::String(value) => Option::Some(value.as_ref()),
+            _ => Option::None
+        })
+    }
+
+    pub fn get_int(&self,
+                   key: &str) -> Result<&BigInt, TypeMismatch> {
+        self.get_typed(key, ValueType::Integer, |value| match value {
+            ValueHolder::Integer(value) => Option::Some(value),
+            _ => Option::None
+        })
+    }
+
+    pub fn get_decimal(&self,
+                       key: &str) -> Result<&BigRational, TypeMismatch> {
+        self.get_typed(key, ValueType::Decimal, |value| match value {
+            ValueHolder::Decimal(value) => Option::Some(value),
+            _ => Option::None
+        })
+    }
+
+    pub fn get_date(&self,
+                    key: &str) -> Result<&NaiveDate, TypeMismatch> {
+        self.get_typed(key, ValueType::LocalDate, |value| match value {
+            ValueHolder::LocalDate(value) => Option::Some(value),
+            _ => Option::None
+        })
+    }
+
+    pub fn get_date_time(&self,
+                         key: &str) -> Result<&NaiveDateTime, TypeMismatch> {
+        self.get_typed(key, ValueType::LocalDateTime, |value| match value {
+            ValueHolder::LocalDateTime(value) => Option::Some(value),
+            _ => Option::None
+        })
+    }
+
+    pub fn get_duration(&self,
+                        key: &str) -> Result<&Duration, TypeMismatch> {
+        self.get_typed(key, ValueType::Duration, |value| match value {
+            ValueHolder::Duration(value) => Option::Some(value),
+            _ => Option::None
+        })
+    }
+
+    // Values present in both payloads under the same name only conflict if they differ.
+    pub fn merge(mut self,
+                 other: ValuesPayload,
+                 policy: MergePolicy) -> Result<ValuesPayload, MergeError> {
+        for (name, value) in other.values {
+            match self.values.get(&name) {
+                Some(current) if *current != value => match policy {
+                    MergePolicy::PreferLeft => {},
+                    MergePolicy::PreferRight => { self.values.insert(name, value); },
+                    MergePolicy::ErrorOnConflict =>
+                        return Result::Err(MergeError::ConflictingValues(name))
+                },
+                Some(_) => {},
+                None => {
+                    self.keys.insert(name.clone());
+                    self.values.insert(name, value);
+                }
+            }
+        }
+
+        Result::Ok(self)
+    }
+
+    pub fn with_prefix(self,
+                       prefix: &str) -> ValuesPayload {
+        ValuesPayload::new(self.values
+            .into_iter()
+            .map(|(name, value)| (format!("{}{}", prefix, name), value))
+            .collect())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ValueHolder)> {
+        self.values.iter()
+    }
+
     pub fn get_keys(&self) -> &HashSet<String> {
         &self.keys
     }
@@ -205,9 +541,39 @@ impl ValuesPayload {
         self.keys
     }
 
+    pub fn find_number_longer_than(&self,
+                                   max_digits: usize) -> Option<&String> {
+        self.values
+            .iter()
+            .find(|(_, value)| value.get_number_digits().is_some_and(|digits| digits > max_digits))
+            .map(|(name, _)| name)
+    }
+
+    fn get_typed<'a, T>(&'a self,
+                        key: &str,
+                        expected_type: ValueType,
+                        extract: impl Fn(&'a ValueHolder) -> Option<&'a T>) -> Result<&'a T, TypeMismatch> {
+        let value = self.values
+            .get(key)
+            .ok_or_else(|| TypeMismatch::MissingValue(key.to_owned()))?;
+
+        extract(value)
+            .ok_or_else(|| TypeMismatch::UnexpectedType(key.to_owned(), expected_type, value.get_value_type()))
+    }
+
 }
 
+// Counted from the bits, so it may be short by one.
+fn count_digits(value: &BigInt) -> usize {
+    match value.bits() {
+        0 => 1,
+        bits => ((bits - 1) as f64 * std::f64::consts::LOG10_2) as usize + 1
+    }
+}
 
+fn count_rational_digits(value: &BigRational) -> usize {
+    count_digits(value.numer()).max(count_digits(value.denom()))
+}
 
 #[cfg(test)]
 mod tests {
@@ -230,4 +596,101 @@ mod tests {
                    ValueHolder::Integer(BigInt::from(0)));
     }
 
+    #[test]
+    fn test_uuid_starts_with() {
+        let uuid = ValueHolder::Uuid(Uuid::parse_str("67E55044-10B1-426F-9247-BB680E5FE0C8").unwrap());
+
+        assert!(uuid.starts_with(&"67e55044-10b1".into()));
+        assert!(uuid.starts_with(&"67E55044".into()));
+        assert!(!uuid.starts_with(&"67e5504410b1".into()));
+        assert_eq!(uuid, ValueHolder::Uuid(Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap()));
+    }
+
+    #[test]
+    fn test_length_comparison() {
+        let bytes = ValueHolder::Bytes(Arc::new(vec![1, 2, 3]));
+
+        assert!(bytes.length_eq(&ValueHolder::Integer(BigInt::from(3))));
+        assert!(bytes.length_gt(&ValueHolder::Integer(BigInt::from(2))));
+        assert!(bytes.length_lt(&ValueHolder::Integer(BigInt::from(4))));
+        assert!(ValueHolder::from("żółw").length_eq(&ValueHolder::Integer(BigInt::from(4))));
+        assert!(!bytes.length_eq(&ValueHolder::Decimal(BigRational::from_f64(3.0).unwrap())));
+        assert!(!ValueHolder::Boolean(true).length_lt(&ValueHolder::Integer(BigInt::from(4))));
+    }
+
+    #[test]
+    fn test_number_digits() {
+        let long_integer: BigInt = "9".repeat(50).parse().unwrap();
+
+        assert_eq!(Option::Some(1), ValueHolder::Integer(BigInt::from(0)).get_number_digits());
+        assert_eq!(Option::Some(50), ValueHolder::Integer(long_integer.clone()).get_number_digits());
+        assert_eq!(Option::Some(50), ValueHolder::Decimal(
+            BigRational::new(BigInt::from(1), long_integer.clone())).get_number_digits());
+        assert_eq!(Option::None, ValueHolder::Boolean(true).get_number_digits());
+        assert_eq!(Option::Some(&"long".to_owned()),
+                   ValuesPayload::singleton("long".to_owned(), ValueHolder::Integer(long_integer))
+                       .find_number_longer_than(49));
+    }
+
+    #[test]
+    fn test_typed_getters() {
+        let mut values = HashMap::new();
+        values.insert("name".to_owned(), ValueHolder::from("alice"));
+        values.insert("age".to_owned(), ValueHolder::Integer(BigInt::from(42)));
+        values.insert("born".to_owned(), ValueHolder::LocalDate(NaiveDate::from_ymd(1980, 5, 17)));
+        let payload = ValuesPayload::new(values);
+
+        assert_eq!(Result::Ok(&"alice".to_owned()), payload.get_string("name"));
+        assert_eq!(Result::Ok(&BigInt::from(42)), payload.get_int("age"));
+        assert_eq!(Result::Ok(&NaiveDate::from_ymd(1980, 5, 17)), payload.get_date("born"));
+        assert_eq!(Result::Err(TypeMismatch::UnexpectedType("age".to_owned(), ValueType::String, ValueType::Integer)),
+                   payload.get_string("age"));
+        assert_eq!(Result::Err(TypeMismatch::MissingValue("city".to_owned())), payload.get_string("city"));
+        assert_eq!(3, payload.iter().count());
+    }
+
+    #[test]
+    fn test_merges_payloads() {
+        let left = ValuesPayload::singleton("tier".to_owned(), "gold".into())
+            .merge(ValuesPayload::singleton("age".to_owned(), ValueHolder::Integer(BigInt::from(42))),
+                   MergePolicy::ErrorOnConflict)
+            .unwrap();
+        let right = ValuesPayload::singleton("tier".to_owned(), "silver".into());
+
+        assert_eq!(Result::Ok(&"gold".to_owned()),
+                   left.clone().merge(right.clone(), MergePolicy::PreferLeft).unwrap().get_string("tier"));
+        assert_eq!(Result::Ok(&"silver".to_owned()),
+                   left.clone().merge(right.clone(), MergePolicy::PreferRight).unwrap().get_string("tier"));
+        assert_eq!(Result::Err(MergeError::ConflictingValues("tier".to_owned())),
+                   left.clone().merge(right, MergePolicy::ErrorOnConflict));
+        assert_eq!(Result::Ok(left.clone()), left.clone().merge(left.clone(), MergePolicy::ErrorOnConflict));
+        assert_eq!(2, left.get_keys().len());
+    }
+
+    #[test]
+    fn test_prefixes_payloads() {
+        let payload = ValuesPayload::singleton("name".to_owned(), "alice".into()).with_prefix("user.");
+
+        assert_eq!(Result::Ok(&"alice".to_owned()), payload.get_string("user.name"));
+        assert!(payload.get_keys().contains("user.name"));
+        assert_eq!(1, payload.get_keys().len());
+    }
+
+    #[test]
+    fn test_eq_with_tolerance() {
+        let tolerance = BigRational::from_f64(0.000001).unwrap();
+
+        assert!(ValueHolder::Decimal(BigRational::from_f64(0.1 + 0.2).unwrap())
+            .eq_with_tolerance(&ValueHolder::Decimal(BigRational::from_f64(0.3).unwrap()),
+                               &tolerance));
+        assert!(ValueHolder::Decimal(BigRational::from_f64(2.0000001).unwrap())
+            .eq_with_tolerance(&ValueHolder::Integer(BigInt::from(2)), &tolerance));
+        assert!(ValueHolder::Decimal(BigRational::from_f64(0.321421).unwrap())
+            .ne_with_tolerance(&ValueHolder::Decimal(BigRational::from_f64(0.321423).unwrap()),
+                               &tolerance));
+        assert!(ValueHolder::from("0.3")
+            .ne_with_tolerance(&ValueHolder::Decimal(BigRational::from_f64(0.3).unwrap()),
+                               &tolerance));
+    }
+
 }
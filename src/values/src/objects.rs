@@ -0,0 +1,115 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ValueHolder;
+
+// Nested values by their names. Hashed and compared by their entries sorted by name, so that
+// objects behave like every other value regardless of the order of their entries.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct ValueHoldersObject {
+
+    entries: HashMap<String, ValueHolder>
+
+}
+
+impl ValueHoldersObject {
+
+    pub fn new(entries: HashMap<String, ValueHolder>) -> ValueHoldersObject {
+        ValueHoldersObject {
+            entries
+        }
+    }
+
+    pub fn get_entries(&self) -> &HashMap<String, ValueHolder> {
+        &self.entries
+    }
+
+    pub fn get(&self,
+               name: &str) -> Option<&ValueHolder> {
+        self.entries.get(name)
+    }
+
+    // Follows a dotted path such as address.city through the nested objects.
+    pub fn get_path(&self,
+                    path: &str) -> Option<&ValueHolder> {
+        let mut segments = path.split('.');
+        let mut value = self.get(segments.next()?)?;
+
+        for segment in segments {
+            value = match value {
+                ValueHolder::Object(object) => object.get(segment)?,
+                _ => return Option::None
+            };
+        }
+        Option::Some(value)
+    }
+
+    fn sorted_entries(&self) -> Vec<(&String, &ValueHolder)> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|(this, _), (other, _)| this.cmp(other));
+        entries
+    }
+
+}
+
+impl Hash for ValueHoldersObject {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sorted_entries().hash(state);
+    }
+}
+
+impl PartialOrd for ValueHoldersObject {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.sorted_entries().partial_cmp(&other.sorted_entries())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::hash_map::DefaultHasher;
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn object(entries: Vec<(&str, ValueHolder)>) -> ValueHoldersObject {
+        ValueHoldersObject::new(entries
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect())
+    }
+
+    fn hash(object: &ValueHoldersObject) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        object.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_follows_dotted_paths() {
+        let user = object(vec![
+            ("name", "Alice".into()),
+            ("address", ValueHolder::Object(Arc::new(object(vec![("city", "Warsaw".into())]))))]);
+
+        assert_eq!(Option::Some(&"Warsaw".into()), user.get_path("address.city"));
+        assert_eq!(Option::Some(&"Alice".into()), user.get_path("name"));
+        assert_eq!(Option::None, user.get_path("name.first"));
+        assert_eq!(Option::None, user.get_path("address.street"));
+    }
+
+    #[test]
+    fn test_compares_regardless_of_entries_order() {
+        let this = object(vec![("a", ValueHolder::Boolean(true)), ("b", "2".into())]);
+        let other = object(vec![("b", "2".into()), ("a", ValueHolder::Boolean(true))]);
+
+        assert_eq!(this, other);
+        assert_eq!(hash(&this), hash(&other));
+        assert_eq!(Option::Some(Ordering::Equal), this.partial_cmp(&other));
+        assert_eq!(Option::Some(Ordering::Less),
+                   this.partial_cmp(&object(vec![("a", ValueHolder::Boolean(true)), ("b", "3".into())])));
+    }
+
+}
@@ -0,0 +1,224 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use num::{BigInt, BigRational, FromPrimitive};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+pub enum Unit {
+
+    Bytes,
+    Currency(String),
+    Gibibytes,
+    Kibibytes,
+    Mebibytes,
+    Percent
+
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum QuantityValueError {
+
+    InvalidAmount,
+    InvalidCurrencyCode(String),
+    InvalidUnit(String),
+    InvalidWhitespaceSeparatedStructure,
+    MissingAmount,
+    MissingUnit,
+    UnitMismatch(Unit, Unit)
+
+}
+
+impl Unit {
+
+    pub fn currency(code: &str) -> Result<Unit, QuantityValueError> {
+        if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) {
+            Result::Ok(Unit::Currency(code.to_owned()))
+        } else {
+            Result::Err(QuantityValueError::InvalidCurrencyCode(code.to_owned()))
+        }
+    }
+
+    fn bytes_multiplier(&self) -> Option<BigInt> {
+        match self {
+            Unit::Bytes => Option::Some(BigInt::from(1)),
+            Unit::Kibibytes => Option::Some(BigInt::from(1u64 << 10)),
+            Unit::Mebibytes => Option::Some(BigInt::from(1u64 << 20)),
+            Unit::Gibibytes => Option::Some(BigInt::from(1u64 << 30)),
+            _ => Option::None
+        }
+    }
+
+}
+
+impl FromStr for Unit {
+    type Err = QuantityValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "B" => Result::Ok(Unit::Bytes),
+            "KiB" => Result::Ok(Unit::Kibibytes),
+            "MiB" => Result::Ok(Unit::Mebibytes),
+            "GiB" => Result::Ok(Unit::Gibibytes),
+            "%" => Result::Ok(Unit::Percent),
+            _ => Unit::currency(s)
+                .map_err(|_| QuantityValueError::InvalidUnit(s.to_owned()))
+        }
+    }
+
+}
+
+// Quantities are only ordered against quantities with the same unit, comparing
+// cents to dollars or bytes to percents yields no ordering at all.
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Quantity {
+
+    amount: BigRational,
+    unit: Unit
+
+}
+
+impl Quantity {
+
+    pub fn new(amount: BigRational,
+               unit: Unit) -> Quantity {
+        Quantity {
+            amount,
+            unit
+        }
+    }
+
+    pub fn get_amount(&self) -> &BigRational {
+        &self.amount
+    }
+
+    pub fn get_unit(&self) -> &Unit {
+        &self.unit
+    }
+
+    pub fn check_unit(&self,
+                      other: &Quantity) -> Result<(), QuantityValueError> {
+        if self.unit == other.unit {
+            Result::Ok(())
+        } else {
+            Result::Err(QuantityValueError::UnitMismatch(self.unit.clone(), other.unit.clone()))
+        }
+    }
+
+    pub fn convert(&self,
+                   unit: &Unit) -> Result<Quantity, QuantityValueError> {
+        if self.unit == *unit {
+            return Result::Ok(self.clone());
+        }
+        match (self.unit.bytes_multiplier(), unit.bytes_multiplier()) {
+            (Option::Some(from), Option::Some(to)) =>
+                Result::Ok(Quantity::new(&self.amount * BigRational::new(from, to), unit.clone())),
+            (_, _) => Result::Err(QuantityValueError::UnitMismatch(self.unit.clone(), unit.clone()))
+        }
+    }
+
+    fn amount_from_value(value: &Value) -> Option<BigRational> {
+        match value {
+            Value::Number(number) => number.as_f64().and_then(BigRational::from_f64),
+            Value::String(string) => string.parse::<f64>().ok().and_then(BigRational::from_f64),
+            _ => Option::None
+        }
+    }
+
+}
+
+impl PartialOrd for Quantity {
+
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.unit != other.unit {
+            return Option::None;
+        }
+        self.amount.partial_cmp(&other.amount)
+    }
+
+}
+
+impl FromStr for Quantity {
+    type Err = QuantityValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let (amount, unit) = match trimmed.strip_suffix('%') {
+            Some(amount) => (amount.trim(), "%"),
+            None => {
+                let parts: Vec<&str> = trimmed.split_whitespace().collect();
+                if parts.len() != 2 {
+                    return Result::Err(QuantityValueError::InvalidWhitespaceSeparatedStructure);
+                }
+                (parts[0], parts[1])
+            }
+        };
+        match amount.parse::<f64>().ok().and_then(BigRational::from_f64) {
+            Some(amount) => Result::Ok(Quantity::new(amount, Unit::from_str(unit)?)),
+            None => Result::Err(QuantityValueError::InvalidAmount)
+        }
+    }
+
+}
+
+impl TryFrom<&Map<String, Value>> for Quantity {
+    type Error = QuantityValueError;
+
+    fn try_from(value: &Map<String, Value>) -> Result<Self, Self::Error> {
+        let amount = match value.get("amount") {
+            None => return Result::Err(QuantityValueError::MissingAmount),
+            Some(amount) => Quantity::amount_from_value(amount)
+                .ok_or(QuantityValueError::InvalidAmount)?
+        };
+        match value.get("unit") {
+            Some(Value::String(unit)) => Result::Ok(Quantity::new(amount, Unit::from_str(unit)?)),
+            Some(unit) => Result::Err(QuantityValueError::InvalidUnit(unit.to_string())),
+            None => Result::Err(QuantityValueError::MissingUnit)
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Result::Ok(Quantity::new(BigRational::from_f64(12.5).unwrap(),
+                                            Unit::Currency("USD".to_owned()))),
+                   Quantity::from_str("12.5 USD"));
+        assert_eq!(Result::Ok(Quantity::new(BigRational::from_f64(15.0).unwrap(), Unit::Percent)),
+                   Quantity::from_str("15%"));
+        assert_eq!(Result::Err(QuantityValueError::InvalidUnit("usd".to_owned())),
+                   Quantity::from_str("12.5 usd"));
+        assert_eq!(Result::Err(QuantityValueError::InvalidWhitespaceSeparatedStructure),
+                   Quantity::from_str("12.5"));
+    }
+
+    #[test]
+    fn test_compares_only_same_units() {
+        let dollars = Quantity::new(BigRational::from_f64(1.0).unwrap(),
+                                    Unit::Currency("USD".to_owned()));
+        let euros = Quantity::new(BigRational::from_f64(50.0).unwrap(),
+                                  Unit::Currency("EUR".to_owned()));
+
+        assert_eq!(Option::None, dollars.partial_cmp(&euros));
+        assert!(!dollars.lt(&euros) && !dollars.gt(&euros));
+        assert_eq!(Result::Err(QuantityValueError::UnitMismatch(
+            Unit::Currency("USD".to_owned()), Unit::Currency("EUR".to_owned()))),
+                   dollars.check_unit(&euros));
+    }
+
+    #[test]
+    fn test_convert_bytes() {
+        let quantity = Quantity::new(BigRational::from_f64(1.5).unwrap(), Unit::Kibibytes);
+
+        assert_eq!(Result::Ok(Quantity::new(BigRational::from_f64(1536.0).unwrap(), Unit::Bytes)),
+                   quantity.convert(&Unit::Bytes));
+        assert!(quantity.convert(&Unit::Percent).is_err());
+    }
+
+}
@@ -0,0 +1,75 @@
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+
+// (De)serialized as its name, which is validated like the one given to new.
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct Symbol {
+
+    name: String
+
+}
+
+impl Symbol {
+
+    pub fn new(name: &str) -> Option<Symbol> {
+        let mut chars = name.chars();
+        let is_valid = match chars.next() {
+            Some(first) => (first.is_ascii_alphabetic() || first == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+            None => false
+        };
+        if is_valid {
+            Option::Some(Symbol {
+                name: name.to_owned()
+            })
+        } else {
+            Option::None
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+}
+
+impl TryFrom<String> for Symbol {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Symbol::new(&name).ok_or_else(|| format!("Invalid symbol name: {}", name))
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> Self {
+        symbol.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_names() {
+        for name in ["ACTIVE", "on_hold", "_internal", "level2"].iter() {
+            assert_eq!(Option::Some(name.to_string()),
+                       Symbol::new(name).map(|symbol| symbol.get_name().clone()));
+        }
+        for name in ["", "2fast", "on hold", "on-hold"].iter() {
+            assert_eq!(Option::None, Symbol::new(name));
+        }
+    }
+
+    #[test]
+    fn test_deserializes_only_valid_names() {
+        assert_eq!("\"on_hold\"", serde_json::to_string(&Symbol::new("on_hold").unwrap()).unwrap());
+        assert_eq!(Symbol::new("on_hold"), serde_json::from_str("\"on_hold\"").ok());
+        assert!(serde_json::from_str::<Symbol>("\"on hold\"").is_err());
+        assert!(serde_json::from_str::<Symbol>("\"\"").is_err());
+    }
+
+}
@@ -0,0 +1,60 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub(crate) fn normalized_eq(this: &str,
+                            other: &str) -> bool {
+    this.nfc().eq(other.nfc())
+}
+
+pub(crate) fn grapheme_contains(this: &str,
+                                other: &str) -> bool {
+    let (this, other) = (graphemes(this), graphemes(other));
+
+    other.is_empty() || this.windows(other.len()).any(|window| window == other.as_slice())
+}
+
+pub(crate) fn grapheme_ends_with(this: &str,
+                                 other: &str) -> bool {
+    graphemes(this).ends_with(&graphemes(other))
+}
+
+pub(crate) fn grapheme_starts_with(this: &str,
+                                   other: &str) -> bool {
+    graphemes(this).starts_with(&graphemes(other))
+}
+
+fn graphemes(value: &str) -> Vec<String> {
+    value.nfc()
+        .collect::<String>()
+        .graphemes(true)
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSED: &str = "caf\u{e9}";
+    const DECOMPOSED: &str = "cafe\u{301}";
+
+    #[test]
+    fn test_compares_normalized_forms() {
+        assert_ne!(COMPOSED, DECOMPOSED);
+        assert!(normalized_eq(COMPOSED, DECOMPOSED));
+        assert!(!normalized_eq(COMPOSED, "cafe"));
+    }
+
+    #[test]
+    fn test_matches_whole_graphemes_only() {
+        assert!(DECOMPOSED.contains("cafe"));
+        assert!(!grapheme_contains(DECOMPOSED, "cafe"));
+        assert!(grapheme_contains(DECOMPOSED, COMPOSED));
+        assert!(grapheme_starts_with(COMPOSED, "caf"));
+        assert!(!grapheme_ends_with(DECOMPOSED, "e"));
+        assert!(grapheme_ends_with("I \u{2764}\u{fe0f} \u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+                                   "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}"));
+        assert!(!grapheme_contains("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}", "\u{1f468}"));
+    }
+
+}
@@ -4,7 +4,8 @@ use std::hash::{Hash, Hasher};
 use chrono::Weekday;
 use chrono_tz::Tz;
 use isolang::Language;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error;
 
 pub trait Wrapper<T> {
 
@@ -105,10 +106,19 @@ impl Eq for TzWrapper {}
 pub struct LanguageWrapper {
 
     code: String,
+    #[serde(deserialize_with = "deserialize_language")]
     value: Language
 
 }
 
+// Languages only deserialize from borrowed strings, which json values do not hold.
+fn deserialize_language<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Language, D::Error> {
+    let code = String::deserialize(deserializer)?;
+
+    Language::from_639_3(&code)
+        .ok_or_else(|| D::Error::custom(format!("unknown language {}", code)))
+}
+
 impl Wrapper<Language> for LanguageWrapper {
 
     fn new(value: Language) -> Self {
@@ -1,9 +1,9 @@
+use std::convert::TryFrom;
 use std::str::FromStr;
 
 use chrono::NaiveDateTime;
 use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use serde::export::TryFrom;
 use serde_json::Value;
 
 use crate::wrappers::{TzWrapper, Wrapper};
@@ -0,0 +1,287 @@
+use std::collections::HashSet;
+use std::io;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use actix_web::client::Client;
+use actix_web::rt::System;
+use actix_web::rt::time::{delay_for, timeout};
+use futures::channel::mpsc::{self, Receiver};
+use futures::StreamExt;
+use log::warn;
+use ring::hmac;
+
+use buttercup_api::events::{DefinitionEvent, DefinitionEventService};
+use buttercup_blackboards::LocalBlackboardError;
+use buttercup_blackboards::outbox::Outbox;
+
+const EVENT_HEADER: &str = "X-Buttercup-Event";
+const SIGNATURE_HEADER: &str = "X-Buttercup-Signature";
+const DELIVERY_HEADER: &str = "X-Buttercup-Delivery";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(1);
+const OUTBOX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+// Posts activations, rollbacks and failed self-tests of definitions to the configured
+// urls, one event at a time so that receivers get them in order. The body is the json of
+// the event, its signature is the hex encoded hmac-sha256 of the body, sha256=<hex>.
+//
+// With an outbox the events are staged there first and sent from it, the ones a receiver
+// did not take are sent again later and after restarts. Every event then carries its
+// outbox sequence in X-Buttercup-Delivery, the same on redeliveries.
+pub struct WebhookNotifier {
+
+    urls: Vec<String>,
+    key: Option<hmac::Key>,
+    max_retries: u32,
+    outbox: Option<Outbox>
+
+}
+
+impl WebhookNotifier {
+
+    pub fn new(urls: Vec<String>,
+               secret: Option<&str>,
+               max_retries: u32) -> WebhookNotifier {
+        WebhookNotifier {
+            urls,
+            key: secret.map(|secret| hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes())),
+            max_retries,
+            outbox: Option::None
+        }
+    }
+
+    pub fn with_outbox(mut self,
+                       outbox: Outbox) -> WebhookNotifier {
+        self.outbox = Option::Some(outbox);
+        self
+    }
+
+    pub fn is_notified(event: &DefinitionEvent) -> bool {
+        matches!(event,
+            DefinitionEvent::Activated { .. }
+            | DefinitionEvent::RolledBack { .. }
+            | DefinitionEvent::SelfTestFailed { .. })
+    }
+
+    pub fn sign(&self,
+                body: &[u8]) -> Option<String> {
+        self.key.as_ref().map(|key| format!(
+            "sha256={}",
+            hmac::sign(key, body)
+                .as_ref()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()))
+    }
+
+    // Webhooks are sent from a thread of their own, running the system of the http client,
+    // so that slow receivers never hold up the workers.
+    pub fn spawn(self,
+                 events: Arc<DefinitionEventService>) -> io::Result<JoinHandle<()>> {
+        let receiver = events.subscribe();
+
+        thread::Builder::new()
+            .name("webhooks".to_owned())
+            .spawn(move || System::new("webhooks").block_on(self.run(events, receiver)))
+    }
+
+    pub fn stage(&self,
+                 outbox: &Outbox,
+                 event: &DefinitionEvent) -> Result<u64, LocalBlackboardError> {
+        let body = serde_json::to_vec(event)
+            .map_err(|err| LocalBlackboardError::SerializeError(err.to_string()))?;
+
+        outbox.stage(event.get_name(), &body, &self.urls)
+    }
+
+    // Staging does not wait for the receivers, the outbox is sent from a task of its own,
+    // woken up by staged events or every OUTBOX_RETRY_INTERVAL.
+    async fn run(self,
+                 events: Arc<DefinitionEventService>,
+                 mut receiver: Receiver<DefinitionEvent>) {
+        let notifier = Rc::new(self);
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .finish();
+        let mut staged = notifier.outbox.as_ref().map(|_| {
+            let (sender, staged) = mpsc::channel(1);
+            actix_web::rt::spawn(notifier.clone().keep_dispatching(client.clone(), staged));
+            sender
+        });
+
+        loop {
+            while let Some(event) = receiver.next().await {
+                if !WebhookNotifier::is_notified(&event) {
+                    continue;
+                }
+
+                match (&notifier.outbox, &mut staged) {
+                    (Some(outbox), Some(staged)) => match notifier.stage(outbox, &event) {
+                        Ok(_) => {
+                            let _ = staged.try_send(());
+                        }
+                        Err(err) => warn!("Could not stage {:?} in the outbox: {:?}", event, err)
+                    },
+                    _ => notifier.notify(&client, &event).await
+                }
+            }
+
+            warn!("Webhooks fell behind the definition events, some of them were not sent");
+            receiver = events.subscribe();
+        }
+    }
+
+    async fn notify(&self,
+                    client: &Client,
+                    event: &DefinitionEvent) {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => body,
+            Err(err) => {
+                warn!("Could not serialize {:?}: {}", event, err);
+                return;
+            }
+        };
+        let description = format!("{} of tree {}", event.get_name(), event.get_tree_id());
+
+        for url in &self.urls {
+            self.deliver(client, url, event.get_name(), &body, Option::None, &description).await;
+        }
+    }
+
+    async fn keep_dispatching(self: Rc<Self>,
+                              client: Client,
+                              mut staged: Receiver<()>) {
+        if let Some(outbox) = &self.outbox {
+            loop {
+                self.dispatch(&client, outbox).await;
+                let _ = timeout(OUTBOX_RETRY_INTERVAL, staged.next()).await;
+            }
+        }
+    }
+
+    // Messages stay in the outbox until their receiver takes them. Once a receiver has not
+    // taken one, its later messages wait for the next dispatch, so that they keep the order.
+    async fn dispatch(&self,
+                      client: &Client,
+                      outbox: &Outbox) {
+        let messages = match outbox.get_pending() {
+            Ok(messages) => messages,
+            Err(err) => {
+                warn!("Could not read the webhook outbox: {:?}", err);
+                return;
+            }
+        };
+        let mut failed_urls = HashSet::new();
+
+        for message in messages {
+            if failed_urls.contains(message.get_destination()) {
+                continue;
+            }
+
+            let description = format!("delivery {} ({})", message.get_sequence(), message.get_name());
+            if self.deliver(client,
+                            message.get_destination(),
+                            message.get_name(),
+                            message.get_body(),
+                            Option::Some(*message.get_sequence()),
+                            &description).await {
+                if let Err(err) = outbox.acknowledge(&message) {
+                    warn!("Could not acknowledge {} in the webhook outbox: {:?}", description, err);
+                }
+            } else {
+                failed_urls.insert(message.get_destination().clone());
+            }
+        }
+    }
+
+    async fn deliver(&self,
+                     client: &Client,
+                     url: &str,
+                     name: &str,
+                     body: &[u8],
+                     delivery: Option<u64>,
+                     description: &str) -> bool {
+        let signature = self.sign(body);
+        let mut delay = FIRST_RETRY_DELAY;
+
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                delay_for(delay).await;
+                delay *= 2;
+            }
+
+            let mut request = client.post(url)
+                .content_type("application/json")
+                .header(EVENT_HEADER, name);
+            if let Some(signature) = &signature {
+                request = request.header(SIGNATURE_HEADER, signature.as_str());
+            }
+            if let Some(delivery) = delivery {
+                request = request.header(DELIVERY_HEADER, delivery.to_string());
+            }
+
+            match request.send_body(body.to_vec()).await {
+                Ok(response) if response.status().is_success() => return true,
+                Ok(response) => warn!("Webhook {} answered {} to {}",
+                                      url, response.status(), description),
+                Err(err) => warn!("Could not send {} to webhook {}: {}",
+                                  description, url, err)
+            }
+        }
+
+        false
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signs_bodies_with_secret() {
+        let notifier = WebhookNotifier::new(Vec::new(), Option::Some("Jefe"), 0);
+
+        assert_eq!(Option::Some("sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843".to_owned()),
+                   notifier.sign(b"what do ya want for nothing?"));
+        assert_eq!(Option::None, WebhookNotifier::new(Vec::new(), Option::None, 0).sign(b"body"));
+    }
+
+    #[test]
+    fn test_notifies_lifecycle_events_only() {
+        assert!(WebhookNotifier::is_notified(&DefinitionEvent::RolledBack { tree_id: 1, version: 1 }));
+        assert!(WebhookNotifier::is_notified(
+            &DefinitionEvent::SelfTestFailed { tree_id: 1, version: 2, failed_fixtures: 1 }));
+        assert!(!WebhookNotifier::is_notified(&DefinitionEvent::Updated { tree_id: 1, version: 2 }));
+    }
+
+    #[test]
+    fn test_stages_events_for_every_url() {
+        let path = std::env::temp_dir()
+            .join(format!("buttercup-webhooks-outbox-{}", std::process::id()))
+            .into_os_string();
+        {
+            let outbox = Outbox::open(path.clone()).unwrap();
+            let notifier = WebhookNotifier::new(vec!["http://a".to_owned(), "http://b".to_owned()],
+                                                Option::None,
+                                                0);
+            let event = DefinitionEvent::Activated { tree_id: 1, version: 2 };
+
+            assert_eq!(0, notifier.stage(&outbox, &event).unwrap());
+
+            let pending = outbox.get_pending().unwrap();
+            assert_eq!(vec!["http://a", "http://b"],
+                       pending.iter()
+                           .map(|message| message.get_destination().as_str())
+                           .collect::<Vec<_>>());
+            assert_eq!("activated", pending[0].get_name());
+            assert_eq!(serde_json::to_vec(&event).unwrap(), *pending[1].get_body());
+        }
+
+        Outbox::destroy(path).unwrap();
+    }
+
+}